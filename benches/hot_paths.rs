@@ -0,0 +1,286 @@
+// Chemins critiques, sans matériel ni réseau: trames NMEA vers échantillon GPS, filtre de l'IMU,
+// validation des commandes et rapports cycliques, lot de 100 échantillons vers les sorties locales,
+// enregistrement et relecture. Données d'entrée dans benches/fixtures. A lancer avec:
+//   cargo bench --no-default-features --features fake-sensors,fake-actuators --bench hot_paths
+
+#[cfg(not(feature = "real-sensors"))]
+mod bench {
+    use std::hint::black_box;
+    use std::path::PathBuf;
+    use std::time::Duration;
+
+    use chrono::{TimeZone, Utc};
+    use criterion::{Criterion, Throughput};
+    use nalgebra::Vector3;
+    use nmea_parser::ParsedMessage;
+
+    use voiturerc::actuators::mock::Mock;
+    use voiturerc::actuators::Control;
+    use voiturerc::clock::Clock;
+    use voiturerc::config::Encoding;
+    use voiturerc::jsonl::file::{Limits, Sink};
+    use voiturerc::record::{ModemData, ModemStatus, Record};
+    use voiturerc::sensors::gps::nmea::{self, Nmea};
+    use voiturerc::sensors::imu::filter::Complementary;
+    use voiturerc::sensors::reader::{AnalogData, Data, GpsData, ImuData, MagData};
+    use voiturerc::sensors::replay::{self, Recorder};
+
+    /// Trames d'un récepteur GNSS (RMC, VTG, GGA, GSA, GSV, GLL), 1 Hz, CRLF
+    const NMEA: &str = include_str!("fixtures/nmea.txt");
+    /// Registres bruts de l'IMU à 100 Hz: intervalle (µs), accélération puis vitesse angulaire
+    const IMU: &str = include_str!("fixtures/imu.csv");
+    /// Enregistrements control:realtime reçus de la base, deux hors limites
+    const CONTROL: &str = include_str!("fixtures/control.jsonl");
+
+    /// Taille du lot envoyé aux sorties
+    const BATCH: usize = 100;
+    /// Taille d'une lecture du port série du GPS
+    const UART_READ: usize = 255;
+    /// Echelles de l'IMU (±2 g, ±250 °/s)
+    const ACCEL_SCALE: f32 = 16384.0;
+    const GYRO_SCALE: f32 = 131.0;
+
+    fn clock() -> Clock {
+        Clock::virtual_at(Utc.with_ymd_and_hms(2024, 6, 1, 12, 30, 5).unwrap())
+    }
+
+    /// Dossier temporaire propre au banc d'essai
+    fn directory(name: &str) -> PathBuf {
+        let directory = std::env::temp_dir().join(format!("rc-telemetrie-bench-{}-{}", name, std::process::id()));
+        let _ = std::fs::remove_dir_all(&directory);
+        std::fs::create_dir_all(&directory).unwrap();
+        directory
+    }
+
+    /// Echantillons IMU bruts: intervalle, accélération (LSB), vitesse angulaire (LSB)
+    fn imu_samples() -> Vec<(Duration, Vector3<f32>, Vector3<f32>)> {
+        IMU.lines()
+            .filter(|line| !line.starts_with('#'))
+            .map(|line| {
+                let values: Vec<f32> = line.split(',').map(|value| value.parse().unwrap()).collect();
+                (
+                    Duration::from_micros(values[0] as u64),
+                    Vector3::new(values[1], values[2], values[3]),
+                    Vector3::new(values[4], values[5], values[6]),
+                )
+            })
+            .collect()
+    }
+
+    /// Trames vers échantillons GPS, un échantillon par position (GGA)
+    fn parse_nmea(clock: &Clock) -> Vec<GpsData> {
+        let mut nmea = Nmea::default();
+        let mut gps = GpsData::default();
+        let mut records = Vec::new();
+        for chunk in NMEA.as_bytes().chunks(UART_READ) {
+            for message in nmea.push(chunk) {
+                nmea::apply(&mut gps, &message);
+                if let ParsedMessage::Gga(_) = message {
+                    gps.stamp = clock.stamp();
+                    records.push(gps);
+                }
+            }
+        }
+        records
+    }
+
+    /// Filtre et échantillon IMU pour chaque mesure brute
+    fn process_imu(clock: &Clock, samples: &[(Duration, Vector3<f32>, Vector3<f32>)]) -> Vec<ImuData> {
+        let mut filter = Complementary::new();
+        let mut elapsed = Duration::ZERO;
+        samples
+            .iter()
+            .map(|(interval, accel, gyro)| {
+                elapsed += *interval;
+                let angles = filter.update(accel / ACCEL_SCALE, gyro / GYRO_SCALE, *interval, 12.0);
+                ImuData {
+                    stamp: clock.stamp_at(elapsed),
+                    angles: (angles.x, angles.y, angles.z),
+                    temp: 31.5,
+                    ..ImuData::default()
+                }
+            })
+            .collect()
+    }
+
+    /// Lot d'échantillons de tous les types, dans l'ordre de production
+    fn batch(clock: &Clock) -> Vec<Record> {
+        let imu = process_imu(clock, &imu_samples());
+        let gps = parse_nmea(clock);
+        let mut records = Vec::with_capacity(BATCH);
+        for (n, imu) in imu.iter().enumerate().take(BATCH) {
+            records.push(Record::Imu(*imu));
+            match n % 10 {
+                3 => records.push(Record::Mag(MagData {
+                    stamp: imu.stamp,
+                    raw: (-212, 87, -401),
+                    heading: 92.4,
+                    calibrated: (-212.0, 87.0, -401.0),
+                    true_heading: 94.4,
+                    declination: 2.0,
+                })),
+                5 => records.push(Record::Gps(gps[n / 10 % gps.len()])),
+                7 => records.push(Record::Analog(AnalogData {
+                    stamp: imu.stamp,
+                    battery: 7.84,
+                    ..AnalogData::default()
+                })),
+                9 => records.push(Record::Modem(ModemData {
+                    status: ModemStatus { quality: 64, ..ModemStatus::default() },
+                    stamp: imu.stamp,
+                })),
+                _ => {}
+            }
+        }
+        records.truncate(BATCH);
+        records
+    }
+
+    pub fn nmea_to_record(c: &mut Criterion) {
+        let clock = clock();
+        assert!(!parse_nmea(&clock).is_empty(), "aucune position dans les trames");
+
+        let mut group = c.benchmark_group("nmea");
+        group.throughput(Throughput::Bytes(NMEA.len() as u64));
+        group.bench_function("parse to GPS records", |b| b.iter(|| black_box(parse_nmea(&clock))));
+        group.finish();
+    }
+
+    pub fn imu_processing(c: &mut Criterion) {
+        let clock = clock();
+        let samples = imu_samples();
+
+        let mut group = c.benchmark_group("imu");
+        group.throughput(Throughput::Elements(samples.len() as u64));
+        group.bench_function("filter and build records", |b| {
+            b.iter(|| {
+                let records: Vec<Record> = process_imu(&clock, &samples).into_iter().map(Record::Imu).collect();
+                black_box(records)
+            })
+        });
+        group.finish();
+    }
+
+    pub fn control_mapping(c: &mut Criterion) {
+        let lines: Vec<&str> = CONTROL.lines().collect();
+
+        let mut group = c.benchmark_group("control");
+        group.throughput(Throughput::Elements(lines.len() as u64));
+        group.bench_function("validate and map to duty cycles", |b| {
+            let mut mock = Mock::default();
+            b.iter(|| {
+                for line in lines.iter() {
+                    let control: Control = serde_json::from_str(line).unwrap();
+                    let _ = black_box(mock.apply(&control));
+                }
+            })
+        });
+        group.finish();
+    }
+
+    pub fn sink_batch(c: &mut Criterion) {
+        let clock = clock();
+        let records = batch(&clock);
+        assert_eq!(records.len(), BATCH);
+
+        let mut group = c.benchmark_group("sink");
+        group.throughput(Throughput::Elements(BATCH as u64));
+
+        let directory = directory("jsonl");
+        let limits = Limits {
+            max_bytes: 64 * 1024 * 1024,
+            max_age: None,
+            gzip: false,
+            budget: Some(256 * 1024 * 1024),
+        };
+        let mut sink = Sink::new(&directory, "bench", limits, &clock).unwrap();
+        group.bench_function("jsonl batch of 100", |b| {
+            b.iter(|| {
+                for record in records.iter() {
+                    sink.write(&voiturerc::jsonl::line::encode("bench", record).unwrap()).unwrap();
+                }
+                sink.flush().unwrap();
+            })
+        });
+        sink.close().unwrap();
+        let _ = std::fs::remove_dir_all(&directory);
+
+        group.bench_function("grafana lines batch of 100", |b| {
+            b.iter(|| {
+                let lines: Vec<String> = records
+                    .iter()
+                    .filter_map(|record| voiturerc::grafana::line::line("rc-1", record).unwrap())
+                    .collect();
+                black_box(lines.join("\n"))
+            })
+        });
+        group.finish();
+    }
+
+    pub fn record_replay(c: &mut Criterion) {
+        let clock = clock();
+        let imu = process_imu(&clock, &imu_samples());
+        let gps = parse_nmea(&clock);
+        let samples: Vec<Data> = imu
+            .iter()
+            .take(BATCH)
+            .enumerate()
+            .map(|(n, imu)| Data {
+                imu: *imu,
+                gps: gps[n / 10 % gps.len()],
+                analog: AnalogData {
+                    stamp: imu.stamp,
+                    battery: 7.84,
+                    ..AnalogData::default()
+                },
+                ..Data::default()
+            })
+            .collect();
+
+        let mut group = c.benchmark_group("record");
+        group.throughput(Throughput::Elements(BATCH as u64));
+
+        let directory = directory("record");
+        {
+            let mut recorder = Recorder::create(&directory, &clock, Encoding::Protobuf).unwrap();
+            group.bench_function("append 100 samples", |b| {
+                b.iter(|| {
+                    for data in samples.iter() {
+                        recorder.write(data).unwrap();
+                    }
+                })
+            });
+        }
+
+        // Enregistrement de référence pour la relecture
+        let _ = std::fs::remove_dir_all(&directory);
+        {
+            let mut recorder = Recorder::create(&directory, &clock, Encoding::Protobuf).unwrap();
+            for data in samples.iter() {
+                recorder.write(data).unwrap();
+            }
+        }
+        let path = std::fs::read_dir(&directory).unwrap().next().unwrap().unwrap().path();
+        group.bench_function("replay 100 samples", |b| {
+            b.iter(|| black_box(replay::decode(&path, &mut std::io::sink()).unwrap()))
+        });
+        group.finish();
+        let _ = std::fs::remove_dir_all(&directory);
+    }
+}
+
+#[cfg(not(feature = "real-sensors"))]
+criterion::criterion_group!(
+    benches,
+    bench::nmea_to_record,
+    bench::imu_processing,
+    bench::control_mapping,
+    bench::sink_batch,
+    bench::record_replay
+);
+#[cfg(not(feature = "real-sensors"))]
+criterion::criterion_main!(benches);
+
+// Le banc d'essai utilise uniquement les capteurs simulés
+#[cfg(feature = "real-sensors")]
+fn main() {}
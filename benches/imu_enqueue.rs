@@ -0,0 +1,54 @@
+// Coût de la production d'un échantillon IMU (capteur simulé) et de son ajout dans la file de l'écrivain.
+// A lancer avec:
+//   cargo bench --no-default-features --features fake-sensors,fake-actuators
+
+#[cfg(not(feature = "real-sensors"))]
+mod bench {
+    use std::sync::Arc;
+    use std::time::Duration;
+
+    use chrono::Utc;
+    use criterion::Criterion;
+    use tokio::sync::{watch, Notify};
+
+    use voiturerc::channel::DropOldest;
+    use voiturerc::clock::Clock;
+    use voiturerc::config::{Config, MagCalibration, SensorMode};
+    use voiturerc::selftest::SelfTest;
+    use voiturerc::sensors::reader::Poller;
+    use voiturerc::sensors::sim::Simulation;
+
+    pub fn imu_enqueue(c: &mut Criterion) {
+        let mut config = Config::default();
+        config.sensors.mag.mode = SensorMode::Disabled;
+        config.sensors.analog.mode = SensorMode::Disabled;
+        config.sensors.gps.mode = SensorMode::Disabled;
+
+        let clock = Clock::virtual_at(Utc::now());
+        let simulation = Simulation::shared(&config.simulation);
+        let step = simulation.lock().unwrap().step_duration();
+        let (_calibration, mag_calibration) = watch::channel(MagCalibration::default());
+        let mut poller = Poller::new(&config, &clock, &simulation, &SelfTest::new(), &mag_calibration).unwrap();
+        let queue = DropOldest::new(config.writer.imu_queue, Arc::new(Notify::new()));
+        let mut elapsed = Duration::ZERO;
+
+        c.bench_function("produce and enqueue one IMU record", |b| {
+            b.iter(|| {
+                elapsed += step;
+                clock.set(elapsed);
+
+                poller.poll();
+                queue.push(poller.data.imu);
+            })
+        });
+    }
+}
+
+#[cfg(not(feature = "real-sensors"))]
+criterion::criterion_group!(benches, bench::imu_enqueue);
+#[cfg(not(feature = "real-sensors"))]
+criterion::criterion_main!(benches);
+
+// Le banc d'essai utilise uniquement les capteurs simulés
+#[cfg(feature = "real-sensors")]
+fn main() {}
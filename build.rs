@@ -0,0 +1,71 @@
+use std::process::Command;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Exécute une commande git, retourne sa sortie (None si git est indisponible)
+fn git(args: &[&str]) -> Option<String> {
+    let output = Command::new("git").args(args).output().ok()?;
+    if !output.status.success() {
+        return None;
+    }
+
+    Some(String::from_utf8_lossy(&output.stdout).trim().to_string())
+}
+
+/// Méthode du service gRPC, messages écrits à la main dans src/proto
+fn method(name: &str, route: &str, input: &str, output: &str) -> tonic_build::manual::MethodBuilder {
+    tonic_build::manual::Method::builder()
+        .name(name)
+        .route_name(route)
+        .input_type(format!("crate::proto::{}", input))
+        .output_type(format!("crate::proto::{}", output))
+        .codec_path("tonic::codec::ProstCodec")
+}
+
+// Serveur du service Telemetry (proto/telemetry.proto), sans protoc
+fn grpc() {
+    let service = tonic_build::manual::Service::builder()
+        .name("Telemetry")
+        .package("rc_telemetrie")
+        .method(
+            method("stream_telemetry", "StreamTelemetry", "StreamRequest", "Record")
+                .server_streaming()
+                .build(),
+        )
+        .method(method("get_status", "GetStatus", "StatusRequest", "Status").build())
+        .method(
+            method("send_control", "SendControl", "Control", "ControlSummary")
+                .client_streaming()
+                .build(),
+        )
+        .build();
+
+    tonic_build::manual::Builder::new()
+        .build_client(false)
+        .compile(&[service]);
+
+    println!("cargo:rerun-if-changed=proto");
+}
+
+// Informations de compilation, intégrées au binaire (voir src/metadata.rs), et service gRPC
+fn main() {
+    grpc();
+
+    let commit = git(&["rev-parse", "HEAD"]).unwrap_or_else(|| "unknown".to_string());
+    let dirty = git(&["status", "--porcelain", "--untracked-files=no"])
+        .map(|status| !status.is_empty())
+        .unwrap_or(false);
+    let built = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0);
+
+    println!("cargo:rustc-env=BUILD_GIT_COMMIT={}", commit);
+    println!("cargo:rustc-env=BUILD_GIT_DIRTY={}", dirty);
+    println!("cargo:rustc-env=BUILD_TIMESTAMP={}", built);
+
+    // Recompile lors d'un changement de commit ou de fichiers suivis
+    println!("cargo:rerun-if-changed=.git/HEAD");
+    println!("cargo:rerun-if-changed=.git/index");
+    println!("cargo:rerun-if-changed=.git/refs");
+    println!("cargo:rerun-if-changed=src");
+}
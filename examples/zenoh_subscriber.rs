@@ -0,0 +1,69 @@
+// Abonné Zenoh minimal: affiche les échantillons publiés par le véhicule ([zenoh] dans la config)
+//
+//   cargo run --example zenoh_subscriber -- [clé] [point d'accès]
+//   cargo run --example zenoh_subscriber -- "rc/voiturerc/**" tcp/192.168.1.20:7447
+//
+// Sans point d'accès, le véhicule est découvert par multicast (même réseau local).
+
+use zenoh::bytes::Encoding;
+
+fn f64_at(payload: &[u8], offset: usize) -> f64 {
+    f64::from_le_bytes(payload[4 + offset..4 + offset + 8].try_into().unwrap())
+}
+
+fn f32_at(payload: &[u8], offset: usize) -> f32 {
+    f32::from_le_bytes(payload[4 + offset..4 + offset + 4].try_into().unwrap())
+}
+
+/// Quelques champs des messages CDR (ros/msg), après l'horodatage (16 octets)
+fn describe_cdr(key: &str, payload: &[u8]) -> String {
+    if key.ends_with("/gps") && payload.len() >= 4 + 56 {
+        format!(
+            "lat {} lon {} ({} km/h)",
+            f64_at(payload, 24),
+            f64_at(payload, 32),
+            f64_at(payload, 16)
+        )
+    } else if key.ends_with("/imu") && payload.len() >= 4 + 32 {
+        format!(
+            "tangage {} roulis {} lacet {}",
+            f32_at(payload, 16),
+            f32_at(payload, 20),
+            f32_at(payload, 24)
+        )
+    } else {
+        format!("{} octets", payload.len())
+    }
+}
+
+#[tokio::main]
+async fn main() -> anyhow::Result<()> {
+    let mut args = std::env::args().skip(1);
+    let key = args.next().unwrap_or_else(|| "rc/**".to_string());
+
+    let mut config = zenoh::Config::default();
+    config
+        .insert_json5("mode", r#""peer""#)
+        .map_err(|e| anyhow::anyhow!(e))?;
+    if let Some(endpoint) = args.next() {
+        config
+            .insert_json5("connect/endpoints", &format!(r#"["{}"]"#, endpoint))
+            .map_err(|e| anyhow::anyhow!(e))?;
+    }
+
+    let session = zenoh::open(config).await.map_err(|e| anyhow::anyhow!(e))?;
+    let subscriber = session.declare_subscriber(&key).await.map_err(|e| anyhow::anyhow!(e))?;
+    println!("Abonné à {}", key);
+
+    while let Ok(sample) = subscriber.recv_async().await {
+        let key = sample.key_expr().to_string();
+        let payload = sample.payload().to_bytes();
+        if *sample.encoding() == Encoding::APPLICATION_CDR {
+            println!("{}: {}", key, describe_cdr(&key, &payload));
+        } else {
+            println!("{}: {}", key, String::from_utf8_lossy(&payload));
+        }
+    }
+
+    Ok(())
+}
@@ -0,0 +1,443 @@
+use std::collections::BTreeMap;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use tokio::sync::{broadcast, mpsc, watch};
+
+use crate::actuators::Control;
+use crate::clock::Clock;
+use crate::config::LinkLossPolicy;
+
+/// Commandes en attente entre les sources et la boucle de contrôle
+const QUEUE: usize = 16;
+
+/// Evènements de la liaison en attente de lecture
+const LINK_EVENTS: usize = 16;
+
+/// Commande validée, horodatée à sa réception (horloge de l'arbitrage)
+pub struct Command {
+    /// Source de la commande (ex: "db", "grpc")
+    pub source: &'static str,
+    pub control: Control,
+    pub received: Instant,
+    /// Vitesses maximales en vigueur à la transmission: marche avant, marche arrière
+    pub max_speed: (f64, f64),
+}
+
+impl Command {
+    /// Commande d'une limite de vitesse plus basse (coupure, arrêt d'urgence): la consigne de
+    /// la rampe est ramenée sous `max_speed`, la sortie au-delà sans rampe
+    pub fn limited(&self) -> bool {
+        self.source == "limit"
+    }
+}
+
+/// Dernière commande acceptée, sortie appliquée aux actionneurs et limite de vitesse active
+#[derive(Clone, Default)]
+pub struct ControlState {
+    pub input: Control,
+    /// Réception de la dernière commande acceptée
+    pub received: Option<Instant>,
+    pub output: Control,
+    pub limit: Option<SpeedLimit>,
+    /// Dernière sortie appliquée par la boucle de contrôle, None: boucle pas encore démarrée
+    pub applied: Option<Instant>,
+}
+
+/// Limite de vitesse la plus basse et sa cause (ex: "rollover", "thermal.esc")
+#[derive(Clone, Debug, PartialEq)]
+pub struct SpeedLimit {
+    pub max: f64,
+    pub cause: String,
+}
+
+/// Vitesse maximale par source, marche avant et marche arrière (valeurs absolues), la plus basse
+/// s'applique dans chaque sens
+type Limits = BTreeMap<String, (f64, f64)>;
+
+/// Vitesses maximales autorisées par l'ensemble des limites: marche avant, marche arrière
+fn max_speed(limits: &Limits) -> (f64, f64) {
+    limits
+        .values()
+        .fold((1.0, 1.0), |(forward, reverse), max| (forward.min(max.0), reverse.min(max.1)))
+}
+
+/// Limite la plus basse, quel que soit le sens, la première source dans l'ordre alphabétique en
+/// cas d'égalité
+fn lowest(limits: &Limits) -> Option<SpeedLimit> {
+    limits
+        .iter()
+        .map(|(cause, (forward, reverse))| (cause, forward.min(*reverse)))
+        .fold(None, |lowest: Option<(&String, f64)>, (cause, max)| match lowest {
+            Some((_, current)) if current <= max => lowest,
+            _ => Some((cause, max)),
+        })
+        .map(|(cause, max)| SpeedLimit {
+            max,
+            cause: cause.clone(),
+        })
+}
+
+/// Perte ou retour de la liaison de contrôle
+#[derive(Clone, Debug, PartialEq)]
+pub enum LinkEvent {
+    /// Aucune commande d'aucune source depuis le délai de l'homme mort
+    Lost(LinkLossPolicy),
+    /// Commandes reçues à nouveau: durée depuis la dernière commande et politique appliquée
+    Restored { outage: Duration, policy: LinkLossPolicy },
+}
+
+/// Etat de la liaison, partagé entre les sources (retour) et la boucle de contrôle (perte)
+struct Link {
+    policy: LinkLossPolicy,
+    /// Dernière commande reçue, None: aucune liaison établie
+    last: Option<Instant>,
+    lost: bool,
+    /// Pertes de la liaison depuis le démarrage (failsafe)
+    failsafes: u64,
+    events: broadcast::Sender<LinkEvent>,
+}
+
+impl Link {
+    /// Commande reçue (même refusée car le véhicule est désarmé)
+    fn seen(&mut self, now: Instant) {
+        let last = self.last.replace(now);
+        if !std::mem::take(&mut self.lost) {
+            return;
+        }
+        let outage = last.map(|last| now.duration_since(last)).unwrap_or_default();
+        let _ = self.events.send(LinkEvent::Restored {
+            outage,
+            policy: self.policy,
+        });
+    }
+
+    /// Délai de l'homme mort expiré: retourne vrai à la perte d'une liaison établie
+    fn timeout(&mut self) -> bool {
+        if self.lost || self.last.is_none() {
+            return false;
+        }
+        self.lost = true;
+        self.failsafes += 1;
+        let _ = self.events.send(LinkEvent::Lost(self.policy));
+        true
+    }
+}
+
+/// Résultat de l'attente d'une commande
+pub enum Next {
+    Command(Command),
+    /// Aucune commande récente: les actionneurs doivent passer au neutre (homme mort)
+    Timeout,
+    /// Plus aucune source
+    Closed,
+}
+
+/// Entrée des commandes, partagée par toutes les sources. Chaque commande est validée avant
+/// d'être transmise; la plus récente, toutes sources confondues, est appliquée.
+#[derive(Clone)]
+pub struct Commands {
+    sender: mpsc::Sender<Command>,
+    clock: Clock,
+    /// Véhicule armé: désarmé, toutes les commandes sont refusées
+    armed: Arc<watch::Sender<bool>>,
+    /// Vitesse maximale imposée par chaque règle de sécurité (retournement, batterie)
+    limits: Arc<watch::Sender<Limits>>,
+    state: Arc<watch::Sender<ControlState>>,
+    link: Arc<Mutex<Link>>,
+    /// Demandes d'armement transmises aux vérifications avant armement, si installées
+    prearm: Arc<Mutex<Option<mpsc::UnboundedSender<&'static str>>>>,
+}
+
+impl Commands {
+    /// Vérifie et transmet une commande, refusée si elle est invalide, si le véhicule est
+    /// désarmé ou si la file est pleine
+    pub fn submit(&self, source: &'static str, control: Control) -> anyhow::Result<()> {
+        control.validate()?;
+        let now = self.clock.now();
+        self.link.lock().unwrap().seen(now);
+        if !*self.armed.borrow() {
+            return Err(anyhow::anyhow!("Véhicule désarmé"));
+        }
+        self.sender
+            .try_send(Command {
+                source,
+                control,
+                received: now,
+                max_speed: (1.0, 1.0),
+            })
+            .map_err(|e| anyhow::anyhow!("File des commandes: {}", e))?;
+        self.state.send_modify(|state| {
+            state.input = control;
+            state.received = Some(now);
+        });
+        Ok(())
+    }
+
+    /// Arme ou désarme le véhicule. Désarmé, les commandes sont refusées et la boucle de
+    /// contrôle passe au neutre à l'expiration de l'homme mort.
+    pub fn arm(&self, source: &'static str, armed: bool) {
+        if self.armed.send_replace(armed) != armed {
+            tracing::info!(target: "control", "Véhicule {} ({})", if armed { "armé" } else { "désarmé" }, source);
+        }
+    }
+
+    /// Demande d'armement d'une source externe: soumise aux vérifications avant armement si
+    /// elles sont installées, le désarmement est toujours immédiat
+    pub fn request_arm(&self, source: &'static str, armed: bool) {
+        if armed {
+            if let Some(prearm) = self.prearm.lock().unwrap().as_ref() {
+                if prearm.send(source).is_ok() {
+                    return;
+                }
+            }
+        }
+        self.arm(source, armed);
+    }
+
+    /// Installe les vérifications avant armement: les demandes d'armement sont reçues par
+    /// le récepteur retourné, qui arme lui-même le véhicule
+    pub fn prearm(&self) -> mpsc::UnboundedReceiver<&'static str> {
+        let (sender, receiver) = mpsc::unbounded_channel();
+        *self.prearm.lock().unwrap() = Some(sender);
+        receiver
+    }
+
+    /// Durée depuis la dernière commande d'une source, None: aucune commande reçue
+    pub fn last_command(&self) -> Option<Duration> {
+        self.link.lock().unwrap().last.map(|last| self.clock.since(last))
+    }
+
+    /// Liaison de contrôle perdue: aucune commande depuis le délai de l'homme mort, après une
+    /// liaison établie (failsafe)
+    pub fn link_lost(&self) -> bool {
+        self.link.lock().unwrap().lost
+    }
+
+    /// Pertes de la liaison de contrôle depuis le démarrage
+    pub fn failsafes(&self) -> u64 {
+        self.link.lock().unwrap().failsafes
+    }
+
+    /// Durée depuis la dernière sortie appliquée par la boucle de contrôle, None: aucune.
+    /// Une boucle active applique une sortie au moins à chaque délai de l'homme mort.
+    pub fn last_applied(&self) -> Option<Duration> {
+        self.state.borrow().applied.map(|applied| self.clock.since(applied))
+    }
+
+    /// Boucle de contrôle terminée (arbitrage abandonné, ex: actionneurs indisponibles)
+    pub fn closed(&self) -> bool {
+        self.sender.is_closed()
+    }
+
+    /// Horloge de l'arbitrage, référence des instants de réception des commandes
+    pub fn clock(&self) -> &Clock {
+        &self.clock
+    }
+
+    /// Etat armé/désarmé, notifié à chaque changement
+    pub fn armed(&self) -> watch::Receiver<bool> {
+        self.armed.subscribe()
+    }
+
+    /// Limite la vitesse (valeur absolue, 0: moteur coupé) ou lève la limite d'une source. La
+    /// plus basse des limites s'applique à chaque commande, la direction reste commandée.
+    pub fn limit(&self, source: &str, max: Option<f64>) {
+        self.set_limit(source, max.map(|max| (max, max)));
+    }
+
+    /// Limite la vitesse en marche avant uniquement (0: marche avant coupée, la marche arrière
+    /// reste possible) ou lève la limite d'une source
+    pub fn limit_forward(&self, source: &str, max: Option<f64>) {
+        self.set_limit(source, max.map(|max| (max, 1.0)));
+    }
+
+    fn set_limit(&self, source: &str, max: Option<(f64, f64)>) {
+        let changed = self.limits.send_if_modified(|limits| {
+            let previous = match max {
+                Some(max) => limits.insert(source.to_string(), max),
+                None => limits.remove(source),
+            };
+            previous != max
+        });
+        if changed {
+            let limit = lowest(&self.limits.borrow());
+            self.state.send_modify(|state| state.limit = limit);
+            match max {
+                Some((forward, reverse)) if forward == reverse => {
+                    tracing::info!(target: "control", "Vitesse limitée à {:.0} % ({})", forward * 100.0, source)
+                }
+                Some((forward, _)) => {
+                    tracing::info!(target: "control", "Marche avant limitée à {:.0} % ({})", forward * 100.0, source)
+                }
+                None => tracing::info!(target: "control", "Limite de vitesse levée ({})", source),
+            }
+        }
+    }
+
+    /// Coupe ou rétablit le moteur (limite de vitesse nulle)
+    pub fn cutoff(&self, source: &str, active: bool) {
+        self.limit(source, active.then_some(0.0));
+    }
+
+    /// Commande et sortie des actionneurs, mises à jour à chaque changement
+    pub fn state(&self) -> watch::Receiver<ControlState> {
+        self.state.subscribe()
+    }
+
+    /// Abonnement aux pertes et retours de la liaison de contrôle
+    pub fn link(&self) -> broadcast::Receiver<LinkEvent> {
+        self.link.lock().unwrap().events.subscribe()
+    }
+}
+
+/// Réception des commandes par la boucle de contrôle, mêmes règles pour toutes les sources
+pub struct Arbiter {
+    receiver: mpsc::Receiver<Command>,
+    clock: Clock,
+    dead_timeout: Duration,
+    /// Début de l'attente en cours, conservé si `next` est interrompu
+    waiting: Option<Instant>,
+    limits: watch::Receiver<Limits>,
+    /// Limites (avant, arrière) de la dernière commande transmise
+    max_speed: (f64, f64),
+    state: Arc<watch::Sender<ControlState>>,
+    armed: Arc<watch::Sender<bool>>,
+    link: Arc<Mutex<Link>>,
+}
+
+impl Arbiter {
+    /// `policy`: action à la perte de la liaison, c'est-à-dire sans commande d'aucune source
+    /// pendant le délai de l'homme mort. Une source active suffit à l'éviter. Les délais sont
+    /// mesurés avec `clock`.
+    pub fn new(clock: &Clock, dead_timeout: Duration, policy: LinkLossPolicy) -> (Commands, Self) {
+        let (sender, receiver) = mpsc::channel(QUEUE);
+        // Armé au démarrage: les sources existantes ne gèrent pas l'armement
+        let armed = Arc::new(watch::channel(true).0);
+        let (limits, limits_receiver) = watch::channel(Limits::new());
+        let state = Arc::new(watch::channel(ControlState::default()).0);
+        let link = Arc::new(Mutex::new(Link {
+            policy,
+            last: None,
+            lost: false,
+            failsafes: 0,
+            events: broadcast::channel(LINK_EVENTS).0,
+        }));
+        let commands = Commands {
+            sender,
+            clock: clock.clone(),
+            armed: armed.clone(),
+            limits: Arc::new(limits),
+            state: state.clone(),
+            link: link.clone(),
+            prearm: Arc::new(Mutex::new(None)),
+        };
+        (
+            commands,
+            Self {
+                receiver,
+                clock: clock.clone(),
+                dead_timeout,
+                waiting: None,
+                limits: limits_receiver,
+                max_speed: (1.0, 1.0),
+                state,
+                armed,
+                link,
+            },
+        )
+    }
+
+    /// Attend la prochaine commande. Une commande restée en file plus longtemps que le délai de
+    /// l'homme mort est ignorée; sans commande pendant ce délai, retourne `Next::Timeout`.
+    /// Une limite de vitesse plus basse est appliquée immédiatement, sans attendre la commande
+    /// suivante. Interrompu (select), le délai continue depuis le début de l'attente.
+    pub async fn next(&mut self) -> Next {
+        let next = self.wait().await;
+        self.waiting = None;
+        next
+    }
+
+    async fn wait(&mut self) -> Next {
+        let since = *self.waiting.get_or_insert(self.clock.now());
+        loop {
+            let remaining = self.dead_timeout.saturating_sub(self.clock.since(since));
+            let result = tokio::select! {
+                result = self.clock.timeout(remaining, self.receiver.recv()) => result,
+                Ok(()) = self.limits.changed() => {
+                    let (forward, reverse) = max_speed(&self.limits.borrow_and_update());
+                    // Limite relevée: la rampe est déjà sous l'ancienne limite
+                    let (last_forward, last_reverse) = std::mem::replace(&mut self.max_speed, (forward, reverse));
+                    if forward >= last_forward && reverse >= last_reverse {
+                        continue;
+                    }
+                    // Consigne (dernière commande) ou sortie au-delà: la rampe ne doit plus
+                    // dépasser la nouvelle limite
+                    let (input, output) = {
+                        let state = self.state.borrow();
+                        (state.input, state.output)
+                    };
+                    let allowed = -reverse..=forward;
+                    if allowed.contains(&input.speed) && allowed.contains(&output.speed) {
+                        continue;
+                    }
+                    return Next::Command(Command {
+                        source: "limit",
+                        control: Control {
+                            speed: output.speed.clamp(-reverse, forward),
+                            ..output
+                        },
+                        received: self.clock.now(),
+                        max_speed: (forward, reverse),
+                    });
+                }
+            };
+
+            match result {
+                Some(Some(command)) if self.clock.since(command.received) > self.dead_timeout => continue,
+                Some(Some(mut command)) => {
+                    let (forward, reverse) = max_speed(&self.limits.borrow());
+                    command.control.speed = command.control.speed.clamp(-reverse, forward);
+                    command.max_speed = (forward, reverse);
+                    self.max_speed = (forward, reverse);
+                    return Next::Command(command);
+                }
+                Some(None) => return Next::Closed,
+                None => {
+                    self.link_timeout();
+                    return Next::Timeout;
+                }
+            }
+        }
+    }
+
+    /// Perte de la liaison: le neutre est appliqué par la boucle de contrôle (`Next::Timeout`),
+    /// la politique décide en plus du désarmement. Ignorée si le véhicule est déjà désarmé.
+    fn link_timeout(&self) {
+        if !*self.armed.borrow() {
+            return;
+        }
+        let mut link = self.link.lock().unwrap();
+        if !link.timeout() {
+            return;
+        }
+        tracing::info!(target: "control", "Liaison perdue: {}", link.policy.name());
+        if link.policy == LinkLossPolicy::StopAndDisarm && self.armed.send_replace(false) {
+            tracing::info!(target: "control", "Véhicule désarmé (link_loss)");
+        }
+    }
+
+    /// Horloge de l'arbitrage, partagée avec la boucle de contrôle (rampe, moteur)
+    pub fn clock(&self) -> &Clock {
+        &self.clock
+    }
+
+    /// Sortie effectivement appliquée aux actionneurs (journal blackbox)
+    pub fn applied(&self, output: Control) {
+        let now = self.clock.now();
+        self.state.send_modify(|state| {
+            state.output = output;
+            state.applied = Some(now);
+        });
+    }
+}
@@ -0,0 +1,97 @@
+use std::time::{Duration, Instant};
+
+use crate::actuators::Control;
+use crate::config::AutoDisarmConfig;
+
+/// Durée expirée
+#[derive(Debug, PartialEq)]
+pub enum Expiry {
+    /// Durée maximale armé en continu
+    Armed,
+    /// Aucune commande au-delà du seuil
+    Idle,
+}
+
+impl Expiry {
+    pub fn kind(&self) -> &'static str {
+        match self {
+            Expiry::Armed => "armed_timeout",
+            Expiry::Idle => "idle_timeout",
+        }
+    }
+}
+
+/// Durées restantes avant le désarmement, None: durée désactivée ou sans objet
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct Countdown {
+    pub armed: Option<Duration>,
+    pub idle: Option<Duration>,
+}
+
+/// Minuteries du désarmement automatique. La durée armé repart à chaque armement,
+/// l'inactivité à chaque armement ou commande au-delà du seuil; chacune n'expire qu'une fois.
+pub struct AutoDisarm {
+    max_armed: Option<Duration>,
+    idle: Option<Duration>,
+    threshold: f64,
+    /// Début de l'armement en cours
+    armed_since: Option<Instant>,
+    /// Dernière activité, None: inactivité déjà expirée
+    active_at: Option<Instant>,
+}
+
+impl AutoDisarm {
+    pub fn new(config: &AutoDisarmConfig, armed: bool, now: Instant) -> Self {
+        let minutes = |min: u64| (min > 0).then(|| Duration::from_secs(min * 60));
+        Self {
+            max_armed: minutes(config.max_armed_min),
+            idle: minutes(config.idle_min),
+            threshold: config.idle_threshold,
+            armed_since: armed.then_some(now),
+            active_at: Some(now),
+        }
+    }
+
+    /// Changement de l'armement
+    pub fn armed(&mut self, armed: bool, now: Instant) {
+        self.armed_since = armed.then_some(now);
+        if armed {
+            self.active_at = Some(now);
+        }
+    }
+
+    /// Commande reçue à `at`
+    pub fn input(&mut self, control: &Control, at: Instant) {
+        if control.speed.abs() > self.threshold || control.steer.abs() > self.threshold {
+            self.active_at = Some(at);
+        }
+    }
+
+    /// Durée expirée depuis le dernier appel (durée armé en priorité)
+    pub fn update(&mut self, now: Instant) -> Option<Expiry> {
+        if let (Some(max), Some(since)) = (self.max_armed, self.armed_since) {
+            if now.duration_since(since) >= max {
+                self.armed_since = None;
+                return Some(Expiry::Armed);
+            }
+        }
+        if let (Some(idle), Some(at)) = (self.idle, self.active_at) {
+            if now.duration_since(at) >= idle {
+                self.active_at = None;
+                return Some(Expiry::Idle);
+            }
+        }
+        None
+    }
+
+    pub fn countdown(&self, now: Instant) -> Countdown {
+        let remaining =
+            |limit: Option<Duration>, since: Option<Instant>| Some(limit?.saturating_sub(now.duration_since(since?)));
+        Countdown {
+            armed: remaining(self.max_armed, self.armed_since),
+            idle: self
+                .idle
+                .map(|idle| remaining(Some(idle), self.active_at).unwrap_or_default()),
+        }
+    }
+}
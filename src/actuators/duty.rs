@@ -0,0 +1,32 @@
+// Rapports cycliques des sorties PWM (50 Hz) de l'ESC et du servo de direction
+
+use crate::config::{MotorConfig, SteeringConfig};
+
+/// Période des sorties PWM (µs)
+const PERIOD_US: f64 = 20_000.0;
+
+/// Rapport cyclique de l'ESC calibré pour une vitesse dans [-1, 1] (négatif: marche arrière),
+/// neutre hors de l'intervalle
+pub fn calibrated_motor(speed: f64, calibration: &MotorConfig) -> f64 {
+    // Validation SYSTEMATIQUE des données.
+    if !(-1.0..=1.0).contains(&speed) || speed == 0.0 {
+        return calibration.neutral_us / PERIOD_US;
+    }
+
+    let endpoint = if speed < 0.0 { calibration.reverse_us } else { calibration.forward_us };
+    let direction = (endpoint - calibration.neutral_us).signum();
+    let range = (endpoint - calibration.neutral_us).abs() - calibration.deadband_us;
+    (calibration.neutral_us + direction * (calibration.deadband_us + speed.abs() * range)) / PERIOD_US
+}
+
+/// Rapport cyclique du servo calibré pour une direction dans [-1, 1] (négatif: gauche), limitée
+/// aux butées, centre calibré si la direction n'est pas un nombre
+pub fn calibrated_steering(steer: f64, calibration: &SteeringConfig) -> f64 {
+    if steer.is_nan() {
+        return calibration.center_us / PERIOD_US;
+    }
+
+    let steer = if calibration.reverse { -steer } else { steer }.clamp(-1.0, 1.0);
+    let endpoint = if steer < 0.0 { calibration.left_us } else { calibration.right_us };
+    (calibration.center_us + steer.abs() * (endpoint - calibration.center_us)) / PERIOD_US
+}
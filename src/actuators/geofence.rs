@@ -0,0 +1,199 @@
+use crate::config::{GeofenceConfig, Severity};
+use crate::sensors::reader::GpsData;
+
+/// Rayon terrestre moyen (m)
+const EARTH_RADIUS: f64 = 6_371_000.0;
+
+/// Polygone projeté localement (m), autour de son premier sommet
+pub struct Fence {
+    pub name: String,
+    origin: (f64, f64),
+    points: Vec<(f64, f64)>,
+}
+
+impl Fence {
+    /// Sommets en [latitude, longitude]
+    pub fn new(name: &str, polygon: &[[f64; 2]]) -> Self {
+        let origin = polygon
+            .first()
+            .map(|[latitude, longitude]| (*latitude, *longitude))
+            .unwrap_or_default();
+        let mut fence = Self {
+            name: name.to_string(),
+            origin,
+            points: Vec::new(),
+        };
+        fence.points = polygon
+            .iter()
+            .map(|[latitude, longitude]| fence.project(*latitude, *longitude))
+            .collect();
+        fence
+    }
+
+    /// Projection équirectangulaire: précise à l'échelle d'un terrain
+    fn project(&self, latitude: f64, longitude: f64) -> (f64, f64) {
+        let x = (longitude - self.origin.1).to_radians() * EARTH_RADIUS * self.origin.0.to_radians().cos();
+        let y = (latitude - self.origin.0).to_radians() * EARTH_RADIUS;
+        (x, y)
+    }
+
+    /// Distance à la limite (m), positive à l'intérieur, négative à l'extérieur
+    pub fn depth(&self, latitude: f64, longitude: f64) -> f64 {
+        let (x, y) = self.project(latitude, longitude);
+        let mut inside = false;
+        let mut distance = f64::INFINITY;
+
+        for (n, a) in self.points.iter().enumerate() {
+            let b = self.points[(n + 1) % self.points.len()];
+            // Lancer de rayon horizontal
+            if (a.1 > y) != (b.1 > y) && x < a.0 + (y - a.1) * (b.0 - a.0) / (b.1 - a.1) {
+                inside = !inside;
+            }
+
+            let (dx, dy) = (b.0 - a.0, b.1 - a.1);
+            let length = dx * dx + dy * dy;
+            let t = if length > 0.0 {
+                (((x - a.0) * dx + (y - a.1) * dy) / length).clamp(0.0, 1.0)
+            } else {
+                0.0
+            };
+            distance = distance.min((x - a.0 - t * dx).hypot(y - a.1 - t * dy));
+        }
+
+        if inside {
+            distance
+        } else {
+            -distance
+        }
+    }
+}
+
+/// Règle appliquée selon la position
+#[derive(Clone, Debug, PartialEq)]
+pub enum Zone {
+    /// Aucune limite
+    Free,
+    /// Dans une zone lente (la plus lente si plusieurs)
+    Slow { name: String, max: f64 },
+    /// Position non fiable: vitesse limitée par précaution
+    Degraded,
+    /// Hors de la zone autorisée: arrêt
+    Outside { name: String },
+}
+
+/// Changement de zone, avec la position
+#[derive(Debug, PartialEq)]
+pub struct Change {
+    pub zone: Zone,
+    /// Vitesse maximale, None: aucune limite
+    pub max: Option<f64>,
+    pub latitude: f64,
+    pub longitude: f64,
+}
+
+impl Change {
+    pub fn severity(&self) -> Severity {
+        match self.zone {
+            Zone::Free => Severity::Info,
+            Zone::Slow { .. } | Zone::Degraded => Severity::Warning,
+            Zone::Outside { .. } => Severity::Critical,
+        }
+    }
+
+    pub fn message(&self) -> String {
+        let position = format!("({:.6}, {:.6})", self.latitude, self.longitude);
+        let max = self.max.unwrap_or(1.0) * 100.0;
+        match &self.zone {
+            Zone::Free => format!("Hors des zones lentes {}: vitesse libre", position),
+            Zone::Slow { name, .. } => format!("Zone lente {} {}: vitesse limitée à {:.0} %", name, position, max),
+            Zone::Degraded => format!("Position GPS non fiable {}: vitesse limitée à {:.0} %", position, max),
+            Zone::Outside { name } => format!("Sortie de la zone {} {}: arrêt", name, position),
+        }
+    }
+}
+
+/// Evaluation des zones avec hystérésis: une zone est atteinte dès sa limite franchie, mais
+/// n'est quittée qu'à `margin_m` au-delà
+pub struct Geofence {
+    containment: Option<Fence>,
+    /// Zones lentes, vitesse maximale et présence à l'intérieur
+    slow: Vec<(Fence, f64, bool)>,
+    outside: bool,
+    margin: f64,
+    min_satellites: u8,
+    degraded: f64,
+    zone: Zone,
+}
+
+impl Geofence {
+    pub fn new(config: &GeofenceConfig) -> Self {
+        Self {
+            containment: (!config.containment.is_empty())
+                .then(|| Fence::new(&config.containment_name, &config.containment)),
+            slow: config
+                .slow_zones
+                .iter()
+                .map(|zone| (Fence::new(&zone.name, &zone.polygon), zone.max_speed, false))
+                .collect(),
+            outside: false,
+            margin: config.margin_m,
+            min_satellites: config.min_satellites,
+            degraded: config.degraded_max_speed,
+            zone: Zone::Free,
+        }
+    }
+
+    /// Nouvelle position (None si périmée), retourne le changement de zone
+    pub fn update(&mut self, gps: Option<&GpsData>) -> Option<Change> {
+        let reliable = gps.filter(|gps| gps.fix && gps.satellites >= self.min_satellites);
+
+        let (zone, max) = match reliable {
+            None => (Zone::Degraded, Some(self.degraded)),
+            Some(gps) => self.evaluate(gps.latitude, gps.longitude),
+        };
+        if zone == self.zone {
+            return None;
+        }
+
+        self.zone = zone.clone();
+        let (latitude, longitude) = gps.map(|gps| (gps.latitude, gps.longitude)).unwrap_or_default();
+        Some(Change {
+            zone,
+            max,
+            latitude,
+            longitude,
+        })
+    }
+
+    fn evaluate(&mut self, latitude: f64, longitude: f64) -> (Zone, Option<f64>) {
+        let margin = self.margin;
+        if let Some(containment) = self.containment.as_ref() {
+            let depth = containment.depth(latitude, longitude);
+            self.outside = if self.outside { depth < margin } else { depth < 0.0 };
+            if self.outside {
+                let name = containment.name.clone();
+                return (Zone::Outside { name }, Some(0.0));
+            }
+        }
+
+        let mut slowest: Option<(&str, f64)> = None;
+        for (fence, max, inside) in self.slow.iter_mut() {
+            let depth = fence.depth(latitude, longitude);
+            *inside = if *inside { depth > -margin } else { depth > 0.0 };
+            if *inside && slowest.is_none_or(|(_, slowest)| *max < slowest) {
+                slowest = Some((&fence.name, *max));
+            }
+        }
+
+        match slowest {
+            Some((name, max)) => (
+                Zone::Slow {
+                    name: name.to_string(),
+                    max,
+                },
+                Some(max),
+            ),
+            None => (Zone::Free, None),
+        }
+    }
+}
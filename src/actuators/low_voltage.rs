@@ -0,0 +1,160 @@
+use std::time::{Duration, Instant};
+
+use crate::config::{LowVoltageConfig, Severity};
+
+/// Mesure de la batterie et charge au même instant
+pub struct Sample {
+    /// Tension de la batterie (V)
+    pub voltage: f32,
+    /// Courant mesuré (A), si disponible
+    pub current: Option<f32>,
+    /// Vitesse appliquée au moteur (-1 à 1)
+    pub throttle: f64,
+}
+
+/// Palier franchi
+#[derive(Debug, PartialEq)]
+pub enum Step {
+    /// Vitesse maximale réduite (palier 1, 2, ...)
+    Limit { stage: usize, max: f64, cell_v: f32 },
+    /// Moteur coupé et véhicule désarmé, définitivement
+    Cutoff { cell_v: f32 },
+}
+
+impl Step {
+    /// Vitesse maximale après le palier
+    pub fn max(&self) -> f64 {
+        match self {
+            Step::Limit { max, .. } => *max,
+            Step::Cutoff { .. } => 0.0,
+        }
+    }
+
+    pub fn kind(&self) -> &'static str {
+        match self {
+            Step::Limit { .. } => "low_voltage",
+            Step::Cutoff { .. } => "low_voltage_cutoff",
+        }
+    }
+
+    pub fn severity(&self) -> Severity {
+        match self {
+            Step::Limit { .. } => Severity::Warning,
+            Step::Cutoff { .. } => Severity::Critical,
+        }
+    }
+
+    pub fn message(&self) -> String {
+        match self {
+            Step::Limit { stage, max, cell_v } => format!(
+                "Tension basse ({:.2} V/élément): vitesse limitée à {:.0} % (palier {})",
+                cell_v,
+                max * 100.0,
+                stage
+            ),
+            Step::Cutoff { cell_v } => {
+                format!(
+                    "Tension basse ({:.2} V/élément): moteur coupé, véhicule désarmé",
+                    cell_v
+                )
+            }
+        }
+    }
+}
+
+/// Protection de la batterie: chaque période passée sous la tension minimale à faible charge
+/// franchit un palier, après une durée et un nombre de mesures consécutives minimaux. Les
+/// mesures sous forte charge sont ignorées (chute de tension), les paliers ne sont jamais levés.
+/// La période ne s'interrompt qu'au-delà du seuil plus l'hystérésis: une tension qui oscille
+/// autour du seuil ne la relance pas.
+pub struct LowVoltage {
+    cells: f32,
+    floor: f32,
+    hysteresis: f32,
+    sustain: Duration,
+    sustain_samples: u32,
+    limits: Vec<f64>,
+    low_load_throttle: f64,
+    low_load_a: f32,
+    /// Résistance interne (Ω)
+    resistance: f32,
+    /// Paliers franchis, au-delà des limites: coupure
+    stage: usize,
+    /// Début de la période sous la tension minimale
+    below: Option<Instant>,
+    /// Mesures de la période en cours
+    samples: u32,
+}
+
+impl LowVoltage {
+    pub fn new(config: &LowVoltageConfig) -> Self {
+        Self {
+            cells: config.cells.max(1) as f32,
+            floor: config.floor_cell_v,
+            hysteresis: config.hysteresis_cell_v,
+            sustain: Duration::from_secs_f64(config.sustain_s),
+            sustain_samples: config.sustain_samples.max(1),
+            limits: config.throttle_limits.clone(),
+            low_load_throttle: config.low_load_throttle,
+            low_load_a: config.low_load_a,
+            resistance: config.resistance_mohm / 1000.0,
+            stage: 0,
+            below: None,
+            samples: 0,
+        }
+    }
+
+    /// Coupure finale atteinte
+    pub fn latched(&self) -> bool {
+        self.stage > self.limits.len()
+    }
+
+    /// Nouvelle mesure, retourne le palier franchi
+    pub fn update(&mut self, sample: &Sample, now: Instant) -> Option<Step> {
+        if self.latched() {
+            return None;
+        }
+
+        // Tension à vide estimée: compensation de la chute due au courant, si mesuré
+        let (estimate, low_load) = match sample.current {
+            Some(current) => (
+                sample.voltage + current.max(0.0) * self.resistance,
+                current.abs() <= self.low_load_a,
+            ),
+            None => (sample.voltage, sample.throttle.abs() <= self.low_load_throttle),
+        };
+        if !low_load {
+            return None;
+        }
+
+        let cell_v = estimate / self.cells;
+        if cell_v >= self.floor + self.hysteresis {
+            self.below = None;
+            self.samples = 0;
+            return None;
+        }
+        // Entre le seuil et l'hystérésis: la période en cours continue
+        if cell_v >= self.floor && self.below.is_none() {
+            return None;
+        }
+
+        let since = *self.below.get_or_insert(now);
+        self.samples += 1;
+        if now.duration_since(since) < self.sustain || self.samples < self.sustain_samples {
+            return None;
+        }
+
+        // Le palier suivant demande une nouvelle période complète
+        self.below = Some(now);
+        self.samples = 1;
+        self.stage += 1;
+        Some(match self.limits.get(self.stage - 1) {
+            Some(max) => Step::Limit {
+                stage: self.stage,
+                max: *max,
+                cell_v,
+            },
+            None => Step::Cutoff { cell_v },
+        })
+    }
+}
@@ -0,0 +1,80 @@
+use crate::actuators::{duty, Control};
+use crate::config::{MotorConfig, SteeringConfig};
+
+/// Actionneurs factices: les commandes sont validées et enregistrées, sans aucune sortie PWM
+#[derive(Default)]
+pub struct Mock {
+    /// Calibrations de la direction et de l'ESC, comme les actionneurs réels
+    steering: SteeringConfig,
+    motor: MotorConfig,
+    steer: f64,
+    speed: f64,
+    /// Rapports cycliques qui seraient appliqués (moteur, direction)
+    cycles: (f64, f64),
+    applied: u64,
+    rejected: u64,
+}
+
+impl Mock {
+    pub fn new(steering: SteeringConfig, motor: MotorConfig) -> Self {
+        let mut mock = Self {
+            steering,
+            motor,
+            ..Self::default()
+        };
+        mock.neutral();
+        mock
+    }
+
+    /// Applique une commande. Une commande invalide est refusée et remet les actionneurs au neutre.
+    pub fn apply(&mut self, control: &Control) -> anyhow::Result<()> {
+        if let Err(e) = control.validate() {
+            self.rejected += 1;
+            self.neutral();
+            return Err(e);
+        }
+
+        self.steer = control.steer;
+        self.speed = control.speed;
+        self.cycles = (
+            duty::calibrated_motor(control.speed, &self.motor),
+            duty::calibrated_steering(control.steer, &self.steering),
+        );
+        self.applied += 1;
+        Ok(())
+    }
+
+    /// Remet les actionneurs au neutre
+    pub fn neutral(&mut self) {
+        self.steer = 0.0;
+        self.speed = 0.0;
+        self.cycles = (
+            duty::calibrated_motor(0.0, &self.motor),
+            duty::calibrated_steering(0.0, &self.steering),
+        );
+    }
+
+    /// Vitesse appliquée par la rampe, après la commande
+    pub fn speed(&mut self, speed: f64) {
+        self.speed = speed;
+        self.cycles.0 = duty::calibrated_motor(speed, &self.motor);
+    }
+
+    /// Failsafe: moteur au neutre, direction à la position du failsafe
+    pub fn failsafe(&mut self, control: &Control) {
+        self.steer = control.steer;
+        self.speed = 0.0;
+        self.cycles = (
+            duty::calibrated_motor(0.0, &self.motor),
+            duty::calibrated_steering(control.steer, &self.steering),
+        );
+    }
+
+    /// Résumé des commandes reçues
+    pub fn summary(&self) -> String {
+        format!(
+            "{} commande(s) appliquée(s), {} refusée(s), dernière: steer {} speed {} (PWM {:.4} / {:.4})",
+            self.applied, self.rejected, self.steer, self.speed, self.cycles.1, self.cycles.0
+        )
+    }
+}
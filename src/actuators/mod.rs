@@ -1,21 +1,77 @@
-#[cfg(feature = "real-actuators")]
-pub mod motor;
-
 #[cfg(feature = "real-actuators")]
 pub mod steering;
 
 #[cfg(feature = "real-actuators")]
 pub mod switch;
 
+#[cfg(feature = "real-actuators")]
+pub mod power_latch;
+
+pub mod arbiter;
+pub mod auto_disarm;
+pub mod duty;
+pub mod geofence;
+pub mod low_voltage;
+pub mod mock;
+pub mod motor;
+pub mod obstacle;
+pub mod prearm;
+pub mod ramp;
+pub mod rollover;
+pub mod status_led;
+pub mod thermal;
+
+use anyhow::anyhow;
 use serde::Deserialize;
 
-#[derive(Deserialize)]
-pub(crate) struct Control {
+#[derive(Clone, Copy, Default, Deserialize)]
+pub struct Control {
     pub steer: f64,
     pub speed: f64,
 }
 
+impl Control {
+    /// Vérifie que les commandes sont comprises entre -1 et 1
+    pub fn validate(&self) -> anyhow::Result<()> {
+        for (name, value) in [("steer", self.steer), ("speed", self.speed)] {
+            if !(-1.0..=1.0).contains(&value) {
+                return Err(anyhow!("{}: {} hors de [-1, 1]", name, value));
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Failsafe: moteur au neutre, direction à `steer`
+    pub fn failsafe(steer: f64) -> Self {
+        Self { steer, speed: 0.0 }
+    }
+}
+
+/// Enregistrement control:realtime, écrit par l'application de pilotage
+#[derive(Clone, Copy, Default, Deserialize)]
+pub struct ControlRecord {
+    /// Direction, dans [-1, 1]
+    pub steer: f64,
+    /// Vitesse, dans [-1, 1]
+    pub speed: f64,
+    /// Arrêt d'urgence: true coupe le moteur immédiatement, les commandes de vitesse restent sans
+    /// effet jusqu'à un enregistrement avec false. Absent: inchangé.
+    #[serde(default)]
+    pub estop: Option<bool>,
+}
+
+impl ControlRecord {
+    pub fn control(&self) -> Control {
+        Control {
+            steer: self.steer,
+            speed: self.speed,
+        }
+    }
+}
+
+#[cfg(feature = "real-actuators")]
 #[derive(Deserialize)]
-pub(crate) struct Switch {
+pub struct Switch {
     pub esc: bool,
 }
\ No newline at end of file
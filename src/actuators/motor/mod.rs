@@ -1,63 +1,159 @@
+use std::time::{Duration, Instant};
+
 use anyhow::anyhow;
-use  rppal::pwm::{Channel, Polarity, Pwm};
+#[cfg(feature = "real-actuators")]
+use rppal::pwm::{Channel, Polarity, Pwm};
 
-pub struct Motor {
-    pwm: Pwm,
-    is_safe: bool,
-}
+use crate::actuators::duty;
+use crate::config::MotorConfig;
 
-const MOTOR_NEUTRAL: f64 = 0.07;
-const MOTOR_MAX: f64 = 0.10;
-const MOTOR_MAX_REV: f64 = 0.04;
+/// Sortie PWM de l'ESC (rppal sur le Raspberry Pi, sortie factice dans les tests)
+pub trait PwmOutput {
+    fn set_duty_cycle(&self, cycle: f64) -> anyhow::Result<()>;
+    fn duty_cycle(&self) -> anyhow::Result<f64>;
+}
 
-impl Motor {
-    pub fn new() -> anyhow::Result<Self> {
-        println!("[MOTOR] Initialisation ...");
-        let pwm = Pwm::with_frequency(Channel::Pwm0, 50.0, MOTOR_NEUTRAL, Polarity::Normal, true).map_err(|x| anyhow!(x))?;
+#[cfg(feature = "real-actuators")]
+impl PwmOutput for Pwm {
+    fn set_duty_cycle(&self, cycle: f64) -> anyhow::Result<()> {
+        Ok(Pwm::set_duty_cycle(self, cycle)?)
+    }
 
-        Ok(Motor { 
-            pwm: pwm,
-            is_safe: false,
-        })
+    fn duty_cycle(&self) -> anyhow::Result<f64> {
+        Ok(Pwm::duty_cycle(self)?)
     }
+}
 
-    pub fn start_esc(&self, start: bool) -> anyhow::Result<()> {
-        
-        
-        Ok(())
+/// Etape du passage en marche arrière d'un ESC à freinage
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Phase {
+    /// Marche avant ou arrêt après la marche avant: la marche arrière commence par le freinage
+    Forward,
+    /// Freinage (impulsion arrière) jusqu'à l'instant indiqué
+    Brake(Instant),
+    /// Neutre jusqu'à l'instant indiqué, puis marche arrière
+    Neutral(Instant),
+    /// Marche arrière engagée, de nouveau disponible directement après un arrêt
+    Reverse,
+}
+
+/// Séquence freinage puis neutre avant la marche arrière (ESC à freinage, brake_ms > 0)
+pub struct Esc {
+    brake: Duration,
+    neutral: Duration,
+    phase: Phase,
+}
+
+impl Esc {
+    pub fn new(config: &MotorConfig) -> Self {
+        Self {
+            brake: Duration::from_millis(config.brake_ms),
+            neutral: Duration::from_millis(config.neutral_ms),
+            phase: Phase::Forward,
+        }
     }
 
-    pub fn set_speed(&self, mut speed: f64) -> anyhow::Result<()> {
-        if self.is_safe {
-            return Ok(())
+    /// Vitesse à transmettre à l'ESC pour la vitesse demandée
+    pub fn output(&mut self, speed: f64, now: Instant) -> f64 {
+        if self.brake.is_zero() || !(-1.0..=1.0).contains(&speed) {
+            return speed;
         }
 
-        // Validation SYSTEMATIQUE des données.
-        if speed < -1.0 || speed > 1.0  {
-            speed = 0.0;    
+        if speed > 0.0 {
+            self.phase = Phase::Forward;
+            return speed;
         }
 
-        // Calcul du duty cycle.
-        let mut cycle = MOTOR_NEUTRAL;
+        if speed == 0.0 {
+            // Séquence interrompue: à reprendre depuis le début
+            if self.pending() {
+                self.phase = Phase::Forward;
+            }
+            return speed;
+        }
 
-        // Reverse
-        if speed < 0.0 {
-            cycle = MOTOR_NEUTRAL - ((-1.0 * speed) * (MOTOR_NEUTRAL - MOTOR_MAX_REV));
+        if self.phase == Phase::Forward {
+            self.phase = Phase::Brake(now + self.brake);
         }
+        if let Phase::Brake(until) = self.phase {
+            if now < until {
+                return speed;
+            }
+            self.phase = Phase::Neutral(until + self.neutral);
+        }
+        if let Phase::Neutral(until) = self.phase {
+            if now < until {
+                return 0.0;
+            }
+            self.phase = Phase::Reverse;
+        }
+        speed
+    }
 
-        // Forward
-        if speed > 0.0 {
-            cycle = MOTOR_NEUTRAL + (speed * (MOTOR_MAX - MOTOR_NEUTRAL))
+    /// Séquence en cours: la sortie doit être mise à jour sans nouvelle commande
+    pub fn pending(&self) -> bool {
+        matches!(self.phase, Phase::Brake(_) | Phase::Neutral(_))
+    }
+}
+
+pub struct Motor<P: PwmOutput> {
+    pwm: P,
+    calibration: MotorConfig,
+    esc: Esc,
+    is_safe: bool,
+}
+
+#[cfg(feature = "real-actuators")]
+impl Motor<Pwm> {
+    pub fn new(calibration: MotorConfig) -> anyhow::Result<Self> {
+        tracing::info!(target: "motor", "Initialisation ...");
+        let neutral = duty::calibrated_motor(0.0, &calibration);
+        let pwm = Pwm::with_frequency(Channel::Pwm0, 50.0, neutral, Polarity::Normal, true).map_err(|x| anyhow!(x))?;
+
+        Ok(Motor::with_pwm(pwm, calibration))
+    }
+}
+
+impl<P: PwmOutput> Motor<P> {
+    pub fn with_pwm(pwm: P, calibration: MotorConfig) -> Self {
+        Motor {
+            esc: Esc::new(&calibration),
+            pwm,
+            calibration,
+            is_safe: false,
         }
+    }
 
-        // Défini le nouveau duty cycle
-        self.pwm.set_duty_cycle(cycle)?;
+    pub fn set_speed(&mut self, speed: f64, now: Instant) -> anyhow::Result<()> {
+        if self.is_safe {
+            return Ok(())
+        }
+
+        // Défini le nouveau duty cycle (passage en marche arrière de l'ESC, calibration)
+        let speed = self.esc.output(speed, now);
+        self.pwm.set_duty_cycle(duty::calibrated_motor(speed, &self.calibration))?;
         Ok(())
     }
 
+    /// Passage en marche arrière en cours: `set_speed` à rappeler périodiquement
+    pub fn pending(&self) -> bool {
+        self.esc.pending()
+    }
+
+    /// Vérifie que la sortie est bien au neutre
+    pub fn neutral_check(&self) -> anyhow::Result<String> {
+        let cycle = self.pwm.duty_cycle()?;
+        let neutral = duty::calibrated_motor(0.0, &self.calibration);
+        if (cycle - neutral).abs() > 0.001 {
+            return Err(anyhow!("Rapport cyclique {:.3} (neutre: {:.3})", cycle, neutral));
+        }
+
+        Ok(format!("Rapport cyclique: {:.3}", cycle))
+    }
+
+    /// Moteur au neutre, maintenu par l'ESC; les commandes suivantes sont ignorées
     pub fn safe_stop(&mut self) {
-        let _ = self.pwm.set_duty_cycle(0.0);
-        let _ = self.pwm.disable();
+        let _ = self.pwm.set_duty_cycle(duty::calibrated_motor(0.0, &self.calibration));
         self.is_safe = true;
     }
 }
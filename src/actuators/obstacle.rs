@@ -0,0 +1,91 @@
+use crate::config::{ObstacleConfig, Severity};
+use crate::sensors::reader::{RangeData, RangeStatus};
+
+/// Changement de l'état du blocage de la marche avant
+#[derive(Debug, PartialEq)]
+pub enum Transition {
+    /// Obstacle plus proche que la distance d'arrêt: marche avant bloquée
+    Triggered { distance_mm: u16 },
+    /// Obstacle au-delà de la distance de reprise, ou plus aucune cible à portée
+    Cleared,
+    /// Mesures de distance périmées: la règle ne s'applique plus
+    Stale,
+}
+
+impl Transition {
+    /// Marche avant bloquée après la transition
+    pub fn active(&self) -> bool {
+        matches!(self, Transition::Triggered { .. })
+    }
+
+    /// Evènement transmis aux alertes
+    pub fn event(&self) -> &'static str {
+        match self {
+            Transition::Triggered { .. } => "obstacle",
+            Transition::Cleared | Transition::Stale => "obstacle_cleared",
+        }
+    }
+
+    pub fn severity(&self) -> Severity {
+        match self {
+            Transition::Triggered { .. } | Transition::Stale => Severity::Warning,
+            Transition::Cleared => Severity::Info,
+        }
+    }
+
+    pub fn message(&self) -> String {
+        match self {
+            Transition::Triggered { distance_mm } => {
+                format!("Obstacle à {} mm: marche avant bloquée", distance_mm)
+            }
+            Transition::Cleared => "Voie libre: marche avant rétablie".to_string(),
+            Transition::Stale => "Mesures de distance périmées: blocage levé".to_string(),
+        }
+    }
+}
+
+/// Règle d'arrêt devant un obstacle: la marche avant est bloquée sous la distance d'arrêt, puis
+/// rétablie au-delà de la distance de reprise (hystérésis). Une mesure rejetée par le capteur
+/// ne change pas l'état.
+pub struct Obstacle {
+    stop_mm: u16,
+    clear_mm: u16,
+    active: bool,
+}
+
+impl Obstacle {
+    pub fn new(config: &ObstacleConfig) -> Self {
+        Self {
+            stop_mm: config.stop_mm,
+            clear_mm: config.clear_mm,
+            active: false,
+        }
+    }
+
+    /// Nouvelle mesure de distance, None si les mesures sont périmées
+    pub fn update(&mut self, range: Option<&RangeData>) -> Option<Transition> {
+        let status = range.map_or(RangeStatus::Timeout, |range| range.status);
+        let clear = match (status, range.and_then(|range| range.distance_mm)) {
+            (RangeStatus::Valid, Some(distance)) if !self.active && distance < self.stop_mm => {
+                self.active = true;
+                return Some(Transition::Triggered { distance_mm: distance });
+            }
+            (RangeStatus::Valid, Some(distance)) => distance >= self.clear_mm,
+            (RangeStatus::OutOfRange, _) => true,
+            (RangeStatus::Timeout, _) => {
+                if !self.active {
+                    return None;
+                }
+                self.active = false;
+                return Some(Transition::Stale);
+            }
+            _ => false,
+        };
+
+        if !(self.active && clear) {
+            return None;
+        }
+        self.active = false;
+        Some(Transition::Cleared)
+    }
+}
@@ -0,0 +1,13 @@
+use rppal::gpio::Gpio;
+
+/// Relâche le maintien de l'alimentation: la broche garde son niveau après la fin du programme
+pub fn release(pin: u8, high: bool) -> anyhow::Result<()> {
+    let mut pin = Gpio::new()?.get(pin)?.into_output();
+    pin.set_reset_on_drop(false);
+    if high {
+        pin.set_high();
+    } else {
+        pin.set_low();
+    }
+    Ok(())
+}
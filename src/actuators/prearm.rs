@@ -0,0 +1,155 @@
+use std::time::Duration;
+
+use crate::config::PreArmConfig;
+use crate::selftest::Outcome;
+use crate::sensors::reader::{Data, SensorsStatus};
+use crate::sensors::watchdog::Health;
+
+/// Vérifications de démarrage des actionneurs
+pub const ACTUATOR_CHECKS: [&str; 2] = ["motor.neutral", "steering.neutral"];
+
+/// Etat du véhicule au moment de la demande d'armement
+pub struct Snapshot {
+    /// Derniers échantillons des capteurs
+    pub data: Data,
+    pub status: SensorsStatus,
+    pub health: Health,
+    pub stale: Vec<&'static str>,
+    /// Cause de la coupure du moteur active, None: aucune
+    pub cutoff: Option<String>,
+    /// Durée depuis la dernière commande, None: aucune commande reçue
+    pub control_age: Option<Duration>,
+    /// Résultat des vérifications des actionneurs, None: pas encore effectuée
+    pub actuators: Vec<(&'static str, Option<Outcome>)>,
+}
+
+/// Vérification en échec
+#[derive(Debug, PartialEq)]
+pub enum Failure {
+    /// Tension trop basse, None: aucune mesure
+    Battery(Option<f32>),
+    Cutoff(String),
+    ImuUnavailable,
+    ImuTilted {
+        pitch: f32,
+        roll: f32,
+    },
+    /// Commande trop ancienne, None: aucune commande reçue
+    Control(Option<Duration>),
+    Actuator {
+        check: &'static str,
+        outcome: Option<Outcome>,
+    },
+    SensorFault {
+        sensor: &'static str,
+        error: String,
+    },
+    SensorsStale(Vec<&'static str>),
+    NoGpsFix,
+}
+
+impl Failure {
+    pub fn message(&self, config: &PreArmConfig) -> String {
+        match self {
+            Failure::Battery(Some(voltage)) => {
+                format!("batterie {:.2} V sous {:.2} V", voltage, config.min_battery_v)
+            }
+            Failure::Battery(None) => "batterie non mesurée".to_string(),
+            Failure::Cutoff(cause) => format!("coupure du moteur active ({})", cause),
+            Failure::ImuUnavailable => "IMU non initialisée".to_string(),
+            Failure::ImuTilted { pitch, roll } => format!(
+                "véhicule incliné (tangage {:.1}°, roulis {:.1}°, max {:.0}°)",
+                pitch, roll, config.max_tilt_deg
+            ),
+            Failure::Control(Some(age)) => {
+                format!("aucune commande depuis {:.1} s", age.as_secs_f64())
+            }
+            Failure::Control(None) => "aucune commande reçue".to_string(),
+            Failure::Actuator { check, outcome: None } => format!("{}: test de démarrage en cours", check),
+            Failure::Actuator { check, .. } => format!("{}: échec du test de démarrage", check),
+            Failure::SensorFault { sensor, error } => format!("{} en erreur ({})", sensor, error),
+            Failure::SensorsStale(sensors) => format!("capteur critique périmé (périmés: {})", sensors.join(", ")),
+            Failure::NoGpsFix => "aucune position GPS".to_string(),
+        }
+    }
+}
+
+/// Evalue toutes les vérifications activées, retourne chaque vérification en échec
+pub fn evaluate(config: &PreArmConfig, snapshot: &Snapshot) -> Vec<Failure> {
+    let mut failures = Vec::new();
+    let data = &snapshot.data;
+
+    if config.battery {
+        let voltage = (data.analog.stamp.mono_us != 0).then_some(data.analog.battery);
+        if voltage.is_none_or(|voltage| voltage < config.min_battery_v) {
+            failures.push(Failure::Battery(voltage));
+        }
+    }
+
+    if config.cutoff {
+        if let Some(cause) = &snapshot.cutoff {
+            failures.push(Failure::Cutoff(cause.clone()));
+        }
+    }
+
+    if config.imu {
+        if !snapshot.status.imu.available || data.imu.stamp.mono_us == 0 {
+            failures.push(Failure::ImuUnavailable);
+        } else {
+            // Même ordre que l'IMU: tangage, roulis, lacet
+            let (pitch, roll, _) = data.imu.angles;
+            if pitch.abs() > config.max_tilt_deg || roll.abs() > config.max_tilt_deg {
+                failures.push(Failure::ImuTilted { pitch, roll });
+            }
+        }
+    }
+
+    if config.control {
+        let max = Duration::from_millis(config.control_max_age_ms);
+        if snapshot.control_age.is_none_or(|age| age > max) {
+            failures.push(Failure::Control(snapshot.control_age));
+        }
+    }
+
+    if config.actuators {
+        for (check, outcome) in snapshot.actuators.iter() {
+            if !matches!(outcome, Some(Outcome::Pass | Outcome::Skip)) {
+                failures.push(Failure::Actuator {
+                    check,
+                    outcome: *outcome,
+                });
+            }
+        }
+    }
+
+    if config.sensors {
+        let status = &snapshot.status;
+        for (sensor, status) in [
+            ("imu", &status.imu),
+            ("mag", &status.mag),
+            ("analog", &status.analog),
+            ("power", &status.power),
+            ("baro", &status.baro),
+            ("encoder", &status.encoder),
+            ("range", &status.range),
+            ("gps", &status.gps),
+        ] {
+            // Capteur désactivé: aucune tentative d'initialisation
+            if let (false, Some(error), 1..) = (status.available, &status.error, status.attempts) {
+                failures.push(Failure::SensorFault {
+                    sensor,
+                    error: error.clone(),
+                });
+            }
+        }
+        if snapshot.health == Health::Critical {
+            failures.push(Failure::SensorsStale(snapshot.stale.clone()));
+        }
+    }
+
+    if config.gps && !(data.gps.stamp.mono_us != 0 && data.gps.fix) {
+        failures.push(Failure::NoGpsFix);
+    }
+
+    failures
+}
@@ -0,0 +1,82 @@
+use std::time::{Duration, Instant};
+
+/// Période de mise à jour de la sortie pendant une rampe
+pub const TICK: Duration = Duration::from_millis(20);
+
+/// Rampe de la vitesse moteur: la sortie rejoint la consigne avec une variation limitée
+/// (fraction de la pleine échelle par seconde). L'accélération limite l'éloignement du neutre,
+/// la décélération le retour vers le neutre; un changement de sens passe par le neutre.
+/// Une variation de 0 n'est pas limitée.
+pub struct Ramp {
+    accel: f64,
+    decel: f64,
+    target: f64,
+    output: f64,
+    last: Option<Instant>,
+}
+
+impl Ramp {
+    pub fn new(accel_per_s: f64, decel_per_s: f64) -> Self {
+        Self {
+            accel: accel_per_s,
+            decel: decel_per_s,
+            target: 0.0,
+            output: 0.0,
+            last: None,
+        }
+    }
+
+    /// Nouvelle consigne, retourne la sortie
+    pub fn set(&mut self, target: f64, now: Instant) -> f64 {
+        self.update(now);
+        self.target = target;
+        self.update(now)
+    }
+
+    /// Sortie à la consigne sans rampe (failsafe, arrêt d'urgence, limite de vitesse)
+    pub fn cut(&mut self, speed: f64) -> f64 {
+        self.target = speed;
+        self.output = speed;
+        speed
+    }
+
+    /// Limite de vitesse: consigne et sortie ramenées dans `[min, max]`, la sortie au-delà sans
+    /// rampe. En deçà, la sortie continue de rejoindre la consigne limitée. Retourne la sortie.
+    pub fn clamp(&mut self, min: f64, max: f64) -> f64 {
+        self.target = self.target.clamp(min, max);
+        self.output = self.output.clamp(min, max);
+        self.output
+    }
+
+    /// Avance la sortie vers la consigne, retourne la sortie
+    pub fn update(&mut self, now: Instant) -> f64 {
+        let dt = self
+            .last
+            .map_or(0.0, |last| now.saturating_duration_since(last).as_secs_f64());
+        self.last = Some(now);
+
+        // Même sens et plus loin du neutre: accélération, sinon décélération jusqu'au neutre
+        let accelerating = self.output == 0.0
+            || (self.output * self.target > 0.0 && self.target.abs() > self.output.abs());
+        let (goal, rate) = if accelerating {
+            (self.target, self.accel)
+        } else if self.output * self.target > 0.0 {
+            (self.target, self.decel)
+        } else {
+            (0.0, self.decel)
+        };
+
+        let max_step = rate * dt;
+        self.output = if rate > 0.0 && (goal - self.output).abs() > max_step {
+            self.output + (goal - self.output).signum() * max_step
+        } else {
+            goal
+        };
+        self.output
+    }
+
+    /// Sortie à la consigne: aucune mise à jour nécessaire
+    pub fn settled(&self) -> bool {
+        self.output == self.target
+    }
+}
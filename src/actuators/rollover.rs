@@ -0,0 +1,167 @@
+use std::time::{Duration, Instant};
+
+use serde::Deserialize;
+
+use crate::config::RolloverConfig;
+use crate::sensors::reader::ImuData;
+
+/// Levée de la coupure demandée par la base (control:rollover), une fois la voiture remise sur
+/// ses roues
+#[derive(Clone, Debug, Default, Deserialize)]
+pub struct RolloverReset {}
+
+/// Changement de l'état de la coupure
+#[derive(Debug, PartialEq)]
+pub enum Transition {
+    /// Voiture retournée: moteur coupé
+    Triggered { roll: f32, pitch: f32, g: f32 },
+    /// Choc (accélération au-delà du seuil): moteur coupé
+    Crash { roll: f32, pitch: f32, g: f32 },
+    /// Attitude rétablie depuis le délai de reprise
+    Recovered,
+    /// Réarmement du véhicule ou levée demandée par la base
+    Rearmed,
+    /// Données de l'IMU périmées: la règle ne s'applique plus
+    Stale,
+}
+
+impl Transition {
+    /// Coupure active après la transition
+    pub fn active(&self) -> bool {
+        matches!(self, Transition::Triggered { .. } | Transition::Crash { .. })
+    }
+
+    /// Evènement enregistré
+    pub fn event(&self) -> &'static str {
+        match self {
+            Transition::Triggered { .. } => "rollover",
+            Transition::Crash { .. } => "crash",
+            Transition::Recovered | Transition::Rearmed | Transition::Stale => "rollover_cleared",
+        }
+    }
+
+    pub fn message(&self) -> String {
+        match self {
+            Transition::Triggered { roll, pitch, g } => {
+                format!(
+                    "Retournement (roulis {:.1}°, tangage {:.1}°, {:.2} g): moteur coupé",
+                    roll, pitch, g
+                )
+            }
+            Transition::Crash { roll, pitch, g } => {
+                format!(
+                    "Choc de {:.2} g (roulis {:.1}°, tangage {:.1}°): moteur coupé",
+                    g, roll, pitch
+                )
+            }
+            Transition::Recovered => "Attitude rétablie: moteur réactivé".to_string(),
+            Transition::Rearmed => "Réarmement: moteur réactivé".to_string(),
+            Transition::Stale => "Données IMU périmées: coupure levée".to_string(),
+        }
+    }
+}
+
+/// Règle de coupure du moteur selon l'attitude: au-delà de l'angle maximal pendant le délai
+/// d'anti-rebond, ou sur un choc, le moteur est coupé jusqu'au retour d'une attitude normale
+/// pendant le délai de reprise (sauf coupure maintenue) ou jusqu'au réarmement du véhicule.
+pub struct Rollover {
+    max_angle: f32,
+    /// Accélération déclenchant la coupure (g), 0: désactivé
+    crash_g: f32,
+    debounce: Duration,
+    reenable: Duration,
+    latch: bool,
+    /// Début du dépassement de l'angle, coupure inactive
+    over: Option<Instant>,
+    /// Début de l'attitude normale, coupure active
+    normal: Option<Instant>,
+    active: bool,
+}
+
+impl Rollover {
+    pub fn new(config: &RolloverConfig) -> Self {
+        Self {
+            max_angle: config.max_angle_deg,
+            crash_g: config.crash_g,
+            debounce: Duration::from_millis(config.debounce_ms),
+            reenable: Duration::from_millis(config.reenable_ms),
+            latch: config.latch,
+            over: None,
+            normal: None,
+            active: false,
+        }
+    }
+
+    /// Nouvel état de l'IMU, None si ses données sont périmées
+    pub fn update(&mut self, imu: Option<&ImuData>, now: Instant) -> Option<Transition> {
+        let Some(imu) = imu else {
+            self.over = None;
+            self.normal = None;
+            // Une coupure maintenue attend toujours le réarmement
+            if self.active && !self.latch {
+                self.active = false;
+                return Some(Transition::Stale);
+            }
+            return None;
+        };
+
+        // Même ordre que l'IMU: tangage, roulis, lacet
+        let (pitch, roll, _) = imu.angles;
+        let tilted = roll.abs() > self.max_angle || pitch.abs() > self.max_angle;
+
+        if !self.active {
+            if !tilted {
+                self.over = None;
+                return None;
+            }
+            let since = *self.over.get_or_insert(now);
+            if now.duration_since(since) < self.debounce {
+                return None;
+            }
+            self.over = None;
+            self.active = true;
+            return Some(Transition::Triggered { roll, pitch, g: magnitude(imu.accel) });
+        }
+
+        if self.latch || tilted {
+            self.normal = None;
+            return None;
+        }
+        let since = *self.normal.get_or_insert(now);
+        if now.duration_since(since) < self.reenable {
+            return None;
+        }
+        self.normal = None;
+        self.active = false;
+        Some(Transition::Recovered)
+    }
+
+    /// Nouvel échantillon de l'IMU: un choc coupe le moteur sans anti-rebond
+    pub fn impact(&mut self, imu: &ImuData) -> Option<Transition> {
+        let g = magnitude(imu.accel);
+        if self.active || self.crash_g <= 0.0 || g <= self.crash_g {
+            return None;
+        }
+        self.over = None;
+        self.normal = None;
+        self.active = true;
+        let (pitch, roll, _) = imu.angles;
+        Some(Transition::Crash { roll, pitch, g })
+    }
+
+    /// Réarmement du véhicule ou levée demandée par la base: lève la coupure, qu'elle soit
+    /// maintenue ou non
+    pub fn rearm(&mut self) -> Option<Transition> {
+        if !self.active {
+            return None;
+        }
+        self.normal = None;
+        self.active = false;
+        Some(Transition::Rearmed)
+    }
+}
+
+/// Norme de l'accélération (g)
+fn magnitude((x, y, z): (f32, f32, f32)) -> f32 {
+    (x * x + y * y + z * z).sqrt()
+}
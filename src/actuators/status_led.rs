@@ -0,0 +1,125 @@
+use std::time::{Duration, Instant};
+
+#[cfg(feature = "real-actuators")]
+use rppal::gpio::{Gpio, OutputPin};
+
+use crate::config::StatusLedConfig;
+
+/// Etat signalé par la LED
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Pattern {
+    /// Base connectée, composants en ordre
+    Solid,
+    /// Mode dégradé: composant en défaut, base déconnectée ou tampon local utilisé
+    SlowBlink,
+    /// Failsafe de la liaison de contrôle
+    FastBlink,
+    /// Arrêt
+    Off,
+}
+
+impl Pattern {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Pattern::Solid => "fixe",
+            Pattern::SlowBlink => "clignotement lent",
+            Pattern::FastBlink => "clignotement rapide",
+            Pattern::Off => "éteinte",
+        }
+    }
+}
+
+/// Etat du système signalé par la LED: le failsafe prime sur le mode dégradé
+pub fn pattern(connected: bool, buffering: bool, degraded: bool, failsafe: bool) -> Pattern {
+    if failsafe {
+        Pattern::FastBlink
+    } else if !connected || buffering || degraded {
+        Pattern::SlowBlink
+    } else {
+        Pattern::Solid
+    }
+}
+
+/// Sortie de la LED (broche GPIO sur le Raspberry Pi, sortie factice sinon)
+pub trait LedOutput {
+    fn set(&mut self, on: bool);
+
+    /// Nouvel état signalé
+    fn pattern(&mut self, _pattern: Pattern) {}
+}
+
+#[cfg(feature = "real-actuators")]
+impl LedOutput for OutputPin {
+    fn set(&mut self, on: bool) {
+        if on {
+            self.set_high();
+        } else {
+            self.set_low();
+        }
+    }
+}
+
+/// Broche de la LED, éteinte à l'ouverture
+#[cfg(feature = "real-actuators")]
+pub fn open(pin: u8) -> anyhow::Result<OutputPin> {
+    Ok(Gpio::new()?.get(pin)?.into_output_low())
+}
+
+/// LED factice: les changements d'état sont journalisés
+pub struct FakeLed;
+
+impl LedOutput for FakeLed {
+    fn set(&mut self, _on: bool) {}
+
+    fn pattern(&mut self, pattern: Pattern) {
+        tracing::info!(target: "status_led", "LED factice: {}", pattern.name());
+    }
+}
+
+/// LED d'état: allumée, éteinte ou clignotante selon l'état signalé
+pub struct StatusLed<O: LedOutput> {
+    output: O,
+    slow: Duration,
+    fast: Duration,
+    pattern: Option<Pattern>,
+    /// Début de l'état signalé, référence du clignotement
+    since: Instant,
+    on: Option<bool>,
+}
+
+impl<O: LedOutput> StatusLed<O> {
+    pub fn new(output: O, config: &StatusLedConfig, now: Instant) -> Self {
+        Self {
+            output,
+            slow: Duration::from_millis(config.slow_period_ms),
+            fast: Duration::from_millis(config.fast_period_ms),
+            pattern: None,
+            since: now,
+            on: None,
+        }
+    }
+
+    /// Met à jour la LED pour l'état signalé à `now`. Vrai si l'état a changé.
+    pub fn update(&mut self, pattern: Pattern, now: Instant) -> bool {
+        let changed = self.pattern != Some(pattern);
+        if changed {
+            self.pattern = Some(pattern);
+            self.since = now;
+            self.output.pattern(pattern);
+        }
+
+        // Allumée pendant la première moitié de chaque période
+        let elapsed = now.saturating_duration_since(self.since);
+        let on = match pattern {
+            Pattern::Solid => true,
+            Pattern::Off => false,
+            Pattern::SlowBlink => elapsed.as_millis() % self.slow.as_millis() < self.slow.as_millis() / 2,
+            Pattern::FastBlink => elapsed.as_millis() % self.fast.as_millis() < self.fast.as_millis() / 2,
+        };
+        if self.on != Some(on) {
+            self.on = Some(on);
+            self.output.set(on);
+        }
+        changed
+    }
+}
@@ -1,59 +1,53 @@
 use anyhow::anyhow;
 use  rppal::pwm::{Channel, Polarity, Pwm};
 
+use crate::actuators::duty;
+use crate::config::SteeringConfig;
+
 pub struct Steering {
     pwm: Pwm,
+    calibration: SteeringConfig,
     is_safe: bool,
 }
 
-const STEER_MID: f64 = 0.076;
-const STEER_LEFT: f64 = 0.088;
-const STEER_RIGHT: f64 = 0.064;
-
 impl Steering {
-    pub fn new() -> anyhow::Result<Self> {
-        println!("[STEERING] Initialisation ...");
+    pub fn new(calibration: SteeringConfig) -> anyhow::Result<Self> {
+        tracing::info!(target: "steering", "Initialisation ...");
 
-        let pwm = Pwm::with_frequency(Channel::Pwm1, 50.0, STEER_MID, Polarity::Normal, true).map_err(|x| anyhow!(x))?;
+        let center = duty::calibrated_steering(0.0, &calibration);
+        let pwm = Pwm::with_frequency(Channel::Pwm1, 50.0, center, Polarity::Normal, true).map_err(|x| anyhow!(x))?;
 
         Ok(Steering {
             pwm: pwm,
+            calibration,
             is_safe: false,
         })
     }
 
-    pub fn set_steer(&self, mut steer: f64) -> anyhow::Result<()> {
+    pub fn set_steer(&self, steer: f64) -> anyhow::Result<()> {
         if self.is_safe {
             return Ok(())
         }
 
-        // Validation SYSTEMATIQUE des données.
-        if steer < -1.0 || steer > 1.0 {
-            steer = 0.0;
-        }
-
-        // Calcul du duty cycle.
-
-        let mut cycle = STEER_MID;
-
-        // Gauche
-        if steer < 0.0 {
-            cycle = STEER_MID + ((-1.0 * steer) * (STEER_LEFT - STEER_MID));
-        }
+        // Calcul du duty cycle (trim, butées et sens calibrés).
+        self.pwm.set_duty_cycle(duty::calibrated_steering(steer, &self.calibration))?;
+        Ok(())
+    }
 
-        // Droite
-        if steer > 0.0 {
-            cycle = STEER_MID - (steer * (STEER_MID - STEER_RIGHT))
+    /// Vérifie que la direction est bien au centre calibré
+    pub fn neutral_check(&self) -> anyhow::Result<String> {
+        let cycle = self.pwm.duty_cycle()?;
+        let center = duty::calibrated_steering(0.0, &self.calibration);
+        if (cycle - center).abs() > 0.001 {
+            return Err(anyhow!("Rapport cyclique {:.3} (centre: {:.3})", cycle, center));
         }
 
-        // Calcul du duty cycle.
-        self.pwm.set_duty_cycle(cycle)?;
-        Ok(())
+        Ok(format!("Rapport cyclique: {:.3}", cycle))
     }
 
+    /// Direction au centre calibré, maintenu par le servo; les commandes suivantes sont ignorées
     pub fn safe_stop(&mut self) {
-        let _ = self.pwm.set_duty_cycle(0.0);
-        let _ = self.pwm.disable();
+        let _ = self.pwm.set_duty_cycle(duty::calibrated_steering(0.0, &self.calibration));
         self.is_safe = true;
     }
 }
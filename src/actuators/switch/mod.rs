@@ -1,7 +1,7 @@
 use anyhow::anyhow;
 use rppal::gpio::{Gpio, OutputPin};
 
-pub(crate) struct Switch {
+pub struct Switch {
     gpio: Gpio,
     esc_pin: OutputPin,
 }
@@ -0,0 +1,58 @@
+use crate::config::ThermalSensor;
+
+/// Pas de la limite thermique: une nouvelle limite par tranche de 5 %, pas à chaque mesure
+const STEP: f64 = 0.05;
+
+/// Vitesse maximale selon la température: 100 % jusqu'à la température d'alerte, puis
+/// décroissance linéaire jusqu'au plancher à la température critique, 0 au-delà
+pub fn scale(sensor: &ThermalSensor, temp: f32) -> f64 {
+    if temp <= sensor.warning_c {
+        return 1.0;
+    }
+    if temp >= sensor.critical_c {
+        return 0.0;
+    }
+
+    let ratio = ((temp - sensor.warning_c) / (sensor.critical_c - sensor.warning_c)) as f64;
+    let max = 1.0 - (1.0 - sensor.floor) * ratio;
+    // Arrondi au pas inférieur, sans descendre sous le plancher
+    (((max / STEP) + 1e-9).floor() * STEP).max(sensor.floor)
+}
+
+/// Passage de la température critique
+#[derive(Debug, PartialEq)]
+pub enum Change {
+    /// Température critique atteinte: moteur coupé
+    Tripped,
+    /// Retour sous la température de reprise
+    Recovered,
+}
+
+/// Limite d'une température surveillée, avec hystérésis après la coupure
+pub struct Thermal {
+    pub sensor: ThermalSensor,
+    tripped: bool,
+}
+
+impl Thermal {
+    pub fn new(sensor: ThermalSensor) -> Self {
+        Self { sensor, tripped: false }
+    }
+
+    /// Nouvelle température: vitesse maximale et éventuel changement de la coupure
+    pub fn update(&mut self, temp: f32) -> (f64, Option<Change>) {
+        if self.tripped {
+            if temp >= self.sensor.recovery_c {
+                return (0.0, None);
+            }
+            self.tripped = false;
+            return (scale(&self.sensor, temp), Some(Change::Recovered));
+        }
+
+        if temp >= self.sensor.critical_c {
+            self.tripped = true;
+            return (0.0, Some(Change::Tripped));
+        }
+        (scale(&self.sensor, temp), None)
+    }
+}
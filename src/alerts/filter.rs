@@ -0,0 +1,53 @@
+use std::collections::BTreeMap;
+use std::time::{Duration, Instant};
+
+use crate::config::{AlertsConfig, Severity};
+
+/// Type de l'alerte de test (--test-alert), jamais filtrée
+pub const TEST: &str = "test";
+
+/// Décision du filtre pour un évènement
+#[derive(Debug, PartialEq)]
+pub enum Decision {
+    Send,
+    /// Type ou gravité non retenus
+    Ignored,
+    /// Même type envoyé il y a moins de l'intervalle minimal
+    Suppressed,
+}
+
+/// Sélection des évènements envoyés: types, gravité minimale et intervalle minimal par type
+pub struct Filter {
+    events: Vec<String>,
+    min_severity: Severity,
+    min_interval: Duration,
+    last: BTreeMap<String, Instant>,
+}
+
+impl Filter {
+    pub fn new(config: &AlertsConfig) -> Self {
+        Self {
+            events: config.events.clone(),
+            min_severity: config.min_severity,
+            min_interval: Duration::from_secs(config.min_interval_s),
+            last: BTreeMap::new(),
+        }
+    }
+
+    pub fn check(&mut self, kind: &str, severity: Severity, now: Instant) -> Decision {
+        if kind == TEST {
+            return Decision::Send;
+        }
+        if severity < self.min_severity || !(self.events.is_empty() || self.events.iter().any(|event| event == kind)) {
+            return Decision::Ignored;
+        }
+
+        match self.last.get(kind) {
+            Some(last) if now.duration_since(*last) < self.min_interval => Decision::Suppressed,
+            _ => {
+                self.last.insert(kind.to_string(), now);
+                Decision::Send
+            }
+        }
+    }
+}
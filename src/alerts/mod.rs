@@ -0,0 +1,146 @@
+use std::sync::atomic::Ordering;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use tokio::sync::broadcast::error::RecvError;
+use tokio::sync::{broadcast, mpsc, watch};
+use tokio_util::sync::CancellationToken;
+
+use crate::clock::Clock;
+use crate::config::AlertsConfig;
+use crate::writer::{Event, Notice, Writer};
+
+pub mod filter;
+pub mod monitor;
+pub mod webhook;
+
+use filter::{Decision, Filter};
+use monitor::Monitor;
+use webhook::{AlertMetrics, Payload};
+
+/// Intervalle de vérification de la perte de la télémétrie
+const SILENCE_CHECK: Duration = Duration::from_secs(1);
+
+/// Envoi des évènements retenus par le filtre à chaque webhook. Chaque webhook a sa propre
+/// file: un webhook injoignable ne retarde pas les autres.
+pub async fn run(
+    config: AlertsConfig,
+    vehicle: String,
+    run: String,
+    mut notices: broadcast::Receiver<Notice>,
+    metrics: Arc<AlertMetrics>,
+    token: CancellationToken,
+) {
+    if config.webhooks.is_empty() {
+        tracing::error!(target: "alerts", "Aucun webhook configuré.");
+    }
+
+    let client = match reqwest::Client::builder()
+        .timeout(Duration::from_secs(config.timeout_s))
+        .build()
+    {
+        Ok(client) => client,
+        Err(e) => {
+            tracing::error!(target: "alerts", "Client HTTP indisponible: {}", e);
+            return;
+        }
+    };
+
+    let mut webhooks = Vec::new();
+    for url in config.webhooks.iter() {
+        let (sender, receiver) = mpsc::channel(webhook::QUEUE_LEN);
+        tokio::spawn(webhook::deliver(
+            url.clone(),
+            client.clone(),
+            config.retries,
+            receiver,
+            metrics.clone(),
+            token.clone(),
+        ));
+        webhooks.push(sender);
+    }
+
+    tracing::info!(target: "alerts", "Démarrage ({} webhook(s)).", webhooks.len());
+    let mut filter = Filter::new(&config);
+
+    loop {
+        let notice = tokio::select! {
+            _ = token.cancelled() => break,
+            notice = notices.recv() => match notice {
+                Ok(notice) => notice,
+                Err(RecvError::Lagged(count)) => {
+                    tracing::error!(target: "alerts", "{} évènement(s) perdu(s)", count);
+                    continue;
+                }
+                Err(RecvError::Closed) => break,
+            },
+        };
+
+        match filter.check(notice.kind, notice.severity, Instant::now()) {
+            Decision::Send => {}
+            Decision::Ignored => continue,
+            Decision::Suppressed => {
+                metrics.suppressed.fetch_add(1, Ordering::Relaxed);
+                continue;
+            }
+        }
+
+        let payload = Arc::new(Payload::new(
+            &vehicle,
+            &run,
+            notice.kind,
+            notice.severity,
+            &notice.details,
+            notice.stamp,
+        ));
+        for sender in webhooks.iter() {
+            if sender.try_send(payload.clone()).is_err() {
+                metrics.dropped.fetch_add(1, Ordering::Relaxed);
+            }
+        }
+    }
+
+    tracing::info!(target: "alerts", "Arrêt.");
+}
+
+/// Surveillance de l'armement et des échantillons: arrêt d'urgence, batterie critique et perte
+/// de la télémétrie, transmis comme évènements (base et alertes)
+pub async fn watch(
+    config: AlertsConfig,
+    writer: Writer,
+    mut armed: watch::Receiver<bool>,
+    clock: Clock,
+    token: CancellationToken,
+) {
+    let mut records = writer.subscribe();
+    let mut monitor = Monitor::new(&config, Instant::now());
+    let mut interval = tokio::time::interval(SILENCE_CHECK);
+
+    loop {
+        let alerts = tokio::select! {
+            _ = token.cancelled() => break,
+            changed = armed.changed() => {
+                if changed.is_err() {
+                    break;
+                }
+                let armed = *armed.borrow_and_update();
+                monitor.armed(armed).into_iter().collect()
+            }
+            record = records.recv() => match record {
+                Ok(record) => monitor.record(&record, Instant::now()),
+                // Un retard de lecture n'est pas une perte de la télémétrie
+                Err(RecvError::Lagged(_)) => Vec::new(),
+                Err(RecvError::Closed) => break,
+            },
+            _ = interval.tick() => monitor.tick(Instant::now()).into_iter().collect(),
+        };
+
+        for alert in alerts {
+            tracing::info!(target: "alerts", "{}: {}", alert.kind, alert.details);
+            let event = Event::Alert(alert.kind, alert.severity, alert.details, clock.stamp());
+            if writer.event(event).await.is_err() {
+                return;
+            }
+        }
+    }
+}
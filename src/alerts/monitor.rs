@@ -0,0 +1,115 @@
+use std::time::{Duration, Instant};
+
+use crate::config::{AlertsConfig, Severity};
+use crate::record::Record;
+
+/// Remontée de tension nécessaire pour réarmer l'alerte batterie (V)
+const BATTERY_HYSTERESIS: f32 = 0.2;
+
+/// Evènement détecté par la surveillance
+#[derive(Debug, PartialEq)]
+pub struct Alert {
+    pub kind: &'static str,
+    pub severity: Severity,
+    pub details: String,
+}
+
+impl Alert {
+    fn new(kind: &'static str, severity: Severity, details: String) -> Self {
+        Self {
+            kind,
+            severity,
+            details,
+        }
+    }
+}
+
+/// Surveillance des échantillons et de l'armement: une alerte par passage de seuil
+pub struct Monitor {
+    battery_critical: f32,
+    silence: Option<Duration>,
+    armed: bool,
+    /// Batterie sous le seuil critique
+    low: bool,
+    last: Instant,
+    silent: bool,
+}
+
+impl Monitor {
+    pub fn new(config: &AlertsConfig, now: Instant) -> Self {
+        Self {
+            battery_critical: config.battery_critical_v,
+            silence: (config.silence_s > 0).then(|| Duration::from_secs(config.silence_s)),
+            armed: true,
+            low: false,
+            last: now,
+            silent: false,
+        }
+    }
+
+    /// Changement de l'armement: le désarmement est un arrêt d'urgence
+    pub fn armed(&mut self, armed: bool) -> Option<Alert> {
+        let disarmed = self.armed && !armed;
+        self.armed = armed;
+        disarmed.then(|| {
+            Alert::new(
+                "estop",
+                Severity::Critical,
+                "Arrêt d'urgence: véhicule désarmé".to_string(),
+            )
+        })
+    }
+
+    /// Echantillon reçu: fin d'un silence, batterie critique
+    pub fn record(&mut self, record: &Record, now: Instant) -> Vec<Alert> {
+        let mut alerts = Vec::new();
+
+        if std::mem::take(&mut self.silent) {
+            alerts.push(Alert::new(
+                "telemetry_restored",
+                Severity::Info,
+                format!(
+                    "Télémétrie rétablie après {} s",
+                    now.duration_since(self.last).as_secs()
+                ),
+            ));
+        }
+        self.last = now;
+
+        if let Record::Analog(analog) = record {
+            if self.battery_critical > 0.0 {
+                if !self.low && analog.battery < self.battery_critical {
+                    self.low = true;
+                    alerts.push(Alert::new(
+                        "battery_critical",
+                        Severity::Critical,
+                        format!(
+                            "Batterie critique: {:.2} V (seuil {:.2} V)",
+                            analog.battery, self.battery_critical
+                        ),
+                    ));
+                } else if self.low && analog.battery >= self.battery_critical + BATTERY_HYSTERESIS {
+                    self.low = false;
+                }
+            }
+        }
+
+        alerts
+    }
+
+    /// Vérification périodique de la perte de la télémétrie
+    pub fn tick(&mut self, now: Instant) -> Option<Alert> {
+        let silence = self.silence?;
+        let elapsed = now.duration_since(self.last);
+        if self.silent || elapsed < silence {
+            return None;
+        }
+
+        self.silent = true;
+        Some(Alert::new(
+            "telemetry_silent",
+            Severity::Critical,
+            format!("Aucun échantillon depuis {} s", elapsed.as_secs()),
+        ))
+    }
+}
@@ -0,0 +1,222 @@
+use std::collections::VecDeque;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+use chrono::SecondsFormat;
+use serde::Serialize;
+use tokio::sync::mpsc;
+use tokio::time::{sleep_until, Instant};
+use tokio_util::sync::CancellationToken;
+
+use crate::clock::Stamp;
+use crate::config::Severity;
+
+/// Délai initial avant une nouvelle tentative
+pub const RETRY_MIN: Duration = Duration::from_secs(1);
+
+/// Délai maximum entre deux tentatives
+pub const RETRY_MAX: Duration = Duration::from_secs(60);
+
+/// Alertes en attente par webhook, la plus ancienne est perdue au-delà
+pub const QUEUE_LEN: usize = 32;
+
+/// Compteurs des alertes, exposés sur /metrics
+#[derive(Default)]
+pub struct AlertMetrics {
+    /// Alertes envoyées (une par webhook)
+    pub sent: AtomicU64,
+    /// Alertes abandonnées après la dernière tentative
+    pub failed: AtomicU64,
+    /// Nouvelles tentatives après un échec
+    pub retries: AtomicU64,
+    /// Alertes perdues, file d'attente pleine
+    pub dropped: AtomicU64,
+    /// Alertes ignorées par l'intervalle minimal
+    pub suppressed: AtomicU64,
+}
+
+/// Contenu JSON envoyé aux webhooks. "text" (Slack) et "content" (Discord) résument l'alerte.
+#[derive(Clone, Debug, Serialize)]
+pub struct Payload {
+    pub vehicle: String,
+    pub run: String,
+    pub event: String,
+    pub severity: Severity,
+    pub details: String,
+    /// Heure UTC de l'évènement (RFC 3339)
+    pub timestamp: String,
+    pub text: String,
+    pub content: String,
+}
+
+impl Payload {
+    pub fn new(vehicle: &str, run: &str, event: &str, severity: Severity, details: &str, stamp: Stamp) -> Self {
+        let label = match severity {
+            Severity::Info => "Info",
+            Severity::Warning => "Attention",
+            Severity::Critical => "Critique",
+        };
+        let text = format!("[{}] {} ({}): {}", vehicle, label, event, details);
+        Self {
+            vehicle: vehicle.to_string(),
+            run: run.to_string(),
+            event: event.to_string(),
+            severity,
+            details: details.to_string(),
+            timestamp: stamp.utc.to_rfc3339_opts(SecondsFormat::Millis, true),
+            content: text.clone(),
+            text,
+        }
+    }
+}
+
+struct Pending {
+    payload: Arc<Payload>,
+    attempts: u32,
+}
+
+/// File d'envoi d'un webhook: les alertes partent dans l'ordre, un échec bloque la file
+/// jusqu'à la tentative suivante (délai croissant), sans perdre les alertes suivantes.
+pub struct Queue {
+    pending: VecDeque<Pending>,
+    retries: u32,
+    backoff: Duration,
+    next: Instant,
+}
+
+/// Issue d'une tentative d'envoi
+#[derive(Debug, PartialEq)]
+pub enum Outcome {
+    Sent,
+    /// Nouvelle tentative après le délai
+    Retry(Duration),
+    /// Dernière tentative échouée, alerte abandonnée
+    Failed,
+}
+
+impl Queue {
+    /// `retries`: nombre maximal de tentatives par alerte
+    pub fn new(retries: u32, now: Instant) -> Self {
+        Self {
+            pending: VecDeque::new(),
+            retries: retries.max(1),
+            backoff: RETRY_MIN,
+            next: now,
+        }
+    }
+
+    /// Ajoute une alerte, vrai si la plus ancienne a été perdue
+    pub fn push(&mut self, payload: Arc<Payload>) -> bool {
+        let full = self.pending.len() >= QUEUE_LEN;
+        if full {
+            self.pending.pop_front();
+        }
+        self.pending.push_back(Pending { payload, attempts: 0 });
+        full
+    }
+
+    pub fn len(&self) -> usize {
+        self.pending.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.pending.is_empty()
+    }
+
+    /// Prochaine alerte à envoyer et heure de la tentative
+    pub fn next(&self) -> Option<(Arc<Payload>, Instant)> {
+        self.pending.front().map(|pending| (pending.payload.clone(), self.next))
+    }
+
+    /// Résultat de la tentative sur la première alerte
+    pub fn result(&mut self, success: bool, now: Instant) -> Outcome {
+        let Some(pending) = self.pending.front_mut() else {
+            return Outcome::Sent;
+        };
+
+        if success {
+            self.pending.pop_front();
+            self.backoff = RETRY_MIN;
+            self.next = now;
+            return Outcome::Sent;
+        }
+
+        // Le délai continue de croître après un abandon: le webhook est probablement injoignable
+        let delay = self.backoff;
+        self.backoff = (self.backoff * 2).min(RETRY_MAX);
+        self.next = now + delay;
+
+        pending.attempts += 1;
+        if pending.attempts >= self.retries {
+            self.pending.pop_front();
+            Outcome::Failed
+        } else {
+            Outcome::Retry(delay)
+        }
+    }
+}
+
+/// Envoi des alertes reçues vers un webhook, jusqu'à l'arrêt du programme
+pub async fn deliver(
+    url: String,
+    client: reqwest::Client,
+    retries: u32,
+    mut receiver: mpsc::Receiver<Arc<Payload>>,
+    metrics: Arc<AlertMetrics>,
+    token: CancellationToken,
+) {
+    let mut queue = Queue::new(retries, Instant::now());
+
+    loop {
+        let next = queue.next();
+        tokio::select! {
+            _ = token.cancelled() => break,
+            payload = receiver.recv() => match payload {
+                Some(payload) => {
+                    if queue.push(payload) {
+                        metrics.dropped.fetch_add(1, Ordering::Relaxed);
+                        tracing::error!(target: "alerts", "{}: file pleine, alerte la plus ancienne perdue", url);
+                    }
+                }
+                None => break,
+            },
+            _ = sleep_until(next.as_ref().map(|(_, at)| *at).unwrap_or_else(Instant::now)), if next.is_some() => {
+                let Some((payload, _)) = next else {
+                    continue;
+                };
+                let result = client.post(&url).json(payload.as_ref()).send().await;
+                let success = match result {
+                    Ok(response) if response.status().is_success() => true,
+                    Ok(response) => {
+                        tracing::error!(target: "alerts", "{}: réponse {}", url, response.status());
+                        false
+                    }
+                    Err(e) => {
+                        tracing::error!(target: "alerts", "{}: envoi impossible ({})", url, e);
+                        false
+                    }
+                };
+
+                match queue.result(success, Instant::now()) {
+                    Outcome::Sent => {
+                        metrics.sent.fetch_add(1, Ordering::Relaxed);
+                        tracing::info!(target: "alerts", "Alerte {} envoyée à {}", payload.event, url);
+                    }
+                    Outcome::Retry(delay) => {
+                        metrics.retries.fetch_add(1, Ordering::Relaxed);
+                        tracing::error!(target: "alerts", "Nouvelle tentative dans {} s", delay.as_secs());
+                    }
+                    Outcome::Failed => {
+                        metrics.failed.fetch_add(1, Ordering::Relaxed);
+                        tracing::error!(target: "alerts", "Alerte {} abandonnée pour {}", payload.event, url);
+                    }
+                }
+            }
+        }
+    }
+
+    if !queue.is_empty() {
+        tracing::error!(target: "alerts", "{}: {} alerte(s) non envoyée(s) à l'arrêt", url, queue.len());
+    }
+}
@@ -0,0 +1,99 @@
+use std::path::PathBuf;
+
+use clap::{Parser, Subcommand};
+
+use crate::config;
+use crate::export;
+use crate::logs::subscriber::LogFormat;
+
+/// Télémétrie et contrôle de la voiture RC
+#[derive(Parser)]
+#[command(version)]
+pub struct Args {
+    /// Fichier de configuration (valeurs par défaut s'il est absent)
+    #[arg(long, global = true, default_value = config::CONFIG_PATH, value_name = "FICHIER")]
+    pub config: PathBuf,
+
+    /// Rejoue un enregistrement au lieu de lire les capteurs (actionneurs simulés)
+    #[arg(long, value_name = "FICHIER")]
+    pub replay: Option<PathBuf>,
+
+    /// Facteur d'accélération du rejeu (par défaut: replay_speed de la section [record])
+    #[arg(long, value_name = "FACTEUR")]
+    pub replay_speed: Option<f64>,
+
+    /// Recommence le rejeu à la fin du fichier au lieu de s'arrêter (par défaut: replay_loop de
+    /// la section [record], --replay-loop=false pour s'arrêter à la fin)
+    #[arg(long, num_args = 0..=1, require_equals = true, default_missing_value = "true", value_name = "BOOL")]
+    pub replay_loop: Option<bool>,
+
+    /// Exécute tout le programme sans écriture en base ni sortie vers les actionneurs
+    #[arg(long)]
+    pub dry_run: bool,
+
+    /// Format du journal (niveaux selon la variable RUST_LOG, ex: RUST_LOG=control=debug,db=warn)
+    #[arg(long, global = true, value_enum, default_value_t = LogFormat::Text, value_name = "FORMAT")]
+    pub log_format: LogFormat,
+
+    /// Envoie une alerte de test aux webhooks configurés (section [alerts]) au démarrage
+    #[arg(long)]
+    pub test_alert: bool,
+
+    #[command(subcommand)]
+    pub command: Option<Command>,
+}
+
+/// Outils hors exécution
+#[derive(Subcommand)]
+pub enum Command {
+    /// Convertit un enregistrement (binaire ou JSON) en JSON lines
+    Decode {
+        /// Enregistrement à convertir
+        input: PathBuf,
+        /// Fichier de sortie (sortie standard si absent)
+        #[arg(long, short, value_name = "FICHIER")]
+        output: Option<PathBuf>,
+    },
+    /// Exporte une exécution enregistrée dans le journal JSON lines ou la base (GPX, CSV ou KML)
+    Export {
+        /// Identifiant de l'exécution (ex: run-1718000000)
+        #[arg(long)]
+        run: String,
+        #[arg(long, value_enum)]
+        format: export::Format,
+        /// Origine des enregistrements: journal ou base (section [database] de la configuration:
+        /// historique des échantillons et évènements)
+        #[arg(long, value_enum, default_value = "journal")]
+        source: export::Source,
+        /// Dossier de destination
+        #[arg(long, value_name = "DOSSIER")]
+        out: PathBuf,
+        /// Dossier du journal (par défaut: directory de la section [jsonl] de la configuration)
+        #[arg(long, value_name = "DOSSIER")]
+        journal: Option<PathBuf>,
+        /// Seuil de l'événement "batterie faible" du KML (V)
+        #[arg(long, default_value_t = 6.6, value_name = "VOLTS")]
+        battery_min: f32,
+    },
+    /// Convertit un journal blackbox (.bbl) en CSV
+    Blackbox {
+        /// Journal à convertir
+        input: PathBuf,
+        /// Fichier de sortie (sortie standard si absent)
+        #[arg(long, short, value_name = "FICHIER")]
+        output: Option<PathBuf>,
+    },
+}
+
+impl Args {
+    /// Vérifie la cohérence des arguments
+    pub fn validate(&self) -> anyhow::Result<()> {
+        if let Some(speed) = self.replay_speed {
+            if !(speed > 0.0 && speed.is_finite()) {
+                return Err(anyhow::anyhow!("Facteur de rejeu invalide: {}", speed));
+            }
+        }
+
+        Ok(())
+    }
+}
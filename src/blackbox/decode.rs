@@ -0,0 +1,207 @@
+use std::collections::BTreeMap;
+use std::io::Write;
+
+use super::encode::{
+    ENCODING_NULL, ENCODING_SIGNED_VB, ENCODING_UNSIGNED_VB, EVENT_LOG_END, PREDICT_INC, PREDICT_PREVIOUS,
+    PREDICT_STRAIGHT_LINE, PREDICT_ZERO,
+};
+
+/// Journal blackbox relu: en-tête et valeurs de chaque trame principale (loopIteration, time
+/// puis les champs)
+pub struct Log {
+    pub headers: BTreeMap<String, String>,
+    pub names: Vec<String>,
+    pub frames: Vec<Vec<i64>>,
+    /// Evénement de fin présent (journal fermé proprement)
+    pub complete: bool,
+}
+
+/// Prédicteur et encodage d'un champ, pour un type de trame
+#[derive(Clone, Copy)]
+struct Coding {
+    predictor: u8,
+    encoding: u8,
+}
+
+struct Input<'a> {
+    bytes: &'a [u8],
+    position: usize,
+}
+
+impl Input<'_> {
+    fn byte(&mut self) -> anyhow::Result<u8> {
+        let byte = *self
+            .bytes
+            .get(self.position)
+            .ok_or_else(|| anyhow::anyhow!("Fin du journal inattendue"))?;
+        self.position += 1;
+        Ok(byte)
+    }
+
+    fn unsigned_vb(&mut self) -> anyhow::Result<u32> {
+        let mut value = 0u32;
+        for shift in (0..35).step_by(7) {
+            let byte = self.byte()?;
+            value |= ((byte & 0x7F) as u32) << shift;
+            if byte & 0x80 == 0 {
+                return Ok(value);
+            }
+        }
+        Err(anyhow::anyhow!("Entier trop long à l'octet {}", self.position))
+    }
+
+    fn signed_vb(&mut self) -> anyhow::Result<i32> {
+        let value = self.unsigned_vb()?;
+        Ok(((value >> 1) as i32) ^ -((value & 1) as i32))
+    }
+
+    fn value(&mut self, encoding: u8) -> anyhow::Result<i32> {
+        match encoding {
+            ENCODING_SIGNED_VB => self.signed_vb(),
+            ENCODING_UNSIGNED_VB => Ok(self.unsigned_vb()? as i32),
+            ENCODING_NULL => Ok(0),
+            _ => Err(anyhow::anyhow!("Encodage {} non pris en charge", encoding)),
+        }
+    }
+}
+
+/// Liste d'entiers d'une ligne d'en-tête ("0,1,1")
+fn numbers(headers: &BTreeMap<String, String>, name: &str, count: usize) -> anyhow::Result<Vec<u8>> {
+    let line = headers
+        .get(name)
+        .ok_or_else(|| anyhow::anyhow!("En-tête \"{}\" absent", name))?;
+    let values = line
+        .split(',')
+        .map(|value| value.trim().parse::<u8>())
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| anyhow::anyhow!("En-tête \"{}\": {}", name, e))?;
+    if values.len() != count {
+        return Err(anyhow::anyhow!(
+            "En-tête \"{}\": {} valeur(s) pour {} champ(s)",
+            name,
+            values.len(),
+            count
+        ));
+    }
+    Ok(values)
+}
+
+fn codings(headers: &BTreeMap<String, String>, frame: char, count: usize) -> anyhow::Result<Vec<Coding>> {
+    let predictors = numbers(headers, &format!("Field {} predictor", frame), count)?;
+    let encodings = numbers(headers, &format!("Field {} encoding", frame), count)?;
+    Ok(predictors
+        .into_iter()
+        .zip(encodings)
+        .map(|(predictor, encoding)| Coding { predictor, encoding })
+        .collect())
+}
+
+impl Log {
+    /// Relit un journal: prédicteurs et encodages écrits par `Encoder` uniquement
+    pub fn parse(bytes: &[u8]) -> anyhow::Result<Self> {
+        let mut input = Input { bytes, position: 0 };
+
+        let mut headers = BTreeMap::new();
+        while bytes.get(input.position) == Some(&b'H') {
+            let end = bytes[input.position..]
+                .iter()
+                .position(|byte| *byte == b'\n')
+                .ok_or_else(|| anyhow::anyhow!("En-tête non terminé"))?;
+            let line = std::str::from_utf8(&bytes[input.position..input.position + end])?;
+            input.position += end + 1;
+
+            if let Some((name, value)) = line.trim_start_matches("H ").split_once(':') {
+                headers.insert(name.to_string(), value.to_string());
+            }
+        }
+
+        let names: Vec<String> = headers
+            .get("Field I name")
+            .ok_or_else(|| anyhow::anyhow!("En-tête \"Field I name\" absent"))?
+            .split(',')
+            .map(str::to_string)
+            .collect();
+        let signed = numbers(&headers, "Field I signed", names.len())?;
+        let intra = codings(&headers, 'I', names.len())?;
+        let inter = codings(&headers, 'P', names.len())?;
+
+        let mut frames: Vec<Vec<i64>> = Vec::new();
+        let mut previous: Vec<i32> = Vec::new();
+        let mut previous2: Vec<i32> = Vec::new();
+        let mut complete = false;
+
+        while input.position < bytes.len() {
+            let position = input.position;
+            let values = match input.byte()? {
+                b'I' => {
+                    let mut values = Vec::with_capacity(names.len());
+                    for coding in intra.iter() {
+                        if coding.predictor != PREDICT_ZERO {
+                            return Err(anyhow::anyhow!("Prédicteur I {} non pris en charge", coding.predictor));
+                        }
+                        values.push(input.value(coding.encoding)?);
+                    }
+                    previous2 = values.clone();
+                    values
+                }
+                b'P' => {
+                    if previous.is_empty() {
+                        return Err(anyhow::anyhow!("Trame P sans trame I à l'octet {}", position));
+                    }
+                    let mut values = Vec::with_capacity(names.len());
+                    for (n, coding) in inter.iter().enumerate() {
+                        let delta = input.value(coding.encoding)?;
+                        let predicted = match coding.predictor {
+                            PREDICT_ZERO => 0,
+                            PREDICT_PREVIOUS => previous[n],
+                            PREDICT_STRAIGHT_LINE => previous[n].wrapping_mul(2).wrapping_sub(previous2[n]),
+                            PREDICT_INC => previous[n].wrapping_add(1),
+                            predictor => return Err(anyhow::anyhow!("Prédicteur P {} non pris en charge", predictor)),
+                        };
+                        values.push(predicted.wrapping_add(delta));
+                    }
+                    previous2 = std::mem::take(&mut previous);
+                    values
+                }
+                b'E' if input.byte()? == EVENT_LOG_END => {
+                    complete = true;
+                    break;
+                }
+                byte => return Err(anyhow::anyhow!("Trame {:#04x} inconnue à l'octet {}", byte, position)),
+            };
+
+            frames.push(
+                values
+                    .iter()
+                    .zip(&signed)
+                    .map(|(value, signed)| {
+                        if *signed == 1 {
+                            *value as i64
+                        } else {
+                            *value as u32 as i64
+                        }
+                    })
+                    .collect(),
+            );
+            previous = values;
+        }
+
+        Ok(Self {
+            headers,
+            names,
+            frames,
+            complete,
+        })
+    }
+
+    /// Conversion CSV: une ligne par trame, noms des champs en première ligne
+    pub fn write_csv(&self, output: &mut impl Write) -> anyhow::Result<()> {
+        writeln!(output, "{}", self.names.join(","))?;
+        for frame in self.frames.iter() {
+            let line: Vec<String> = frame.iter().map(i64::to_string).collect();
+            writeln!(output, "{}", line.join(","))?;
+        }
+        output.flush()?;
+        Ok(())
+    }
+}
@@ -0,0 +1,172 @@
+use std::io::Write;
+
+/// Prédicteurs du format blackbox utilisés ici
+pub const PREDICT_ZERO: u8 = 0;
+pub const PREDICT_PREVIOUS: u8 = 1;
+pub const PREDICT_STRAIGHT_LINE: u8 = 2;
+pub const PREDICT_INC: u8 = 6;
+
+/// Encodages du format blackbox utilisés ici
+pub const ENCODING_SIGNED_VB: u8 = 0;
+pub const ENCODING_UNSIGNED_VB: u8 = 1;
+pub const ENCODING_NULL: u8 = 9;
+
+/// Evénement de fin du journal
+pub const EVENT_LOG_END: u8 = 255;
+pub const LOG_END: &[u8] = b"End of log\0";
+
+/// Champ d'une trame principale (I/P), après loopIteration et time
+#[derive(Clone, Debug, PartialEq)]
+pub struct Field {
+    pub name: String,
+    pub signed: bool,
+}
+
+/// Entier non signé à longueur variable: 7 bits par octet, bit de poids fort = suite
+pub fn unsigned_vb(out: &mut Vec<u8>, mut value: u32) {
+    while value >= 0x80 {
+        out.push((value as u8) | 0x80);
+        value >>= 7;
+    }
+    out.push(value as u8);
+}
+
+/// Entier signé à longueur variable (zigzag)
+pub fn signed_vb(out: &mut Vec<u8>, value: i32) {
+    unsigned_vb(out, ((value << 1) ^ (value >> 31)) as u32);
+}
+
+/// Ecriture d'un journal blackbox: en-tête de définition des champs puis une trame par
+/// itération, trame I (valeurs complètes) toutes les `i_interval` itérations, trames P
+/// (écart à la prédiction) entre les deux.
+pub struct Encoder<W: Write> {
+    out: W,
+    /// Champ signé, pour l'encodage des trames I
+    signed: Vec<bool>,
+    i_interval: u32,
+    iteration: u32,
+    /// Deux dernières trames (time puis champs), pour les prédicteurs
+    previous: Vec<i32>,
+    previous2: Vec<i32>,
+    frame: Vec<u8>,
+}
+
+impl<W: Write> Encoder<W> {
+    /// Ecrit l'en-tête. `headers`: lignes d'information supplémentaires (nom, valeur).
+    pub fn new(
+        mut out: W,
+        fields: &[Field],
+        i_interval: u32,
+        headers: &[(&str, String)],
+    ) -> anyhow::Result<Self> {
+        let i_interval = i_interval.max(1);
+
+        let mut names = vec!["loopIteration".to_string(), "time".to_string()];
+        names.extend(fields.iter().map(|field| field.name.clone()));
+        let list = |values: Vec<u8>| values.iter().map(u8::to_string).collect::<Vec<_>>().join(",");
+
+        let mut signed = vec![0, 0];
+        signed.extend(fields.iter().map(|field| field.signed as u8));
+        let mut i_encoding = vec![ENCODING_UNSIGNED_VB, ENCODING_UNSIGNED_VB];
+        i_encoding.extend(fields.iter().map(|field| {
+            if field.signed {
+                ENCODING_SIGNED_VB
+            } else {
+                ENCODING_UNSIGNED_VB
+            }
+        }));
+        let mut p_predictor = vec![PREDICT_INC, PREDICT_STRAIGHT_LINE];
+        p_predictor.extend(fields.iter().map(|_| PREDICT_PREVIOUS));
+        let mut p_encoding = vec![ENCODING_NULL, ENCODING_SIGNED_VB];
+        p_encoding.extend(fields.iter().map(|_| ENCODING_SIGNED_VB));
+
+        let mut lines = vec![
+            (
+                "Product",
+                "Blackbox flight data recorder by Nicholas Sherlock".to_string(),
+            ),
+            ("Data version", "2".to_string()),
+            ("I interval", i_interval.to_string()),
+            ("P interval", "1/1".to_string()),
+            ("Field I name", names.join(",")),
+            ("Field I signed", list(signed)),
+            ("Field I predictor", list(vec![PREDICT_ZERO; names.len()])),
+            ("Field I encoding", list(i_encoding)),
+            ("Field P predictor", list(p_predictor)),
+            ("Field P encoding", list(p_encoding)),
+            ("Firmware type", "Cleanflight".to_string()),
+        ];
+        lines.extend(headers.iter().map(|(name, value)| (*name, value.clone())));
+        for (name, value) in lines {
+            writeln!(out, "H {}:{}", name, value)?;
+        }
+
+        Ok(Self {
+            out,
+            signed: fields.iter().map(|field| field.signed).collect(),
+            i_interval,
+            iteration: 0,
+            previous: Vec::new(),
+            previous2: Vec::new(),
+            frame: Vec::new(),
+        })
+    }
+
+    /// Ecrit la trame d'une itération: temps (µs) et valeur de chaque champ
+    pub fn write(&mut self, time_us: u32, values: &[i32]) -> anyhow::Result<()> {
+        if values.len() != self.signed.len() {
+            return Err(anyhow::anyhow!(
+                "{} valeur(s) pour {} champ(s)",
+                values.len(),
+                self.signed.len()
+            ));
+        }
+
+        let mut current = Vec::with_capacity(values.len() + 1);
+        current.push(time_us as i32);
+        current.extend_from_slice(values);
+
+        self.frame.clear();
+        if self.iteration.is_multiple_of(self.i_interval) {
+            self.frame.push(b'I');
+            unsigned_vb(&mut self.frame, self.iteration);
+            unsigned_vb(&mut self.frame, time_us);
+            for (value, signed) in values.iter().zip(&self.signed) {
+                if *signed {
+                    signed_vb(&mut self.frame, *value);
+                } else {
+                    unsigned_vb(&mut self.frame, *value as u32);
+                }
+            }
+            // Après une trame I, les deux prédictions partent de ses valeurs
+            self.previous2 = current.clone();
+        } else {
+            self.frame.push(b'P');
+            let predicted = self.previous[0].wrapping_mul(2).wrapping_sub(self.previous2[0]);
+            signed_vb(&mut self.frame, current[0].wrapping_sub(predicted));
+            for (value, previous) in values.iter().zip(&self.previous[1..]) {
+                signed_vb(&mut self.frame, value.wrapping_sub(*previous));
+            }
+            self.previous2 = std::mem::take(&mut self.previous);
+        }
+        self.previous = current;
+
+        self.out.write_all(&self.frame)?;
+        self.iteration = self.iteration.wrapping_add(1);
+        Ok(())
+    }
+
+    /// Ecrit les trames en attente sur le disque
+    pub fn flush(&mut self) -> anyhow::Result<()> {
+        self.out.flush()?;
+        Ok(())
+    }
+
+    /// Evénement de fin du journal, le fichier peut être fermé
+    pub fn finish(mut self) -> anyhow::Result<W> {
+        self.out.write_all(&[b'E', EVENT_LOG_END])?;
+        self.out.write_all(LOG_END)?;
+        self.out.flush()?;
+        Ok(self.out)
+    }
+}
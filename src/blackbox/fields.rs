@@ -0,0 +1,96 @@
+use crate::actuators::arbiter::ControlState;
+use crate::clock::Stamp;
+use crate::config::{SensorMode, SensorsConfig};
+use crate::sensors::reader::Data;
+
+use super::encode::Field;
+
+/// Origine de la valeur d'un champ
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Source {
+    /// Roulis, tangage, lacet (dixièmes de degré)
+    Attitude(usize),
+    /// Direction demandée (-500 à 500)
+    SteerInput,
+    /// Vitesse demandée (1000 à 2000, neutre 1500)
+    SpeedInput,
+    /// Impulsion appliquée à la direction (µs)
+    Steering,
+    /// Impulsion appliquée à l'ESC (µs)
+    Motor,
+    /// Tension de la batterie (centièmes de volt)
+    Battery,
+    /// Courant (centièmes d'ampère), depuis le signal CAN configuré
+    Current,
+    /// Cap GPS avec fix, du magnétomètre sinon (dixièmes de degré)
+    Heading,
+}
+
+/// Champs du journal selon les capteurs activés, noms repris des journaux Betaflight/INAV
+/// quand un équivalent existe
+pub fn fields(sensors: &SensorsConfig, current: bool) -> Vec<(Field, Source)> {
+    let enabled = |mode: SensorMode| mode != SensorMode::Disabled;
+    let field = |name: &str, signed: bool, source: Source| {
+        (
+            Field {
+                name: name.to_string(),
+                signed,
+            },
+            source,
+        )
+    };
+
+    let mut fields = Vec::new();
+    if enabled(sensors.imu.mode) {
+        for axis in 0..3 {
+            fields.push(field(&format!("attitude[{}]", axis), true, Source::Attitude(axis)));
+        }
+    }
+    fields.push(field("rcCommand[0]", true, Source::SteerInput));
+    fields.push(field("rcCommand[3]", false, Source::SpeedInput));
+    fields.push(field("servo[0]", false, Source::Steering));
+    fields.push(field("motor[0]", false, Source::Motor));
+    if enabled(sensors.analog.mode) {
+        fields.push(field("vbatLatest", false, Source::Battery));
+    }
+    if current {
+        fields.push(field("amperageLatest", true, Source::Current));
+    }
+    if enabled(sensors.mag.mode) || enabled(sensors.gps.mode) {
+        fields.push(field("heading", false, Source::Heading));
+    }
+    fields
+}
+
+/// Impulsion d'une commande entre -1 et 1 (µs, neutre 1500)
+fn pulse(value: f64) -> i32 {
+    1500 + (value.clamp(-1.0, 1.0) * 500.0).round() as i32
+}
+
+impl Source {
+    /// Valeur du champ à partir des derniers échantillons (0 tant qu'ils sont inconnus)
+    pub fn value(&self, data: &Data, control: &ControlState, current: Option<f64>) -> i32 {
+        match *self {
+            Source::Attitude(axis) => {
+                let (pitch, roll, yaw) = data.imu.angles;
+                ([roll, pitch, yaw][axis] * 10.0).round() as i32
+            }
+            Source::SteerInput => (control.input.steer.clamp(-1.0, 1.0) * 500.0).round() as i32,
+            Source::SpeedInput => pulse(control.input.speed),
+            Source::Steering => pulse(control.output.steer),
+            Source::Motor => pulse(control.output.speed),
+            Source::Battery => (data.analog.battery.max(0.0) * 100.0).round() as i32,
+            Source::Current => current.map_or(0, |amps| (amps * 100.0).round() as i32),
+            Source::Heading => {
+                let heading = if data.gps.fix {
+                    data.gps.heading
+                } else if data.mag.stamp != Stamp::default() {
+                    data.mag.true_heading as f64
+                } else {
+                    0.0
+                };
+                (heading.rem_euclid(360.0) * 10.0).round() as i32
+            }
+        }
+    }
+}
@@ -0,0 +1,127 @@
+use std::fs::File;
+use std::io::BufWriter;
+use std::thread;
+use std::time::{Duration, Instant};
+
+use tokio::sync::watch;
+use tokio_util::sync::CancellationToken;
+
+use crate::actuators::arbiter::ControlState;
+use crate::clock::Clock;
+use crate::config::{BlackboxConfig, SensorsConfig};
+use crate::writer::Writer;
+
+pub mod decode;
+pub mod encode;
+pub mod fields;
+
+use encode::{Encoder, Field};
+
+/// Intervalle d'écriture sur le disque, perte maximale en cas de coupure
+const FLUSH_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Journal blackbox de l'exécution (un fichier .bbl par exécution), lisible par les outils
+/// Betaflight (blackbox-log-viewer, blackbox_decode). L'échantillonnage et l'écriture se font
+/// dans un thread dédié, à la fréquence configurée.
+pub async fn run(
+    config: BlackboxConfig,
+    sensors: SensorsConfig,
+    run: String,
+    writer: Writer,
+    control: watch::Receiver<ControlState>,
+    clock: Clock,
+    token: CancellationToken,
+) {
+    let thread = thread::Builder::new()
+        .name("blackbox".to_string())
+        .spawn(move || log_loop(&config, &sensors, &run, &writer, &control, &clock, &token));
+
+    let result = match thread {
+        Ok(thread) => tokio::task::spawn_blocking(move || thread.join()).await,
+        Err(e) => {
+            tracing::error!(target: "blackbox", "Impossible de démarrer le journal: {}", e);
+            return;
+        }
+    };
+    match result {
+        Ok(Ok(Ok(frames))) => tracing::info!(target: "blackbox", "Arrêt ({} trame(s)).", frames),
+        Ok(Ok(Err(e))) => tracing::error!(target: "blackbox", "Arrêt du journal: {}", e),
+        _ => tracing::error!(target: "blackbox", "Arrêt inattendu du journal."),
+    }
+}
+
+fn log_loop(
+    config: &BlackboxConfig,
+    sensors: &SensorsConfig,
+    run: &str,
+    writer: &Writer,
+    control: &watch::Receiver<ControlState>,
+    clock: &Clock,
+    token: &CancellationToken,
+) -> anyhow::Result<u64> {
+    let current = config.current_signal.split_once('.');
+    let (fields, sources): (Vec<Field>, Vec<_>) = fields::fields(sensors, current.is_some()).into_iter().unzip();
+
+    std::fs::create_dir_all(&config.directory)?;
+    let path = config.directory.join(format!("{}.bbl", run));
+    let headers = [
+        (
+            "Firmware revision",
+            format!("rc-telemetrie {}", env!("CARGO_PKG_VERSION")),
+        ),
+        ("Log start datetime", clock.stamp().utc.to_rfc3339()),
+        ("Run", run.to_string()),
+    ];
+    let mut encoder = Encoder::new(
+        BufWriter::new(File::create(&path)?),
+        &fields,
+        config.i_interval,
+        &headers,
+    )?;
+
+    tracing::info!(
+        target: "blackbox",
+        "Journal {} ({} Hz): {}",
+        path.display(),
+        config.rate_hz,
+        fields
+            .iter()
+            .map(|field| field.name.as_str())
+            .collect::<Vec<_>>()
+            .join(", ")
+    );
+
+    let period = Duration::from_secs_f64(1.0 / config.rate_hz as f64);
+    let mut next = Instant::now();
+    let mut last_flush = Instant::now();
+    let mut frames = 0u64;
+    let mut values = Vec::with_capacity(sources.len());
+
+    while !token.is_cancelled() {
+        let data = writer.latest().data;
+        let state = control.borrow().clone();
+        let amps = current.and_then(|(message, signal)| writer.latest_can().get(message)?.signals.get(signal).copied());
+
+        values.clear();
+        values.extend(sources.iter().map(|source| source.value(&data, &state, amps)));
+        encoder.write(clock.elapsed().as_micros() as u32, &values)?;
+        frames += 1;
+
+        if last_flush.elapsed() >= FLUSH_INTERVAL {
+            last_flush = Instant::now();
+            encoder.flush()?;
+        }
+
+        // Période fixe, sans rattrapage après un retard
+        next += period;
+        let now = Instant::now();
+        if next > now {
+            thread::sleep(next - now);
+        } else {
+            next = now;
+        }
+    }
+
+    encoder.finish()?;
+    Ok(frames)
+}
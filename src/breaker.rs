@@ -0,0 +1,194 @@
+use std::collections::VecDeque;
+use std::future::Future;
+use std::time::{Duration, Instant};
+
+use serde::Serialize;
+
+use crate::clock::Clock;
+use crate::config::BreakerConfig;
+
+/// Etat du coupe-circuit
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BreakerState {
+    /// Ecritures transmises
+    #[default]
+    Closed,
+    /// Ecritures détournées, en attente de la prochaine écriture de test
+    Open,
+    /// Ecritures de test, une à la fois
+    HalfOpen,
+}
+
+impl BreakerState {
+    pub fn name(&self) -> &'static str {
+        match self {
+            BreakerState::Closed => "closed",
+            BreakerState::Open => "open",
+            BreakerState::HalfOpen => "half_open",
+        }
+    }
+}
+
+/// Etat et compteurs du coupe-circuit
+#[derive(Clone, Copy, Debug, Default, PartialEq, Serialize)]
+pub struct BreakerStats {
+    pub state: BreakerState,
+    /// Nombre d'ouvertures
+    pub opened: u64,
+    /// Ecritures détournées (non tentées)
+    pub diverted: u64,
+    /// Ecritures de test
+    pub probes: u64,
+}
+
+/// Changement d'état et sa cause
+#[derive(Clone, Debug, PartialEq)]
+pub struct Transition {
+    pub state: BreakerState,
+    pub reason: String,
+}
+
+/// Coupe-circuit autour des écritures vers un puits lent ou en erreur (base de donnée).
+/// Fermé: chaque écriture est mesurée sur une fenêtre glissante. Ouvert: les écritures sont
+/// détournées, une écriture de test est autorisée à chaque intervalle. Refermé après plusieurs
+/// écritures de test réussies.
+pub struct Breaker {
+    config: BreakerConfig,
+    clock: Clock,
+    /// Dernières écritures: latence et réussite
+    window: VecDeque<(Duration, bool)>,
+    /// Instant de l'ouverture ou du dernier test en échec
+    opened_at: Option<Instant>,
+    /// Tests réussis consécutifs
+    successes: u32,
+    stats: BreakerStats,
+    transitions: Vec<Transition>,
+}
+
+impl Breaker {
+    pub fn new(config: &BreakerConfig, clock: &Clock) -> Self {
+        Self {
+            config: config.clone(),
+            clock: clock.clone(),
+            window: VecDeque::with_capacity(config.window),
+            opened_at: None,
+            successes: 0,
+            stats: BreakerStats::default(),
+            transitions: Vec::new(),
+        }
+    }
+
+    /// Effectue l'écriture si le coupe-circuit l'autorise, None: écriture détournée
+    pub async fn call<T>(
+        &mut self,
+        write: impl Future<Output = anyhow::Result<T>>,
+    ) -> Option<anyhow::Result<T>> {
+        if !self.admit() {
+            self.stats.diverted += 1;
+            return None;
+        }
+
+        let start = self.clock.now();
+        let result = write.await;
+        self.record(self.clock.since(start), result.is_ok());
+        Some(result)
+    }
+
+    pub fn stats(&self) -> BreakerStats {
+        self.stats
+    }
+
+    /// Changements d'état depuis le dernier appel
+    pub fn transitions(&mut self) -> Vec<Transition> {
+        std::mem::take(&mut self.transitions)
+    }
+
+    /// Ecriture autorisée: coupe-circuit désactivé, fermé, ou test en cours
+    fn admit(&mut self) -> bool {
+        if !self.config.enabled {
+            return true;
+        }
+
+        match self.stats.state {
+            BreakerState::Closed | BreakerState::HalfOpen => true,
+            BreakerState::Open => {
+                let probe = Duration::from_millis(self.config.probe_interval_ms);
+                if self
+                    .opened_at
+                    .is_some_and(|opened| self.clock.since(opened) < probe)
+                {
+                    return false;
+                }
+
+                self.successes = 0;
+                self.transition(BreakerState::HalfOpen, "écriture de test".to_string());
+                true
+            }
+        }
+    }
+
+    fn record(&mut self, latency: Duration, ok: bool) {
+        if !self.config.enabled {
+            return;
+        }
+
+        let max_latency = Duration::from_millis(self.config.max_latency_ms);
+        match self.stats.state {
+            BreakerState::Closed => {
+                if self.window.len() >= self.config.window {
+                    self.window.pop_front();
+                }
+                self.window.push_back((latency, ok));
+
+                if self.window.len() < self.config.min_writes {
+                    return;
+                }
+
+                let count = self.window.len() as u32;
+                let mean = self
+                    .window
+                    .iter()
+                    .map(|(latency, _)| *latency)
+                    .sum::<Duration>()
+                    / count;
+                let errors = self.window.iter().filter(|(_, ok)| !ok).count() as f64 / count as f64;
+                if errors > self.config.max_error_rate {
+                    self.open(format!("{:.0} % d'écritures en erreur", errors * 100.0));
+                } else if mean > max_latency {
+                    self.open(format!("latence moyenne de {} ms", mean.as_millis()));
+                }
+            }
+            BreakerState::HalfOpen => {
+                self.stats.probes += 1;
+                if !ok {
+                    self.open("écriture de test en erreur".to_string());
+                } else if latency > max_latency {
+                    self.open(format!("écriture de test en {} ms", latency.as_millis()));
+                } else {
+                    self.successes += 1;
+                    if self.successes >= self.config.close_after {
+                        self.window.clear();
+                        self.opened_at = None;
+                        let reason = format!("{} écriture(s) de test réussie(s)", self.successes);
+                        self.transition(BreakerState::Closed, reason);
+                    }
+                }
+            }
+            BreakerState::Open => {}
+        }
+    }
+
+    fn open(&mut self, reason: String) {
+        if self.stats.state == BreakerState::Closed {
+            self.stats.opened += 1;
+        }
+        self.opened_at = Some(self.clock.now());
+        self.transition(BreakerState::Open, reason);
+    }
+
+    fn transition(&mut self, state: BreakerState, reason: String) {
+        self.stats.state = state;
+        self.transitions.push(Transition { state, reason });
+    }
+}
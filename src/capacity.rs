@@ -0,0 +1,87 @@
+use serde::{Deserialize, Serialize};
+
+/// Ecart maximal entre deux mesures de courant intégrées (µs): au-delà (capteur arrêté,
+/// application en pause), l'intervalle n'est pas compté
+pub const MAX_GAP_US: u64 = 2_000_000;
+
+/// Remise à zéro demandée par la base (control:battery), après un changement de batterie
+#[derive(Clone, Debug, Default, Deserialize)]
+pub struct CapacityReset {
+    /// Capacité déjà consommée de la nouvelle batterie (mAh)
+    #[serde(default)]
+    pub consumed_mah: f64,
+}
+
+/// Dernière capacité publiée par le suivi de la batterie
+#[derive(Clone, Copy, Debug, Default, PartialEq, Serialize)]
+pub struct CapacityState {
+    pub consumed_mah: f64,
+    pub remaining_pct: f64,
+}
+
+/// Capacité consommée de la batterie, intégrée depuis le courant du pack (méthode des trapèzes).
+/// Une mesure de courant manquante reprend la précédente une seule fois, puis l'intégration
+/// s'arrête jusqu'à la mesure suivante.
+pub struct Capacity {
+    capacity_mah: f64,
+    consumed_mah: f64,
+    /// Dernière mesure: horodatage monotone (µs), courant (A)
+    last: Option<(u64, f32)>,
+    /// Dernière mesure reprise pour un échantillon manquant
+    held: bool,
+}
+
+impl Capacity {
+    pub fn new(capacity_mah: f64) -> Self {
+        Self {
+            capacity_mah,
+            consumed_mah: 0.0,
+            last: None,
+            held: false,
+        }
+    }
+
+    /// Nouvelle batterie, `consumed_mah` déjà consommés
+    pub fn reset(&mut self, consumed_mah: f64) {
+        self.consumed_mah = consumed_mah.max(0.0);
+        self.last = None;
+        self.held = false;
+    }
+
+    /// Echantillon du capteur analogique, `current` absent: mesure du courant perdue
+    pub fn update(&mut self, mono_us: u64, current: Option<f32>) {
+        let current = match (current, self.last) {
+            (Some(current), _) => {
+                self.held = false;
+                current
+            }
+            (None, Some((_, last))) if !self.held => {
+                self.held = true;
+                last
+            }
+            (None, _) => {
+                self.last = None;
+                return;
+            }
+        };
+
+        if let Some((last_us, last)) = self.last {
+            let dt_us = mono_us.saturating_sub(last_us);
+            if dt_us <= MAX_GAP_US {
+                // A·s -> mAh
+                let amp_s = (last + current) as f64 / 2.0 * dt_us as f64 / 1e6;
+                self.consumed_mah += amp_s / 3.6;
+            }
+        }
+        self.last = Some((mono_us, current));
+    }
+
+    pub fn consumed_mah(&self) -> f64 {
+        self.consumed_mah
+    }
+
+    /// Capacité restante (%), entre 0 et 100
+    pub fn remaining_pct(&self) -> f64 {
+        (100.0 * (1.0 - self.consumed_mah / self.capacity_mah)).clamp(0.0, 100.0)
+    }
+}
@@ -0,0 +1,174 @@
+use std::collections::VecDeque;
+use std::sync::{Arc, Mutex};
+
+use serde::Serialize;
+use tokio::sync::{mpsc, Notify};
+
+/// Etat d'une file: profondeur actuelle et nombre d'éléments perdus ou fusionnés
+#[derive(Clone, Copy, Debug, Default, PartialEq, Serialize)]
+pub struct ChannelStats {
+    pub depth: usize,
+    pub capacity: usize,
+    pub dropped: u64,
+}
+
+struct Bounded<T> {
+    queue: VecDeque<T>,
+    capacity: usize,
+    dropped: u64,
+}
+
+/// File bornée: quand elle est pleine, l'élément le plus ancien est supprimé
+pub struct DropOldest<T> {
+    inner: Arc<Mutex<Bounded<T>>>,
+    notify: Arc<Notify>,
+}
+
+impl<T> Clone for DropOldest<T> {
+    fn clone(&self) -> Self {
+        Self {
+            inner: self.inner.clone(),
+            notify: self.notify.clone(),
+        }
+    }
+}
+
+impl<T> DropOldest<T> {
+    /// Constructeur, `notify` est signalé à chaque ajout
+    pub fn new(capacity: usize, notify: Arc<Notify>) -> Self {
+        let capacity = capacity.max(1);
+        Self {
+            inner: Arc::new(Mutex::new(Bounded {
+                queue: VecDeque::with_capacity(capacity),
+                capacity,
+                dropped: 0,
+            })),
+            notify,
+        }
+    }
+
+    /// Ajoute un élément, sans jamais attendre
+    pub fn push(&self, item: T) {
+        {
+            let mut inner = self.inner.lock().unwrap();
+            if inner.queue.len() >= inner.capacity {
+                inner.queue.pop_front();
+                inner.dropped += 1;
+            }
+            inner.queue.push_back(item);
+        }
+
+        self.notify.notify_one();
+    }
+
+    /// Récupère l'élément le plus ancien
+    pub fn pop(&self) -> Option<T> {
+        self.inner.lock().unwrap().queue.pop_front()
+    }
+
+    pub fn stats(&self) -> ChannelStats {
+        let inner = self.inner.lock().unwrap();
+        ChannelStats {
+            depth: inner.queue.len(),
+            capacity: inner.capacity,
+            dropped: inner.dropped,
+        }
+    }
+}
+
+struct Slot<T> {
+    pending: Option<T>,
+    coalesced: u64,
+}
+
+/// Emplacement unique: un nouvel élément remplace celui qui n'a pas encore été traité
+pub struct Coalesce<T> {
+    inner: Arc<Mutex<Slot<T>>>,
+    notify: Arc<Notify>,
+}
+
+impl<T> Clone for Coalesce<T> {
+    fn clone(&self) -> Self {
+        Self {
+            inner: self.inner.clone(),
+            notify: self.notify.clone(),
+        }
+    }
+}
+
+impl<T> Coalesce<T> {
+    /// Constructeur, `notify` est signalé à chaque ajout
+    pub fn new(notify: Arc<Notify>) -> Self {
+        Self {
+            inner: Arc::new(Mutex::new(Slot {
+                pending: None,
+                coalesced: 0,
+            })),
+            notify,
+        }
+    }
+
+    /// Remplace l'élément en attente, sans jamais attendre
+    pub fn push(&self, item: T) {
+        {
+            let mut inner = self.inner.lock().unwrap();
+            if inner.pending.replace(item).is_some() {
+                inner.coalesced += 1;
+            }
+        }
+
+        self.notify.notify_one();
+    }
+
+    /// Récupère l'élément en attente
+    pub fn take(&self) -> Option<T> {
+        self.inner.lock().unwrap().pending.take()
+    }
+
+    pub fn stats(&self) -> ChannelStats {
+        let inner = self.inner.lock().unwrap();
+        ChannelStats {
+            depth: inner.pending.is_some() as usize,
+            capacity: 1,
+            dropped: inner.coalesced,
+        }
+    }
+}
+
+/// File bornée sans perte: l'émetteur attend qu'une place se libère
+pub struct Lossless<T> {
+    sender: mpsc::Sender<T>,
+}
+
+impl<T> Clone for Lossless<T> {
+    fn clone(&self) -> Self {
+        Self {
+            sender: self.sender.clone(),
+        }
+    }
+}
+
+impl<T> Lossless<T> {
+    /// Constructeur, retourne l'émetteur et le récepteur
+    pub fn new(capacity: usize) -> (Self, mpsc::Receiver<T>) {
+        let (sender, receiver) = mpsc::channel(capacity.max(1));
+        (Self { sender }, receiver)
+    }
+
+    /// Ajoute un élément, attend si la file est pleine
+    pub async fn send(&self, item: T) -> anyhow::Result<()> {
+        self.sender
+            .send(item)
+            .await
+            .map_err(|_| anyhow::anyhow!("File fermée"))
+    }
+
+    pub fn stats(&self) -> ChannelStats {
+        let capacity = self.sender.max_capacity();
+        ChannelStats {
+            depth: capacity - self.sender.capacity(),
+            capacity,
+            dropped: 0,
+        }
+    }
+}
@@ -0,0 +1,149 @@
+use std::future::Future;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use tokio::sync::watch;
+
+/// Horodatage d'un échantillon: temps monotone depuis le démarrage et heure UTC dérivée
+#[derive(Clone, Copy, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct Stamp {
+    /// Microsecondes depuis le démarrage (monotone)
+    pub mono_us: u64,
+    /// Heure UTC, calculée depuis l'ancrage et le temps monotone
+    pub utc: DateTime<Utc>,
+}
+
+#[derive(Clone)]
+enum Source {
+    /// Horloge monotone du système
+    Real(Instant),
+    /// Horloge monotone accélérée (simulation)
+    Accelerated(Instant, f64),
+    /// Horloge avancée manuellement (rejeu, tests), en microsecondes. Les attentes sont
+    /// réveillées à chaque avance.
+    Virtual(Arc<watch::Sender<u64>>),
+}
+
+/// Source de temps unique, partagée par tous les capteurs et la logique dépendante du temps
+/// (homme mort, anti-rebonds, fraîcheur). Les instants de `now` ne sont comparables qu'entre
+/// instants d'une même horloge.
+#[derive(Clone)]
+pub struct Clock {
+    anchor: DateTime<Utc>,
+    /// Instant correspondant à l'ancrage
+    origin: Instant,
+    source: Source,
+}
+
+impl Clock {
+    /// Horloge réelle, ancrée sur l'heure actuelle
+    pub fn start() -> Self {
+        let origin = Instant::now();
+        Self {
+            anchor: Utc::now(),
+            origin,
+            source: Source::Real(origin),
+        }
+    }
+
+    /// Horloge réelle accélérée d'un facteur `scale`, ancrée sur l'heure actuelle
+    pub fn accelerated(scale: f64) -> Self {
+        let origin = Instant::now();
+        Self {
+            anchor: Utc::now(),
+            origin,
+            source: Source::Accelerated(origin, scale),
+        }
+    }
+
+    /// Horloge virtuelle ancrée sur l'heure donnée, n'avance qu'avec `set`
+    pub fn virtual_at(anchor: DateTime<Utc>) -> Self {
+        Self {
+            anchor,
+            origin: Instant::now(),
+            source: Source::Virtual(Arc::new(watch::channel(0).0)),
+        }
+    }
+
+    /// Temps écoulé depuis l'ancrage
+    pub fn elapsed(&self) -> Duration {
+        match &self.source {
+            Source::Real(start) => start.elapsed(),
+            Source::Accelerated(start, scale) => start.elapsed().mul_f64(*scale),
+            Source::Virtual(us) => Duration::from_micros(*us.borrow()),
+        }
+    }
+
+    /// Instant monotone présent selon cette horloge
+    pub fn now(&self) -> Instant {
+        self.origin + self.elapsed()
+    }
+
+    /// Durée écoulée depuis un instant de cette horloge (nulle pour un instant futur)
+    pub fn since(&self, instant: Instant) -> Duration {
+        self.now().saturating_duration_since(instant)
+    }
+
+    /// Attend que la durée donnée soit écoulée selon cette horloge
+    pub async fn sleep(&self, duration: Duration) {
+        match &self.source {
+            Source::Real(_) => tokio::time::sleep(duration).await,
+            Source::Accelerated(_, scale) => tokio::time::sleep(duration.div_f64(*scale)).await,
+            Source::Virtual(us) => {
+                let due = (self.elapsed() + duration).as_micros() as u64;
+                let _ = us.subscribe().wait_for(|us| *us >= due).await;
+            }
+        }
+    }
+
+    /// Attend un instant de cette horloge (immédiat s'il est passé)
+    pub async fn sleep_until(&self, deadline: Instant) {
+        self.sleep(deadline.saturating_duration_since(self.now())).await
+    }
+
+    /// Comme `sleep`, en bloquant le thread appelant (threads hors runtime, ex: rejeu)
+    pub fn sleep_blocking(&self, duration: Duration) {
+        match &self.source {
+            Source::Real(_) => std::thread::sleep(duration),
+            Source::Accelerated(_, scale) => std::thread::sleep(duration.div_f64(*scale)),
+            Source::Virtual(_) => futures::executor::block_on(self.sleep(duration)),
+        }
+    }
+
+    /// Attend `future` au plus `duration` selon cette horloge, None: délai expiré
+    pub async fn timeout<F: Future>(&self, duration: Duration, future: F) -> Option<F::Output> {
+        tokio::select! {
+            biased;
+            output = future => Some(output),
+            _ = self.sleep(duration) => None,
+        }
+    }
+
+    /// Horodate un échantillon à l'instant présent
+    pub fn stamp(&self) -> Stamp {
+        self.stamp_at(self.elapsed())
+    }
+
+    /// Horodatage correspondant à un temps écoulé donné
+    pub fn stamp_at(&self, elapsed: Duration) -> Stamp {
+        let mono_us = elapsed.as_micros() as u64;
+        Stamp {
+            mono_us,
+            utc: self.anchor + chrono::Duration::microseconds(mono_us as i64),
+        }
+    }
+
+    /// Positionne une horloge virtuelle, sans jamais reculer (sans effet sur l'horloge réelle)
+    pub fn set(&self, elapsed: Duration) {
+        if let Source::Virtual(us) = &self.source {
+            let elapsed = elapsed.as_micros() as u64;
+            us.send_if_modified(|us| {
+                let forward = elapsed > *us;
+                *us = (*us).max(elapsed);
+                forward
+            });
+        }
+    }
+}
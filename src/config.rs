@@ -0,0 +1,3073 @@
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+
+use crate::sensors::can::decode::CanMessage;
+use crate::sensors::sim::Scenario;
+
+/// Emplacement par défaut du fichier de configuration
+pub const CONFIG_PATH: &str = "/etc/rc-telemetrie/config.toml";
+
+/// Paramètres modifiables sans redémarrage (préfixes des clés).
+/// Doit rester cohérent avec `Config::apply_runtime`.
+const RELOADABLE: &[&str] = &["timing.", "writer.stats_interval_s"];
+
+/// Changements entre la configuration active et le fichier rechargé
+#[derive(Default)]
+pub struct ConfigDiff {
+    /// Appliqués immédiatement
+    pub applied: Vec<String>,
+    /// Ignorés jusqu'au prochain redémarrage (broches, bus, base de donnée, ...)
+    pub restart: Vec<String>,
+}
+
+#[derive(Clone, Deserialize, Serialize)]
+#[serde(default)]
+pub struct Config {
+    /// Aucune écriture en base ni sortie vers les actionneurs (équivalent de --dry-run)
+    pub dry_run: bool,
+    /// Nom du véhicule, utilisé dans les annonces réseau
+    pub vehicle: String,
+    pub database: DatabaseConfig,
+    pub i2c: I2cConfig,
+    pub sensors: SensorsConfig,
+    pub record: RecordConfig,
+    pub run: RunConfig,
+    /// Scénario des capteurs simulés
+    pub simulation: Scenario,
+    pub writer: WriterConfig,
+    pub timing: TimingConfig,
+    pub mavlink: MavlinkConfig,
+    pub http: HttpConfig,
+    pub udp: UdpConfig,
+    pub can: CanConfig,
+    pub csv: CsvConfig,
+    pub jsonl: JsonlConfig,
+    pub mdns: MdnsConfig,
+    pub grpc: GrpcConfig,
+    pub grafana: GrafanaConfig,
+    pub mqtt: MqttConfig,
+    pub zenoh: ZenohConfig,
+    pub sport: SportConfig,
+    pub blackbox: BlackboxConfig,
+    pub alerts: AlertsConfig,
+    pub fusion: FusionConfig,
+    pub heading_check: HeadingCheckConfig,
+    pub gps_time: GpsTimeConfig,
+    pub rollover: RolloverConfig,
+    pub obstacle: ObstacleConfig,
+    pub low_voltage: LowVoltageConfig,
+    pub capacity: CapacityConfig,
+    pub thermal: ThermalConfig,
+    pub geofence: GeofenceConfig,
+    pub watchdog: WatchdogConfig,
+    pub control: ControlConfig,
+    pub auto_disarm: AutoDisarmConfig,
+    pub prearm: PreArmConfig,
+    pub status_led: StatusLedConfig,
+    pub logs: LogsConfig,
+}
+
+/// Télémétrie FrSky S.Port vers l'émetteur: le véhicule répond aux interrogations du récepteur
+/// comme un ou plusieurs capteurs
+#[derive(Clone, Deserialize, Serialize)]
+#[serde(default)]
+pub struct SportConfig {
+    pub enabled: bool,
+    /// Port série relié au bus S.Port
+    pub device: PathBuf,
+    /// Inverseur externe sur le câblage: le port série travaille en logique normale.
+    /// Sinon, le port série doit inverser lui-même le signal (ex: adaptateur FTDI configuré).
+    pub external_inverter: bool,
+    /// Signal CAN du courant ("message.signal"), vide: courant non transmis
+    pub current_signal: String,
+    pub sensors: Vec<SportSensor>,
+}
+
+/// Capteur simulé sur le bus S.Port
+#[derive(Clone, Deserialize, Serialize)]
+pub struct SportSensor {
+    /// Identifiant du capteur (0-27)
+    pub id: u8,
+    /// Valeurs transmises tour à tour
+    pub values: Vec<SportValue>,
+}
+
+/// Valeur transmise par un capteur S.Port
+#[derive(Clone, Copy, Debug, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SportValue {
+    /// Tension de la batterie
+    Vfas,
+    /// Courant, depuis le signal CAN configuré
+    Current,
+    Latitude,
+    Longitude,
+    /// Vitesse GPS
+    Speed,
+    /// Cap GPS, ou du magnétomètre sans fix
+    Heading,
+}
+
+/// Publication Zenoh (ROS 2): une clé par type d'échantillon
+#[derive(Clone, Deserialize, Serialize)]
+#[serde(default)]
+pub struct ZenohConfig {
+    pub enabled: bool,
+    pub mode: ZenohMode,
+    /// Points d'accès à joindre (ex: "tcp/192.168.1.10:7447")
+    pub connect: Vec<String>,
+    /// Points d'accès en écoute, vide: choix de Zenoh
+    pub listen: Vec<String>,
+    /// Découverte des autres sessions par multicast
+    pub multicast: bool,
+    /// Clé de publication, {vehicle} et {type} sont remplacés
+    pub key: String,
+    /// Types d'échantillons publiés
+    pub records: Vec<String>,
+    pub encoding: ZenohEncoding,
+}
+
+/// Mode de la session Zenoh
+#[derive(Clone, Copy, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ZenohMode {
+    /// Sans routeur: échanges directs avec les autres sessions
+    Peer,
+    /// Via un routeur (connect)
+    Client,
+}
+
+/// Encodage des messages Zenoh
+#[derive(Clone, Copy, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ZenohEncoding {
+    Json,
+    /// Messages ROS 2 (CDR), définitions dans ros/msg
+    Cdr,
+}
+
+/// Connexion au serveur SurrealDB
+#[derive(Clone, Deserialize, Serialize)]
+#[serde(default)]
+pub struct DatabaseConfig {
+    /// Adresse du serveur (ws://, wss://, http://, https://)
+    pub url: String,
+    pub namespace: String,
+    pub database: String,
+    /// Utilisateur root, vide: aucune authentification
+    pub username: String,
+    /// Mot de passe, sinon variable d'environnement DB_PASSWORD. Jamais affiché dans les journaux.
+    #[serde(skip_serializing)]
+    pub password: String,
+}
+
+/// Connexion à un broker MQTT. Disponibilité publiée sur <topic>/<véhicule>/availability
+/// ("online", "offline" en dernière volonté).
+#[derive(Clone, Deserialize, Serialize)]
+#[serde(default)]
+pub struct MqttConfig {
+    pub enabled: bool,
+    pub host: String,
+    pub port: u16,
+    /// Identifiant du client, vide: rc-<véhicule>
+    pub client_id: String,
+    /// Utilisateur, vide: connexion anonyme
+    pub username: String,
+    /// Mot de passe, sinon variable d'environnement MQTT_PASSWORD. Jamais affiché dans les journaux.
+    #[serde(skip_serializing)]
+    pub password: String,
+    /// Préfixe des sujets: <topic>/<véhicule>/...
+    pub topic: String,
+    /// Intervalle de maintien de la connexion (secondes)
+    pub keep_alive_s: u64,
+    /// Connexion chiffrée (TLS, port 8883 en général)
+    pub tls: bool,
+    /// Autorité de certification du broker (PEM), vide: certificats du système
+    pub ca_file: PathBuf,
+    pub homeassistant: HomeAssistantConfig,
+    pub telemetry: MqttTelemetryConfig,
+}
+
+/// Découverte Home Assistant (MQTT discovery) des entités du véhicule
+#[derive(Clone, Deserialize, Serialize)]
+#[serde(default)]
+pub struct HomeAssistantConfig {
+    pub enabled: bool,
+    /// Préfixe de découverte configuré dans Home Assistant
+    pub discovery_prefix: String,
+    /// Entités publiées: "battery", "position", "signal", "armed"
+    pub entities: Vec<String>,
+    /// Intervalle minimal entre deux états d'une même entité (secondes)
+    pub state_interval_s: u64,
+}
+
+/// Publication des échantillons en JSON sur <topic>/<véhicule>/<type>
+#[derive(Clone, Deserialize, Serialize)]
+#[serde(default)]
+pub struct MqttTelemetryConfig {
+    pub enabled: bool,
+    /// Types d'échantillons publiés
+    pub records: Vec<String>,
+    /// Qualité de service: 0 (au plus une fois), 1 (au moins une fois) ou 2 (exactement une fois)
+    pub qos: u8,
+    /// Fréquence maximale par type (Hz), 0: chaque échantillon
+    pub max_rate: f64,
+    /// Messages conservés pendant une coupure du broker, les plus anciens sont perdus
+    pub queue: usize,
+}
+
+/// Envoi vers Grafana Live (push WebSocket, protocole Influx)
+#[derive(Clone, Deserialize, Serialize)]
+#[serde(default)]
+pub struct GrafanaConfig {
+    pub enabled: bool,
+    /// Adresse de Grafana (ex: "http://grafana.local:3000")
+    pub url: String,
+    /// Jeton d'API (compte de service), sinon variable d'environnement GRAFANA_TOKEN.
+    /// Jamais affiché dans les journaux.
+    #[serde(skip_serializing)]
+    pub token: String,
+    /// Identifiant du flux: canaux stream/<prefix>/<véhicule>/<type>
+    pub prefix: String,
+    /// Types d'échantillons envoyés
+    pub records: Vec<String>,
+    /// Fréquence maximale par canal (Hz), 0: chaque échantillon
+    pub max_rate: f64,
+}
+
+/// Serveur gRPC (proto/telemetry.proto): flux de télémétrie, état et commandes
+#[derive(Clone, Deserialize, Serialize)]
+#[serde(default)]
+pub struct GrpcConfig {
+    pub enabled: bool,
+    /// Adresse d'écoute
+    pub listen: String,
+}
+
+/// Annonce mDNS (_rc-telemetrie._tcp.local) du serveur HTTP et de l'envoi UDP
+#[derive(Clone, Default, Deserialize, Serialize)]
+#[serde(default)]
+pub struct MdnsConfig {
+    pub enabled: bool,
+}
+
+/// Export CSV: un fichier par type d'échantillon et par exécution
+#[derive(Clone, Deserialize, Serialize)]
+#[serde(default)]
+pub struct CsvConfig {
+    pub enabled: bool,
+    /// Dossier des exports (un sous-dossier par exécution)
+    pub directory: PathBuf,
+    /// Types d'échantillons exportés
+    pub records: Vec<String>,
+    /// Intervalle d'écriture sur le disque (millisecondes), perte maximale en cas de coupure
+    pub flush_interval_ms: u64,
+    /// Taille maximale d'un fichier (Mo), un nouveau fichier est commencé au-delà
+    pub max_file_mb: u64,
+}
+
+/// Journal JSON lines de tous les échantillons, avec rotation et budget disque
+#[derive(Clone, Deserialize, Serialize)]
+#[serde(default)]
+pub struct JsonlConfig {
+    pub enabled: bool,
+    /// Dossier du journal, commun à toutes les exécutions
+    pub directory: PathBuf,
+    /// Types d'échantillons écrits
+    pub records: Vec<String>,
+    /// Intervalle d'écriture sur le disque (millisecondes), perte maximale en cas de coupure
+    pub flush_interval_ms: u64,
+    /// Taille maximale d'un fichier (Mo)
+    pub max_file_mb: u64,
+    /// Age maximal d'un fichier (secondes), 0: pas de limite
+    pub max_age_s: u64,
+    /// Compression gzip des fichiers terminés
+    pub gzip: bool,
+    /// Taille totale maximale du dossier (Mo), les plus anciens fichiers sont supprimés au-delà.
+    /// 0: pas de limite
+    pub max_total_mb: u64,
+}
+
+/// Journal blackbox (format Betaflight) de l'exécution: attitude, commandes, sorties des
+/// actionneurs, batterie et cap
+#[derive(Clone, Deserialize, Serialize)]
+#[serde(default)]
+pub struct BlackboxConfig {
+    pub enabled: bool,
+    /// Dossier des journaux, un fichier par exécution
+    pub directory: PathBuf,
+    /// Fréquence d'échantillonnage (Hz)
+    pub rate_hz: u32,
+    /// Une trame complète (I) toutes les `i_interval` trames, trames différentielles (P) entre
+    pub i_interval: u32,
+    /// Signal CAN du courant ("message.signal"), vide: courant non journalisé
+    pub current_signal: String,
+}
+
+/// Boucle de contrôle et arbitrage des sources de commandes
+#[derive(Clone, Deserialize, Serialize)]
+#[serde(default)]
+pub struct ControlConfig {
+    /// Délai de l'homme mort: sans commande d'aucune source, failsafe après ce délai
+    pub dead_timeout_ms: u64,
+    /// Direction appliquée au failsafe, dans [-1, 1] (0: centrée), le moteur passe au neutre
+    pub failsafe_steer: f64,
+    /// Rampe de la vitesse moteur (pleine échelle par seconde), 0: sans rampe. Le failsafe,
+    /// l'arrêt d'urgence et les limites de vitesse coupent le moteur sans rampe.
+    pub accel_per_s: f64,
+    pub decel_per_s: f64,
+    pub steering: SteeringConfig,
+    pub motor: MotorConfig,
+    /// Action sans commande d'aucune source au-delà du délai de l'homme mort
+    pub link_loss_policy: LinkLossPolicy,
+    /// Arrête le programme si les actionneurs ne peuvent pas être initialisés, sinon la
+    /// télémétrie continue sans contrôle
+    pub required: bool,
+    /// Transport des commandes du pilote (gRPC et MAVLink restent disponibles en plus)
+    pub source: ControlSource,
+}
+
+impl ControlConfig {
+    pub fn dead_timeout(&self) -> Duration {
+        Duration::from_millis(self.dead_timeout_ms)
+    }
+}
+
+impl Default for ControlConfig {
+    fn default() -> Self {
+        Self {
+            dead_timeout_ms: 500,
+            failsafe_steer: 0.0,
+            accel_per_s: 2.0,
+            decel_per_s: 4.0,
+            steering: SteeringConfig::default(),
+            motor: MotorConfig::default(),
+            link_loss_policy: LinkLossPolicy::default(),
+            required: false,
+            source: ControlSource::default(),
+        }
+    }
+}
+
+/// Calibration du servo de direction: impulsions (µs, 50 Hz) au centre et en butée
+#[derive(Clone, Deserialize, Serialize)]
+#[serde(default)]
+pub struct SteeringConfig {
+    /// Centre réel (trim), servo non centré mécaniquement
+    pub center_us: f64,
+    /// Butées gauche (direction -1) et droite (direction 1), asymétriques si besoin
+    pub left_us: f64,
+    pub right_us: f64,
+    /// Inverse le sens de la direction
+    pub reverse: bool,
+}
+
+impl Default for SteeringConfig {
+    fn default() -> Self {
+        Self {
+            center_us: 1520.0,
+            left_us: 1760.0,
+            right_us: 1280.0,
+            reverse: false,
+        }
+    }
+}
+
+impl SteeringConfig {
+    fn validate(&self) -> anyhow::Result<()> {
+        for (name, pulse) in [("center_us", self.center_us), ("left_us", self.left_us), ("right_us", self.right_us)] {
+            if !(500.0..=2500.0).contains(&pulse) {
+                return Err(anyhow::anyhow!("control.steering: {} {} hors de [500, 2500]", name, pulse));
+            }
+        }
+
+        if (self.left_us - self.center_us) * (self.right_us - self.center_us) >= 0.0 {
+            return Err(anyhow::anyhow!(
+                "control.steering: center_us {} doit être entre left_us {} et right_us {}",
+                self.center_us,
+                self.left_us,
+                self.right_us
+            ));
+        }
+
+        Ok(())
+    }
+}
+
+/// Calibration de l'ESC: impulsions (µs, 50 Hz) au neutre et à pleine vitesse dans chaque sens
+#[derive(Clone, Deserialize, Serialize)]
+#[serde(default)]
+pub struct MotorConfig {
+    pub neutral_us: f64,
+    /// Pleine vitesse en marche avant (vitesse 1) et en marche arrière (vitesse -1)
+    pub forward_us: f64,
+    pub reverse_us: f64,
+    /// Zone morte de l'ESC de part et d'autre du neutre, sautée par les vitesses non nulles
+    pub deadband_us: f64,
+    /// Passage en marche arrière après la marche avant: freinage (impulsion arrière) pendant
+    /// brake_ms puis neutre pendant neutral_ms. 0: marche arrière immédiate
+    pub brake_ms: u64,
+    pub neutral_ms: u64,
+}
+
+impl Default for MotorConfig {
+    fn default() -> Self {
+        Self {
+            neutral_us: 1400.0,
+            forward_us: 2000.0,
+            reverse_us: 800.0,
+            deadband_us: 0.0,
+            brake_ms: 0,
+            neutral_ms: 100,
+        }
+    }
+}
+
+impl MotorConfig {
+    fn validate(&self) -> anyhow::Result<()> {
+        for (name, pulse) in [("neutral_us", self.neutral_us), ("forward_us", self.forward_us), ("reverse_us", self.reverse_us)] {
+            if !(500.0..=2500.0).contains(&pulse) {
+                return Err(anyhow::anyhow!("control.motor: {} {} hors de [500, 2500]", name, pulse));
+            }
+        }
+
+        if (self.forward_us - self.neutral_us) * (self.reverse_us - self.neutral_us) >= 0.0 {
+            return Err(anyhow::anyhow!(
+                "control.motor: neutral_us {} doit être entre forward_us {} et reverse_us {}",
+                self.neutral_us,
+                self.forward_us,
+                self.reverse_us
+            ));
+        }
+
+        let range = (self.forward_us - self.neutral_us).abs().min((self.reverse_us - self.neutral_us).abs());
+        if !(0.0..range).contains(&self.deadband_us) {
+            return Err(anyhow::anyhow!(
+                "control.motor: deadband_us {} hors de [0, {})",
+                self.deadband_us,
+                range
+            ));
+        }
+
+        if self.brake_ms > 2000 || self.neutral_ms > 2000 {
+            return Err(anyhow::anyhow!("control.motor: brake_ms et neutral_ms limités à 2000"));
+        }
+
+        Ok(())
+    }
+}
+
+/// Transport des commandes du pilote
+#[derive(Clone, Copy, Debug, Default, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ControlSource {
+    /// Live de la table control:realtime
+    #[default]
+    Db,
+    /// Sujet <topic>/<véhicule>/control du broker MQTT (JSON: steer, speed, seq)
+    Mqtt,
+}
+
+/// Action à la perte de la liaison de contrôle (base de donnée, gRPC, ...)
+#[derive(Clone, Copy, Debug, Default, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum LinkLossPolicy {
+    /// Moteur au neutre, direction au failsafe, reprise dès la commande suivante
+    #[default]
+    Stop,
+    /// Moteur au neutre et véhicule désarmé jusqu'au réarmement
+    StopAndDisarm,
+    /// Retour au point de départ (nécessite une navigation autonome, non disponible)
+    ReturnToStart,
+}
+
+impl LinkLossPolicy {
+    pub fn name(self) -> &'static str {
+        match self {
+            LinkLossPolicy::Stop => "stop",
+            LinkLossPolicy::StopAndDisarm => "stop_and_disarm",
+            LinkLossPolicy::ReturnToStart => "return_to_start",
+        }
+    }
+}
+
+/// Vérifications avant armement, chacune désactivable (banc d'essai)
+#[derive(Clone, Deserialize, Serialize)]
+#[serde(default)]
+pub struct PreArmConfig {
+    pub enabled: bool,
+    /// Tension de la batterie au moins égale à `min_battery_v`
+    pub battery: bool,
+    pub min_battery_v: f32,
+    /// Aucune coupure du moteur active (retournement, batterie, température, zone, ...)
+    pub cutoff: bool,
+    /// IMU initialisée, roulis et tangage inférieurs à `max_tilt_deg`
+    pub imu: bool,
+    pub max_tilt_deg: f32,
+    /// Commande reçue d'une source depuis moins de `control_max_age_ms`
+    pub control: bool,
+    pub control_max_age_ms: u64,
+    /// Test de démarrage des actionneurs réussi
+    pub actuators: bool,
+    /// Aucun capteur en erreur ni capteur critique périmé
+    pub sensors: bool,
+    /// Position GPS disponible (fix)
+    pub gps: bool,
+}
+
+/// Désarmement automatique: durée maximale armé et inactivité
+#[derive(Clone, Deserialize, Serialize)]
+#[serde(default)]
+pub struct AutoDisarmConfig {
+    /// Durée maximale armé en continu (minutes), 0: aucune
+    pub max_armed_min: u64,
+    /// Durée sans commande au-delà du seuil avant le désarmement (minutes), 0: aucune
+    pub idle_min: u64,
+    /// Commande (vitesse ou direction, valeur absolue) considérée comme une activité
+    pub idle_threshold: f64,
+    /// Action supplémentaire à l'expiration de l'inactivité
+    pub idle_action: IdleAction,
+    /// Broche GPIO (BCM) du maintien de l'alimentation
+    pub latch_pin: u8,
+    /// Niveau de la broche qui relâche le maintien
+    pub latch_release_high: bool,
+}
+
+/// LED d'état sur une broche GPIO: fixe si tout est en ordre, clignotement lent en mode dégradé,
+/// rapide pendant le failsafe de la liaison de contrôle, éteinte à l'arrêt
+#[derive(Clone, Deserialize, Serialize)]
+#[serde(default)]
+pub struct StatusLedConfig {
+    pub enabled: bool,
+    /// Broche GPIO (BCM) de la LED
+    pub pin: u8,
+    /// Période du clignotement lent (ms)
+    pub slow_period_ms: u64,
+    /// Période du clignotement rapide (ms)
+    pub fast_period_ms: u64,
+}
+
+/// Action après le désarmement pour inactivité
+#[derive(Clone, Copy, Debug, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum IdleAction {
+    /// Désarmement uniquement
+    Disarm,
+    /// Arrêt propre du programme
+    Shutdown,
+    /// Arrêt propre puis relâchement du maintien de l'alimentation (GPIO)
+    PowerLatch,
+}
+
+/// Fusion de l'IMU et du magnétomètre (filtre de Madgwick): attitude et cap publiés dans
+/// nav:attitude à chaque mesure de l'IMU
+#[derive(Clone, Deserialize, Serialize)]
+#[serde(default)]
+pub struct FusionConfig {
+    pub enabled: bool,
+    /// Gain de la correction par l'accéléromètre et le magnétomètre (rad/s): plus élevé, la
+    /// dérive du gyroscope est corrigée plus vite mais les accélérations perturbent davantage
+    pub beta: f32,
+    /// Corrige le lacet avec le magnétomètre, sinon il suit le gyroscope seul
+    pub magnetometer: bool,
+    /// Age maximal de la mesure du magnétomètre utilisée (ms)
+    pub mag_stale_ms: u64,
+}
+
+impl Default for FusionConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            beta: 0.1,
+            magnetometer: true,
+            mag_stale_ms: 500,
+        }
+    }
+}
+
+/// Comparaison de la route GPS et du cap vrai du magnétomètre en mouvement: l'écart lissé est
+/// publié dans nav:heading_error, un écart persistant signale une calibration suspecte
+/// (status:health)
+#[derive(Clone, Deserialize, Serialize)]
+#[serde(default)]
+pub struct HeadingCheckConfig {
+    pub enabled: bool,
+    /// Vitesse GPS minimale (m/s): en dessous, la route n'est pas fiable
+    pub min_speed_ms: f32,
+    /// Constante de temps du lissage de l'écart (s)
+    pub smoothing_s: f32,
+    /// Ecart lissé au-delà duquel la calibration est suspecte (°)
+    pub max_error_deg: f32,
+    /// Durée en mouvement au-delà de l'écart avant le signalement (s)
+    pub persist_s: u64,
+    /// Age maximal de la mesure du magnétomètre comparée (ms)
+    pub mag_stale_ms: u64,
+}
+
+impl Default for HeadingCheckConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            min_speed_ms: 2.0,
+            smoothing_s: 5.0,
+            max_error_deg: 20.0,
+            persist_s: 10,
+            mag_stale_ms: 500,
+        }
+    }
+}
+
+/// Réglage de l'horloge système sur l'heure GPS (Raspberry Pi sans RTC, avant le réseau), une
+/// fois par exécution
+#[derive(Clone, Deserialize, Serialize)]
+#[serde(default)]
+pub struct GpsTimeConfig {
+    pub enabled: bool,
+    /// Ecart minimal entre l'heure GPS et l'horloge système avant le réglage (s)
+    pub threshold_s: f64,
+}
+
+impl Default for GpsTimeConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            threshold_s: 2.0,
+        }
+    }
+}
+
+/// Coupure du moteur lorsque la voiture est retournée ou trop inclinée (attitude de l'IMU)
+#[derive(Clone, Deserialize, Serialize)]
+#[serde(default)]
+pub struct RolloverConfig {
+    pub enabled: bool,
+    /// Roulis ou tangage (valeur absolue) au-delà duquel le moteur est coupé (degrés)
+    pub max_angle_deg: f32,
+    /// Durée minimale au-delà de l'angle avant la coupure (ms)
+    pub debounce_ms: u64,
+    /// Durée d'attitude normale avant la reprise du contrôle (ms)
+    pub reenable_ms: u64,
+    /// Coupure maintenue jusqu'au réarmement manuel du véhicule
+    pub latch: bool,
+    /// Age maximal des données de l'IMU, au-delà la règle est désactivée (ms)
+    pub stale_ms: u64,
+    /// Accélération (norme, g) au-delà de laquelle un choc coupe le moteur, 0: désactivé. Doit
+    /// rester sous la plage de l'accéléromètre (sensors.imu.accel_range_g).
+    pub crash_g: f32,
+}
+
+/// Arrêt devant un obstacle (capteur de distance sensors.range): la marche avant est bloquée
+/// tant qu'un obstacle est plus proche que `stop_mm`, la marche arrière reste disponible
+#[derive(Clone, Deserialize, Serialize)]
+#[serde(default)]
+pub struct ObstacleConfig {
+    pub enabled: bool,
+    /// Distance en deçà de laquelle la marche avant est bloquée (mm)
+    pub stop_mm: u16,
+    /// Distance au-delà de laquelle la marche avant est rétablie (mm, hystérésis)
+    pub clear_mm: u16,
+    /// Age maximal des mesures de distance, au-delà la règle est désactivée (ms)
+    pub stale_ms: u64,
+}
+
+/// Capacité consommée de la batterie, intégrée depuis le courant mesuré (sensors.analog.current)
+#[derive(Clone, Deserialize, Serialize)]
+#[serde(default)]
+pub struct CapacityConfig {
+    pub enabled: bool,
+    /// Capacité nominale de la batterie (mAh)
+    pub capacity_mah: f64,
+}
+
+impl Default for CapacityConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            capacity_mah: 5000.0,
+        }
+    }
+}
+
+/// Coupure du moteur sur tension basse de la batterie: la vitesse maximale est réduite par
+/// paliers, puis le moteur est coupé et le véhicule désarmé jusqu'au redémarrage
+#[derive(Clone, Deserialize, Serialize)]
+#[serde(default)]
+pub struct LowVoltageConfig {
+    pub enabled: bool,
+    /// Nombre d'éléments en série de la batterie
+    pub cells: u32,
+    /// Tension minimale par élément (V)
+    pub floor_cell_v: f32,
+    /// Remontée au-dessus du seuil nécessaire pour interrompre la période (V par élément)
+    pub hysteresis_cell_v: f32,
+    /// Durée sous la tension minimale, à faible charge, avant chaque palier (s)
+    pub sustain_s: f64,
+    /// Mesures consécutives sous la tension minimale, à faible charge, avant chaque palier: une
+    /// mesure isolée ne suffit pas, même espacée de plus de sustain_s de la précédente
+    pub sustain_samples: u32,
+    /// Vitesse maximale de chaque palier (0 à 1, décroissante), puis coupure
+    pub throttle_limits: Vec<f64>,
+    /// Vitesse appliquée maximale considérée comme une faible charge (sans mesure du courant)
+    pub low_load_throttle: f64,
+    /// Signal CAN du courant ("message.signal"), vide: charge estimée par la vitesse appliquée
+    pub current_signal: String,
+    /// Courant maximal considéré comme une faible charge (A)
+    pub low_load_a: f32,
+    /// Résistance interne de la batterie pour compenser la chute de tension (mΩ), 0: aucune
+    pub resistance_mohm: f32,
+}
+
+/// Réduction de la vitesse maximale selon les températures de l'ESC et du moteur (signaux CAN)
+#[derive(Clone, Deserialize, Serialize)]
+#[serde(default)]
+pub struct ThermalConfig {
+    pub enabled: bool,
+    /// Age maximal d'une température, au-delà sa limite est levée (s)
+    pub stale_s: f64,
+    pub sensors: Vec<ThermalSensor>,
+}
+
+/// Température surveillée: vitesse maximale de 100 % à `warning_c` jusqu'à `floor` à
+/// `critical_c`, moteur coupé au-delà jusqu'au retour sous `recovery_c`
+#[derive(Clone, Deserialize, Serialize)]
+#[serde(default)]
+pub struct ThermalSensor {
+    /// Nom de la cause dans les limites (thermal.<name>)
+    pub name: String,
+    /// Signal CAN de la température ("message.signal", °C)
+    pub signal: String,
+    pub warning_c: f32,
+    pub critical_c: f32,
+    pub recovery_c: f32,
+    /// Vitesse maximale à la température critique (0 à 1)
+    pub floor: f64,
+}
+
+/// Zones GPS: vitesse limitée dans les zones lentes, arrêt hors de la zone autorisée
+#[derive(Clone, Deserialize, Serialize)]
+#[serde(default)]
+pub struct GeofenceConfig {
+    pub enabled: bool,
+    /// Nom de la zone autorisée, dans les évènements
+    pub containment_name: String,
+    /// Zone autorisée ([latitude, longitude] des sommets), vide: aucune
+    pub containment: Vec<[f64; 2]>,
+    pub slow_zones: Vec<SlowZone>,
+    /// Distance au-delà de la limite d'une zone avant d'en sortir (m)
+    pub margin_m: f64,
+    /// Nombre minimal de satellites d'une position fiable
+    pub min_satellites: u8,
+    /// Age maximal de la position (s)
+    pub stale_s: f64,
+    /// Vitesse maximale sans position fiable (0 à 1)
+    pub degraded_max_speed: f64,
+}
+
+/// Zone lente: vitesse maximale à l'intérieur du polygone
+#[derive(Clone, Default, Deserialize, Serialize)]
+#[serde(default)]
+pub struct SlowZone {
+    pub name: String,
+    /// Vitesse maximale (0 à 1)
+    pub max_speed: f64,
+    /// [latitude, longitude] des sommets
+    pub polygon: Vec<[f64; 2]>,
+}
+
+/// Surveillance de la fraîcheur des capteurs: un capteur sans nouvel échantillon depuis
+/// `interval_ms` × `tolerance` est périmé
+#[derive(Clone, Deserialize, Serialize)]
+#[serde(default)]
+pub struct WatchdogConfig {
+    pub enabled: bool,
+    /// Multiple de l'intervalle attendu avant de déclarer un capteur périmé
+    pub tolerance: f64,
+    /// Vitesse maximale de la mesure "limit" (0 à 1)
+    pub limit_max_speed: f64,
+    /// Intervalle d'écriture de la santé de chaque composant (health:<composant>, ms)
+    pub health_interval_ms: u64,
+    /// Durée sans commande avant de déclarer la liaison de contrôle périmée (ms)
+    pub control_stale_ms: u64,
+    pub imu: WatchedSensor,
+    pub mag: WatchedSensor,
+    pub analog: WatchedSensor,
+    pub power: WatchedSensor,
+    pub baro: WatchedSensor,
+    pub encoder: WatchedSensor,
+    pub range: WatchedSensor,
+    pub gps: WatchedSensor,
+}
+
+/// Capteur surveillé
+#[derive(Clone, Deserialize, Serialize)]
+#[serde(default)]
+pub struct WatchedSensor {
+    /// Intervalle attendu entre deux échantillons (ms)
+    pub interval_ms: u64,
+    /// Mesure appliquée tant que le capteur est périmé (capteur critique si différente de none)
+    pub mitigation: Mitigation,
+}
+
+/// Mesure de sécurité appliquée à la perte d'un capteur critique
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Mitigation {
+    /// Evènement uniquement
+    None,
+    /// Vitesse maximale réduite (limit_max_speed)
+    Limit,
+    /// Moteur coupé et véhicule désarmé
+    Failsafe,
+}
+
+/// Gravité d'un évènement, du moins au plus grave
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Severity {
+    Info,
+    Warning,
+    Critical,
+}
+
+/// Alertes envoyées à des webhooks (JSON en POST) lors des évènements importants: arrêt
+/// d'urgence, batterie critique, perte de la télémétrie, ...
+#[derive(Clone, Deserialize, Serialize)]
+#[serde(default)]
+pub struct AlertsConfig {
+    pub enabled: bool,
+    /// Adresses des webhooks, chaque alerte est envoyée à toutes
+    pub webhooks: Vec<String>,
+    /// Types d'évènements envoyés (ex: "estop", "battery_critical"), vide: tous
+    pub events: Vec<String>,
+    /// Gravité minimale des évènements envoyés
+    pub min_severity: Severity,
+    /// Intervalle minimal entre deux alertes du même type (s)
+    pub min_interval_s: u64,
+    /// Nombre maximal de tentatives d'envoi d'une alerte
+    pub retries: u32,
+    /// Délai maximal d'une requête (s)
+    pub timeout_s: u64,
+    /// Tension de la batterie critique (V), 0: désactivé
+    pub battery_critical_v: f32,
+    /// Durée sans échantillon avant l'alerte de perte de la télémétrie (s), 0: désactivé
+    pub silence_s: u64,
+}
+
+/// Bus CAN (SocketCAN): messages décodés selon une table proche d'un fichier DBC
+#[derive(Clone, Deserialize, Serialize)]
+#[serde(default)]
+pub struct CanConfig {
+    pub enabled: bool,
+    /// Interface réseau (ex: "can0")
+    pub interface: String,
+    /// Intervalle de publication des compteurs du bus (secondes)
+    pub stats_interval_s: u64,
+    pub messages: Vec<CanMessage>,
+}
+
+/// Envoi des échantillons en datagrammes UDP (JSON), sans broker
+#[derive(Clone, Deserialize, Serialize)]
+#[serde(default)]
+pub struct UdpConfig {
+    pub enabled: bool,
+    /// Destinataire: adresse unicast, de diffusion (ex: 192.168.1.255) ou multicast
+    pub target: String,
+    /// Types d'échantillons envoyés
+    pub records: Vec<String>,
+    /// Fréquence maximale par type (Hz), absent ou 0: chaque échantillon
+    pub rates: BTreeMap<String, f64>,
+    /// Taille maximale d'un datagramme (octets), le contenu est retiré au-delà
+    pub mtu: usize,
+    /// Durée de vie des paquets multicast (sauts)
+    pub multicast_ttl: u32,
+    pub encoding: Encoding,
+    pub frame: UdpFrameConfig,
+}
+
+/// Trame binaire compacte des dernières valeurs, à fréquence fixe (indépendante de udp.enabled)
+#[derive(Clone, Deserialize, Serialize)]
+#[serde(default)]
+pub struct UdpFrameConfig {
+    /// Destinataire: adresse unicast, de diffusion ou multicast, vide: trames désactivées
+    pub target: String,
+    /// Fréquence d'envoi (Hz)
+    pub rate_hz: f64,
+    /// Durée de vie des paquets multicast (sauts)
+    pub multicast_ttl: u32,
+}
+
+/// Serveur HTTP local (WebSocket et API de télémétrie)
+#[derive(Clone, Deserialize, Serialize)]
+#[serde(default)]
+pub struct HttpConfig {
+    pub enabled: bool,
+    /// Adresse d'écoute
+    pub listen: String,
+    /// Echantillons conservés en mémoire par table pour /api/history
+    pub history_len: usize,
+    /// Origines autorisées (CORS) pour les tableaux de bord web, "*" pour toutes
+    pub cors_origins: Vec<String>,
+    /// Format des trames /ws par défaut, modifiable par chaque client
+    pub ws_format: Format,
+    /// Compteurs Prometheus sur /metrics
+    pub metrics: bool,
+}
+
+/// Sortie MAVLink pour les stations sol (QGroundControl, Mission Planner)
+#[derive(Clone, Deserialize, Serialize)]
+#[serde(default)]
+pub struct MavlinkConfig {
+    pub enabled: bool,
+    pub transport: MavlinkTransport,
+    /// Adresse d'écoute (serveur TCP, ou socket UDP)
+    pub listen: String,
+    /// Station sol destinataire en UDP (sinon: dernière adresse ayant envoyé un paquet)
+    pub target: Option<String>,
+    pub system_id: u8,
+    pub component_id: u8,
+    /// Fréquences d'envoi (Hz), 0 désactive le message
+    pub rates: MavlinkRates,
+}
+
+#[derive(Clone, Copy, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum MavlinkTransport {
+    Udp,
+    Tcp,
+}
+
+#[derive(Clone, Deserialize, Serialize)]
+#[serde(default)]
+pub struct MavlinkRates {
+    pub heartbeat: f64,
+    pub sys_status: f64,
+    pub gps_raw_int: f64,
+    pub global_position_int: f64,
+    pub attitude: f64,
+    pub vfr_hud: f64,
+    pub battery_status: f64,
+}
+
+/// Mesure de la régularité des boucles de capteurs
+#[derive(Clone, Deserialize, Serialize)]
+#[serde(default)]
+pub struct TimingConfig {
+    /// Nombre de mesures utilisées pour les percentiles
+    pub window: usize,
+    /// Intervalle attendu de la boucle de lecture des capteurs sans IMU réelle (ms), sinon
+    /// celui de sensors.imu.rate_hz
+    pub reader_interval_ms: u64,
+    /// Dépassement toléré au-delà de l'intervalle attendu (ms)
+    pub jitter_ms: u64,
+    /// Durée de dépassement continu avant un avertissement (secondes)
+    pub sustain_s: u64,
+}
+
+/// Taille des files entre les capteurs et l'écrivain de la base de donnée
+#[derive(Clone, Deserialize, Serialize)]
+#[serde(default)]
+pub struct WriterConfig {
+    /// Echantillons IMU en attente (les plus anciens sont perdus au-delà)
+    pub imu_queue: usize,
+    /// Echantillons du magnétomètre en attente
+    pub mag_queue: usize,
+    /// Echantillons analogiques en attente
+    pub analog_queue: usize,
+    /// Echantillons du moniteur de puissance en attente
+    pub power_queue: usize,
+    /// Echantillons du baromètre en attente
+    pub baro_queue: usize,
+    /// Echantillons de l'encodeur de roue en attente
+    pub encoder_queue: usize,
+    /// Mesures du capteur de distance en attente
+    pub range_queue: usize,
+    /// Positions GPS en attente, jamais perdues: file pleine, la boucle des capteurs attend
+    pub gps_queue: usize,
+    /// Evènements en attente (jamais perdus, les émetteurs attendent au-delà)
+    pub events_queue: usize,
+    /// Intervalle de publication de l'état des files (secondes)
+    pub stats_interval_s: u64,
+    /// Messages CAN décodés en attente
+    pub can_queue: usize,
+    /// Echantillons en attente pour chaque client des sorties locales (WebSocket, ...)
+    pub records_queue: usize,
+    pub breaker: BreakerConfig,
+    pub spool: SpoolConfig,
+}
+
+/// Coupe-circuit des écritures d'échantillons dans la base: ouvert, les échantillons ne sont plus
+/// écrits en base (toujours transmis aux sorties locales, et au tampon local s'il est activé)
+/// pour ne pas retarder les capteurs
+#[derive(Clone, Deserialize, Serialize)]
+#[serde(default)]
+pub struct BreakerConfig {
+    pub enabled: bool,
+    /// Nombre de dernières écritures observées
+    pub window: usize,
+    /// Ecritures observées avant de pouvoir ouvrir le coupe-circuit
+    pub min_writes: usize,
+    /// Latence moyenne maximale d'une écriture (ms), une tentative plus lente est un échec
+    pub max_latency_ms: u64,
+    /// Proportion maximale d'écritures en erreur, entre 0 et 1
+    pub max_error_rate: f64,
+    /// Intervalle entre deux écritures de test lorsque le coupe-circuit est ouvert (ms)
+    pub probe_interval_ms: u64,
+    /// Ecritures de test réussies consécutives avant de refermer le coupe-circuit
+    pub close_after: u32,
+}
+
+/// Tampon local des échantillons non écrits en base (écriture en échec ou coupe-circuit ouvert),
+/// relus dans l'ordre une fois la base de nouveau joignable. Conservé entre deux exécutions.
+#[derive(Clone, Deserialize, Serialize)]
+#[serde(default)]
+pub struct SpoolConfig {
+    pub enabled: bool,
+    pub directory: PathBuf,
+    /// Nombre maximal d'échantillons conservés, les plus anciens sont supprimés au-delà
+    pub max_records: u64,
+    /// Taille maximale du tampon (Mo)
+    pub max_mb: u64,
+}
+
+#[derive(Clone, Deserialize, Serialize)]
+#[serde(default)]
+pub struct RecordConfig {
+    /// Enregistre les données des capteurs (un fichier par exécution)
+    pub enabled: bool,
+    /// Dossier des enregistrements
+    pub directory: PathBuf,
+    /// Format des fichiers, le rejeu et `decode` lisent les deux
+    pub encoding: Encoding,
+    /// Facteur d'accélération du rejeu (--replay), remplacé par --replay-speed
+    pub replay_speed: f64,
+    /// Recommence le rejeu à la fin du fichier au lieu de s'arrêter, remplacé par --replay-loop
+    pub replay_loop: bool,
+}
+
+/// Journal du programme écrit dans des fichiers, avec rotation par taille, pour les systèmes
+/// sans journald
+#[derive(Clone, Deserialize, Serialize)]
+#[serde(default)]
+pub struct LogsConfig {
+    pub enabled: bool,
+    /// Dossier des journaux (rc-telemetrie.log, rc-telemetrie.log.1, ..., panic.log)
+    pub directory: PathBuf,
+    /// Taille maximale d'un fichier (Mo)
+    pub max_file_mb: u64,
+    /// Nombre de fichiers conservés, fichier en cours compris
+    pub max_files: usize,
+}
+
+#[derive(Clone, Deserialize, Serialize)]
+#[serde(default)]
+pub struct RunConfig {
+    /// Dossier d'état de l'exécution (fichier PID, marqueur d'arrêt non propre)
+    pub directory: PathBuf,
+}
+
+#[derive(Clone, Deserialize, Serialize)]
+#[serde(default)]
+pub struct I2cConfig {
+    /// Bus I2C déclarés (ouverts uniquement s'ils sont utilisés)
+    pub buses: Vec<I2cBusConfig>,
+    /// Arrête le programme si un bus utilisé par un capteur réel ne peut pas être ouvert au
+    /// démarrage. Sinon les capteurs I2C sont indisponibles et le bus est réessayé périodiquement.
+    pub required: bool,
+}
+
+#[derive(Clone, Deserialize, Serialize)]
+pub struct I2cBusConfig {
+    /// Numéro du bus (ex: 1 pour /dev/i2c-1)
+    pub bus: Option<u8>,
+    /// Chemin du bus, alternative au numéro (ex: /dev/i2c-3)
+    pub path: Option<String>,
+    /// Adresse d'un multiplexeur TCA9548 présent sur ce bus
+    pub mux: Option<u16>,
+}
+
+#[derive(Clone, Default, Deserialize, Serialize)]
+#[serde(default)]
+pub struct SensorsConfig {
+    pub imu: ImuConfig,
+    pub mag: MagConfig,
+    pub analog: AnalogConfig,
+    pub power: PowerConfig,
+    pub baro: BaroConfig,
+    pub encoder: EncoderConfig,
+    pub range: RangeConfig,
+    pub gps: GpsConfig,
+    pub modem: ModemConfig,
+    pub supervisor: SupervisorConfig,
+}
+
+/// Redémarrage d'un capteur dont la lecture panique ou ne produit plus rien
+#[derive(Clone, Deserialize, Serialize)]
+#[serde(default)]
+pub struct SupervisorConfig {
+    /// Nombre maximal de redémarrages d'un capteur, abandonné au-delà
+    pub max_restarts: u32,
+    /// Attente avant le premier redémarrage (ms), doublée à chaque redémarrage
+    pub backoff_ms: u64,
+    /// Durée sans échantillon (s) après laquelle un capteur qui en a déjà produit est
+    /// redémarré, 0: désactivé
+    pub stuck_s: u64,
+}
+
+/// Encodage des échantillons enregistrés ou envoyés (la base reste en JSON)
+#[derive(Clone, Copy, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Encoding {
+    Json,
+    /// Binaire compact, schéma dans proto/records.proto
+    Protobuf,
+}
+
+/// Format des messages des sorties locales (WebSocket, ...), mêmes champs dans les deux cas
+#[derive(Clone, Copy, Debug, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Format {
+    Json,
+    /// MessagePack, champs nommés comme en JSON
+    Msgpack,
+}
+
+impl Format {
+    pub fn encode<T: Serialize>(&self, value: &T) -> anyhow::Result<Vec<u8>> {
+        match self {
+            Format::Json => Ok(serde_json::to_vec(value)?),
+            Format::Msgpack => Ok(rmp_serde::to_vec_named(value)?),
+        }
+    }
+}
+
+/// Source des données d'un capteur
+#[derive(Clone, Copy, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SensorMode {
+    /// Capteur matériel (nécessite la feature real-sensors)
+    Real,
+    /// Capteur simulé, dérivé du scénario de simulation
+    Fake,
+    /// Aucune donnée
+    Disabled,
+}
+
+/// Centrale inertielle (MPU6050). Les réglages du capteur sont appliqués à son initialisation, une
+/// combinaison impossible arrête le programme au démarrage.
+#[derive(Clone, Deserialize, Serialize)]
+#[serde(default)]
+pub struct ImuConfig {
+    pub mode: SensorMode,
+    #[serde(flatten)]
+    pub i2c: I2cDeviceConfig,
+    /// Arrête le programme si le capteur n'est pas disponible au démarrage
+    pub required: bool,
+    /// Fréquence des mesures (Hz), cadence aussi la boucle des capteurs avec l'IMU réelle
+    pub rate_hz: u32,
+    /// Plage de l'accéléromètre (±g): 2, 4, 8 ou 16
+    pub accel_range_g: u8,
+    /// Plage du gyroscope (±°/s): 250, 500, 1000 ou 2000
+    pub gyro_range_dps: u16,
+    /// Bande passante du filtre passe-bas numérique (Hz): 256 (sans filtre), 188, 98, 42, 20,
+    /// 10 ou 5, au plus la moitié de rate_hz
+    pub dlpf_hz: u16,
+}
+
+impl Default for ImuConfig {
+    fn default() -> Self {
+        Self {
+            mode: SensorMode::default(),
+            i2c: I2cDeviceConfig::default(),
+            required: false,
+            rate_hz: 20,
+            accel_range_g: 2,
+            gyro_range_dps: 250,
+            dlpf_hz: 10,
+        }
+    }
+}
+
+impl ImuConfig {
+    /// Intervalle entre deux mesures
+    pub fn period(&self) -> Duration {
+        Duration::from_secs_f64(1.0 / self.rate_hz.max(1) as f64)
+    }
+}
+
+/// Magnétomètre (HMC5883L): cap calculé après la correction des perturbations du châssis
+#[derive(Clone, Deserialize, Serialize)]
+#[serde(default)]
+pub struct MagConfig {
+    pub mode: SensorMode,
+    #[serde(flatten)]
+    pub i2c: I2cDeviceConfig,
+    /// Arrête le programme si le capteur n'est pas disponible au démarrage
+    pub required: bool,
+    /// Déclinaison magnétique locale (°, positive vers l'est), ajoutée au cap magnétique sans
+    /// modèle ou sans position GPS
+    pub declination_deg: f32,
+    /// Coefficients du World Magnetic Model (WMM.COF): déclinaison calculée à la position GPS
+    pub wmm_file: Option<PathBuf>,
+    /// Correction appliquée à chaque mesure, remplacée par la dernière calibration enregistrée
+    /// dans la base (calibration:mag)
+    pub calibration: MagCalibration,
+    /// Durée maximale d'une calibration (s), terminée automatiquement au-delà
+    pub calibration_timeout_s: u64,
+    /// Nombre minimal de mesures pour ajuster une calibration
+    pub calibration_min_samples: usize,
+}
+
+impl Default for MagConfig {
+    fn default() -> Self {
+        Self {
+            mode: SensorMode::default(),
+            i2c: I2cDeviceConfig::default(),
+            required: false,
+            declination_deg: 2.44,
+            wmm_file: None,
+            calibration: MagCalibration::default(),
+            calibration_timeout_s: 120,
+            calibration_min_samples: 200,
+        }
+    }
+}
+
+/// Correction du magnétomètre: champ corrigé = soft_iron · (brut - hard_iron)
+#[derive(Clone, Copy, Debug, PartialEq, Deserialize, Serialize)]
+pub struct MagCalibration {
+    /// Décalage dû aux pièces aimantées (hard iron), unités brutes
+    pub hard_iron: [f32; 3],
+    /// Déformation due aux pièces ferromagnétiques (soft iron), par lignes
+    pub soft_iron: [[f32; 3]; 3],
+}
+
+impl Default for MagCalibration {
+    /// Aucune correction
+    fn default() -> Self {
+        Self {
+            hard_iron: [0.0; 3],
+            soft_iron: [[1.0, 0.0, 0.0], [0.0, 1.0, 0.0], [0.0, 0.0, 1.0]],
+        }
+    }
+}
+
+impl MagCalibration {
+    /// Champ corrigé d'une mesure brute
+    pub fn apply(&self, raw: (i16, i16, i16)) -> (f32, f32, f32) {
+        let centered = [
+            raw.0 as f32 - self.hard_iron[0],
+            raw.1 as f32 - self.hard_iron[1],
+            raw.2 as f32 - self.hard_iron[2],
+        ];
+        let [x, y, z] = self.soft_iron.map(|row| row.iter().zip(centered).map(|(a, b)| a * b).sum::<f32>());
+        (x, y, z)
+    }
+}
+
+/// Convertisseur analogique (ADS1115): tension de la batterie et prises d'équilibrage
+#[derive(Clone, Default, Deserialize, Serialize)]
+#[serde(default)]
+pub struct AnalogConfig {
+    pub mode: SensorMode,
+    #[serde(flatten)]
+    pub i2c: I2cDeviceConfig,
+    /// Arrête le programme si le capteur n'est pas disponible au démarrage
+    pub required: bool,
+    /// Prises d'équilibrage, de l'élément le plus bas au pack complet. Vide: tension de la
+    /// batterie seule (entrée différentielle AIN0-AIN1)
+    pub channels: Vec<AnalogChannel>,
+    /// Capteur de courant (shunt amplifié, capteur à effet Hall), None: courant non mesuré
+    pub current: Option<CurrentChannel>,
+}
+
+/// Capteur de courant relié à une entrée de l'ADC: courant = (tension - zero_v) / volts_per_a
+#[derive(Clone, Deserialize, Serialize)]
+pub struct CurrentChannel {
+    /// Entrée de l'ADC (0 à 3)
+    pub input: u8,
+    /// Tension du capteur sans courant (V)
+    pub zero_v: f32,
+    /// Sensibilité du capteur (V par A)
+    pub volts_per_a: f32,
+}
+
+/// Prise d'équilibrage reliée à une entrée de l'ADC, tension mesurée par rapport à la masse
+#[derive(Clone, Deserialize, Serialize)]
+pub struct AnalogChannel {
+    /// Entrée de l'ADC (0 à 3)
+    pub input: u8,
+    /// Rapport du pont diviseur (tension de la prise / tension mesurée)
+    pub ratio: f32,
+    /// Faux: entrée non câblée, jamais mesurée
+    #[serde(default = "enabled")]
+    pub enabled: bool,
+}
+
+fn enabled() -> bool {
+    true
+}
+
+impl AnalogConfig {
+    fn validate(&self) -> anyhow::Result<()> {
+        let mut inputs = Vec::new();
+        for channel in self.channels.iter() {
+            if channel.input > 3 || inputs.contains(&channel.input) {
+                return Err(anyhow::anyhow!("analog: entrée {} invalide ou déjà utilisée", channel.input));
+            }
+            inputs.push(channel.input);
+
+            if !(channel.ratio > 0.0 && channel.ratio.is_finite()) {
+                return Err(anyhow::anyhow!("analog: entrée {}: ratio {} invalide", channel.input, channel.ratio));
+            }
+        }
+
+        if let Some(current) = &self.current {
+            if current.input > 3 || inputs.contains(&current.input) {
+                return Err(anyhow::anyhow!("analog: courant: entrée {} invalide ou déjà utilisée", current.input));
+            }
+            if current.volts_per_a == 0.0 || !current.volts_per_a.is_finite() || !current.zero_v.is_finite() {
+                return Err(anyhow::anyhow!("analog: courant: zero_v ou volts_per_a invalide"));
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Moniteur de puissance (INA219): tension, courant et puissance de l'alimentation de l'ESC
+#[derive(Clone, Deserialize, Serialize)]
+#[serde(default)]
+pub struct PowerConfig {
+    pub mode: SensorMode,
+    #[serde(flatten)]
+    pub i2c: I2cDeviceConfig,
+    /// Adresse du capteur (0x40 à 0x4F selon A0/A1, 0x40 par défaut)
+    pub address: u16,
+    /// Arrête le programme si le capteur n'est pas disponible au démarrage
+    pub required: bool,
+    /// Résistance du shunt (ohm)
+    pub shunt_ohm: f64,
+    /// Courant maximal attendu (A), fixe la résolution du courant mesuré
+    pub max_current_a: f64,
+}
+
+impl Default for PowerConfig {
+    fn default() -> Self {
+        Self {
+            mode: SensorMode::Disabled,
+            i2c: I2cDeviceConfig::default(),
+            address: 0x40,
+            required: false,
+            shunt_ohm: 0.1,
+            max_current_a: 3.2,
+        }
+    }
+}
+
+impl PowerConfig {
+    /// Tension maximale aux bornes du shunt mesurée par le INA219 (V)
+    pub const MAX_SHUNT_V: f64 = 0.32;
+
+    fn validate(&self) -> anyhow::Result<()> {
+        if !(0x40..=0x4F).contains(&self.address) {
+            return Err(anyhow::anyhow!("power: adresse {:#04x} hors de [0x40, 0x4f]", self.address));
+        }
+
+        if !(self.shunt_ohm > 0.0 && self.shunt_ohm.is_finite()) {
+            return Err(anyhow::anyhow!("power: shunt_ohm {} invalide", self.shunt_ohm));
+        }
+
+        if !(self.max_current_a > 0.0 && self.max_current_a.is_finite()) {
+            return Err(anyhow::anyhow!("power: max_current_a {} invalide", self.max_current_a));
+        }
+
+        if self.shunt_ohm * self.max_current_a > Self::MAX_SHUNT_V + 1e-9 {
+            return Err(anyhow::anyhow!(
+                "power: {} A dans {} ohm dépassent la plage du shunt ({} V)",
+                self.max_current_a,
+                self.shunt_ohm,
+                Self::MAX_SHUNT_V
+            ));
+        }
+
+        Ok(())
+    }
+}
+
+/// Baromètre (BMP280 ou BME280): pression, température ambiante, humidité (BME280) et altitude
+/// relative au point de démarrage
+#[derive(Clone, Deserialize, Serialize)]
+#[serde(default)]
+pub struct BaroConfig {
+    pub mode: SensorMode,
+    #[serde(flatten)]
+    pub i2c: I2cDeviceConfig,
+    /// Adresse du capteur (0x76 ou 0x77 selon SDO)
+    pub address: u16,
+    /// Arrête le programme si le capteur n'est pas disponible au démarrage
+    pub required: bool,
+    /// Echantillons moyennés pour la pression de référence (altitude 0) au démarrage
+    pub zero_samples: u32,
+}
+
+impl Default for BaroConfig {
+    fn default() -> Self {
+        Self {
+            mode: SensorMode::Disabled,
+            i2c: I2cDeviceConfig::default(),
+            address: 0x76,
+            required: false,
+            zero_samples: 10,
+        }
+    }
+}
+
+impl BaroConfig {
+    fn validate(&self) -> anyhow::Result<()> {
+        if !matches!(self.address, 0x76 | 0x77) {
+            return Err(anyhow::anyhow!("baro: adresse {:#04x} différente de 0x76 et 0x77", self.address));
+        }
+
+        if self.zero_samples == 0 {
+            return Err(anyhow::anyhow!("baro: zero_samples doit être supérieur à 0"));
+        }
+
+        Ok(())
+    }
+}
+
+/// Encodeur de roue (capteur à effet Hall sur une broche GPIO): vitesse et distance parcourue
+#[derive(Clone, Deserialize, Serialize)]
+#[serde(default)]
+pub struct EncoderConfig {
+    pub mode: SensorMode,
+    /// Broche GPIO (numérotation BCM)
+    pub pin: u8,
+    /// Arrête le programme si le capteur n'est pas disponible au démarrage
+    pub required: bool,
+    /// Impulsions par tour de roue (aimants sur l'arbre, rapport de transmission compris)
+    pub pulses_per_rev: u32,
+    /// Circonférence de la roue (m)
+    pub wheel_circumference_m: f64,
+    /// Intervalle du calcul de la vitesse (ms)
+    pub interval_ms: u64,
+}
+
+impl Default for EncoderConfig {
+    fn default() -> Self {
+        Self {
+            mode: SensorMode::Disabled,
+            pin: 17,
+            required: false,
+            pulses_per_rev: 1,
+            wheel_circumference_m: 0.33,
+            interval_ms: 100,
+        }
+    }
+}
+
+impl EncoderConfig {
+    /// Distance parcourue par impulsion (m)
+    pub fn meters_per_pulse(&self) -> f64 {
+        self.wheel_circumference_m / self.pulses_per_rev as f64
+    }
+
+    fn validate(&self) -> anyhow::Result<()> {
+        if self.pin > 27 {
+            return Err(anyhow::anyhow!("encoder: pin {} invalide (0 à 27)", self.pin));
+        }
+
+        if self.pulses_per_rev == 0 {
+            return Err(anyhow::anyhow!("encoder: pulses_per_rev doit être supérieur à 0"));
+        }
+
+        if !(self.wheel_circumference_m > 0.0 && self.wheel_circumference_m.is_finite()) {
+            return Err(anyhow::anyhow!(
+                "encoder: wheel_circumference_m {} invalide",
+                self.wheel_circumference_m
+            ));
+        }
+
+        if self.interval_ms == 0 {
+            return Err(anyhow::anyhow!("encoder: interval_ms doit être supérieur à 0"));
+        }
+
+        Ok(())
+    }
+}
+
+/// Capteur de distance à temps de vol (VL53L1X) orienté vers l'avant
+#[derive(Clone, Deserialize, Serialize)]
+#[serde(default)]
+pub struct RangeConfig {
+    pub mode: SensorMode,
+    #[serde(flatten)]
+    pub i2c: I2cDeviceConfig,
+    /// Adresse du capteur
+    pub address: u16,
+    /// Arrête le programme si le capteur n'est pas disponible au démarrage
+    pub required: bool,
+    /// Délai sans mesure avant un échantillon "timeout" (ms)
+    pub timeout_ms: u64,
+}
+
+impl Default for RangeConfig {
+    fn default() -> Self {
+        Self {
+            mode: SensorMode::Disabled,
+            i2c: I2cDeviceConfig::default(),
+            address: 0x29,
+            required: false,
+            timeout_ms: 500,
+        }
+    }
+}
+
+impl RangeConfig {
+    fn validate(&self) -> anyhow::Result<()> {
+        if !(0x08..=0x77).contains(&self.address) {
+            return Err(anyhow::anyhow!("range: adresse {:#04x} hors de [0x08, 0x77]", self.address));
+        }
+
+        if self.timeout_ms == 0 {
+            return Err(anyhow::anyhow!("range: timeout_ms doit être supérieur à 0"));
+        }
+
+        Ok(())
+    }
+}
+
+#[derive(Clone, Default, Deserialize, Serialize)]
+#[serde(default)]
+pub struct GpsConfig {
+    pub mode: SensorMode,
+    /// Port série du GPS, sinon variable d'environnement GPS_DEVICE, par défaut /dev/ttyS0
+    pub device: Option<PathBuf>,
+    /// Vitesse du port série (bauds), sinon variable d'environnement GPS_BAUD, par défaut 38400
+    pub baud: Option<u32>,
+    /// Arrête le programme si le GPS n'est pas disponible au démarrage
+    pub required: bool,
+}
+
+impl GpsConfig {
+    /// Port série et vitesse du GPS
+    pub fn port(&self) -> anyhow::Result<(PathBuf, u32)> {
+        let device = match &self.device {
+            Some(device) => device.clone(),
+            None => std::env::var_os("GPS_DEVICE")
+                .map(PathBuf::from)
+                .unwrap_or_else(|| PathBuf::from("/dev/ttyS0")),
+        };
+
+        let baud = match (self.baud, std::env::var("GPS_BAUD")) {
+            (Some(baud), _) => baud,
+            (None, Ok(baud)) => baud
+                .parse()
+                .map_err(|e| anyhow::anyhow!("gps: GPS_BAUD {} invalide: {}", baud, e))?,
+            (None, Err(_)) => 38400,
+        };
+        if baud == 0 {
+            return Err(anyhow::anyhow!("gps: vitesse du port série nulle"));
+        }
+
+        Ok((device, baud))
+    }
+}
+
+impl Default for SupervisorConfig {
+    fn default() -> Self {
+        Self {
+            max_restarts: 5,
+            backoff_ms: 1000,
+            stuck_s: 10,
+        }
+    }
+}
+
+#[derive(Clone, Default, Deserialize, Serialize)]
+#[serde(default)]
+pub struct ModemConfig {
+    pub mode: SensorMode,
+    /// IMEI du modem à utiliser (propriété EquipmentIdentifier), None: premier modem
+    pub imei: Option<String>,
+    /// Port du modem à utiliser (ex: "cdc-wdm0", "ttyUSB2"), None: premier modem
+    pub port: Option<String>,
+}
+
+#[derive(Clone, Deserialize, Serialize)]
+#[serde(default)]
+pub struct I2cDeviceConfig {
+    /// Bus I2C sur lequel se trouve le capteur
+    pub bus: u8,
+    /// Canal du multiplexeur (si le capteur est derrière un TCA9548)
+    pub channel: Option<u8>,
+}
+
+impl Default for I2cConfig {
+    fn default() -> Self {
+        Self {
+            buses: vec![I2cBusConfig {
+                bus: Some(1),
+                path: None,
+                mux: None,
+            }],
+            required: false,
+        }
+    }
+}
+
+impl Default for RecordConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            directory: PathBuf::from("/var/lib/rc-telemetrie/records"),
+            encoding: Encoding::Json,
+            replay_speed: 1.0,
+            replay_loop: false,
+        }
+    }
+}
+
+impl Default for LogsConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            directory: PathBuf::from("/var/log/rc-telemetrie"),
+            max_file_mb: 10,
+            max_files: 5,
+        }
+    }
+}
+
+impl Default for RunConfig {
+    fn default() -> Self {
+        Self {
+            directory: PathBuf::from("/var/lib/rc-telemetrie/run"),
+        }
+    }
+}
+
+impl Default for SensorMode {
+    /// Capteurs réels s'ils sont compilés, simulés sinon
+    fn default() -> Self {
+        if cfg!(feature = "real-sensors") {
+            SensorMode::Real
+        } else {
+            SensorMode::Fake
+        }
+    }
+}
+
+impl Default for WriterConfig {
+    fn default() -> Self {
+        Self {
+            imu_queue: 32,
+            mag_queue: 32,
+            analog_queue: 8,
+            power_queue: 8,
+            baro_queue: 8,
+            encoder_queue: 8,
+            range_queue: 8,
+            gps_queue: 64,
+            events_queue: 64,
+            stats_interval_s: 5,
+            can_queue: 64,
+            records_queue: 256,
+            breaker: BreakerConfig::default(),
+            spool: SpoolConfig::default(),
+        }
+    }
+}
+
+impl Default for SpoolConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            directory: PathBuf::from("/var/lib/rc-telemetrie/spool"),
+            max_records: 1_000_000,
+            max_mb: 64,
+        }
+    }
+}
+
+impl Default for BreakerConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            window: 20,
+            min_writes: 5,
+            max_latency_ms: 200,
+            max_error_rate: 0.5,
+            probe_interval_ms: 5000,
+            close_after: 3,
+        }
+    }
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            dry_run: false,
+            vehicle: "voiturerc".to_string(),
+            database: DatabaseConfig::default(),
+            i2c: I2cConfig::default(),
+            sensors: SensorsConfig::default(),
+            record: RecordConfig::default(),
+            run: RunConfig::default(),
+            simulation: Scenario::default(),
+            writer: WriterConfig::default(),
+            timing: TimingConfig::default(),
+            mavlink: MavlinkConfig::default(),
+            http: HttpConfig::default(),
+            udp: UdpConfig::default(),
+            can: CanConfig::default(),
+            csv: CsvConfig::default(),
+            jsonl: JsonlConfig::default(),
+            mdns: MdnsConfig::default(),
+            grpc: GrpcConfig::default(),
+            grafana: GrafanaConfig::default(),
+            mqtt: MqttConfig::default(),
+            zenoh: ZenohConfig::default(),
+            sport: SportConfig::default(),
+            blackbox: BlackboxConfig::default(),
+            alerts: AlertsConfig::default(),
+            fusion: FusionConfig::default(),
+            heading_check: HeadingCheckConfig::default(),
+            gps_time: GpsTimeConfig::default(),
+            rollover: RolloverConfig::default(),
+            obstacle: ObstacleConfig::default(),
+            low_voltage: LowVoltageConfig::default(),
+            capacity: CapacityConfig::default(),
+            thermal: ThermalConfig::default(),
+            geofence: GeofenceConfig::default(),
+            watchdog: WatchdogConfig::default(),
+            control: ControlConfig::default(),
+            auto_disarm: AutoDisarmConfig::default(),
+            prearm: PreArmConfig::default(),
+            status_led: StatusLedConfig::default(),
+            logs: LogsConfig::default(),
+        }
+    }
+}
+
+impl Default for SportConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            device: PathBuf::from("/dev/ttyAMA1"),
+            external_inverter: true,
+            current_signal: String::new(),
+            sensors: vec![
+                // Identifiants des capteurs FrSky FAS et GPS
+                SportSensor {
+                    id: 2,
+                    values: vec![SportValue::Vfas, SportValue::Current],
+                },
+                SportSensor {
+                    id: 3,
+                    values: vec![
+                        SportValue::Latitude,
+                        SportValue::Longitude,
+                        SportValue::Speed,
+                        SportValue::Heading,
+                    ],
+                },
+            ],
+        }
+    }
+}
+
+impl Default for ZenohConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            mode: ZenohMode::Peer,
+            connect: Vec::new(),
+            listen: Vec::new(),
+            multicast: true,
+            key: "rc/{vehicle}/{type}".to_string(),
+            records: ["imu", "mag", "analog", "gps", "modem"]
+                .iter()
+                .map(|kind| kind.to_string())
+                .collect(),
+            encoding: ZenohEncoding::Cdr,
+        }
+    }
+}
+
+// Valeurs fournies à la compilation (DB_URL, DB_USERNAME, DB_PASSWORD) conservées par défaut
+impl Default for DatabaseConfig {
+    fn default() -> Self {
+        Self {
+            url: option_env!("DB_URL")
+                .map(|url| format!("wss://{}", url))
+                .unwrap_or("ws://localhost:8000".to_string()),
+            namespace: "voiturerc".to_string(),
+            database: "voiturerc".to_string(),
+            username: option_env!("DB_USERNAME").unwrap_or("root").to_string(),
+            password: option_env!("DB_PASSWORD").unwrap_or_default().to_string(),
+        }
+    }
+}
+
+impl Default for MqttConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            host: "localhost".to_string(),
+            port: 1883,
+            client_id: String::new(),
+            username: String::new(),
+            password: String::new(),
+            topic: "rc".to_string(),
+            keep_alive_s: 30,
+            tls: false,
+            ca_file: PathBuf::new(),
+            homeassistant: HomeAssistantConfig::default(),
+            telemetry: MqttTelemetryConfig::default(),
+        }
+    }
+}
+
+impl Default for HomeAssistantConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            discovery_prefix: "homeassistant".to_string(),
+            entities: ["battery", "position", "signal", "armed"]
+                .iter()
+                .map(|entity| entity.to_string())
+                .collect(),
+            state_interval_s: 5,
+        }
+    }
+}
+
+impl Default for MqttTelemetryConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            records: ["imu", "mag", "analog", "gps", "modem"]
+                .iter()
+                .map(|kind| kind.to_string())
+                .collect(),
+            qos: 0,
+            max_rate: 10.0,
+            queue: 1000,
+        }
+    }
+}
+
+impl Default for GrafanaConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            url: "http://localhost:3000".to_string(),
+            token: String::new(),
+            prefix: "rc".to_string(),
+            records: ["imu", "mag", "analog", "gps", "modem"]
+                .iter()
+                .map(|kind| kind.to_string())
+                .collect(),
+            max_rate: 10.0,
+        }
+    }
+}
+
+impl Default for JsonlConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            directory: PathBuf::from("/var/lib/rc-telemetrie/jsonl"),
+            records: ["imu", "mag", "analog", "gps", "modem"]
+                .iter()
+                .map(|kind| kind.to_string())
+                .collect(),
+            flush_interval_ms: 2000,
+            max_file_mb: 64,
+            max_age_s: 3600,
+            gzip: false,
+            max_total_mb: 1024,
+        }
+    }
+}
+
+impl Default for BlackboxConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            directory: PathBuf::from("/var/lib/rc-telemetrie/blackbox"),
+            rate_hz: 50,
+            i_interval: 32,
+            current_signal: String::new(),
+        }
+    }
+}
+
+impl Default for ObstacleConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            stop_mm: 300,
+            clear_mm: 400,
+            stale_ms: 500,
+        }
+    }
+}
+
+impl Default for RolloverConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            max_angle_deg: 70.0,
+            debounce_ms: 200,
+            reenable_ms: 2000,
+            latch: false,
+            stale_ms: 500,
+            crash_g: 0.0,
+        }
+    }
+}
+
+impl Default for LowVoltageConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            cells: 2,
+            floor_cell_v: 3.3,
+            hysteresis_cell_v: 0.05,
+            sustain_s: 10.0,
+            sustain_samples: 3,
+            throttle_limits: vec![0.6, 0.3],
+            low_load_throttle: 0.15,
+            current_signal: String::new(),
+            low_load_a: 3.0,
+            resistance_mohm: 0.0,
+        }
+    }
+}
+
+impl Default for ThermalConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            stale_s: 2.0,
+            sensors: Vec::new(),
+        }
+    }
+}
+
+impl Default for ThermalSensor {
+    fn default() -> Self {
+        Self {
+            name: String::new(),
+            signal: String::new(),
+            warning_c: 80.0,
+            critical_c: 100.0,
+            recovery_c: 85.0,
+            floor: 0.3,
+        }
+    }
+}
+
+impl Default for GeofenceConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            containment_name: "terrain".to_string(),
+            containment: Vec::new(),
+            slow_zones: Vec::new(),
+            margin_m: 3.0,
+            min_satellites: 6,
+            stale_s: 3.0,
+            degraded_max_speed: 0.3,
+        }
+    }
+}
+
+impl Default for PreArmConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            battery: true,
+            min_battery_v: 6.8,
+            cutoff: true,
+            imu: true,
+            max_tilt_deg: 25.0,
+            control: true,
+            control_max_age_ms: 1000,
+            actuators: true,
+            sensors: true,
+            gps: false,
+        }
+    }
+}
+
+impl Default for AutoDisarmConfig {
+    fn default() -> Self {
+        Self {
+            max_armed_min: 0,
+            idle_min: 0,
+            idle_threshold: 0.05,
+            idle_action: IdleAction::Disarm,
+            latch_pin: 24,
+            latch_release_high: false,
+        }
+    }
+}
+
+impl Default for StatusLedConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            pin: 23,
+            slow_period_ms: 1000,
+            fast_period_ms: 200,
+        }
+    }
+}
+
+impl Default for WatchdogConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            tolerance: 3.0,
+            limit_max_speed: 0.3,
+            health_interval_ms: 2000,
+            control_stale_ms: 1000,
+            imu: WatchedSensor::default(),
+            mag: WatchedSensor::default(),
+            analog: WatchedSensor::default(),
+            power: WatchedSensor::default(),
+            baro: WatchedSensor::default(),
+            encoder: WatchedSensor {
+                interval_ms: 100,
+                mitigation: Mitigation::None,
+            },
+            range: WatchedSensor {
+                interval_ms: 100,
+                mitigation: Mitigation::None,
+            },
+            gps: WatchedSensor {
+                interval_ms: 1000,
+                mitigation: Mitigation::None,
+            },
+        }
+    }
+}
+
+impl Default for WatchedSensor {
+    fn default() -> Self {
+        Self {
+            interval_ms: 50,
+            mitigation: Mitigation::None,
+        }
+    }
+}
+
+impl Default for AlertsConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            webhooks: Vec::new(),
+            events: Vec::new(),
+            min_severity: Severity::Warning,
+            min_interval_s: 300,
+            retries: 5,
+            timeout_s: 10,
+            battery_critical_v: 6.4,
+            silence_s: 10,
+        }
+    }
+}
+
+impl Default for CsvConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            directory: PathBuf::from("/var/lib/rc-telemetrie/csv"),
+            records: ["imu", "mag", "analog", "gps", "modem"]
+                .iter()
+                .map(|kind| kind.to_string())
+                .collect(),
+            flush_interval_ms: 2000,
+            max_file_mb: 64,
+        }
+    }
+}
+
+impl Default for CanConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            interface: "can0".to_string(),
+            stats_interval_s: 10,
+            messages: Vec::new(),
+        }
+    }
+}
+
+impl Default for UdpConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            target: "255.255.255.255:14600".to_string(),
+            records: ["imu", "mag", "analog", "gps", "modem"]
+                .iter()
+                .map(|kind| kind.to_string())
+                .collect(),
+            rates: [("imu", 10.0), ("mag", 5.0), ("analog", 1.0), ("gps", 5.0), ("modem", 1.0)]
+                .iter()
+                .map(|(kind, rate)| (kind.to_string(), *rate))
+                .collect(),
+            mtu: 1200,
+            multicast_ttl: 1,
+            encoding: Encoding::Json,
+            frame: UdpFrameConfig::default(),
+        }
+    }
+}
+
+impl Default for UdpFrameConfig {
+    fn default() -> Self {
+        Self {
+            target: String::new(),
+            rate_hz: 10.0,
+            multicast_ttl: 1,
+        }
+    }
+}
+
+impl Default for HttpConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            listen: "0.0.0.0:8080".to_string(),
+            history_len: 1200,
+            cors_origins: Vec::new(),
+            ws_format: Format::Json,
+            metrics: true,
+        }
+    }
+}
+
+impl Default for GrpcConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            listen: "0.0.0.0:50051".to_string(),
+        }
+    }
+}
+
+impl Default for MavlinkConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            transport: MavlinkTransport::Udp,
+            listen: "0.0.0.0:14555".to_string(),
+            target: None,
+            system_id: 1,
+            component_id: 1,
+            rates: MavlinkRates::default(),
+        }
+    }
+}
+
+impl Default for MavlinkRates {
+    fn default() -> Self {
+        Self {
+            heartbeat: 1.0,
+            sys_status: 1.0,
+            gps_raw_int: 5.0,
+            global_position_int: 5.0,
+            attitude: 10.0,
+            vfr_hud: 4.0,
+            battery_status: 1.0,
+        }
+    }
+}
+
+impl Default for TimingConfig {
+    fn default() -> Self {
+        Self {
+            window: 256,
+            reader_interval_ms: 50,
+            jitter_ms: 20,
+            sustain_s: 5,
+        }
+    }
+}
+
+impl Default for I2cDeviceConfig {
+    fn default() -> Self {
+        Self { bus: 1, channel: None }
+    }
+}
+
+impl I2cBusConfig {
+    /// Récupére le numéro du bus, depuis le numéro ou depuis le chemin
+    pub fn number(&self) -> anyhow::Result<u8> {
+        if let Some(bus) = self.bus {
+            return Ok(bus);
+        }
+
+        let path = self
+            .path
+            .as_deref()
+            .ok_or(anyhow::anyhow!("Bus I2C sans numéro ni chemin"))?;
+
+        path.strip_prefix("/dev/i2c-")
+            .and_then(|n| n.parse::<u8>().ok())
+            .ok_or(anyhow::anyhow!("Chemin de bus I2C invalide: {}", path))
+    }
+}
+
+impl BreakerConfig {
+    /// Vérifie la fenêtre et les seuils
+    fn validate(&self) -> anyhow::Result<()> {
+        if !(1..=10_000).contains(&self.window) {
+            return Err(anyhow::anyhow!("writer.breaker: window {} hors de [1, 10000]", self.window));
+        }
+
+        if !(1..=self.window).contains(&self.min_writes) {
+            return Err(anyhow::anyhow!(
+                "writer.breaker: min_writes {} hors de [1, {}]",
+                self.min_writes,
+                self.window
+            ));
+        }
+
+        if !(self.max_error_rate > 0.0 && self.max_error_rate <= 1.0) {
+            return Err(anyhow::anyhow!(
+                "writer.breaker: max_error_rate {} hors de ]0, 1]",
+                self.max_error_rate
+            ));
+        }
+
+        if self.max_latency_ms == 0 || self.probe_interval_ms == 0 || self.close_after == 0 {
+            return Err(anyhow::anyhow!(
+                "writer.breaker: max_latency_ms, probe_interval_ms et close_after doivent être positifs"
+            ));
+        }
+
+        Ok(())
+    }
+}
+
+impl DatabaseConfig {
+    /// Vérifie l'adresse et l'espace de noms
+    pub fn validate(&self) -> anyhow::Result<()> {
+        const SCHEMES: [&str; 5] = ["ws://", "wss://", "http://", "https://", "mem://"];
+        if !SCHEMES.iter().any(|scheme| self.url.starts_with(scheme)) {
+            return Err(anyhow::anyhow!(
+                "database: adresse {:?} invalide (ws://, wss://, http:// ou https://)",
+                self.url
+            ));
+        }
+
+        if self.namespace.is_empty() || self.database.is_empty() {
+            return Err(anyhow::anyhow!("database: namespace et database ne peuvent pas être vides"));
+        }
+
+        Ok(())
+    }
+}
+
+impl MavlinkConfig {
+    /// Vérifie les adresses et les fréquences
+    fn validate(&self) -> anyhow::Result<()> {
+        self.listen
+            .parse::<std::net::SocketAddr>()
+            .map_err(|e| anyhow::anyhow!("mavlink: adresse d'écoute {} invalide: {}", self.listen, e))?;
+
+        if let Some(target) = self.target.as_ref() {
+            target
+                .parse::<std::net::SocketAddr>()
+                .map_err(|e| anyhow::anyhow!("mavlink: destinataire {} invalide: {}", target, e))?;
+        }
+
+        let rates = &self.rates;
+        for rate in [
+            rates.heartbeat,
+            rates.sys_status,
+            rates.gps_raw_int,
+            rates.global_position_int,
+            rates.attitude,
+            rates.vfr_hud,
+            rates.battery_status,
+        ] {
+            if !(0.0..=50.0).contains(&rate) {
+                return Err(anyhow::anyhow!("mavlink: fréquence {} Hz hors de [0, 50]", rate));
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl Config {
+    /// Charge la configuration, utilise les valeurs par défaut si le fichier est absent
+    pub fn load(path: &Path) -> anyhow::Result<Self> {
+        if !path.exists() {
+            tracing::info!(
+                target: "config",
+                "Fichier {} absent, utilisation des valeurs par défaut.",
+                path.display()
+            );
+            return Ok(Self::default());
+        }
+
+        let content = std::fs::read_to_string(path)?;
+        let config: Config = toml::from_str(&content)
+            .map_err(|e| anyhow::anyhow!("Fichier {} invalide: {}", path.display(), e))?;
+
+        Ok(config)
+    }
+
+    /// Compare avec une configuration rechargée.
+    /// Retourne la configuration à appliquer (seuls les paramètres modifiables à chaud changent)
+    /// et la liste des changements.
+    pub fn reload(&self, new: &Config) -> anyhow::Result<(Config, ConfigDiff)> {
+        let before = flatten(self)?;
+        let after = flatten(new)?;
+
+        let mut diff = ConfigDiff::default();
+        let keys: BTreeSet<&String> = before.keys().chain(after.keys()).collect();
+        for key in keys {
+            let old = before.get(key).map(String::as_str).unwrap_or("(absent)");
+            let value = after.get(key).map(String::as_str).unwrap_or("(absent)");
+            if old == value {
+                continue;
+            }
+
+            let change = format!("{}: {} -> {}", key, old, value);
+            if RELOADABLE.iter().any(|prefix| key.starts_with(prefix)) {
+                diff.applied.push(change);
+            } else {
+                diff.restart.push(change);
+            }
+        }
+
+        let mut active = self.clone();
+        active.apply_runtime(new);
+        Ok((active, diff))
+    }
+
+    /// Reprend les paramètres modifiables à chaud d'une autre configuration
+    fn apply_runtime(&mut self, new: &Config) {
+        self.timing = new.timing.clone();
+        self.writer.stats_interval_s = new.writer.stats_interval_s;
+    }
+
+    /// Charge la configuration lors d'un rechargement: le fichier doit exister
+    pub fn load_existing(path: &Path) -> anyhow::Result<Self> {
+        if !path.exists() {
+            return Err(anyhow::anyhow!("Fichier {} absent", path.display()));
+        }
+
+        Self::load(path)
+    }
+
+    /// Vérifie la cohérence de la configuration, retourne un résumé
+    pub fn validate(&self) -> anyhow::Result<String> {
+        let mut buses = Vec::new();
+        for declared in self.i2c.buses.iter() {
+            buses.push((declared.number()?, declared.mux.is_some()));
+        }
+
+        let modes = [
+            ("imu", self.sensors.imu.mode),
+            ("mag", self.sensors.mag.mode),
+            ("analog", self.sensors.analog.mode),
+            ("power", self.sensors.power.mode),
+            ("baro", self.sensors.baro.mode),
+            ("encoder", self.sensors.encoder.mode),
+            ("range", self.sensors.range.mode),
+            ("gps", self.sensors.gps.mode),
+            ("modem", self.sensors.modem.mode),
+        ];
+
+        // Le modem passe par le D-Bus, il ne dépend pas de la feature real-sensors
+        for (name, mode) in modes.iter().filter(|(name, _)| *name != "modem") {
+            if *mode == SensorMode::Real && !cfg!(feature = "real-sensors") {
+                return Err(anyhow::anyhow!(
+                    "{}: mode \"real\" indisponible, programme compilé sans la feature real-sensors",
+                    name
+                ));
+            }
+        }
+
+        // Le temps accéléré fausserait l'horodatage des mesures réelles
+        let time_scale = self.simulation.time_scale;
+        if !(time_scale.is_finite() && time_scale > 0.0) {
+            return Err(anyhow::anyhow!("simulation.time_scale: {} doit être positif", time_scale));
+        }
+        let track_speed = self.simulation.track_speed_kmh;
+        if !(track_speed.is_finite() && track_speed > 0.0) {
+            return Err(anyhow::anyhow!("simulation.track_speed_kmh: {} doit être positif", track_speed));
+        }
+        if time_scale != 1.0 {
+            if let Some((name, _)) = modes.iter().find(|(_, mode)| *mode == SensorMode::Real) {
+                return Err(anyhow::anyhow!(
+                    "simulation.time_scale: {} impossible avec le capteur réel {}",
+                    time_scale,
+                    name
+                ));
+            }
+        }
+
+        if self.sensors.gps.mode == SensorMode::Real {
+            self.sensors.gps.port()?;
+        }
+
+        let replay_speed = self.record.replay_speed;
+        if !(replay_speed.is_finite() && replay_speed > 0.0) {
+            return Err(anyhow::anyhow!("record.replay_speed: {} doit être positif", replay_speed));
+        }
+
+        if self.sensors.supervisor.backoff_ms == 0 {
+            return Err(anyhow::anyhow!("sensors.supervisor: backoff_ms doit être positif"));
+        }
+
+        let devices = [
+            ("imu", self.sensors.imu.mode, &self.sensors.imu.i2c),
+            ("mag", self.sensors.mag.mode, &self.sensors.mag.i2c),
+            ("analog", self.sensors.analog.mode, &self.sensors.analog.i2c),
+            ("power", self.sensors.power.mode, &self.sensors.power.i2c),
+            ("baro", self.sensors.baro.mode, &self.sensors.baro.i2c),
+            ("range", self.sensors.range.mode, &self.sensors.range.i2c),
+        ];
+
+        // Seuls les capteurs réels utilisent le bus I2C
+        for (name, mode, device) in devices {
+            if mode != SensorMode::Real {
+                continue;
+            }
+
+            let Some((_, mux)) = buses.iter().find(|(bus, _)| *bus == device.bus) else {
+                return Err(anyhow::anyhow!("{}: bus {} non déclaré", name, device.bus));
+            };
+
+            if let Some(channel) = device.channel {
+                if !mux || channel > 7 {
+                    return Err(anyhow::anyhow!(
+                        "{}: canal {} invalide sur le bus {}",
+                        name,
+                        channel,
+                        device.bus
+                    ));
+                }
+            }
+        }
+
+        if self.logs.enabled {
+            if !(1..=1024).contains(&self.logs.max_file_mb) {
+                return Err(anyhow::anyhow!("logs: max_file_mb {} hors de [1, 1024]", self.logs.max_file_mb));
+            }
+
+            if !(1..=100).contains(&self.logs.max_files) {
+                return Err(anyhow::anyhow!("logs: max_files {} hors de [1, 100]", self.logs.max_files));
+            }
+        }
+
+        self.sensors.analog.validate()?;
+        self.sensors.power.validate()?;
+        self.sensors.baro.validate()?;
+        self.sensors.encoder.validate()?;
+        self.sensors.range.validate()?;
+        self.database.validate()?;
+
+        if self.writer.breaker.enabled {
+            self.writer.breaker.validate()?;
+        }
+
+        let spool = &self.writer.spool;
+        if spool.enabled {
+            if !(100..=100_000_000).contains(&spool.max_records) {
+                return Err(anyhow::anyhow!(
+                    "writer.spool: max_records {} hors de [100, 100000000]",
+                    spool.max_records
+                ));
+            }
+
+            if !(1..=4096).contains(&spool.max_mb) {
+                return Err(anyhow::anyhow!("writer.spool: max_mb {} hors de [1, 4096]", spool.max_mb));
+            }
+        }
+
+        if self.mavlink.enabled {
+            self.mavlink.validate()?;
+        }
+
+        if self.http.enabled {
+            self.http
+                .listen
+                .parse::<std::net::SocketAddr>()
+                .map_err(|e| anyhow::anyhow!("http: adresse d'écoute {} invalide: {}", self.http.listen, e))?;
+
+            if !(1..=100_000).contains(&self.http.history_len) {
+                return Err(anyhow::anyhow!(
+                    "http: history_len {} hors de [1, 100000]",
+                    self.http.history_len
+                ));
+            }
+        }
+
+        if self.grpc.enabled {
+            self.grpc
+                .listen
+                .parse::<std::net::SocketAddr>()
+                .map_err(|e| anyhow::anyhow!("grpc: adresse d'écoute {} invalide: {}", self.grpc.listen, e))?;
+        }
+
+        if self.grafana.enabled {
+            let url = self.grafana.url.as_str();
+            if !["http://", "https://", "ws://", "wss://"].iter().any(|scheme| url.starts_with(scheme)) {
+                return Err(anyhow::anyhow!("grafana: adresse {} invalide (http(s):// ou ws(s)://)", url));
+            }
+
+            let prefix = self.grafana.prefix.as_str();
+            if prefix.is_empty() || !prefix.chars().all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-') {
+                return Err(anyhow::anyhow!("grafana: préfixe {:?} invalide (lettres, chiffres, _ et -)", prefix));
+            }
+
+            // Caractères autorisés dans un canal Grafana Live
+            let vehicle = self.vehicle.as_str();
+            if vehicle.is_empty() || !vehicle.chars().all(|c| c.is_ascii_alphanumeric() || "_-.".contains(c)) {
+                return Err(anyhow::anyhow!("grafana: nom du véhicule {:?} invalide (lettres, chiffres, _, - et .)", vehicle));
+            }
+
+            if self.grafana.max_rate.is_nan() || self.grafana.max_rate < 0.0 {
+                return Err(anyhow::anyhow!("grafana: fréquence {} Hz invalide", self.grafana.max_rate));
+            }
+        }
+
+        if self.mqtt.enabled {
+            if self.mqtt.host.trim().is_empty() || self.mqtt.port == 0 {
+                return Err(anyhow::anyhow!("mqtt: broker {}:{} invalide", self.mqtt.host, self.mqtt.port));
+            }
+
+            if !(5..=3600).contains(&self.mqtt.keep_alive_s) {
+                return Err(anyhow::anyhow!("mqtt: keep_alive_s {} hors de [5, 3600]", self.mqtt.keep_alive_s));
+            }
+
+            // Sujets sans caractères génériques ni niveau vide
+            for (name, value) in [("topic", self.mqtt.topic.as_str()), ("vehicle", self.vehicle.as_str())] {
+                if value.is_empty() || value.contains(['+', '#', '/']) {
+                    return Err(anyhow::anyhow!("mqtt: {} {:?} invalide dans un sujet", name, value));
+                }
+            }
+
+            let homeassistant = &self.mqtt.homeassistant;
+            if homeassistant.enabled {
+                if homeassistant.discovery_prefix.is_empty() || homeassistant.discovery_prefix.contains(['+', '#']) {
+                    return Err(anyhow::anyhow!(
+                        "mqtt.homeassistant: préfixe {:?} invalide",
+                        homeassistant.discovery_prefix
+                    ));
+                }
+
+                if let Some(entity) = homeassistant
+                    .entities
+                    .iter()
+                    .find(|entity| !["battery", "position", "signal", "armed"].contains(&entity.as_str()))
+                {
+                    return Err(anyhow::anyhow!("mqtt.homeassistant: entité inconnue {}", entity));
+                }
+            }
+
+            if self.mqtt.tls && !self.mqtt.ca_file.as_os_str().is_empty() && !self.mqtt.ca_file.is_file() {
+                return Err(anyhow::anyhow!("mqtt: autorité {} introuvable", self.mqtt.ca_file.display()));
+            }
+
+            let telemetry = &self.mqtt.telemetry;
+            if telemetry.enabled {
+                if telemetry.qos > 2 {
+                    return Err(anyhow::anyhow!("mqtt.telemetry: qos {} hors de [0, 2]", telemetry.qos));
+                }
+
+                if telemetry.max_rate.is_nan() || telemetry.max_rate < 0.0 {
+                    return Err(anyhow::anyhow!("mqtt.telemetry: fréquence {} Hz invalide", telemetry.max_rate));
+                }
+
+                if !(1..=100_000).contains(&telemetry.queue) {
+                    return Err(anyhow::anyhow!("mqtt.telemetry: queue {} hors de [1, 100000]", telemetry.queue));
+                }
+            }
+        }
+
+        if self.zenoh.enabled {
+            if !self.zenoh.key.contains("{type}") {
+                return Err(anyhow::anyhow!("zenoh: la clé {} doit contenir {{type}}", self.zenoh.key));
+            }
+
+            if self.zenoh.mode == ZenohMode::Client && self.zenoh.connect.is_empty() {
+                return Err(anyhow::anyhow!("zenoh: mode client sans routeur (connect)"));
+            }
+        }
+
+        if self.sport.enabled {
+            let mut ids = BTreeSet::new();
+            for sensor in self.sport.sensors.iter() {
+                if sensor.id > 27 {
+                    return Err(anyhow::anyhow!("sport: identifiant de capteur {} hors de [0, 27]", sensor.id));
+                }
+                if !ids.insert(sensor.id) {
+                    return Err(anyhow::anyhow!("sport: capteur {} déclaré plusieurs fois", sensor.id));
+                }
+                if sensor.values.is_empty() {
+                    return Err(anyhow::anyhow!("sport: capteur {} sans valeur", sensor.id));
+                }
+            }
+
+            if !signal_reference(&self.sport.current_signal) {
+                return Err(anyhow::anyhow!(
+                    "sport: current_signal {:?} invalide (\"message.signal\")",
+                    self.sport.current_signal
+                ));
+            }
+        }
+
+        if self.blackbox.enabled {
+            if !(1..=1000).contains(&self.blackbox.rate_hz) {
+                return Err(anyhow::anyhow!(
+                    "blackbox: rate_hz {} hors de [1, 1000]",
+                    self.blackbox.rate_hz
+                ));
+            }
+            if self.blackbox.i_interval == 0 {
+                return Err(anyhow::anyhow!("blackbox: i_interval doit être supérieur à 0"));
+            }
+            if !signal_reference(&self.blackbox.current_signal) {
+                return Err(anyhow::anyhow!(
+                    "blackbox: current_signal {:?} invalide (\"message.signal\")",
+                    self.blackbox.current_signal
+                ));
+            }
+        }
+
+        if !(50..=10_000).contains(&self.control.dead_timeout_ms) {
+            return Err(anyhow::anyhow!(
+                "control: dead_timeout_ms {} hors de [50, 10000]",
+                self.control.dead_timeout_ms
+            ));
+        }
+
+        if !(-1.0..=1.0).contains(&self.control.failsafe_steer) {
+            return Err(anyhow::anyhow!(
+                "control: failsafe_steer {} hors de [-1, 1]",
+                self.control.failsafe_steer
+            ));
+        }
+
+        for (name, rate) in [("accel_per_s", self.control.accel_per_s), ("decel_per_s", self.control.decel_per_s)] {
+            if !(rate.is_finite() && rate >= 0.0) {
+                return Err(anyhow::anyhow!("control: {} {} doit être positif ou nul", name, rate));
+            }
+        }
+
+        self.control.steering.validate()?;
+        self.control.motor.validate()?;
+
+        if self.control.link_loss_policy == LinkLossPolicy::ReturnToStart {
+            return Err(anyhow::anyhow!(
+                "control: link_loss_policy return_to_start non disponible (aucune navigation autonome)"
+            ));
+        }
+
+        if self.control.source == ControlSource::Mqtt && !self.mqtt.enabled {
+            return Err(anyhow::anyhow!("control: source mqtt sans broker (mqtt.enabled)"));
+        }
+
+        if self.prearm.enabled && !(0.0..=90.0).contains(&self.prearm.max_tilt_deg) {
+            return Err(anyhow::anyhow!("prearm: max_tilt_deg {} hors de [0, 90]", self.prearm.max_tilt_deg));
+        }
+
+        if !(0.0..1.0).contains(&self.auto_disarm.idle_threshold) {
+            return Err(anyhow::anyhow!(
+                "auto_disarm: idle_threshold {} hors de [0, 1[",
+                self.auto_disarm.idle_threshold
+            ));
+        }
+        if self.auto_disarm.idle_action == IdleAction::PowerLatch && self.auto_disarm.latch_pin > 27 {
+            return Err(anyhow::anyhow!("auto_disarm: latch_pin {} invalide (0 à 27)", self.auto_disarm.latch_pin));
+        }
+
+        if self.auto_disarm.idle_action == IdleAction::PowerLatch
+            && self.sensors.encoder.mode == SensorMode::Real
+            && self.sensors.encoder.pin == self.auto_disarm.latch_pin
+        {
+            return Err(anyhow::anyhow!(
+                "encoder: pin {} déjà utilisée par auto_disarm.latch_pin",
+                self.sensors.encoder.pin
+            ));
+        }
+
+        if self.status_led.enabled {
+            if self.status_led.pin > 27 {
+                return Err(anyhow::anyhow!("status_led: pin {} invalide (0 à 27)", self.status_led.pin));
+            }
+            // Clignotement à la résolution de la tâche de la LED (50 ms par état)
+            for (name, period) in [
+                ("slow_period_ms", self.status_led.slow_period_ms),
+                ("fast_period_ms", self.status_led.fast_period_ms),
+            ] {
+                if period < 100 {
+                    return Err(anyhow::anyhow!("status_led: {} doit être au moins 100", name));
+                }
+            }
+            if self.auto_disarm.idle_action == IdleAction::PowerLatch && self.status_led.pin == self.auto_disarm.latch_pin {
+                return Err(anyhow::anyhow!(
+                    "status_led: pin {} déjà utilisée par auto_disarm.latch_pin",
+                    self.status_led.pin
+                ));
+            }
+        }
+
+        if self.sensors.mag.calibration_min_samples < 20 {
+            return Err(anyhow::anyhow!("sensors.mag: calibration_min_samples doit être au moins 20"));
+        }
+        if self.sensors.mag.calibration_timeout_s == 0 {
+            return Err(anyhow::anyhow!("sensors.mag: calibration_timeout_s doit être supérieur à 0"));
+        }
+
+        if self.fusion.enabled {
+            if self.sensors.imu.mode == SensorMode::Disabled {
+                return Err(anyhow::anyhow!("fusion: IMU (sensors.imu) désactivée"));
+            }
+            if !(self.fusion.beta > 0.0 && self.fusion.beta.is_finite()) {
+                return Err(anyhow::anyhow!("fusion: beta {} invalide", self.fusion.beta));
+            }
+        }
+
+        if self.heading_check.enabled {
+            let check = &self.heading_check;
+            if self.sensors.mag.mode == SensorMode::Disabled || self.sensors.gps.mode == SensorMode::Disabled {
+                return Err(anyhow::anyhow!("heading_check: magnétomètre ou GPS désactivé"));
+            }
+            if !(check.min_speed_ms > 0.0 && check.smoothing_s > 0.0) {
+                return Err(anyhow::anyhow!("heading_check: min_speed_ms et smoothing_s doivent être positifs"));
+            }
+            if !(check.max_error_deg > 0.0 && check.max_error_deg < 180.0) {
+                return Err(anyhow::anyhow!(
+                    "heading_check: max_error_deg {} hors de ]0, 180[",
+                    check.max_error_deg
+                ));
+            }
+        }
+
+        if self.gps_time.enabled {
+            if self.sensors.gps.mode == SensorMode::Disabled {
+                return Err(anyhow::anyhow!("gps_time: GPS désactivé"));
+            }
+            if !(self.gps_time.threshold_s > 0.0 && self.gps_time.threshold_s.is_finite()) {
+                return Err(anyhow::anyhow!("gps_time: threshold_s {} invalide", self.gps_time.threshold_s));
+            }
+        }
+
+        if self.rollover.enabled {
+            if !(self.rollover.max_angle_deg > 0.0 && self.rollover.max_angle_deg < 180.0) {
+                return Err(anyhow::anyhow!(
+                    "rollover: max_angle_deg {} hors de ]0, 180[",
+                    self.rollover.max_angle_deg
+                ));
+            }
+            if self.rollover.stale_ms == 0 {
+                return Err(anyhow::anyhow!("rollover: stale_ms doit être supérieur à 0"));
+            }
+            let crash_g = self.rollover.crash_g;
+            if !(crash_g == 0.0 || (crash_g > 1.0 && crash_g < self.sensors.imu.accel_range_g as f32)) {
+                return Err(anyhow::anyhow!(
+                    "rollover: crash_g {} hors de ]1, {}[ (sensors.imu.accel_range_g)",
+                    crash_g,
+                    self.sensors.imu.accel_range_g
+                ));
+            }
+        }
+
+        if self.obstacle.enabled {
+            if self.sensors.range.mode == SensorMode::Disabled {
+                return Err(anyhow::anyhow!("obstacle: capteur de distance (sensors.range) désactivé"));
+            }
+            if self.obstacle.stop_mm == 0 {
+                return Err(anyhow::anyhow!("obstacle: stop_mm doit être supérieur à 0"));
+            }
+            if self.obstacle.clear_mm < self.obstacle.stop_mm {
+                return Err(anyhow::anyhow!(
+                    "obstacle: clear_mm {} inférieur à stop_mm {}",
+                    self.obstacle.clear_mm,
+                    self.obstacle.stop_mm
+                ));
+            }
+            if self.obstacle.stale_ms == 0 {
+                return Err(anyhow::anyhow!("obstacle: stale_ms doit être supérieur à 0"));
+            }
+        }
+
+        if self.capacity.enabled && !(self.capacity.capacity_mah > 0.0 && self.capacity.capacity_mah.is_finite()) {
+            return Err(anyhow::anyhow!("capacity: capacity_mah {} invalide", self.capacity.capacity_mah));
+        }
+
+        if self.low_voltage.enabled {
+            let low_voltage = &self.low_voltage;
+            if low_voltage.cells == 0 {
+                return Err(anyhow::anyhow!("low_voltage: cells doit être supérieur à 0"));
+            }
+            if !(low_voltage.floor_cell_v > 0.0 && low_voltage.floor_cell_v < 5.0) {
+                return Err(anyhow::anyhow!(
+                    "low_voltage: floor_cell_v {} hors de ]0, 5[",
+                    low_voltage.floor_cell_v
+                ));
+            }
+            if !(0.0..1.0).contains(&low_voltage.hysteresis_cell_v) {
+                return Err(anyhow::anyhow!(
+                    "low_voltage: hysteresis_cell_v {} hors de [0, 1[",
+                    low_voltage.hysteresis_cell_v
+                ));
+            }
+            if !(low_voltage.sustain_s > 0.0 && low_voltage.sustain_s.is_finite()) {
+                return Err(anyhow::anyhow!("low_voltage: sustain_s {} invalide", low_voltage.sustain_s));
+            }
+            if low_voltage.sustain_samples == 0 {
+                return Err(anyhow::anyhow!("low_voltage: sustain_samples doit être supérieur à 0"));
+            }
+            let mut previous = 1.0;
+            for limit in low_voltage.throttle_limits.iter() {
+                if !(0.0..=previous).contains(limit) {
+                    return Err(anyhow::anyhow!(
+                        "low_voltage: throttle_limits {:?} invalide (entre 0 et 1, décroissant)",
+                        low_voltage.throttle_limits
+                    ));
+                }
+                previous = *limit;
+            }
+            if !(0.0..=1.0).contains(&low_voltage.low_load_throttle) {
+                return Err(anyhow::anyhow!(
+                    "low_voltage: low_load_throttle {} hors de [0, 1]",
+                    low_voltage.low_load_throttle
+                ));
+            }
+            if !signal_reference(&low_voltage.current_signal) {
+                return Err(anyhow::anyhow!(
+                    "low_voltage: current_signal {:?} invalide (\"message.signal\")",
+                    low_voltage.current_signal
+                ));
+            }
+            if !(low_voltage.low_load_a >= 0.0 && low_voltage.resistance_mohm >= 0.0) {
+                return Err(anyhow::anyhow!("low_voltage: low_load_a et resistance_mohm doivent être positifs"));
+            }
+        }
+
+        if self.thermal.enabled {
+            if !(self.thermal.stale_s > 0.0 && self.thermal.stale_s.is_finite()) {
+                return Err(anyhow::anyhow!("thermal: stale_s {} invalide", self.thermal.stale_s));
+            }
+            let mut names = BTreeSet::new();
+            for sensor in self.thermal.sensors.iter() {
+                if sensor.name.is_empty() || !names.insert(sensor.name.as_str()) {
+                    return Err(anyhow::anyhow!("thermal: nom {:?} vide ou en double", sensor.name));
+                }
+                if sensor.signal.is_empty() || !signal_reference(&sensor.signal) {
+                    return Err(anyhow::anyhow!(
+                        "thermal.{}: signal {:?} invalide (\"message.signal\")",
+                        sensor.name,
+                        sensor.signal
+                    ));
+                }
+                if !(sensor.warning_c < sensor.critical_c && sensor.recovery_c < sensor.critical_c) {
+                    return Err(anyhow::anyhow!(
+                        "thermal.{}: warning_c et recovery_c doivent être inférieurs à critical_c",
+                        sensor.name
+                    ));
+                }
+                if !(0.0..=1.0).contains(&sensor.floor) {
+                    return Err(anyhow::anyhow!("thermal.{}: floor {} hors de [0, 1]", sensor.name, sensor.floor));
+                }
+            }
+        }
+
+        if self.geofence.enabled {
+            let geofence = &self.geofence;
+            let polygon = |name: &str, points: &[[f64; 2]]| -> anyhow::Result<()> {
+                if points.len() < 3 {
+                    return Err(anyhow::anyhow!("geofence.{}: au moins 3 sommets", name));
+                }
+                if let Some(point) = points
+                    .iter()
+                    .find(|[latitude, longitude]| latitude.abs() > 90.0 || longitude.abs() > 180.0)
+                {
+                    return Err(anyhow::anyhow!("geofence.{}: sommet {:?} invalide", name, point));
+                }
+                Ok(())
+            };
+            if !geofence.containment.is_empty() {
+                polygon(&geofence.containment_name, &geofence.containment)?;
+            }
+            for zone in geofence.slow_zones.iter() {
+                polygon(&zone.name, &zone.polygon)?;
+                if !(0.0..=1.0).contains(&zone.max_speed) {
+                    return Err(anyhow::anyhow!("geofence.{}: max_speed {} hors de [0, 1]", zone.name, zone.max_speed));
+                }
+            }
+            if !(geofence.margin_m >= 0.0 && geofence.margin_m.is_finite()) {
+                return Err(anyhow::anyhow!("geofence: margin_m {} invalide", geofence.margin_m));
+            }
+            if !(geofence.stale_s > 0.0 && geofence.stale_s.is_finite()) {
+                return Err(anyhow::anyhow!("geofence: stale_s {} invalide", geofence.stale_s));
+            }
+            if !(0.0..=1.0).contains(&geofence.degraded_max_speed) {
+                return Err(anyhow::anyhow!(
+                    "geofence: degraded_max_speed {} hors de [0, 1]",
+                    geofence.degraded_max_speed
+                ));
+            }
+        }
+
+        if self.watchdog.enabled {
+            let watchdog = &self.watchdog;
+            if !(watchdog.tolerance >= 1.0 && watchdog.tolerance.is_finite()) {
+                return Err(anyhow::anyhow!("watchdog: tolerance {} invalide (au moins 1)", watchdog.tolerance));
+            }
+            if !(0.0..=1.0).contains(&watchdog.limit_max_speed) {
+                return Err(anyhow::anyhow!(
+                    "watchdog: limit_max_speed {} hors de [0, 1]",
+                    watchdog.limit_max_speed
+                ));
+            }
+            if watchdog.health_interval_ms < 100 {
+                return Err(anyhow::anyhow!(
+                    "watchdog: health_interval_ms {} trop court (au moins 100)",
+                    watchdog.health_interval_ms
+                ));
+            }
+            if watchdog.control_stale_ms == 0 {
+                return Err(anyhow::anyhow!("watchdog: control_stale_ms doit être positif"));
+            }
+            for (name, sensor) in [
+                ("imu", &watchdog.imu),
+                ("mag", &watchdog.mag),
+                ("analog", &watchdog.analog),
+                ("power", &watchdog.power),
+                ("baro", &watchdog.baro),
+                ("encoder", &watchdog.encoder),
+                ("range", &watchdog.range),
+                ("gps", &watchdog.gps),
+            ] {
+                if sensor.interval_ms == 0 {
+                    return Err(anyhow::anyhow!("watchdog.{}: interval_ms doit être positif", name));
+                }
+            }
+        }
+
+        if self.alerts.enabled {
+            if let Some(url) = self
+                .alerts
+                .webhooks
+                .iter()
+                .find(|url| !url.starts_with("http://") && !url.starts_with("https://"))
+            {
+                return Err(anyhow::anyhow!("alerts: adresse {} invalide (http:// ou https://)", url));
+            }
+            if self.alerts.retries == 0 {
+                return Err(anyhow::anyhow!("alerts: retries doit être supérieur à 0"));
+            }
+            if self.alerts.timeout_s == 0 {
+                return Err(anyhow::anyhow!("alerts: timeout_s doit être supérieur à 0"));
+            }
+            if !(self.alerts.battery_critical_v >= 0.0 && self.alerts.battery_critical_v.is_finite()) {
+                return Err(anyhow::anyhow!(
+                    "alerts: battery_critical_v {} invalide",
+                    self.alerts.battery_critical_v
+                ));
+            }
+        }
+
+        if self.mdns.enabled && self.vehicle.trim().is_empty() {
+            return Err(anyhow::anyhow!("mdns: nom du véhicule vide"));
+        }
+
+        if self.can.enabled {
+            let mut ids = BTreeSet::new();
+            for message in self.can.messages.iter() {
+                if !ids.insert(message.id) {
+                    return Err(anyhow::anyhow!("can: identifiant {:#x} déclaré plusieurs fois", message.id));
+                }
+                for signal in message.signals.iter() {
+                    signal
+                        .validate()
+                        .map_err(|e| anyhow::anyhow!("can: message {} ({:#x}): {}", message.name, message.id, e))?;
+                }
+            }
+        }
+
+        if self.udp.enabled {
+            self.udp
+                .target
+                .parse::<std::net::SocketAddr>()
+                .map_err(|e| anyhow::anyhow!("udp: destinataire {} invalide: {}", self.udp.target, e))?;
+
+            if !(128..=65507).contains(&self.udp.mtu) {
+                return Err(anyhow::anyhow!("udp: mtu {} hors de [128, 65507]", self.udp.mtu));
+            }
+
+            if let Some((kind, rate)) = self.udp.rates.iter().find(|(_, rate)| rate.is_nan() || **rate < 0.0) {
+                return Err(anyhow::anyhow!("udp: fréquence {} Hz invalide pour {}", rate, kind));
+            }
+        }
+
+        let frame = &self.udp.frame;
+        if !frame.target.is_empty() {
+            frame
+                .target
+                .parse::<std::net::SocketAddr>()
+                .map_err(|e| anyhow::anyhow!("udp.frame: destinataire {} invalide: {}", frame.target, e))?;
+
+            if !(0.1..=100.0).contains(&frame.rate_hz) {
+                return Err(anyhow::anyhow!("udp.frame: rate_hz {} hors de [0.1, 100]", frame.rate_hz));
+            }
+        }
+
+        let muxes = buses.iter().filter(|(_, mux)| *mux).count();
+        let fakes = modes.iter().filter(|(_, mode)| *mode == SensorMode::Fake).count();
+        Ok(format!(
+            "{} bus I2C, {} multiplexeur(s), {} capteur(s) simulé(s)",
+            buses.len(),
+            muxes,
+            fakes
+        ))
+    }
+}
+
+/// Liste les valeurs de la configuration par clé (ex: "timing.jitter_ms")
+fn flatten(config: &Config) -> anyhow::Result<BTreeMap<String, String>> {
+    fn walk(prefix: &str, value: &toml::Value, keys: &mut BTreeMap<String, String>) {
+        match value {
+            toml::Value::Table(table) => {
+                for (key, value) in table {
+                    let key = if prefix.is_empty() {
+                        key.clone()
+                    } else {
+                        format!("{}.{}", prefix, key)
+                    };
+                    walk(&key, value, keys);
+                }
+            }
+            value => {
+                keys.insert(prefix.to_string(), value.to_string());
+            }
+        }
+    }
+
+    let mut keys = BTreeMap::new();
+    walk("", &toml::Value::try_from(config)?, &mut keys);
+    Ok(keys)
+}
+
+/// Référence à un signal CAN ("message.signal"), vide: aucun signal
+fn signal_reference(signal: &str) -> bool {
+    signal.is_empty() || signal.split_once('.').is_some_and(|(message, name)| !message.is_empty() && !name.is_empty())
+}
@@ -0,0 +1,310 @@
+use std::sync::Arc;
+use std::time::Instant;
+use futures::StreamExt;
+#[cfg(feature = "real-actuators")]
+use rppal::pwm::Pwm;
+use tokio_util::sync::CancellationToken;
+
+use crate::actuators::arbiter::{Arbiter, Commands, Next};
+use crate::actuators::mock::Mock;
+#[cfg(feature = "real-actuators")]
+use crate::actuators::motor::Motor;
+use crate::actuators::ramp::{self, Ramp};
+#[cfg(feature = "real-actuators")]
+use crate::actuators::steering::Steering;
+use crate::actuators::{Control, ControlRecord};
+use crate::config::ControlConfig;
+use crate::database::Database;
+use crate::live::Live;
+use crate::selftest::SelfTest;
+use crate::sensors::sim::{ManualControl, SharedSimulation};
+
+/// Actionneurs pilotés par la boucle de contrôle: sorties PWM du moteur et de la direction, ou
+/// actionneurs factices du véhicule simulé
+pub trait Actuators {
+    /// Direction d'une commande, refusée si la commande est invalide
+    fn steer(&mut self, control: &Control) -> anyhow::Result<()>;
+
+    /// Sortie après la rampe de la vitesse
+    fn drive(&mut self, output: Control, now: Instant) -> anyhow::Result<()>;
+
+    /// Moteur au neutre, direction à la position indiquée
+    fn failsafe(&mut self, failsafe: Control, now: Instant);
+
+    /// Mise à jour périodique requise en dehors de la rampe (passage en marche arrière de l'ESC)
+    fn pending(&self) -> bool {
+        false
+    }
+
+    /// Fin de la boucle de contrôle: actionneurs au neutre
+    fn stop(&mut self);
+}
+
+/// Boucle de contrôle, identique pour les actionneurs réels et factices: commandes de l'arbitrage,
+/// rampe de la vitesse, limites de vitesse et failsafe (homme mort). Les actionneurs passent au
+/// neutre à l'annulation ou quand plus aucune source n'envoie de commande.
+pub async fn run<A: Actuators>(mut arbiter: Arbiter, config: &ControlConfig, actuators: &mut A, token: CancellationToken) {
+    let failsafe = Control::failsafe(config.failsafe_steer);
+    let mut ramp = Ramp::new(config.accel_per_s, config.decel_per_s);
+    // Rampe cadencée par l'horloge de l'arbitrage (temps virtuel en simulation)
+    let clock = arbiter.clock().clone();
+    let mut tick = clock.now();
+    let mut output = Control::default();
+    // Failsafe en cours: journal uniquement au début de la perte des commandes
+    let mut late = false;
+
+    loop {
+        let next = tokio::select! {
+            _ = token.cancelled() => break,
+            // Rampe ou passage en marche arrière de l'ESC en cours
+            _ = clock.sleep_until(tick), if !ramp.settled() || actuators.pending() => {
+                let now = clock.now();
+                tick = now + ramp::TICK;
+                output.speed = ramp.update(now);
+                if let Err(e) = actuators.drive(output, now) {
+                    tracing::error!(target: "control", "Erreur lors du contrôle moteur: {}", e)
+                }
+                arbiter.applied(output);
+                continue;
+            }
+            next = arbiter.next() => next,
+        };
+        match next {
+            Next::Command(command) => {
+                late = false;
+                let control = command.control;
+                tracing::debug!(
+                    target: "control",
+                    "Steer: {} Speed: {} ({})",
+                    control.steer, control.speed, command.source
+                );
+
+                // Commande refusée (ou direction inaccessible): actionneurs au neutre
+                if let Err(e) = actuators.steer(&control) {
+                    tracing::error!(target: "control", "Commande refusée: {}", e);
+                    ramp.cut(0.0);
+                    output = Control::default();
+                    actuators.failsafe(output, clock.now());
+                    arbiter.applied(output);
+                    continue;
+                }
+
+                // Limite de vitesse (coupure, arrêt d'urgence): consigne ramenée sous la limite,
+                // sortie au-delà sans rampe, y compris quand la commande précède la limite
+                output.steer = control.steer;
+                let (forward, reverse) = command.max_speed;
+                output.speed = ramp.clamp(-reverse, forward);
+                if !command.limited() {
+                    output.speed = ramp.set(control.speed, clock.now());
+                }
+                if let Err(e) = actuators.drive(output, clock.now()) {
+                    tracing::error!(target: "control", "Erreur lors du contrôle moteur: {}", e)
+                }
+                arbiter.applied(output);
+            }
+            Next::Timeout => {
+                if !std::mem::replace(&mut late, true) {
+                    tracing::error!(
+                        target: "control",
+                        "Update tardif des données, failsafe: moteur au neutre, direction {}",
+                        failsafe.steer
+                    );
+                }
+                ramp.cut(0.0);
+                output = failsafe;
+                actuators.failsafe(failsafe, clock.now());
+                arbiter.applied(failsafe);
+            }
+            Next::Closed => break,
+        }
+    }
+
+    actuators.stop();
+}
+
+/// Actionneurs factices: commandes validées sans sortie PWM, appliquées au véhicule simulé
+struct Simulated {
+    mock: Mock,
+    simulation: SharedSimulation,
+}
+
+impl Actuators for Simulated {
+    fn steer(&mut self, control: &Control) -> anyhow::Result<()> {
+        self.mock.apply(control)
+    }
+
+    fn drive(&mut self, output: Control, _now: Instant) -> anyhow::Result<()> {
+        self.mock.speed(output.speed);
+        self.simulation.lock().unwrap().set_control(Some(ManualControl {
+            steer: output.steer,
+            speed: output.speed,
+        }));
+        Ok(())
+    }
+
+    fn failsafe(&mut self, failsafe: Control, _now: Instant) {
+        self.mock.failsafe(&failsafe);
+        self.simulation.lock().unwrap().failsafe(failsafe.steer);
+    }
+
+    fn stop(&mut self) {
+        tracing::info!(target: "control", "Actionneurs factices: {}", self.mock.summary());
+        self.mock.neutral();
+        tracing::info!(target: "control", "Actionneurs factices au neutre");
+    }
+}
+
+/// Contrôle simulé: les commandes sont validées par des actionneurs factices
+/// et pilotent le véhicule simulé, sans aucune sortie PWM. Failsafe et rampe de la vitesse
+/// comme avec les actionneurs réels.
+pub async fn fake(
+    arbiter: Arbiter,
+    config: ControlConfig,
+    simulation: SharedSimulation,
+    selftest: SelfTest,
+    token: CancellationToken,
+) {
+    selftest.skip("motor.neutral", "Actionneurs simulés");
+    selftest.skip("steering.neutral", "Actionneurs simulés");
+
+    let mut actuators = Simulated {
+        mock: Mock::new(config.steering.clone(), config.motor.clone()),
+        simulation,
+    };
+    run(arbiter, &config, &mut actuators, token).await;
+}
+
+/// Moteur (ESC) et servo de direction sur les sorties PWM du Raspberry Pi
+#[cfg(feature = "real-actuators")]
+struct Hardware {
+    motor: Motor<Pwm>,
+    steering: Steering,
+}
+
+#[cfg(feature = "real-actuators")]
+impl Actuators for Hardware {
+    fn steer(&mut self, control: &Control) -> anyhow::Result<()> {
+        self.steering.set_steer(control.steer)
+    }
+
+    fn drive(&mut self, output: Control, now: Instant) -> anyhow::Result<()> {
+        self.motor.set_speed(output.speed, now)
+    }
+
+    fn failsafe(&mut self, failsafe: Control, now: Instant) {
+        let _ = self.motor.set_speed(0.0, now);
+        if let Err(e) = self.steering.set_steer(failsafe.steer) {
+            tracing::error!(target: "control", "Erreur lors du contrôle de la direction: {}", e)
+        }
+    }
+
+    fn pending(&self) -> bool {
+        self.motor.pending()
+    }
+
+    fn stop(&mut self) {
+        self.motor.safe_stop();
+        self.steering.safe_stop();
+        tracing::info!(target: "control", "Moteur et direction au neutre");
+    }
+}
+
+/// Contrôle par les actionneurs réels, vérifiés au neutre au démarrage (test de démarrage).
+/// Retourne faux si le moteur ou la direction est indisponible.
+#[cfg(feature = "real-actuators")]
+pub async fn hardware(arbiter: Arbiter, config: ControlConfig, selftest: SelfTest, token: CancellationToken) -> bool {
+    let mut motor = match Motor::new(config.motor.clone()) {
+        Ok(motor) => motor,
+        Err(e) => {
+            tracing::error!(target: "control", "Erreur lors de l'init moteur: {}", e);
+            selftest.record("motor.neutral", Err(e));
+            return false;
+        }
+    };
+    selftest.record("motor.neutral", motor.neutral_check());
+
+    let steering = match Steering::new(config.steering.clone()) {
+        Ok(steering) => steering,
+        Err(e) => {
+            tracing::error!(target: "control", "Erreur lors de l'init steering: {}", e);
+            selftest.record("steering.neutral", Err(e));
+            motor.safe_stop();
+            return false;
+        }
+    };
+    selftest.record("steering.neutral", steering.neutral_check());
+
+    run(arbiter, &config, &mut Hardware { motor, steering }, token).await;
+    true
+}
+
+/// Commandes de la base (control:realtime), transmises à l'arbitrage.
+/// Le live est recréé après une reconnexion à la base, avec une attente croissante en cas d'échec.
+pub async fn db(db: Arc<Database>, commands: Commands, token: CancellationToken) {
+    let mut live = Live::new("control");
+
+    while !token.is_cancelled() {
+        tokio::select! {
+            _ = token.cancelled() => return,
+            _ = db.connected() => {}
+        }
+        let reconnects = db.connection().reconnects;
+
+        let mut stream = match db.live_control().await {
+            Ok(stream) => stream,
+            Err(e) => {
+                if !live.failed(&token, format!("création impossible: {}", e)).await {
+                    return;
+                }
+                continue;
+            }
+        };
+
+        loop {
+            let data = tokio::select! {
+                _ = token.cancelled() => return,
+                _ = db.reconnected(reconnects) => {
+                    tracing::info!(target: "control", "Base reconnectée, live recréé.");
+                    break;
+                }
+                data = stream.next() => data,
+            };
+
+            match data {
+                Some(Ok(data)) => {
+                    live.received();
+                    if let Err(e) = apply(&commands, data.action, &data.data) {
+                        tracing::error!(target: "control", "Commande refusée: {}", e);
+                    }
+                }
+                Some(Err(e)) => tracing::error!(target: "control", "Erreur lors de l'update: {}", e),
+                // Fin du live: recréé après l'attente, le flux peut se terminer dès sa création
+                None => {
+                    if !live.failed(&token, "flux terminé").await {
+                        return;
+                    }
+                    break;
+                }
+            }
+        }
+    }
+}
+
+/// Applique une notification de control:realtime. Création (premier enregistrement de
+/// l'application) et modification sont traitées de la même façon, une suppression est ignorée.
+/// L'arrêt d'urgence est appliqué avant la commande de l'enregistrement.
+pub fn apply(
+    commands: &Commands,
+    action: surrealdb::Action,
+    record: &ControlRecord,
+) -> anyhow::Result<()> {
+    if !matches!(action, surrealdb::Action::Create | surrealdb::Action::Update) {
+        return Ok(());
+    }
+
+    if let Some(estop) = record.estop {
+        commands.cutoff("estop", estop);
+    }
+
+    commands.submit("db", record.control())
+}
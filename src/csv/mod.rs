@@ -0,0 +1,110 @@
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::Path;
+use std::time::Duration;
+
+use serde::Serialize;
+use tokio::sync::broadcast::error::RecvError;
+use tokio::task::block_in_place;
+use tokio::time::{interval, MissedTickBehavior};
+use tokio_util::sync::CancellationToken;
+
+use crate::config::CsvConfig;
+use crate::logs::Repeated;
+use crate::writer::{Record, Writer};
+
+pub mod table;
+
+use table::{Produced, Table};
+
+/// Index des fichiers produits par une exécution (index.json)
+#[derive(Serialize)]
+struct Index<'a> {
+    run: &'a str,
+    files: BTreeMap<&'static str, &'a [Produced]>,
+    /// Enregistrements perdus (écriture trop lente)
+    lost: u64,
+}
+
+/// Ecrit l'index de l'exécution
+fn write_index(directory: &Path, run: &str, tables: &BTreeMap<&'static str, Table>, lost: u64) -> anyhow::Result<()> {
+    let index = Index {
+        run,
+        files: tables.iter().map(|(kind, table)| (*kind, table.produced())).collect(),
+        lost,
+    };
+
+    let path = directory.join("index.json");
+    let temporary = directory.join("index.json.tmp");
+    fs::write(&temporary, serde_json::to_vec_pretty(&index)?)?;
+    fs::rename(temporary, path)?;
+    Ok(())
+}
+
+/// Ecrit les fichiers en attente et l'index sur le disque
+fn flush(directory: &Path, run: &str, tables: &mut BTreeMap<&'static str, Table>, lost: u64) {
+    block_in_place(|| {
+        for (kind, table) in tables.iter_mut() {
+            if let Err(e) = table.flush() {
+                tracing::error!(target: "csv", "Erreur d'écriture de {}: {}", kind, e);
+            }
+        }
+
+        if let Err(e) = write_index(directory, run, tables, lost) {
+            tracing::error!(target: "csv", "Erreur d'écriture de l'index: {}", e);
+        }
+    });
+}
+
+/// Export CSV des échantillons diffusés par l'écrivain, dans <directory>/<exécution>/.
+/// Les fichiers sont écrits sur le disque toutes les `flush_interval_ms` millisecondes.
+pub async fn run(config: CsvConfig, run: String, writer: Writer, token: CancellationToken) {
+    let directory = config.directory.join(&run);
+    if let Err(e) = fs::create_dir_all(&directory) {
+        tracing::error!(target: "csv", "Impossible de créer {}: {}", directory.display(), e);
+        return;
+    }
+
+    let max_bytes = config.max_file_mb.max(1) * 1024 * 1024;
+    let mut tables: BTreeMap<&'static str, Table> = Record::KINDS
+        .iter()
+        .filter(|kind| config.records.iter().any(|r| r == *kind))
+        .map(|kind| (*kind, Table::new(&directory, kind, max_bytes)))
+        .collect();
+
+    tracing::info!(target: "csv", "Export dans {}", directory.display());
+
+    let mut records = writer.subscribe();
+    let mut tick = interval(Duration::from_millis(config.flush_interval_ms.max(100)));
+    tick.set_missed_tick_behavior(MissedTickBehavior::Skip);
+    let mut lost = 0;
+    let mut repeated = Repeated::default();
+
+    loop {
+        tokio::select! {
+            _ = token.cancelled() => break,
+            _ = tick.tick() => flush(&directory, &run, &mut tables, lost),
+            record = records.recv() => match record {
+                Ok(record) => {
+                    let Some(table) = tables.get_mut(record.kind()) else {
+                        continue;
+                    };
+                    match table.write(&record) {
+                        Ok(_) => {
+                            repeated.clear();
+                        }
+                        Err(e) if repeated.first() => {
+                            tracing::error!(target: "csv", "Erreur d'écriture de {}: {}", record.kind(), e)
+                        }
+                        Err(e) => tracing::debug!(target: "csv", "Erreur d'écriture de {}: {}", record.kind(), e),
+                    }
+                }
+                Err(RecvError::Lagged(count)) => lost += count,
+                Err(RecvError::Closed) => break,
+            },
+        }
+    }
+
+    flush(&directory, &run, &mut tables, lost);
+    tracing::info!(target: "csv", "Arrêt.");
+}
@@ -0,0 +1,153 @@
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::path::{Path, PathBuf};
+
+use serde::Serialize;
+use serde_json::Value;
+
+/// Colonnes et valeurs d'un enregistrement, dans l'ordre des champs.
+/// Les structures et les tuples sont aplatis (ex: "stamp.utc", "angles.0").
+pub fn flatten<T: Serialize>(record: &T) -> anyhow::Result<Vec<(String, String)>> {
+    fn walk(prefix: &str, value: &Value, fields: &mut Vec<(String, String)>) {
+        let key = |name: &str| {
+            if prefix.is_empty() {
+                name.to_string()
+            } else {
+                format!("{}.{}", prefix, name)
+            }
+        };
+
+        match value {
+            Value::Object(map) => {
+                for (name, value) in map {
+                    walk(&key(name), value, fields);
+                }
+            }
+            Value::Array(values) => {
+                for (i, value) in values.iter().enumerate() {
+                    walk(&key(&i.to_string()), value, fields);
+                }
+            }
+            Value::String(text) => fields.push((prefix.to_string(), text.clone())),
+            Value::Null => fields.push((prefix.to_string(), String::new())),
+            value => fields.push((prefix.to_string(), value.to_string())),
+        }
+    }
+
+    let mut fields = Vec::new();
+    walk("", &serde_json::to_value(record)?, &mut fields);
+    Ok(fields)
+}
+
+/// Echappe une valeur: entre guillemets si elle contient un séparateur, un guillemet ou un saut de ligne
+pub fn escape(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+/// Ligne CSV terminée par un saut de ligne
+pub fn line<'a>(values: impl Iterator<Item = &'a str>) -> String {
+    let mut line = values.map(escape).collect::<Vec<_>>().join(",");
+    line.push('\n');
+    line
+}
+
+/// Fichier produit, pour l'index de l'exécution
+#[derive(Clone, Serialize)]
+pub struct Produced {
+    pub file: String,
+    pub rows: u64,
+    pub bytes: u64,
+}
+
+/// Fichiers CSV d'un type d'enregistrement: <nom>.csv, puis <nom>.1.csv, ... au-delà de `max_bytes`.
+/// L'en-tête est déduit du premier enregistrement et répété dans chaque fichier.
+pub struct Table {
+    directory: PathBuf,
+    name: String,
+    max_bytes: u64,
+    header: Option<Vec<String>>,
+    file: Option<BufWriter<File>>,
+    /// Fichiers produits, le dernier est en cours d'écriture
+    produced: Vec<Produced>,
+}
+
+impl Table {
+    pub fn new(directory: &Path, name: &str, max_bytes: u64) -> Self {
+        Self {
+            directory: directory.to_path_buf(),
+            name: name.to_string(),
+            max_bytes,
+            header: None,
+            file: None,
+            produced: Vec::new(),
+        }
+    }
+
+    pub fn write<T: Serialize>(&mut self, record: &T) -> anyhow::Result<()> {
+        let fields = flatten(record)?;
+
+        let header = self
+            .header
+            .get_or_insert_with(|| fields.iter().map(|(name, _)| name.clone()).collect());
+        if fields.len() != header.len() || fields.iter().zip(header.iter()).any(|((name, _), h)| name != h) {
+            return Err(anyhow::anyhow!("{}: champs différents de l'en-tête", self.name));
+        }
+
+        let row = line(fields.iter().map(|(_, value)| value.as_str()));
+
+        let full = self
+            .produced
+            .last()
+            .is_some_and(|file| file.rows > 0 && file.bytes + row.len() as u64 > self.max_bytes);
+        if self.file.is_none() || full {
+            self.open()?;
+        }
+
+        let file = self.file.as_mut().unwrap();
+        file.write_all(row.as_bytes())?;
+
+        let produced = self.produced.last_mut().unwrap();
+        produced.rows += 1;
+        produced.bytes += row.len() as u64;
+        Ok(())
+    }
+
+    /// Nouveau fichier (rotation), commence par l'en-tête
+    fn open(&mut self) -> anyhow::Result<()> {
+        self.flush()?;
+
+        let file = match self.produced.len() {
+            0 => format!("{}.csv", self.name),
+            n => format!("{}.{}.csv", self.name, n),
+        };
+
+        let mut writer = BufWriter::new(File::create(self.directory.join(&file))?);
+        let header = line(self.header.iter().flatten().map(String::as_str));
+        writer.write_all(header.as_bytes())?;
+
+        self.file = Some(writer);
+        self.produced.push(Produced {
+            file,
+            rows: 0,
+            bytes: header.len() as u64,
+        });
+        Ok(())
+    }
+
+    /// Ecrit les données en attente sur le disque
+    pub fn flush(&mut self) -> anyhow::Result<()> {
+        if let Some(file) = self.file.as_mut() {
+            file.flush()?;
+            file.get_ref().sync_data()?;
+        }
+        Ok(())
+    }
+
+    pub fn produced(&self) -> &[Produced] {
+        &self.produced
+    }
+}
@@ -1,42 +1,421 @@
-use surrealdb::engine::remote::ws::Client;
-use surrealdb::engine::remote::ws::Wss;
+use std::collections::BTreeMap;
+use std::future::Future;
+use std::sync::{Arc, Mutex, RwLock};
+use std::time::{Duration, Instant};
+
+use serde::Serialize;
+use surrealdb::engine::any::Any;
 use surrealdb::opt::auth::Root;
 use surrealdb::Surreal;
+use tokio::sync::watch;
+use tokio::time::{sleep, timeout};
+use tokio_util::sync::CancellationToken;
 
-use crate::actuators::Control;
+use crate::actuators::arbiter::SpeedLimit;
+use crate::actuators::auto_disarm::Countdown;
+use crate::actuators::rollover::RolloverReset;
+use crate::actuators::ControlRecord;
+use crate::capacity::CapacityReset;
+use crate::config::MagCalibration;
+use crate::fusion::Attitude;
+use crate::heading_check::HeadingError;
+use crate::clock::Stamp;
+use crate::config::DatabaseConfig;
+use crate::logs::LogUsage;
+use crate::metadata::Metadata;
+use crate::metrics::DbMetrics;
+use crate::record::{ModemData, ModemStatus, Record};
+use crate::run::RunState;
+use crate::selftest::Report;
+use crate::sensors::can::{CanData, CanStats};
+use crate::sensors::mag::calibration::{Fit, Request};
+#[cfg(feature = "real-actuators")]
 use crate::actuators::Switch;
 use crate::sensors::reader::AnalogData;
-use crate::sensors::reader::GpsData;
+use crate::sensors::reader::{BaroData, EncoderData, PowerData, RangeData};
+use crate::sensors::reader::{GpsData, SatellitesData};
 use crate::sensors::reader::ImuData;
 use crate::sensors::reader::MagData;
+use crate::sensors::reader::SensorsStatus;
+use crate::sensors::watchdog::{Component, Health};
+use crate::timing::TimingReport;
+use crate::writer::WriterStats;
+
+/// Version du schéma attendue dans l'enregistrement meta:schema
+const SCHEMA_VERSION: u32 = 1;
+
+/// Historique des échantillons (table `<type>_history`), écrit avec la dernière valeur
+/// (`<table>:realtime`): un enregistrement par échantillon, identifié par l'exécution et le temps
+/// monotone. Une relecture du tampon local ne crée pas de doublon.
+const HISTORY: &str = "UPDATE type::thing($history, [$run ?? '', $sample.stamp.mono_us]) CONTENT $sample RETURN NONE;";
+
+/// Echantillon de l'historique: champs du journal et exécution (champ `run`)
+#[derive(Serialize)]
+struct Sample<T> {
+    #[serde(flatten)]
+    data: T,
+    run: Option<String>,
+}
+
+/// Intervalle de vérification de la connexion
+const HEALTH_CHECK: Duration = Duration::from_secs(2);
+
+/// Délai maximal d'une vérification ou d'une tentative de connexion
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Attente entre deux tentatives de reconnexion, doublée à chaque échec
+pub const RECONNECT_MIN: Duration = Duration::from_millis(500);
+pub const RECONNECT_MAX: Duration = Duration::from_secs(30);
+
+/// Etat de la connexion à la base
+#[derive(Clone, Copy, Debug, Default, PartialEq, Serialize)]
+pub struct Connection {
+    pub connected: bool,
+    /// Tentatives de reconnexion depuis le démarrage
+    pub attempts: u64,
+    /// Reconnexions réussies
+    pub reconnects: u64,
+}
+
+/// Destination des écritures en mode dry-run: rien n'est envoyé, les écritures sont comptées
+#[derive(Default)]
+struct DryRunSink {
+    writes: Mutex<BTreeMap<&'static str, u64>>,
+}
+
+impl DryRunSink {
+    fn record(&self, target: &'static str) {
+        let mut writes = self.writes.lock().unwrap();
+        let count = writes.entry(target).or_insert(0);
+        *count += 1;
+
+        if *count == 1 {
+            tracing::info!(target: "db", "Ecriture vers {} ignorée (les suivantes sont uniquement comptées)", target);
+        }
+    }
+
+    fn summary(&self) -> String {
+        let writes = self.writes.lock().unwrap();
+        let total: u64 = writes.values().sum();
+        let detail: Vec<String> = writes
+            .iter()
+            .map(|(target, count)| format!("{}: {}", target, count))
+            .collect();
+
+        format!("{} écriture(s) ignorée(s) ({})", total, detail.join(", "))
+    }
+}
+
+/// Ecritures des échantillons par l'écrivain. Implémenté par la base, et par une base simulée
+/// dans les tests de l'écrivain.
+pub trait TelemetryStore: Send + Sync {
+    /// Etat de la connexion
+    fn connection(&self) -> Connection;
 
-pub(crate) struct Database {
-    db: Surreal<Client>,
+    /// Ecrit un échantillon: dernière valeur de sa table et historique
+    fn send_record(&self, record: Record) -> impl Future<Output = anyhow::Result<()>> + Send;
+
+    /// Ecrit le dernier état d'un message CAN
+    fn send_can(&self, data: CanData) -> impl Future<Output = anyhow::Result<()>> + Send;
+}
+
+pub struct Database {
+    /// Client actif, remplacé lors d'une reconnexion
+    db: RwLock<Surreal<Any>>,
+    /// Paramètres de connexion, None: base fournie par l'appelant (aucune reconnexion)
+    config: Option<DatabaseConfig>,
+    connection: watch::Sender<Connection>,
+    sink: Option<DryRunSink>,
+    /// Identifiant de l'exécution, ajouté aux échantillons et aux évènements
+    run: Option<String>,
+    /// Ecritures des échantillons par table (/metrics)
+    metrics: Arc<DbMetrics>,
 }
 
 impl Database {
-    pub(crate) async fn new() -> anyhow::Result<Self> {
-        let db = Surreal::new::<Wss>(env!("DB_URL")).await?;
+    /// Constructeur. En mode dry-run, la base est lue normalement mais aucune écriture n'est envoyée.
+    pub async fn new(config: &DatabaseConfig, dry_run: bool) -> anyhow::Result<Self> {
+        config.validate()?;
+        let db = connect(config).await?;
 
-        db.signin(Root {
-            username: env!("DB_USERNAME"),
-            password: env!("DB_PASSWORD"),
-        }).await?;
+        let mut database = Self::open(db, config, dry_run).await?;
+        database.config = Some(config.clone());
+        Ok(database)
+    }
+
+    /// Base déjà connectée et authentifiée (serveur distant ou base embarquée "mem://" des tests)
+    pub async fn open(
+        db: Surreal<Any>,
+        config: &DatabaseConfig,
+        dry_run: bool,
+    ) -> anyhow::Result<Self> {
+        db.use_ns(&config.namespace).use_db(&config.database).await?;
 
-        db.use_ns("voiturerc").use_db("voiturerc").await?;
-        
-        Ok(Self { db })
+        let connection = Connection {
+            connected: true,
+            ..Connection::default()
+        };
+        Ok(Self {
+            db: RwLock::new(db),
+            config: None,
+            connection: watch::Sender::new(connection),
+            sink: dry_run.then(DryRunSink::default),
+            run: None,
+            metrics: Arc::default(),
+        })
+    }
+
+    /// Identifiant de l'exécution écrit avec chaque échantillon et évènement (champ `run`)
+    pub fn with_run(mut self, id: &str) -> Self {
+        self.run = Some(id.to_string());
+        self
+    }
+
+    // Client actif (partagé, peu coûteux à copier).
+    fn client(&self) -> Surreal<Any> {
+        self.db.read().unwrap().clone()
+    }
+
+    // Etat de la connexion.
+    pub fn connection(&self) -> Connection {
+        *self.connection.borrow()
+    }
+
+    // Suivi de l'état de la connexion.
+    pub fn subscribe(&self) -> watch::Receiver<Connection> {
+        self.connection.subscribe()
+    }
+
+    // Attend que la connexion soit établie.
+    pub async fn connected(&self) {
+        let _ = self.connection.subscribe().wait_for(|connection| connection.connected).await;
+    }
+
+    // Attend une reconnexion, après le nombre de reconnexions donné.
+    pub async fn reconnected(&self, since: u64) {
+        let _ = self
+            .connection
+            .subscribe()
+            .wait_for(|connection| connection.connected && connection.reconnects != since)
+            .await;
+    }
+
+    // Compteurs des écritures des échantillons.
+    pub fn metrics(&self) -> Arc<DbMetrics> {
+        self.metrics.clone()
+    }
+
+    // Ecrit un échantillon (dernière valeur et historique), compte la réussite ou l'échec et la durée.
+    pub async fn send_record(&self, record: Record) -> anyhow::Result<()> {
+        let start = Instant::now();
+        let result = match record {
+            Record::Imu(data) => self.send_imu(data).await,
+            Record::Mag(data) => self.send_mag(data).await,
+            Record::Analog(data) => self.send_analog(data).await,
+            Record::Power(data) => self.send_power(data).await,
+            Record::Baro(data) => self.send_baro(data).await,
+            Record::Encoder(data) => self.send_encoder(data).await,
+            Record::Range(data) => self.send_range(data).await,
+            Record::Gps(data) => self.send_gps(data).await,
+            Record::Modem(data) => self.send_modem(data.status, data.stamp).await,
+            Record::Satellites(data) => self.send_satellites(data).await,
+        };
+        self.metrics.record(record.kind(), result.is_ok(), start.elapsed());
+        result
+    }
+
+    // Echantillon de l'historique, marqué de l'exécution.
+    fn sample<T>(&self, data: T) -> Sample<T> {
+        Sample {
+            data,
+            run: self.run.clone(),
+        }
+    }
+
+    // Intercepte une écriture en mode dry-run (retourne vrai si elle doit être ignorée).
+    fn dry_run(&self, target: &'static str) -> bool {
+        match &self.sink {
+            Some(sink) => {
+                sink.record(target);
+                true
+            }
+            None => false,
+        }
+    }
+
+    // Résumé des écritures ignorées (uniquement en mode dry-run).
+    pub fn dry_run_summary(&self) -> Option<String> {
+        self.sink.as_ref().map(DryRunSink::summary)
+    }
+
+    // Vérifie la connexion, retourne la version du serveur.
+    pub async fn check(&self) -> anyhow::Result<String> {
+        let version = self.client().version().await?;
+        Ok(format!("SurrealDB {}", version))
+    }
+
+    // Vérifie la version du schéma (None si aucune version n'est enregistrée).
+    pub async fn check_schema(&self) -> anyhow::Result<Option<String>> {
+        let mut result = self
+            .client()
+            .query("SELECT VALUE version FROM meta:schema;")
+            .await?;
+
+        let version: Option<u32> = result.take(0)?;
+        match version {
+            None => Ok(None),
+            Some(v) if v == SCHEMA_VERSION => Ok(Some(format!("Version {}", v))),
+            Some(v) => Err(anyhow::anyhow!("Version {} (attendue: {})", v, SCHEMA_VERSION)),
+        }
+    }
+
+    // Envoi le rapport du test de démarrage.
+    pub async fn send_selftest(&self, report: Report) -> anyhow::Result<()> {
+        if self.dry_run("selftest:last") {
+            return Ok(());
+        }
+
+        let mut result = self
+            .client()
+            .query("UPDATE selftest:last SET passed = $passed, checks = $checks, date = time::now();")
+            .bind(("passed", report.passed))
+            .bind(("checks", report.checks))
+            .await?;
+
+        if let Some(e) = result.take_errors().remove(&0) {
+            return Err(anyhow::anyhow!(e));
+        }
+
+        Ok(())
     }
 
     // Envoi les données des différents capteurs analogiques.
-    pub(crate) async fn send_analog(&self, data: AnalogData) -> anyhow::Result<()> {
+    pub async fn send_analog(&self, data: AnalogData) -> anyhow::Result<()> {
+        if self.dry_run("levels:realtime") {
+            return Ok(());
+        }
+
         let mut result = self
-            .db
-            .query("UPDATE levels:realtime SET battery = $battery;")
+            .client()
+            .query("UPDATE levels:realtime SET battery = $battery, cells = $cells, current = $current, stamp = $stamp, run = $run;")
+            .query(HISTORY)
+            .bind(("run", self.run.clone()))
+            .bind(("history", "analog_history"))
+            .bind(("sample", self.sample(data)))
             .bind(("battery", data.battery))
+            .bind(("current", data.current))
+            // Aucune prise d'équilibrage mesurée: champ absent
+            .bind(("cells", Some(data.measured_cells()).filter(|cells| !cells.is_empty())))
+            .bind(("stamp", data.stamp))
             .await?;
 
-        if let Some(e) = result.take_errors().remove(&0) {
+        if let Some(e) = result.take_errors().into_values().next() {
+            return Err(anyhow::anyhow!(e));
+        }
+
+        Ok(())
+    }
+
+    // Envoi les données du moniteur de puissance
+    pub async fn send_power(&self, data: PowerData) -> anyhow::Result<()> {
+        if self.dry_run("power:realtime") {
+            return Ok(());
+        }
+
+        let mut result = self
+            .client()
+            .query("UPDATE power:realtime SET voltage = $voltage, current = $current, power = $power, stamp = $stamp, run = $run;")
+            .query(HISTORY)
+            .bind(("run", self.run.clone()))
+            .bind(("history", "power_history"))
+            .bind(("sample", self.sample(data)))
+            .bind(("voltage", data.voltage))
+            .bind(("current", data.current))
+            .bind(("power", data.power))
+            .bind(("stamp", data.stamp))
+            .await?;
+
+        if let Some(e) = result.take_errors().into_values().next() {
+            return Err(anyhow::anyhow!(e));
+        }
+
+        Ok(())
+    }
+
+    // Envoi les données du baromètre
+    pub async fn send_baro(&self, data: BaroData) -> anyhow::Result<()> {
+        if self.dry_run("baro:realtime") {
+            return Ok(());
+        }
+
+        let mut result = self
+            .client()
+            .query("UPDATE baro:realtime SET pressure = $pressure, temperature = $temperature, humidity = $humidity, altitude = $altitude, stamp = $stamp, run = $run;")
+            .query(HISTORY)
+            .bind(("run", self.run.clone()))
+            .bind(("history", "baro_history"))
+            .bind(("sample", self.sample(data)))
+            .bind(("pressure", data.pressure))
+            .bind(("temperature", data.temperature))
+            // BMP280: champ absent
+            .bind(("humidity", data.humidity))
+            .bind(("altitude", data.altitude))
+            .bind(("stamp", data.stamp))
+            .await?;
+
+        if let Some(e) = result.take_errors().into_values().next() {
+            return Err(anyhow::anyhow!(e));
+        }
+
+        Ok(())
+    }
+
+    // Envoi les données de l'encodeur de roue
+    pub async fn send_encoder(&self, data: EncoderData) -> anyhow::Result<()> {
+        if self.dry_run("encoder:realtime") {
+            return Ok(());
+        }
+
+        let mut result = self
+            .client()
+            .query("UPDATE encoder:realtime SET speed_kmh = $speed_kmh, distance_m = $distance_m, pulses = $pulses, stamp = $stamp, run = $run;")
+            .query(HISTORY)
+            .bind(("run", self.run.clone()))
+            .bind(("history", "encoder_history"))
+            .bind(("sample", self.sample(data)))
+            .bind(("speed_kmh", data.speed_kmh))
+            .bind(("distance_m", data.distance_m))
+            .bind(("pulses", data.pulses))
+            .bind(("stamp", data.stamp))
+            .await?;
+
+        if let Some(e) = result.take_errors().into_values().next() {
+            return Err(anyhow::anyhow!(e));
+        }
+
+        Ok(())
+    }
+
+    // Envoi les données du capteur de distance
+    pub async fn send_range(&self, data: RangeData) -> anyhow::Result<()> {
+        if self.dry_run("range:realtime") {
+            return Ok(());
+        }
+
+        let mut result = self
+            .client()
+            .query("UPDATE range:realtime SET distance_mm = $distance_mm, status = $status, stamp = $stamp, run = $run;")
+            .query(HISTORY)
+            .bind(("run", self.run.clone()))
+            .bind(("history", "range_history"))
+            .bind(("sample", self.sample(data)))
+            // Aucune distance (hors de portée, délai dépassé): champ absent
+            .bind(("distance_mm", data.distance_mm))
+            .bind(("status", data.status))
+            .bind(("stamp", data.stamp))
+            .await?;
+
+        if let Some(e) = result.take_errors().into_values().next() {
             return Err(anyhow::anyhow!(e));
         }
 
@@ -44,11 +423,189 @@ impl Database {
     }
 
     // Envoi les données du modem
-    pub(crate) async fn send_modem(&self, quality: u32) -> anyhow::Result<()> {
+    pub async fn send_modem(&self, status: ModemStatus, stamp: Stamp) -> anyhow::Result<()> {
+        if self.dry_run("modem:realtime") {
+            return Ok(());
+        }
+
+        let mut result = self
+            .client()
+            .query("UPDATE modem:realtime SET quality = $quality, technology = $technology, registration = $registration, operator = $operator, rsrp = $rsrp, rsrq = $rsrq, sinr = $sinr, stamp = $stamp, run = $run;")
+            .query(HISTORY)
+            .bind(("run", self.run.clone()))
+            .bind(("history", "modem_history"))
+            .bind(("sample", self.sample(ModemData { status, stamp })))
+            .bind(("quality", status.quality))
+            .bind(("technology", status.technology))
+            .bind(("registration", status.registration))
+            .bind(("operator", status.operator))
+            .bind(("rsrp", status.rsrp))
+            .bind(("rsrq", status.rsrq))
+            .bind(("sinr", status.sinr))
+            .bind(("stamp", stamp))
+            .await?;
+
+        if let Some(e) = result.take_errors().into_values().next() {
+            return Err(anyhow::anyhow!(e));
+        }
+
+        Ok(())
+    }
+
+    // Envoi les satellites et la précision du GPS.
+    pub async fn send_satellites(&self, data: SatellitesData) -> anyhow::Result<()> {
+        if self.dry_run("satellites:realtime") {
+            return Ok(());
+        }
+
+        let mut result = self
+            .client()
+            .query("UPDATE satellites:realtime SET pdop = $pdop, hdop = $hdop, vdop = $vdop, used = $used, in_view = $in_view, snr_mean = $snr_mean, stamp = $stamp, run = $run;")
+            .query(HISTORY)
+            .bind(("run", self.run.clone()))
+            .bind(("history", "satellites_history"))
+            .bind(("sample", self.sample(data)))
+            .bind(("pdop", data.pdop))
+            .bind(("hdop", data.hdop))
+            .bind(("vdop", data.vdop))
+            .bind(("used", data.used))
+            .bind(("in_view", data.in_view))
+            .bind(("snr_mean", data.snr_mean))
+            .bind(("stamp", data.stamp))
+            .await?;
+
+        if let Some(e) = result.take_errors().into_values().next() {
+            return Err(anyhow::anyhow!(e));
+        }
+
+        Ok(())
+    }
+
+    // Envoi les données GPS.
+    pub async fn send_gps(&self, data: GpsData) -> anyhow::Result<()> {
+        if self.dry_run("nav:realtime") {
+            return Ok(());
+        }
+
+        let mut result = self
+            .client()
+            .query("UPDATE nav:realtime SET latitude = $latitude, longitude = $longitude, satellite_count = $satellite_count, fix = $fix, speed = $speed, gps_heading = $gps_heading, gps_time = $gps_time, gps_valid = $gps_valid, gps_stamp = $gps_stamp, run = $run;")
+            .query(HISTORY)
+            .bind(("run", self.run.clone()))
+            .bind(("history", "gps_history"))
+            .bind(("sample", self.sample(data)))
+            .bind(("latitude", data.latitude))
+            .bind(("longitude", data.longitude))
+            .bind(("satellite_count", data.satellites))
+            .bind(("fix", data.fix))
+            .bind(("speed", data.speed_kmh))
+            .bind(("gps_heading", data.heading))
+            .bind(("gps_time", data.time))
+            .bind(("gps_valid", data.valid))
+            .bind(("gps_stamp", data.stamp))
+            .await?;
+
+        if let Some(e) = result.take_errors().into_values().next() {
+            return Err(anyhow::anyhow!(e));
+        }
+
+        Ok(())
+    }
+
+    // Envoi les données du magnétomètre.
+    pub async fn send_mag(&self, data: MagData) -> anyhow::Result<()> {
+        if self.dry_run("nav:realtime") {
+            return Ok(());
+        }
+
+        let mut result = self
+            .client()
+            .query("UPDATE nav:realtime SET mag_raw = $mag_raw, mag_calibrated = $mag_calibrated, mag_heading = $mag_heading, mag_true_heading = $mag_true_heading, mag_declination = $mag_declination, mag_stamp = $mag_stamp, run = $run;")
+            .query(HISTORY)
+            .bind(("run", self.run.clone()))
+            .bind(("history", "mag_history"))
+            .bind(("sample", self.sample(data)))
+            .bind(("mag_raw", data.raw))
+            .bind(("mag_calibrated", data.calibrated))
+            .bind(("mag_heading", data.heading))
+            .bind(("mag_true_heading", data.true_heading))
+            .bind(("mag_declination", data.declination))
+            .bind(("mag_stamp", data.stamp))
+            .await?;
+
+        if let Some(e) = result.take_errors().into_values().next() {
+            return Err(anyhow::anyhow!(e));
+        }
+
+        Ok(())
+    }
+
+    // Envoi les données de l'IMU.
+    pub async fn send_imu(&self, data: ImuData) -> anyhow::Result<()> {
+        if self.dry_run("nav:realtime") {
+            return Ok(());
+        }
+
         let mut result = self
-            .db
-            .query("UPDATE modem:realtime SET quality = $quality;")
-            .bind(("quality", quality))
+            .client()
+            .query("UPDATE nav:realtime SET angles = $angles, temp = $temp, accel = $accel, gyro = $gyro, imu_stamp = $imu_stamp, run = $run;")
+            .query(HISTORY)
+            .bind(("run", self.run.clone()))
+            .bind(("history", "imu_history"))
+            .bind(("sample", self.sample(data)))
+            .bind(("angles", data.angles))
+            .bind(("temp", data.temp))
+            .bind(("accel", data.accel))
+            .bind(("gyro", data.gyro))
+            .bind(("imu_stamp", data.stamp))
+            .await?;
+
+        if let Some(e) = result.take_errors().into_values().next() {
+            return Err(anyhow::anyhow!(e));
+        }
+
+        Ok(())
+    }
+
+    // Envoi les signaux décodés d'un message CAN (un enregistrement par message).
+    pub async fn send_can(&self, data: CanData) -> anyhow::Result<()> {
+        if self.dry_run("can") {
+            return Ok(());
+        }
+
+        let mut result = self
+            .client()
+            .query("UPDATE type::thing('can', $message) SET id = $id, signals = $signals, stamp = $stamp, run = $run;")
+            .bind(("run", self.run.clone()))
+            .bind(("message", data.message))
+            .bind(("id", data.id))
+            .bind(("signals", data.signals))
+            .bind(("stamp", data.stamp))
+            .await?;
+
+        if let Some(e) = result.take_errors().remove(&0) {
+            return Err(anyhow::anyhow!(e));
+        }
+
+        Ok(())
+    }
+
+    // Envoi les compteurs du bus CAN.
+    pub async fn send_can_status(&self, stats: CanStats, stamp: Stamp) -> anyhow::Result<()> {
+        if self.dry_run("status:can") {
+            return Ok(());
+        }
+
+        let mut result = self
+            .client()
+            .query("UPDATE status:can SET frames = $frames, decoded = $decoded, unknown = $unknown, unknown_ids = $unknown_ids, errors = $errors, bus_off = $bus_off, stamp = $stamp;")
+            .bind(("frames", stats.frames))
+            .bind(("decoded", stats.decoded))
+            .bind(("unknown", stats.unknown))
+            .bind(("unknown_ids", stats.unknown_ids))
+            .bind(("errors", stats.errors))
+            .bind(("bus_off", stats.bus_off))
+            .bind(("stamp", stamp))
             .await?;
 
         if let Some(e) = result.take_errors().remove(&0) {
@@ -58,21 +615,17 @@ impl Database {
         Ok(())
     }
 
-    // Envoi les données de navigation.
-    pub(crate) async fn send_nav(&self, gps_data: GpsData, mag_data: MagData, imu_data: ImuData) -> anyhow::Result<()> {
+    // Envoi l'état de la coupure du moteur (voiture retournée).
+    pub async fn send_rollover_status(&self, active: bool, stamp: Stamp) -> anyhow::Result<()> {
+        if self.dry_run("status:control") {
+            return Ok(());
+        }
+
         let mut result = self
-            .db
-            .query("UPDATE nav:realtime SET latitude = $latitude, longitude = $longitude, satellite_count = $satellite_count, fix = $fix, speed = $speed, gps_heading = $gps_heading, mag_raw = $raw, mag_heading = $mag_heading, angles = $angles, temp = $temp;")
-            .bind(("latitude", gps_data.latitude))
-            .bind(("longitude", gps_data.longitude))
-            .bind(("satellite_count", gps_data.satellites))
-            .bind(("fix", gps_data.fix))
-            .bind(("speed", gps_data.speed_kmh))
-            .bind(("gps_heading", gps_data.heading))
-            .bind(("mag_raw", mag_data.raw))
-            .bind(("mag_heading", mag_data.heading))
-            .bind(("angles", imu_data.angles))
-            .bind(("temp", imu_data.temp))
+            .client()
+            .query("UPDATE status:control SET rollover = $rollover, stamp = $stamp;")
+            .bind(("rollover", active))
+            .bind(("stamp", stamp))
             .await?;
 
         if let Some(e) = result.take_errors().remove(&0) {
@@ -82,10 +635,430 @@ impl Database {
         Ok(())
     }
 
+    // Envoi la limite de vitesse active (vitesse maximale et cause), nulle sans limite.
+    pub async fn send_limit_status(&self, limit: Option<SpeedLimit>, stamp: Stamp) -> anyhow::Result<()> {
+        if self.dry_run("status:control") {
+            return Ok(());
+        }
+
+        let (max, cause) = match limit {
+            Some(limit) => (Some(limit.max), Some(limit.cause)),
+            None => (None, None),
+        };
+        let mut result = self
+            .client()
+            .query("UPDATE status:control SET limit = $limit, limit_cause = $cause, stamp = $stamp;")
+            .bind(("limit", max))
+            .bind(("cause", cause))
+            .bind(("stamp", stamp))
+            .await?;
+
+        if let Some(e) = result.take_errors().remove(&0) {
+            return Err(anyhow::anyhow!(e));
+        }
+
+        Ok(())
+    }
+
+    // Envoi les durées restantes avant le désarmement automatique (secondes), nulles si désactivées.
+    pub async fn send_countdown_status(&self, countdown: Countdown, stamp: Stamp) -> anyhow::Result<()> {
+        if self.dry_run("status:control") {
+            return Ok(());
+        }
+
+        let mut result = self
+            .client()
+            .query("UPDATE status:control SET armed_remaining_s = $armed, idle_remaining_s = $idle, stamp = $stamp;")
+            .bind(("armed", countdown.armed.map(|remaining| remaining.as_secs())))
+            .bind(("idle", countdown.idle.map(|remaining| remaining.as_secs())))
+            .bind(("stamp", stamp))
+            .await?;
+
+        if let Some(e) = result.take_errors().remove(&0) {
+            return Err(anyhow::anyhow!(e));
+        }
+
+        Ok(())
+    }
+
+    // Envoi la vitesse commandée et la vitesse appliquée au moteur après la rampe.
+    pub async fn send_speed_status(&self, command: f64, output: f64, stamp: Stamp) -> anyhow::Result<()> {
+        if self.dry_run("status:control") {
+            return Ok(());
+        }
+
+        let mut result = self
+            .client()
+            .query("UPDATE status:control SET speed_command = $command, speed_output = $output, stamp = $stamp;")
+            .bind(("command", command))
+            .bind(("output", output))
+            .bind(("stamp", stamp))
+            .await?;
+
+        if let Some(e) = result.take_errors().remove(&0) {
+            return Err(anyhow::anyhow!(e));
+        }
+
+        Ok(())
+    }
+
+    // Envoi la capacité consommée et restante de la batterie
+    pub async fn send_capacity_status(&self, consumed_mah: f64, remaining_pct: f64, stamp: Stamp) -> anyhow::Result<()> {
+        if self.dry_run("status:battery") {
+            return Ok(());
+        }
+
+        let mut result = self
+            .client()
+            .query("UPDATE status:battery SET consumed_mah = $consumed, remaining_pct = $remaining, stamp = $stamp;")
+            .bind(("consumed", consumed_mah))
+            .bind(("remaining", remaining_pct))
+            .bind(("stamp", stamp))
+            .await?;
+
+        if let Some(e) = result.take_errors().remove(&0) {
+            return Err(anyhow::anyhow!(e));
+        }
+
+        Ok(())
+    }
+
+    // Envoi l'attitude estimée par la fusion de l'IMU et du magnétomètre
+    pub async fn send_attitude(&self, attitude: Attitude, stamp: Stamp) -> anyhow::Result<()> {
+        if self.dry_run("nav:attitude") {
+            return Ok(());
+        }
+
+        let mut result = self
+            .client()
+            .query("UPDATE nav:attitude SET roll = $roll, pitch = $pitch, yaw = $yaw, quaternion = $quaternion, stamp = $stamp;")
+            .bind(("roll", attitude.roll))
+            .bind(("pitch", attitude.pitch))
+            .bind(("yaw", attitude.yaw))
+            .bind(("quaternion", attitude.quaternion))
+            .bind(("stamp", stamp))
+            .await?;
+
+        if let Some(e) = result.take_errors().remove(&0) {
+            return Err(anyhow::anyhow!(e));
+        }
+
+        Ok(())
+    }
+
+    // Envoi l'écart entre la route GPS et le cap du magnétomètre.
+    pub async fn send_heading_error(&self, error: HeadingError, stamp: Stamp) -> anyhow::Result<()> {
+        if self.dry_run("nav:heading_error") {
+            return Ok(());
+        }
+
+        let mut result = self
+            .client()
+            .query("UPDATE nav:heading_error SET error = $error, smoothed = $smoothed, speed = $speed, stamp = $stamp;")
+            .bind(("error", error.error))
+            .bind(("smoothed", error.smoothed))
+            .bind(("speed", error.speed_ms))
+            .bind(("stamp", stamp))
+            .await?;
+
+        if let Some(e) = result.take_errors().remove(&0) {
+            return Err(anyhow::anyhow!(e));
+        }
+
+        Ok(())
+    }
+
+    // Envoi l'état de la calibration du magnétomètre (écart persistant avec la route GPS).
+    pub async fn send_mag_suspect_status(&self, suspect: bool, stamp: Stamp) -> anyhow::Result<()> {
+        if self.dry_run("status:health") {
+            return Ok(());
+        }
+
+        let mut result = self
+            .client()
+            .query("UPDATE status:health SET mag_calibration_suspect = $suspect, stamp = $stamp;")
+            .bind(("suspect", suspect))
+            .bind(("stamp", stamp))
+            .await?;
+
+        if let Some(e) = result.take_errors().remove(&0) {
+            return Err(anyhow::anyhow!(e));
+        }
+
+        Ok(())
+    }
+
+    // Enregistre la calibration du magnétomètre, relue au démarrage
+    pub async fn send_mag_calibration(&self, fit: Fit, samples: usize, stamp: Stamp) -> anyhow::Result<()> {
+        if self.dry_run("calibration:mag") {
+            return Ok(());
+        }
+
+        let mut result = self
+            .client()
+            .query("UPDATE calibration:mag SET hard_iron = $hard_iron, soft_iron = $soft_iron, radius = $radius, residual = $residual, samples = $samples, stamp = $stamp;")
+            .bind(("hard_iron", fit.calibration.hard_iron))
+            .bind(("soft_iron", fit.calibration.soft_iron))
+            .bind(("radius", fit.radius))
+            .bind(("residual", fit.residual))
+            .bind(("samples", samples))
+            .bind(("stamp", stamp))
+            .await?;
+
+        if let Some(e) = result.take_errors().remove(&0) {
+            return Err(anyhow::anyhow!(e));
+        }
+
+        Ok(())
+    }
+
+    // Récupère la dernière calibration du magnétomètre enregistrée
+    pub async fn mag_calibration(&self) -> anyhow::Result<Option<MagCalibration>> {
+        let mut result = self
+            .client()
+            .query("SELECT hard_iron, soft_iron FROM calibration:mag;")
+            .await?;
+
+        Ok(result.take::<Option<MagCalibration>>(0)?)
+    }
+
+    // Envoi l'état d'initialisation des capteurs et de chaque bus I2C.
+    pub async fn send_status(&self, status: SensorsStatus, stamp: Stamp) -> anyhow::Result<()> {
+        if self.dry_run("status:sensors") {
+            return Ok(());
+        }
+
+        let mut result = self
+            .client()
+            .query("UPDATE status:sensors SET imu = $imu, mag = $mag, analog = $analog, gps = $gps, i2c = $i2c, stamp = $stamp;")
+            .bind(("imu", status.imu))
+            .bind(("mag", status.mag))
+            .bind(("analog", status.analog))
+            .bind(("gps", status.gps))
+            // Clés des objets de la base: numéro de bus en texte
+            .bind(("i2c", status.i2c.iter().map(|(bus, status)| (bus.to_string(), status.clone())).collect::<BTreeMap<_, _>>()))
+            .bind(("stamp", stamp))
+            .await?;
+
+        if let Some(e) = result.take_errors().remove(&0) {
+            return Err(anyhow::anyhow!(e));
+        }
+
+        Ok(())
+    }
+
+    // Envoi l'état de santé des capteurs et les capteurs périmés.
+    pub async fn send_health(&self, health: Health, stale: &[&'static str], stamp: Stamp) -> anyhow::Result<()> {
+        if self.dry_run("status:sensors") {
+            return Ok(());
+        }
+
+        let mut result = self
+            .client()
+            .query("UPDATE status:sensors SET health = $health, stale = $stale, stamp = $stamp;")
+            .bind(("health", health))
+            .bind(("stale", stale.to_vec()))
+            .bind(("stamp", stamp))
+            .await?;
+
+        if let Some(e) = result.take_errors().remove(&0) {
+            return Err(anyhow::anyhow!(e));
+        }
+
+        Ok(())
+    }
+
+    // Envoi la santé de chaque composant (un enregistrement par composant).
+    pub async fn send_components(&self, components: Vec<Component>, stamp: Stamp) -> anyhow::Result<()> {
+        if self.dry_run("health") {
+            return Ok(());
+        }
+
+        let mut result = self
+            .client()
+            .query(
+                "FOR $component IN $components { \
+                 UPDATE type::thing('health', $component.name) SET status = $component.status, \
+                 silent_ms = $component.silent_ms, error = $component.error, restarts = $component.restarts, \
+                 stamp = $stamp; };",
+            )
+            .bind(("components", components))
+            .bind(("stamp", stamp))
+            .await?;
+
+        if let Some(e) = result.take_errors().remove(&0) {
+            return Err(anyhow::anyhow!(e));
+        }
+
+        Ok(())
+    }
+
+    // Envoi l'état de l'exécution (identifiant, nombre d'arrêts non propres, version)
+    // et crée l'enregistrement de l'exécution.
+    pub async fn send_run(&self, state: RunState, metadata: Metadata, stamp: Stamp) -> anyhow::Result<()> {
+        if self.dry_run("status:run") {
+            return Ok(());
+        }
+
+        let mut result = self
+            .client()
+            .query("UPDATE status:run SET id = $id, pid = $pid, crashes = $crashes, unclean = $unclean, build = $build, stamp = $stamp;")
+            .query("CREATE type::thing('run', $id) SET pid = $pid, build = $build, stamp = $stamp;")
+            .bind(("id", state.id))
+            .bind(("pid", state.pid))
+            .bind(("crashes", state.crashes))
+            .bind(("unclean", state.unclean))
+            .bind(("build", metadata))
+            .bind(("stamp", stamp))
+            .await?;
+
+        if let Some(e) = result.take_errors().into_values().next() {
+            return Err(anyhow::anyhow!(e));
+        }
+
+        Ok(())
+    }
+
+    // Envoi l'occupation du dossier des journaux.
+    pub async fn send_logs_status(&self, usage: LogUsage, stamp: Stamp) -> anyhow::Result<()> {
+        if self.dry_run("status:run") {
+            return Ok(());
+        }
+
+        let mut result = self
+            .client()
+            .query("UPDATE status:run SET logs = $logs, logs_stamp = $stamp;")
+            .bind(("logs", usage))
+            .bind(("stamp", stamp))
+            .await?;
+
+        if let Some(e) = result.take_errors().remove(&0) {
+            return Err(anyhow::anyhow!(e));
+        }
+
+        Ok(())
+    }
+
+    // Envoi l'état des files d'écriture.
+    pub async fn send_writer_status(&self, stats: WriterStats) -> anyhow::Result<()> {
+        if self.dry_run("status:writer") {
+            return Ok(());
+        }
+
+        let mut result = self
+            .client()
+            .query("UPDATE status:writer SET imu = $imu, mag = $mag, analog = $analog, gps = $gps, modem = $modem, satellites = $satellites, can = $can, events = $events, breaker = $breaker, connection = $connection, spool = $spool;")
+            .bind(("imu", stats.imu))
+            .bind(("mag", stats.mag))
+            .bind(("analog", stats.analog))
+            .bind(("gps", stats.gps))
+            .bind(("modem", stats.modem))
+            .bind(("satellites", stats.satellites))
+            .bind(("can", stats.can))
+            .bind(("events", stats.events))
+            .bind(("breaker", stats.breaker))
+            .bind(("connection", stats.connection))
+            .bind(("spool", stats.spool))
+            .await?;
+
+        if let Some(e) = result.take_errors().remove(&0) {
+            return Err(anyhow::anyhow!(e));
+        }
+
+        Ok(())
+    }
+
+    // Envoi le résumé des durées des boucles.
+    pub async fn send_timing(&self, report: TimingReport, stamp: Stamp) -> anyhow::Result<()> {
+        if self.dry_run("status:timing") {
+            return Ok(());
+        }
+
+        let mut result = self
+            .client()
+            .query("UPDATE status:timing SET reader = $reader, pipeline = $pipeline, stamp = $stamp;")
+            .bind(("reader", report.reader))
+            .bind(("pipeline", report.pipeline))
+            .bind(("stamp", stamp))
+            .await?;
+
+        if let Some(e) = result.take_errors().remove(&0) {
+            return Err(anyhow::anyhow!(e));
+        }
+
+        Ok(())
+    }
+
+    // Enregistre un évènement (avertissement, ...).
+    pub async fn send_event(&self, kind: &str, message: &str, stamp: Stamp) -> anyhow::Result<()> {
+        if self.dry_run("event") {
+            return Ok(());
+        }
+
+        let mut result = self
+            .client()
+            .query("CREATE event SET kind = $kind, message = $message, stamp = $stamp, run = $run;")
+            .bind(("run", self.run.clone()))
+            .bind(("kind", kind.to_string()))
+            .bind(("message", message.to_string()))
+            .bind(("stamp", stamp))
+            .await?;
+
+        if let Some(e) = result.take_errors().remove(&0) {
+            return Err(anyhow::anyhow!(e));
+        }
+
+        Ok(())
+    }
+
+    // Vérifie qu'une exécution a été enregistrée (table run).
+    pub async fn has_run(&self, id: &str) -> anyhow::Result<bool> {
+        let mut result = self
+            .client()
+            .query("SELECT VALUE true FROM type::thing('run', $id);")
+            .bind(("id", id.to_string()))
+            .await?;
+
+        let found: Option<bool> = result.take(0)?;
+        Ok(found.unwrap_or(false))
+    }
+
+    // Lit une page des enregistrements d'une exécution (export), avec les champs du journal.
+    // Types d'échantillons de Record::KINDS (historique <type>_history), ou "event" pour les évènements.
+    pub async fn run_records(
+        &self,
+        kind: &str,
+        run: &str,
+        start: usize,
+        limit: usize,
+    ) -> anyhow::Result<Vec<serde_json::Value>> {
+        let select = match kind {
+            "event" => "SELECT stamp, kind, message FROM event WHERE run = $run".to_string(),
+            kind if Record::KINDS.contains(&kind) => {
+                format!("SELECT * OMIT id, run FROM {}_history WHERE run = $run", kind)
+            }
+            _ => return Err(anyhow::anyhow!("Type d'enregistrement inconnu: {}", kind)),
+        };
+
+        let mut result = self
+            .client()
+            .query(format!("{} ORDER BY stamp LIMIT $limit START $start;", select))
+            .bind(("run", run.to_string()))
+            .bind(("limit", limit))
+            .bind(("start", start))
+            .await?;
+
+        Ok(result.take(0)?)
+    }
+
     // Mets l'intégralité des switchs à 0
-    pub(crate) async fn reset_switch(&self) -> anyhow::Result<()> {
+    pub async fn reset_switch(&self) -> anyhow::Result<()> {
+        if self.dry_run("switch:realtime") {
+            return Ok(());
+        }
+
         let mut result = self
-            .db
+            .client()
             .query("UPDATE switch:realtime SET esc = $esc;")
             .bind(("esc", false))
             .await?;
@@ -98,24 +1071,165 @@ impl Database {
     }
 
     // Prépare un stream des switchs.
-    pub(crate) async fn live_switch(
+    #[cfg(feature = "real-actuators")]
+    pub async fn live_switch(
         &self,
-    ) -> anyhow::Result<surrealdb::method::Stream<'_, Client, std::option::Option<Switch>>> {
-        self.db
+    ) -> anyhow::Result<surrealdb::method::Stream<'static, Any, std::option::Option<Switch>>> {
+        self.client()
             .select(("switch", "realtime"))
+            .into_owned()
             .live()
             .await
             .map_err(|x| anyhow::anyhow!(x))
     }
 
     // Prépare un stream des contrôles.
-    pub(crate) async fn live_control(
+    pub async fn live_control(
         &self,
-    ) -> anyhow::Result<surrealdb::method::Stream<'_, Client, std::option::Option<Control>>> {
-        self.db
+    ) -> anyhow::Result<surrealdb::method::Stream<'static, Any, std::option::Option<ControlRecord>>> {
+        self.client()
             .select(("control", "realtime"))
+            .into_owned()
+            .live()
+            .await
+            .map_err(|x| anyhow::anyhow!(x))
+    }
+
+    // Prépare un stream des commandes de calibration du magnétomètre.
+    pub async fn live_mag_calibration(
+        &self,
+    ) -> anyhow::Result<surrealdb::method::Stream<'static, Any, std::option::Option<Request>>> {
+        self.client()
+            .select(("control", "mag_calibration"))
+            .into_owned()
+            .live()
+            .await
+            .map_err(|x| anyhow::anyhow!(x))
+    }
+
+    // Prépare un stream des levées de la coupure du moteur (voiture retournée ou choc).
+    pub async fn live_rollover(
+        &self,
+    ) -> anyhow::Result<surrealdb::method::Stream<'static, Any, std::option::Option<RolloverReset>>> {
+        self.client()
+            .select(("control", "rollover"))
+            .into_owned()
             .live()
             .await
             .map_err(|x| anyhow::anyhow!(x))
     }
+
+    // Prépare un stream des remises à zéro de la capacité de la batterie.
+    pub async fn live_capacity(
+        &self,
+    ) -> anyhow::Result<surrealdb::method::Stream<'static, Any, std::option::Option<CapacityReset>>> {
+        self.client()
+            .select(("control", "battery"))
+            .into_owned()
+            .live()
+            .await
+            .map_err(|x| anyhow::anyhow!(x))
+    }
+}
+
+impl TelemetryStore for Database {
+    fn connection(&self) -> Connection {
+        Database::connection(self)
+    }
+
+    async fn send_record(&self, record: Record) -> anyhow::Result<()> {
+        Database::send_record(self, record).await
+    }
+
+    async fn send_can(&self, data: CanData) -> anyhow::Result<()> {
+        Database::send_can(self, data).await
+    }
+}
+
+/// Connexion et authentification
+async fn connect(config: &DatabaseConfig) -> anyhow::Result<Surreal<Any>> {
+    let db = surrealdb::engine::any::connect(config.url.as_str())
+        .await
+        .map_err(|e| anyhow::anyhow!("{}: {}", config.url, e))?;
+
+    if !config.username.is_empty() {
+        let password = if config.password.is_empty() {
+            std::env::var("DB_PASSWORD").unwrap_or_default()
+        } else {
+            config.password.clone()
+        };
+
+        db.signin(Root {
+            username: &config.username,
+            password: &password,
+        }).await?;
+    }
+
+    Ok(db)
+}
+
+/// Surveille la connexion à la base. Connexion perdue (coupure du lien 4G, serveur redémarré):
+/// nouveau client avec une attente croissante entre les tentatives. Les écritures échouent
+/// pendant la coupure, les tâches qui en dépendent attendent la reconnexion.
+pub async fn supervise(db: Arc<Database>, token: CancellationToken) {
+    let Some(config) = db.config.clone() else {
+        return;
+    };
+
+    loop {
+        tokio::select! {
+            _ = token.cancelled() => return,
+            _ = sleep(HEALTH_CHECK) => {}
+        }
+
+        let error = match timeout(CONNECT_TIMEOUT, db.client().health()).await {
+            Ok(Ok(_)) => continue,
+            Ok(Err(e)) => e.to_string(),
+            Err(_) => format!("aucune réponse en {} s", CONNECT_TIMEOUT.as_secs()),
+        };
+        tracing::error!(target: "db", "Connexion perdue: {}", error);
+        db.connection.send_modify(|connection| connection.connected = false);
+
+        let mut delay = RECONNECT_MIN;
+        loop {
+            tokio::select! {
+                _ = token.cancelled() => return,
+                _ = sleep(delay) => {}
+            }
+
+            db.connection.send_modify(|connection| connection.attempts += 1);
+            let attempts = db.connection().attempts;
+            let reconnect = async {
+                let client = connect(&config).await?;
+                client.use_ns(&config.namespace).use_db(&config.database).await?;
+                anyhow::Ok(client)
+            };
+            let result = match timeout(CONNECT_TIMEOUT, reconnect).await {
+                Ok(result) => result,
+                Err(_) => Err(anyhow::anyhow!("aucune réponse en {} s", CONNECT_TIMEOUT.as_secs())),
+            };
+
+            match result {
+                Ok(client) => {
+                    *db.db.write().unwrap() = client;
+                    db.connection.send_modify(|connection| {
+                        connection.connected = true;
+                        connection.reconnects += 1;
+                    });
+                    tracing::info!(target: "db", "Connexion rétablie (tentative {}).", attempts);
+                    break;
+                }
+                Err(e) => {
+                    delay = (delay * 2).min(RECONNECT_MAX);
+                    tracing::warn!(
+                        target: "db",
+                        "Reconnexion impossible (tentative {}): {}, nouvel essai dans {:.1} s",
+                        attempts,
+                        e,
+                        delay.as_secs_f64()
+                    );
+                }
+            }
+        }
+    }
 }
@@ -0,0 +1,67 @@
+use std::io::Write;
+
+use chrono::SecondsFormat;
+
+use crate::sensors::reader::GpsData;
+
+use super::xml;
+
+/// Trace GPX 1.1, écrite au fil des points: un segment par période avec fix
+pub struct Gpx<W: Write> {
+    out: W,
+    segment: bool,
+    pub points: u64,
+}
+
+impl<W: Write> Gpx<W> {
+    pub fn new(mut out: W, name: &str) -> anyhow::Result<Self> {
+        writeln!(out, r#"<?xml version="1.0" encoding="UTF-8"?>"#)?;
+        writeln!(
+            out,
+            r#"<gpx version="1.1" creator="rc-telemetrie" xmlns="http://www.topografix.com/GPX/1/1">"#
+        )?;
+        writeln!(out, "<metadata><name>{}</name></metadata>", xml(name))?;
+        writeln!(out, "<trk><name>{}</name>", xml(name))?;
+        Ok(Self {
+            out,
+            segment: false,
+            points: 0,
+        })
+    }
+
+    /// Ajoute un échantillon GPS, une perte de fix termine le segment en cours
+    pub fn push(&mut self, gps: &GpsData) -> anyhow::Result<()> {
+        if !gps.fix {
+            return self.close_segment();
+        }
+
+        if !self.segment {
+            writeln!(self.out, "<trkseg>")?;
+            self.segment = true;
+        }
+        writeln!(
+            self.out,
+            r#"<trkpt lat="{:.7}" lon="{:.7}"><time>{}</time></trkpt>"#,
+            gps.latitude,
+            gps.longitude,
+            gps.stamp.utc.to_rfc3339_opts(SecondsFormat::Millis, true)
+        )?;
+        self.points += 1;
+        Ok(())
+    }
+
+    fn close_segment(&mut self) -> anyhow::Result<()> {
+        if std::mem::take(&mut self.segment) {
+            writeln!(self.out, "</trkseg>")?;
+        }
+        Ok(())
+    }
+
+    pub fn finish(mut self) -> anyhow::Result<W> {
+        self.close_segment()?;
+        writeln!(self.out, "</trk>")?;
+        writeln!(self.out, "</gpx>")?;
+        self.out.flush()?;
+        Ok(self.out)
+    }
+}
@@ -0,0 +1,135 @@
+use std::io::Write;
+
+use chrono::SecondsFormat;
+
+use crate::clock::Stamp;
+use crate::sensors::reader::GpsData;
+
+use super::xml;
+
+/// Seuils des couleurs du trajet (km/h)
+const SPEED_STEPS: [f64; 3] = [5.0, 15.0, 30.0];
+
+/// Couleur de chaque tranche de vitesse (aabbggrr): vert, jaune, orange, rouge
+const SPEED_COLORS: [&str; 4] = ["ff00ff00", "ff00ffff", "ff0080ff", "ff0000ff"];
+
+/// Evénement placé sur la carte
+struct Event {
+    name: String,
+    stamp: Stamp,
+    latitude: f64,
+    longitude: f64,
+}
+
+/// Trajet KML coloré selon la vitesse, écrit au fil des points: une ligne par suite de points
+/// de la même tranche de vitesse. Les événements sont ajoutés à la fin, dans leur dossier.
+pub struct Kml<W: Write> {
+    out: W,
+    /// Tranche de la ligne en cours et dernier point, None hors fix
+    line: Option<usize>,
+    last: Option<GpsData>,
+    events: Vec<Event>,
+    pub points: u64,
+}
+
+/// Tranche de vitesse d'un point
+fn step(speed_kmh: f64) -> usize {
+    SPEED_STEPS.iter().filter(|threshold| speed_kmh >= **threshold).count()
+}
+
+impl<W: Write> Kml<W> {
+    pub fn new(mut out: W, name: &str) -> anyhow::Result<Self> {
+        writeln!(out, r#"<?xml version="1.0" encoding="UTF-8"?>"#)?;
+        writeln!(out, r#"<kml xmlns="http://www.opengis.net/kml/2.2">"#)?;
+        writeln!(out, "<Document><name>{}</name>", xml(name))?;
+        for (n, color) in SPEED_COLORS.iter().enumerate() {
+            writeln!(
+                out,
+                r#"<Style id="speed{}"><LineStyle><color>{}</color><width>4</width></LineStyle></Style>"#,
+                n, color
+            )?;
+        }
+        writeln!(out, "<Folder><name>Trajet</name>")?;
+        Ok(Self {
+            out,
+            line: None,
+            last: None,
+            events: Vec::new(),
+            points: 0,
+        })
+    }
+
+    /// Ajoute un échantillon GPS, une perte de fix interrompt le trajet
+    pub fn push(&mut self, gps: &GpsData) -> anyhow::Result<()> {
+        if !gps.fix {
+            if self.line.is_some() {
+                self.close_line()?;
+                self.event("Perte du fix GPS", gps.stamp);
+            }
+            return Ok(());
+        }
+
+        let step = step(gps.speed_kmh);
+        if self.line != Some(step) {
+            let previous = self.line.and(self.last);
+            self.close_line()?;
+            write!(
+                self.out,
+                r##"<Placemark><styleUrl>#speed{}</styleUrl><LineString><tessellate>1</tessellate><coordinates>"##,
+                step
+            )?;
+            // La nouvelle ligne part du dernier point de la précédente: trajet continu
+            if let Some(previous) = previous {
+                write!(self.out, "{:.7},{:.7},0 ", previous.longitude, previous.latitude)?;
+            }
+            self.line = Some(step);
+        }
+
+        write!(self.out, "{:.7},{:.7},0 ", gps.longitude, gps.latitude)?;
+        self.last = Some(*gps);
+        self.points += 1;
+        Ok(())
+    }
+
+    fn close_line(&mut self) -> anyhow::Result<()> {
+        if self.line.take().is_some() {
+            writeln!(self.out, "</coordinates></LineString></Placemark>")?;
+        }
+        Ok(())
+    }
+
+    /// Evénement à la dernière position connue, ignoré avant le premier fix
+    pub fn event(&mut self, name: &str, stamp: Stamp) {
+        if let Some(last) = self.last {
+            self.events.push(Event {
+                name: name.to_string(),
+                stamp,
+                latitude: last.latitude,
+                longitude: last.longitude,
+            });
+        }
+    }
+
+    pub fn finish(mut self) -> anyhow::Result<W> {
+        self.close_line()?;
+        writeln!(self.out, "</Folder>")?;
+
+        writeln!(self.out, "<Folder><name>Evénements</name>")?;
+        for event in self.events.iter() {
+            writeln!(
+                self.out,
+                "<Placemark><name>{}</name><TimeStamp><when>{}</when></TimeStamp><Point><coordinates>{:.7},{:.7},0</coordinates></Point></Placemark>",
+                xml(&event.name),
+                event.stamp.utc.to_rfc3339_opts(SecondsFormat::Millis, true),
+                event.longitude,
+                event.latitude
+            )?;
+        }
+        writeln!(self.out, "</Folder>")?;
+
+        writeln!(self.out, "</Document>")?;
+        writeln!(self.out, "</kml>")?;
+        self.out.flush()?;
+        Ok(self.out)
+    }
+}
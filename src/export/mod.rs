@@ -0,0 +1,263 @@
+use std::collections::BTreeMap;
+use std::fs::File;
+use std::io::{BufRead, BufWriter};
+use std::path::PathBuf;
+
+use serde::Deserialize;
+use serde_json::{Map, Value};
+
+use crate::clock::Stamp;
+use crate::csv::table::Table;
+use crate::database::Database;
+use crate::jsonl::file;
+use crate::record::Record;
+use crate::sensors::reader::{AnalogData, GpsData};
+
+pub mod gpx;
+pub mod kml;
+
+use gpx::Gpx;
+use kml::Kml;
+
+/// Format des fichiers exportés
+#[derive(Clone, Copy, Debug, PartialEq, clap::ValueEnum)]
+pub enum Format {
+    /// Trace GPS (GPX 1.1)
+    Gpx,
+    /// Un fichier CSV par type d'échantillon
+    Csv,
+    /// Trajet coloré selon la vitesse et événements (Google Earth)
+    Kml,
+}
+
+/// Origine des enregistrements exportés
+#[derive(Clone, Copy, Debug, PartialEq, clap::ValueEnum)]
+pub enum Source {
+    /// Journal JSON lines: historique complet de l'exécution
+    Journal,
+    /// Base SurrealDB: historique des échantillons et évènements de l'exécution
+    Db,
+}
+
+/// Nombre d'enregistrements lus par requête dans la base
+const PAGE: usize = 500;
+
+/// Paramètres de l'export d'une exécution
+pub struct Options {
+    pub run: String,
+    pub format: Format,
+    /// Dossier de destination
+    pub out: PathBuf,
+    /// Dossier du journal JSON lines
+    pub journal: PathBuf,
+    /// Seuil de l'événement "batterie faible" (V)
+    pub battery_min: f32,
+}
+
+/// Résultat de l'export
+pub struct Summary {
+    pub files: Vec<PathBuf>,
+    pub records: u64,
+    /// Lignes illisibles ignorées
+    pub skipped: u64,
+}
+
+/// Echappe un texte XML
+pub fn xml(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+/// Evènement de l'exécution (table event de la base)
+#[derive(Deserialize)]
+struct Event {
+    message: String,
+    stamp: Stamp,
+}
+
+/// Destination des échantillons selon le format
+enum Output {
+    Gpx(Gpx<BufWriter<File>>),
+    Csv(BTreeMap<String, Table>),
+    Kml {
+        kml: Kml<BufWriter<File>>,
+        /// Batterie déjà sous le seuil (un seul événement par passage sous le seuil)
+        low: bool,
+    },
+}
+
+/// Fichiers de sortie en cours d'écriture et comptes de l'export
+struct Exporter<'a> {
+    options: &'a Options,
+    output: Output,
+    records: u64,
+    skipped: u64,
+}
+
+impl<'a> Exporter<'a> {
+    fn create(options: &'a Options) -> anyhow::Result<Self> {
+        std::fs::create_dir_all(&options.out)?;
+        let create = |extension: &str| -> anyhow::Result<BufWriter<File>> {
+            Ok(BufWriter::new(File::create(options.out.join(format!("{}.{}", options.run, extension)))?))
+        };
+
+        let output = match options.format {
+            Format::Gpx => Output::Gpx(Gpx::new(create("gpx")?, &options.run)?),
+            Format::Csv => Output::Csv(BTreeMap::new()),
+            Format::Kml => Output::Kml {
+                kml: Kml::new(create("kml")?, &options.run)?,
+                low: false,
+            },
+        };
+
+        Ok(Self {
+            options,
+            output,
+            records: 0,
+            skipped: 0,
+        })
+    }
+
+    /// Ajoute un enregistrement, ignoré (et compté) s'il ne correspond pas à son type
+    fn push(&mut self, origin: &str, kind: &str, fields: Map<String, Value>) {
+        if let Err(e) = write(&mut self.output, self.options, kind, fields) {
+            tracing::warn!(target: "export", "{}: ligne ignorée ({})", origin, e);
+            self.skipped += 1;
+            return;
+        }
+        self.records += 1;
+    }
+
+    fn finish(self) -> anyhow::Result<Summary> {
+        let path = |extension: &str| self.options.out.join(format!("{}.{}", self.options.run, extension));
+        let files = match self.output {
+            Output::Gpx(gpx) => {
+                gpx.finish()?;
+                vec![path("gpx")]
+            }
+            Output::Csv(mut tables) => {
+                let mut files = Vec::new();
+                for table in tables.values_mut() {
+                    table.flush()?;
+                    files.extend(table.produced().iter().map(|produced| self.options.out.join(&produced.file)));
+                }
+                files
+            }
+            Output::Kml { kml, .. } => {
+                kml.finish()?;
+                vec![path("kml")]
+            }
+        };
+
+        Ok(Summary {
+            files,
+            records: self.records,
+            skipped: self.skipped,
+        })
+    }
+}
+
+/// Exporte une exécution depuis le journal JSON lines, ligne par ligne: la mémoire utilisée ne
+/// dépend pas de la durée de l'exécution.
+pub fn export(options: &Options) -> anyhow::Result<Summary> {
+    let files = file::run_files(&options.journal, &options.run).unwrap_or_default();
+    if files.is_empty() {
+        return Err(anyhow::anyhow!(
+            "Exécution {} introuvable: aucun fichier {}.*.jsonl dans {}",
+            options.run,
+            options.run,
+            options.journal.display()
+        ));
+    }
+
+    let mut exporter = Exporter::create(options)?;
+    for path in files {
+        let origin = path.display().to_string();
+        for line in file::reader(&path)?.lines() {
+            let line = line?;
+            // Dernière ligne incomplète d'un journal non réparé, ou ligne d'une autre version
+            let Ok(Value::Object(mut fields)) = serde_json::from_str::<Value>(&line) else {
+                exporter.skipped += 1;
+                continue;
+            };
+            let kind = match fields.shift_remove("type") {
+                Some(Value::String(kind)) if Record::KINDS.contains(&kind.as_str()) => kind,
+                _ => {
+                    exporter.skipped += 1;
+                    continue;
+                }
+            };
+            fields.shift_remove("run_id");
+
+            exporter.push(&origin, &kind, fields);
+        }
+    }
+
+    exporter.finish()
+}
+
+/// Exporte une exécution depuis la base, par pages de `PAGE` enregistrements: historique des
+/// échantillons de l'exécution (tables <type>_history) et évènements (placemarks du KML, table
+/// "event" du CSV).
+pub async fn export_db(options: &Options, db: &Database) -> anyhow::Result<Summary> {
+    if !db.has_run(&options.run).await? {
+        return Err(anyhow::anyhow!(
+            "Exécution {} introuvable: aucun enregistrement run:⟨{}⟩ dans la base",
+            options.run,
+            options.run
+        ));
+    }
+
+    // Positions d'abord: les évènements du KML sont placés sur le dernier point GPS lu
+    let others = Record::KINDS.into_iter().filter(|kind| *kind != "gps");
+    let mut exporter = Exporter::create(options)?;
+    for kind in ["gps"].into_iter().chain(others).chain(["event"]) {
+        let mut start = 0;
+        loop {
+            let page = db.run_records(kind, &options.run, start, PAGE).await?;
+            for record in page.iter().cloned() {
+                match record {
+                    Value::Object(fields) => exporter.push(kind, kind, fields),
+                    _ => exporter.skipped += 1,
+                }
+            }
+
+            if page.len() < PAGE {
+                break;
+            }
+            start += PAGE;
+        }
+    }
+
+    exporter.finish()
+}
+
+fn write(output: &mut Output, options: &Options, kind: &str, fields: Map<String, Value>) -> anyhow::Result<()> {
+    match output {
+        Output::Gpx(gpx) if kind == "gps" => gpx.push(&serde_json::from_value::<GpsData>(Value::Object(fields))?),
+        Output::Csv(tables) => tables
+            .entry(kind.to_string())
+            .or_insert_with(|| Table::new(&options.out, &format!("{}.{}", options.run, kind), u64::MAX))
+            .write(&fields),
+        Output::Kml { kml, .. } if kind == "gps" => {
+            kml.push(&serde_json::from_value::<GpsData>(Value::Object(fields))?)
+        }
+        Output::Kml { kml, .. } if kind == "event" => {
+            let event = serde_json::from_value::<Event>(Value::Object(fields))?;
+            kml.event(&event.message, event.stamp);
+            Ok(())
+        }
+        Output::Kml { kml, low } if kind == "analog" => {
+            let analog = serde_json::from_value::<AnalogData>(Value::Object(fields))?;
+            let below = analog.battery < options.battery_min;
+            if below && !*low {
+                kml.event(&format!("Batterie faible ({:.2} V)", analog.battery), analog.stamp);
+            }
+            *low = below;
+            Ok(())
+        }
+        _ => Ok(()),
+    }
+}
@@ -0,0 +1,138 @@
+use serde::Serialize;
+
+/// Ecart maximal entre deux mesures de l'IMU intégrées (µs): au-delà (capteur arrêté, application
+/// en pause), l'intervalle n'est pas intégré
+pub const MAX_GAP_US: u64 = 500_000;
+
+/// Attitude estimée, dans le repère du capteur
+#[derive(Clone, Copy, Debug, Default, PartialEq, Serialize)]
+pub struct Attitude {
+    /// Roulis (°)
+    pub roll: f32,
+    /// Tangage (°)
+    pub pitch: f32,
+    /// Lacet (°), de 0 à 360
+    pub yaw: f32,
+    /// Quaternion (w, x, y, z)
+    pub quaternion: [f32; 4],
+}
+
+/// Filtre de Madgwick: le gyroscope est intégré, puis corrigé par descente de gradient vers la
+/// pesanteur (accéléromètre) et le nord magnétique (magnétomètre). Sans champ magnétique, seuls
+/// le roulis et le tangage sont corrigés.
+pub struct Madgwick {
+    /// Gain de la correction (rad/s)
+    beta: f32,
+    /// Quaternion (w, x, y, z)
+    q: [f32; 4],
+}
+
+impl Madgwick {
+    pub fn new(beta: f32) -> Self {
+        Self {
+            beta,
+            q: [1.0, 0.0, 0.0, 0.0],
+        }
+    }
+
+    /// Nouvelle mesure: vitesse angulaire (°/s), accélération (g), champ magnétique (unité
+    /// quelconque, None: non utilisé) et intervalle depuis la mesure précédente (s)
+    pub fn update(&mut self, gyro: (f32, f32, f32), accel: (f32, f32, f32), mag: Option<(f32, f32, f32)>, dt: f32) {
+        let (gx, gy, gz) = (gyro.0.to_radians(), gyro.1.to_radians(), gyro.2.to_radians());
+        let [q0, q1, q2, q3] = self.q;
+
+        // Variation du quaternion due au gyroscope
+        let mut dq = [
+            0.5 * (-q1 * gx - q2 * gy - q3 * gz),
+            0.5 * (q0 * gx + q2 * gz - q3 * gy),
+            0.5 * (q0 * gy - q1 * gz + q3 * gx),
+            0.5 * (q0 * gz + q1 * gy - q2 * gx),
+        ];
+
+        // Accélération nulle (chute libre, capteur absent): gyroscope seul
+        if let Some((ax, ay, az)) = normalize(accel) {
+            let step = match mag.and_then(normalize) {
+                Some(mag) => gradient_marg(self.q, (ax, ay, az), mag),
+                None => gradient_imu(self.q, (ax, ay, az)),
+            };
+            if let Some(step) = normalize4(step) {
+                for (dq, step) in dq.iter_mut().zip(step) {
+                    *dq -= self.beta * step;
+                }
+            }
+        }
+
+        let q = [q0 + dq[0] * dt, q1 + dq[1] * dt, q2 + dq[2] * dt, q3 + dq[3] * dt];
+        if let Some(q) = normalize4(q) {
+            self.q = q;
+        }
+    }
+
+    /// Attitude actuelle
+    pub fn attitude(&self) -> Attitude {
+        let [w, x, y, z] = self.q;
+        let roll = (2.0 * (w * x + y * z)).atan2(1.0 - 2.0 * (x * x + y * y));
+        let pitch = (2.0 * (w * y - z * x)).clamp(-1.0, 1.0).asin();
+        let yaw = (2.0 * (w * z + x * y)).atan2(1.0 - 2.0 * (y * y + z * z));
+
+        Attitude {
+            roll: roll.to_degrees(),
+            pitch: pitch.to_degrees(),
+            yaw: yaw.to_degrees().rem_euclid(360.0),
+            quaternion: self.q,
+        }
+    }
+}
+
+/// Gradient de l'erreur entre la pesanteur attendue et mesurée
+fn gradient_imu(q: [f32; 4], (ax, ay, az): (f32, f32, f32)) -> [f32; 4] {
+    let [q0, q1, q2, q3] = q;
+    let f1 = 2.0 * (q1 * q3 - q0 * q2) - ax;
+    let f2 = 2.0 * (q0 * q1 + q2 * q3) - ay;
+    let f3 = 1.0 - 2.0 * (q1 * q1 + q2 * q2) - az;
+
+    [
+        -2.0 * q2 * f1 + 2.0 * q1 * f2,
+        2.0 * q3 * f1 + 2.0 * q0 * f2 - 4.0 * q1 * f3,
+        -2.0 * q0 * f1 + 2.0 * q3 * f2 - 4.0 * q2 * f3,
+        2.0 * q1 * f1 + 2.0 * q2 * f2,
+    ]
+}
+
+/// Gradient de l'erreur sur la pesanteur et le champ magnétique. Le champ de référence est le
+/// champ mesuré ramené dans le plan horizontal (nord) et la verticale, l'inclinaison magnétique
+/// locale n'a donc pas à être connue.
+fn gradient_marg(q: [f32; 4], accel: (f32, f32, f32), (mx, my, mz): (f32, f32, f32)) -> [f32; 4] {
+    let [q0, q1, q2, q3] = q;
+
+    // Champ mesuré dans le repère terrestre
+    let hx = 2.0 * (mx * (0.5 - q2 * q2 - q3 * q3) + my * (q1 * q2 - q0 * q3) + mz * (q1 * q3 + q0 * q2));
+    let hy = 2.0 * (mx * (q1 * q2 + q0 * q3) + my * (0.5 - q1 * q1 - q3 * q3) + mz * (q2 * q3 - q0 * q1));
+    let bx = (hx * hx + hy * hy).sqrt();
+    let bz = 2.0 * (mx * (q1 * q3 - q0 * q2) + my * (q2 * q3 + q0 * q1) + mz * (0.5 - q1 * q1 - q2 * q2));
+
+    // Erreur sur le champ magnétique: référence (bx, 0, bz) ramenée dans le repère du capteur
+    let f4 = 2.0 * bx * (0.5 - q2 * q2 - q3 * q3) + 2.0 * bz * (q1 * q3 - q0 * q2) - mx;
+    let f5 = 2.0 * bx * (q1 * q2 - q0 * q3) + 2.0 * bz * (q0 * q1 + q2 * q3) - my;
+    let f6 = 2.0 * bx * (q0 * q2 + q1 * q3) + 2.0 * bz * (0.5 - q1 * q1 - q2 * q2) - mz;
+
+    let [s0, s1, s2, s3] = gradient_imu(q, accel);
+    [
+        s0 - 2.0 * bz * q2 * f4 + (-2.0 * bx * q3 + 2.0 * bz * q1) * f5 + 2.0 * bx * q2 * f6,
+        s1 + 2.0 * bz * q3 * f4 + (2.0 * bx * q2 + 2.0 * bz * q0) * f5 + (2.0 * bx * q3 - 4.0 * bz * q1) * f6,
+        s2 + (-4.0 * bx * q2 - 2.0 * bz * q0) * f4 + (2.0 * bx * q1 + 2.0 * bz * q3) * f5 + (2.0 * bx * q0 - 4.0 * bz * q2) * f6,
+        s3 + (-4.0 * bx * q3 + 2.0 * bz * q1) * f4 + (-2.0 * bx * q0 + 2.0 * bz * q2) * f5 + 2.0 * bx * q1 * f6,
+    ]
+}
+
+/// Vecteur unitaire, None: vecteur nul
+fn normalize((x, y, z): (f32, f32, f32)) -> Option<(f32, f32, f32)> {
+    let norm = (x * x + y * y + z * z).sqrt();
+    (norm > f32::EPSILON && norm.is_finite()).then(|| (x / norm, y / norm, z / norm))
+}
+
+/// Quaternion unitaire, None: quaternion nul
+fn normalize4(q: [f32; 4]) -> Option<[f32; 4]> {
+    let norm = q.iter().map(|v| v * v).sum::<f32>().sqrt();
+    (norm > f32::EPSILON && norm.is_finite()).then(|| q.map(|v| v / norm))
+}
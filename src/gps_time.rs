@@ -0,0 +1,68 @@
+use std::time::Duration;
+
+use chrono::{DateTime, Utc};
+
+use crate::sensors::reader::GpsData;
+
+/// Numéro de la capacité CAP_SYS_TIME (linux/capability.h)
+const CAP_SYS_TIME: u32 = 25;
+
+/// Heure GPS utilisable: fix et dernière trame RMC active
+pub fn gps_time(gps: &GpsData) -> Option<DateTime<Utc>> {
+    gps.time.filter(|_| gps.fix && gps.valid)
+}
+
+/// Ecart à appliquer à l'horloge système pour rejoindre l'heure GPS, None: inférieur au seuil
+pub fn offset(gps: DateTime<Utc>, system: DateTime<Utc>, threshold: Duration) -> Option<chrono::Duration> {
+    let offset = gps - system;
+    let magnitude = offset.to_std().or_else(|_| (-offset).to_std()).ok()?;
+
+    (magnitude > threshold).then_some(offset)
+}
+
+/// Capacité CAP_SYS_TIME présente dans les capacités effectives (ligne CapEff de /proc/<pid>/status)
+pub fn cap_sys_time(status: &str) -> bool {
+    status
+        .lines()
+        .find_map(|line| line.strip_prefix("CapEff:"))
+        .and_then(|mask| u64::from_str_radix(mask.trim(), 16).ok())
+        .is_some_and(|mask| mask & (1 << CAP_SYS_TIME) != 0)
+}
+
+/// Le processus peut régler l'horloge système
+pub fn can_set_time() -> bool {
+    std::fs::read_to_string("/proc/self/status").is_ok_and(|status| cap_sys_time(&status))
+}
+
+/// Horloge système déjà synchronisée par le noyau (NTP, chrony, systemd-timesyncd)
+#[cfg(target_os = "linux")]
+pub fn synchronized() -> bool {
+    let mut timex: libc::timex = unsafe { std::mem::zeroed() };
+    let state = unsafe { libc::adjtimex(&mut timex) };
+    state >= 0 && state != libc::TIME_ERROR
+}
+
+/// Sans adjtimex, l'horloge est considérée comme non synchronisée
+#[cfg(not(target_os = "linux"))]
+pub fn synchronized() -> bool {
+    false
+}
+
+/// Règle l'horloge système (CLOCK_REALTIME) à l'heure donnée
+#[cfg(target_os = "linux")]
+pub fn set_system_time(time: DateTime<Utc>) -> anyhow::Result<()> {
+    let spec = libc::timespec {
+        tv_sec: time.timestamp() as libc::time_t,
+        tv_nsec: time.timestamp_subsec_nanos() as libc::c_long,
+    };
+    if unsafe { libc::clock_settime(libc::CLOCK_REALTIME, &spec) } != 0 {
+        return Err(std::io::Error::last_os_error().into());
+    }
+
+    Ok(())
+}
+
+#[cfg(not(target_os = "linux"))]
+pub fn set_system_time(_time: DateTime<Utc>) -> anyhow::Result<()> {
+    Err(anyhow::anyhow!("réglage de l'horloge système non pris en charge sur ce système"))
+}
@@ -0,0 +1,83 @@
+use serde_json::Value;
+
+use crate::record::Record;
+
+/// Chemin du canal Grafana Live d'un type d'échantillon, après stream/<préfixe>/
+pub fn measurement(vehicle: &str, kind: &str) -> String {
+    format!("{}/{}", vehicle, kind)
+}
+
+/// Champs numériques d'un échantillon, aplatis (ex: "angles_0", "raw_2"). L'horodatage est
+/// retiré (heure de la ligne), les booléens valent 0 ou 1, les autres valeurs sont ignorées.
+pub fn fields(record: &Record) -> anyhow::Result<Vec<(String, f64)>> {
+    fn walk(prefix: &str, value: &Value, fields: &mut Vec<(String, f64)>) {
+        let key = |name: &str| {
+            if prefix.is_empty() {
+                name.to_string()
+            } else {
+                format!("{}_{}", prefix, name)
+            }
+        };
+
+        match value {
+            Value::Object(map) => {
+                for (name, value) in map {
+                    if prefix.is_empty() && name == "stamp" {
+                        continue;
+                    }
+                    walk(&key(name), value, fields);
+                }
+            }
+            Value::Array(values) => {
+                for (i, value) in values.iter().enumerate() {
+                    walk(&key(&i.to_string()), value, fields);
+                }
+            }
+            Value::Number(number) => {
+                if let Some(number) = number.as_f64().filter(|number| number.is_finite()) {
+                    fields.push((prefix.to_string(), number));
+                }
+            }
+            Value::Bool(flag) => fields.push((prefix.to_string(), if *flag { 1.0 } else { 0.0 })),
+            Value::String(_) | Value::Null => {}
+        }
+    }
+
+    let mut fields = Vec::new();
+    walk("", &serde_json::to_value(record)?, &mut fields);
+    Ok(fields)
+}
+
+/// Echappe un nom (mesure ou champ) du protocole Influx
+fn escape(name: &str, special: &[char]) -> String {
+    let mut escaped = String::with_capacity(name.len());
+    for c in name.chars() {
+        if c == '\\' || special.contains(&c) {
+            escaped.push('\\');
+        }
+        escaped.push(c);
+    }
+    escaped
+}
+
+/// Ligne au protocole Influx attendu par Grafana Live: <mesure> champ=valeur,... <heure en ns>.
+/// None si l'échantillon n'a aucun champ numérique.
+pub fn line(vehicle: &str, record: &Record) -> anyhow::Result<Option<String>> {
+    let fields = fields(record)?;
+    if fields.is_empty() {
+        return Ok(None);
+    }
+
+    let fields: Vec<String> = fields
+        .iter()
+        .map(|(name, value)| format!("{}={}", escape(name, &[',', '=', ' ']), value))
+        .collect();
+    let time = record.stamp().utc.timestamp_nanos_opt().unwrap_or_default();
+
+    Ok(Some(format!(
+        "{} {} {}",
+        escape(&measurement(vehicle, record.kind()), &[',', ' ']),
+        fields.join(","),
+        time
+    )))
+}
@@ -0,0 +1,207 @@
+use std::collections::BTreeMap;
+use std::time::{Duration, Instant};
+
+use futures::{SinkExt, StreamExt};
+use tokio::sync::broadcast::error::RecvError;
+use tokio::time::{sleep, timeout};
+use tokio_tungstenite::tungstenite::client::IntoClientRequest;
+use tokio_tungstenite::tungstenite::http::{header, HeaderValue, StatusCode};
+use tokio_tungstenite::tungstenite::{Error, Message};
+use tokio_util::sync::CancellationToken;
+
+use crate::config::GrafanaConfig;
+use crate::writer::{Record, Writer};
+
+pub mod line;
+
+/// Délai initial avant une nouvelle connexion
+const RETRY_MIN: Duration = Duration::from_secs(1);
+
+/// Délai maximum entre deux connexions
+const RETRY_MAX: Duration = Duration::from_secs(60);
+
+/// Durée maximale d'un envoi, la connexion est considérée perdue au-delà
+const SEND_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Intervalle du résumé des échantillons non envoyés
+const DROP_SUMMARY: Duration = Duration::from_secs(30);
+
+/// Adresse WebSocket de push de Grafana Live: <url>/api/live/push/<préfixe>
+fn push_url(config: &GrafanaConfig) -> String {
+    let url = config.url.trim_end_matches('/');
+    let url = if let Some(rest) = url.strip_prefix("https://") {
+        format!("wss://{}", rest)
+    } else if let Some(rest) = url.strip_prefix("http://") {
+        format!("ws://{}", rest)
+    } else {
+        url.to_string()
+    };
+
+    format!("{}/api/live/push/{}", url, config.prefix)
+}
+
+/// Limite la fréquence d'envoi sur chaque canal
+struct RateLimiter {
+    interval: Option<Duration>,
+    last: BTreeMap<&'static str, Instant>,
+}
+
+impl RateLimiter {
+    fn new(rate: f64) -> Self {
+        Self {
+            interval: (rate > 0.0).then(|| Duration::from_secs_f64(1.0 / rate)),
+            last: BTreeMap::new(),
+        }
+    }
+
+    /// Vrai si l'échantillon peut être envoyé maintenant
+    fn allow(&mut self, kind: &'static str, now: Instant) -> bool {
+        let Some(interval) = self.interval else {
+            return true;
+        };
+
+        match self.last.get(kind) {
+            Some(last) if now.duration_since(*last) < interval => false,
+            _ => {
+                self.last.insert(kind, now);
+                true
+            }
+        }
+    }
+}
+
+/// Envoi des échantillons vers Grafana Live (canaux stream/<préfixe>/<véhicule>/<type>).
+/// Une connexion perdue ou refusée est reprise avec un délai croissant; les échantillons
+/// diffusés entre-temps ne sont pas conservés.
+pub async fn run(config: GrafanaConfig, vehicle: String, writer: Writer, token: CancellationToken) {
+    for kind in config.records.iter() {
+        if !Record::KINDS.contains(&kind.as_str()) {
+            tracing::warn!(target: "grafana", "Type d'échantillon inconnu ignoré: {}", kind);
+        }
+    }
+
+    let url = push_url(&config);
+    let mut delay = RETRY_MIN;
+
+    while !token.is_cancelled() {
+        match connect(&config, &url).await {
+            Ok(socket) => {
+                tracing::info!(
+                    target: "grafana",
+                    "Connecté à {} (stream/{}/{}/...)",
+                    url, config.prefix, vehicle
+                );
+                delay = RETRY_MIN;
+
+                match push(&config, &vehicle, &writer, socket, &token).await {
+                    Ok(()) => break,
+                    Err(e) => tracing::error!(target: "grafana", "Connexion perdue: {}", e),
+                }
+            }
+            Err(e) => {
+                tracing::warn!(
+                    target: "grafana",
+                    "Connexion impossible ({}), nouvel essai dans {}s.",
+                    e,
+                    delay.as_secs()
+                );
+                tokio::select! {
+                    _ = token.cancelled() => break,
+                    _ = sleep(delay) => {}
+                }
+                delay = (delay * 2).min(RETRY_MAX);
+            }
+        }
+    }
+
+    tracing::info!(target: "grafana", "Arrêt.");
+}
+
+type Socket = tokio_tungstenite::WebSocketStream<tokio_tungstenite::MaybeTlsStream<tokio::net::TcpStream>>;
+
+async fn connect(config: &GrafanaConfig, url: &str) -> anyhow::Result<Socket> {
+    let mut request = url.into_client_request()?;
+    let token = if config.token.is_empty() {
+        std::env::var("GRAFANA_TOKEN").unwrap_or_default()
+    } else {
+        config.token.clone()
+    };
+    if !token.is_empty() {
+        request.headers_mut().insert(
+            header::AUTHORIZATION,
+            HeaderValue::from_str(&format!("Bearer {}", token))?,
+        );
+    }
+
+    match timeout(SEND_TIMEOUT, tokio_tungstenite::connect_async(request)).await {
+        Err(_) => Err(anyhow::anyhow!("pas de réponse")),
+        Ok(Ok((socket, _))) => Ok(socket),
+        Ok(Err(Error::Http(response)))
+            if matches!(response.status(), StatusCode::UNAUTHORIZED | StatusCode::FORBIDDEN) =>
+        {
+            Err(anyhow::anyhow!("authentification refusée ({})", response.status()))
+        }
+        Ok(Err(e)) => Err(e.into()),
+    }
+}
+
+/// Envoie les échantillons jusqu'à l'arrêt (Ok) ou une erreur de la connexion
+async fn push(
+    config: &GrafanaConfig,
+    vehicle: &str,
+    writer: &Writer,
+    socket: Socket,
+    token: &CancellationToken,
+) -> anyhow::Result<()> {
+    let (mut sender, mut receiver) = socket.split();
+    let mut records = writer.subscribe();
+    let mut limiter = RateLimiter::new(config.max_rate);
+    let mut dropped = 0u64;
+    let mut last_summary = Instant::now();
+
+    loop {
+        let record = tokio::select! {
+            _ = token.cancelled() => {
+                let _ = sender.send(Message::Close(None)).await;
+                return Ok(());
+            }
+            message = receiver.next() => match message {
+                Some(Ok(Message::Close(frame))) => {
+                    return Err(anyhow::anyhow!("fermée par le serveur ({:?})", frame.map(|f| f.reason)));
+                }
+                Some(Ok(_)) => continue,
+                Some(Err(e)) => return Err(e.into()),
+                None => return Err(anyhow::anyhow!("fermée par le serveur")),
+            },
+            record = records.recv() => match record {
+                Ok(record) => record,
+                Err(RecvError::Lagged(count)) => {
+                    dropped += count;
+                    continue;
+                }
+                Err(RecvError::Closed) => return Ok(()),
+            },
+        };
+
+        let kind = record.kind();
+        if !config.records.iter().any(|k| k == kind) {
+            continue;
+        }
+        if !limiter.allow(kind, Instant::now()) {
+            continue;
+        }
+
+        let Ok(Some(line)) = line::line(vehicle, &record) else {
+            continue;
+        };
+        timeout(SEND_TIMEOUT, sender.send(Message::Text(line)))
+            .await
+            .map_err(|_| anyhow::anyhow!("envoi bloqué depuis {}s", SEND_TIMEOUT.as_secs()))??;
+
+        if dropped > 0 && last_summary.elapsed() >= DROP_SUMMARY {
+            tracing::error!(target: "grafana", "{} échantillon(s) non envoyé(s) (envoi trop lent).", dropped);
+            dropped = 0;
+            last_summary = Instant::now();
+        }
+    }
+}
@@ -0,0 +1,162 @@
+use std::net::SocketAddr;
+
+use tokio::sync::{broadcast, mpsc};
+use tokio_stream::wrappers::ReceiverStream;
+use tokio_util::sync::CancellationToken;
+use tonic::transport::Server;
+use tonic::{Request, Response, Status, Streaming};
+
+use crate::actuators::arbiter::Commands;
+use crate::actuators::Control;
+use crate::clock::Clock;
+use crate::config::GrpcConfig;
+use crate::proto;
+use crate::writer::{Record, Writer};
+
+// Serveur généré par build.rs
+include!(concat!(env!("OUT_DIR"), "/rc_telemetrie.Telemetry.rs"));
+
+use telemetry_server::{Telemetry, TelemetryServer};
+
+/// Echantillons en attente par client, un client trop lent perd les suivants
+const STREAM_QUEUE: usize = 64;
+
+struct Service {
+    writer: Writer,
+    commands: Commands,
+    clock: Clock,
+    vehicle: String,
+    run: String,
+    token: CancellationToken,
+}
+
+#[tonic::async_trait]
+impl Telemetry for Service {
+    type StreamTelemetryStream = ReceiverStream<Result<proto::Record, Status>>;
+
+    async fn stream_telemetry(
+        &self,
+        request: Request<proto::StreamRequest>,
+    ) -> Result<Response<Self::StreamTelemetryStream>, Status> {
+        let kinds = request.into_inner().records;
+        if let Some(kind) = kinds.iter().find(|kind| !Record::KINDS.contains(&kind.as_str())) {
+            return Err(Status::invalid_argument(format!(
+                "Type inconnu: {} (types: {})",
+                kind,
+                Record::KINDS.join(", ")
+            )));
+        }
+
+        let (sender, receiver) = mpsc::channel(STREAM_QUEUE);
+        let mut records = self.writer.subscribe();
+        let token = self.token.clone();
+        tokio::spawn(async move {
+            loop {
+                let record = tokio::select! {
+                    _ = token.cancelled() => break,
+                    _ = sender.closed() => break,
+                    record = records.recv() => record,
+                };
+
+                match record {
+                    Ok(record) => {
+                        if !kinds.is_empty() && !kinds.iter().any(|kind| kind == record.kind()) {
+                            continue;
+                        }
+                        if let Err(mpsc::error::TrySendError::Closed(_)) = sender.try_send(Ok(record.into())) {
+                            break;
+                        }
+                    }
+                    Err(broadcast::error::RecvError::Lagged(_)) => continue,
+                    Err(broadcast::error::RecvError::Closed) => break,
+                }
+            }
+        });
+
+        Ok(Response::new(ReceiverStream::new(receiver)))
+    }
+
+    async fn get_status(&self, _: Request<proto::StatusRequest>) -> Result<Response<proto::Status>, Status> {
+        let status = self.writer.status();
+
+        Ok(Response::new(proto::Status {
+            vehicle: self.vehicle.clone(),
+            run: self.run.clone(),
+            version: env!("CARGO_PKG_VERSION").to_string(),
+            uptime_us: self.clock.stamp().mono_us,
+            imu: Some((&status.imu).into()),
+            mag: Some((&status.mag).into()),
+            analog: Some((&status.analog).into()),
+            gps: Some((&status.gps).into()),
+            power: Some((&status.power).into()),
+            baro: Some((&status.baro).into()),
+            encoder: Some((&status.encoder).into()),
+            range: Some((&status.range).into()),
+        }))
+    }
+
+    async fn send_control(
+        &self,
+        request: Request<Streaming<proto::Control>>,
+    ) -> Result<Response<proto::ControlSummary>, Status> {
+        let mut stream = request.into_inner();
+        let mut summary = proto::ControlSummary::default();
+
+        while let Some(control) = stream.message().await? {
+            let control = Control {
+                steer: control.steer,
+                speed: control.speed,
+            };
+
+            // Même arbitrage que les autres sources (validation, homme mort)
+            match self.commands.submit("grpc", control) {
+                Ok(_) => summary.accepted += 1,
+                Err(e) => {
+                    summary.rejected += 1;
+                    summary.error = e.to_string();
+                }
+            }
+        }
+
+        Ok(Response::new(summary))
+    }
+}
+
+/// Serveur gRPC: flux de télémétrie, état des capteurs et commandes des actionneurs
+pub async fn run(
+    config: GrpcConfig,
+    writer: Writer,
+    commands: Commands,
+    clock: Clock,
+    vehicle: String,
+    run: String,
+    token: CancellationToken,
+) {
+    let address: SocketAddr = match config.listen.parse() {
+        Ok(address) => address,
+        Err(e) => {
+            tracing::error!(target: "grpc", "Adresse d'écoute {} invalide: {}", config.listen, e);
+            return;
+        }
+    };
+
+    let service = Service {
+        writer,
+        commands,
+        clock,
+        vehicle,
+        run,
+        token: token.clone(),
+    };
+
+    tracing::info!(target: "grpc", "Serveur sur {}", address);
+    let result = Server::builder()
+        .add_service(TelemetryServer::new(service))
+        .serve_with_shutdown(address, token.cancelled_owned())
+        .await;
+
+    match result {
+        Ok(_) => tracing::info!(target: "grpc", "Arrêt."),
+        Err(e) => tracing::error!(target: "grpc", "Arrêt du serveur: {}", e),
+    }
+}
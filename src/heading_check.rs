@@ -0,0 +1,137 @@
+use serde::Serialize;
+
+use crate::config::{HeadingCheckConfig, Severity};
+
+/// Ecart maximal entre deux comparaisons intégrées (µs): au-delà (GPS perdu, arrêt), le lissage
+/// repart de la nouvelle mesure
+pub const MAX_GAP_US: u64 = 5_000_000;
+
+/// Ecart entre la route GPS et le cap du magnétomètre
+#[derive(Clone, Copy, Debug, Default, PartialEq, Serialize)]
+pub struct HeadingError {
+    /// Ecart mesuré (°, de -180 à 180): route GPS - cap du magnétomètre
+    pub error: f32,
+    /// Ecart lissé (°, de -180 à 180)
+    pub smoothed: f32,
+    /// Vitesse GPS (m/s)
+    pub speed_ms: f32,
+}
+
+/// Changement de l'état de la calibration du magnétomètre
+#[derive(Debug, PartialEq)]
+pub enum Transition {
+    /// Ecart lissé au-delà du seuil pendant `persist_s` en mouvement
+    Suspect { smoothed: f32 },
+    /// Ecart lissé revenu sous la moitié du seuil
+    Cleared,
+}
+
+impl Transition {
+    /// Calibration suspecte après la transition
+    pub fn suspect(&self) -> bool {
+        matches!(self, Transition::Suspect { .. })
+    }
+
+    pub fn severity(&self) -> Severity {
+        match self {
+            Transition::Suspect { .. } => Severity::Warning,
+            Transition::Cleared => Severity::Info,
+        }
+    }
+
+    pub fn message(&self) -> String {
+        match self {
+            Transition::Suspect { smoothed } => {
+                format!("Cap du magnétomètre écarté de {:.0}° de la route GPS: calibration suspecte", smoothed)
+            }
+            Transition::Cleared => "Cap du magnétomètre cohérent avec la route GPS".to_string(),
+        }
+    }
+}
+
+/// Angle ramené entre -180 et 180°
+pub fn wrap_180(angle: f32) -> f32 {
+    let angle = (angle + 180.0).rem_euclid(360.0) - 180.0;
+    if angle == -180.0 {
+        180.0
+    } else {
+        angle
+    }
+}
+
+/// Comparaison de la route GPS (définie seulement en mouvement) et du cap du magnétomètre. L'écart
+/// est lissé (moyenne exponentielle sur `smoothing_s`, en tenant compte du passage par 0/360°);
+/// un écart persistant signale une calibration suspecte. A l'arrêt, aucune comparaison n'est faite
+/// et la durée de l'écart n'est pas comptée.
+pub struct HeadingCheck {
+    min_speed_ms: f32,
+    smoothing_s: f32,
+    max_error_deg: f32,
+    persist_us: u64,
+    /// Dernière comparaison: horodatage monotone (µs), écart lissé
+    last: Option<(u64, f32)>,
+    /// Durée en mouvement avec un écart lissé au-delà du seuil (µs)
+    above_us: u64,
+    suspect: bool,
+}
+
+impl HeadingCheck {
+    pub fn new(config: &HeadingCheckConfig) -> Self {
+        Self {
+            min_speed_ms: config.min_speed_ms,
+            smoothing_s: config.smoothing_s,
+            max_error_deg: config.max_error_deg,
+            persist_us: config.persist_s * 1_000_000,
+            last: None,
+            above_us: 0,
+            suspect: false,
+        }
+    }
+
+    /// Nouvelle route GPS (°, vitesse en m/s) et cap du magnétomètre (°) à l'instant `mono_us`.
+    /// None à l'arrêt ou sous la vitesse minimale: la route n'a pas de sens.
+    pub fn update(
+        &mut self,
+        course: f32,
+        speed_ms: f32,
+        heading: f32,
+        mono_us: u64,
+    ) -> Option<(HeadingError, Option<Transition>)> {
+        if !(speed_ms >= self.min_speed_ms && course.is_finite() && heading.is_finite()) {
+            return None;
+        }
+
+        let error = wrap_180(course - heading);
+        let (smoothed, dt_us) = match self.last {
+            Some((last_us, smoothed)) if mono_us.saturating_sub(last_us) <= MAX_GAP_US => {
+                let dt_us = mono_us.saturating_sub(last_us);
+                let alpha = 1.0 - (-(dt_us as f32 / 1e6) / self.smoothing_s).exp();
+                (wrap_180(smoothed + alpha * wrap_180(error - smoothed)), dt_us)
+            }
+            _ => (error, 0),
+        };
+        self.last = Some((mono_us, smoothed));
+
+        let sample = HeadingError {
+            error,
+            smoothed,
+            speed_ms,
+        };
+
+        let transition = if smoothed.abs() > self.max_error_deg {
+            self.above_us += dt_us;
+            (!self.suspect && self.above_us >= self.persist_us).then(|| {
+                self.suspect = true;
+                Transition::Suspect { smoothed }
+            })
+        } else {
+            self.above_us = 0;
+            (self.suspect && smoothed.abs() < self.max_error_deg / 2.0).then(|| {
+                self.suspect = false;
+                Transition::Cleared
+            })
+        };
+
+        Some((sample, transition))
+    }
+}
@@ -0,0 +1,166 @@
+use std::collections::BTreeMap;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use axum::extract::{Path, Query, State};
+use axum::http::StatusCode;
+use axum::response::{IntoResponse, Response};
+use axum::Json;
+use serde::{Deserialize, Serialize};
+use tokio::sync::broadcast::{self, error::RecvError};
+use tokio_util::sync::CancellationToken;
+
+use crate::http::history::Ring;
+use crate::http::status::{HealthStatus, Status};
+use crate::http::AppState;
+use crate::sensors::can::CanData;
+use crate::writer::{Latest, Record};
+
+/// Durée d'historique retournée par défaut (secondes)
+const DEFAULT_HISTORY_S: f64 = 60.0;
+
+/// Derniers échantillons de chaque table, en mémoire (indépendant de la base)
+pub struct History {
+    tables: BTreeMap<&'static str, Mutex<Ring<Record>>>,
+}
+
+impl History {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            tables: Record::KINDS
+                .iter()
+                .map(|table| (*table, Mutex::new(Ring::new(capacity))))
+                .collect(),
+        }
+    }
+
+    fn push(&self, record: Record) {
+        if let Some(ring) = self.tables.get(record.kind()) {
+            ring.lock().unwrap().push(record.stamp().mono_us, record);
+        }
+    }
+
+    /// Echantillons conservés et capacité de chaque table
+    pub fn usage(&self) -> Vec<(&'static str, usize, usize)> {
+        self.tables
+            .iter()
+            .map(|(table, ring)| {
+                let ring = ring.lock().unwrap();
+                (*table, ring.len(), ring.capacity())
+            })
+            .collect()
+    }
+
+    fn since(&self, table: &str, since_us: u64) -> Option<Vec<Record>> {
+        self.tables
+            .get(table)
+            .map(|ring| ring.lock().unwrap().since(since_us))
+    }
+}
+
+/// Alimente l'historique depuis la diffusion de l'écrivain
+pub async fn feed(history: Arc<History>, mut records: broadcast::Receiver<Record>, token: CancellationToken) {
+    loop {
+        tokio::select! {
+            _ = token.cancelled() => return,
+            record = records.recv() => match record {
+                Ok(record) => history.push(record),
+                Err(RecvError::Lagged(_)) => continue,
+                Err(RecvError::Closed) => return,
+            },
+        }
+    }
+}
+
+/// Dernière valeur d'une table
+pub fn latest_record(latest: &Latest, table: &str) -> Option<Record> {
+    match table {
+        "imu" => Some(Record::Imu(latest.data.imu)),
+        "mag" => Some(Record::Mag(latest.data.mag)),
+        "analog" => Some(Record::Analog(latest.data.analog)),
+        "power" => Some(Record::Power(latest.data.power)),
+        "baro" => Some(Record::Baro(latest.data.baro)),
+        "encoder" => Some(Record::Encoder(latest.data.encoder)),
+        "range" => Some(Record::Range(latest.data.range)),
+        "gps" => Some(Record::Gps(latest.data.gps)),
+        "modem" => Some(Record::Modem(latest.modem)),
+        "satellites" => Some(Record::Satellites(latest.data.satellites)),
+        _ => None,
+    }
+}
+
+fn unknown_table(table: &str) -> (StatusCode, String) {
+    (StatusCode::NOT_FOUND, format!("Table inconnue: {} (tables: {})", table, Record::KINDS.join(", ")))
+}
+
+/// Dernières valeurs des capteurs et des messages CAN
+#[derive(Serialize)]
+pub struct LatestResponse {
+    #[serde(flatten)]
+    latest: Latest,
+    can: BTreeMap<String, CanData>,
+}
+
+/// GET /api/latest
+pub async fn latest(State(state): State<AppState>) -> Json<LatestResponse> {
+    Json(LatestResponse {
+        latest: state.writer.latest(),
+        can: state.writer.latest_can(),
+    })
+}
+
+/// GET /api/latest/<table> (ou /api/latest/can: dernier message de chaque type)
+pub async fn latest_table(State(state): State<AppState>, Path(table): Path<String>) -> Response {
+    if table == "can" {
+        return Json(state.writer.latest_can()).into_response();
+    }
+
+    match latest_record(&state.writer.latest(), &table) {
+        Some(record) => Json(record).into_response(),
+        None => unknown_table(&table).into_response(),
+    }
+}
+
+/// GET /status: position, attitude, batterie, cap, modem et contrôle
+pub async fn status(State(state): State<AppState>) -> Json<Status> {
+    let latest = state.writer.latest();
+    let control = state.commands.state().borrow().clone();
+    Json(Status::new(&latest.data, &latest.modem, &control, *state.commands.armed().borrow()))
+}
+
+/// GET /health: santé des capteurs, de chaque composant et connexion à la base
+pub async fn health(State(state): State<AppState>) -> Json<HealthStatus> {
+    let (health, stale) = state.writer.health();
+    Json(HealthStatus {
+        health,
+        stale,
+        db_connected: state.writer.stats().connection.connected,
+        components: state.health.borrow().as_ref().map(|snapshot| snapshot.components.clone()),
+    })
+}
+
+#[derive(Deserialize)]
+pub struct HistoryQuery {
+    seconds: Option<f64>,
+}
+
+/// GET /api/history/<table>?seconds=60
+pub async fn history(
+    State(state): State<AppState>,
+    Path(table): Path<String>,
+    Query(query): Query<HistoryQuery>,
+) -> Result<Json<Vec<Record>>, (StatusCode, String)> {
+    let seconds = query.seconds.unwrap_or(DEFAULT_HISTORY_S);
+    if seconds.is_nan() || seconds < 0.0 {
+        return Err((StatusCode::BAD_REQUEST, format!("Durée invalide: {}", seconds)));
+    }
+
+    let window = Duration::try_from_secs_f64(seconds).unwrap_or(Duration::MAX);
+    let since = state.clock.elapsed().saturating_sub(window);
+
+    state
+        .history
+        .since(&table, since.as_micros() as u64)
+        .map(Json)
+        .ok_or_else(|| unknown_table(&table))
+}
@@ -0,0 +1,46 @@
+use std::collections::VecDeque;
+
+/// File circulaire bornée, horodatée en temps monotone (microsecondes).
+/// Au-delà de la capacité, l'élément le plus ancien est remplacé.
+pub struct Ring<T> {
+    capacity: usize,
+    items: VecDeque<(u64, T)>,
+}
+
+impl<T: Clone> Ring<T> {
+    pub fn new(capacity: usize) -> Self {
+        let capacity = capacity.max(1);
+        Self {
+            capacity,
+            items: VecDeque::with_capacity(capacity),
+        }
+    }
+
+    pub fn push(&mut self, mono_us: u64, item: T) {
+        if self.items.len() == self.capacity {
+            self.items.pop_front();
+        }
+        self.items.push_back((mono_us, item));
+    }
+
+    pub fn len(&self) -> usize {
+        self.items.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.items.is_empty()
+    }
+
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    /// Eléments horodatés à partir de `since_us`, du plus ancien au plus récent
+    pub fn since(&self, since_us: u64) -> Vec<T> {
+        self.items
+            .iter()
+            .filter(|(mono_us, _)| *mono_us >= since_us)
+            .map(|(_, item)| item.clone())
+            .collect()
+    }
+}
@@ -0,0 +1,240 @@
+use std::fmt::Write;
+use std::sync::atomic::Ordering;
+use std::sync::Arc;
+
+use axum::extract::State;
+use axum::http::{HeaderValue, Method};
+use axum::routing::get;
+use axum::Router;
+use tokio::net::TcpListener;
+use tokio::sync::watch;
+use tokio_util::sync::CancellationToken;
+use tower_http::cors::{AllowOrigin, CorsLayer};
+
+use crate::actuators::arbiter::Commands;
+use crate::alerts::webhook::AlertMetrics;
+use crate::breaker::BreakerState;
+use crate::clock::Clock;
+use crate::config::{Format, HttpConfig};
+use crate::metrics::{DbMetrics, PREFIX};
+use crate::record::Record;
+use crate::sensors::watchdog::Snapshot;
+use crate::writer::Writer;
+
+mod api;
+pub mod history;
+pub mod status;
+mod ws;
+
+/// Compteurs exposés sur /metrics
+#[derive(Default)]
+pub struct Metrics {
+    pub ws: ws::WsMetrics,
+    pub alerts: Arc<AlertMetrics>,
+    /// Ecritures des échantillons dans la base
+    pub db: Arc<DbMetrics>,
+}
+
+/// Etat partagé par les routes
+#[derive(Clone)]
+pub struct AppState {
+    writer: Writer,
+    history: Arc<api::History>,
+    clock: Clock,
+    metrics: Arc<Metrics>,
+    /// Etat du contrôle (/status) et pertes de la liaison (/metrics)
+    commands: Commands,
+    /// Santé des composants publiée par le watchdog (/health)
+    health: watch::Receiver<Option<Snapshot>>,
+    /// Format des trames /ws par défaut
+    ws_format: Format,
+    token: CancellationToken,
+}
+
+/// Serveur HTTP local: télémétrie en direct, sans passer par la base
+pub async fn run(
+    config: HttpConfig,
+    writer: Writer,
+    clock: Clock,
+    metrics: Arc<Metrics>,
+    commands: Commands,
+    health: watch::Receiver<Option<Snapshot>>,
+    token: CancellationToken,
+) {
+    let history = Arc::new(api::History::new(config.history_len));
+    tokio::spawn(api::feed(history.clone(), writer.subscribe(), token.clone()));
+
+    let state = AppState {
+        writer,
+        history,
+        clock,
+        metrics,
+        commands,
+        health,
+        ws_format: config.ws_format,
+        token: token.clone(),
+    };
+
+    let mut app = Router::new()
+        .route("/ws", get(ws::handler))
+        .route("/status", get(api::status))
+        .route("/health", get(api::health))
+        .route("/api/latest", get(api::latest))
+        .route("/api/latest/:table", get(api::latest_table))
+        .route("/api/history/:table", get(api::history));
+    // Compteurs Prometheus, désactivables sans couper le serveur
+    if config.metrics {
+        app = app.route("/metrics", get(metrics_text));
+    }
+    let app = app.with_state(state);
+
+    let app = match cors(&config.cors_origins) {
+        Some(cors) => app.layer(cors),
+        None => app,
+    };
+
+    let listener = match TcpListener::bind(&config.listen).await {
+        Ok(listener) => listener,
+        Err(e) => {
+            tracing::error!(target: "http", "Impossible d'écouter sur {}: {}", config.listen, e);
+            return;
+        }
+    };
+
+    tracing::info!(target: "http", "Serveur sur {}", config.listen);
+    let result = axum::serve(listener, app)
+        .with_graceful_shutdown(token.cancelled_owned())
+        .await;
+
+    match result {
+        Ok(_) => tracing::info!(target: "http", "Arrêt."),
+        Err(e) => tracing::error!(target: "http", "Arrêt du serveur: {}", e),
+    }
+}
+
+/// En-têtes CORS pour les origines configurées (aucun si la liste est vide)
+fn cors(origins: &[String]) -> Option<CorsLayer> {
+    if origins.is_empty() {
+        return None;
+    }
+
+    let allow = if origins.iter().any(|origin| origin == "*") {
+        AllowOrigin::any()
+    } else {
+        let origins = origins.iter().filter_map(|origin| match HeaderValue::from_str(origin) {
+            Ok(value) => Some(value),
+            Err(_) => {
+                tracing::warn!(target: "http", "Origine CORS invalide ignorée: {}", origin);
+                None
+            }
+        });
+        AllowOrigin::list(origins)
+    };
+
+    Some(CorsLayer::new().allow_origin(allow).allow_methods([Method::GET]))
+}
+
+/// Compteurs au format texte Prometheus
+async fn metrics_text(State(state): State<AppState>) -> String {
+    let ws = &state.metrics.ws;
+    let alerts = &state.metrics.alerts;
+    let counters = [
+        ("ws_connections", "gauge", &ws.connections),
+        ("ws_connections_total", "counter", &ws.accepted),
+        ("ws_frames_sent_total", "counter", &ws.sent),
+        ("ws_frames_dropped_total", "counter", &ws.dropped),
+        ("ws_slow_disconnects_total", "counter", &ws.slow),
+        ("alerts_sent_total", "counter", &alerts.sent),
+        ("alerts_failed_total", "counter", &alerts.failed),
+        ("alerts_retries_total", "counter", &alerts.retries),
+        ("alerts_dropped_total", "counter", &alerts.dropped),
+        ("alerts_suppressed_total", "counter", &alerts.suppressed),
+    ];
+
+    let mut text = String::new();
+    for (name, kind, value) in counters {
+        let _ = writeln!(text, "# TYPE {}{} {}", PREFIX, name, kind);
+        let _ = writeln!(text, "{}{} {}", PREFIX, name, value.load(Ordering::Relaxed));
+    }
+
+    let _ = writeln!(text, "# TYPE {}history_samples gauge", PREFIX);
+    for (table, len, capacity) in state.history.usage() {
+        let _ = writeln!(text, "{}history_samples{{table=\"{}\",capacity=\"{}\"}} {}", PREFIX, table, capacity, len);
+    }
+
+    // Coupe-circuit des écritures d'échantillons dans la base
+    let breaker = state.writer.stats().breaker;
+    let _ = writeln!(text, "# TYPE {}db_breaker_state gauge", PREFIX);
+    for candidate in [BreakerState::Closed, BreakerState::Open, BreakerState::HalfOpen] {
+        let active = u8::from(breaker.state == candidate);
+        let _ = writeln!(text, "{}db_breaker_state{{state=\"{}\"}} {}", PREFIX, candidate.name(), active);
+    }
+    for (name, value) in [
+        ("db_breaker_opened_total", breaker.opened),
+        ("db_breaker_diverted_total", breaker.diverted),
+        ("db_breaker_probes_total", breaker.probes),
+    ] {
+        let _ = writeln!(text, "# TYPE {}{} counter", PREFIX, name);
+        let _ = writeln!(text, "{}{} {}", PREFIX, name, value);
+    }
+
+    // Connexion à la base et reconnexions
+    let connection = state.writer.stats().connection;
+    let _ = writeln!(text, "# TYPE {}db_connected gauge", PREFIX);
+    let _ = writeln!(text, "{}db_connected {}", PREFIX, u8::from(connection.connected));
+    for (name, value) in [
+        ("db_reconnect_attempts_total", connection.attempts),
+        ("db_reconnects_total", connection.reconnects),
+    ] {
+        let _ = writeln!(text, "# TYPE {}{} counter", PREFIX, name);
+        let _ = writeln!(text, "{}{} {}", PREFIX, name, value);
+    }
+
+    // Tampon local des échantillons non écrits en base
+    if let Some(spool) = state.writer.stats().spool {
+        for (name, value) in [("spool_records", spool.records), ("spool_bytes", spool.bytes)] {
+            let _ = writeln!(text, "# TYPE {}{} gauge", PREFIX, name);
+            let _ = writeln!(text, "{}{} {}", PREFIX, name, value);
+        }
+        for (name, value) in [
+            ("spool_evicted_total", spool.evicted),
+            ("spool_replayed_total", spool.replayed),
+            ("spool_errors_total", spool.errors),
+        ] {
+            let _ = writeln!(text, "# TYPE {}{} counter", PREFIX, name);
+            let _ = writeln!(text, "{}{} {}", PREFIX, name, value);
+        }
+    }
+
+    // Ecritures des échantillons par table: réussites, échecs et latence
+    state.metrics.db.write(&mut text);
+
+    // Dernières valeurs et âge du dernier échantillon de chaque capteur
+    let latest = state.writer.latest();
+    for (name, value) in [
+        ("battery_volts", latest.data.analog.battery as f64),
+        ("modem_signal_quality", latest.modem.status.quality as f64),
+    ] {
+        let _ = writeln!(text, "# TYPE {}{} gauge", PREFIX, name);
+        let _ = writeln!(text, "{}{} {}", PREFIX, name, value);
+    }
+    let now_us = state.clock.elapsed().as_micros() as u64;
+    let _ = writeln!(text, "# TYPE {}sample_age_seconds gauge", PREFIX);
+    for table in Record::KINDS {
+        let Some(stamp) = api::latest_record(&latest, table).map(|record| record.stamp()) else {
+            continue;
+        };
+        // Aucun échantillon depuis le démarrage: absent
+        if stamp.mono_us == 0 {
+            continue;
+        }
+        let age = now_us.saturating_sub(stamp.mono_us) as f64 / 1e6;
+        let _ = writeln!(text, "{}sample_age_seconds{{sensor=\"{}\"}} {}", PREFIX, table, age);
+    }
+
+    // Pertes de la liaison de contrôle (failsafe)
+    let _ = writeln!(text, "# TYPE {}control_failsafes_total counter", PREFIX);
+    let _ = writeln!(text, "{}control_failsafes_total {}", PREFIX, state.commands.failsafes());
+
+    text
+}
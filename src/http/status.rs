@@ -0,0 +1,84 @@
+use serde::Serialize;
+
+use crate::actuators::arbiter::ControlState;
+use crate::record::ModemData;
+use crate::sensors::reader::Data;
+use crate::sensors::watchdog::{Component, Health};
+
+/// Dernière position GPS
+#[derive(Debug, PartialEq, Serialize)]
+pub struct GpsStatus {
+    pub fix: bool,
+    pub latitude: f64,
+    pub longitude: f64,
+    pub speed_kmh: f64,
+    pub satellites: u8,
+}
+
+/// Commande reçue, sortie appliquée et limite de vitesse active
+#[derive(Debug, PartialEq, Serialize)]
+pub struct ControlStatus {
+    pub armed: bool,
+    pub steer: f64,
+    pub speed: f64,
+    pub output_steer: f64,
+    pub output_speed: f64,
+    /// Vitesse maximale et sa cause, None: aucune limite
+    pub limit: Option<f64>,
+    pub limit_cause: Option<String>,
+}
+
+/// Résumé des dernières valeurs pour le débogage au bord de la piste (GET /status)
+#[derive(Debug, PartialEq, Serialize)]
+pub struct Status {
+    pub gps: GpsStatus,
+    /// Tangage, roulis, lacet (°)
+    pub angles: (f32, f32, f32),
+    pub battery_v: f32,
+    /// Cap magnétique et cap vrai (°)
+    pub heading: f32,
+    pub true_heading: f32,
+    /// Qualité du signal du modem (%)
+    pub modem_quality: u32,
+    pub control: ControlStatus,
+}
+
+impl Status {
+    pub fn new(data: &Data, modem: &ModemData, control: &ControlState, armed: bool) -> Self {
+        Self {
+            gps: GpsStatus {
+                fix: data.gps.fix,
+                latitude: data.gps.latitude,
+                longitude: data.gps.longitude,
+                speed_kmh: data.gps.speed_kmh,
+                satellites: data.gps.satellites,
+            },
+            angles: data.imu.angles,
+            battery_v: data.analog.battery,
+            heading: data.mag.heading,
+            true_heading: data.mag.true_heading,
+            modem_quality: modem.status.quality,
+            control: ControlStatus {
+                armed,
+                steer: control.input.steer,
+                speed: control.input.speed,
+                output_steer: control.output.steer,
+                output_speed: control.output.speed,
+                limit: control.limit.as_ref().map(|limit| limit.max),
+                limit_cause: control.limit.as_ref().map(|limit| limit.cause.clone()),
+            },
+        }
+    }
+}
+
+/// Santé des capteurs et de la connexion à la base (GET /health)
+#[derive(Serialize)]
+pub struct HealthStatus {
+    pub health: Health,
+    /// Capteurs périmés
+    pub stale: Vec<&'static str>,
+    pub db_connected: bool,
+    /// Santé de chaque composant publiée par le watchdog, None: watchdog désactivé ou aucune
+    /// publication
+    pub components: Option<Vec<Component>>,
+}
@@ -0,0 +1,141 @@
+use std::collections::BTreeSet;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::Duration;
+
+use axum::extract::ws::{Message, WebSocket, WebSocketUpgrade};
+use axum::extract::State;
+use axum::response::Response;
+use serde::{Deserialize, Serialize};
+use tokio::sync::broadcast::error::RecvError;
+use tokio::time::timeout;
+
+use crate::clock::Stamp;
+use crate::config::Format;
+use crate::http::AppState;
+use crate::writer::Record;
+
+/// Durée maximale d'envoi d'une trame, un client plus lent est déconnecté
+const SEND_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// Compteurs des clients WebSocket
+#[derive(Default)]
+pub struct WsMetrics {
+    /// Clients connectés
+    pub connections: AtomicU64,
+    pub accepted: AtomicU64,
+    pub sent: AtomicU64,
+    /// Trames perdues par les clients en retard
+    pub dropped: AtomicU64,
+    /// Clients déconnectés car trop lents à recevoir
+    pub slow: AtomicU64,
+}
+
+/// Message d'un client: types d'échantillons voulus (liste vide: tous) et/ou format des trames
+#[derive(Deserialize)]
+struct ClientMessage {
+    subscribe: Option<BTreeSet<String>>,
+    format: Option<Format>,
+}
+
+/// Format des trames suivantes, envoyé à la connexion et à chaque changement (toujours en JSON)
+#[derive(Serialize)]
+struct Negotiation {
+    format: Format,
+}
+
+/// Trame envoyée pour chaque échantillon
+#[derive(Serialize)]
+struct Frame<'a> {
+    #[serde(rename = "type")]
+    kind: &'static str,
+    stamp: Stamp,
+    data: &'a Record,
+}
+
+pub async fn handler(ws: WebSocketUpgrade, State(state): State<AppState>) -> Response {
+    ws.on_upgrade(move |socket| serve(socket, state))
+}
+
+/// Envoie les échantillons au client. La diffusion ne l'attend jamais: un client en retard
+/// perd les trames les plus anciennes, un client bloqué est déconnecté.
+async fn serve(mut socket: WebSocket, state: AppState) {
+    let metrics = &state.metrics.ws;
+    metrics.connections.fetch_add(1, Ordering::Relaxed);
+    metrics.accepted.fetch_add(1, Ordering::Relaxed);
+
+    let mut records = state.writer.subscribe();
+    let mut tables: Option<BTreeSet<String>> = None;
+    let mut format = state.ws_format;
+
+    if socket.send(negotiation(format)).await.is_err() {
+        metrics.connections.fetch_sub(1, Ordering::Relaxed);
+        return;
+    }
+
+    loop {
+        tokio::select! {
+            _ = state.token.cancelled() => break,
+            record = records.recv() => match record {
+                Ok(record) => {
+                    if tables.as_ref().is_some_and(|tables| !tables.contains(record.kind())) {
+                        continue;
+                    }
+
+                    let frame = Frame {
+                        kind: record.kind(),
+                        stamp: record.stamp(),
+                        data: &record,
+                    };
+                    let Ok(bytes) = format.encode(&frame) else {
+                        continue;
+                    };
+                    let message = match format {
+                        Format::Json => Message::Text(String::from_utf8(bytes).unwrap_or_default()),
+                        Format::Msgpack => Message::Binary(bytes),
+                    };
+
+                    match timeout(SEND_TIMEOUT, socket.send(message)).await {
+                        Ok(Ok(_)) => {
+                            metrics.sent.fetch_add(1, Ordering::Relaxed);
+                        }
+                        Ok(Err(_)) => break,
+                        Err(_) => {
+                            tracing::error!(target: "http", "Client WebSocket trop lent, déconnexion.");
+                            metrics.slow.fetch_add(1, Ordering::Relaxed);
+                            break;
+                        }
+                    }
+                }
+                Err(RecvError::Lagged(count)) => {
+                    metrics.dropped.fetch_add(count, Ordering::Relaxed);
+                }
+                Err(RecvError::Closed) => break,
+            },
+            message = socket.recv() => match message {
+                Some(Ok(Message::Text(text))) => match serde_json::from_str::<ClientMessage>(&text) {
+                    Ok(message) => {
+                        if let Some(subscribe) = message.subscribe {
+                            tables = (!subscribe.is_empty()).then_some(subscribe);
+                        }
+                        if let Some(requested) = message.format {
+                            format = requested;
+                            if socket.send(negotiation(format)).await.is_err() {
+                                break;
+                            }
+                        }
+                    }
+                    Err(e) => tracing::error!(target: "http", "Message WebSocket invalide: {}", e),
+                },
+                Some(Ok(Message::Close(_))) | Some(Err(_)) | None => break,
+                Some(Ok(_)) => {}
+            },
+        }
+    }
+
+    metrics.connections.fetch_sub(1, Ordering::Relaxed);
+}
+
+/// Annonce du format des trames
+fn negotiation(format: Format) -> Message {
+    Message::Text(serde_json::to_string(&Negotiation { format }).unwrap_or_default())
+}
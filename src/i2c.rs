@@ -1,7 +1,14 @@
 #![allow(unused)]
 
+use std::collections::{BTreeMap, HashMap};
+use std::sync::{Arc, Mutex};
+
+use anyhow::anyhow;
 use rppal::i2c::I2c;
 
+use crate::config::{I2cConfig, I2cDeviceConfig};
+use crate::sensors::reader::SensorStatus;
+
 pub trait I2CBit {
     fn ecriture_word(&self, command: u8, data: u8) -> anyhow::Result<()> ;
     fn lecture_word(&self, command: u8) -> anyhow::Result<u8> ;
@@ -160,4 +167,216 @@ impl I2CBit for I2c {
         self.block_write(command, &mut buffer).map_err(|e| anyhow::anyhow!(e))?;
         Ok(())
     }
-}
\ No newline at end of file
+}
+/// Bus I2C partagé, avec son éventuel multiplexeur TCA9548
+pub struct Bus {
+    i2c: I2c,
+    mux: Option<Tca9548>,
+}
+
+/// Multiplexeur TCA9548 (8 canaux)
+struct Tca9548 {
+    address: u16,
+    selected: Option<u8>,
+}
+
+/// Accès à un périphérique: un bus et, si besoin, un canal du multiplexeur
+#[derive(Clone)]
+pub struct I2cHandle {
+    bus: Arc<Mutex<Bus>>,
+    channel: Option<u8>,
+}
+
+/// Ensemble des bus I2C déclarés, ouverts à la demande
+pub struct I2cBuses {
+    config: I2cConfig,
+    buses: HashMap<u8, Arc<Mutex<Bus>>>,
+    /// Dernière ouverture de chaque bus: réussite ou erreur, nombre de tentatives
+    status: BTreeMap<u8, SensorStatus>,
+}
+
+/// Prépare les bus I2C (ouverts seulement lorsqu'un capteur en a besoin)
+pub fn init_i2c(config: &I2cConfig) -> I2cBuses {
+    I2cBuses {
+        config: config.clone(),
+        buses: HashMap::new(),
+        status: BTreeMap::new(),
+    }
+}
+
+impl I2cBuses {
+    /// Récupére l'accès à un périphérique, ouvre le bus au premier usage
+    /// (un bus en erreur sera de nouveau ouvert à la prochaine demande)
+    pub fn handle(&mut self, device: &I2cDeviceConfig) -> anyhow::Result<I2cHandle> {
+        let bus = self
+            .bus(device.bus)
+            .map_err(|e| anyhow!("Bus {} indisponible: {}", device.bus, e))?;
+
+        if let Some(channel) = device.channel {
+            if channel > 7 {
+                return Err(anyhow!("Canal de multiplexeur invalide: {}", channel));
+            }
+
+            if bus.lock().map_err(|_| anyhow!("Bus {} verrouillé", device.bus))?.mux.is_none() {
+                return Err(anyhow!("Aucun multiplexeur sur le bus {}", device.bus));
+            }
+        }
+
+        Ok(I2cHandle {
+            bus,
+            channel: device.channel,
+        })
+    }
+
+    /// Inventaire des périphériques présents sur les bus déclarés (et derrière leur multiplexeur)
+    pub fn inventory(&mut self) -> anyhow::Result<String> {
+        let mut inventory = Vec::new();
+
+        for declared in self.config.buses.clone().iter() {
+            let number = declared.number()?;
+            let bus = self.bus(number)?;
+
+            let mut bus = bus.lock().map_err(|_| anyhow!("Bus {} verrouillé", number))?;
+            let mut channels = vec![None];
+            if bus.mux.is_some() {
+                channels.extend((0..8).map(Some));
+            }
+
+            for channel in channels {
+                let found = bus.scan(channel)?;
+                let found: Vec<String> = found.iter().map(|a| format!("{:#04x}", a)).collect();
+                match channel {
+                    None => inventory.push(format!("bus {}: [{}]", number, found.join(" "))),
+                    Some(c) => inventory.push(format!("bus {} canal {}: [{}]", number, c, found.join(" "))),
+                }
+            }
+        }
+
+        Ok(inventory.join(", "))
+    }
+
+    /// Ouvre au démarrage les bus des périphériques donnés, erreur si l'un d'eux est indisponible
+    pub fn open_all(&mut self, devices: &[&I2cDeviceConfig]) -> anyhow::Result<String> {
+        let mut opened: Vec<u8> = Vec::new();
+        for device in devices {
+            if !opened.contains(&device.bus) {
+                self.bus(device.bus)
+                    .map_err(|e| anyhow!("Bus {} indisponible: {}", device.bus, e))?;
+                opened.push(device.bus);
+            }
+        }
+
+        let opened: Vec<String> = opened.iter().map(|bus| bus.to_string()).collect();
+        Ok(format!("Bus ouvert(s): {}", opened.join(", ")))
+    }
+
+    /// Etat de la dernière ouverture de chaque bus, par numéro de bus
+    pub fn status(&self) -> &BTreeMap<u8, SensorStatus> {
+        &self.status
+    }
+
+    /// Bus déjà ouvert, ou ouvert à la demande
+    fn bus(&mut self, number: u8) -> anyhow::Result<Arc<Mutex<Bus>>> {
+        if let Some(bus) = self.buses.get(&number) {
+            return Ok(bus.clone());
+        }
+
+        let opened = self.open(number);
+        let status = self.status.entry(number).or_default();
+        status.attempts += 1;
+        match opened {
+            Ok(bus) => {
+                if status.error.is_some() {
+                    tracing::info!(target: "i2c", "Bus {} de nouveau disponible.", number);
+                }
+                status.available = true;
+                status.error = None;
+                self.buses.insert(number, bus.clone());
+                Ok(bus)
+            }
+            Err(e) => {
+                status.available = false;
+                status.error = Some(e.to_string());
+                Err(e)
+            }
+        }
+    }
+
+    /// Ouvre un bus déclaré dans la configuration
+    fn open(&self, number: u8) -> anyhow::Result<Arc<Mutex<Bus>>> {
+        let mut mux = None;
+        for declared in self.config.buses.iter() {
+            if declared.number().ok() == Some(number) {
+                mux = declared.mux;
+            }
+        }
+
+        tracing::info!(target: "i2c", "Ouverture du bus {} ...", number);
+        let i2c = I2c::with_bus(number)?;
+
+        Ok(Arc::new(Mutex::new(Bus {
+            i2c,
+            mux: mux.map(|address| Tca9548 {
+                address,
+                selected: None,
+            }),
+        })))
+    }
+}
+
+impl Bus {
+    /// Sélectionne le canal du multiplexeur (aucun canal si None)
+    fn select(&mut self, channel: Option<u8>) -> anyhow::Result<()> {
+        let Some(mux) = self.mux.as_mut() else {
+            return Ok(());
+        };
+
+        if mux.selected == channel {
+            return Ok(());
+        }
+
+        let mask = channel.map(|c| 1u8 << c).unwrap_or(0);
+        self.i2c.set_slave_address(mux.address)?;
+        if let Err(e) = self.i2c.write(&[mask]) {
+            // L'état du multiplexeur est inconnu, il sera réécrit à la prochaine transaction
+            mux.selected = None;
+            return Err(anyhow!(e));
+        }
+
+        mux.selected = channel;
+        Ok(())
+    }
+
+    /// Liste les adresses qui répondent sur un canal (lecture d'un octet)
+    fn scan(&mut self, channel: Option<u8>) -> anyhow::Result<Vec<u16>> {
+        self.select(channel)?;
+        let mux = self.mux.as_ref().map(|m| m.address);
+
+        let mut found = Vec::new();
+        for address in 0x03..=0x77u16 {
+            // Le multiplexeur répond sur tous les canaux
+            if channel.is_some() && Some(address) == mux {
+                continue;
+            }
+
+            self.i2c.set_slave_address(address)?;
+            if self.i2c.read(&mut [0]).is_ok() {
+                found.push(address);
+            }
+        }
+
+        Ok(found)
+    }
+}
+
+impl I2cHandle {
+    /// Exécute une transaction sur le périphérique, le bus reste verrouillé durant toute la transaction
+    pub fn transaction<T>(
+        &self,
+        f: impl FnOnce(&mut I2c) -> anyhow::Result<T>,
+    ) -> anyhow::Result<T> {
+        let mut bus = self.bus.lock().map_err(|_| anyhow!("Bus I2C verrouillé"))?;
+        bus.select(self.channel)?;
+        f(&mut bus.i2c)
+    }
+}
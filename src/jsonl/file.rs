@@ -0,0 +1,289 @@
+use std::fs::{self, File, OpenOptions};
+use std::io::{BufRead, BufReader, BufWriter, Read, Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant, SystemTime};
+
+use flate2::read::GzDecoder;
+use flate2::write::GzEncoder;
+use flate2::Compression;
+
+use crate::clock::Clock;
+
+/// Limites des fichiers JSON lines
+#[derive(Clone, Copy)]
+pub struct Limits {
+    /// Taille maximale d'un fichier (octets)
+    pub max_bytes: u64,
+    /// Age maximal d'un fichier, None: pas de rotation par l'âge
+    pub max_age: Option<Duration>,
+    /// Compression gzip des fichiers terminés
+    pub gzip: bool,
+    /// Taille totale maximale du dossier (octets), None: pas de suppression
+    pub budget: Option<u64>,
+}
+
+/// Fichier en cours d'écriture
+struct Current {
+    path: PathBuf,
+    file: BufWriter<File>,
+    bytes: u64,
+    opened: Instant,
+}
+
+/// Fichiers JSON lines d'une exécution: <run>.0000.jsonl, <run>.0001.jsonl, ... Un nouveau fichier
+/// est commencé au-delà de la taille ou de l'âge maximal. Opérations bloquantes (disque), à
+/// appeler depuis un thread dédié.
+pub struct Sink {
+    directory: PathBuf,
+    run: String,
+    limits: Limits,
+    /// Horloge de l'âge des fichiers
+    clock: Clock,
+    current: Option<Current>,
+    /// Numéro du prochain fichier
+    next: u32,
+}
+
+impl Sink {
+    pub fn new(directory: &Path, run: &str, limits: Limits, clock: &Clock) -> anyhow::Result<Self> {
+        fs::create_dir_all(directory)?;
+
+        Ok(Self {
+            directory: directory.to_path_buf(),
+            run: run.to_string(),
+            limits,
+            clock: clock.clone(),
+            current: None,
+            next: 0,
+        })
+    }
+
+    /// Ajoute une ligne (sans saut de ligne final), commence un nouveau fichier si nécessaire
+    pub fn write(&mut self, line: &[u8]) -> anyhow::Result<()> {
+        let length = line.len() as u64 + 1;
+        let rotate = self.current.as_ref().is_some_and(|current| {
+            let full = current.bytes > 0 && current.bytes + length > self.limits.max_bytes;
+            let old = self.limits.max_age.is_some_and(|age| self.clock.since(current.opened) >= age);
+            full || old
+        });
+        if rotate {
+            self.close()?;
+        }
+        if self.current.is_none() {
+            self.open()?;
+        }
+
+        let current = self.current.as_mut().unwrap();
+        current.file.write_all(line)?;
+        current.file.write_all(b"\n")?;
+        current.bytes += length;
+        Ok(())
+    }
+
+    /// Ecrit les données en attente sur le disque. Un fichier trop ancien est terminé, même
+    /// sans nouvelle ligne.
+    pub fn flush(&mut self) -> anyhow::Result<()> {
+        let old = self.current.as_ref().is_some_and(|current| {
+            self.limits.max_age.is_some_and(|age| self.clock.since(current.opened) >= age)
+        });
+        if old {
+            return self.close();
+        }
+
+        if let Some(current) = self.current.as_mut() {
+            current.file.flush()?;
+            current.file.get_ref().sync_data()?;
+        }
+        Ok(())
+    }
+
+    /// Termine le fichier en cours: écriture sur le disque, compression, respect du budget
+    pub fn close(&mut self) -> anyhow::Result<()> {
+        let Some(mut current) = self.current.take() else {
+            return Ok(());
+        };
+
+        current.file.flush()?;
+        current.file.get_ref().sync_data()?;
+        drop(current.file);
+
+        if self.limits.gzip {
+            compress(&current.path)?;
+        }
+
+        self.prune()
+    }
+
+    fn open(&mut self) -> anyhow::Result<()> {
+        let path = self.directory.join(format!("{}.{:04}.jsonl", self.run, self.next));
+        self.next += 1;
+
+        let file = OpenOptions::new().create(true).append(true).open(&path)?;
+        let bytes = file.metadata()?.len();
+        self.current = Some(Current {
+            path,
+            file: BufWriter::new(file),
+            bytes,
+            opened: self.clock.now(),
+        });
+
+        self.prune()
+    }
+
+    /// Supprime les fichiers les plus anciens (hors fichier en cours) au-delà du budget
+    fn prune(&self) -> anyhow::Result<()> {
+        let Some(budget) = self.limits.budget else {
+            return Ok(());
+        };
+
+        let current = self.current.as_ref().map(|current| current.path.as_path());
+        let mut files = files(&self.directory)?;
+        let mut total: u64 = files.iter().map(|(_, _, size)| size).sum();
+
+        files.sort_by(|a, b| (a.1, &a.0).cmp(&(b.1, &b.0)));
+        for (path, _, size) in files {
+            if total <= budget {
+                break;
+            }
+            if Some(path.as_path()) == current {
+                continue;
+            }
+
+            fs::remove_file(&path)?;
+            tracing::info!(target: "jsonl", "Budget dépassé, {} supprimé.", path.display());
+            total -= size;
+        }
+
+        Ok(())
+    }
+}
+
+/// Fichiers JSON lines du dossier (compressés ou non): chemin, date de modification, taille
+fn files(directory: &Path) -> anyhow::Result<Vec<(PathBuf, SystemTime, u64)>> {
+    let mut files = Vec::new();
+    for entry in fs::read_dir(directory)? {
+        let entry = entry?;
+        let name = entry.file_name();
+        let name = name.to_string_lossy();
+        if !(name.ends_with(".jsonl") || name.ends_with(".jsonl.gz")) {
+            continue;
+        }
+
+        let metadata = entry.metadata()?;
+        files.push((entry.path(), metadata.modified()?, metadata.len()));
+    }
+
+    Ok(files)
+}
+
+/// Fichiers d'une exécution dans l'ordre d'écriture (<run>.NNNN.jsonl, compressés ou non).
+/// Après un arrêt pendant une compression, la version compressée (complète) est retenue.
+pub fn run_files(directory: &Path, run: &str) -> anyhow::Result<Vec<PathBuf>> {
+    let mut numbered = Vec::new();
+    for (path, _, _) in files(directory)? {
+        let name = path.file_name().unwrap_or_default().to_string_lossy().into_owned();
+        let Some(rest) = name.strip_prefix(run).and_then(|rest| rest.strip_prefix('.')) else {
+            continue;
+        };
+        let plain = !rest.ends_with(".gz");
+        let number = rest
+            .trim_end_matches(".gz")
+            .strip_suffix(".jsonl")
+            .and_then(|number| number.parse::<u32>().ok());
+        if let Some(number) = number {
+            numbered.push((number, plain, path));
+        }
+    }
+
+    numbered.sort();
+    numbered.dedup_by_key(|(number, _, _)| *number);
+    Ok(numbered.into_iter().map(|(_, _, path)| path).collect())
+}
+
+/// Lecture d'un fichier du journal, décompressé si nécessaire
+pub fn reader(path: &Path) -> anyhow::Result<Box<dyn BufRead>> {
+    let file = File::open(path)?;
+    if path.extension().is_some_and(|extension| extension == "gz") {
+        Ok(Box::new(BufReader::new(GzDecoder::new(file))))
+    } else {
+        Ok(Box::new(BufReader::new(file)))
+    }
+}
+
+/// Compresse un fichier terminé en <fichier>.gz. Le fichier compressé n'apparaît qu'une fois
+/// complet (écrit sous un nom temporaire puis renommé).
+fn compress(path: &Path) -> anyhow::Result<()> {
+    let mut name = path.as_os_str().to_owned();
+    name.push(".gz");
+    let target = PathBuf::from(name);
+    let temporary = target.with_extension("gz.tmp");
+
+    let mut encoder = GzEncoder::new(BufWriter::new(File::create(&temporary)?), Compression::default());
+    std::io::copy(&mut File::open(path)?, &mut encoder)?;
+    let file = encoder.finish()?.into_inner().map_err(|e| e.into_error())?;
+    file.sync_all()?;
+
+    fs::rename(&temporary, &target)?;
+    fs::remove_file(path)?;
+    Ok(())
+}
+
+/// Retire la dernière ligne d'un fichier si elle est incomplète (arrêt brutal pendant une
+/// écriture). Retourne le nombre d'octets retirés.
+pub fn repair(path: &Path) -> anyhow::Result<u64> {
+    let mut file = OpenOptions::new().read(true).write(true).open(path)?;
+    let length = file.metadata()?.len();
+
+    // Recherche du dernier saut de ligne depuis la fin, par blocs
+    let mut end = length;
+    let mut block = [0u8; 4096];
+    let complete = loop {
+        if end == 0 {
+            break 0;
+        }
+
+        let start = end.saturating_sub(block.len() as u64);
+        let size = (end - start) as usize;
+        file.seek(SeekFrom::Start(start))?;
+        file.read_exact(&mut block[..size])?;
+
+        if let Some(position) = block[..size].iter().rposition(|&byte| byte == b'\n') {
+            break start + position as u64 + 1;
+        }
+        end = start;
+    };
+
+    if complete < length {
+        file.set_len(complete)?;
+        file.sync_all()?;
+    }
+
+    Ok(length - complete)
+}
+
+/// Fichiers laissés par une exécution précédente: dernière ligne incomplète retirée, compressés
+/// si demandé. Les compressions interrompues (.gz.tmp) sont supprimées.
+pub fn recover(directory: &Path, gzip: bool) -> anyhow::Result<()> {
+    for entry in fs::read_dir(directory)? {
+        let path = entry?.path();
+        let name = path.file_name().unwrap_or_default().to_string_lossy().into_owned();
+
+        if name.ends_with(".jsonl.gz.tmp") {
+            fs::remove_file(&path)?;
+            continue;
+        }
+        if !name.ends_with(".jsonl") {
+            continue;
+        }
+
+        let removed = repair(&path)?;
+        if removed > 0 {
+            tracing::info!(target: "jsonl", "{}: dernière ligne incomplète retirée ({} octets).", path.display(), removed);
+        }
+        if gzip {
+            compress(&path)?;
+        }
+    }
+
+    Ok(())
+}
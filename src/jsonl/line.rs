@@ -0,0 +1,25 @@
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+
+use crate::record::Record;
+
+/// Ligne du fichier: champs de l'enregistrement, avec son type, son heure et l'exécution
+#[derive(Serialize)]
+struct Line<'a> {
+    #[serde(rename = "type")]
+    kind: &'static str,
+    ts: DateTime<Utc>,
+    run_id: &'a str,
+    #[serde(flatten)]
+    record: &'a Record,
+}
+
+/// Ligne JSON d'un enregistrement, sans retour à la ligne
+pub fn encode(run: &str, record: &Record) -> serde_json::Result<Vec<u8>> {
+    serde_json::to_vec(&Line {
+        kind: record.kind(),
+        ts: record.stamp().utc,
+        run_id: run,
+        record,
+    })
+}
@@ -0,0 +1,122 @@
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError};
+use std::thread;
+use std::time::Duration;
+
+use tokio::sync::broadcast::error::RecvError;
+use tokio_util::sync::CancellationToken;
+
+use crate::clock::Clock;
+use crate::config::JsonlConfig;
+use crate::logs::Repeated;
+use crate::writer::{Record, Writer};
+
+pub mod file;
+pub mod line;
+
+use file::{Limits, Sink};
+
+/// Enregistrements en attente d'écriture, les suivants sont perdus si le disque est trop lent
+const QUEUE: usize = 4096;
+
+/// Journal JSON lines de tous les échantillons diffusés par l'écrivain. L'écriture (rotation,
+/// compression, fsync) se fait dans un thread dédié, jamais dans le runtime.
+pub async fn run(config: JsonlConfig, run: String, writer: Writer, clock: Clock, token: CancellationToken) {
+    let (sender, receiver) = mpsc::sync_channel(QUEUE);
+    let records_config = config.records.clone();
+    let thread = thread::Builder::new()
+        .name("jsonl".to_string())
+        .spawn(move || write_loop(config, run, clock, receiver));
+    let thread = match thread {
+        Ok(thread) => thread,
+        Err(e) => {
+            tracing::error!(target: "jsonl", "Impossible de démarrer l'écriture: {}", e);
+            return;
+        }
+    };
+
+    let mut records = writer.subscribe();
+    let mut lost = 0u64;
+
+    loop {
+        tokio::select! {
+            _ = token.cancelled() => break,
+            record = records.recv() => match record {
+                Ok(record) => {
+                    if !records_config.iter().any(|kind| kind == record.kind()) {
+                        continue;
+                    }
+                    if let Err(mpsc::TrySendError::Full(_)) = sender.try_send(record) {
+                        lost += 1;
+                    }
+                }
+                Err(RecvError::Lagged(count)) => lost += count,
+                Err(RecvError::Closed) => break,
+            },
+        }
+    }
+
+    // Fin de l'écriture: le thread vide la file puis termine le fichier en cours
+    drop(sender);
+    let _ = tokio::task::spawn_blocking(move || thread.join()).await;
+
+    if lost > 0 {
+        tracing::error!(target: "jsonl", "{} enregistrement(s) perdu(s) (écriture trop lente).", lost);
+    }
+    tracing::info!(target: "jsonl", "Arrêt.");
+}
+
+fn write_loop(config: JsonlConfig, run: String, clock: Clock, receiver: Receiver<Record>) {
+    if let Err(e) = file::recover(&config.directory, config.gzip) {
+        tracing::error!(target: "jsonl", "Reprise des fichiers précédents: {}", e);
+    }
+
+    let limits = Limits {
+        max_bytes: config.max_file_mb.max(1) * 1024 * 1024,
+        max_age: (config.max_age_s > 0).then(|| Duration::from_secs(config.max_age_s)),
+        gzip: config.gzip,
+        budget: (config.max_total_mb > 0).then(|| config.max_total_mb * 1024 * 1024),
+    };
+    let mut sink = match Sink::new(&config.directory, &run, limits, &clock) {
+        Ok(sink) => sink,
+        Err(e) => {
+            tracing::error!(target: "jsonl", "Impossible de créer {}: {}", config.directory.display(), e);
+            return;
+        }
+    };
+
+    tracing::info!(target: "jsonl", "Journal dans {}", config.directory.display());
+
+    let interval = Duration::from_millis(config.flush_interval_ms.max(100));
+    let mut last_flush = clock.now();
+    let mut repeated = Repeated::default();
+
+    loop {
+        match receiver.recv_timeout(interval) {
+            Ok(record) => {
+                let result = line::encode(&run, &record)
+                    .map_err(anyhow::Error::from)
+                    .and_then(|line| sink.write(&line));
+                match result {
+                    Ok(_) => {
+                        repeated.clear();
+                    }
+                    Err(e) if repeated.first() => tracing::error!(target: "jsonl", "Erreur d'écriture: {}", e),
+                    Err(e) => tracing::debug!(target: "jsonl", "Erreur d'écriture: {}", e),
+                }
+            }
+            Err(RecvTimeoutError::Timeout) => {}
+            Err(RecvTimeoutError::Disconnected) => break,
+        }
+
+        if clock.since(last_flush) >= interval {
+            last_flush = clock.now();
+            if let Err(e) = sink.flush() {
+                tracing::error!(target: "jsonl", "Erreur d'écriture: {}", e);
+            }
+        }
+    }
+
+    if let Err(e) = sink.close() {
+        tracing::error!(target: "jsonl", "Erreur à la fermeture: {}", e);
+    }
+}
@@ -0,0 +1,48 @@
+// Modules de la télémétrie, partagés par le binaire, les tests d'intégration et les benchmarks
+
+pub mod actuators;
+pub mod alerts;
+pub mod blackbox;
+pub mod breaker;
+pub mod capacity;
+pub mod channel;
+pub mod clock;
+pub mod config;
+pub mod control;
+pub mod csv;
+pub mod database;
+pub mod export;
+pub mod fusion;
+pub mod gps_time;
+pub mod grafana;
+pub mod grpc;
+pub mod heading_check;
+pub mod http;
+pub mod jsonl;
+pub mod live;
+pub mod logs;
+pub mod mavlink;
+pub mod mdns;
+pub mod metadata;
+pub mod metrics;
+pub mod modem;
+pub mod mqtt;
+pub mod pipeline;
+pub mod proto;
+pub mod record;
+pub mod run;
+pub mod selftest;
+pub mod sensors;
+pub mod signals;
+pub mod spool;
+pub mod sport;
+#[cfg(unix)]
+pub mod systemd;
+pub mod tasks;
+pub mod timing;
+pub mod udp;
+pub mod writer;
+pub mod zenoh_bridge;
+
+#[cfg(feature = "real-sensors")]
+pub mod i2c;
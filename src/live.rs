@@ -0,0 +1,84 @@
+use std::fmt::Display;
+use std::time::Duration;
+
+use tokio::time::sleep;
+use tokio_util::sync::CancellationToken;
+
+/// Attente avant de recréer un live en échec, doublée à chaque échec
+pub const RETRY_MIN: Duration = Duration::from_millis(250);
+
+/// Attente maximale entre deux créations d'un live
+pub const RETRY_MAX: Duration = Duration::from_secs(10);
+
+/// Nouvelles tentatives d'un live (requête LIVE de la base): création refusée (droits, table
+/// absente) ou flux terminé. L'attente croît à chaque échec et revient au minimum à la première
+/// notification reçue: un live créé puis aussitôt terminé reste en échec. Les erreurs identiques
+/// successives sont regroupées dans le journal.
+pub struct Live {
+    name: &'static str,
+    delay: Duration,
+    /// Echecs depuis la dernière notification reçue
+    failures: u32,
+    error: Option<String>,
+}
+
+impl Live {
+    /// `name`: nom du live dans le journal (ex: "control")
+    pub fn new(name: &'static str) -> Self {
+        Self {
+            name,
+            delay: RETRY_MIN,
+            failures: 0,
+            error: None,
+        }
+    }
+
+    /// Notification reçue: le live fonctionne
+    pub fn received(&mut self) {
+        if self.failures > 0 {
+            tracing::info!(target: "db", live = self.name, "Live rétabli après {} échec(s)", self.failures);
+        }
+        self.delay = RETRY_MIN;
+        self.failures = 0;
+        self.error = None;
+    }
+
+    /// Echec du live, attend avant la tentative suivante. Faux: annulé pendant l'attente
+    pub async fn failed(&mut self, token: &CancellationToken, error: impl Display) -> bool {
+        let delay = self.delay();
+        let error = error.to_string();
+
+        if self.error.as_ref() != Some(&error) {
+            tracing::warn!(
+                target: "db",
+                live = self.name,
+                "Erreur du live: {}, nouvel essai dans {:.1} s",
+                error,
+                delay.as_secs_f64()
+            );
+            self.error = Some(error);
+        } else if self.failures.is_power_of_two() {
+            tracing::warn!(
+                target: "db",
+                live = self.name,
+                "Live toujours en échec ({} tentatives): {}, nouvel essai dans {:.1} s",
+                self.failures,
+                error,
+                delay.as_secs_f64()
+            );
+        }
+
+        tokio::select! {
+            _ = token.cancelled() => false,
+            _ = sleep(delay) => true,
+        }
+    }
+
+    /// Compte un échec, retourne l'attente avant la tentative suivante
+    fn delay(&mut self) -> Duration {
+        self.failures += 1;
+        let delay = self.delay;
+        self.delay = (self.delay * 2).min(RETRY_MAX);
+        delay
+    }
+}
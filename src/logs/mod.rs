@@ -0,0 +1,166 @@
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex, PoisonError};
+use std::time::Duration;
+
+use tokio_util::sync::CancellationToken;
+use tracing_appender::non_blocking::{NonBlockingBuilder, WorkerGuard};
+
+use crate::clock::Clock;
+use crate::config::LogsConfig;
+use crate::writer::{Event, Writer};
+
+pub mod repeat;
+pub mod rolling;
+pub mod subscriber;
+
+pub use repeat::Repeated;
+pub use rolling::LogUsage;
+
+/// Dernière panique: message, emplacement et pile d'appels
+const PANIC_FILE: &str = "panic.log";
+
+/// Intervalle de publication de l'occupation du dossier des journaux
+const USAGE_INTERVAL: Duration = Duration::from_secs(60);
+
+/// Fichier journal alimenté par le journal du programme, écrit par un thread dédié
+/// (tracing_appender): les tâches du programme n'attendent jamais le disque.
+struct Appender {
+    directory: PathBuf,
+    max_bytes: u64,
+    max_files: usize,
+    /// Thread d'écriture en cours, None une fois fermé
+    guard: Mutex<Option<WorkerGuard>>,
+}
+
+impl Appender {
+    /// Ouvre le fichier en cours et y dirige les lignes suivantes
+    fn open(&self, guard: &mut Option<WorkerGuard>) -> anyhow::Result<()> {
+        let rolling = rolling::Rolling::open(&self.directory, self.max_bytes, self.max_files)?;
+        let (writer, worker) = NonBlockingBuilder::default().thread_name("logs").finish(rolling);
+        subscriber::set_file(Some(writer));
+        *guard = Some(worker);
+        Ok(())
+    }
+
+    /// Ecrit les lignes en attente: le thread d'écriture s'arrête une fois la file vidée
+    fn close(&self, guard: &mut Option<WorkerGuard>) {
+        subscriber::set_file(None);
+        drop(guard.take());
+    }
+
+    /// Ecrit les lignes en attente puis reprend l'écriture (sans effet une fois fermé)
+    fn flush(&self) -> anyhow::Result<()> {
+        let mut guard = self.guard.lock().unwrap_or_else(PoisonError::into_inner);
+        if guard.is_none() {
+            return Ok(());
+        }
+
+        self.close(&mut guard);
+        self.open(&mut guard)
+    }
+}
+
+/// Journal du programme écrit dans les fichiers journaux, jusqu'à `close`
+pub struct Logs {
+    appender: Arc<Appender>,
+}
+
+/// Ecrit le journal du programme dans le dossier des journaux, avec rotation par taille. Une
+/// panique écrit les lignes en attente puis est écrite dans panic.log.
+pub fn start(config: &LogsConfig) -> anyhow::Result<Logs> {
+    let appender = Arc::new(Appender {
+        directory: config.directory.clone(),
+        max_bytes: config.max_file_mb * 1024 * 1024,
+        max_files: config.max_files,
+        guard: Mutex::new(None),
+    });
+    appender.open(&mut appender.guard.lock().unwrap())?;
+
+    let directory = config.directory.clone();
+    let hooked = appender.clone();
+    let previous = std::panic::take_hook();
+    std::panic::set_hook(Box::new(move |info| {
+        previous(info);
+        if let Err(e) = hooked.flush() {
+            eprintln!("[LOGS] Impossible de rouvrir le journal: {}", e);
+        }
+        if let Err(e) = write_panic(&directory, info) {
+            tracing::error!(target: "logs", "Impossible d'écrire {}: {}", PANIC_FILE, e);
+        }
+    }));
+
+    tracing::info!(target: "logs", "Journal écrit dans {}", config.directory.display());
+    Ok(Logs { appender })
+}
+
+impl Logs {
+    /// Arrêt propre: lignes en attente écrites, plus rien n'est copié dans le fichier. Les
+    /// paniques suivantes sont toujours écrites dans panic.log.
+    pub fn close(self) {
+        tracing::info!(target: "logs", "Fermeture du journal.");
+        let mut guard = self.appender.guard.lock().unwrap_or_else(PoisonError::into_inner);
+        self.appender.close(&mut guard);
+    }
+}
+
+/// Ecrit la dernière panique (remplace la précédente) et la synchronise sur le disque
+fn write_panic(
+    directory: &std::path::Path,
+    info: &std::panic::PanicHookInfo,
+) -> anyhow::Result<()> {
+    use std::io::Write;
+
+    let payload = info
+        .payload()
+        .downcast_ref::<&str>()
+        .map(|message| message.to_string())
+        .or_else(|| info.payload().downcast_ref::<String>().cloned())
+        .unwrap_or_else(|| "(message non textuel)".to_string());
+    let location = info
+        .location()
+        .map(|location| location.to_string())
+        .unwrap_or_else(|| "inconnu".to_string());
+    let thread = std::thread::current();
+
+    let mut file = std::fs::File::create(directory.join(PANIC_FILE))?;
+    writeln!(
+        file,
+        "Panique le {} (thread {})",
+        chrono::Utc::now().to_rfc3339(),
+        thread.name().unwrap_or("?")
+    )?;
+    writeln!(file, "Emplacement: {}", location)?;
+    writeln!(file, "Message: {}", payload)?;
+    writeln!(file)?;
+    writeln!(file, "Pile d'appels:")?;
+    writeln!(file, "{}", std::backtrace::Backtrace::force_capture())?;
+    file.sync_all()?;
+    Ok(())
+}
+
+/// Publie périodiquement l'occupation du dossier des journaux (status:run)
+pub async fn publish(
+    directory: PathBuf,
+    writer: Writer,
+    clock: Clock,
+    token: CancellationToken,
+) {
+    loop {
+        match rolling::usage(&directory) {
+            Ok(usage) => {
+                let _ = writer.event(Event::Logs(usage, clock.stamp())).await;
+            }
+            Err(e) => tracing::error!(
+                target: "logs",
+                "Occupation de {} inconnue: {}",
+                directory.display(),
+                e
+            ),
+        }
+
+        tokio::select! {
+            _ = token.cancelled() => break,
+            _ = tokio::time::sleep(USAGE_INTERVAL) => {}
+        }
+    }
+}
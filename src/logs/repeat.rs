@@ -0,0 +1,19 @@
+/// Erreur qui se répète à chaque itération d'une boucle: seule la première occurrence est
+/// journalisée à son niveau, les suivantes en debug jusqu'au retour à la normale
+#[derive(Debug, Default)]
+pub struct Repeated {
+    count: u64,
+}
+
+impl Repeated {
+    /// Nouvelle occurrence. Vrai pour la première, à journaliser à son niveau
+    pub fn first(&mut self) -> bool {
+        self.count += 1;
+        self.count == 1
+    }
+
+    /// Retour à la normale: nombre d'occurrences depuis la première (0: aucune)
+    pub fn clear(&mut self) -> u64 {
+        std::mem::take(&mut self.count)
+    }
+}
@@ -0,0 +1,118 @@
+use std::fs::{self, File, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+use serde::Serialize;
+
+/// Fichier journal en cours
+pub const LOG_FILE: &str = "rc-telemetrie.log";
+
+/// Occupation du dossier des journaux
+#[derive(Clone, Copy, Debug, Default, PartialEq, Serialize)]
+pub struct LogUsage {
+    pub bytes: u64,
+    pub files: u64,
+}
+
+/// Fichiers journaux avec rotation par taille: rc-telemetrie.log (en cours), rc-telemetrie.log.1
+/// (le plus récent), ... Opérations bloquantes (disque), à appeler depuis un thread dédié.
+pub struct Rolling {
+    directory: PathBuf,
+    /// Taille maximale d'un fichier (octets)
+    max_bytes: u64,
+    /// Nombre de fichiers conservés, fichier en cours compris
+    max_files: usize,
+    file: File,
+    bytes: u64,
+}
+
+impl Rolling {
+    /// Ouvre le fichier en cours, à la suite du journal de l'exécution précédente
+    pub fn open(directory: &Path, max_bytes: u64, max_files: usize) -> anyhow::Result<Self> {
+        fs::create_dir_all(directory)?;
+
+        let file = append(directory)?;
+        let bytes = file.metadata()?.len();
+        Ok(Self {
+            directory: directory.to_path_buf(),
+            max_bytes: max_bytes.max(1),
+            max_files: max_files.max(1),
+            file,
+            bytes,
+        })
+    }
+
+    /// Ajoute des données, commence un nouveau fichier au-delà de la taille maximale
+    pub fn write(&mut self, data: &[u8]) -> anyhow::Result<()> {
+        if self.bytes > 0 && self.bytes + data.len() as u64 > self.max_bytes {
+            self.rotate()?;
+        }
+
+        self.file.write_all(data)?;
+        self.bytes += data.len() as u64;
+        Ok(())
+    }
+
+    /// Ecrit les données sur le disque
+    pub fn sync(&mut self) -> anyhow::Result<()> {
+        self.file.sync_data()?;
+        Ok(())
+    }
+
+    /// Décale les fichiers terminés (le plus ancien est supprimé), puis commence un fichier vide
+    fn rotate(&mut self) -> anyhow::Result<()> {
+        self.sync()?;
+
+        let path = |n: usize| match n {
+            0 => self.directory.join(LOG_FILE),
+            n => self.directory.join(format!("{}.{}", LOG_FILE, n)),
+        };
+
+        let oldest = path(self.max_files - 1);
+        if oldest.exists() {
+            fs::remove_file(&oldest)?;
+        }
+        for n in (0..self.max_files - 1).rev() {
+            if path(n).exists() {
+                fs::rename(path(n), path(n + 1))?;
+            }
+        }
+
+        self.file = append(&self.directory)?;
+        self.bytes = 0;
+        Ok(())
+    }
+}
+
+/// Destination du thread d'écriture du journal (tracing_appender)
+impl Write for Rolling {
+    fn write(&mut self, data: &[u8]) -> std::io::Result<usize> {
+        Rolling::write(self, data).map_err(std::io::Error::other)?;
+        Ok(data.len())
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        self.file.flush()
+    }
+}
+
+fn append(directory: &Path) -> anyhow::Result<File> {
+    Ok(OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(directory.join(LOG_FILE))?)
+}
+
+/// Taille et nombre de fichiers du dossier des journaux
+pub fn usage(directory: &Path) -> anyhow::Result<LogUsage> {
+    let mut usage = LogUsage::default();
+    for entry in fs::read_dir(directory)? {
+        let metadata = entry?.metadata()?;
+        if metadata.is_file() {
+            usage.bytes += metadata.len();
+            usage.files += 1;
+        }
+    }
+
+    Ok(usage)
+}
@@ -0,0 +1,123 @@
+use std::fmt;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{PoisonError, RwLock};
+
+use tracing::{Event, Subscriber};
+use tracing_appender::non_blocking::NonBlocking;
+use tracing_subscriber::fmt::format::{Format, Json, Writer};
+use tracing_subscriber::fmt::time::FormatTime;
+use tracing_subscriber::fmt::writer::{MakeWriter, OptionalWriter};
+use tracing_subscriber::fmt::{layer, FmtContext, FormatEvent, FormatFields};
+use tracing_subscriber::layer::SubscriberExt;
+use tracing_subscriber::registry::LookupSpan;
+use tracing_subscriber::util::SubscriberInitExt;
+use tracing_subscriber::EnvFilter;
+
+/// Niveaux sans variable RUST_LOG: journaux du programme, avertissements des bibliothèques.
+/// Exemple de variable: RUST_LOG=control=debug,db=warn
+const DEFAULT_FILTER: &str = "info,zenoh=warn,surrealdb=warn,rumqttc=warn,hyper=warn,h2=warn,tonic=warn,tower=warn,reqwest=warn,rustls=warn,tungstenite=warn,tokio_tungstenite=warn,mdns_sd=warn";
+
+/// Mode dry-run: indiqué sur chaque ligne du journal (après la date en texte, champ "dry_run"
+/// en JSON)
+static DRY_RUN: AtomicBool = AtomicBool::new(false);
+
+/// Fichier journal (logs::start), rien n'y est écrit tant qu'il n'est pas ouvert
+static FILE: RwLock<Option<NonBlocking>> = RwLock::new(None);
+
+/// Format des lignes du journal
+#[derive(Clone, Copy, Debug, Default, PartialEq, clap::ValueEnum)]
+pub enum LogFormat {
+    /// Lisible: date, niveau, tâche, module et message
+    #[default]
+    Text,
+    /// Un objet JSON par ligne
+    Json,
+}
+
+/// Date UTC des lignes, suivie de l'indication du mode dry-run
+struct Timestamp {
+    dry_run: bool,
+}
+
+impl FormatTime for Timestamp {
+    fn format_time(&self, w: &mut Writer<'_>) -> fmt::Result {
+        write!(w, "{}", chrono::Utc::now().format("%Y-%m-%dT%H:%M:%S%.3fZ"))?;
+        if self.dry_run && DRY_RUN.load(Ordering::Relaxed) {
+            write!(w, " [DRY-RUN]")?;
+        }
+        Ok(())
+    }
+}
+
+/// Objet JSON par ligne, précédé du champ "dry_run" en mode dry-run
+struct JsonLine(Format<Json, Timestamp>);
+
+impl<S, N> FormatEvent<S, N> for JsonLine
+where
+    S: Subscriber + for<'a> LookupSpan<'a>,
+    N: for<'a> FormatFields<'a> + 'static,
+{
+    fn format_event(&self, ctx: &FmtContext<'_, S, N>, mut writer: Writer<'_>, event: &Event<'_>) -> fmt::Result {
+        if !DRY_RUN.load(Ordering::Relaxed) {
+            return self.0.format_event(ctx, writer, event);
+        }
+
+        let mut line = String::new();
+        self.0.format_event(ctx, Writer::new(&mut line), event)?;
+        match line.strip_prefix('{') {
+            Some(fields) => write!(writer, "{{\"dry_run\":true,{}", fields),
+            None => writer.write_str(&line),
+        }
+    }
+}
+
+/// Format JSON des lignes du journal, date sans l'indication du mode dry-run
+fn json() -> JsonLine {
+    JsonLine(Format::default().json().with_timer(Timestamp { dry_run: false }))
+}
+
+/// Lignes destinées au fichier journal, ignorées s'il n'est pas ouvert
+struct LogFile;
+
+impl<'a> MakeWriter<'a> for LogFile {
+    type Writer = OptionalWriter<NonBlocking>;
+
+    fn make_writer(&'a self) -> Self::Writer {
+        FILE.read().unwrap_or_else(PoisonError::into_inner).clone().into()
+    }
+}
+
+/// Journal du programme sur la sortie d'erreur et dans le fichier journal une fois ouvert,
+/// filtré par la variable RUST_LOG. Les directives invalides de la variable sont ignorées.
+pub fn init(format: LogFormat) {
+    let filter = match std::env::var(EnvFilter::DEFAULT_ENV) {
+        Ok(directives) => EnvFilter::builder().parse_lossy(directives),
+        Err(_) => EnvFilter::new(DEFAULT_FILTER),
+    };
+    let registry = tracing_subscriber::registry().with(filter);
+
+    let result = match format {
+        LogFormat::Text => registry
+            .with(layer().with_writer(std::io::stderr).with_ansi(false).with_timer(Timestamp { dry_run: true }))
+            .with(layer().with_writer(LogFile).with_ansi(false).with_timer(Timestamp { dry_run: true }))
+            .try_init(),
+        LogFormat::Json => registry
+            .with(layer().json().event_format(json()).with_writer(std::io::stderr))
+            .with(layer().json().event_format(json()).with_writer(LogFile))
+            .try_init(),
+    };
+    if let Err(e) = result {
+        eprintln!("[LOGS] Journal déjà initialisé: {}", e);
+    }
+}
+
+/// Mode dry-run actif, indiqué sur les lignes suivantes
+pub fn set_dry_run() {
+    DRY_RUN.store(true, Ordering::Relaxed);
+}
+
+/// Ecrit les lignes suivantes dans le fichier journal (thread d'écriture de logs::start),
+/// None: plus de copie dans un fichier
+pub fn set_file(writer: Option<NonBlocking>) {
+    *FILE.write().unwrap_or_else(PoisonError::into_inner) = writer;
+}
@@ -1,317 +1,2156 @@
-mod actuators;
-mod database;
-mod sensors;
-
-#[cfg(feature = "real-sensors")]
-mod i2c;
-
-#[cfg(feature = "real-sensors")]
+mod args;
 
 use std::{
     sync::Arc,
-    time::Duration,
+    time::{Duration, Instant},
 };
 
+use clap::Parser;
+use voiturerc::{
+    actuators, alerts, blackbox, capacity, clock, config, control, csv, database, export, fusion,
+    gps_time, grafana, grpc, heading_check, http, jsonl, live, logs, mavlink, mdns, metadata, modem,
+    mqtt, pipeline, record, run, selftest, sensors, signals, tasks, udp, writer, zenoh_bridge
+};
+#[cfg(feature = "real-sensors")]
+use voiturerc::sport;
+#[cfg(feature = "systemd")]
+use voiturerc::systemd;
+use config::SensorMode;
 use database::Database;
 use futures::StreamExt;
-use tokio::time::{sleep, timeout};
+use tokio::sync::watch;
+use tokio::time::sleep;
 use tokio_util::sync::CancellationToken;
-use zbus::{
-    fdo,
-    names::InterfaceName,
-    zvariant,
-    Connection,
-};
-use zvariant::OwnedValue;
+use zbus::Connection;
 
 #[cfg(unix)]
 use tokio::signal::unix::SignalKind;
-use tokio::signal::{self};
 
-const DEAD_TIMEOUT: u64 = 500;
+/// Intervalle de vérification des modifications du fichier de configuration
+const CONFIG_POLL: Duration = Duration::from_secs(2);
+
+/// Intervalle de vérification de l'attitude (coupure en cas de retournement)
+const ROLLOVER_CHECK: Duration = Duration::from_millis(20);
+
+/// Intervalle de vérification de la distance de l'obstacle
+const OBSTACLE_CHECK: Duration = Duration::from_millis(20);
+
+/// Intervalle de vérification de la tension de la batterie
+const LOW_VOLTAGE_CHECK: Duration = Duration::from_millis(100);
+
+/// Intervalle de lecture du courant de la batterie (capacité consommée)
+const CAPACITY_CHECK: Duration = Duration::from_millis(100);
+
+/// Intervalle entre deux écritures de la capacité consommée (status:battery)
+const CAPACITY_STATUS: Duration = Duration::from_secs(5);
+
+/// Intervalle minimal entre deux écritures des vitesses commandée et appliquée (status:control)
+const SPEED_STATUS: Duration = Duration::from_millis(200);
+
+/// Intervalle de vérification des températures
+const THERMAL_CHECK: Duration = Duration::from_millis(200);
+
+/// Intervalle d'évaluation des zones GPS
+const GEOFENCE_CHECK: Duration = Duration::from_millis(200);
+
+/// Intervalle de vérification de la fraîcheur des capteurs
+const WATCHDOG_CHECK: Duration = Duration::from_millis(50);
+
+/// Intervalle de mise à jour de la LED d'état (résolution du clignotement)
+const STATUS_LED_TICK: Duration = Duration::from_millis(50);
+
+/// Intervalle de vérification du désarmement automatique
+const AUTO_DISARM_CHECK: Duration = Duration::from_millis(500);
+
+/// Intervalle de publication des durées restantes avant le désarmement automatique
+const COUNTDOWN_PUBLISH: Duration = Duration::from_secs(5);
+
+/// Durée du test de démarrage (en secondes) avant l'envoi du rapport
+const SELFTEST_DURATION: u64 = 10;
+
+/// Attente maximale de la fin des tâches lors de l'arrêt
+const SHUTDOWN_GRACE: Duration = Duration::from_secs(3);
+
+/// Période de lecture de l'état du modem
+const MODEM_PERIOD: Duration = Duration::from_millis(500);
+
+/// Délai avant une nouvelle recherche du modem (D-Bus ou ModemManager indisponible, aucun modem)
+const MODEM_RETRY: Duration = Duration::from_secs(10);
 
 #[tokio::main]
 async fn main() {
     let token = CancellationToken::new();
 
+    let args = args::Args::parse();
+    logs::subscriber::init(args.log_format);
+    if let Err(e) = args.validate() {
+        panic!("[ARGS] Arguments invalides: {}", e);
+    }
+    let replay = args.replay.is_some();
+
+    if let Some(command) = args.command.as_ref() {
+        if let Err(e) = run_command(command, &args.config).await {
+            tracing::error!(target: "main", "{}", e);
+            std::process::exit(1);
+        }
+        return;
+    }
+
+    // Signaux d'arrêt (SIGINT, SIGTERM), écoutés dès le démarrage
+    let mut signals = match signals::Signals::install() {
+        Ok(signals) => signals,
+        Err(e) => {
+            tracing::error!(target: "main", "Impossible d'écouter les signaux d'arrêt: {}", e);
+            std::process::exit(1);
+        }
+    };
+
+    // Chargement de la configuration
+    let config = match config::Config::load(&args.config) {
+        Ok(config) => config,
+        Err(e) => {
+            tracing::error!(target: "config", "Erreur de configuration: {}", e);
+            std::process::exit(1);
+        }
+    };
+
+    // Source de temps commune à tous les capteurs, accélérée uniquement avec une simulation valide
+    let clock = match config.validate() {
+        Ok(_) if config.simulation.time_scale != 1.0 => {
+            tracing::info!(target: "sim", "Temps accéléré x{}", config.simulation.time_scale);
+            clock::Clock::accelerated(config.simulation.time_scale)
+        }
+        _ => clock::Clock::start(),
+    };
+
+    // Journal du programme dans les fichiers journaux, avant les premiers messages de l'exécution
+    let logs = if config.logs.enabled {
+        match logs::start(&config.logs) {
+            Ok(logs) => Some(logs),
+            Err(e) => {
+                tracing::error!(target: "logs", "Impossible d'écrire le journal dans {}: {}", config.logs.directory.display(), e);
+                None
+            }
+        }
+    } else {
+        None
+    };
+
+    // Version du programme et empreinte de la configuration
+    let metadata = metadata::Metadata::new(&config);
+    tracing::info!(target: "main", "{}", metadata.summary());
+
+    let dry_run = args.dry_run || config.dry_run;
+    if dry_run {
+        logs::subscriber::set_dry_run();
+        tracing::info!(target: "main", "Mode dry-run: aucune écriture en base, actionneurs factices.");
+    }
+
+    // Dossier d'état de l'exécution, détecte un arrêt non propre précédent
+    let run = match run::RunDir::open(&config.run.directory) {
+        Ok(run) => run,
+        Err(e) => {
+            panic!("[RUN] Impossible de préparer le dossier d'exécution: {}", e);
+        }
+    };
+
+    // Test de démarrage
+    let selftest = selftest::SelfTest::new();
+    selftest.record("config", config.validate());
+
     // Préparation de la base de donnée
-    println!("[DB] Connexion à la base de donnée ...");
-    let db = match Database::new().await {
+    tracing::info!(target: "db", "Connexion à la base de donnée ...");
+    let db = match Database::new(&config.database, dry_run).await {
         Ok(db) => {
-            println!("[DB] Connexion établie.");
-            Arc::new(db)
+            tracing::info!(target: "db", "Connexion établie.");
+            Arc::new(db.with_run(&run.state.id))
         }
         Err(e) => {
             panic!("[DB] Erreur de connexion: {}", e);
         }
     };
 
-    // Capteur
-    {
-        let token = token.child_token();
+    // Tâches de l'exécution, attendues lors de l'arrêt
+    let mut tasks = tasks::Tasks::new();
 
-        let mut reader = sensors::reader::Reader::new(token.clone()).expect("[CAPTEURS] Impossible de gérer les capteurs.");
-        let db = db.clone();
-        tokio::spawn(async move {
-            while !token.is_cancelled() {
-                if let Some(data) = reader.next().await {
-                    if let Ok(data) = data {
-                        let _ = db.send_analog(data.analog).await;
-                        let _ = db.send_nav(data.gps, data.mag, data.imu).await;
-                    }
+    // Reconnexion automatique si la connexion est perdue
+    tasks.spawn("db.supervise", database::supervise(db.clone(), token.child_token()));
 
-                    sleep(Duration::from_millis(1000 / 30)).await;
-                }
-            }
-        });
+    selftest.record("db", db.check().await);
+    match db.check_schema().await {
+        Ok(Some(version)) => selftest.record("db.schema", Ok(version)),
+        Ok(None) => selftest.skip("db.schema", "Aucune version de schéma enregistrée"),
+        Err(e) => selftest.record("db.schema", Err(e)),
     }
-    
-    // Modem 4G
-    {
-        let token = token.child_token();
-        let db = db.clone();
 
-        #[cfg(feature = "real-sensors")]
-        {
-            let connection = Connection::system()
-                .await
-                .expect("Impossible de gérer le D-BUS");
-
-            tokio::spawn(async move {
-                let proxy = fdo::PropertiesProxy::builder(&connection)
-                    .destination("org.freedesktop.ModemManager1")
-                    .expect("Destination invalide")
-                    .path("/org/freedesktop/ModemManager1/Modem/0")
-                    .expect("Path invalide")
-                    .build()
-                    .await
-                    .expect("Impossible de créer le proxy pour la propriété");
+    // Configuration active, mise à jour lors d'un rechargement
+    let (config_sender, config_updates) = watch::channel(config.clone());
 
-                while !token.is_cancelled() {
-                    let signal_quality: OwnedValue = proxy
-                        .get(
-                            InterfaceName::try_from("org.freedesktop.ModemManager1.Modem")
-                                .expect("Type invalide"),
-                            "SignalQuality",
-                        )
-                        .await
-                        .expect("Impossible de récupérer la valeur SignalQuality.");
+    // Ecrivain unique de la base de donnée
+    let (writer, writer_task) = writer::spawn(db.clone(), config_updates.clone(), &clock, token.child_token());
+    tasks.spawn("writer", writer_task);
 
-                    let signal = <(u32, bool)>::try_from(signal_quality).unwrap_or((0, false));
+    // Occupation du dossier des journaux, pour ne pas remplir la carte sans le savoir
+    if config.logs.enabled {
+        tasks.spawn("logs", logs::publish(
+            config.logs.directory.clone(),
+            writer.clone(),
+            clock.clone(),
+            token.child_token(),
+        ));
+    }
 
-                    println!("Signal: {}", signal.0);
+    // Compteurs exposés par le serveur HTTP
+    let metrics = Arc::new(http::Metrics {
+        db: db.metrics(),
+        ..http::Metrics::default()
+    });
 
-                    let _ = db.send_modem(signal.0).await;
-                    tokio::time::sleep(Duration::from_millis(500)).await;
-                }
-            });
+    // Alertes vers les webhooks, abonnées avant le premier évènement
+    if config.alerts.enabled {
+        tasks.spawn("alerts", alerts::run(
+            config.alerts.clone(),
+            config.vehicle.clone(),
+            run.state.id.clone(),
+            writer.notices(),
+            metrics.alerts.clone(),
+            token.child_token(),
+        ));
+    }
+    if args.test_alert {
+        if config.alerts.enabled {
+            let message = "Alerte de test (--test-alert)".to_string();
+            let event = writer::Event::Alert(alerts::filter::TEST, config::Severity::Info, message, clock.stamp());
+            let _ = writer.event(event).await;
+        } else {
+            tracing::warn!(target: "alerts", "--test-alert ignoré: alertes désactivées ([alerts] enabled = false)");
         }
+    }
 
-        #[cfg(feature = "fake-sensors")]
-        {
-            tokio::spawn(async move {
-                let mut rng = rand::thread_rng();
-
-                while !token.is_cancelled() {
-                    let signal: u32 = rng.gen();
-                    let _ = db.send_modem(signal).await;
-                    tokio::time::sleep(Duration::from_millis(500)).await;
-                }
-            });
+    // Etat de l'exécution
+    {
+        let state = run.state.clone();
+        if let Some(previous) = state.unclean.clone() {
+            let _ = writer.event(writer::Event::UncleanShutdown(previous, clock.stamp())).await;
         }
+        let _ = writer.event(writer::Event::Run(state, metadata, clock.stamp())).await;
     }
 
-    // Switch (Activation fonction unique)
+    // Sortie MAVLink vers une station sol
+    if config.mavlink.enabled {
+        tasks.spawn("mavlink", mavlink::run(
+            config.mavlink.clone(),
+            writer.clone(),
+            clock.clone(),
+            token.child_token(),
+        ));
+    }
+
+    // Envoi UDP des échantillons (réseau local)
+    if config.udp.enabled {
+        tasks.spawn("udp", udp::run(config.udp.clone(), writer.clone(), token.child_token()));
+    }
+    if !config.udp.frame.target.is_empty() {
+        tasks.spawn("udp.frame", udp::frames(
+            config.udp.frame.clone(),
+            writer.clone(),
+            clock.clone(),
+            token.child_token(),
+        ));
+    }
+
+    // Bus CAN (ESC, BMS)
+    if config.can.enabled {
+        tasks.spawn("can", sensors::can::run(
+            config.can.clone(),
+            writer.clone(),
+            clock.clone(),
+            token.child_token(),
+        ));
+    }
+
+    // Export CSV de l'exécution
+    if config.csv.enabled {
+        tasks.spawn("csv", csv::run(
+            config.csv.clone(),
+            run.state.id.clone(),
+            writer.clone(),
+            token.child_token(),
+        ));
+    }
+
+    // Journal JSON lines de l'exécution
+    if config.jsonl.enabled {
+        tasks.spawn("jsonl", jsonl::run(
+            config.jsonl.clone(),
+            run.state.id.clone(),
+            writer.clone(),
+            clock.clone(),
+            token.child_token(),
+        ));
+    }
+
+    // Envoi vers Grafana Live
+    if config.grafana.enabled {
+        tasks.spawn("grafana", grafana::run(
+            config.grafana.clone(),
+            config.vehicle.clone(),
+            writer.clone(),
+            token.child_token(),
+        ));
+    }
+
+    // Publication Zenoh (ROS 2)
+    if config.zenoh.enabled {
+        tasks.spawn("zenoh", zenoh_bridge::run(
+            config.zenoh.clone(),
+            config.vehicle.clone(),
+            writer.clone(),
+            token.child_token(),
+        ));
+    }
+
+    // Télémétrie S.Port vers l'émetteur (port série du Raspberry Pi)
+    #[cfg(feature = "real-sensors")]
+    if config.sport.enabled {
+        tasks.spawn("sport", sport::run(config.sport.clone(), writer.clone(), token.child_token()));
+    }
+    #[cfg(not(feature = "real-sensors"))]
+    if config.sport.enabled {
+        tracing::error!(target: "sport", "Télémétrie S.Port indisponible sans la feature real-sensors.");
+    }
+
+    // Annonce mDNS des services locaux
+    if config.mdns.enabled {
+        tasks.spawn("mdns", mdns::run(config.clone(), run.state.id.clone(), token.child_token()));
+    }
+
+    // Véhicule simulé, partagé par les capteurs simulés, le modem et le contrôle
+    let scenario = config.simulation.with_track().unwrap_or_else(|e| {
+        tracing::error!(target: "sim", "Parcours ignoré: {}", e);
+        sensors::sim::Scenario {
+            track: None,
+            ..config.simulation.clone()
+        }
+    });
+    let scenario = scenario.with_seed().unwrap_or_else(|e| {
+        tracing::error!(target: "sim", "{}, graine tirée au hasard", e);
+        sensors::sim::Scenario {
+            seed: Some(sensors::sim::random_seed()),
+            ..scenario.clone()
+        }
+    });
+    // Graine journalisée pour rejouer l'exécution (seed ou RC_SIM_SEED)
+    tracing::info!(target: "sim", "Graine de la simulation: {}", scenario.seed.unwrap_or_default());
+    let simulation = sensors::sim::Simulation::shared(&scenario);
+
+    // Correction du magnétomètre, remplacée par la calibration enregistrée ou ajustée
+    let (mag_calibration, mag_calibration_rx) = watch::channel(config.sensors.mag.calibration);
+
+    // Capteur
     {
         let token = token.child_token();
-        let db = db.clone();
 
-        // Réinitialise les switchs
-        if let Err(e) = db.reset_switch().await {
-            println!("[SWITCH] Impossible de réinitialiser les switchs ({e})");
+        let reader = match args.replay.clone() {
+            Some(path) => {
+                let options = sensors::replay::ReplayOptions {
+                    path,
+                    speed: args.replay_speed.unwrap_or(config.record.replay_speed),
+                    looping: args.replay_loop.unwrap_or(config.record.replay_loop),
+                    pace: clock::Clock::start(),
+                };
+                sensors::reader::Reader::replay(token.clone(), options, &selftest)
+            }
+            None => sensors::reader::Reader::new(
+                token.clone(),
+                config_updates.clone(),
+                &clock,
+                &simulation,
+                &selftest,
+                &mag_calibration_rx,
+            ),
+        };
+        let reader = reader.expect("[CAPTEURS] Impossible de gérer les capteurs.");
+
+        // Pas d'enregistrement d'un rejeu
+        let mut recorder = None;
+        if config.record.enabled && !replay {
+            match sensors::replay::Recorder::create(&config.record.directory, reader.clock(), config.record.encoding) {
+                Ok(r) => recorder = Some(r),
+                Err(e) => tracing::error!(target: "record", "Impossible de démarrer l'enregistrement: {}", e),
+            }
         }
 
-        #[cfg(feature = "real-actuators")]
-        {
-            let switch = crate::actuators::switch::Switch::new();
-            if let Err(e) = switch {
-                println!("[SWITCH] Erreur lors de l'init des switchs: {}", e);
-                return;
+        tasks.spawn("sensors", pipeline::run(
+            reader,
+            recorder,
+            writer.clone(),
+            config_updates.clone(),
+            token,
+        ));
+    }
+
+    // Modem 4G
+    {
+        let token = token.child_token();
+        let writer = writer.clone();
+        let selftest = selftest.clone();
+        let clock = clock.clone();
+
+        match config.sensors.modem.mode {
+            SensorMode::Real => {
+                tasks.spawn("modem", modem_task(config.sensors.modem.clone(), writer, clock, selftest, token));
             }
-            let mut switch = switch.unwrap();
 
-            while !token.is_cancelled() {
-                let stream = db.live_switch().await;
+            SensorMode::Fake => {
+                selftest.skip("modem", "Modem simulé");
 
-                match stream {
-                    Ok(mut s) => {
-                        while !token.is_cancelled() {
-                            let sw = s.next().await;
-                            if let Some(Ok(data)) = sw {
-                                if data.data.esc { switch.start_esc() } else { switch.stop_esc() };
-                            }
-                        }
-                    },
-                    Err(e) => {
-                        eprintln!("[SWITCH] Erreur lors de la création du live: {}", e);
-                    }
-                }
+                tasks.spawn("modem", pipeline::fake_modem(simulation.clone(), writer, clock, token));
             }
 
-            switch.stop_esc();
+            SensorMode::Disabled => {
+                selftest.skip("modem", "Modem désactivé");
+            }
         }
     }
 
-    // Controle analogique
+    // Rechargement de la configuration (SIGHUP ou modification du fichier)
+    tasks.spawn("config.reload", config_reload(
+        args.config.clone(),
+        config_sender,
+        writer.clone(),
+        clock.clone(),
+        token.child_token(),
+    ));
+
+    // Rapport du test de démarrage
+    {
+        let writer = writer.clone();
+        let selftest = selftest.clone();
+        let token = token.child_token();
+        tasks.spawn("selftest", async move {
+            tokio::select! {
+                _ = token.cancelled() => return,
+                _ = sleep(Duration::from_secs(SELFTEST_DURATION)) => {}
+            }
+
+            let report = selftest.report(selftest::EXPECTED);
+            report.print();
+
+            if let Err(e) = writer.event(writer::Event::SelfTest(report)).await {
+                tracing::error!(target: "selftest", "Erreur lors de l'envoi du rapport: {}", e);
+            }
+        });
+    }
+
+    // Switch (Activation fonction unique)
     {
+        #[cfg(feature = "real-actuators")]
         let token = token.child_token();
         let db = db.clone();
-        tokio::spawn(async move {
-            #[cfg(feature = "real-actuators")]
-            {
-                let motor = crate::actuators::motor::Motor::new();
-                if let Err(e) = motor {
-                    println!("[CONTROL] Erreur lors de l'init moteur: {}", e);
-                    return;
-                }
-                let mut motor = motor.unwrap();
 
-                let steer = crate::actuators::steering::Steering::new();
-                if let Err(e) = steer {
-                    println!("[CONTROL] Erreur lors de l'init steering: {}", e);
+        // Réinitialise les switchs
+        if let Err(e) = db.reset_switch().await {
+            tracing::warn!(target: "switch", "Impossible de réinitialiser les switchs ({e})");
+        }
+
+        // Tâche séparée: la boucle des switchs ne doit pas bloquer l'attente des signaux d'arrêt
+        #[cfg(feature = "real-actuators")]
+        if replay || dry_run {
+            tracing::info!(target: "switch", "Rejeu ou dry-run: switchs désactivés.");
+        } else {
+            tasks.spawn("switch", async move {
+                let switch = crate::actuators::switch::Switch::new();
+                if let Err(e) = switch {
+                    tracing::error!(target: "switch", "Erreur lors de l'init des switchs: {}", e);
                     return;
                 }
-                let mut steer = steer.unwrap();
+                let mut switch = switch.unwrap();
 
+                // Live recréé à la fin du flux ou après une reconnexion à la base
+                let mut live = live::Live::new("switch");
                 while !token.is_cancelled() {
-                    let stream = db.live_control().await;
+                    tokio::select! {
+                        _ = token.cancelled() => break,
+                        _ = db.connected() => {}
+                    }
+                    let reconnects = db.connection().reconnects;
+                    let stream = db.live_switch().await;
 
                     match stream {
                         Ok(mut s) => {
-                            while !token.is_cancelled() {
-                                let control =
-                                    timeout(Duration::from_millis(DEAD_TIMEOUT), s.next()).await;
-                                match control {
-                                    Ok(data) => {
-                                        if data.is_none() {
-                                            continue;
-                                        }
-
-                                        let data = data.unwrap();
-                                        match data {
-                                            Ok(data) => {
-                                                if data.action != surrealdb::Action::Update {
-                                                    continue;
-                                                }
-
-                                                if let Err(e) = steer.set_steer(data.data.steer) {
-                                                    eprintln!("[CONTROL] Erreur lors du contrôle de la direction: {}", e)
-                                                }
-
-                                                if let Err(e) = motor.set_speed(data.data.speed) {
-                                                    eprintln!("[CONTROL] Erreur lors du contrôle moteur: {}", e)
-                                                }
-                                            }
-
-                                            Err(e) => {
-                                                eprintln!(
-                                                    "[CONTROL] Erreur lors de l'update: {}",
-                                                    e
-                                                );
-                                            }
-                                        }
+                            loop {
+                                let sw = tokio::select! {
+                                    _ = token.cancelled() => break,
+                                    _ = db.reconnected(reconnects) => break,
+                                    sw = s.next() => sw,
+                                };
+                                match sw {
+                                    Some(Ok(data)) => {
+                                        live.received();
+                                        if data.data.esc { switch.start_esc() } else { switch.stop_esc() };
                                     }
-                                    Err(_) => {
-                                        eprintln!("[CONTROL] Update tardif des données...");
-                                        let _ = motor.set_speed(0.0);
+                                    Some(Err(_)) => {}
+                                    None => {
+                                        live.failed(&token, "flux terminé").await;
+                                        break;
                                     }
                                 }
                             }
-                        }
+                        },
                         Err(e) => {
-                            eprintln!("[CONTROL] Erreur lors de la création du live: {}", e);
+                            live.failed(&token, format!("création impossible: {}", e)).await;
                         }
                     }
                 }
 
-                motor.safe_stop();
-                steer.safe_stop();
-            }
+                switch.stop_esc();
+            });
+        }
+    }
 
-            #[cfg(feature = "fake-actuators")]
-            {
-                while !token.is_cancelled() {
-                    let stream = db.live_control().await;
+    // Controle analogique: toutes les sources passent par l'arbitrage (validation, homme mort)
+    // Commandes externes en temps réel, même en simulation accélérée
+    let (commands, arbiter) = actuators::arbiter::Arbiter::new(
+        &clock::Clock::start(),
+        config.control.dead_timeout(),
+        config.control.link_loss_policy,
+    );
+    if config.control.source == config::ControlSource::Db {
+        tasks.spawn("control.db", control::db(db.clone(), commands.clone(), token.child_token()));
+    }
+    tasks.spawn("link.events", link_events(
+        commands.link(),
+        config.control.dead_timeout(),
+        writer.clone(),
+        clock.clone(),
+        token.child_token(),
+    ));
 
-                    match stream {
-                        Ok(mut s) => {
-                            while !token.is_cancelled() {
-                                let control =
-                                    timeout(Duration::from_millis(DEAD_TIMEOUT), s.next()).await;
-                                match control {
-                                    Ok(data) => {
-                                        if data.is_none() {
-                                            continue;
-                                        }
-
-                                        let data = data.unwrap();
-                                        match data {
-                                            Ok(data) => {
-                                                if data.action != surrealdb::Action::Update {
-                                                    continue;
-                                                }
-
-                                                println!(
-                                                    "[CONTROL] Steer: {} Speed: {}",
-                                                    data.data.steer, data.data.speed
-                                                );
-                                            }
-
-                                            Err(e) => {
-                                                eprintln!(
-                                                    "[CONTROL] Erreur lors de l'update: {}",
-                                                    e
-                                                );
-                                            }
-                                        }
-                                    }
-                                    Err(_) => {
-                                        eprintln!("[CONTROL] Update tardif des données...");
-                                    }
-                                }
-                            }
-                        }
-                        Err(e) => {
-                            eprintln!("[CONTROL] Erreur lors de la création du live: {}", e);
-                        }
-                    }
+    // Calibration du magnétomètre, commandée par la base (control:mag_calibration)
+    if config.sensors.mag.mode != SensorMode::Disabled {
+        let (requests, received) = tokio::sync::mpsc::channel(4);
+        tasks.spawn("mag_calibration.db", forward_live(
+            "mag",
+            db.clone(),
+            |db| async move { db.live_mag_calibration().await },
+            requests,
+            token.child_token(),
+        ));
+        tasks.spawn("mag_calibration", mag_calibration_guard(
+            config.sensors.mag.clone(),
+            db.clone(),
+            received,
+            mag_calibration,
+            writer.clone(),
+            clock.clone(),
+            token.child_token(),
+        ));
+    }
+
+    // Attitude et cap fusionnés de l'IMU et du magnétomètre
+    if config.fusion.enabled {
+        tasks.spawn("fusion", attitude_fusion(config.fusion.clone(), writer.clone(), token.child_token()));
+    }
+
+    // Cohérence de la route GPS et du cap du magnétomètre
+    if config.heading_check.enabled {
+        tasks.spawn("heading_check", heading_check_guard(
+            config.heading_check.clone(),
+            writer.clone(),
+            token.child_token(),
+        ));
+    }
+
+    // Horloge système réglée sur l'heure GPS (pas de RTC), jamais sur un GPS simulé
+    if config.gps_time.enabled {
+        if config.sensors.gps.mode == SensorMode::Real {
+            tasks.spawn("gps_time", gps_time_guard(config.gps_time.clone(), writer.clone(), token.child_token()));
+        } else {
+            tracing::info!(target: "gps_time", "Réglage de l'horloge ignoré: GPS simulé");
+        }
+    }
+
+    // Coupure du moteur si la voiture est retournée, levée par la base (control:rollover)
+    if config.rollover.enabled {
+        let (resets, received) = tokio::sync::mpsc::channel(4);
+        tasks.spawn("rollover.db", forward_live(
+            "rollover",
+            db.clone(),
+            |db| async move { db.live_rollover().await },
+            resets,
+            token.child_token(),
+        ));
+        tasks.spawn("rollover", rollover_guard(
+            config.rollover.clone(),
+            received,
+            writer.clone(),
+            commands.clone(),
+            clock.clone(),
+            token.child_token(),
+        ));
+    }
+
+    // Marche avant bloquée devant un obstacle
+    if config.obstacle.enabled {
+        tasks.spawn("obstacle", obstacle_guard(
+            config.obstacle.clone(),
+            writer.clone(),
+            commands.clone(),
+            clock.clone(),
+            token.child_token(),
+        ));
+    }
+
+    // Coupure du moteur sur tension basse de la batterie
+    if config.low_voltage.enabled {
+        tasks.spawn("low_voltage", low_voltage_guard(
+            config.low_voltage.clone(),
+            writer.clone(),
+            commands.clone(),
+            clock.clone(),
+            token.child_token(),
+        ));
+    }
+
+    // Capacité consommée de la batterie, remise à zéro par la base (control:battery)
+    if config.capacity.enabled {
+        let (resets, received) = tokio::sync::mpsc::channel(4);
+        tasks.spawn("capacity.db", forward_live(
+            "capacity",
+            db.clone(),
+            |db| async move { db.live_capacity().await },
+            resets,
+            token.child_token(),
+        ));
+        tasks.spawn("capacity", capacity_guard(
+            config.capacity.clone(),
+            received,
+            writer.clone(),
+            clock.clone(),
+            token.child_token(),
+        ));
+    }
+
+    // Limitation de la vitesse selon les températures de l'ESC et du moteur
+    if config.thermal.enabled {
+        tasks.spawn("thermal", thermal_guard(
+            config.thermal.clone(),
+            writer.clone(),
+            commands.clone(),
+            clock.clone(),
+            token.child_token(),
+        ));
+    }
+
+    // Zones GPS: vitesse limitée, arrêt hors de la zone autorisée
+    if config.geofence.enabled {
+        tasks.spawn("geofence", geofence_guard(
+            config.geofence.clone(),
+            writer.clone(),
+            commands.clone(),
+            clock.clone(),
+            token.child_token(),
+        ));
+    }
+
+    // Fraîcheur des capteurs, indépendante du thread de lecture. Santé des composants partagée
+    // avec le watchdog systemd.
+    let (health, health_updates) = watch::channel(None);
+    if config.watchdog.enabled {
+        tasks.spawn("watchdog", sensor_watchdog(
+            config.clone(),
+            writer.clone(),
+            commands.clone(),
+            health,
+            clock.clone(),
+            token.child_token(),
+        ));
+    }
+
+    // Serveur HTTP local (WebSocket, API, état et santé)
+    if config.http.enabled {
+        tasks.spawn("http", http::run(
+            config.http.clone(),
+            writer.clone(),
+            clock.clone(),
+            metrics.clone(),
+            commands.clone(),
+            health_updates.clone(),
+            token.child_token(),
+        ));
+    }
+
+    // LED d'état, factice en rejeu, en dry-run ou sans actionneurs réels
+    if config.status_led.enabled {
+        let config = config.status_led.clone();
+        let health = health_updates.clone();
+        let writer = writer.clone();
+        let commands = commands.clone();
+        let token = token.child_token();
+        tasks.spawn("status_led", async move {
+            #[cfg(feature = "real-actuators")]
+            if !(replay || dry_run) {
+                match actuators::status_led::open(config.pin) {
+                    Ok(pin) => status_led(pin, config, health, writer, commands, token).await,
+                    Err(e) => tracing::error!(target: "status_led", "Erreur lors de l'init de la LED: {}", e),
                 }
+                return;
             }
+            status_led(actuators::status_led::FakeLed, config, health, writer, commands, token).await;
         });
     }
 
-    #[cfg(unix)]
+    // Vérifications avant armement
+    if config.prearm.enabled {
+        tasks.spawn("prearm", prearm_checks(
+            config.prearm.clone(),
+            writer.clone(),
+            commands.clone(),
+            selftest.clone(),
+            clock.clone(),
+            token.child_token(),
+        ));
+    }
+
+    // Désarmement automatique (durée armé, inactivité)
+    let power_off = CancellationToken::new();
+    if config.auto_disarm.max_armed_min > 0 || config.auto_disarm.idle_min > 0 {
+        tasks.spawn("auto_disarm", auto_disarm_guard(
+            config.auto_disarm.clone(),
+            writer.clone(),
+            commands.clone(),
+            clock.clone(),
+            power_off.clone(),
+            token.child_token(),
+        ));
+    }
+
+    // Limite de vitesse active, vitesses commandée et appliquée (status:control)
+    tasks.spawn("limit.status", limit_status(commands.state(), writer.clone(), clock.clone(), token.child_token()));
+    tasks.spawn("speed.status", speed_status(commands.state(), writer.clone(), clock.clone(), token.child_token()));
+
+    // Serveur gRPC (télémétrie et commandes)
+    if config.grpc.enabled {
+        tasks.spawn("grpc", grpc::run(
+            config.grpc.clone(),
+            writer.clone(),
+            commands.clone(),
+            clock.clone(),
+            config.vehicle.clone(),
+            run.state.id.clone(),
+            token.child_token(),
+        ));
+    }
+
+    // Journal blackbox de l'exécution (commandes et sorties des actionneurs comprises)
+    if config.blackbox.enabled {
+        tasks.spawn("blackbox", blackbox::run(
+            config.blackbox.clone(),
+            config.sensors.clone(),
+            run.state.id.clone(),
+            writer.clone(),
+            commands.state(),
+            clock.clone(),
+            token.child_token(),
+        ));
+    }
+
+    // Surveillance de l'arrêt d'urgence, de la batterie et de la télémétrie
+    if config.alerts.enabled {
+        tasks.spawn("alerts.watch", alerts::watch(
+            config.alerts.clone(),
+            writer.clone(),
+            commands.armed(),
+            clock.clone(),
+            token.child_token(),
+        ));
+    }
+
+    // Broker MQTT (disponibilité, entités Home Assistant, armement, télémétrie et commandes)
+    if config.mqtt.enabled {
+        let control = (config.control.source == config::ControlSource::Mqtt)
+            .then(|| mqtt::control::Sequence::new(config.control.dead_timeout()));
+        tasks.spawn("mqtt", mqtt::run(
+            config.mqtt.clone(),
+            config.vehicle.clone(),
+            writer.clone(),
+            commands,
+            control,
+            token.child_token(),
+        ));
+    }
+
+    // Actionneurs requis (control.required) indisponibles: arrêt du programme
+    let control_failed = CancellationToken::new();
     {
-        let mut test = tokio::signal::unix::signal(SignalKind::interrupt()).unwrap();
-        tokio::select! {
-            _ = test.recv() => {
-                println!("Signal d'interruption reçu");
-                // token.cancel();
-            },
-            _ = signal::ctrl_c() => {
-                println!("Signal de contrôle C reçu");
-                // token.cancel();
-            },
+        let token = token.child_token();
+        let selftest = selftest.clone();
+        let simulation = simulation.clone();
+        let control_config = config.control.clone();
+        #[cfg(feature = "real-actuators")]
+        let (required, failed) = (config.control.required, control_failed.clone());
+        tasks.spawn("control", async move {
+            // Le rejeu et le dry-run forcent les actionneurs factices
+            if replay || dry_run {
+                control::fake(arbiter, control_config, simulation, selftest, token).await;
+                return;
+            }
+
+            #[cfg(feature = "real-actuators")]
+            if !control::hardware(arbiter, control_config, selftest, token).await && required {
+                failed.cancel();
+            }
+
+            #[cfg(feature = "fake-actuators")]
+            control::fake(arbiter, control_config, simulation, selftest, token).await;
+        });
+    }
+
+    // Service systemd prêt (base connectée, tâches lancées), pings du watchdog selon la santé
+    #[cfg(feature = "systemd")]
+    let notifier = systemd_ready(&config, health_updates, &mut tasks, token.child_token());
+    #[cfg(not(feature = "systemd"))]
+    drop(health_updates);
+
+    tokio::select! {
+        signal = signals.recv() => {
+            tracing::info!(target: "main", "Signal {} reçu, arrêt ...", signal);
+        },
+        _ = power_off.cancelled() => {
+            tracing::info!(target: "main", "Arrêt après inactivité");
+        },
+        _ = control_failed.cancelled() => {
+            tracing::error!(target: "control", "Actionneurs requis indisponibles (control.required), arrêt ...");
+        },
+    }
+
+    #[cfg(feature = "systemd")]
+    if let Some(notifier) = notifier.as_ref() {
+        if let Err(e) = notifier.notify("STOPPING=1") {
+            tracing::error!(target: "systemd", "Notification de l'arrêt impossible: {}", e);
         }
     }
 
-    #[cfg(not(unix))]
-    {
+    // Arrêt propre: fin des tâches (actionneurs au neutre, files de l'écrivain écrites)
+    token.cancel();
+    tasks.shutdown(SHUTDOWN_GRACE).await;
+    run.shutdown();
+    if let Some(logs) = logs {
+        logs.close();
+    }
+
+    // Relâchement du maintien de l'alimentation, en dernier
+    if power_off.is_cancelled() && config.auto_disarm.idle_action == config::IdleAction::PowerLatch {
+        #[cfg(feature = "real-actuators")]
+        if let Err(e) =
+            actuators::power_latch::release(config.auto_disarm.latch_pin, config.auto_disarm.latch_release_high)
+        {
+            tracing::error!(target: "power", "Impossible de relâcher le maintien de l'alimentation: {}", e);
+        }
+        #[cfg(not(feature = "real-actuators"))]
+        tracing::info!(target: "power", "Maintien de l'alimentation simulé: relâché");
+    }
+
+    if control_failed.is_cancelled() {
+        std::process::exit(1);
+    }
+}
+
+/// Outils hors exécution, sans capteurs (base uniquement pour l'export depuis la base)
+async fn run_command(command: &args::Command, config_path: &std::path::Path) -> anyhow::Result<()> {
+    match command {
+        args::Command::Decode { input, output } => {
+            let written = match output {
+                Some(path) => {
+                    let mut file = std::io::BufWriter::new(std::fs::File::create(path)?);
+                    sensors::replay::decode(input, &mut file)?
+                }
+                None => sensors::replay::decode(input, &mut std::io::stdout().lock())?,
+            };
+            tracing::info!(target: "decode", "{} échantillon(s) convertis.", written);
+        }
+        args::Command::Export {
+            run,
+            format,
+            source,
+            out,
+            journal,
+            battery_min,
+        } => {
+            let journal = match journal {
+                Some(journal) => journal.clone(),
+                None => config::Config::load(config_path)?.jsonl.directory,
+            };
+            let options = export::Options {
+                run: run.clone(),
+                format: *format,
+                out: out.clone(),
+                journal,
+                battery_min: *battery_min,
+            };
+            let summary = match source {
+                export::Source::Journal => export::export(&options)?,
+                export::Source::Db => {
+                    let config = config::Config::load(config_path)?;
+                    let db = database::Database::new(&config.database, false).await?;
+                    export::export_db(&options, &db).await?
+                }
+            };
+            if summary.skipped > 0 {
+                tracing::warn!(target: "export", "{} ligne(s) illisible(s) ignorée(s).", summary.skipped);
+            }
+            // Fichiers produits sur la sortie standard, un par ligne
+            for file in summary.files.iter() {
+                println!("{}", file.display());
+            }
+            tracing::info!(target: "export", "{} échantillon(s) exporté(s).", summary.records);
+        }
+        args::Command::Blackbox { input, output } => {
+            let log = blackbox::decode::Log::parse(&std::fs::read(input)?)?;
+            if let Some(start) = log.headers.get("Log start datetime") {
+                tracing::info!(target: "decode", "Journal démarré le {}", start);
+            }
+            match output {
+                Some(path) => log.write_csv(&mut std::io::BufWriter::new(std::fs::File::create(path)?))?,
+                None => log.write_csv(&mut std::io::stdout().lock())?,
+            }
+            if !log.complete {
+                tracing::warn!(target: "decode", "Journal incomplet (arrêt non propre).");
+            }
+            tracing::info!(target: "decode", "{} trame(s) convertie(s).", log.frames.len());
+        }
+    }
+
+    Ok(())
+}
+
+/// Recharge la configuration sur SIGHUP ou modification du fichier.
+/// Seuls les paramètres modifiables à chaud sont appliqués, une configuration invalide est ignorée.
+async fn config_reload(
+    path: std::path::PathBuf,
+    sender: watch::Sender<config::Config>,
+    writer: writer::Writer,
+    clock: clock::Clock,
+    token: CancellationToken,
+) {
+    let modified = |path: &std::path::Path| std::fs::metadata(path).and_then(|m| m.modified()).ok();
+    let mut last_modified = modified(&path);
+
+    #[cfg(unix)]
+    let mut hangup = match tokio::signal::unix::signal(SignalKind::hangup()) {
+        Ok(signal) => Some(signal),
+        Err(e) => {
+            tracing::error!(target: "config", "Impossible d'écouter SIGHUP: {}", e);
+            None
+        }
+    };
+
+    loop {
+        #[cfg(unix)]
+        let hangup = async {
+            match hangup.as_mut() {
+                Some(signal) => signal.recv().await,
+                None => std::future::pending().await,
+            }
+        };
+        #[cfg(not(unix))]
+        let hangup = std::future::pending::<Option<()>>();
+
         tokio::select! {
-            _ = signal::ctrl_c() => {
-                println!("Signal de contrôle C reçu");
-                token.cancel();
-            },
+            _ = token.cancelled() => return,
+            _ = hangup => tracing::info!(target: "config", "SIGHUP reçu, rechargement ..."),
+            _ = sleep(CONFIG_POLL) => {
+                let current = modified(&path);
+                if current == last_modified {
+                    continue;
+                }
+                tracing::info!(target: "config", "Fichier modifié, rechargement ...");
+            }
+        }
+        last_modified = modified(&path);
+
+        let result = config::Config::load_existing(&path).and_then(|new| {
+            new.validate()?;
+            sender.borrow().reload(&new)
+        });
+
+        let (active, diff) = match result {
+            Ok(result) => result,
+            Err(e) => {
+                let message = format!("Rechargement refusé, configuration précédente conservée: {}", e);
+                tracing::error!(target: "config", "{}", message);
+                let _ = writer.event(writer::Event::Warning(message, clock.stamp())).await;
+                continue;
+            }
+        };
+
+        if diff.applied.is_empty() && diff.restart.is_empty() {
+            tracing::info!(target: "config", "Aucun changement.");
+            continue;
+        }
+
+        for change in diff.applied.iter() {
+            tracing::info!(target: "config", "Appliqué: {}", change);
+            let message = format!("Appliqué: {}", change);
+            let _ = writer.event(writer::Event::Config(message, clock.stamp())).await;
+        }
+
+        for change in diff.restart.iter() {
+            tracing::info!(target: "config", "Redémarrage nécessaire, non appliqué: {}", change);
+            let message = format!("Redémarrage nécessaire, non appliqué: {}", change);
+            let _ = writer.event(writer::Event::Config(message, clock.stamp())).await;
+        }
+
+        if !diff.applied.is_empty() {
+            sender.send_replace(active);
+        }
+    }
+}
+
+/// Coupure du moteur selon l'attitude de l'IMU (dernier échantillon reçu par l'écrivain) et sur
+/// un choc (chaque échantillon). Un échantillon qui ne change plus depuis `stale_ms` désactive la
+/// règle, le réarmement du véhicule lève la coupure.
+async fn rollover_guard(
+    config: config::RolloverConfig,
+    mut resets: tokio::sync::mpsc::Receiver<actuators::rollover::RolloverReset>,
+    writer: writer::Writer,
+    commands: actuators::arbiter::Commands,
+    clock: clock::Clock,
+    token: CancellationToken,
+) {
+    let mut rollover = actuators::rollover::Rollover::new(&config);
+    let stale = Duration::from_millis(config.stale_ms);
+    let mut armed = commands.armed();
+    // Chaque échantillon de l'IMU pour la détection des chocs, brefs entre deux vérifications
+    let mut records = writer.subscribe();
+    let mut last_stamp = None;
+    let mut last_change = clock.now();
+    let mut last_check = clock.now();
+
+    loop {
+        // Vérification périodique de l'attitude, non retardée par les échantillons reçus
+        let check = ROLLOVER_CHECK.saturating_sub(clock.since(last_check));
+        let transition = tokio::select! {
+            _ = token.cancelled() => break,
+            Ok(()) = armed.changed() => {
+                if !*armed.borrow_and_update() {
+                    continue;
+                }
+                rollover.rearm()
+            }
+            Some(_) = resets.recv() => rollover.rearm(),
+            record = records.recv(), if config.crash_g > 0.0 => match record {
+                Ok(record::Record::Imu(imu)) => rollover.impact(&imu),
+                Ok(_) | Err(tokio::sync::broadcast::error::RecvError::Lagged(_)) => continue,
+                Err(tokio::sync::broadcast::error::RecvError::Closed) => break,
+            },
+            _ = clock.sleep(check) => {
+                last_check = clock.now();
+                let imu = writer.latest().data.imu;
+                if last_stamp != Some(imu.stamp) {
+                    last_stamp = Some(imu.stamp);
+                    last_change = clock.now();
+                }
+                let fresh = imu.stamp.mono_us != 0 && clock.since(last_change) < stale;
+                rollover.update(fresh.then_some(&imu), clock.now())
+            }
+        };
+
+        let Some(transition) = transition else {
+            continue;
+        };
+        let message = transition.message();
+        tracing::info!(target: "rollover", "{}", message);
+        commands.cutoff("rollover", transition.active());
+        let _ = writer
+            .event(writer::Event::Rollover(transition.event(), transition.active(), message, clock.stamp()))
+            .await;
+    }
+
+    commands.cutoff("rollover", false);
+}
+
+/// Enregistrements de contrôle créés ou modifiés (live de la base ouvert par `open_live`),
+/// transmis à la tâche qui les applique. Le live est recréé après une reconnexion à la base, avec
+/// une attente croissante en cas d'échec. `name`: nom du live dans le journal.
+async fn forward_live<T, F, Fut>(
+    name: &'static str,
+    db: Arc<Database>,
+    open_live: F,
+    tx: tokio::sync::mpsc::Sender<T>,
+    token: CancellationToken,
+) where
+    T: serde::de::DeserializeOwned + Unpin,
+    F: Fn(Arc<Database>) -> Fut,
+    Fut: std::future::Future<
+        Output = anyhow::Result<surrealdb::method::Stream<'static, surrealdb::engine::any::Any, Option<T>>>,
+    >,
+{
+    let mut live = live::Live::new(name);
+
+    while !token.is_cancelled() {
+        tokio::select! {
+            _ = token.cancelled() => return,
+            _ = db.connected() => {}
+        }
+        let reconnects = db.connection().reconnects;
+
+        let mut stream = match open_live(db.clone()).await {
+            Ok(stream) => stream,
+            Err(e) => {
+                if !live.failed(&token, format!("création impossible: {}", e)).await {
+                    return;
+                }
+                continue;
+            }
+        };
+
+        loop {
+            let data = tokio::select! {
+                _ = token.cancelled() => return,
+                _ = db.reconnected(reconnects) => break,
+                data = stream.next() => data,
+            };
+
+            match data {
+                Some(Ok(data)) => {
+                    live.received();
+                    if matches!(data.action, surrealdb::Action::Create | surrealdb::Action::Update)
+                        && tx.send(data.data).await.is_err()
+                    {
+                        return;
+                    }
+                }
+                Some(Err(e)) => tracing::error!(target: "db", live = name, "Erreur lors de l'update: {}", e),
+                None => {
+                    if !live.failed(&token, "flux terminé").await {
+                        return;
+                    }
+                    break;
+                }
+            }
+        }
+    }
+}
+
+/// Fusion de chaque échantillon de l'IMU avec le dernier échantillon du magnétomètre reçu par
+/// l'écrivain, l'attitude est publiée à la fréquence de l'IMU
+async fn attitude_fusion(config: config::FusionConfig, writer: writer::Writer, token: CancellationToken) {
+    let mut filter = fusion::Madgwick::new(config.beta);
+    let mag_stale_us = config.mag_stale_ms * 1000;
+    let mut records = writer.subscribe();
+    let mut mag = None;
+    let mut last_us = None;
+
+    loop {
+        let record = tokio::select! {
+            _ = token.cancelled() => return,
+            record = records.recv() => match record {
+                Ok(record) => record,
+                Err(tokio::sync::broadcast::error::RecvError::Lagged(_)) => continue,
+                Err(tokio::sync::broadcast::error::RecvError::Closed) => return,
+            },
+        };
+
+        let imu = match record {
+            record::Record::Mag(data) if config.magnetometer => {
+                mag = Some(data);
+                continue;
+            }
+            record::Record::Imu(data) => data,
+            _ => continue,
+        };
+
+        // Premier échantillon ou interruption: l'intervalle n'est pas intégré
+        let now_us = imu.stamp.mono_us;
+        let dt_us = last_us.map(|last: u64| now_us.saturating_sub(last));
+        last_us = Some(now_us);
+        let Some(dt_us) = dt_us.filter(|dt_us| *dt_us <= fusion::MAX_GAP_US) else {
+            continue;
+        };
+
+        let field = mag
+            .filter(|mag| now_us.saturating_sub(mag.stamp.mono_us) <= mag_stale_us)
+            .map(|mag| mag.calibrated);
+        filter.update(imu.gyro, imu.accel, field, dt_us as f32 / 1e6);
+
+        let event = writer::Event::Attitude(filter.attitude(), imu.stamp);
+        let _ = writer.event(event).await;
+    }
+}
+
+/// Comparaison de chaque échantillon GPS avec fix au dernier échantillon du magnétomètre reçu par
+/// l'écrivain: l'écart est publié à la fréquence du GPS, en mouvement uniquement
+async fn heading_check_guard(config: config::HeadingCheckConfig, writer: writer::Writer, token: CancellationToken) {
+    let mut check = heading_check::HeadingCheck::new(&config);
+    let mag_stale_us = config.mag_stale_ms * 1000;
+    let mut records = writer.subscribe();
+    let mut mag = None;
+
+    loop {
+        let record = tokio::select! {
+            _ = token.cancelled() => return,
+            record = records.recv() => match record {
+                Ok(record) => record,
+                Err(tokio::sync::broadcast::error::RecvError::Lagged(_)) => continue,
+                Err(tokio::sync::broadcast::error::RecvError::Closed) => return,
+            },
+        };
+
+        let gps = match record {
+            record::Record::Mag(data) => {
+                mag = Some(data);
+                continue;
+            }
+            record::Record::Gps(data) if data.fix => data,
+            _ => continue,
+        };
+
+        let Some(mag) = mag.filter(|mag| gps.stamp.mono_us.saturating_sub(mag.stamp.mono_us) <= mag_stale_us) else {
+            continue;
+        };
+        let speed_ms = (gps.speed_kmh / 3.6) as f32;
+        let Some((error, transition)) = check.update(gps.heading as f32, speed_ms, mag.true_heading, gps.stamp.mono_us)
+        else {
+            continue;
+        };
+
+        let _ = writer.event(writer::Event::HeadingError(error, gps.stamp)).await;
+        if let Some(transition) = transition {
+            let message = transition.message();
+            tracing::info!(target: "heading", "{}", message);
+            let event = writer::Event::MagSuspect(transition.suspect(), transition.severity(), message, gps.stamp);
+            let _ = writer.event(event).await;
+        }
+    }
+}
+
+/// Modem 4G: recherche du modem auprès de ModemManager, lecture de son état tant qu'il est
+/// exposé, nouvelle recherche quand il disparaît (redémarrage du modem: nouveau chemin)
+async fn modem_task(
+    config: config::ModemConfig,
+    writer: writer::Writer,
+    clock: clock::Clock,
+    selftest: selftest::SelfTest,
+    token: CancellationToken,
+) {
+    while !token.is_cancelled() {
+        if let Err(e) = modem_session(&config, &writer, &clock, &selftest, &token).await {
+            tracing::warn!(target: "modem", "ModemManager inaccessible: {}", e);
+            if !selftest.contains("modem") {
+                selftest.record("modem", Err(e));
+            }
+        }
+
+        tokio::select! {
+            _ = token.cancelled() => {}
+            _ = sleep(MODEM_RETRY) => {}
+        }
+    }
+}
+
+/// Connexion au D-Bus système et suivi des modems, jusqu'à une erreur du D-Bus ou l'arrêt
+async fn modem_session(
+    config: &config::ModemConfig,
+    writer: &writer::Writer,
+    clock: &clock::Clock,
+    selftest: &selftest::SelfTest,
+    token: &CancellationToken,
+) -> anyhow::Result<()> {
+    let connection = Connection::system().await?;
+    let manager = modem::manager(&connection).await?;
+    // Abonnement avant la liste des modems: aucun ajout ne peut être manqué entre les deux
+    let mut added = manager.receive_interfaces_added().await?;
+    let mut removed = manager.receive_interfaces_removed().await?;
+    let mut waiting = false;
+
+    while !token.is_cancelled() {
+        let Some(path) = modem::select(&manager.get_managed_objects().await?, config) else {
+            if !waiting {
+                tracing::warn!(target: "modem", "Aucun modem exposé par ModemManager, en attente");
+                waiting = true;
+            }
+            if !selftest.contains("modem") {
+                selftest.record("modem", Err(anyhow::anyhow!("Aucun modem exposé par ModemManager")));
+            }
+
+            tokio::select! {
+                _ = token.cancelled() => {}
+                _ = added.next() => {}
+                _ = sleep(MODEM_RETRY) => {}
+            }
+            continue;
+        };
+        waiting = false;
+
+        tracing::info!(target: "modem", "Modem sélectionné: {}", path);
+        let modem = modem::Modem::new(&connection, path.as_str()).await?;
+        if let Err(e) = modem.setup_signal().await {
+            tracing::warn!(target: "modem", "Mesures étendues (RSRP, RSRQ, SINR) indisponibles: {}", e);
+        }
+
+        let mut ticker = tokio::time::interval(MODEM_PERIOD);
+        loop {
+            tokio::select! {
+                _ = token.cancelled() => return Ok(()),
+                signal = removed.next() => {
+                    let signal = signal.ok_or_else(|| anyhow::anyhow!("signaux de ModemManager interrompus"))?;
+                    let gone = signal.args().is_ok_and(|args| {
+                        args.object_path.as_str() == path.as_str() && args.interfaces.contains(&modem::MODEM)
+                    });
+                    if gone {
+                        tracing::warn!(target: "modem", "Modem {} retiré par ModemManager", path);
+                        break;
+                    }
+                }
+                _ = ticker.tick() => {
+                    let status = modem.status().await;
+
+                    if !selftest.contains("modem") {
+                        let result = status.as_ref().map(|_| format!("Modem présent ({})", path));
+                        selftest.record("modem", result.map_err(|e| anyhow::anyhow!("{}", e)));
+                    }
+
+                    match status {
+                        Ok(status) => {
+                            tracing::debug!(target: "modem", "Signal: {}", status.quality);
+
+                            writer.modem(writer::ModemData {
+                                status,
+                                stamp: clock.stamp(),
+                            });
+                        }
+                        // Modem retiré sans signal (redémarrage de ModemManager): nouvelle recherche
+                        Err(e) => {
+                            tracing::warn!(target: "modem", "Impossible de lire l'état du modem {}: {}", path, e);
+                            sleep(MODEM_PERIOD).await;
+                            break;
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Réglage de l'horloge système sur l'heure GPS, au plus une fois par exécution: le premier
+/// échantillon avec fix et trame RMC active décide. Aucun réglage si l'horloge est synchronisée
+/// (NTP) ou proche de l'heure GPS.
+async fn gps_time_guard(config: config::GpsTimeConfig, writer: writer::Writer, token: CancellationToken) {
+    let threshold = Duration::from_secs_f64(config.threshold_s);
+    let mut records = writer.subscribe();
+
+    let (time, stamp) = loop {
+        let record = tokio::select! {
+            _ = token.cancelled() => return,
+            record = records.recv() => match record {
+                Ok(record) => record,
+                Err(tokio::sync::broadcast::error::RecvError::Lagged(_)) => continue,
+                Err(tokio::sync::broadcast::error::RecvError::Closed) => return,
+            },
+        };
+
+        if let record::Record::Gps(gps) = record {
+            if let Some(time) = gps_time::gps_time(&gps) {
+                break (time, gps.stamp);
+            }
+        }
+    };
+
+    if gps_time::synchronized() {
+        tracing::info!(target: "gps_time", "Horloge système synchronisée, réglage GPS inutile");
+        return;
+    }
+
+    let system = chrono::Utc::now();
+    let Some(offset) = gps_time::offset(time, system, threshold) else {
+        tracing::info!(target: "gps_time", "Horloge système à moins de {} s de l'heure GPS, aucun réglage", config.threshold_s);
+        return;
+    };
+    let offset_s = offset.num_milliseconds() as f64 / 1000.0;
+
+    if !gps_time::can_set_time() {
+        tracing::error!(
+            target: "gps_time",
+            "Réglage de l'horloge impossible (écart de {:+.3} s): capacité CAP_SYS_TIME manquante",
+            offset_s
+        );
+        return;
+    }
+
+    // Ecart appliqué à l'heure présente: le temps passé depuis la comparaison est conservé
+    if let Err(e) = gps_time::set_system_time(chrono::Utc::now() + offset) {
+        tracing::error!(target: "gps_time", "Réglage de l'horloge impossible (écart de {:+.3} s): {}", offset_s, e);
+        return;
+    }
+
+    let message = format!(
+        "Horloge système réglée sur l'heure GPS: {} -> {} (écart appliqué: {:+.3} s)",
+        system.format("%Y-%m-%d %H:%M:%S UTC"),
+        time.format("%Y-%m-%d %H:%M:%S UTC"),
+        offset_s
+    );
+    tracing::info!(target: "gps_time", "{}", message);
+    let _ = writer.event(writer::Event::Alert("clock_set", config::Severity::Info, message, stamp)).await;
+}
+
+/// Blocage de la marche avant selon la distance de l'obstacle (dernière mesure reçue par
+/// l'écrivain). Une mesure qui ne change plus depuis `stale_ms` désactive la règle.
+async fn obstacle_guard(
+    config: config::ObstacleConfig,
+    writer: writer::Writer,
+    commands: actuators::arbiter::Commands,
+    clock: clock::Clock,
+    token: CancellationToken,
+) {
+    let mut obstacle = actuators::obstacle::Obstacle::new(&config);
+    let stale = Duration::from_millis(config.stale_ms);
+    let mut last_stamp = None;
+    let mut last_change = clock.now();
+
+    loop {
+        tokio::select! {
+            _ = token.cancelled() => break,
+            _ = clock.sleep(OBSTACLE_CHECK) => {}
+        }
+
+        let range = writer.latest().data.range;
+        if last_stamp != Some(range.stamp) {
+            last_stamp = Some(range.stamp);
+            last_change = clock.now();
+        }
+        let fresh = range.stamp.mono_us != 0 && clock.since(last_change) < stale;
+        let Some(transition) = obstacle.update(fresh.then_some(&range)) else {
+            continue;
+        };
+
+        let message = transition.message();
+        tracing::info!(target: "obstacle", "{}", message);
+        commands.limit_forward("obstacle", transition.active().then_some(0.0));
+        let event = writer::Event::Alert(transition.event(), transition.severity(), message, clock.stamp());
+        let _ = writer.event(event).await;
+    }
+
+    commands.limit_forward("obstacle", None);
+}
+
+/// Protection de la batterie: chaque nouvel échantillon analogique est évalué avec le courant
+/// (CAN) ou la vitesse appliquée. La coupure finale désarme le véhicule et n'est jamais levée.
+async fn low_voltage_guard(
+    config: config::LowVoltageConfig,
+    writer: writer::Writer,
+    commands: actuators::arbiter::Commands,
+    clock: clock::Clock,
+    token: CancellationToken,
+) {
+    let mut guard = actuators::low_voltage::LowVoltage::new(&config);
+    let current = config.current_signal.split_once('.');
+    let state = commands.state();
+    let mut last_stamp = None;
+
+    while !guard.latched() {
+        tokio::select! {
+            _ = token.cancelled() => return,
+            _ = clock.sleep(LOW_VOLTAGE_CHECK) => {}
+        }
+
+        let analog = writer.latest().data.analog;
+        if analog.stamp.mono_us == 0 || last_stamp == Some(analog.stamp) {
+            continue;
+        }
+        last_stamp = Some(analog.stamp);
+
+        let sample = actuators::low_voltage::Sample {
+            voltage: analog.battery,
+            current: current.and_then(|(message, signal)| {
+                writer.latest_can().get(message)?.signals.get(signal).map(|value| *value as f32)
+            }),
+            throttle: state.borrow().output.speed,
+        };
+        let Some(step) = guard.update(&sample, clock.now()) else {
+            continue;
+        };
+
+        let message = step.message();
+        tracing::info!(target: "battery", "{}", message);
+        commands.limit("low_voltage", Some(step.max()));
+        if matches!(step, actuators::low_voltage::Step::Cutoff { .. }) {
+            commands.arm("low_voltage", false);
+        }
+        let event = writer::Event::Alert(step.kind(), step.severity(), message, clock.stamp());
+        let _ = writer.event(event).await;
+    }
+}
+
+/// Capacité consommée de la batterie: intègre le courant de chaque nouvel échantillon analogique
+/// et publie la capacité consommée et restante périodiquement et après une remise à zéro.
+async fn capacity_guard(
+    config: config::CapacityConfig,
+    mut resets: tokio::sync::mpsc::Receiver<capacity::CapacityReset>,
+    writer: writer::Writer,
+    clock: clock::Clock,
+    token: CancellationToken,
+) {
+    let mut capacity = capacity::Capacity::new(config.capacity_mah);
+    let mut last_stamp = None;
+    let mut published: Option<Instant> = None;
+
+    loop {
+        tokio::select! {
+            _ = token.cancelled() => return,
+            reset = resets.recv() => {
+                let Some(reset) = reset else { return };
+                tracing::info!(target: "capacity", "Remise à zéro: {:.0} mAh consommés", reset.consumed_mah);
+                capacity.reset(reset.consumed_mah);
+                published = None;
+            }
+            _ = clock.sleep(CAPACITY_CHECK) => {}
+        }
+
+        let analog = writer.latest().data.analog;
+        if analog.stamp.mono_us != 0 && last_stamp != Some(analog.stamp) {
+            last_stamp = Some(analog.stamp);
+            capacity.update(analog.stamp.mono_us, analog.current);
+        }
+
+        let now = clock.now();
+        if published.is_some_and(|published| now.saturating_duration_since(published) < CAPACITY_STATUS) {
+            continue;
+        }
+        published = Some(now);
+        let event = writer::Event::Capacity(capacity.consumed_mah(), capacity.remaining_pct(), clock.stamp());
+        let _ = writer.event(event).await;
+    }
+}
+
+/// Calibration du magnétomètre: la dernière calibration enregistrée est chargée à la connexion à
+/// la base, puis chaque collecte (start) accumule les mesures brutes jusqu'à stop ou
+/// `calibration_timeout_s`. La calibration ajustée est appliquée aux mesures et enregistrée.
+async fn mag_calibration_guard(
+    config: config::MagConfig,
+    db: Arc<Database>,
+    mut requests: tokio::sync::mpsc::Receiver<sensors::mag::calibration::Request>,
+    calibration: watch::Sender<config::MagCalibration>,
+    writer: writer::Writer,
+    clock: clock::Clock,
+    token: CancellationToken,
+) {
+    use sensors::mag::calibration::Command;
+
+    let timeout = Duration::from_secs(config.calibration_timeout_s);
+    let mut loaded = false;
+    // Collecte en cours: mesures, début et abonnement aux échantillons
+    let mut samples = Vec::new();
+    let mut session: Option<(Instant, tokio::sync::broadcast::Receiver<record::Record>)> = None;
+
+    loop {
+        let collecting = session.is_some();
+        let remaining = session
+            .as_ref()
+            .map_or(timeout, |(started, _)| timeout.saturating_sub(clock.now().saturating_duration_since(*started)));
+        let record = async {
+            match session.as_mut() {
+                Some((_, records)) => records.recv().await,
+                None => std::future::pending().await,
+            }
+        };
+
+        let command = tokio::select! {
+            _ = token.cancelled() => return,
+            _ = db.connected(), if !loaded => {
+                loaded = true;
+                match db.mag_calibration().await {
+                    Ok(Some(stored)) => {
+                        tracing::info!(target: "mag", "Calibration enregistrée chargée.");
+                        calibration.send_replace(stored);
+                    }
+                    Ok(None) => {}
+                    Err(e) => tracing::error!(target: "mag", "Lecture de la calibration impossible: {}", e),
+                }
+                continue;
+            }
+            request = requests.recv() => match request {
+                Some(request) => request.command,
+                None => return,
+            },
+            _ = clock.sleep(remaining), if collecting => Command::Stop,
+            record = record => {
+                match record {
+                    Ok(record::Record::Mag(data)) => samples.push(data.raw),
+                    Ok(_) | Err(tokio::sync::broadcast::error::RecvError::Lagged(_)) => {}
+                    Err(tokio::sync::broadcast::error::RecvError::Closed) => return,
+                }
+                continue;
+            }
+        };
+
+        let (severity, message) = match command {
+            Command::Start => {
+                samples.clear();
+                session = Some((clock.now(), writer.subscribe()));
+                (
+                    config::Severity::Info,
+                    "Calibration du magnétomètre démarrée: tourner le véhicule dans toutes les directions".to_string(),
+                )
+            }
+            Command::Cancel if session.take().is_some() => {
+                (config::Severity::Info, "Calibration du magnétomètre annulée".to_string())
+            }
+            Command::Stop if session.take().is_some() => {
+                match mag_calibration_fit(&samples, config.calibration_min_samples) {
+                    Ok(fit) => {
+                        calibration.send_replace(fit.calibration);
+                        let event = writer::Event::MagCalibration(fit, samples.len(), clock.stamp());
+                        let _ = writer.event(event).await;
+                        (
+                            config::Severity::Info,
+                            format!(
+                                "Calibration du magnétomètre enregistrée: {} mesures, écart {:.1} %",
+                                samples.len(),
+                                fit.residual * 100.0
+                            ),
+                        )
+                    }
+                    Err(e) => (
+                        config::Severity::Warning,
+                        format!("Calibration du magnétomètre rejetée, calibration actuelle conservée: {}", e),
+                    ),
+                }
+            }
+            Command::Stop | Command::Cancel => {
+                tracing::info!(target: "mag", "Commande {:?} ignorée: aucune calibration en cours.", command);
+                continue;
+            }
+        };
+
+        tracing::info!(target: "mag", "{}", message);
+        let event = writer::Event::Alert("mag_calibration", severity, message, clock.stamp());
+        let _ = writer.event(event).await;
+    }
+}
+
+/// Ajustement de la calibration sur les mesures d'une collecte
+fn mag_calibration_fit(
+    samples: &[(i16, i16, i16)],
+    min_samples: usize,
+) -> anyhow::Result<sensors::mag::calibration::Fit> {
+    if samples.len() < min_samples {
+        return Err(anyhow::anyhow!("{} mesure(s), au moins {} nécessaires", samples.len(), min_samples));
+    }
+    sensors::mag::calibration::fit(samples)
+}
+
+/// Limitation thermique: une limite par température (thermal.<nom>). Une température qui ne
+/// change plus depuis `stale_s` lève sa limite (échec ouvert) avec un avertissement.
+async fn thermal_guard(
+    config: config::ThermalConfig,
+    writer: writer::Writer,
+    commands: actuators::arbiter::Commands,
+    clock: clock::Clock,
+    token: CancellationToken,
+) {
+    struct Watched {
+        thermal: actuators::thermal::Thermal,
+        source: String,
+        last_stamp: Option<clock::Stamp>,
+        /// Dernière nouvelle mesure (démarrage avant la première)
+        last_change: Instant,
+        stale: bool,
+    }
+
+    let stale = Duration::from_secs_f64(config.stale_s);
+    let mut watched: Vec<Watched> = config
+        .sensors
+        .iter()
+        .map(|sensor| Watched {
+            thermal: actuators::thermal::Thermal::new(sensor.clone()),
+            source: format!("thermal.{}", sensor.name),
+            last_stamp: None,
+            last_change: clock.now(),
+            stale: false,
+        })
+        .collect();
+
+    loop {
+        tokio::select! {
+            _ = token.cancelled() => break,
+            _ = clock.sleep(THERMAL_CHECK) => {}
+        }
+
+        let can = writer.latest_can();
+        for watched in watched.iter_mut() {
+            let name = watched.thermal.sensor.name.clone();
+            let reading = watched.thermal.sensor.signal.split_once('.').and_then(|(message, signal)| {
+                let data = can.get(message)?;
+                Some((data.stamp, *data.signals.get(signal)? as f32))
+            });
+            if let Some((stamp, _)) = reading {
+                if watched.last_stamp != Some(stamp) {
+                    watched.last_stamp = Some(stamp);
+                    watched.last_change = clock.now();
+                }
+            }
+
+            let temp = match reading {
+                Some((_, temp)) if clock.since(watched.last_change) < stale => temp,
+                _ => {
+                    if !watched.stale {
+                        watched.stale = true;
+                        commands.limit(&watched.source, None);
+                        let message = format!("Température {} périmée: limite thermique levée", name);
+                        tracing::error!(target: "thermal", "{}", message);
+                        let _ = writer.event(writer::Event::Warning(message, clock.stamp())).await;
+                    }
+                    continue;
+                }
+            };
+            if std::mem::take(&mut watched.stale) {
+                tracing::info!(target: "thermal", "Température {} de nouveau reçue", name);
+            }
+
+            let (max, change) = watched.thermal.update(temp);
+            commands.limit(&watched.source, (max < 1.0).then_some(max));
+
+            let alert = match change {
+                Some(actuators::thermal::Change::Tripped) => Some((
+                    "overheat",
+                    config::Severity::Critical,
+                    format!("Température {} critique ({:.1} °C): moteur coupé", name, temp),
+                )),
+                Some(actuators::thermal::Change::Recovered) => Some((
+                    "overheat_cleared",
+                    config::Severity::Info,
+                    format!("Température {} rétablie ({:.1} °C)", name, temp),
+                )),
+                None => None,
+            };
+            if let Some((kind, severity, message)) = alert {
+                tracing::info!(target: "thermal", "{}", message);
+                let _ = writer.event(writer::Event::Alert(kind, severity, message, clock.stamp())).await;
+            }
+        }
+    }
+
+    for watched in watched.iter() {
+        commands.limit(&watched.source, None);
+    }
+}
+
+/// Zones GPS évaluées sur la dernière position reçue. Une position qui ne change plus depuis
+/// `stale_s` est traitée comme non fiable (vitesse limitée, jamais l'arrêt).
+async fn geofence_guard(
+    config: config::GeofenceConfig,
+    writer: writer::Writer,
+    commands: actuators::arbiter::Commands,
+    clock: clock::Clock,
+    token: CancellationToken,
+) {
+    let mut geofence = actuators::geofence::Geofence::new(&config);
+    let stale = Duration::from_secs_f64(config.stale_s);
+    let mut last_stamp = None;
+    let mut last_change = clock.now();
+
+    loop {
+        tokio::select! {
+            _ = token.cancelled() => break,
+            _ = clock.sleep(GEOFENCE_CHECK) => {}
+        }
+
+        let gps = writer.latest().data.gps;
+        if last_stamp != Some(gps.stamp) {
+            last_stamp = Some(gps.stamp);
+            last_change = clock.now();
+        }
+        let fresh = gps.stamp.mono_us != 0 && clock.since(last_change) < stale;
+
+        let Some(change) = geofence.update(fresh.then_some(&gps)) else {
+            continue;
+        };
+        let message = change.message();
+        tracing::info!(target: "geofence", "{}", message);
+        commands.limit("geofence", change.max);
+        let event = writer::Event::Alert("geofence", change.severity(), message, clock.stamp());
+        let _ = writer.event(event).await;
+    }
+
+    commands.limit("geofence", None);
+}
+
+/// Surveillance de la fraîcheur des capteurs sur les dernières valeurs reçues par l'écrivain:
+/// un thread de lecture bloqué ne peut pas la retarder. La perte d'un capteur critique limite
+/// la vitesse (watchdog) tant qu'il est périmé, la mesure failsafe désarme aussi le véhicule.
+/// La santé de chaque capteur et de la liaison de contrôle est écrite toutes les
+/// `health_interval_ms` millisecondes.
+async fn sensor_watchdog(
+    config: config::Config,
+    writer: writer::Writer,
+    commands: actuators::arbiter::Commands,
+    health: watch::Sender<Option<sensors::watchdog::Snapshot>>,
+    clock: clock::Clock,
+    token: CancellationToken,
+) {
+    let mut watchdog = sensors::watchdog::Watchdog::new(&config.watchdog, clock.now());
+    let health_interval = Duration::from_millis(config.watchdog.health_interval_ms);
+    let mut last_health = clock.now();
+
+    loop {
+        tokio::select! {
+            _ = token.cancelled() => break,
+            _ = clock.sleep(WATCHDOG_CHECK) => {}
+        }
+
+        let transitions = watchdog.update(&writer.latest().data, clock.now());
+
+        // Santé de chaque composant, écrite même sans transition
+        if clock.since(last_health) >= health_interval {
+            last_health = clock.now();
+            let mut components = watchdog.components(&config, &writer.status(), clock.now());
+            components.push(sensors::watchdog::control(
+                commands.last_command(),
+                Duration::from_millis(config.watchdog.control_stale_ms),
+            ));
+            // Une boucle active applique une sortie au moins à chaque délai de l'homme mort
+            components.push(sensors::watchdog::control_loop(
+                commands.last_applied(),
+                commands.closed(),
+                config.control.dead_timeout() * 2,
+            ));
+            health.send_replace(Some(sensors::watchdog::Snapshot {
+                at: Instant::now(),
+                components: components.clone(),
+            }));
+            let _ = writer.event(writer::Event::Components(components, clock.stamp())).await;
+        }
+
+        if transitions.is_empty() {
+            continue;
+        }
+
+        commands.limit("watchdog", watchdog.max_speed());
+        for transition in transitions.iter() {
+            let message = transition.message();
+            tracing::info!(target: "watchdog", "{}", message);
+            if transition.stale && transition.mitigation == config::Mitigation::Failsafe {
+                commands.arm("watchdog", false);
+            }
+            let event = writer::Event::Alert(transition.event(), transition.severity(), message, clock.stamp());
+            let _ = writer.event(event).await;
+        }
+        let event = writer::Event::Health(watchdog.health(), watchdog.stale(), clock.stamp());
+        let _ = writer.event(event).await;
+    }
+
+    commands.limit("watchdog", None);
+}
+
+/// Notifie systemd que le service est prêt et lance les pings du watchdog systemd, suspendus si
+/// la santé des composants n'est plus publiée ou si la boucle de contrôle est bloquée.
+/// None hors systemd (NOTIFY_SOCKET absent).
+#[cfg(feature = "systemd")]
+fn systemd_ready(
+    config: &config::Config,
+    health: watch::Receiver<Option<sensors::watchdog::Snapshot>>,
+    tasks: &mut tasks::Tasks,
+    token: CancellationToken,
+) -> Option<Arc<systemd::Notifier>> {
+    let notifier = match systemd::Notifier::from_env() {
+        Ok(Some(notifier)) => Arc::new(notifier),
+        Ok(None) => return None,
+        Err(e) => {
+            tracing::error!(target: "systemd", "Socket de notification inutilisable: {}", e);
+            return None;
+        }
+    };
+    if let Err(e) = notifier.notify("READY=1") {
+        tracing::error!(target: "systemd", "Notification du démarrage impossible: {}", e);
+    }
+
+    let Some(interval) = systemd::watchdog_interval_from_env() else {
+        return Some(notifier);
+    };
+    tracing::info!(target: "systemd", "Watchdog systemd: ping toutes les {:.1} s", interval.as_secs_f64());
+    if !config.watchdog.enabled {
+        tracing::warn!(target: "systemd", "Watchdog des capteurs désactivé: pings sans vérification de la santé");
+    }
+
+    // Santé publiée à chaque intervalle du watchdog des capteurs, en retard au-delà de trois
+    let max_age = Duration::from_millis(config.watchdog.health_interval_ms) * 3;
+    let enabled = config.watchdog.enabled;
+    tasks.spawn("systemd", systemd::watchdog(
+        notifier.clone(),
+        interval,
+        move || match health.borrow().as_ref() {
+            Some(snapshot) => snapshot.blocked(Instant::now(), max_age),
+            None if enabled => Some("santé pas encore publiée".to_string()),
+            None => None,
+        },
+        token,
+    ));
+    Some(notifier)
+}
+
+/// LED d'état selon la connexion à la base, le tampon local, la santé des composants publiée par
+/// le watchdog et le failsafe de la liaison de contrôle. Eteinte à l'arrêt.
+async fn status_led<O: actuators::status_led::LedOutput>(
+    output: O,
+    config: config::StatusLedConfig,
+    health: watch::Receiver<Option<sensors::watchdog::Snapshot>>,
+    writer: writer::Writer,
+    commands: actuators::arbiter::Commands,
+    token: CancellationToken,
+) {
+    use actuators::status_led::{Pattern, StatusLed};
+
+    let mut led = StatusLed::new(output, &config, Instant::now());
+    let mut tick = tokio::time::interval(STATUS_LED_TICK);
+    tick.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+
+    loop {
+        tokio::select! {
+            _ = token.cancelled() => break,
+            _ = tick.tick() => {}
+        }
+
+        let stats = writer.stats();
+        let buffering = stats.spool.is_some_and(|spool| spool.records > 0);
+        let degraded = health.borrow().as_ref().is_some_and(|snapshot| snapshot.degraded());
+        let pattern = actuators::status_led::pattern(
+            stats.connection.connected,
+            buffering,
+            degraded,
+            commands.link_lost(),
+        );
+        if led.update(pattern, Instant::now()) {
+            tracing::debug!(target: "status_led", "LED: {}", pattern.name());
+        }
+    }
+
+    led.update(Pattern::Off, Instant::now());
+}
+
+/// Evènements de la liaison de contrôle, mis en file par l'écrivain: une perte de la base de
+/// donnée est enregistrée à son retour
+async fn link_events(
+    mut link: tokio::sync::broadcast::Receiver<actuators::arbiter::LinkEvent>,
+    dead_timeout: Duration,
+    writer: writer::Writer,
+    clock: clock::Clock,
+    token: CancellationToken,
+) {
+    loop {
+        let event = tokio::select! {
+            _ = token.cancelled() => break,
+            event = link.recv() => match event {
+                Ok(event) => event,
+                Err(tokio::sync::broadcast::error::RecvError::Lagged(_)) => continue,
+                Err(_) => break,
+            },
+        };
+
+        let event = match event {
+            actuators::arbiter::LinkEvent::Lost(policy) => writer::Event::Alert(
+                "link_lost",
+                config::Severity::Critical,
+                format!("Aucune commande depuis {} ms: {}", dead_timeout.as_millis(), policy.name()),
+                clock.stamp(),
+            ),
+            actuators::arbiter::LinkEvent::Restored { outage, policy } => {
+                let message = format!(
+                    "Liaison de contrôle rétablie après {:.1} s (action: {})",
+                    outage.as_secs_f64(),
+                    policy.name()
+                );
+                tracing::info!(target: "control", "{}", message);
+                writer::Event::Alert("link_restored", config::Severity::Warning, message, clock.stamp())
+            }
+        };
+        let _ = writer.event(event).await;
+    }
+}
+
+/// Vérifications avant chaque demande d'armement: le véhicule n'est armé que si toutes les
+/// vérifications activées réussissent, sinon un évènement liste chaque échec
+async fn prearm_checks(
+    config: config::PreArmConfig,
+    writer: writer::Writer,
+    commands: actuators::arbiter::Commands,
+    selftest: selftest::SelfTest,
+    clock: clock::Clock,
+    token: CancellationToken,
+) {
+    let mut requests = commands.prearm();
+    let state = commands.state();
+
+    loop {
+        let source = tokio::select! {
+            _ = token.cancelled() => break,
+            Some(source) = requests.recv() => source,
+        };
+
+        let (health, stale) = writer.health();
+        let snapshot = actuators::prearm::Snapshot {
+            data: writer.latest().data,
+            status: writer.status(),
+            health,
+            stale,
+            cutoff: state
+                .borrow()
+                .limit
+                .as_ref()
+                .filter(|limit| limit.max == 0.0)
+                .map(|limit| limit.cause.clone()),
+            control_age: commands.last_command(),
+            actuators: actuators::prearm::ACTUATOR_CHECKS
+                .iter()
+                .map(|check| (*check, selftest.outcome(check)))
+                .collect(),
+        };
+
+        let failures = actuators::prearm::evaluate(&config, &snapshot);
+        if failures.is_empty() {
+            commands.arm(source, true);
+            continue;
+        }
+
+        let reasons: Vec<String> = failures.iter().map(|failure| failure.message(&config)).collect();
+        let message = format!("Armement refusé ({}): {}", source, reasons.join("; "));
+        tracing::info!(target: "prearm", "{}", message);
+        let event = writer::Event::Alert("arm_rejected", config::Severity::Warning, message, clock.stamp());
+        let _ = writer.event(event).await;
+    }
+}
+
+/// Désarmement automatique après la durée maximale armé ou une période d'inactivité, durées
+/// restantes publiées dans status:control. Après l'inactivité, `power_off` déclenche l'arrêt
+/// propre du programme si l'action le demande.
+async fn auto_disarm_guard(
+    config: config::AutoDisarmConfig,
+    writer: writer::Writer,
+    commands: actuators::arbiter::Commands,
+    clock: clock::Clock,
+    power_off: CancellationToken,
+    token: CancellationToken,
+) {
+    // Réception des commandes horodatée par l'horloge de l'arbitrage
+    let control_clock = commands.clock().clone();
+    let mut armed = commands.armed();
+    let state = commands.state();
+    let mut timers = actuators::auto_disarm::AutoDisarm::new(&config, *armed.borrow_and_update(), control_clock.now());
+    let mut last_received = state.borrow().received;
+    let mut last_publish: Option<Instant> = None;
+
+    loop {
+        tokio::select! {
+            _ = token.cancelled() => break,
+            Ok(()) = armed.changed() => {
+                timers.armed(*armed.borrow_and_update(), control_clock.now());
+                continue;
+            }
+            _ = control_clock.sleep(AUTO_DISARM_CHECK) => {}
+        }
+
+        let (input, received) = {
+            let state = state.borrow();
+            (state.input, state.received)
+        };
+        if let Some(at) = received.filter(|_| received != last_received) {
+            last_received = received;
+            timers.input(&input, at);
+        }
+
+        let now = control_clock.now();
+        if let Some(expiry) = timers.update(now) {
+            let message = match expiry {
+                actuators::auto_disarm::Expiry::Armed => {
+                    format!("Armé depuis {} min: véhicule désarmé", config.max_armed_min)
+                }
+                actuators::auto_disarm::Expiry::Idle => format!(
+                    "Aucune commande depuis {} min: véhicule désarmé ({})",
+                    config.idle_min,
+                    match config.idle_action {
+                        config::IdleAction::Disarm => "désarmement",
+                        config::IdleAction::Shutdown => "arrêt du programme",
+                        config::IdleAction::PowerLatch => "arrêt et coupure de l'alimentation",
+                    }
+                ),
+            };
+            tracing::info!(target: "auto_disarm", "{}", message);
+            commands.arm("auto_disarm", false);
+            let event = writer::Event::Alert(expiry.kind(), config::Severity::Warning, message, clock.stamp());
+            let _ = writer.event(event).await;
+
+            if expiry == actuators::auto_disarm::Expiry::Idle && config.idle_action != config::IdleAction::Disarm {
+                power_off.cancel();
+            }
+        }
+
+        if last_publish.is_none_or(|last| control_clock.since(last) >= COUNTDOWN_PUBLISH) {
+            last_publish = Some(now);
+            let event = writer::Event::Countdown(timers.countdown(now), clock.stamp());
+            let _ = writer.event(event).await;
+        }
+    }
+}
+
+/// Publie la limite de vitesse active à chaque changement (status:control)
+async fn limit_status(
+    mut state: watch::Receiver<actuators::arbiter::ControlState>,
+    writer: writer::Writer,
+    clock: clock::Clock,
+    token: CancellationToken,
+) {
+    let mut last = None;
+    loop {
+        tokio::select! {
+            _ = token.cancelled() => break,
+            changed = state.changed() => if changed.is_err() {
+                break;
+            },
+        }
+
+        let limit = state.borrow_and_update().limit.clone();
+        if last.as_ref() == Some(&limit) {
+            continue;
+        }
+        last = Some(limit.clone());
+        let _ = writer.event(writer::Event::Limit(limit, clock.stamp())).await;
+    }
+}
+
+/// Publie la vitesse commandée et la vitesse appliquée au moteur (rampe), pour le réglage de la
+/// rampe (status:control)
+async fn speed_status(
+    mut state: watch::Receiver<actuators::arbiter::ControlState>,
+    writer: writer::Writer,
+    clock: clock::Clock,
+    token: CancellationToken,
+) {
+    let mut last = None;
+    loop {
+        tokio::select! {
+            _ = token.cancelled() => break,
+            changed = state.changed() => if changed.is_err() {
+                break;
+            },
+        }
+
+        let speeds = {
+            let state = state.borrow_and_update();
+            (state.input.speed, state.output.speed)
+        };
+        if last == Some(speeds) {
+            continue;
+        }
+        last = Some(speeds);
+        let _ = writer.event(writer::Event::Speed(speeds.0, speeds.1, clock.stamp())).await;
+
+        // Changements pendant l'attente: seule la dernière valeur est écrite
+        tokio::select! {
+            _ = token.cancelled() => break,
+            _ = sleep(SPEED_STATUS) => {}
         }
     }
 }
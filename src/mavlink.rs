@@ -0,0 +1,524 @@
+use std::net::SocketAddr;
+use std::sync::Arc;
+use std::time::Duration;
+
+use ::mavlink::dialects::common::{
+    GpsFixType, MavAutopilot, MavBatteryFunction, MavBatteryType, MavMessage, MavModeFlag, MavState,
+    MavSysStatusSensor, MavType, ATTITUDE_DATA, BATTERY_STATUS_DATA, GLOBAL_POSITION_INT_DATA,
+    GPS_RAW_INT_DATA, HEARTBEAT_DATA, SYS_STATUS_DATA, VFR_HUD_DATA,
+};
+use ::mavlink::error::MessageReadError;
+use ::mavlink::{AsyncMavlinkReader, MAVLinkV2MessageRaw, MavHeader, MavlinkReader, Message};
+use tokio::io::AsyncWriteExt;
+use tokio::net::{TcpListener, TcpStream, UdpSocket};
+use tokio::sync::broadcast;
+use tokio::time::{interval, Instant, MissedTickBehavior};
+use tokio_util::sync::CancellationToken;
+
+use crate::clock::{Clock, Stamp};
+use crate::config::{MavlinkConfig, MavlinkTransport};
+use crate::writer::{Latest, Writer};
+
+/// Version du protocole annoncée dans HEARTBEAT
+const MAVLINK_VERSION: u8 = 3;
+
+/// Tensions par élément dans BATTERY_STATUS
+const BATTERY_CELLS: usize = 10;
+
+/// Un échantillon plus ancien est considéré comme absent
+const STALE: Duration = Duration::from_secs(2);
+
+/// Intervalle de vérification des messages à envoyer
+const TICK: Duration = Duration::from_millis(20);
+
+/// Trames en attente par client TCP (les plus anciennes sont perdues au-delà)
+const TCP_BACKLOG: usize = 64;
+
+/// Encodeur de trames MAVLink 2, numérote les trames envoyées
+pub struct Encoder {
+    system_id: u8,
+    component_id: u8,
+    sequence: u8,
+}
+
+impl Encoder {
+    pub fn new(system_id: u8, component_id: u8) -> Self {
+        Self {
+            system_id,
+            component_id,
+            sequence: 0,
+        }
+    }
+
+    /// Encode un message dans une trame complète (zéros de fin du contenu non transmis)
+    pub fn frame(&mut self, message: &MavMessage) -> Vec<u8> {
+        let header = MavHeader {
+            system_id: self.system_id,
+            component_id: self.component_id,
+            sequence: self.sequence,
+        };
+        let mut raw = MAVLinkV2MessageRaw::new();
+        raw.serialize_message(header, message);
+
+        self.sequence = self.sequence.wrapping_add(1);
+        raw.raw_bytes().to_vec()
+    }
+}
+
+/// Messages valides d'un datagramme (MAVLink 1 ou 2), les octets invalides sont ignorés
+pub fn decode(datagram: &[u8]) -> Vec<(MavHeader, MavMessage)> {
+    let mut reader = MavlinkReader::new(datagram);
+    let mut messages = Vec::new();
+    loop {
+        match reader.read_any_message::<MavMessage>() {
+            Ok(message) => messages.push(message),
+            // Trame non décodable (contenu invalide), la lecture reprend à la suivante
+            Err(MessageReadError::Parse(_)) => continue,
+            Err(MessageReadError::Io(_)) => return messages,
+        }
+    }
+}
+
+/// Echantillon récent (moins de `STALE`)
+fn fresh(stamp: &Stamp, now: Duration) -> bool {
+    stamp.mono_us != 0 && now.saturating_sub(Duration::from_micros(stamp.mono_us)) < STALE
+}
+
+/// Millisecondes depuis le démarrage
+fn boot_ms(now: Duration) -> u32 {
+    now.as_millis() as u32
+}
+
+/// Degrés vers 1E7 degrés
+fn deg_e7(degrees: f64) -> i32 {
+    (degrees * 1e7).round() as i32
+}
+
+/// Degrés vers centi-degrés [0, 36000[
+fn cdeg(degrees: f64) -> u16 {
+    (degrees.rem_euclid(360.0) * 100.0).round() as u16 % 36000
+}
+
+/// Radians dans [-pi, pi]
+fn radians(degrees: f32) -> f32 {
+    let r = degrees.to_radians();
+    (r + std::f32::consts::PI).rem_euclid(2.0 * std::f32::consts::PI) - std::f32::consts::PI
+}
+
+/// HEARTBEAT (#0): véhicule terrestre piloté manuellement
+pub fn heartbeat() -> MavMessage {
+    MavMessage::HEARTBEAT(HEARTBEAT_DATA {
+        custom_mode: 0,
+        mavtype: MavType::MAV_TYPE_GROUND_ROVER,
+        autopilot: MavAutopilot::MAV_AUTOPILOT_GENERIC,
+        base_mode: MavModeFlag::MAV_MODE_FLAG_MANUAL_INPUT_ENABLED,
+        system_status: MavState::MAV_STATE_ACTIVE,
+        mavlink_version: MAVLINK_VERSION,
+    })
+}
+
+/// SYS_STATUS (#1): capteurs présents, tension, courant et capacité restante de la batterie
+pub fn sys_status(latest: &Latest, now: Duration) -> MavMessage {
+    let data = &latest.data;
+    type Sensor = MavSysStatusSensor;
+    let mut present = Sensor::empty();
+    let mut healthy = Sensor::empty();
+    for (sensors, stamp) in [
+        (Sensor::MAV_SYS_STATUS_SENSOR_3D_GYRO | Sensor::MAV_SYS_STATUS_SENSOR_3D_ACCEL, &data.imu.stamp),
+        (Sensor::MAV_SYS_STATUS_SENSOR_3D_MAG, &data.mag.stamp),
+        (Sensor::MAV_SYS_STATUS_SENSOR_GPS, &data.gps.stamp),
+        (Sensor::MAV_SYS_STATUS_SENSOR_BATTERY, &data.analog.stamp),
+    ] {
+        if stamp.mono_us != 0 {
+            present |= sensors;
+        }
+        if fresh(stamp, now) {
+            healthy |= sensors;
+        }
+    }
+
+    let voltage = if data.analog.stamp.mono_us != 0 {
+        millivolts(data.analog.battery)
+    } else {
+        u16::MAX
+    };
+
+    MavMessage::SYS_STATUS(SYS_STATUS_DATA {
+        onboard_control_sensors_present: present,
+        onboard_control_sensors_enabled: present,
+        onboard_control_sensors_health: healthy,
+        load: 0,
+        voltage_battery: voltage,
+        current_battery: centiamps(latest),
+        drop_rate_comm: 0,
+        errors_comm: 0,
+        errors_count1: 0,
+        errors_count2: 0,
+        errors_count3: 0,
+        errors_count4: 0,
+        battery_remaining: remaining(latest),
+    })
+}
+
+/// Volts vers millivolts, u16::MAX réservé à "inconnu"
+fn millivolts(volts: f32) -> u16 {
+    (volts * 1000.0).round().clamp(0.0, u16::MAX as f32 - 1.0) as u16
+}
+
+/// Dernier courant mesuré en cA, -1: inconnu
+fn centiamps(latest: &Latest) -> i16 {
+    latest
+        .data
+        .analog
+        .current
+        .map_or(-1, |amps| (amps * 100.0).round().clamp(i16::MIN as f32, i16::MAX as f32) as i16)
+}
+
+/// Capacité restante (%), -1: inconnue
+fn remaining(latest: &Latest) -> i8 {
+    latest.capacity.map_or(-1, |capacity| capacity.remaining_pct.round() as i8)
+}
+
+/// BATTERY_STATUS (#147): tension de chaque élément (sinon de la batterie dans le premier),
+/// courant et capacité consommée
+pub fn battery_status(latest: &Latest) -> MavMessage {
+    let analog = &latest.data.analog;
+    let mut voltages = [u16::MAX; BATTERY_CELLS];
+    if analog.stamp.mono_us != 0 {
+        if analog.cells.iter().any(Option::is_some) {
+            for (voltage, cell) in voltages.iter_mut().zip(analog.cells.iter()) {
+                *voltage = cell.map_or(u16::MAX, millivolts);
+            }
+        } else {
+            voltages[0] = millivolts(analog.battery);
+        }
+    }
+    let consumed = latest
+        .capacity
+        .map_or(-1, |capacity| capacity.consumed_mah.round().clamp(0.0, i32::MAX as f64) as i32);
+
+    MavMessage::BATTERY_STATUS(BATTERY_STATUS_DATA {
+        current_consumed: consumed, // mAh
+        energy_consumed: -1, // inconnu
+        temperature: i16::MAX, // inconnue
+        voltages,
+        current_battery: centiamps(latest),
+        id: 0,
+        battery_function: MavBatteryFunction::MAV_BATTERY_FUNCTION_ALL,
+        mavtype: MavBatteryType::MAV_BATTERY_TYPE_LIPO,
+        battery_remaining: remaining(latest),
+    })
+}
+
+/// Type de fix GPS
+fn fix_type(latest: &Latest) -> GpsFixType {
+    let gps = &latest.data.gps;
+    if gps.stamp.mono_us == 0 {
+        GpsFixType::GPS_FIX_TYPE_NO_GPS
+    } else if gps.fix {
+        GpsFixType::GPS_FIX_TYPE_3D_FIX
+    } else {
+        GpsFixType::GPS_FIX_TYPE_NO_FIX
+    }
+}
+
+/// GPS_RAW_INT (#24): position brute du GPS
+pub fn gps_raw_int(latest: &Latest) -> MavMessage {
+    let gps = &latest.data.gps;
+    let speed_cms = (gps.speed_kmh / 3.6 * 100.0).round().clamp(0.0, u16::MAX as f64 - 1.0) as u16;
+
+    MavMessage::GPS_RAW_INT(GPS_RAW_INT_DATA {
+        time_usec: gps.stamp.utc.timestamp_micros().max(0) as u64,
+        lat: deg_e7(gps.latitude),
+        lon: deg_e7(gps.longitude),
+        alt: 0, // non mesurée
+        eph: u16::MAX, // inconnu
+        epv: u16::MAX, // inconnu
+        vel: speed_cms,
+        cog: cdeg(gps.heading),
+        fix_type: fix_type(latest),
+        satellites_visible: gps.satellites,
+    })
+}
+
+/// GLOBAL_POSITION_INT (#33): position et vitesse au sol
+pub fn global_position_int(latest: &Latest, now: Duration) -> MavMessage {
+    let gps = &latest.data.gps;
+    let speed = gps.speed_kmh / 3.6 * 100.0;
+    let heading = gps.heading.to_radians();
+
+    MavMessage::GLOBAL_POSITION_INT(GLOBAL_POSITION_INT_DATA {
+        time_boot_ms: boot_ms(now),
+        lat: deg_e7(gps.latitude),
+        lon: deg_e7(gps.longitude),
+        alt: 0,
+        relative_alt: 0,
+        vx: (speed * heading.cos()).round() as i16, // nord (cm/s)
+        vy: (speed * heading.sin()).round() as i16, // est (cm/s)
+        vz: 0,
+        hdg: cdeg(gps.heading),
+    })
+}
+
+/// ATTITUDE (#30): angles de l'IMU
+pub fn attitude(latest: &Latest, now: Duration) -> MavMessage {
+    let (pitch, roll, yaw) = latest.data.imu.angles;
+
+    MavMessage::ATTITUDE(ATTITUDE_DATA {
+        time_boot_ms: boot_ms(now),
+        roll: radians(roll),
+        pitch: radians(pitch),
+        yaw: radians(yaw),
+        rollspeed: 0.0,
+        pitchspeed: 0.0,
+        yawspeed: 0.0,
+    })
+}
+
+/// VFR_HUD (#74): vitesse et cap pour l'affichage tête haute
+pub fn vfr_hud(latest: &Latest) -> MavMessage {
+    let data = &latest.data;
+    let speed = (data.gps.speed_kmh / 3.6) as f32;
+
+    // Cap GPS avec un fix, sinon celui du magnétomètre
+    let heading = if data.gps.fix {
+        data.gps.heading
+    } else {
+        data.mag.true_heading as f64
+    };
+
+    MavMessage::VFR_HUD(VFR_HUD_DATA {
+        airspeed: speed,
+        groundspeed: speed,
+        alt: 0.0,
+        climb: 0.0,
+        heading: heading.rem_euclid(360.0).round() as i16 % 360,
+        throttle: 0, // inconnu
+    })
+}
+
+/// Planification d'un message à sa fréquence
+struct Schedule {
+    period: Option<Duration>,
+    next: Instant,
+}
+
+impl Schedule {
+    fn new(rate: f64) -> Self {
+        Self {
+            period: (rate > 0.0).then(|| Duration::from_secs_f64(1.0 / rate)),
+            next: Instant::now(),
+        }
+    }
+
+    /// Vrai si le message doit être envoyé maintenant
+    fn due(&mut self, now: Instant) -> bool {
+        let Some(period) = self.period else {
+            return false;
+        };
+
+        if now < self.next {
+            return false;
+        }
+
+        self.next += period;
+        if self.next < now {
+            self.next = now + period;
+        }
+        true
+    }
+}
+
+/// Messages dus, encodés
+struct Emitter {
+    encoder: Encoder,
+    heartbeat: Schedule,
+    sys_status: Schedule,
+    gps_raw_int: Schedule,
+    global_position_int: Schedule,
+    attitude: Schedule,
+    vfr_hud: Schedule,
+    battery_status: Schedule,
+}
+
+impl Emitter {
+    fn new(config: &MavlinkConfig) -> Self {
+        let rates = &config.rates;
+        Self {
+            encoder: Encoder::new(config.system_id, config.component_id),
+            heartbeat: Schedule::new(rates.heartbeat),
+            sys_status: Schedule::new(rates.sys_status),
+            gps_raw_int: Schedule::new(rates.gps_raw_int),
+            global_position_int: Schedule::new(rates.global_position_int),
+            attitude: Schedule::new(rates.attitude),
+            vfr_hud: Schedule::new(rates.vfr_hud),
+            battery_status: Schedule::new(rates.battery_status),
+        }
+    }
+
+    fn due(&mut self, latest: &Latest, elapsed: Duration) -> Vec<Vec<u8>> {
+        let now = Instant::now();
+        let mut messages = Vec::new();
+
+        if self.heartbeat.due(now) {
+            messages.push(heartbeat());
+        }
+        if self.sys_status.due(now) {
+            messages.push(sys_status(latest, elapsed));
+        }
+        if self.gps_raw_int.due(now) {
+            messages.push(gps_raw_int(latest));
+        }
+        if self.global_position_int.due(now) {
+            messages.push(global_position_int(latest, elapsed));
+        }
+        if self.attitude.due(now) {
+            messages.push(attitude(latest, elapsed));
+        }
+        if self.vfr_hud.due(now) {
+            messages.push(vfr_hud(latest));
+        }
+        if self.battery_status.due(now) {
+            messages.push(battery_status(latest));
+        }
+
+        messages.iter().map(|m| self.encoder.frame(m)).collect()
+    }
+}
+
+/// Sortie MAVLink: les messages sont construits depuis les dernières valeurs de l'écrivain.
+/// Les messages reçus (HEARTBEAT de la station sol, COMMAND_LONG, ...) sont décodés et
+/// journalisés, sans être exécutés.
+pub async fn run(config: MavlinkConfig, writer: Writer, clock: Clock, token: CancellationToken) {
+    let result = match config.transport {
+        MavlinkTransport::Udp => run_udp(&config, &writer, &clock, &token).await,
+        MavlinkTransport::Tcp => run_tcp(&config, &writer, &clock, &token).await,
+    };
+
+    if let Err(e) = result {
+        tracing::error!(target: "mavlink", "Arrêt de la sortie: {}", e);
+    }
+}
+
+async fn run_udp(config: &MavlinkConfig, writer: &Writer, clock: &Clock, token: &CancellationToken) -> anyhow::Result<()> {
+    let socket = UdpSocket::bind(&config.listen).await?;
+    let mut target = config.target.as_ref().map(|t| t.parse()).transpose()?;
+    tracing::info!(target: "mavlink", "UDP sur {}, destinataire: {:?}", config.listen, target);
+
+    let mut emitter = Emitter::new(config);
+    let mut tick = interval(TICK);
+    tick.set_missed_tick_behavior(MissedTickBehavior::Skip);
+    let mut buffer = [0u8; 512];
+
+    loop {
+        tokio::select! {
+            _ = token.cancelled() => return Ok(()),
+            received = socket.recv_from(&mut buffer) => {
+                let Ok((len, from)) = received else {
+                    continue;
+                };
+                let messages = decode(&buffer[..len]);
+                for (header, message) in &messages {
+                    received_message(header, message, from);
+                }
+
+                // Sans destinataire configuré, répond à la dernière station sol connue
+                if config.target.is_none() && !messages.is_empty() && target != Some(from) {
+                    tracing::info!(target: "mavlink", "Station sol: {} (système {})", from, messages[0].0.system_id);
+                    target = Some(from);
+                }
+            }
+            _ = tick.tick() => {
+                let Some(target) = target else {
+                    continue;
+                };
+
+                for frame in emitter.due(&writer.latest(), clock.elapsed()) {
+                    if let Err(e) = socket.send_to(&frame, target).await {
+                        tracing::error!(target: "mavlink", "Erreur d'envoi vers {}: {}", target, e);
+                        break;
+                    }
+                }
+            }
+        }
+    }
+}
+
+async fn run_tcp(config: &MavlinkConfig, writer: &Writer, clock: &Clock, token: &CancellationToken) -> anyhow::Result<()> {
+    let listener = TcpListener::bind(&config.listen).await?;
+    tracing::info!(target: "mavlink", "Serveur TCP sur {}", config.listen);
+
+    let (frames, _) = broadcast::channel::<Arc<Vec<u8>>>(TCP_BACKLOG);
+    let mut emitter = Emitter::new(config);
+    let mut tick = interval(TICK);
+    tick.set_missed_tick_behavior(MissedTickBehavior::Skip);
+
+    loop {
+        tokio::select! {
+            _ = token.cancelled() => return Ok(()),
+            accepted = listener.accept() => match accepted {
+                Ok((stream, from)) => {
+                    tracing::info!(target: "mavlink", "Client connecté: {}", from);
+                    tokio::spawn(serve_client(stream, from, frames.subscribe(), token.clone()));
+                }
+                Err(e) => tracing::error!(target: "mavlink", "Erreur de connexion: {}", e),
+            },
+            _ = tick.tick() => {
+                if frames.receiver_count() == 0 {
+                    continue;
+                }
+
+                for frame in emitter.due(&writer.latest(), clock.elapsed()) {
+                    let _ = frames.send(Arc::new(frame));
+                }
+            }
+        }
+    }
+}
+
+/// Message reçu d'une station sol, journalisé uniquement
+fn received_message(header: &MavHeader, message: &MavMessage, from: SocketAddr) {
+    tracing::debug!(
+        target: "mavlink",
+        "{} reçu de {} (système {}, composant {})",
+        message.message_name(),
+        from,
+        header.system_id,
+        header.component_id
+    );
+}
+
+/// Client TCP: un client lent perd des trames, il ne bloque jamais les autres
+async fn serve_client(
+    stream: TcpStream,
+    from: SocketAddr,
+    mut frames: broadcast::Receiver<Arc<Vec<u8>>>,
+    token: CancellationToken,
+) {
+    let (read, mut write) = stream.into_split();
+    // Trame partielle conservée si la lecture est interrompue (select)
+    let mut reader = AsyncMavlinkReader::new(read);
+
+    loop {
+        tokio::select! {
+            _ = token.cancelled() => return,
+            frame = frames.recv() => match frame {
+                Ok(frame) => {
+                    if write.write_all(&frame).await.is_err() {
+                        break;
+                    }
+                }
+                Err(broadcast::error::RecvError::Lagged(_)) => continue,
+                Err(broadcast::error::RecvError::Closed) => return,
+            },
+            read = reader.read_any_message::<MavMessage>() => match read {
+                Ok((header, message)) => received_message(&header, &message, from),
+                // Trame invalide ignorée, la lecture reprend à la suivante
+                Err(MessageReadError::Parse(_)) => {}
+                Err(MessageReadError::Io(_)) => break,
+            },
+        }
+    }
+
+    tracing::info!(target: "mavlink", "Client déconnecté: {}", from);
+}
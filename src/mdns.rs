@@ -0,0 +1,128 @@
+use std::net::SocketAddr;
+
+use mdns_sd::{DaemonEvent, ServiceDaemon, ServiceInfo};
+use tokio_util::sync::CancellationToken;
+
+use crate::config::Config;
+
+/// Type du service HTTP/WebSocket annoncé
+const SERVICE_TCP: &str = "_rc-telemetrie._tcp.local.";
+/// Type du service de télémétrie UDP annoncé
+const SERVICE_UDP: &str = "_rc-telemetrie._udp.local.";
+
+/// Nom d'hôte DNS dérivé du nom du véhicule (minuscules, chiffres et tirets)
+fn hostname(vehicle: &str) -> String {
+    let label: String = vehicle
+        .to_lowercase()
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c } else { '-' })
+        .collect();
+    let label = label.trim_matches('-');
+
+    if label.is_empty() {
+        "rc-telemetrie.local.".to_string()
+    } else {
+        format!("{}.local.", label)
+    }
+}
+
+/// Port d'une adresse de la configuration
+fn port(address: &str) -> Option<u16> {
+    address.parse::<SocketAddr>().ok().map(|address| address.port())
+}
+
+/// Services à annoncer: serveur HTTP et envoi UDP s'ils sont activés
+fn services(config: &Config, run: &str) -> anyhow::Result<Vec<ServiceInfo>> {
+    let host = hostname(&config.vehicle);
+    let properties = [
+        ("vehicle", config.vehicle.as_str()),
+        ("run", run),
+        ("version", env!("CARGO_PKG_VERSION")),
+    ];
+
+    let mut services = Vec::new();
+    let mut announce = |service: &str, port: u16| -> anyhow::Result<()> {
+        let info = ServiceInfo::new(service, &config.vehicle, &host, "", port, &properties[..])?;
+        services.push(info.enable_addr_auto());
+        Ok(())
+    };
+
+    if let Some(port) = config.http.enabled.then(|| port(&config.http.listen)).flatten() {
+        announce(SERVICE_TCP, port)?;
+    }
+
+    if let Some(port) = config.udp.enabled.then(|| port(&config.udp.target)).flatten() {
+        announce(SERVICE_UDP, port)?;
+    }
+
+    Ok(services)
+}
+
+/// Annonce des services locaux en mDNS jusqu'à l'arrêt du programme.
+/// En cas de conflit, le nom d'instance reçoit un suffixe (ex: "voiture (2)").
+pub async fn run(config: Config, run: String, token: CancellationToken) {
+    let services = match services(&config, &run) {
+        Ok(services) if services.is_empty() => {
+            tracing::info!(target: "mdns", "Aucun service à annoncer (http et udp désactivés).");
+            return;
+        }
+        Ok(services) => services,
+        Err(e) => {
+            tracing::error!(target: "mdns", "Service invalide: {}", e);
+            return;
+        }
+    };
+
+    let daemon = match ServiceDaemon::new() {
+        Ok(daemon) => daemon,
+        Err(e) => {
+            tracing::error!(target: "mdns", "Impossible de démarrer le répondeur: {}", e);
+            return;
+        }
+    };
+
+    let events = daemon.monitor().ok();
+    let mut names = Vec::new();
+    for service in services {
+        let name = service.get_fullname().to_string();
+        match daemon.register(service) {
+            Ok(_) => {
+                tracing::info!(target: "mdns", "Annonce de {}", name);
+                names.push(name);
+            }
+            Err(e) => tracing::error!(target: "mdns", "Impossible d'annoncer {}: {}", name, e),
+        }
+    }
+
+    loop {
+        let event = tokio::select! {
+            _ = token.cancelled() => break,
+            event = async { events.as_ref()?.recv_async().await.ok() } => event,
+        };
+
+        match event {
+            Some(DaemonEvent::NameChange(change)) => {
+                tracing::info!(target: "mdns", "Nom déjà utilisé, {} devient {}", change.original, change.new_name);
+            }
+            Some(DaemonEvent::Error(e)) => tracing::error!(target: "mdns", "Erreur: {}", e),
+            Some(_) => {}
+            // Plus d'évènements: attend l'arrêt
+            None => {
+                token.cancelled().await;
+                break;
+            }
+        }
+    }
+
+    // Retrait des annonces (paquets "goodbye") avant l'arrêt du répondeur
+    for name in names {
+        if let Ok(status) = daemon.unregister(&name) {
+            let _ = status.recv_async().await;
+        }
+    }
+    if let Ok(status) = daemon.shutdown() {
+        let _ = status.recv_async().await;
+    }
+
+    tracing::info!(target: "mdns", "Arrêt.");
+}
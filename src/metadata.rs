@@ -0,0 +1,68 @@
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+
+use crate::config::Config;
+
+/// Version du programme et configuration utilisée, pour retrouver la version ayant produit une exécution
+#[derive(Clone, Debug, Serialize)]
+pub struct Metadata {
+    pub version: String,
+    /// Commit git de la compilation ("unknown" hors d'un dépôt git)
+    pub commit: String,
+    /// Modifications non commitées lors de la compilation
+    pub dirty: bool,
+    pub built: DateTime<Utc>,
+    /// Empreinte de la configuration chargée au démarrage
+    pub config_hash: String,
+    /// Nom de la machine ("unknown" s'il n'est pas lisible)
+    pub hostname: String,
+}
+
+impl Metadata {
+    /// Informations de compilation (build.rs) et empreinte de la configuration
+    pub fn new(config: &Config) -> Self {
+        let built = env!("BUILD_TIMESTAMP").parse::<i64>().unwrap_or(0);
+
+        Self {
+            version: env!("CARGO_PKG_VERSION").to_string(),
+            commit: env!("BUILD_GIT_COMMIT").to_string(),
+            dirty: env!("BUILD_GIT_DIRTY") == "true",
+            built: DateTime::from_timestamp(built, 0).unwrap_or_default(),
+            config_hash: config_hash(config),
+            hostname: hostname(),
+        }
+    }
+
+    /// Résumé sur une ligne, utilisé dans les logs et les en-têtes d'export
+    pub fn summary(&self) -> String {
+        let commit = self.commit.get(..12).unwrap_or(&self.commit);
+        format!(
+            "v{} ({}{}, compilé le {}), configuration {}, machine {}",
+            self.version,
+            commit,
+            if self.dirty { "-dirty" } else { "" },
+            self.built.format("%Y-%m-%d %H:%M:%S UTC"),
+            self.config_hash,
+            self.hostname
+        )
+    }
+}
+
+/// Empreinte FNV-1a de la configuration sérialisée (stable entre les compilations)
+fn config_hash(config: &Config) -> String {
+    let content = toml::to_string(config).unwrap_or_default();
+    let hash = content.bytes().fold(0xcbf29ce484222325u64, |hash, byte| {
+        (hash ^ byte as u64).wrapping_mul(0x100000001b3)
+    });
+
+    format!("{:016x}", hash)
+}
+
+/// Nom de la machine (noyau Linux), "unknown" ailleurs ou s'il n'est pas lisible
+fn hostname() -> String {
+    std::fs::read_to_string("/proc/sys/kernel/hostname")
+        .map(|name| name.trim().to_string())
+        .ok()
+        .filter(|name| !name.is_empty())
+        .unwrap_or_else(|| "unknown".to_string())
+}
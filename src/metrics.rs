@@ -0,0 +1,105 @@
+use std::collections::BTreeMap;
+use std::fmt::Write;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::Duration;
+
+use crate::record::Record;
+
+/// Préfixe des métriques Prometheus
+pub const PREFIX: &str = "rc_telemetrie_";
+
+/// Bornes des histogrammes de latence des écritures (s)
+const LATENCY_BUCKETS: [f64; 10] = [0.001, 0.0025, 0.005, 0.01, 0.025, 0.05, 0.1, 0.25, 0.5, 1.0];
+
+/// Histogramme de durées au format Prometheus (compteurs cumulés par borne)
+#[derive(Default)]
+pub struct Histogram {
+    buckets: [AtomicU64; LATENCY_BUCKETS.len()],
+    count: AtomicU64,
+    sum_us: AtomicU64,
+}
+
+impl Histogram {
+    pub fn observe(&self, duration: Duration) {
+        let seconds = duration.as_secs_f64();
+        for (bucket, bound) in self.buckets.iter().zip(LATENCY_BUCKETS) {
+            if seconds <= bound {
+                bucket.fetch_add(1, Ordering::Relaxed);
+            }
+        }
+        self.count.fetch_add(1, Ordering::Relaxed);
+        self.sum_us.fetch_add(duration.as_micros() as u64, Ordering::Relaxed);
+    }
+
+    /// Lignes de l'histogramme `name` pour les étiquettes données (ex: `table="imu"`)
+    fn write(&self, text: &mut String, name: &str, labels: &str) {
+        for (bucket, bound) in self.buckets.iter().zip(LATENCY_BUCKETS) {
+            let _ = writeln!(
+                text,
+                "{}{}_bucket{{{},le=\"{}\"}} {}",
+                PREFIX,
+                name,
+                labels,
+                bound,
+                bucket.load(Ordering::Relaxed)
+            );
+        }
+        let count = self.count.load(Ordering::Relaxed);
+        let _ = writeln!(text, "{}{}_bucket{{{},le=\"+Inf\"}} {}", PREFIX, name, labels, count);
+        let sum = self.sum_us.load(Ordering::Relaxed) as f64 / 1e6;
+        let _ = writeln!(text, "{}{}_sum{{{}}} {}", PREFIX, name, labels, sum);
+        let _ = writeln!(text, "{}{}_count{{{}}} {}", PREFIX, name, labels, count);
+    }
+}
+
+/// Ecritures d'une table
+#[derive(Default)]
+struct TableMetrics {
+    ok: AtomicU64,
+    failed: AtomicU64,
+    latency: Histogram,
+}
+
+/// Ecritures des échantillons dans la base, par table
+pub struct DbMetrics {
+    tables: BTreeMap<&'static str, TableMetrics>,
+}
+
+impl Default for DbMetrics {
+    fn default() -> Self {
+        Self {
+            tables: Record::KINDS
+                .iter()
+                .map(|table| (*table, TableMetrics::default()))
+                .collect(),
+        }
+    }
+}
+
+impl DbMetrics {
+    /// Ecriture terminée: réussie ou en échec, et sa durée
+    pub fn record(&self, table: &str, ok: bool, elapsed: Duration) {
+        let Some(metrics) = self.tables.get(table) else {
+            return;
+        };
+        let counter = if ok { &metrics.ok } else { &metrics.failed };
+        counter.fetch_add(1, Ordering::Relaxed);
+        metrics.latency.observe(elapsed);
+    }
+
+    /// Compteurs et histogrammes au format texte Prometheus
+    pub fn write(&self, text: &mut String) {
+        for (name, ok) in [("db_inserts_total", true), ("db_insert_failures_total", false)] {
+            let _ = writeln!(text, "# TYPE {}{} counter", PREFIX, name);
+            for (table, metrics) in &self.tables {
+                let counter = if ok { &metrics.ok } else { &metrics.failed };
+                let _ = writeln!(text, "{}{}{{table=\"{}\"}} {}", PREFIX, name, table, counter.load(Ordering::Relaxed));
+            }
+        }
+
+        let _ = writeln!(text, "# TYPE {}db_insert_seconds histogram", PREFIX);
+        for (table, metrics) in &self.tables {
+            metrics.latency.write(text, "db_insert_seconds", &format!("table=\"{}\"", table));
+        }
+    }
+}
@@ -0,0 +1,181 @@
+use std::collections::HashMap;
+
+use zbus::fdo::{ManagedObjects, ObjectManagerProxy, PropertiesProxy};
+use zbus::names::InterfaceName;
+use zbus::zvariant::{Optional, OwnedObjectPath, OwnedValue};
+use zbus::{Connection, Proxy};
+
+use crate::config::ModemConfig;
+use crate::record::{AccessTechnology, ModemStatus, Operator, Registration};
+
+const SERVICE: &str = "org.freedesktop.ModemManager1";
+/// Objet de ModemManager sous lequel les modems sont exposés (ObjectManager)
+const MANAGER: &str = "/org/freedesktop/ModemManager1";
+pub const MODEM: &str = "org.freedesktop.ModemManager1.Modem";
+const MODEM_3GPP: &str = "org.freedesktop.ModemManager1.Modem.Modem3gpp";
+const SIGNAL: &str = "org.freedesktop.ModemManager1.Modem.Signal";
+
+/// Période de rafraîchissement des mesures étendues par le modem (secondes)
+const SIGNAL_RATE_S: u32 = 1;
+
+/// Bits de MMModemAccessTechnology, de la technologie la plus récente à la plus ancienne
+const TECHNOLOGIES: [(u32, AccessTechnology); 8] = [
+    (1 << 15, AccessTechnology::Nr),
+    (1 << 14 | 1 << 16 | 1 << 17, AccessTechnology::Lte),
+    (1 << 10 | 1 << 11 | 1 << 12 | 1 << 13, AccessTechnology::Cdma),
+    (1 << 6 | 1 << 7 | 1 << 8 | 1 << 9, AccessTechnology::Hspa),
+    (1 << 5, AccessTechnology::Umts),
+    (1 << 4, AccessTechnology::Edge),
+    (1 << 3, AccessTechnology::Gprs),
+    (1 << 1 | 1 << 2, AccessTechnology::Gsm),
+];
+
+/// Technologie la plus récente de la propriété AccessTechnologies, None: inconnue
+pub fn technology(mask: u32) -> Option<AccessTechnology> {
+    TECHNOLOGIES
+        .into_iter()
+        .find(|(bits, _)| mask & bits != 0)
+        .map(|(_, technology)| technology)
+}
+
+/// Etat de la propriété RegistrationState (MMModem3gppRegistrationState), None: inconnu
+pub fn registration(state: u32) -> Option<Registration> {
+    match state {
+        0 => Some(Registration::Idle),
+        1 | 6 | 9 => Some(Registration::Home),
+        2 => Some(Registration::Searching),
+        3 => Some(Registration::Denied),
+        5 | 7 | 10 => Some(Registration::Roaming),
+        8 | 11 => Some(Registration::Emergency),
+        _ => None,
+    }
+}
+
+/// Mesures étendues d'une propriété de l'interface Signal (Lte, Nr5g), absentes si non fournies
+pub fn apply_signal(status: &mut ModemStatus, values: &HashMap<String, OwnedValue>) {
+    let metric = |key: &str| {
+        values
+            .get(key)
+            .and_then(|value| f64::try_from(value).ok())
+            .filter(|value| value.is_finite())
+    };
+
+    status.rsrp = metric("rsrp");
+    status.rsrq = metric("rsrq");
+    status.sinr = metric("snr");
+}
+
+/// Propriété texte d'une interface
+fn text<'a>(properties: &'a HashMap<String, OwnedValue>, name: &str) -> Option<&'a str> {
+    properties.get(name).and_then(|value| <&str>::try_from(value).ok())
+}
+
+/// Le modem correspond à l'IMEI et au port configurés (port principal ou l'un des ports)
+fn matches(properties: &HashMap<String, OwnedValue>, config: &ModemConfig) -> bool {
+    let imei = config
+        .imei
+        .as_deref()
+        .is_none_or(|imei| text(properties, "EquipmentIdentifier") == Some(imei));
+    let port = config.port.as_deref().is_none_or(|port| {
+        let ports = properties
+            .get("Ports")
+            .and_then(|value| value.try_clone().ok())
+            .and_then(|value| <Vec<(String, u32)>>::try_from(value).ok())
+            .unwrap_or_default();
+        text(properties, "PrimaryPort") == Some(port) || ports.iter().any(|(name, _)| name == port)
+    });
+
+    imei && port
+}
+
+/// Modem à utiliser parmi les objets de ModemManager: celui de plus petit numéro correspondant à
+/// la configuration (modem.imei, modem.port)
+pub fn select(objects: &ManagedObjects, config: &ModemConfig) -> Option<OwnedObjectPath> {
+    objects
+        .iter()
+        .filter(|(_, interfaces)| {
+            interfaces
+                .iter()
+                .any(|(name, properties)| name.as_str() == MODEM && matches(properties, config))
+        })
+        .map(|(path, _)| path)
+        .min_by_key(|path| (path.as_str().len(), path.as_str()))
+        .cloned()
+}
+
+/// Liste des modems de ModemManager et signaux d'ajout et de retrait
+pub async fn manager(connection: &Connection) -> zbus::Result<ObjectManagerProxy<'static>> {
+    ObjectManagerProxy::builder(connection)
+        .destination(SERVICE)?
+        .path(MANAGER)?
+        .build()
+        .await
+}
+
+fn interface(name: &'static str) -> InterfaceName<'static> {
+    InterfaceName::from_static_str_unchecked(name)
+}
+
+/// Modem exposé par ModemManager
+pub struct Modem {
+    properties: PropertiesProxy<'static>,
+    signal: Proxy<'static>,
+}
+
+impl Modem {
+    pub async fn new(connection: &Connection, path: &str) -> zbus::Result<Self> {
+        let properties = PropertiesProxy::builder(connection)
+            .destination(SERVICE)?
+            .path(path.to_string())?
+            .build()
+            .await?;
+        let signal = Proxy::new(connection, SERVICE, path.to_string(), SIGNAL).await?;
+
+        Ok(Self { properties, signal })
+    }
+
+    /// Active les mesures étendues (RSRP, RSRQ, SINR) de l'interface Signal
+    pub async fn setup_signal(&self) -> zbus::Result<()> {
+        self.signal.call("Setup", &(SIGNAL_RATE_S,)).await
+    }
+
+    /// Etat du modem, seule l'interface Modem est indispensable
+    pub async fn status(&self) -> zbus::Result<ModemStatus> {
+        let modem = self.properties.get_all(Optional::from(Some(interface(MODEM)))).await?;
+
+        let mut status = ModemStatus {
+            quality: modem
+                .get("SignalQuality")
+                .and_then(|value| value.try_clone().ok())
+                .and_then(|value| <(u32, bool)>::try_from(value).ok())
+                .map_or(0, |(quality, _)| quality),
+            technology: modem
+                .get("AccessTechnologies")
+                .and_then(|value| u32::try_from(value).ok())
+                .and_then(technology),
+            ..ModemStatus::default()
+        };
+
+        // Pas d'interface Modem3gpp pour un modem CDMA
+        if let Ok(gpp) = self.properties.get_all(Optional::from(Some(interface(MODEM_3GPP)))).await {
+            status.registration = gpp
+                .get("RegistrationState")
+                .and_then(|value| u32::try_from(value).ok())
+                .and_then(registration);
+            status.operator = text(&gpp, "OperatorName").and_then(Operator::new);
+        }
+
+        // Mesures vides tant que Signal.Setup n'a pas été appelé
+        let key = match status.technology {
+            Some(AccessTechnology::Nr) => "Nr5g",
+            _ => "Lte",
+        };
+        if let Ok(values) = self.properties.get(interface(SIGNAL), key).await {
+            if let Ok(values) = HashMap::<String, OwnedValue>::try_from(values) {
+                apply_signal(&mut status, &values);
+            }
+        }
+
+        Ok(status)
+    }
+}
@@ -0,0 +1,54 @@
+use std::time::{Duration, Instant};
+
+use serde::Deserialize;
+
+use crate::actuators::Control;
+
+/// Commande du pilote reçue sur <topic>/<véhicule>/control
+#[derive(Deserialize)]
+pub struct ControlCommand {
+    /// Direction, dans [-1, 1]
+    pub steer: f64,
+    /// Vitesse, dans [-1, 1]
+    pub speed: f64,
+    /// Numéro croissant attribué par l'émetteur
+    pub seq: u64,
+}
+
+impl ControlCommand {
+    pub fn parse(payload: &[u8]) -> anyhow::Result<Self> {
+        Ok(serde_json::from_slice(payload)?)
+    }
+
+    pub fn control(&self) -> Control {
+        Control {
+            steer: self.steer,
+            speed: self.speed,
+        }
+    }
+}
+
+/// Ordre des commandes: une commande dont le numéro n'est pas supérieur au précédent (en retard
+/// ou en double) est ignorée. Après `reset` sans commande acceptée, la numérotation peut repartir
+/// de zéro (redémarrage de l'émetteur).
+pub struct Sequence {
+    reset: Duration,
+    last: Option<(u64, Instant)>,
+}
+
+impl Sequence {
+    pub fn new(reset: Duration) -> Self {
+        Self { reset, last: None }
+    }
+
+    /// Vrai si la commande `seq`, reçue à `now`, doit être appliquée
+    pub fn accept(&mut self, seq: u64, now: Instant) -> bool {
+        if let Some((last, at)) = self.last {
+            if seq <= last && now.duration_since(at) < self.reset {
+                return false;
+            }
+        }
+        self.last = Some((seq, now));
+        true
+    }
+}
@@ -0,0 +1,184 @@
+use serde_json::{json, Value};
+
+use crate::config::HomeAssistantConfig;
+use crate::record::Record;
+
+/// Message MQTT: sujet, contenu, conservé par le broker
+pub struct Publish {
+    pub topic: String,
+    pub payload: String,
+    pub retain: bool,
+}
+
+/// Sujets du véhicule: <topic>/<véhicule>/...
+#[derive(Clone)]
+pub struct Topics {
+    base: String,
+}
+
+impl Topics {
+    pub fn new(topic: &str, vehicle: &str) -> Self {
+        Self {
+            base: format!("{}/{}", topic, vehicle),
+        }
+    }
+
+    /// Disponibilité du véhicule ("online", "offline" en dernière volonté)
+    pub fn availability(&self) -> String {
+        format!("{}/availability", self.base)
+    }
+
+    /// Etat d'une entité
+    pub fn state(&self, object: &str) -> String {
+        format!("{}/{}", self.base, object)
+    }
+
+    /// Commandes de l'interrupteur armé/désarmé
+    pub fn arm_command(&self) -> String {
+        format!("{}/armed/set", self.base)
+    }
+
+    /// Commandes du pilote (direction, vitesse)
+    pub fn control(&self) -> String {
+        format!("{}/control", self.base)
+    }
+}
+
+/// Identifiant Home Assistant du véhicule: lettres, chiffres, _ et -
+pub fn node_id(vehicle: &str) -> String {
+    vehicle
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() || c == '-' { c } else { '_' })
+        .collect()
+}
+
+/// Messages de découverte (conservés) des entités configurées, sur
+/// <préfixe>/<composant>/<véhicule>/<entité>/config
+pub fn discovery(config: &HomeAssistantConfig, topics: &Topics, vehicle: &str, version: &str) -> Vec<Publish> {
+    let node = node_id(vehicle);
+    let device = json!({
+        "identifiers": [node],
+        "name": vehicle,
+        "manufacturer": "rc-telemetrie",
+        "sw_version": version,
+    });
+
+    config
+        .entities
+        .iter()
+        .filter_map(|object| {
+            let (component, mut entity) = match object.as_str() {
+                "battery" => (
+                    "sensor",
+                    json!({
+                        "name": "Batterie",
+                        "state_topic": topics.state("battery"),
+                        "unit_of_measurement": "V",
+                        "device_class": "voltage",
+                        "state_class": "measurement",
+                    }),
+                ),
+                "signal" => (
+                    "sensor",
+                    json!({
+                        "name": "Signal",
+                        "state_topic": topics.state("signal"),
+                        "unit_of_measurement": "%",
+                        "state_class": "measurement",
+                        "icon": "mdi:signal",
+                    }),
+                ),
+                "position" => (
+                    "device_tracker",
+                    json!({
+                        "name": "Position",
+                        "json_attributes_topic": topics.state("position"),
+                        "source_type": "gps",
+                    }),
+                ),
+                "armed" => (
+                    "switch",
+                    json!({
+                        "name": "Armé",
+                        "state_topic": topics.state("armed"),
+                        "command_topic": topics.arm_command(),
+                        "payload_on": "ON",
+                        "payload_off": "OFF",
+                        "icon": "mdi:car-key",
+                    }),
+                ),
+                _ => return None,
+            };
+
+            let fields = entity.as_object_mut()?;
+            fields.insert("unique_id".to_string(), Value::from(format!("{}_{}", node, object)));
+            fields.insert("availability_topic".to_string(), Value::from(topics.availability()));
+            fields.insert("device".to_string(), device.clone());
+
+            Some(Publish {
+                topic: format!("{}/{}/{}/{}/config", config.discovery_prefix, component, node, object),
+                payload: entity.to_string(),
+                retain: true,
+            })
+        })
+        .collect()
+}
+
+/// Etat d'une entité tiré d'un échantillon: entité et message. None si l'échantillon ne
+/// concerne aucune entité (ou une position sans fix GPS).
+pub fn state(topics: &Topics, record: &Record) -> Option<(&'static str, Publish)> {
+    let (object, payload) = match record {
+        Record::Analog(data) => ("battery", format!("{:.2}", data.battery)),
+        Record::Modem(data) => ("signal", data.status.quality.to_string()),
+        Record::Gps(data) if data.fix => (
+            "position",
+            json!({
+                "latitude": data.latitude,
+                "longitude": data.longitude,
+                "speed_kmh": data.speed_kmh,
+                "satellites": data.satellites,
+            })
+            .to_string(),
+        ),
+        _ => return None,
+    };
+
+    Some((
+        object,
+        Publish {
+            topic: topics.state(object),
+            payload,
+            retain: false,
+        },
+    ))
+}
+
+/// Disponibilité du véhicule (conservée)
+pub fn availability(topics: &Topics, online: bool) -> Publish {
+    Publish {
+        topic: topics.availability(),
+        payload: if online { "online" } else { "offline" }.to_string(),
+        retain: true,
+    }
+}
+
+/// Etat de l'interrupteur armé/désarmé (conservé)
+pub fn armed(topics: &Topics, armed: bool) -> Publish {
+    Publish {
+        topic: topics.state("armed"),
+        payload: if armed { "ON" } else { "OFF" }.to_string(),
+        retain: true,
+    }
+}
+
+/// Commande reçue pour l'interrupteur armé/désarmé
+pub fn parse_arm(payload: &[u8]) -> anyhow::Result<bool> {
+    match std::str::from_utf8(payload).map(str::trim) {
+        Ok("ON") => Ok(true),
+        Ok("OFF") => Ok(false),
+        _ => Err(anyhow::anyhow!(
+            "commande {:?} invalide (ON ou OFF)",
+            String::from_utf8_lossy(payload)
+        )),
+    }
+}
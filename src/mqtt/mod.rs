@@ -0,0 +1,284 @@
+use std::collections::BTreeMap;
+use std::time::{Duration, Instant};
+
+use rumqttc::{AsyncClient, Event, LastWill, MqttOptions, Packet, QoS, Transport};
+use tokio::sync::broadcast::error::RecvError;
+use tokio::time::{sleep_until, timeout};
+use tokio_util::sync::CancellationToken;
+
+use crate::actuators::arbiter::Commands;
+use crate::config::MqttConfig;
+use crate::record::Record;
+use crate::writer::Writer;
+
+pub mod control;
+pub mod homeassistant;
+pub mod telemetry;
+
+use control::{ControlCommand, Sequence};
+use homeassistant::{Publish, Topics};
+use telemetry::Telemetry;
+
+/// Délai initial avant une nouvelle connexion
+const RETRY_MIN: Duration = Duration::from_secs(1);
+
+/// Délai maximum entre deux connexions
+const RETRY_MAX: Duration = Duration::from_secs(60);
+
+/// Messages en attente d'envoi vers le broker, les suivants sont perdus
+const QUEUE: usize = 64;
+
+/// Durée laissée à l'envoi de la disponibilité "offline" à l'arrêt
+const CLOSE_TIMEOUT: Duration = Duration::from_secs(1);
+
+/// Sujet sur lequel Home Assistant annonce son démarrage ("online")
+fn homeassistant_status(prefix: &str) -> String {
+    format!("{}/status", prefix)
+}
+
+/// Qualité de service configurée (0, 1 ou 2)
+fn qos(level: u8) -> QoS {
+    match level {
+        0 => QoS::AtMostOnce,
+        1 => QoS::AtLeastOnce,
+        _ => QoS::ExactlyOnce,
+    }
+}
+
+/// Transport chiffré: autorité de la configuration, sinon certificats du système
+fn tls(config: &MqttConfig) -> anyhow::Result<Transport> {
+    if config.ca_file.as_os_str().is_empty() {
+        return Ok(Transport::tls_with_default_config());
+    }
+    let ca = std::fs::read(&config.ca_file)
+        .map_err(|e| anyhow::anyhow!("lecture de {} impossible: {}", config.ca_file.display(), e))?;
+    Ok(Transport::tls(ca, None, None))
+}
+
+/// Connexion au broker MQTT: disponibilité du véhicule, entités Home Assistant et télémétrie.
+/// L'interrupteur armé/désarmé et, avec `control`, les commandes du pilote passent par
+/// l'arbitrage des commandes, comme toute autre source.
+pub async fn run(
+    config: MqttConfig,
+    vehicle: String,
+    writer: Writer,
+    commands: Commands,
+    mut control: Option<Sequence>,
+    token: CancellationToken,
+) {
+    let topics = Topics::new(&config.topic, &vehicle);
+    let homeassistant = &config.homeassistant;
+    let entities: Vec<&str> = if homeassistant.enabled {
+        homeassistant.entities.iter().map(String::as_str).collect()
+    } else {
+        Vec::new()
+    };
+
+    let client_id = if config.client_id.is_empty() {
+        format!("rc-{}", vehicle)
+    } else {
+        config.client_id.clone()
+    };
+    let mut options = MqttOptions::new(client_id, config.host.clone(), config.port);
+    options.set_keep_alive(Duration::from_secs(config.keep_alive_s));
+    options.set_last_will(LastWill::new(topics.availability(), "offline", QoS::AtLeastOnce, true));
+    if !config.username.is_empty() {
+        let password = if config.password.is_empty() {
+            std::env::var("MQTT_PASSWORD").unwrap_or_default()
+        } else {
+            config.password.clone()
+        };
+        options.set_credentials(config.username.clone(), password);
+    }
+    if config.tls {
+        match tls(&config) {
+            Ok(transport) => {
+                options.set_transport(transport);
+            }
+            Err(e) => {
+                tracing::error!(target: "mqtt", "TLS: {}", e);
+                return;
+            }
+        }
+    }
+
+    let mut telemetry = config.telemetry.enabled.then(|| Telemetry::new(&config.telemetry));
+    let telemetry_qos = qos(config.telemetry.qos);
+    if telemetry.is_some() {
+        for kind in config.telemetry.records.iter() {
+            if !Record::KINDS.contains(&kind.as_str()) {
+                tracing::warn!(target: "mqtt", "Type d'échantillon inconnu ignoré: {}", kind);
+            }
+        }
+    }
+
+    let (client, mut events) = AsyncClient::new(options, QUEUE);
+    let publish = |message: Publish, qos: QoS| {
+        // Non bloquant: file pleine, le message est perdu
+        let _ = client.try_publish(message.topic, qos, message.retain, message.payload);
+    };
+    // Non bloquant: file pleine, le message reste en attente
+    let send_telemetry = |message: &Publish| {
+        client
+            .try_publish(message.topic.clone(), telemetry_qos, message.retain, message.payload.clone())
+            .is_ok()
+    };
+    let announce = || {
+        for message in homeassistant::discovery(homeassistant, &topics, &vehicle, env!("CARGO_PKG_VERSION")) {
+            publish(message, QoS::AtLeastOnce);
+        }
+    };
+
+    let mut records = writer.subscribe();
+    let mut armed = commands.armed();
+    let interval = Duration::from_secs(homeassistant.state_interval_s);
+    let mut last_state: BTreeMap<&'static str, Instant> = BTreeMap::new();
+    let mut connected = false;
+    let mut delay = RETRY_MIN;
+    // Prochaine tentative de connexion: les échantillons restent reçus pendant l'attente
+    let mut retry: Option<tokio::time::Instant> = None;
+
+    tracing::info!(target: "mqtt", "Connexion à {}:{} ...", config.host, config.port);
+
+    loop {
+        if connected {
+            if let Some(telemetry) = telemetry.as_mut() {
+                telemetry.flush(send_telemetry);
+            }
+        }
+
+        tokio::select! {
+            _ = token.cancelled() => break,
+            _ = sleep_until(retry.unwrap_or_else(tokio::time::Instant::now)), if retry.is_some() => {
+                // La connexion est reprise par le prochain appel à poll()
+                retry = None;
+            }
+            event = events.poll(), if retry.is_none() => match event {
+                Ok(Event::Incoming(Packet::ConnAck(_))) => {
+                    tracing::info!(target: "mqtt", "Connecté à {}:{}", config.host, config.port);
+                    connected = true;
+                    delay = RETRY_MIN;
+                    last_state.clear();
+
+                    if let Some(telemetry) = telemetry.as_mut() {
+                        let dropped = telemetry.take_dropped();
+                        if dropped > 0 {
+                            tracing::warn!(
+                                target: "mqtt",
+                                "{} échantillons perdus pendant la coupure ({} en attente)",
+                                dropped,
+                                telemetry.pending()
+                            );
+                        }
+                    }
+
+                    publish(homeassistant::availability(&topics, true), QoS::AtLeastOnce);
+                    if homeassistant.enabled {
+                        announce();
+                        let status = homeassistant_status(&homeassistant.discovery_prefix);
+                        let _ = client.try_subscribe(status, QoS::AtLeastOnce);
+                    }
+                    if control.is_some() {
+                        // Au plus une fois: une commande perdue est remplacée par la suivante
+                        let _ = client.try_subscribe(topics.control(), QoS::AtMostOnce);
+                    }
+                    if entities.contains(&"armed") {
+                        let _ = client.try_subscribe(topics.arm_command(), QoS::AtLeastOnce);
+                        publish(homeassistant::armed(&topics, *armed.borrow()), QoS::AtLeastOnce);
+                    }
+                }
+                Ok(Event::Incoming(Packet::Publish(message))) => {
+                    if let Some(sequence) = control.as_mut().filter(|_| message.topic == topics.control()) {
+                        match ControlCommand::parse(&message.payload) {
+                            Ok(command) if sequence.accept(command.seq, Instant::now()) => {
+                                if let Err(e) = commands.submit("mqtt", command.control()) {
+                                    tracing::error!(target: "mqtt", "Commande refusée: {}", e);
+                                }
+                            }
+                            Ok(command) => {
+                                tracing::debug!(target: "mqtt", "Commande {} hors séquence ignorée", command.seq);
+                            }
+                            Err(e) => tracing::error!(target: "mqtt", "Commande illisible: {}", e),
+                        }
+                    } else if message.topic == topics.arm_command() {
+                        match homeassistant::parse_arm(&message.payload) {
+                            Ok(value) => commands.request_arm("homeassistant", value),
+                            Err(e) => tracing::error!(target: "mqtt", "Commande refusée: {}", e),
+                        }
+                    } else if homeassistant.enabled
+                        && message.topic == homeassistant_status(&homeassistant.discovery_prefix)
+                        && message.payload.as_ref() == b"online"
+                    {
+                        // Redémarrage de Home Assistant: nouvelle annonce des entités
+                        announce();
+                        last_state.clear();
+                    }
+                }
+                Ok(_) => {}
+                Err(e) => {
+                    if connected {
+                        tracing::error!(target: "mqtt", "Connexion perdue: {}", e);
+                    } else {
+                        tracing::warn!(
+                            target: "mqtt",
+                            "Connexion impossible ({}), nouvel essai dans {}s.",
+                            e,
+                            delay.as_secs()
+                        );
+                    }
+                    connected = false;
+                    retry = Some(tokio::time::Instant::now() + delay);
+                    delay = (delay * 2).min(RETRY_MAX);
+                }
+            },
+            changed = armed.changed() => {
+                if changed.is_ok() && connected && entities.contains(&"armed") {
+                    publish(homeassistant::armed(&topics, *armed.borrow_and_update()), QoS::AtLeastOnce);
+                }
+            }
+            record = records.recv() => match record {
+                Ok(record) => {
+                    if let Some(telemetry) = telemetry.as_mut() {
+                        telemetry.push(&topics, &record, Instant::now());
+                    }
+                    if !connected {
+                        continue;
+                    }
+                    let Some((object, message)) = homeassistant::state(&topics, &record) else {
+                        continue;
+                    };
+                    if !entities.contains(&object) {
+                        continue;
+                    }
+
+                    let now = Instant::now();
+                    if last_state.get(object).is_some_and(|last| now.duration_since(*last) < interval) {
+                        continue;
+                    }
+                    last_state.insert(object, now);
+                    publish(message, QoS::AtMostOnce);
+                }
+                Err(RecvError::Lagged(_)) => {}
+                Err(RecvError::Closed) => break,
+            },
+        }
+    }
+
+    // Arrêt propre: le véhicule est annoncé indisponible, sans attendre la dernière volonté
+    if connected {
+        if let Some(telemetry) = telemetry.as_mut() {
+            telemetry.flush(send_telemetry);
+        }
+        publish(homeassistant::availability(&topics, false), QoS::AtLeastOnce);
+        let _ = client.try_disconnect();
+        let _ = timeout(CLOSE_TIMEOUT, async {
+            while !matches!(
+                events.poll().await,
+                Ok(Event::Outgoing(rumqttc::Outgoing::Disconnect)) | Err(_)
+            ) {}
+        })
+        .await;
+    }
+
+    tracing::info!(target: "mqtt", "Arrêt.");
+}
@@ -0,0 +1,82 @@
+use std::collections::{BTreeMap, VecDeque};
+use std::time::{Duration, Instant};
+
+use crate::config::MqttTelemetryConfig;
+use crate::record::Record;
+
+use super::homeassistant::{Publish, Topics};
+
+/// Télémétrie publiée sur <topic>/<véhicule>/<type>: échantillons sélectionnés, limités en
+/// fréquence et conservés pendant une coupure du broker (les plus anciens sont perdus)
+pub struct Telemetry {
+    records: Vec<String>,
+    interval: Option<Duration>,
+    last: BTreeMap<&'static str, Instant>,
+    pending: VecDeque<Publish>,
+    capacity: usize,
+    dropped: u64,
+}
+
+impl Telemetry {
+    pub fn new(config: &MqttTelemetryConfig) -> Self {
+        Self {
+            records: config.records.clone(),
+            interval: (config.max_rate > 0.0).then(|| Duration::from_secs_f64(1.0 / config.max_rate)),
+            last: BTreeMap::new(),
+            pending: VecDeque::new(),
+            capacity: config.queue,
+            dropped: 0,
+        }
+    }
+
+    /// Met l'échantillon en attente d'envoi s'il est sélectionné et que sa fréquence le permet.
+    /// Vrai si un message a été ajouté.
+    pub fn push(&mut self, topics: &Topics, record: &Record, now: Instant) -> bool {
+        let kind = record.kind();
+        if !self.records.iter().any(|k| k == kind) {
+            return false;
+        }
+
+        if let Some(interval) = self.interval {
+            if self.last.get(kind).is_some_and(|last| now.duration_since(*last) < interval) {
+                return false;
+            }
+            self.last.insert(kind, now);
+        }
+
+        let Ok(payload) = serde_json::to_string(record) else {
+            return false;
+        };
+        if self.pending.len() >= self.capacity {
+            self.pending.pop_front();
+            self.dropped += 1;
+        }
+        self.pending.push_back(Publish {
+            topic: topics.state(kind),
+            payload,
+            retain: false,
+        });
+        true
+    }
+
+    /// Envoie les messages en attente dans l'ordre, jusqu'au premier refusé par `send`
+    /// (file du client pleine), qui reste en attente
+    pub fn flush(&mut self, mut send: impl FnMut(&Publish) -> bool) {
+        while let Some(message) = self.pending.front() {
+            if !send(message) {
+                break;
+            }
+            self.pending.pop_front();
+        }
+    }
+
+    /// Messages en attente d'envoi
+    pub fn pending(&self) -> usize {
+        self.pending.len()
+    }
+
+    /// Messages perdus (file pleine) depuis le dernier appel
+    pub fn take_dropped(&mut self) -> u64 {
+        std::mem::take(&mut self.dropped)
+    }
+}
@@ -0,0 +1,156 @@
+use std::time::{Duration, Instant};
+
+use futures::StreamExt;
+use tokio::sync::watch;
+use tokio::time::sleep;
+use tokio_util::sync::CancellationToken;
+
+use crate::clock::Clock;
+use crate::config::Config;
+use crate::record::{AccessTechnology, ModemStatus, Operator, Registration};
+use crate::sensors::reader::{Data, Reader};
+use crate::sensors::replay::Recorder;
+use crate::sensors::sim::SharedSimulation;
+use crate::timing::{LoopTimer, TimingReport};
+use crate::writer::{Event, ModemData, Writer};
+
+/// Période de la boucle des capteurs
+const PERIOD: Duration = Duration::from_millis(1000 / 30);
+
+/// Période du modem simulé
+const MODEM_PERIOD: Duration = Duration::from_millis(500);
+
+/// Boucle des capteurs: nouveaux échantillons vers l'écrivain (et l'enregistrement), état des
+/// capteurs, gigue et rapport de temps
+pub async fn run(
+    mut reader: Reader,
+    mut recorder: Option<Recorder>,
+    writer: Writer,
+    mut updates: watch::Receiver<Config>,
+    token: CancellationToken,
+) {
+    let mut timer = LoopTimer::new("pipeline", PERIOD, &updates.borrow().timing);
+    let mut last_status = None;
+    let mut last = Data::default();
+    let mut last_timing = Instant::now();
+
+    while !token.is_cancelled() {
+        // Rechargement de la configuration
+        if updates.has_changed().unwrap_or(false) {
+            timer.configure(PERIOD, &updates.borrow_and_update().timing);
+        }
+        let timing_interval = Duration::from_secs(updates.borrow().writer.stats_interval_s.max(1));
+
+        let start = timer.start();
+
+        // Fin du flux (annulation ou fin du rejeu)
+        let Some(data) = reader.next().await else {
+            break;
+        };
+
+        if let (Ok(data), Some(r)) = (&data, recorder.as_mut()) {
+            if let Err(e) = r.write(data) {
+                tracing::error!(target: "record", "Erreur d'écriture, arrêt de l'enregistrement: {}", e);
+                recorder = None;
+            }
+        }
+        timer.read_done(start.elapsed());
+
+        let submit = Instant::now();
+        if let Ok(data) = data {
+            // Uniquement les nouveaux échantillons de chaque capteur
+            if data.imu.stamp != last.imu.stamp {
+                writer.imu(data.imu);
+            }
+            if data.mag.stamp != last.mag.stamp {
+                writer.mag(data.mag);
+            }
+            if data.analog.stamp != last.analog.stamp {
+                writer.analog(data.analog);
+            }
+            if data.power.stamp != last.power.stamp {
+                writer.power(data.power);
+            }
+            if data.baro.stamp != last.baro.stamp {
+                writer.baro(data.baro);
+            }
+            if data.encoder.stamp != last.encoder.stamp {
+                writer.encoder(data.encoder);
+            }
+            if data.range.stamp != last.range.stamp {
+                writer.range(data.range);
+            }
+            if data.gps.stamp != last.gps.stamp {
+                // File sans perte: attend l'écrivain, sauf à l'arrêt
+                tokio::select! {
+                    _ = token.cancelled() => break,
+                    _ = writer.gps(data.gps) => {}
+                }
+            }
+            if data.satellites.stamp != last.satellites.stamp {
+                writer.satellites(data.satellites);
+            }
+            last = data;
+        }
+
+        // Etat des capteurs, envoyé uniquement lors d'un changement
+        if let Some(status) = reader.status_changed(last_status.as_ref()) {
+            let event = Event::Status(status.clone(), reader.clock().stamp());
+            if writer.event(event).await.is_ok() {
+                last_status = Some(status);
+            }
+        }
+        timer.submit_done(submit.elapsed());
+
+        // Gigue des boucles de capteurs
+        let mut warnings = reader.warnings();
+        warnings.extend(timer.check());
+        for warning in warnings {
+            tracing::info!(target: "timing", "{}", warning);
+            let _ = writer.event(Event::Warning(warning, reader.clock().stamp())).await;
+        }
+
+        if last_timing.elapsed() >= timing_interval {
+            last_timing = Instant::now();
+            let report = TimingReport {
+                reader: reader.timing(),
+                pipeline: timer.report(),
+            };
+            let _ = writer.event(Event::Timing(report, reader.clock().stamp())).await;
+        }
+
+        sleep(PERIOD).await;
+    }
+}
+
+/// Etat du modem simulé: réseau LTE de l'opérateur, mesures étendues déduites de la qualité
+fn fake_modem_status(quality: u32) -> ModemStatus {
+    let quality = quality.min(100);
+    let ratio = quality as f64 / 100.0;
+
+    ModemStatus {
+        quality,
+        technology: Some(AccessTechnology::Lte),
+        registration: Some(if quality == 0 { Registration::Searching } else { Registration::Home }),
+        operator: Operator::new("Simulé"),
+        rsrp: Some(-140.0 + 96.0 * ratio),
+        rsrq: Some(-20.0 + 17.0 * ratio),
+        sinr: Some(-10.0 + 40.0 * ratio),
+    }
+}
+
+/// Etat du modem du véhicule simulé
+pub async fn fake_modem(simulation: SharedSimulation, writer: Writer, clock: Clock, token: CancellationToken) {
+    while !token.is_cancelled() {
+        let signal = {
+            let mut simulation = simulation.lock().unwrap();
+            simulation.sync(clock.elapsed());
+            simulation.readings().signal
+        };
+        writer.modem(ModemData {
+            status: fake_modem_status(signal),
+            stamp: clock.stamp(),
+        });
+        sleep(MODEM_PERIOD).await;
+    }
+}
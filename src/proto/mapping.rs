@@ -0,0 +1,388 @@
+use chrono::DateTime;
+
+use crate::clock::Stamp;
+use crate::proto;
+use crate::record::{AccessTechnology, ModemData, ModemStatus, Operator, Record, Registration};
+use crate::sensors::reader::{
+    AnalogData, BaroData, Data, EncoderData, GpsData, ImuData, MagData, PowerData, RangeData, RangeStatus, SatellitesData, SensorStatus,
+    MAX_CELLS,
+};
+
+// Conversion des échantillons internes vers les messages protobuf, et retour.
+// Un champ absent (ancienne version du schéma) donne la valeur par défaut.
+
+impl From<Stamp> for proto::Stamp {
+    fn from(stamp: Stamp) -> Self {
+        Self {
+            mono_us: stamp.mono_us,
+            utc_us: stamp.utc.timestamp_micros(),
+        }
+    }
+}
+
+impl From<ImuData> for proto::Imu {
+    fn from(data: ImuData) -> Self {
+        let (pitch, roll, yaw) = data.angles;
+        let (accel_x, accel_y, accel_z) = data.accel;
+        let (gyro_x, gyro_y, gyro_z) = data.gyro;
+        Self {
+            stamp: Some(data.stamp.into()),
+            pitch,
+            roll,
+            yaw,
+            temp: data.temp,
+            accel_x,
+            accel_y,
+            accel_z,
+            gyro_x,
+            gyro_y,
+            gyro_z,
+        }
+    }
+}
+
+impl From<MagData> for proto::Mag {
+    fn from(data: MagData) -> Self {
+        let (x, y, z) = data.raw;
+        let (calibrated_x, calibrated_y, calibrated_z) = data.calibrated;
+        Self {
+            stamp: Some(data.stamp.into()),
+            raw_x: x as i32,
+            raw_y: y as i32,
+            raw_z: z as i32,
+            heading: data.heading,
+            calibrated_x,
+            calibrated_y,
+            calibrated_z,
+            true_heading: data.true_heading,
+            declination: data.declination,
+        }
+    }
+}
+
+impl From<AnalogData> for proto::Analog {
+    fn from(data: AnalogData) -> Self {
+        Self {
+            stamp: Some(data.stamp.into()),
+            battery: data.battery,
+            cells: data.measured_cells(),
+            current: data.current,
+        }
+    }
+}
+
+impl From<GpsData> for proto::Gps {
+    fn from(data: GpsData) -> Self {
+        Self {
+            stamp: Some(data.stamp.into()),
+            speed_kmh: data.speed_kmh,
+            latitude: data.latitude,
+            longitude: data.longitude,
+            satellites: data.satellites as u32,
+            fix: data.fix,
+            heading: data.heading,
+            time_us: data.time.map_or(0, |time| time.timestamp_micros()),
+            valid: data.valid,
+        }
+    }
+}
+
+impl From<SatellitesData> for proto::Satellites {
+    fn from(data: SatellitesData) -> Self {
+        Self {
+            stamp: Some(data.stamp.into()),
+            pdop: data.pdop,
+            hdop: data.hdop,
+            vdop: data.vdop,
+            used: data.used as u32,
+            in_view: data.in_view as u32,
+            snr_mean: data.snr_mean,
+        }
+    }
+}
+
+impl From<ModemData> for proto::Modem {
+    fn from(data: ModemData) -> Self {
+        Self {
+            stamp: Some(data.stamp.into()),
+            quality: data.status.quality,
+            technology: data.status.technology.map(|technology| technology.name()).unwrap_or_default().to_string(),
+            registration: data.status.registration.map(|registration| registration.name()).unwrap_or_default().to_string(),
+            operator: data.status.operator.map(|operator| operator.as_str().to_string()).unwrap_or_default(),
+            rsrp: data.status.rsrp,
+            rsrq: data.status.rsrq,
+            sinr: data.status.sinr,
+        }
+    }
+}
+
+impl From<PowerData> for proto::Power {
+    fn from(data: PowerData) -> Self {
+        Self {
+            stamp: Some(data.stamp.into()),
+            voltage: data.voltage,
+            current: data.current,
+            power: data.power,
+        }
+    }
+}
+
+impl From<BaroData> for proto::Baro {
+    fn from(data: BaroData) -> Self {
+        Self {
+            stamp: Some(data.stamp.into()),
+            pressure: data.pressure,
+            temperature: data.temperature,
+            humidity: data.humidity,
+            altitude: data.altitude,
+        }
+    }
+}
+
+impl From<EncoderData> for proto::Encoder {
+    fn from(data: EncoderData) -> Self {
+        Self {
+            stamp: Some(data.stamp.into()),
+            speed_kmh: data.speed_kmh,
+            distance_m: data.distance_m,
+            pulses: data.pulses,
+        }
+    }
+}
+
+impl From<RangeData> for proto::Range {
+    fn from(data: RangeData) -> Self {
+        Self {
+            stamp: Some(data.stamp.into()),
+            distance_mm: data.distance_mm.map(u32::from),
+            status: data.status.name().to_string(),
+        }
+    }
+}
+
+impl From<Record> for proto::Record {
+    fn from(record: Record) -> Self {
+        let record = match record {
+            Record::Imu(data) => proto::record::Record::Imu(data.into()),
+            Record::Mag(data) => proto::record::Record::Mag(data.into()),
+            Record::Analog(data) => proto::record::Record::Analog(data.into()),
+            Record::Gps(data) => proto::record::Record::Gps(data.into()),
+            Record::Modem(data) => proto::record::Record::Modem(data.into()),
+            Record::Satellites(data) => proto::record::Record::Satellites(data.into()),
+            Record::Power(data) => proto::record::Record::Power(data.into()),
+            Record::Baro(data) => proto::record::Record::Baro(data.into()),
+            Record::Encoder(data) => proto::record::Record::Encoder(data.into()),
+            Record::Range(data) => proto::record::Record::Range(data.into()),
+        };
+
+        Self { record: Some(record) }
+    }
+}
+
+impl From<Data> for proto::Data {
+    fn from(data: Data) -> Self {
+        Self {
+            imu: Some(data.imu.into()),
+            mag: Some(data.mag.into()),
+            analog: Some(data.analog.into()),
+            gps: Some(data.gps.into()),
+            satellites: Some(data.satellites.into()),
+            power: Some(data.power.into()),
+            baro: Some(data.baro.into()),
+            encoder: Some(data.encoder.into()),
+            range: Some(data.range.into()),
+        }
+    }
+}
+
+impl From<proto::Stamp> for Stamp {
+    fn from(stamp: proto::Stamp) -> Self {
+        Self {
+            mono_us: stamp.mono_us,
+            utc: DateTime::from_timestamp_micros(stamp.utc_us).unwrap_or_default(),
+        }
+    }
+}
+
+impl From<proto::Imu> for ImuData {
+    fn from(imu: proto::Imu) -> Self {
+        Self {
+            stamp: imu.stamp.map(Into::into).unwrap_or_default(),
+            angles: (imu.pitch, imu.roll, imu.yaw),
+            temp: imu.temp,
+            accel: (imu.accel_x, imu.accel_y, imu.accel_z),
+            gyro: (imu.gyro_x, imu.gyro_y, imu.gyro_z),
+        }
+    }
+}
+
+impl From<proto::Mag> for MagData {
+    fn from(mag: proto::Mag) -> Self {
+        let raw = |value: i32| value.clamp(i16::MIN as i32, i16::MAX as i32) as i16;
+        Self {
+            stamp: mag.stamp.map(Into::into).unwrap_or_default(),
+            raw: (raw(mag.raw_x), raw(mag.raw_y), raw(mag.raw_z)),
+            heading: mag.heading,
+            calibrated: (mag.calibrated_x, mag.calibrated_y, mag.calibrated_z),
+            true_heading: mag.true_heading,
+            declination: mag.declination,
+        }
+    }
+}
+
+impl From<proto::Analog> for AnalogData {
+    fn from(analog: proto::Analog) -> Self {
+        let mut cells = [None; MAX_CELLS];
+        for (cell, voltage) in cells.iter_mut().zip(analog.cells) {
+            *cell = Some(voltage);
+        }
+
+        Self {
+            stamp: analog.stamp.map(Into::into).unwrap_or_default(),
+            battery: analog.battery,
+            cells,
+            current: analog.current,
+        }
+    }
+}
+
+impl From<proto::Gps> for GpsData {
+    fn from(gps: proto::Gps) -> Self {
+        Self {
+            stamp: gps.stamp.map(Into::into).unwrap_or_default(),
+            speed_kmh: gps.speed_kmh,
+            latitude: gps.latitude,
+            longitude: gps.longitude,
+            satellites: gps.satellites.min(u8::MAX as u32) as u8,
+            fix: gps.fix,
+            heading: gps.heading,
+            time: (gps.time_us != 0)
+                .then(|| DateTime::from_timestamp_micros(gps.time_us))
+                .flatten(),
+            valid: gps.valid,
+        }
+    }
+}
+
+impl From<proto::Satellites> for SatellitesData {
+    fn from(satellites: proto::Satellites) -> Self {
+        Self {
+            stamp: satellites.stamp.map(Into::into).unwrap_or_default(),
+            pdop: satellites.pdop,
+            hdop: satellites.hdop,
+            vdop: satellites.vdop,
+            used: satellites.used.min(u8::MAX as u32) as u8,
+            in_view: satellites.in_view.min(u8::MAX as u32) as u8,
+            snr_mean: satellites.snr_mean,
+        }
+    }
+}
+
+impl From<proto::Power> for PowerData {
+    fn from(power: proto::Power) -> Self {
+        Self {
+            stamp: power.stamp.map(Into::into).unwrap_or_default(),
+            voltage: power.voltage,
+            current: power.current,
+            power: power.power,
+        }
+    }
+}
+
+impl From<proto::Baro> for BaroData {
+    fn from(baro: proto::Baro) -> Self {
+        Self {
+            stamp: baro.stamp.map(Into::into).unwrap_or_default(),
+            pressure: baro.pressure,
+            temperature: baro.temperature,
+            humidity: baro.humidity,
+            altitude: baro.altitude,
+        }
+    }
+}
+
+impl From<proto::Encoder> for EncoderData {
+    fn from(encoder: proto::Encoder) -> Self {
+        Self {
+            stamp: encoder.stamp.map(Into::into).unwrap_or_default(),
+            speed_kmh: encoder.speed_kmh,
+            distance_m: encoder.distance_m,
+            pulses: encoder.pulses,
+        }
+    }
+}
+
+impl From<proto::Range> for RangeData {
+    fn from(range: proto::Range) -> Self {
+        Self {
+            stamp: range.stamp.map(Into::into).unwrap_or_default(),
+            distance_mm: range.distance_mm.and_then(|distance| u16::try_from(distance).ok()),
+            status: RangeStatus::from_name(&range.status).unwrap_or_default(),
+        }
+    }
+}
+
+impl From<proto::Modem> for ModemData {
+    fn from(modem: proto::Modem) -> Self {
+        Self {
+            status: ModemStatus {
+                quality: modem.quality,
+                technology: AccessTechnology::from_name(&modem.technology),
+                registration: Registration::from_name(&modem.registration),
+                operator: Operator::new(&modem.operator),
+                rsrp: modem.rsrp,
+                rsrq: modem.rsrq,
+                sinr: modem.sinr,
+            },
+            stamp: modem.stamp.map(Into::into).unwrap_or_default(),
+        }
+    }
+}
+
+impl TryFrom<proto::Record> for Record {
+    type Error = anyhow::Error;
+
+    fn try_from(record: proto::Record) -> anyhow::Result<Self> {
+        Ok(match record.record {
+            Some(proto::record::Record::Imu(imu)) => Record::Imu(imu.into()),
+            Some(proto::record::Record::Mag(mag)) => Record::Mag(mag.into()),
+            Some(proto::record::Record::Analog(analog)) => Record::Analog(analog.into()),
+            Some(proto::record::Record::Gps(gps)) => Record::Gps(gps.into()),
+            Some(proto::record::Record::Modem(modem)) => Record::Modem(modem.into()),
+            Some(proto::record::Record::Satellites(satellites)) => Record::Satellites(satellites.into()),
+            Some(proto::record::Record::Power(power)) => Record::Power(power.into()),
+            Some(proto::record::Record::Baro(baro)) => Record::Baro(baro.into()),
+            Some(proto::record::Record::Encoder(encoder)) => Record::Encoder(encoder.into()),
+            Some(proto::record::Record::Range(range)) => Record::Range(range.into()),
+            // Variante inconnue (version plus récente du schéma) ou absente
+            None => return Err(anyhow::anyhow!("Echantillon de type inconnu")),
+        })
+    }
+}
+
+impl From<proto::Data> for Data {
+    fn from(data: proto::Data) -> Self {
+        Self {
+            imu: data.imu.map(Into::into).unwrap_or_default(),
+            mag: data.mag.map(Into::into).unwrap_or_default(),
+            analog: data.analog.map(Into::into).unwrap_or_default(),
+            gps: data.gps.map(Into::into).unwrap_or_default(),
+            satellites: data.satellites.map(Into::into).unwrap_or_default(),
+            power: data.power.map(Into::into).unwrap_or_default(),
+            baro: data.baro.map(Into::into).unwrap_or_default(),
+            encoder: data.encoder.map(Into::into).unwrap_or_default(),
+            range: data.range.map(Into::into).unwrap_or_default(),
+        }
+    }
+}
+
+impl From<&SensorStatus> for proto::SensorStatus {
+    fn from(status: &SensorStatus) -> Self {
+        Self {
+            available: status.available,
+            error: status.error.clone().unwrap_or_default(),
+            attempts: status.attempts,
+            restarts: status.restarts,
+        }
+    }
+}
@@ -0,0 +1,334 @@
+// Messages de proto/records.proto et proto/telemetry.proto, écrits à la main (mêmes numéros de
+// champ). Règles d'évolution du schéma: voir proto/records.proto.
+
+mod mapping;
+
+#[derive(Clone, Copy, PartialEq, prost::Message)]
+pub struct Stamp {
+    #[prost(uint64, tag = "1")]
+    pub mono_us: u64,
+    #[prost(int64, tag = "2")]
+    pub utc_us: i64,
+}
+
+#[derive(Clone, Copy, PartialEq, prost::Message)]
+pub struct Imu {
+    #[prost(message, optional, tag = "1")]
+    pub stamp: Option<Stamp>,
+    #[prost(float, tag = "2")]
+    pub pitch: f32,
+    #[prost(float, tag = "3")]
+    pub roll: f32,
+    #[prost(float, tag = "4")]
+    pub yaw: f32,
+    #[prost(float, tag = "5")]
+    pub temp: f32,
+    /// Accélération (g)
+    #[prost(float, tag = "6")]
+    pub accel_x: f32,
+    #[prost(float, tag = "7")]
+    pub accel_y: f32,
+    #[prost(float, tag = "8")]
+    pub accel_z: f32,
+    /// Vitesse angulaire (°/s)
+    #[prost(float, tag = "9")]
+    pub gyro_x: f32,
+    #[prost(float, tag = "10")]
+    pub gyro_y: f32,
+    #[prost(float, tag = "11")]
+    pub gyro_z: f32,
+}
+
+#[derive(Clone, Copy, PartialEq, prost::Message)]
+pub struct Mag {
+    #[prost(message, optional, tag = "1")]
+    pub stamp: Option<Stamp>,
+    #[prost(sint32, tag = "2")]
+    pub raw_x: i32,
+    #[prost(sint32, tag = "3")]
+    pub raw_y: i32,
+    #[prost(sint32, tag = "4")]
+    pub raw_z: i32,
+    #[prost(float, tag = "5")]
+    pub heading: f32,
+    /// Champ corrigé par la calibration
+    #[prost(float, tag = "6")]
+    pub calibrated_x: f32,
+    #[prost(float, tag = "7")]
+    pub calibrated_y: f32,
+    #[prost(float, tag = "8")]
+    pub calibrated_z: f32,
+    /// Cap vrai et déclinaison appliquée (degrés)
+    #[prost(float, tag = "9")]
+    pub true_heading: f32,
+    #[prost(float, tag = "10")]
+    pub declination: f32,
+}
+
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct Analog {
+    #[prost(message, optional, tag = "1")]
+    pub stamp: Option<Stamp>,
+    #[prost(float, tag = "2")]
+    pub battery: f32,
+    #[prost(float, repeated, tag = "3")]
+    pub cells: Vec<f32>,
+    #[prost(float, optional, tag = "4")]
+    pub current: Option<f32>,
+}
+
+#[derive(Clone, Copy, PartialEq, prost::Message)]
+pub struct Gps {
+    #[prost(message, optional, tag = "1")]
+    pub stamp: Option<Stamp>,
+    #[prost(double, tag = "2")]
+    pub speed_kmh: f64,
+    #[prost(double, tag = "3")]
+    pub latitude: f64,
+    #[prost(double, tag = "4")]
+    pub longitude: f64,
+    #[prost(uint32, tag = "5")]
+    pub satellites: u32,
+    #[prost(bool, tag = "6")]
+    pub fix: bool,
+    #[prost(double, tag = "7")]
+    pub heading: f64,
+    #[prost(int64, tag = "8")]
+    pub time_us: i64,
+    #[prost(bool, tag = "9")]
+    pub valid: bool,
+}
+
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct Modem {
+    #[prost(message, optional, tag = "1")]
+    pub stamp: Option<Stamp>,
+    #[prost(uint32, tag = "2")]
+    pub quality: u32,
+    #[prost(string, tag = "3")]
+    pub technology: String,
+    #[prost(string, tag = "4")]
+    pub registration: String,
+    #[prost(string, tag = "5")]
+    pub operator: String,
+    #[prost(double, optional, tag = "6")]
+    pub rsrp: Option<f64>,
+    #[prost(double, optional, tag = "7")]
+    pub rsrq: Option<f64>,
+    #[prost(double, optional, tag = "8")]
+    pub sinr: Option<f64>,
+}
+
+#[derive(Clone, Copy, PartialEq, prost::Message)]
+pub struct Satellites {
+    #[prost(message, optional, tag = "1")]
+    pub stamp: Option<Stamp>,
+    #[prost(double, tag = "2")]
+    pub pdop: f64,
+    #[prost(double, tag = "3")]
+    pub hdop: f64,
+    #[prost(double, tag = "4")]
+    pub vdop: f64,
+    #[prost(uint32, tag = "5")]
+    pub used: u32,
+    #[prost(uint32, tag = "6")]
+    pub in_view: u32,
+    #[prost(double, tag = "7")]
+    pub snr_mean: f64,
+}
+
+#[derive(Clone, Copy, PartialEq, prost::Message)]
+pub struct Power {
+    #[prost(message, optional, tag = "1")]
+    pub stamp: Option<Stamp>,
+    #[prost(float, tag = "2")]
+    pub voltage: f32,
+    #[prost(float, tag = "3")]
+    pub current: f32,
+    #[prost(float, tag = "4")]
+    pub power: f32,
+}
+
+#[derive(Clone, Copy, PartialEq, prost::Message)]
+pub struct Baro {
+    #[prost(message, optional, tag = "1")]
+    pub stamp: Option<Stamp>,
+    #[prost(float, tag = "2")]
+    pub pressure: f32,
+    #[prost(float, tag = "3")]
+    pub temperature: f32,
+    #[prost(float, optional, tag = "4")]
+    pub humidity: Option<f32>,
+    #[prost(float, tag = "5")]
+    pub altitude: f32,
+}
+
+#[derive(Clone, Copy, PartialEq, prost::Message)]
+pub struct Encoder {
+    #[prost(message, optional, tag = "1")]
+    pub stamp: Option<Stamp>,
+    #[prost(double, tag = "2")]
+    pub speed_kmh: f64,
+    #[prost(double, tag = "3")]
+    pub distance_m: f64,
+    #[prost(uint64, tag = "4")]
+    pub pulses: u64,
+}
+
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct Range {
+    #[prost(message, optional, tag = "1")]
+    pub stamp: Option<Stamp>,
+    /// Absent sauf pour une mesure valide
+    #[prost(uint32, optional, tag = "2")]
+    pub distance_mm: Option<u32>,
+    /// valid, out_of_range, invalid ou timeout
+    #[prost(string, tag = "3")]
+    pub status: String,
+}
+
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct Record {
+    #[prost(oneof = "record::Record", tags = "1, 2, 3, 4, 5, 6, 7, 8, 9, 10")]
+    pub record: Option<record::Record>,
+}
+
+pub mod record {
+    #[derive(Clone, PartialEq, prost::Oneof)]
+    pub enum Record {
+        #[prost(message, tag = "1")]
+        Imu(super::Imu),
+        #[prost(message, tag = "2")]
+        Mag(super::Mag),
+        #[prost(message, tag = "3")]
+        Analog(super::Analog),
+        #[prost(message, tag = "4")]
+        Gps(super::Gps),
+        #[prost(message, tag = "5")]
+        Modem(super::Modem),
+        #[prost(message, tag = "6")]
+        Satellites(super::Satellites),
+        #[prost(message, tag = "7")]
+        Power(super::Power),
+        #[prost(message, tag = "8")]
+        Baro(super::Baro),
+        #[prost(message, tag = "9")]
+        Encoder(super::Encoder),
+        #[prost(message, tag = "10")]
+        Range(super::Range),
+    }
+}
+
+/// Dernières valeurs des capteurs
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct Data {
+    #[prost(message, optional, tag = "1")]
+    pub imu: Option<Imu>,
+    #[prost(message, optional, tag = "2")]
+    pub mag: Option<Mag>,
+    #[prost(message, optional, tag = "3")]
+    pub analog: Option<Analog>,
+    #[prost(message, optional, tag = "4")]
+    pub gps: Option<Gps>,
+    #[prost(message, optional, tag = "5")]
+    pub satellites: Option<Satellites>,
+    #[prost(message, optional, tag = "6")]
+    pub power: Option<Power>,
+    #[prost(message, optional, tag = "7")]
+    pub baro: Option<Baro>,
+    #[prost(message, optional, tag = "8")]
+    pub encoder: Option<Encoder>,
+    #[prost(message, optional, tag = "9")]
+    pub range: Option<Range>,
+}
+
+/// Echantillon d'un enregistrement binaire
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct Sample {
+    #[prost(message, optional, tag = "1")]
+    pub stamp: Option<Stamp>,
+    #[prost(message, optional, tag = "2")]
+    pub data: Option<Data>,
+}
+
+/// Datagramme UDP binaire
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct Datagram {
+    #[prost(uint64, tag = "1")]
+    pub seq: u64,
+    #[prost(string, tag = "2")]
+    pub r#type: String,
+    #[prost(message, optional, tag = "3")]
+    pub stamp: Option<Stamp>,
+    #[prost(message, optional, tag = "4")]
+    pub record: Option<Record>,
+    #[prost(bool, tag = "5")]
+    pub truncated: bool,
+}
+
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct StreamRequest {
+    #[prost(string, repeated, tag = "1")]
+    pub records: Vec<String>,
+}
+
+#[derive(Clone, Copy, PartialEq, prost::Message)]
+pub struct StatusRequest {}
+
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct SensorStatus {
+    #[prost(bool, tag = "1")]
+    pub available: bool,
+    #[prost(string, tag = "2")]
+    pub error: String,
+    #[prost(uint32, tag = "3")]
+    pub attempts: u32,
+    #[prost(uint32, tag = "4")]
+    pub restarts: u32,
+}
+
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct Status {
+    #[prost(string, tag = "1")]
+    pub vehicle: String,
+    #[prost(string, tag = "2")]
+    pub run: String,
+    #[prost(string, tag = "3")]
+    pub version: String,
+    #[prost(uint64, tag = "4")]
+    pub uptime_us: u64,
+    #[prost(message, optional, tag = "5")]
+    pub imu: Option<SensorStatus>,
+    #[prost(message, optional, tag = "6")]
+    pub mag: Option<SensorStatus>,
+    #[prost(message, optional, tag = "7")]
+    pub analog: Option<SensorStatus>,
+    #[prost(message, optional, tag = "8")]
+    pub gps: Option<SensorStatus>,
+    #[prost(message, optional, tag = "9")]
+    pub power: Option<SensorStatus>,
+    #[prost(message, optional, tag = "10")]
+    pub baro: Option<SensorStatus>,
+    #[prost(message, optional, tag = "11")]
+    pub encoder: Option<SensorStatus>,
+    #[prost(message, optional, tag = "12")]
+    pub range: Option<SensorStatus>,
+}
+
+#[derive(Clone, Copy, PartialEq, prost::Message)]
+pub struct Control {
+    #[prost(double, tag = "1")]
+    pub steer: f64,
+    #[prost(double, tag = "2")]
+    pub speed: f64,
+}
+
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct ControlSummary {
+    #[prost(uint32, tag = "1")]
+    pub accepted: u32,
+    #[prost(uint32, tag = "2")]
+    pub rejected: u32,
+    #[prost(string, tag = "3")]
+    pub error: String,
+}
@@ -0,0 +1,225 @@
+use serde::{Serialize, Serializer};
+
+use crate::clock::Stamp;
+use crate::sensors::reader::{AnalogData, BaroData, EncoderData, GpsData, ImuData, MagData, PowerData, RangeData, SatellitesData};
+
+/// Longueur maximale du nom de l'opérateur (octets)
+const OPERATOR_LEN: usize = 32;
+
+/// Technologie d'accès au réseau mobile
+#[derive(Clone, Copy, Debug, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AccessTechnology {
+    Gsm,
+    Gprs,
+    Edge,
+    Umts,
+    /// HSDPA, HSUPA, HSPA et HSPA+
+    Hspa,
+    /// CDMA2000 (1xRTT, EV-DO)
+    Cdma,
+    Lte,
+    /// 5G NR
+    Nr,
+}
+
+impl AccessTechnology {
+    pub fn name(&self) -> &'static str {
+        match self {
+            AccessTechnology::Gsm => "gsm",
+            AccessTechnology::Gprs => "gprs",
+            AccessTechnology::Edge => "edge",
+            AccessTechnology::Umts => "umts",
+            AccessTechnology::Hspa => "hspa",
+            AccessTechnology::Cdma => "cdma",
+            AccessTechnology::Lte => "lte",
+            AccessTechnology::Nr => "nr",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        [
+            AccessTechnology::Gsm,
+            AccessTechnology::Gprs,
+            AccessTechnology::Edge,
+            AccessTechnology::Umts,
+            AccessTechnology::Hspa,
+            AccessTechnology::Cdma,
+            AccessTechnology::Lte,
+            AccessTechnology::Nr,
+        ]
+        .into_iter()
+        .find(|technology| technology.name() == name)
+    }
+}
+
+/// Enregistrement auprès du réseau mobile
+#[derive(Clone, Copy, Debug, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Registration {
+    /// Non enregistré, aucune recherche en cours
+    Idle,
+    /// Réseau de l'opérateur
+    Home,
+    /// Recherche d'un réseau
+    Searching,
+    /// Enregistrement refusé
+    Denied,
+    /// Réseau d'un autre opérateur
+    Roaming,
+    /// Appels d'urgence uniquement
+    Emergency,
+}
+
+impl Registration {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Registration::Idle => "idle",
+            Registration::Home => "home",
+            Registration::Searching => "searching",
+            Registration::Denied => "denied",
+            Registration::Roaming => "roaming",
+            Registration::Emergency => "emergency",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        [
+            Registration::Idle,
+            Registration::Home,
+            Registration::Searching,
+            Registration::Denied,
+            Registration::Roaming,
+            Registration::Emergency,
+        ]
+        .into_iter()
+        .find(|registration| registration.name() == name)
+    }
+}
+
+/// Nom de l'opérateur, stocké sur place pour que l'échantillon reste copiable sans allocation
+#[derive(Clone, Copy, PartialEq)]
+pub struct Operator {
+    name: [u8; OPERATOR_LEN],
+    len: u8,
+}
+
+impl Operator {
+    /// Nom tronqué à OPERATOR_LEN octets (sur une limite de caractère), None: nom vide
+    pub fn new(name: &str) -> Option<Self> {
+        let name = name.trim();
+        let mut len = name.len().min(OPERATOR_LEN);
+        while !name.is_char_boundary(len) {
+            len -= 1;
+        }
+        if len == 0 {
+            return None;
+        }
+
+        let mut operator = Self {
+            name: [0; OPERATOR_LEN],
+            len: len as u8,
+        };
+        operator.name[..len].copy_from_slice(&name.as_bytes()[..len]);
+        Some(operator)
+    }
+
+    pub fn as_str(&self) -> &str {
+        std::str::from_utf8(&self.name[..self.len as usize]).unwrap_or_default()
+    }
+}
+
+impl std::fmt::Debug for Operator {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.as_str().fmt(f)
+    }
+}
+
+impl Serialize for Operator {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(self.as_str())
+    }
+}
+
+/// Etat du modem 4G (ModemManager), None: information non fournie par le modem
+#[derive(Clone, Copy, Debug, Default, PartialEq, Serialize)]
+pub struct ModemStatus {
+    /// Qualité du signal (%)
+    pub quality: u32,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub technology: Option<AccessTechnology>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub registration: Option<Registration>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub operator: Option<Operator>,
+    /// Puissance reçue des signaux de référence, RSRP (dBm)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub rsrp: Option<f64>,
+    /// Qualité reçue des signaux de référence, RSRQ (dB)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub rsrq: Option<f64>,
+    /// Rapport signal sur bruit et interférences, SINR (dB)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub sinr: Option<f64>,
+}
+
+#[derive(Clone, Copy, Default, Serialize)]
+pub struct ModemData {
+    #[serde(flatten)]
+    pub status: ModemStatus,
+    pub stamp: Stamp,
+}
+
+/// Echantillon diffusé aux sorties locales, tel que transmis à la base
+#[derive(Clone, Copy, Serialize)]
+#[serde(untagged)]
+pub enum Record {
+    Imu(ImuData),
+    Mag(MagData),
+    Analog(AnalogData),
+    Gps(GpsData),
+    Modem(ModemData),
+    Satellites(SatellitesData),
+    Power(PowerData),
+    Baro(BaroData),
+    Encoder(EncoderData),
+    Range(RangeData),
+}
+
+impl Record {
+    /// Types d'échantillons diffusés
+    pub const KINDS: [&'static str; 10] = [
+        "imu", "mag", "analog", "gps", "modem", "satellites", "power", "baro", "encoder", "range",
+    ];
+
+    /// Type d'échantillon
+    pub fn kind(&self) -> &'static str {
+        match self {
+            Record::Imu(_) => "imu",
+            Record::Mag(_) => "mag",
+            Record::Analog(_) => "analog",
+            Record::Gps(_) => "gps",
+            Record::Modem(_) => "modem",
+            Record::Satellites(_) => "satellites",
+            Record::Power(_) => "power",
+            Record::Baro(_) => "baro",
+            Record::Encoder(_) => "encoder",
+            Record::Range(_) => "range",
+        }
+    }
+
+    pub fn stamp(&self) -> Stamp {
+        match self {
+            Record::Imu(data) => data.stamp,
+            Record::Mag(data) => data.stamp,
+            Record::Analog(data) => data.stamp,
+            Record::Gps(data) => data.stamp,
+            Record::Modem(data) => data.stamp,
+            Record::Satellites(data) => data.stamp,
+            Record::Power(data) => data.stamp,
+            Record::Baro(data) => data.stamp,
+            Record::Encoder(data) => data.stamp,
+            Record::Range(data) => data.stamp,
+        }
+    }
+}
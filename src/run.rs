@@ -0,0 +1,108 @@
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use serde::Serialize;
+
+/// Fichier contenant le PID du processus en cours
+const PID_FILE: &str = "rc-telemetrie.pid";
+/// Marqueur présent pendant l'exécution, supprimé uniquement lors d'un arrêt propre
+const RUNNING_MARKER: &str = "running";
+/// Nombre d'arrêts non propres détectés depuis l'installation
+const CRASH_COUNTER: &str = "crashes";
+
+/// Etat de l'exécution, publié au démarrage
+#[derive(Clone, Debug, Serialize)]
+pub struct RunState {
+    /// Identifiant de l'exécution
+    pub id: String,
+    pub pid: u32,
+    /// Nombre d'arrêts non propres
+    pub crashes: u64,
+    /// Exécution précédente, si elle ne s'est pas arrêtée proprement
+    pub unclean: Option<String>,
+}
+
+/// Dossier d'état de l'exécution: fichier PID, marqueur d'exécution et compteur de crashs
+pub struct RunDir {
+    directory: PathBuf,
+    pub state: RunState,
+}
+
+impl RunDir {
+    /// Prépare le dossier, détecte un arrêt non propre de l'exécution précédente.
+    /// Refuse de démarrer uniquement si une autre instance est encore en cours.
+    pub fn open(directory: &Path) -> anyhow::Result<Self> {
+        std::fs::create_dir_all(directory)?;
+
+        let pid = std::process::id();
+        let pid_path = directory.join(PID_FILE);
+        if let Ok(content) = std::fs::read_to_string(&pid_path) {
+            match content.trim().parse::<u32>() {
+                Ok(previous) if previous != pid && alive(previous) => {
+                    return Err(anyhow::anyhow!(
+                        "Une autre instance est en cours (PID {}, {})",
+                        previous,
+                        pid_path.display()
+                    ));
+                }
+                Ok(previous) => tracing::info!(target: "run", "Fichier PID obsolète (PID {}), remplacé.", previous),
+                Err(_) => tracing::info!(target: "run", "Fichier PID invalide, remplacé."),
+            }
+        }
+
+        let mut crashes = std::fs::read_to_string(directory.join(CRASH_COUNTER))
+            .ok()
+            .and_then(|content| content.trim().parse::<u64>().ok())
+            .unwrap_or(0);
+
+        // Le marqueur contient l'identifiant de l'exécution qui l'a créé
+        let marker = directory.join(RUNNING_MARKER);
+        let unclean = std::fs::read_to_string(&marker)
+            .ok()
+            .map(|content| content.trim().to_string());
+        if let Some(previous) = unclean.as_ref() {
+            crashes += 1;
+            std::fs::write(directory.join(CRASH_COUNTER), crashes.to_string())?;
+            tracing::info!(target: "run", "Arrêt non propre de l'exécution {} ({} au total).", previous, crashes);
+        }
+
+        let now = SystemTime::now().duration_since(UNIX_EPOCH)?.as_secs();
+        let id = format!("run-{}", now);
+
+        std::fs::write(&pid_path, pid.to_string())?;
+        std::fs::write(&marker, &id)?;
+
+        tracing::info!(target: "run", "Exécution {} (PID {}), dossier {}", id, pid, directory.display());
+
+        Ok(Self {
+            directory: directory.to_path_buf(),
+            state: RunState {
+                id,
+                pid,
+                crashes,
+                unclean,
+            },
+        })
+    }
+
+    /// Arrêt propre: supprime le marqueur d'exécution et le fichier PID
+    pub fn shutdown(self) {
+        for name in [RUNNING_MARKER, PID_FILE] {
+            if let Err(e) = std::fs::remove_file(self.directory.join(name)) {
+                tracing::error!(target: "run", "Impossible de supprimer {}: {}", name, e);
+            }
+        }
+    }
+}
+
+/// Vérifie si un processus existe encore
+#[cfg(target_os = "linux")]
+fn alive(pid: u32) -> bool {
+    Path::new("/proc").join(pid.to_string()).exists()
+}
+
+/// Sans /proc, un ancien PID est considéré comme obsolète
+#[cfg(not(target_os = "linux"))]
+fn alive(_pid: u32) -> bool {
+    false
+}
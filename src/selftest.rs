@@ -0,0 +1,151 @@
+use std::sync::{Arc, Mutex};
+
+use serde::{Deserialize, Serialize};
+
+use crate::sensors::reader::SensorStatus;
+
+/// Vérifications attendues lors du test de démarrage
+pub const EXPECTED: &[&str] = &[
+    "config",
+    "db",
+    "db.schema",
+    "i2c.bus",
+    "i2c.scan",
+    "imu.whoami",
+    "mag.whoami",
+    "imu.init",
+    "mag.init",
+    "analog.init",
+    "power.init",
+    "baro.init",
+    "encoder.init",
+    "range.init",
+    "gps.init",
+    "imu.sample",
+    "mag.sample",
+    "analog.sample",
+    "power.sample",
+    "baro.sample",
+    "encoder.sample",
+    "range.sample",
+    "gps.sample",
+    "motor.neutral",
+    "steering.neutral",
+    "modem",
+];
+
+/// Résultat d'une vérification
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Outcome {
+    Pass,
+    Fail,
+    Skip,
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+pub struct Check {
+    pub name: String,
+    pub outcome: Outcome,
+    pub details: String,
+}
+
+/// Rapport complet du test de démarrage
+#[derive(Clone, Serialize, Deserialize)]
+pub struct Report {
+    pub passed: bool,
+    pub checks: Vec<Check>,
+}
+
+/// Collecte des vérifications effectuées au démarrage par les différentes tâches
+#[derive(Clone, Default)]
+pub struct SelfTest {
+    checks: Arc<Mutex<Vec<Check>>>,
+}
+
+impl SelfTest {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Enregistre le résultat d'une vérification (remplace un résultat précédent du même nom)
+    pub fn record(&self, name: &str, result: anyhow::Result<String>) {
+        let (outcome, details) = match result {
+            Ok(details) => (Outcome::Pass, details),
+            Err(e) => (Outcome::Fail, e.to_string()),
+        };
+
+        self.push(name, outcome, details);
+    }
+
+    /// Enregistre une vérification non effectuée
+    pub fn skip(&self, name: &str, reason: &str) {
+        self.push(name, Outcome::Skip, reason.to_string());
+    }
+
+    /// Enregistre le résultat de la première tentative d'initialisation d'un capteur
+    pub fn record_init(&self, name: &str, status: &SensorStatus) {
+        if status.attempts == 0 || self.contains(name) {
+            return;
+        }
+
+        match &status.error {
+            Some(e) => self.record(name, Err(anyhow::anyhow!(e.clone()))),
+            None => self.record(name, Ok("Capteur disponible".to_string())),
+        }
+    }
+
+    /// Vérifie si une vérification a déjà été enregistrée
+    pub fn contains(&self, name: &str) -> bool {
+        self.checks.lock().unwrap().iter().any(|c| c.name == name)
+    }
+
+    /// Résultat d'une vérification, None: pas encore enregistrée
+    pub fn outcome(&self, name: &str) -> Option<Outcome> {
+        self.checks.lock().unwrap().iter().find(|c| c.name == name).map(|c| c.outcome)
+    }
+
+    fn push(&self, name: &str, outcome: Outcome, details: String) {
+        let mut checks = self.checks.lock().unwrap();
+        checks.retain(|c| c.name != name);
+        checks.push(Check {
+            name: name.to_string(),
+            outcome,
+            details,
+        });
+    }
+
+    /// Termine le test: les vérifications attendues sans résultat sont ignorées
+    pub fn report(&self, expected: &[&str]) -> Report {
+        for name in expected {
+            if !self.contains(name) {
+                self.skip(name, "Aucun résultat avant la fin du test");
+            }
+        }
+
+        let checks = self.checks.lock().unwrap().clone();
+        let passed = checks.iter().all(|c| c.outcome != Outcome::Fail);
+
+        Report { passed, checks }
+    }
+}
+
+impl Report {
+    /// Affiche le rapport dans les logs
+    pub fn print(&self) {
+        for check in self.checks.iter() {
+            let outcome = match check.outcome {
+                Outcome::Pass => "OK",
+                Outcome::Fail => "ECHEC",
+                Outcome::Skip => "IGNORE",
+            };
+            tracing::info!(target: "selftest", "{:<16} {:<6} {}", check.name, outcome, check.details);
+        }
+
+        if self.passed {
+            tracing::info!(target: "selftest", "Résultat: OK");
+        } else {
+            tracing::info!(target: "selftest", "Résultat: ECHEC");
+        }
+    }
+}
@@ -12,13 +12,13 @@ use tokio_stream::Stream;
 
 use crate::sensors::analog::registry;
 
-pub(crate) struct Analog {}
+pub struct Analog {}
 
 // Voir documentation : https://www.ti.com/lit/ds/symlink/ads1118.pdf
 
 impl Analog {
     /// Constructeur
-    pub(crate) fn new(i2c: &mut I2c) -> anyhow::Result<Self> {        
+    pub fn new(i2c: &mut I2c) -> anyhow::Result<Self> {        
         // Créer l'objet et commence l'initialisation
         let mut analog = Analog {};
 
@@ -35,7 +35,7 @@ impl Analog {
 
     // Permet l'initialisation du module avec les valeurs demandées
     fn init(&mut self, i2c: &mut I2c) -> anyhow::Result<()> {
-        println!("[ANALOG] Initialisation ...");
+        tracing::info!(target: "analog", "Initialisation ...");
         self.reset(i2c)?;
         self.set_datarate(i2c, registry::ADS1115_CONFIG_DR_128_VAL);
         self.set_mode(i2c, true);
@@ -120,7 +120,7 @@ impl Analog {
                 return Ok((0.256 * 2.0) / 2.0_f32.powf(16.0));
             }
             default => {
-                println!("[ANALOG] Gain inconnu, défini à 1 par défaut.");
+                tracing::info!(target: "analog", "Gain inconnu, défini à 1 par défaut.");
                 return Ok(1.0);
             }
         }
@@ -169,7 +169,7 @@ impl Analog {
     }
 
     /// Récupére la valeur de la batterie
-    pub(crate) fn get_battery(&mut self, i2c: &mut I2c) -> anyhow::Result<f32> {
+    pub fn get_battery(&mut self, i2c: &mut I2c) -> anyhow::Result<f32> {
         self.set_slave(i2c)?;
         self.get_voltage(
             i2c,
@@ -177,4 +177,29 @@ impl Analog {
             registry::ADS1115_CONFIG_PGA_FSR_4_096_VAL,
         )
     }
+
+    /// Récupère la tension d'une entrée (0 à 3) par rapport à la masse
+    fn get_input(&mut self, i2c: &mut I2c, input: u8) -> anyhow::Result<f32> {
+        let mux = match input {
+            0 => registry::ADS1115_CONFIG_MUX_AIN0_GND_VAL,
+            1 => registry::ADS1115_CONFIG_MUX_AIN1_GND_VAL,
+            2 => registry::ADS1115_CONFIG_MUX_AIN2_GND_VAL,
+            _ => registry::ADS1115_CONFIG_MUX_AIN3_GND_VAL,
+        };
+
+        self.set_slave(i2c)?;
+        let voltage = self.get_voltage(i2c, mux, registry::ADS1115_CONFIG_PGA_FSR_4_096_VAL)?;
+        // get_voltage applique le gain du pont de la batterie
+        Ok(voltage / registry::ANALOG_BATT_GAIN)
+    }
+
+    /// Récupère la tension d'une prise d'équilibrage, avant le pont diviseur de rapport `ratio`
+    pub fn get_tap(&mut self, i2c: &mut I2c, input: u8, ratio: f32) -> anyhow::Result<f32> {
+        Ok(self.get_input(i2c, input)? * ratio)
+    }
+
+    /// Récupère le courant d'un capteur de courant (A)
+    pub fn get_current(&mut self, i2c: &mut I2c, input: u8, zero_v: f32, volts_per_a: f32) -> anyhow::Result<f32> {
+        Ok((self.get_input(i2c, input)? - zero_v) / volts_per_a)
+    }
 }
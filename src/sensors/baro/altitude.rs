@@ -0,0 +1,42 @@
+/// Altitude relative (m) pour une pression `pressure` et une pression de référence
+/// `reference` (Pa), formule internationale du nivellement barométrique
+pub fn altitude(pressure: f64, reference: f64) -> f64 {
+    44330.0 * (1.0 - (pressure / reference).powf(1.0 / 5.255))
+}
+
+/// Mise à zéro de l'altitude au démarrage: la pression de référence est la moyenne des
+/// premiers échantillons, l'altitude reste à 0 jusqu'à la fin de la mise à zéro
+pub struct Zero {
+    samples: u32,
+    count: u32,
+    sum: f64,
+    reference: Option<f64>,
+}
+
+impl Zero {
+    /// `samples`: nombre d'échantillons moyennés (au moins 1)
+    pub fn new(samples: u32) -> Self {
+        Self {
+            samples: samples.max(1),
+            count: 0,
+            sum: 0.0,
+            reference: None,
+        }
+    }
+
+    /// Altitude relative (m) d'un nouvel échantillon de pression (Pa)
+    pub fn altitude(&mut self, pressure: f64) -> f32 {
+        let Some(reference) = self.reference else {
+            self.sum += pressure;
+            self.count += 1;
+            if self.count >= self.samples {
+                let reference = self.sum / self.count as f64;
+                tracing::info!(target: "baro", "Altitude mise à zéro ({:.0} Pa)", reference);
+                self.reference = Some(reference);
+            }
+            return 0.0;
+        };
+
+        altitude(pressure, reference) as f32
+    }
+}
@@ -0,0 +1,93 @@
+use rppal::i2c::I2c;
+use std::thread::sleep;
+use std::time::Duration;
+
+use anyhow::anyhow;
+
+use crate::i2c::I2CBit;
+use crate::sensors::baro::compensation::{self, Calibration, Measure};
+use crate::sensors::baro::registry;
+
+// Voir documentation : https://www.bosch-sensortec.com/media/boschsensortec/downloads/datasheets/bst-bme280-ds002.pdf
+
+pub struct Bme280 {
+    address: u16,
+    calibration: Calibration,
+}
+
+impl Bme280 {
+    /// Constructeur, BMP280 ou BME280 selon l'identifiant du capteur
+    pub fn new(i2c: &mut I2c, address: u16) -> anyhow::Result<Self> {
+        i2c.set_slave_address(address)?;
+        let humidity = match i2c.lecture_word(registry::BME280_CHIP_ID)? {
+            registry::BMP280_CHIP_ID_VAL => false,
+            registry::BME280_CHIP_ID_VAL => true,
+            id => return Err(anyhow!("Identifiant inattendu: {:#04x}", id)),
+        };
+
+        tracing::info!(target: "baro", "Initialisation ({}) ...", if humidity { "BME280" } else { "BMP280" });
+        i2c.ecriture_word(registry::BME280_RESET, registry::BME280_RESET_VAL)?;
+        sleep(Duration::from_millis(2));
+        // Copie des coefficients en cours après la réinitialisation
+        for _ in 0..10 {
+            if !i2c.lecture_bit8(registry::BME280_STATUS, registry::BME280_STATUS_IM_UPDATE_BIT)? {
+                break;
+            }
+            sleep(Duration::from_millis(1));
+        }
+
+        let baro = Bme280 {
+            address,
+            calibration: Self::calibration(i2c, humidity)?,
+        };
+
+        // ctrl_hum n'est pris en compte qu'après l'écriture de ctrl_meas
+        if humidity {
+            i2c.ecriture_word(registry::BME280_CTRL_HUM, registry::BME280_CTRL_HUM_VAL)?;
+        }
+        i2c.ecriture_word(registry::BME280_CONFIG, registry::BME280_CONFIG_VAL)?;
+        i2c.ecriture_word(registry::BME280_CTRL_MEAS, registry::BME280_CTRL_MEAS_VAL)?;
+
+        Ok(baro)
+    }
+
+    /// Coefficients de compensation enregistrés en usine
+    fn calibration(i2c: &mut I2c, humidity: bool) -> anyhow::Result<Calibration> {
+        let mut tp = [0u8; 24];
+        i2c.block_read(registry::BME280_CALIB_TP, &mut tp)?;
+
+        let mut h = [0u8; 7];
+        let humidity = if humidity {
+            let h1 = i2c.lecture_word(registry::BME280_CALIB_H1)?;
+            i2c.block_read(registry::BME280_CALIB_H2, &mut h)?;
+            Some((h1, &h))
+        } else {
+            None
+        };
+
+        Ok(Calibration::from_registers(&tp, humidity))
+    }
+
+    /// Capteur d'humidité présent (BME280)
+    pub fn humidity(&self) -> bool {
+        self.calibration.humidity.is_some()
+    }
+
+    /// Récupère la pression, la température et l'humidité (BME280)
+    pub fn get_measure(&self, i2c: &mut I2c) -> anyhow::Result<Measure> {
+        i2c.set_slave_address(self.address)?;
+
+        // Lecture groupée: les registres d'une même mesure restent cohérents
+        let mut data = [0u8; 8];
+        let len = if self.humidity() { 8 } else { 6 };
+        i2c.block_read(registry::BME280_DATA, &mut data[..len])?;
+
+        let (adc_p, adc_t, adc_h) = compensation::raw_sample(&data);
+        if adc_p == 0x80000 || adc_t == 0x80000 {
+            // Valeur de réinitialisation: mesure pas encore disponible
+            return Err(anyhow!("Mesure indisponible"));
+        }
+
+        Ok(self.calibration.compensate(adc_p, adc_t, self.humidity().then_some(adc_h)))
+    }
+}
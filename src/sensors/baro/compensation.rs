@@ -0,0 +1,126 @@
+// Compensation Bosch (BMP280, BME280), formules en virgule flottante des fiches techniques
+// (section 8.1 pour le BMP280, 4.2.3 pour le BME280).
+
+/// Coefficients de compensation lus dans le capteur
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct Calibration {
+    pub t1: u16,
+    pub t2: i16,
+    pub t3: i16,
+    pub p1: u16,
+    pub p2: i16,
+    pub p3: i16,
+    pub p4: i16,
+    pub p5: i16,
+    pub p6: i16,
+    pub p7: i16,
+    pub p8: i16,
+    pub p9: i16,
+    /// Coefficients de l'humidité, None: BMP280 (pas d'humidité)
+    pub humidity: Option<HumidityCalibration>,
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct HumidityCalibration {
+    pub h1: u8,
+    pub h2: i16,
+    pub h3: u8,
+    pub h4: i16,
+    pub h5: i16,
+    pub h6: i8,
+}
+
+/// Mesure compensée
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Measure {
+    /// Pression (Pa)
+    pub pressure: f64,
+    /// Température (°C)
+    pub temperature: f64,
+    /// Humidité relative (%), None: BMP280
+    pub humidity: Option<f64>,
+}
+
+impl Calibration {
+    /// Coefficients des registres 0x88 à 0x9F, puis 0xA1 et 0xE1 à 0xE7 pour un BME280
+    pub fn from_registers(tp: &[u8; 24], humidity: Option<(u8, &[u8; 7])>) -> Self {
+        let u16_at = |i: usize| u16::from_le_bytes([tp[i], tp[i + 1]]);
+        let i16_at = |i: usize| i16::from_le_bytes([tp[i], tp[i + 1]]);
+
+        Self {
+            t1: u16_at(0),
+            t2: i16_at(2),
+            t3: i16_at(4),
+            p1: u16_at(6),
+            p2: i16_at(8),
+            p3: i16_at(10),
+            p4: i16_at(12),
+            p5: i16_at(14),
+            p6: i16_at(16),
+            p7: i16_at(18),
+            p8: i16_at(20),
+            p9: i16_at(22),
+            humidity: humidity.map(|(h1, h)| HumidityCalibration {
+                h1,
+                h2: i16::from_le_bytes([h[0], h[1]]),
+                h3: h[2],
+                // Valeurs sur 12 bits partageant l'octet 0xE5
+                h4: ((h[3] as i8 as i16) << 4) | (h[4] & 0x0F) as i16,
+                h5: ((h[5] as i8 as i16) << 4) | (h[4] >> 4) as i16,
+                h6: h[6] as i8,
+            }),
+        }
+    }
+
+    /// Mesure compensée des valeurs brutes (pression et température sur 20 bits, humidité sur 16)
+    pub fn compensate(&self, adc_p: i32, adc_t: i32, adc_h: Option<i32>) -> Measure {
+        let (adc_p, adc_t) = (adc_p as f64, adc_t as f64);
+
+        let var1 = (adc_t / 16384.0 - self.t1 as f64 / 1024.0) * self.t2 as f64;
+        let var2 = (adc_t / 131072.0 - self.t1 as f64 / 8192.0).powi(2) * self.t3 as f64;
+        let t_fine = var1 + var2;
+
+        Measure {
+            pressure: self.pressure(adc_p, t_fine),
+            temperature: t_fine / 5120.0,
+            humidity: self.humidity.zip(adc_h).map(|(h, adc_h)| h.compensate(adc_h as f64, t_fine)),
+        }
+    }
+
+    fn pressure(&self, adc_p: f64, t_fine: f64) -> f64 {
+        let mut var1 = t_fine / 2.0 - 64000.0;
+        let mut var2 = var1 * var1 * self.p6 as f64 / 32768.0;
+        var2 += var1 * self.p5 as f64 * 2.0;
+        var2 = var2 / 4.0 + self.p4 as f64 * 65536.0;
+        var1 = (self.p3 as f64 * var1 * var1 / 524288.0 + self.p2 as f64 * var1) / 524288.0;
+        var1 = (1.0 + var1 / 32768.0) * self.p1 as f64;
+        if var1 == 0.0 {
+            // Coefficients invalides: évite la division par zéro
+            return 0.0;
+        }
+
+        let mut p = 1048576.0 - adc_p;
+        p = (p - var2 / 4096.0) * 6250.0 / var1;
+        let var1 = self.p9 as f64 * p * p / 2147483648.0;
+        let var2 = p * self.p8 as f64 / 32768.0;
+        p + (var1 + var2 + self.p7 as f64) / 16.0
+    }
+}
+
+impl HumidityCalibration {
+    fn compensate(&self, adc_h: f64, t_fine: f64) -> f64 {
+        let var = t_fine - 76800.0;
+        let var = (adc_h - (self.h4 as f64 * 64.0 + self.h5 as f64 / 16384.0 * var))
+            * (self.h2 as f64 / 65536.0
+                * (1.0 + self.h6 as f64 / 67108864.0 * var * (1.0 + self.h3 as f64 / 67108864.0 * var)));
+        (var * (1.0 - self.h1 as f64 * var / 524288.0)).clamp(0.0, 100.0)
+    }
+}
+
+/// Valeurs brutes des registres 0xF7 à 0xFE: pression, température et humidité
+pub fn raw_sample(data: &[u8; 8]) -> (i32, i32, i32) {
+    let adc_p = (data[0] as i32) << 12 | (data[1] as i32) << 4 | (data[2] as i32) >> 4;
+    let adc_t = (data[3] as i32) << 12 | (data[4] as i32) << 4 | (data[5] as i32) >> 4;
+    let adc_h = (data[6] as i32) << 8 | data[7] as i32;
+    (adc_p, adc_t, adc_h)
+}
@@ -0,0 +1,8 @@
+pub mod altitude;
+pub mod compensation;
+
+#[cfg(feature = "real-sensors")]
+mod registry;
+
+#[cfg(feature = "real-sensors")]
+pub mod bme280;
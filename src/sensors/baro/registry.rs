@@ -0,0 +1,33 @@
+#![allow(unused)]
+
+pub const BME280_CHIP_ID: u8 = 0xD0;
+pub const BME280_RESET: u8 = 0xE0;
+pub const BME280_CTRL_HUM: u8 = 0xF2;
+pub const BME280_STATUS: u8 = 0xF3;
+pub const BME280_CTRL_MEAS: u8 = 0xF4;
+pub const BME280_CONFIG: u8 = 0xF5;
+
+/// Mesures: pression (3 octets), température (3 octets), humidité (2 octets, BME280)
+pub const BME280_DATA: u8 = 0xF7;
+
+/// Coefficients de la température et de la pression (0x88 à 0x9F)
+pub const BME280_CALIB_TP: u8 = 0x88;
+/// Premier coefficient de l'humidité (BME280)
+pub const BME280_CALIB_H1: u8 = 0xA1;
+/// Coefficients suivants de l'humidité (0xE1 à 0xE7, BME280)
+pub const BME280_CALIB_H2: u8 = 0xE1;
+
+pub const BMP280_CHIP_ID_VAL: u8 = 0x58;
+pub const BME280_CHIP_ID_VAL: u8 = 0x60;
+
+pub const BME280_RESET_VAL: u8 = 0xB6;
+
+/// Sur-échantillonnage de l'humidité x1
+pub const BME280_CTRL_HUM_VAL: u8 = 0b001;
+/// Température x2 (010), pression x16 (101), mode normal (11)
+pub const BME280_CTRL_MEAS_VAL: u8 = 0x57;
+/// Attente de 0.5 ms entre deux mesures (000), filtre IIR x16 (100)
+pub const BME280_CONFIG_VAL: u8 = 0x10;
+
+/// Bit du registre d'état: copie des coefficients en cours
+pub const BME280_STATUS_IM_UPDATE_BIT: u8 = 0;
@@ -0,0 +1,156 @@
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+
+/// Message CAN à décoder (équivalent d'une entrée BO_ d'un fichier DBC)
+#[derive(Clone, Default, Deserialize, Serialize)]
+#[serde(default)]
+pub struct CanMessage {
+    /// Identifiant (11 ou 29 bits)
+    pub id: u32,
+    pub name: String,
+    pub signals: Vec<CanSignal>,
+}
+
+/// Signal d'un message (équivalent d'une entrée SG_): valeur = brut * scale + offset
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(default)]
+pub struct CanSignal {
+    pub name: String,
+    /// Bit de départ, numérotation DBC (bit de poids faible en little-endian, fort en big-endian)
+    pub start_bit: u16,
+    /// Longueur en bits (1 à 64)
+    pub length: u8,
+    pub scale: f64,
+    pub offset: f64,
+    /// Valeur brute signée (complément à deux)
+    pub signed: bool,
+    /// Ordre Motorola (big-endian), sinon Intel (little-endian)
+    pub big_endian: bool,
+}
+
+impl Default for CanSignal {
+    fn default() -> Self {
+        Self {
+            name: String::new(),
+            start_bit: 0,
+            length: 8,
+            scale: 1.0,
+            offset: 0.0,
+            signed: false,
+            big_endian: false,
+        }
+    }
+}
+
+impl CanSignal {
+    /// Vérifie que le signal tient dans une trame CAN (8 octets)
+    pub fn validate(&self) -> anyhow::Result<()> {
+        if self.name.is_empty() {
+            return Err(anyhow::anyhow!("signal sans nom"));
+        }
+
+        if !(1..=64).contains(&self.length) || self.start_bit >= 64 {
+            return Err(anyhow::anyhow!(
+                "{}: bit de départ {} ou longueur {} invalide",
+                self.name,
+                self.start_bit,
+                self.length
+            ));
+        }
+
+        if self.bits(8).is_none() {
+            return Err(anyhow::anyhow!("{}: dépasse les 8 octets de la trame", self.name));
+        }
+
+        Ok(())
+    }
+
+    /// Positions des bits du signal, du poids fort au poids faible (None si hors de `len` octets
+    /// ou longueur invalide)
+    fn bits(&self, len: usize) -> Option<Vec<usize>> {
+        // Signal non validé (ex: longueur nulle): aucune valeur plutôt qu'un débordement
+        if !(1..=64).contains(&self.length) {
+            return None;
+        }
+
+        let length = self.length as usize;
+        let mut bits = Vec::with_capacity(length);
+
+        if self.big_endian {
+            // Motorola: départ sur le bit de poids fort, puis bits décroissants dans l'octet,
+            // et passage au bit 7 de l'octet suivant
+            let mut position = self.start_bit as usize;
+            for i in 0..length {
+                if position >= len * 8 {
+                    return None;
+                }
+                bits.push(position);
+
+                if i + 1 < length {
+                    position = match position % 8 {
+                        0 => position + 15,
+                        _ => position - 1,
+                    };
+                }
+            }
+        } else {
+            // Intel: départ sur le bit de poids faible, bits croissants
+            let position = self.start_bit as usize;
+            if position + length > len * 8 {
+                return None;
+            }
+            bits.extend((position..position + length).rev());
+        }
+
+        Some(bits)
+    }
+
+    /// Valeur brute du signal (None si la trame est trop courte)
+    pub fn raw(&self, data: &[u8]) -> Option<i128> {
+        let bits = self.bits(data.len())?;
+        let raw = bits.iter().fold(0u64, |raw, bit| {
+            (raw << 1) | ((data[bit / 8] >> (bit % 8)) & 1) as u64
+        });
+
+        let length = self.length as u32;
+        if self.signed && length < 64 && raw & (1 << (length - 1)) != 0 {
+            Some(raw as i128 - (1i128 << length))
+        } else if self.signed && length == 64 {
+            Some(raw as i64 as i128)
+        } else {
+            Some(raw as i128)
+        }
+    }
+
+    /// Valeur physique du signal (None si la trame est trop courte)
+    pub fn decode(&self, data: &[u8]) -> Option<f64> {
+        self.raw(data).map(|raw| raw as f64 * self.scale + self.offset)
+    }
+}
+
+/// Décodage des trames selon la table des messages
+pub struct Decoder {
+    messages: BTreeMap<u32, CanMessage>,
+}
+
+impl Decoder {
+    pub fn new(messages: &[CanMessage]) -> Self {
+        Self {
+            messages: messages.iter().map(|message| (message.id, message.clone())).collect(),
+        }
+    }
+
+    /// Décode une trame: nom du message et valeur des signaux présents dans la trame.
+    /// None si l'identifiant n'est pas dans la table.
+    pub fn decode(&self, id: u32, data: &[u8]) -> Option<(&str, BTreeMap<String, f64>)> {
+        let message = self.messages.get(&id)?;
+        let signals = message
+            .signals
+            .iter()
+            .filter_map(|signal| signal.decode(data).map(|value| (signal.name.clone(), value)))
+            .collect();
+
+        Some((&message.name, signals))
+    }
+}
@@ -0,0 +1,164 @@
+use std::collections::BTreeMap;
+use std::time::{Duration, Instant};
+
+use serde::Serialize;
+use tokio_util::sync::CancellationToken;
+
+use crate::clock::{Clock, Stamp};
+use crate::config::CanConfig;
+use crate::writer::{Event, Writer};
+
+pub mod decode;
+#[cfg(target_os = "linux")]
+mod socket;
+
+/// Délai avant une nouvelle ouverture du bus après une erreur
+#[cfg(target_os = "linux")]
+const REOPEN_DELAY: Duration = Duration::from_secs(5);
+
+/// Message CAN décodé
+#[derive(Clone, Serialize)]
+pub struct CanData {
+    pub stamp: Stamp,
+    pub id: u32,
+    pub message: String,
+    pub signals: BTreeMap<String, f64>,
+}
+
+/// Compteurs du bus, publiés périodiquement
+#[derive(Clone, Default, Serialize)]
+pub struct CanStats {
+    pub frames: u64,
+    pub decoded: u64,
+    /// Trames d'identifiant absent de la table
+    pub unknown: u64,
+    /// Trames inconnues par identifiant (hexadécimal)
+    pub unknown_ids: BTreeMap<String, u64>,
+    /// Trames d'erreur du contrôleur
+    pub errors: u64,
+    pub bus_off: u64,
+}
+
+#[cfg(target_os = "linux")]
+impl CanStats {
+    /// Compte une trame inconnue, retourne vrai à la première trame de cet identifiant
+    fn count_unknown(&mut self, id: u32) -> bool {
+        self.unknown += 1;
+        let count = self.unknown_ids.entry(format!("{:#x}", id)).or_insert(0);
+        *count += 1;
+        *count == 1
+    }
+}
+
+/// Lecture du bus CAN: les messages de la table sont décodés et transmis à l'écrivain.
+/// En cas d'erreur, le bus est rouvert jusqu'à l'arrêt du programme.
+#[cfg(target_os = "linux")]
+pub async fn run(config: CanConfig, writer: Writer, clock: Clock, token: CancellationToken) {
+    use socket::{CanSocket, Frame, CAN_ERR_BUSOFF, CAN_ERR_RESTARTED};
+
+    let decoder = decode::Decoder::new(&config.messages);
+    let interval = Duration::from_secs(config.stats_interval_s.max(1));
+    let mut stats = CanStats::default();
+    let mut last_stats = Instant::now();
+    let mut last_error = None;
+
+    while !token.is_cancelled() {
+        let socket = match CanSocket::open(&config.interface) {
+            Ok(socket) => {
+                tracing::info!(target: "can", "Lecture de {} ({} message(s) décodé(s))", config.interface, config.messages.len());
+                last_error = None;
+                socket
+            }
+            Err(e) => {
+                // Une seule fois par erreur différente, le bus est réessayé en boucle
+                let message = format!("Impossible d'ouvrir {}: {}", config.interface, e);
+                if last_error.as_ref() != Some(&message) {
+                    tracing::error!(target: "can", "{}", message);
+                    let _ = writer.event(Event::Can(message.clone(), clock.stamp())).await;
+                    last_error = Some(message);
+                }
+
+                tokio::select! {
+                    _ = token.cancelled() => break,
+                    _ = tokio::time::sleep(REOPEN_DELAY) => continue,
+                }
+            }
+        };
+
+        loop {
+            let frame = tokio::select! {
+                _ = token.cancelled() => break,
+                _ = tokio::time::sleep(interval.saturating_sub(last_stats.elapsed())) => None,
+                frame = socket.read() => Some(frame),
+            };
+
+            match frame {
+                None => {}
+                Some(Ok(Frame::Data { id, data, len })) => {
+                    stats.frames += 1;
+                    match decoder.decode(id, &data[..len]) {
+                        Some((message, signals)) => {
+                            stats.decoded += 1;
+                            writer.can(CanData {
+                                stamp: clock.stamp(),
+                                id,
+                                message: message.to_string(),
+                                signals,
+                            });
+                        }
+                        None => {
+                            if stats.count_unknown(id) {
+                                tracing::info!(target: "can", "Identifiant inconnu: {:#x}", id);
+                            }
+                        }
+                    }
+                }
+                Some(Ok(Frame::Error(class))) => {
+                    stats.errors += 1;
+                    if class & CAN_ERR_BUSOFF != 0 {
+                        stats.bus_off += 1;
+                        let message = format!("Bus {} hors ligne (bus-off)", config.interface);
+                        tracing::error!(target: "can", "{}", message);
+                        let _ = writer.event(Event::Can(message, clock.stamp())).await;
+                    }
+                    if class & CAN_ERR_RESTARTED != 0 {
+                        let message = format!("Bus {} redémarré", config.interface);
+                        tracing::info!(target: "can", "{}", message);
+                        let _ = writer.event(Event::Can(message, clock.stamp())).await;
+                    }
+                }
+                Some(Ok(Frame::Remote)) => stats.frames += 1,
+                Some(Err(e)) => {
+                    let message = format!("Erreur de lecture sur {}: {}", config.interface, e);
+                    tracing::error!(target: "can", "{}", message);
+                    let _ = writer.event(Event::Can(message, clock.stamp())).await;
+                    break;
+                }
+            }
+
+            if last_stats.elapsed() >= interval {
+                last_stats = Instant::now();
+                let _ = writer.event(Event::CanStats(stats.clone(), clock.stamp())).await;
+            }
+        }
+
+        if !token.is_cancelled() {
+            tokio::select! {
+                _ = token.cancelled() => {}
+                _ = tokio::time::sleep(REOPEN_DELAY) => {}
+            }
+        }
+    }
+
+    let _ = writer.event(Event::CanStats(stats, clock.stamp())).await;
+    tracing::info!(target: "can", "Arrêt.");
+}
+
+/// SocketCAN n'existe que sous Linux
+#[cfg(not(target_os = "linux"))]
+pub async fn run(config: CanConfig, writer: Writer, clock: Clock, token: CancellationToken) {
+    let _ = token;
+    let message = format!("SocketCAN indisponible sur ce système, {} ignoré", config.interface);
+    tracing::error!(target: "can", "{}", message);
+    let _ = writer.event(Event::Can(message, clock.stamp())).await;
+}
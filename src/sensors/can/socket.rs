@@ -0,0 +1,51 @@
+use std::io;
+
+use socketcan::{CanFrame, EmbeddedFrame, Frame as _, SocketOptions};
+
+/// Classes des trames d'erreur
+pub use socketcan::errors::{CAN_ERR_BUSOFF, CAN_ERR_RESTARTED};
+
+pub enum Frame {
+    Data { id: u32, data: [u8; 8], len: usize },
+    /// Trame d'erreur du contrôleur, classes d'erreur (CAN_ERR_*)
+    Error(u32),
+    Remote,
+}
+
+/// Socket CAN_RAW non bloquante, lue avec tokio
+pub struct CanSocket {
+    socket: socketcan::tokio::CanSocket,
+}
+
+impl CanSocket {
+    /// Ouvre l'interface (ex: "can0"), reçoit aussi les trames d'erreur
+    pub fn open(interface: &str) -> io::Result<Self> {
+        let socket = socketcan::tokio::CanSocket::open(interface)?;
+        socket.set_error_filter_accept_all()?;
+        Ok(Self { socket })
+    }
+
+    /// Attend la prochaine trame
+    pub async fn read(&self) -> io::Result<Frame> {
+        self.socket.read_frame().await.map(Frame::from)
+    }
+}
+
+impl From<CanFrame> for Frame {
+    fn from(frame: CanFrame) -> Self {
+        match frame {
+            CanFrame::Data(frame) => {
+                let len = frame.data().len().min(8);
+                let mut data = [0; 8];
+                data[..len].copy_from_slice(&frame.data()[..len]);
+                Frame::Data {
+                    id: frame.raw_id(),
+                    data,
+                    len,
+                }
+            }
+            CanFrame::Remote(_) => Frame::Remote,
+            CanFrame::Error(frame) => Frame::Error(frame.error_bits()),
+        }
+    }
+}
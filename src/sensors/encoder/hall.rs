@@ -0,0 +1,34 @@
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+
+use rppal::gpio::{Gpio, InputPin, Trigger};
+
+/// Compteur des impulsions d'un capteur à effet Hall sur une broche GPIO (front montant),
+/// incrémenté par l'interruption de rppal
+pub struct Hall {
+    // Conservée: l'interruption est désactivée quand la broche est libérée
+    _pin: InputPin,
+    count: Arc<AtomicU64>,
+}
+
+impl Hall {
+    /// Constructeur, `pin`: numéro BCM de la broche
+    pub fn new(pin: u8) -> anyhow::Result<Self> {
+        tracing::info!(target: "encoder", "Initialisation (GPIO {}) ...", pin);
+        let count = Arc::new(AtomicU64::new(0));
+
+        // Sortie à collecteur ouvert des capteurs courants: tirage au niveau haut
+        let mut input = Gpio::new()?.get(pin)?.into_input_pullup();
+        let counter = count.clone();
+        input.set_async_interrupt(Trigger::RisingEdge, move |_| {
+            counter.fetch_add(1, Ordering::Relaxed);
+        })?;
+
+        Ok(Self { _pin: input, count })
+    }
+
+    /// Impulsions comptées depuis l'initialisation
+    pub fn count(&self) -> u64 {
+        self.count.load(Ordering::Relaxed)
+    }
+}
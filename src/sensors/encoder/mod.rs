@@ -0,0 +1,4 @@
+pub mod odometry;
+
+#[cfg(feature = "real-sensors")]
+pub mod hall;
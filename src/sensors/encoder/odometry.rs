@@ -0,0 +1,56 @@
+/// Mesure de l'encodeur de roue
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Measure {
+    /// Vitesse sur le dernier intervalle (km/h)
+    pub speed_kmh: f64,
+    /// Distance parcourue depuis le démarrage (m)
+    pub distance_m: f64,
+    /// Impulsions comptées depuis le démarrage
+    pub pulses: u64,
+}
+
+/// Vitesse et distance à partir du compte d'impulsions d'un capteur à effet Hall, calculées à
+/// intervalle fixe: le nombre d'impulsions de chaque intervalle donne la vitesse moyenne
+pub struct Odometry {
+    meters_per_pulse: f64,
+    interval_us: u64,
+    /// Compte au démarrage
+    start: Option<u64>,
+    /// Dernière mesure: horodatage monotone (µs), compte
+    last: Option<(u64, u64)>,
+}
+
+impl Odometry {
+    /// `meters_per_pulse`: circonférence de la roue / impulsions par tour
+    pub fn new(meters_per_pulse: f64, interval_us: u64) -> Self {
+        Self {
+            meters_per_pulse,
+            interval_us,
+            start: None,
+            last: None,
+        }
+    }
+
+    /// Compte d'impulsions lu à l'instant `mono_us` (µs), retourne une mesure par intervalle.
+    /// Le premier compte sert de référence (vitesse et distance nulles).
+    pub fn update(&mut self, mono_us: u64, count: u64) -> Option<Measure> {
+        let start = *self.start.get_or_insert(count);
+
+        let speed = match self.last {
+            None => 0.0,
+            Some((last_us, _)) if mono_us.saturating_sub(last_us) < self.interval_us => return None,
+            Some((last_us, last)) => {
+                let dt = (mono_us - last_us) as f64 / 1e6;
+                count.saturating_sub(last) as f64 * self.meters_per_pulse / dt
+            }
+        };
+        self.last = Some((mono_us, count));
+
+        let pulses = count.saturating_sub(start);
+        Some(Measure {
+            speed_kmh: speed * 3.6,
+            distance_m: pulses as f64 * self.meters_per_pulse,
+            pulses,
+        })
+    }
+}
@@ -1,63 +1,43 @@
+pub mod nmea;
+
+#[cfg(feature = "real-sensors")]
 use nmea_parser::*;
 
 #[cfg(feature = "real-sensors")]
 use rppal::uart::{Parity, Uart};
 
 #[cfg(feature = "real-sensors")]
-use std::path::Path;
+use std::path::{Path, PathBuf};
 
-pub(crate) struct GPS {
+#[cfg(feature = "real-sensors")]
+pub struct GPS {
     uart: Uart,
-    parser: NmeaParser,
-    buffer: Vec<u8>,
+    device: PathBuf,
+    nmea: nmea::Nmea,
 }
 
+#[cfg(feature = "real-sensors")]
 impl GPS {
-    pub(crate) fn new() -> anyhow::Result<Self> {
-            let parser = NmeaParser::new();
-            let path = Path::new("/dev/ttyS0");
-            let uart = Uart::with_path(path, 38400, Parity::None, 8, 1)?;
-            let buffer = Vec::new();
+    pub fn new(device: &Path, baud: u32) -> anyhow::Result<Self> {
+            let uart = Uart::with_path(device, baud, Parity::None, 8, 1)
+                .map_err(|e| anyhow::anyhow!("{}: {}", device.display(), e))?;
+            let nmea = nmea::Nmea::default();
 
-            Ok(GPS { uart, parser, buffer })
+            Ok(GPS { uart, device: device.to_path_buf(), nmea })
     }
 
-    pub(crate) fn read(&mut self) -> anyhow::Result<Option<Vec<ParsedMessage>>> {
+    pub fn read(&mut self) -> anyhow::Result<Option<Vec<ParsedMessage>>> {
         // Lecture des données.
         let current_char = &mut [0;255];
-        match self.uart.read(current_char) {
-            Ok(size) => {
-                if size > 0 {
-                    self.buffer.extend_from_slice(&current_char[0..size]);
-                }
-            },
-            Err(e) => {
-                println!("[GPS] Erreur: {}\n", e);
-            }       
+        let size = self.uart.read(current_char)?;
+
+        // Aucune donnée: le port disparaît si le GPS USB est débranché ou renuméroté
+        if size == 0 && !self.device.exists() {
+            return Err(anyhow::anyhow!("{} absent", self.device.display()));
         }
 
         // Traitement des messages.
-        let mut trames = Vec::new();
-        while self.buffer.contains(&b'\n') {
-            let trame = self.buffer.iter().position(|&x| x == '\n' as u8).map(|idx| {
-                let (left, right) = self.buffer.split_at(idx + 1);
-                let trame = left.to_vec();
-                self.buffer = right.to_vec();
-                trame
-            });
-
-            match trame {
-                Some(v) => {
-                    let trame = String::from_utf8(v).unwrap_or(String::new());
-                    if let Ok(sentence) = self.parser.parse_sentence(trame.as_str()) {
-                        trames.push(sentence);
-                    }
-                },
-                None => {
-                    println!("[GPS] Trame non connue.\n");
-                },
-            }
-        }
+        let trames = self.nmea.push(&current_char[0..size]);
 
         if trames.len() == 0 {
             return Ok(Option::None)
@@ -0,0 +1,194 @@
+use nmea_parser::gnss::GgaQualityIndicator;
+use nmea_parser::{NmeaParser, ParsedMessage};
+
+use crate::clock::Stamp;
+use crate::sensors::reader::{GpsData, SatellitesData, SATELLITES_INTERVAL};
+
+/// Longueur maximale d'une ligne (82 caractères pour une trame standard, plus pour les trames
+/// propriétaires): au-delà, le flux est désynchronisé et le tampon est vidé
+pub const MAX_LINE: usize = 256;
+
+/// Découpage du flux série en trames NMEA, une trame par ligne
+#[derive(Default)]
+pub struct Nmea {
+    parser: NmeaParser,
+    buffer: Vec<u8>,
+}
+
+impl Nmea {
+    /// Octets reçus, retourne les trames complètes reconnues (les autres sont ignorées)
+    pub fn push(&mut self, bytes: &[u8]) -> Vec<ParsedMessage> {
+        self.buffer.extend_from_slice(bytes);
+
+        let mut messages = Vec::new();
+        let mut start = 0;
+        while let Some(end) = self.buffer[start..].iter().position(|&x| x == b'\n') {
+            let line = &self.buffer[start..start + end + 1];
+            start += end + 1;
+
+            if !checksum(line) {
+                continue;
+            }
+            let line = std::str::from_utf8(line).unwrap_or_default();
+            if let Ok(message) = self.parser.parse_sentence(line) {
+                messages.push(message);
+            }
+        }
+        self.buffer.drain(..start);
+
+        // Ligne sans fin (bruit sur le port série): mémoire bornée
+        if self.buffer.len() > MAX_LINE {
+            self.buffer.clear();
+        }
+
+        messages
+    }
+}
+
+/// Vérifie la forme d'une trame ($ ou !, données ASCII, *XX puis fin de ligne) et sa somme de
+/// contrôle (XOR des octets entre le début et *)
+pub fn checksum(line: &[u8]) -> bool {
+    let line = line.strip_suffix(b"\n").unwrap_or(line);
+    let line = line.strip_suffix(b"\r").unwrap_or(line);
+
+    let Some((&(b'$' | b'!'), line)) = line.split_first() else {
+        return false;
+    };
+    let Some(star) = line.iter().rposition(|&x| x == b'*') else {
+        return false;
+    };
+
+    let (data, expected) = (&line[..star], &line[star + 1..]);
+    if expected.len() != 2 || !expected.iter().all(u8::is_ascii_hexdigit) {
+        return false;
+    }
+    if !data.iter().all(|x| x.is_ascii_graphic() || *x == b' ') {
+        return false;
+    }
+
+    // Chiffres hexadécimaux ASCII vérifiés: conversion sans erreur
+    let expected = u8::from_str_radix(std::str::from_utf8(expected).unwrap(), 16).unwrap();
+    data.iter().fold(0, |sum, x| sum ^ x) == expected
+}
+
+/// Noeuds vers km/h
+const KNOTS_TO_KMH: f64 = 1.852;
+
+/// Applique une trame (GGA: position, VTG: vitesse et cap, RMC: date, heure et validité) au
+/// dernier échantillon GPS. Une trame RMC sans position (V) est conservée avec `valid` à faux.
+pub fn apply(gps: &mut GpsData, message: &ParsedMessage) {
+    match message {
+        ParsedMessage::Gga(gga) => {
+            gps.latitude = gga.latitude.unwrap_or(0.0);
+            gps.longitude = gga.longitude.unwrap_or(0.0);
+            gps.satellites = gga.satellite_count.unwrap_or(0);
+            gps.fix = gga.quality == GgaQualityIndicator::GpsFix;
+        }
+        ParsedMessage::Vtg(vtg) => {
+            gps.speed_kmh = vtg.sog_kph.unwrap_or(0.0);
+            gps.heading = vtg.cog_true.unwrap_or(0.0);
+        }
+        ParsedMessage::Rmc(rmc) => {
+            gps.time = rmc.timestamp;
+            gps.valid = rmc.status_active == Some(true);
+            if let (Some(latitude), Some(longitude)) = (rmc.latitude, rmc.longitude) {
+                gps.latitude = latitude;
+                gps.longitude = longitude;
+            }
+            if let Some(sog) = rmc.sog_knots {
+                gps.speed_kmh = sog * KNOTS_TO_KMH;
+            }
+            if let Some(bearing) = rmc.bearing {
+                gps.heading = bearing;
+            }
+        }
+        _ => {}
+    }
+}
+
+/// Trames d'un cycle en cours
+#[derive(Clone, Copy, Default, PartialEq)]
+enum Cycle {
+    #[default]
+    None,
+    Gsa,
+    Gsv,
+}
+
+/// Satellites et précision, par cycle du récepteur: les trames GSA consécutives (une par
+/// constellation) puis les groupes GSV consécutifs (assemblés par le parseur) forment un cycle.
+/// Un cycle est pris en compte une fois terminé, à la trame d'un autre type.
+#[derive(Default)]
+pub struct Satellites {
+    /// Dernier cycle complet
+    data: SatellitesData,
+    cycle: Cycle,
+    used: usize,
+    in_view: usize,
+    snr: Vec<f32>,
+    /// Cycle terminé depuis le dernier échantillon
+    updated: bool,
+}
+
+impl Satellites {
+    /// Applique une trame (GSA: précision et satellites utilisés, GSV: satellites visibles)
+    pub fn apply(&mut self, message: &ParsedMessage) {
+        let cycle = match message {
+            // Partie d'un groupe GSV, le groupe complet suit
+            ParsedMessage::Incomplete => return,
+            ParsedMessage::Gsa(_) => Cycle::Gsa,
+            ParsedMessage::Gsv(_) => Cycle::Gsv,
+            _ => Cycle::None,
+        };
+        if cycle != self.cycle {
+            self.finish();
+            self.cycle = cycle;
+        }
+
+        match message {
+            ParsedMessage::Gsa(gsa) => {
+                self.data.pdop = gsa.pdop.unwrap_or(0.0);
+                self.data.hdop = gsa.hdop.unwrap_or(0.0);
+                self.data.vdop = gsa.vdop.unwrap_or(0.0);
+                self.used += gsa.prn_numbers.len();
+            }
+            ParsedMessage::Gsv(group) => {
+                self.in_view += group.len();
+                self.snr.extend(group.iter().filter_map(|satellite| satellite.snr));
+            }
+            _ => {}
+        }
+    }
+
+    /// Fin du cycle en cours
+    fn finish(&mut self) {
+        match self.cycle {
+            Cycle::Gsa => self.data.used = self.used.min(u8::MAX as usize) as u8,
+            Cycle::Gsv => {
+                self.data.in_view = self.in_view.min(u8::MAX as usize) as u8;
+                self.data.snr_mean = match self.snr.len() {
+                    0 => 0.0,
+                    count => self.snr.iter().map(|&snr| snr as f64).sum::<f64>() / count as f64,
+                };
+            }
+            Cycle::None => return,
+        }
+
+        self.used = 0;
+        self.in_view = 0;
+        self.snr.clear();
+        self.updated = true;
+    }
+
+    /// Echantillon si un cycle s'est terminé et que l'intervalle depuis le précédent est écoulé
+    pub fn sample(&mut self, stamp: Stamp) -> Option<SatellitesData> {
+        let elapsed = stamp.mono_us.saturating_sub(self.data.stamp.mono_us);
+        if !self.updated || elapsed < SATELLITES_INTERVAL.as_micros() as u64 {
+            return None;
+        }
+
+        self.updated = false;
+        self.data.stamp = stamp;
+        Some(self.data)
+    }
+}
@@ -0,0 +1,594 @@
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use tokio::sync::watch;
+
+use crate::clock::Clock;
+use crate::config::{AnalogChannel, CurrentChannel, BaroConfig, EncoderConfig, I2cDeviceConfig, MagCalibration, PowerConfig, RangeConfig};
+use crate::i2c::{I2cBuses, I2cHandle};
+use crate::sensors::reader::{AnalogData, BaroData, Data, EncoderData, MagData, ImuData, PowerData, RangeData, RangeStatus, SensorStatus, MAX_CELLS};
+use crate::sensors::mag::declination::{self, Declination};
+use crate::sensors::retry::Pending;
+use crate::sensors::source::{Context, Source};
+use crate::sensors::baro::altitude::Zero;
+use crate::sensors::encoder::odometry::Odometry;
+use crate::sensors::{analog, baro, encoder, gps, imu, mag, power, range};
+
+/// Ouvre le bus d'un capteur I2C et l'initialise
+fn init_i2c<T>(
+    buses: &Mutex<I2cBuses>,
+    device: &I2cDeviceConfig,
+    init: impl FnOnce(&mut rppal::i2c::I2c) -> anyhow::Result<T>,
+) -> anyhow::Result<(I2cHandle, T)> {
+    let i2c = buses.lock().unwrap().handle(device)?;
+    let sensor = i2c.transaction(init)?;
+    Ok((i2c, sensor))
+}
+
+/// Capteur: Magnétique
+pub struct MagSource {
+    buses: Arc<Mutex<I2cBuses>>,
+    device: I2cDeviceConfig,
+    /// Déclinaison appliquée au cap
+    declination: Declination,
+    calibration: watch::Receiver<MagCalibration>,
+    sensor: Pending<(I2cHandle, mag::hmc8553l::HMC8553L)>,
+}
+
+impl MagSource {
+    pub fn new(context: &Context) -> anyhow::Result<Self> {
+        let config = &context.config.sensors.mag;
+        let mut source = Self {
+            buses: context.buses.clone(),
+            device: config.i2c.clone(),
+            declination: Declination::new(config.declination_deg, context.wmm.clone()),
+            calibration: context.mag_calibration.clone(),
+            sensor: Pending::new("mag"),
+        };
+
+        context.selftest.record(
+            "mag.whoami",
+            source.handle().and_then(|i2c| i2c.transaction(mag::hmc8553l::HMC8553L::identify)),
+        );
+
+        if config.required {
+            source.sensor.require(|| init_i2c(&source.buses, &source.device, mag::hmc8553l::HMC8553L::new))?;
+        }
+
+        Ok(source)
+    }
+
+    fn handle(&self) -> anyhow::Result<I2cHandle> {
+        self.buses.lock().unwrap().handle(&self.device)
+    }
+}
+
+impl Source for MagSource {
+    fn poll(&mut self, data: &mut Data) -> bool {
+        let Some((i2c, mag)) = self
+            .sensor
+            .poll(|| init_i2c(&self.buses, &self.device, mag::hmc8553l::HMC8553L::new))
+        else {
+            return false;
+        };
+
+        let Ok(raw) = i2c.transaction(|bus| mag.get_mag_axes_raw(bus)) else {
+            tracing::warn!(target: "mag", "Erreur lors de la récupération des données.");
+            return false;
+        };
+
+        // Correction "Hard Iron" & "Soft Iron" avant le calcul du cap
+        let raw = (raw.x, raw.y, raw.z);
+        let calibrated = self.calibration.borrow().apply(raw);
+        let heading = mag::hmc8553l::HMC8553L::heading(calibrated);
+        let declination = self.declination.at_gps(&data.gps);
+        data.mag = MagData {
+            heading,
+            raw,
+            calibrated,
+            true_heading: declination::true_heading(heading, declination),
+            declination,
+            ..data.mag
+        };
+        true
+    }
+
+    fn status(&self) -> &SensorStatus {
+        self.sensor.status()
+    }
+
+    fn hardware(&self) -> bool {
+        true
+    }
+}
+
+/// Capteur: IMU
+pub struct ImuSource {
+    buses: Arc<Mutex<I2cBuses>>,
+    device: I2cDeviceConfig,
+    /// Réglages du capteur, vérifiés au démarrage
+    settings: imu::settings::Settings,
+    sensor: Pending<(I2cHandle, imu::imu::IMU)>,
+}
+
+impl ImuSource {
+    pub fn new(context: &Context) -> anyhow::Result<Self> {
+        let config = &context.config.sensors.imu;
+        let settings = imu::settings::Settings::new(config.rate_hz, config.accel_range_g, config.gyro_range_dps, config.dlpf_hz)?;
+        let mut source = Self {
+            buses: context.buses.clone(),
+            device: config.i2c.clone(),
+            settings,
+            sensor: Pending::new("imu"),
+        };
+
+        context.selftest.record(
+            "imu.whoami",
+            source.handle().and_then(|i2c| i2c.transaction(imu::imu::IMU::identify)),
+        );
+
+        if config.required {
+            source.sensor.require(|| init_imu(&source.buses, &source.device, source.settings))?;
+        }
+
+        Ok(source)
+    }
+
+    fn handle(&self) -> anyhow::Result<I2cHandle> {
+        self.buses.lock().unwrap().handle(&self.device)
+    }
+}
+
+/// Ouvre le bus de l'IMU et l'initialise avec ses réglages
+fn init_imu(
+    buses: &Mutex<I2cBuses>,
+    device: &I2cDeviceConfig,
+    settings: imu::settings::Settings,
+) -> anyhow::Result<(I2cHandle, imu::imu::IMU)> {
+    init_i2c(buses, device, |i2c| imu::imu::IMU::new(i2c, settings))
+}
+
+impl Source for ImuSource {
+    fn poll(&mut self, data: &mut Data) -> bool {
+        let Some((i2c, imu)) = self.sensor.poll(|| init_imu(&self.buses, &self.device, self.settings)) else {
+            return false;
+        };
+
+        imu.set_speed(data.gps.speed_kmh);
+        if let Err(e) = i2c.transaction(|bus| imu.update(bus)) {
+            tracing::warn!(target: "imu", "Erreur de calcul: {}", e);
+            return false;
+        }
+
+        let angles = imu.get_angles();
+        let temp: f32 = imu.get_temp();
+        let accel = imu.get_last_accel();
+        let gyro = imu.get_last_gyro();
+
+        data.imu = ImuData {
+            angles: (angles.x, angles.y, angles.z),
+            temp,
+            accel: (accel.x, accel.y, accel.z),
+            gyro: (gyro.x, gyro.y, gyro.z),
+            ..data.imu
+        };
+        true
+    }
+
+    fn status(&self) -> &SensorStatus {
+        self.sensor.status()
+    }
+
+    fn hardware(&self) -> bool {
+        true
+    }
+}
+
+/// Capteur: Analog
+pub struct AnalogSource {
+    buses: Arc<Mutex<I2cBuses>>,
+    device: I2cDeviceConfig,
+    /// Prises d'équilibrage câblées, de l'élément le plus bas au pack complet
+    channels: Vec<AnalogChannel>,
+    /// Capteur de courant du pack
+    current: Option<CurrentChannel>,
+    sensor: Pending<(I2cHandle, analog::analog::Analog)>,
+}
+
+impl AnalogSource {
+    pub fn new(context: &Context) -> anyhow::Result<Self> {
+        let config = &context.config.sensors.analog;
+        let mut source = Self {
+            buses: context.buses.clone(),
+            device: config.i2c.clone(),
+            channels: config.channels.iter().filter(|channel| channel.enabled).cloned().collect(),
+            current: config.current.clone(),
+            sensor: Pending::new("analog"),
+        };
+
+        if config.required {
+            source.sensor.require(|| init_i2c(&source.buses, &source.device, analog::analog::Analog::new))?;
+        }
+
+        Ok(source)
+    }
+}
+
+impl Source for AnalogSource {
+    fn poll(&mut self, data: &mut Data) -> bool {
+        let Some((i2c, analog)) = self
+            .sensor
+            .poll(|| init_i2c(&self.buses, &self.device, analog::analog::Analog::new))
+        else {
+            return false;
+        };
+
+        // Prises d'équilibrage: la dernière donne la tension du pack
+        let channels = &self.channels;
+        let current = &self.current;
+        let measured = i2c.transaction(|bus| {
+            // Courant en échec: échantillon de courant perdu, la tension reste publiée
+            let current = current
+                .as_ref()
+                .and_then(|c| analog.get_current(bus, c.input, c.zero_v, c.volts_per_a).ok());
+
+            if channels.is_empty() {
+                return Ok((analog.get_battery(bus)?, [None; MAX_CELLS], current));
+            }
+
+            let mut taps = [None; MAX_CELLS];
+            let mut battery = 0.0;
+            for (tap, channel) in taps.iter_mut().zip(channels) {
+                battery = analog.get_tap(bus, channel.input, channel.ratio)?;
+                *tap = Some(battery);
+            }
+            Ok((battery, AnalogData::cells_from_taps(taps), current))
+        });
+
+        match measured {
+            Ok((battery, cells, current)) => {
+                data.analog.battery = battery;
+                data.analog.cells = cells;
+                data.analog.current = current;
+                true
+            }
+            Err(e) => {
+                tracing::warn!(target: "analog", "Erreur: {}", e);
+                false
+            }
+        }
+    }
+
+    fn status(&self) -> &SensorStatus {
+        self.sensor.status()
+    }
+
+    fn hardware(&self) -> bool {
+        true
+    }
+}
+
+/// Capteur: Moniteur de puissance
+pub struct PowerSource {
+    buses: Arc<Mutex<I2cBuses>>,
+    config: PowerConfig,
+    sensor: Pending<(I2cHandle, power::ina219::Ina219)>,
+}
+
+impl PowerSource {
+    pub fn new(context: &Context) -> anyhow::Result<Self> {
+        let mut source = Self {
+            buses: context.buses.clone(),
+            config: context.config.sensors.power.clone(),
+            sensor: Pending::new("power"),
+        };
+
+        if source.config.required {
+            source.sensor.require(|| init_power(&source.buses, &source.config))?;
+        }
+
+        Ok(source)
+    }
+}
+
+/// Ouvre le bus du moniteur de puissance et l'initialise à son adresse
+fn init_power(
+    buses: &Mutex<I2cBuses>,
+    config: &PowerConfig,
+) -> anyhow::Result<(I2cHandle, power::ina219::Ina219)> {
+    init_i2c(buses, &config.i2c, |i2c| {
+        power::ina219::Ina219::new(i2c, config.address, config.shunt_ohm, config.max_current_a)
+    })
+}
+
+impl Source for PowerSource {
+    fn poll(&mut self, data: &mut Data) -> bool {
+        let Some((i2c, power)) = self.sensor.poll(|| init_power(&self.buses, &self.config)) else {
+            return false;
+        };
+
+        match i2c.transaction(|bus| power.get_measure(bus)) {
+            Ok(measure) => {
+                data.power = PowerData {
+                    voltage: measure.voltage,
+                    current: measure.current,
+                    power: measure.power,
+                    ..data.power
+                };
+                true
+            }
+            Err(e) => {
+                tracing::warn!(target: "power", "Erreur: {}", e);
+                false
+            }
+        }
+    }
+
+    fn status(&self) -> &SensorStatus {
+        self.sensor.status()
+    }
+
+    fn hardware(&self) -> bool {
+        true
+    }
+}
+
+/// Capteur: Baromètre
+pub struct BaroSource {
+    buses: Arc<Mutex<I2cBuses>>,
+    config: BaroConfig,
+    sensor: Pending<(I2cHandle, baro::bme280::Bme280)>,
+    /// Pression de référence conservée si le capteur est réinitialisé
+    zero: Zero,
+}
+
+impl BaroSource {
+    pub fn new(context: &Context) -> anyhow::Result<Self> {
+        let config = context.config.sensors.baro.clone();
+        let mut source = Self {
+            buses: context.buses.clone(),
+            zero: Zero::new(config.zero_samples),
+            config,
+            sensor: Pending::new("baro"),
+        };
+
+        if source.config.required {
+            source.sensor.require(|| init_baro(&source.buses, &source.config))?;
+        }
+
+        Ok(source)
+    }
+}
+
+/// Ouvre le bus du baromètre et l'initialise à son adresse
+fn init_baro(buses: &Mutex<I2cBuses>, config: &BaroConfig) -> anyhow::Result<(I2cHandle, baro::bme280::Bme280)> {
+    init_i2c(buses, &config.i2c, |i2c| baro::bme280::Bme280::new(i2c, config.address))
+}
+
+impl Source for BaroSource {
+    fn poll(&mut self, data: &mut Data) -> bool {
+        let Some((i2c, baro)) = self.sensor.poll(|| init_baro(&self.buses, &self.config)) else {
+            return false;
+        };
+
+        match i2c.transaction(|bus| baro.get_measure(bus)) {
+            Ok(measure) => {
+                data.baro = BaroData {
+                    pressure: measure.pressure as f32,
+                    temperature: measure.temperature as f32,
+                    humidity: measure.humidity.map(|humidity| humidity as f32),
+                    altitude: self.zero.altitude(measure.pressure),
+                    ..data.baro
+                };
+                true
+            }
+            Err(e) => {
+                tracing::warn!(target: "baro", "Erreur: {}", e);
+                false
+            }
+        }
+    }
+
+    fn status(&self) -> &SensorStatus {
+        self.sensor.status()
+    }
+
+    fn hardware(&self) -> bool {
+        true
+    }
+}
+
+/// Capteur: Encodeur de roue
+pub struct EncoderSource {
+    clock: Clock,
+    config: EncoderConfig,
+    sensor: Pending<encoder::hall::Hall>,
+    odometry: Odometry,
+}
+
+impl EncoderSource {
+    pub fn new(context: &Context) -> anyhow::Result<Self> {
+        let config = context.config.sensors.encoder.clone();
+        let mut source = Self {
+            clock: context.clock.clone(),
+            odometry: Odometry::new(config.meters_per_pulse(), config.interval_ms * 1000),
+            config,
+            sensor: Pending::new("encoder"),
+        };
+
+        if source.config.required {
+            source.sensor.require(|| encoder::hall::Hall::new(source.config.pin))?;
+        }
+
+        Ok(source)
+    }
+}
+
+impl Source for EncoderSource {
+    fn poll(&mut self, data: &mut Data) -> bool {
+        let pin = self.config.pin;
+        let Some(hall) = self.sensor.poll(|| encoder::hall::Hall::new(pin)) else {
+            return false;
+        };
+
+        // Impulsions comptées par l'interruption, lues à chaque intervalle
+        let Some(measure) = self.odometry.update(self.clock.stamp().mono_us, hall.count()) else {
+            return false;
+        };
+        data.encoder = EncoderData {
+            speed_kmh: measure.speed_kmh,
+            distance_m: measure.distance_m,
+            pulses: measure.pulses,
+            ..data.encoder
+        };
+        true
+    }
+
+    fn status(&self) -> &SensorStatus {
+        self.sensor.status()
+    }
+}
+
+/// Capteur: Distance
+pub struct RangeSource {
+    buses: Arc<Mutex<I2cBuses>>,
+    clock: Clock,
+    config: RangeConfig,
+    sensor: Pending<(I2cHandle, range::vl53l1x::Vl53l1x)>,
+    /// Dernière mesure ou dernier échantillon "timeout"
+    last: Instant,
+}
+
+impl RangeSource {
+    pub fn new(context: &Context) -> anyhow::Result<Self> {
+        let config = context.config.sensors.range.clone();
+        let mut source = Self {
+            buses: context.buses.clone(),
+            clock: context.clock.clone(),
+            last: context.clock.now(),
+            config,
+            sensor: Pending::new("range"),
+        };
+
+        if source.config.required {
+            source.sensor.require(|| init_range(&source.buses, &source.config))?;
+        }
+
+        Ok(source)
+    }
+}
+
+/// Ouvre le bus du capteur de distance et l'initialise à son adresse
+fn init_range(buses: &Mutex<I2cBuses>, config: &RangeConfig) -> anyhow::Result<(I2cHandle, range::vl53l1x::Vl53l1x)> {
+    init_i2c(buses, &config.i2c, |i2c| range::vl53l1x::Vl53l1x::new(i2c, config.address))
+}
+
+impl Source for RangeSource {
+    fn poll(&mut self, data: &mut Data) -> bool {
+        let Some((i2c, sensor)) = self.sensor.poll(|| init_range(&self.buses, &self.config)) else {
+            return false;
+        };
+
+        match i2c.transaction(|bus| sensor.get_measure(bus)) {
+            Ok(Some(measure)) => {
+                self.last = self.clock.now();
+                data.range = RangeData {
+                    distance_mm: measure.distance_mm,
+                    status: measure.status,
+                    ..data.range
+                };
+                true
+            }
+            // Capteur muet: un échantillon "timeout" par délai écoulé, jamais confondu avec 0 mm
+            Ok(None) => {
+                if self.clock.since(self.last) < Duration::from_millis(self.config.timeout_ms) {
+                    return false;
+                }
+                self.last = self.clock.now();
+                data.range = RangeData {
+                    distance_mm: None,
+                    status: RangeStatus::Timeout,
+                    ..data.range
+                };
+                true
+            }
+            Err(e) => {
+                tracing::warn!(target: "range", "Erreur: {}", e);
+                false
+            }
+        }
+    }
+
+    fn status(&self) -> &SensorStatus {
+        self.sensor.status()
+    }
+
+    fn hardware(&self) -> bool {
+        true
+    }
+}
+
+/// Capteur: GPS
+pub struct GpsSource {
+    clock: Clock,
+    device: PathBuf,
+    baud: u32,
+    sensor: Pending<gps::GPS>,
+    satellites: gps::nmea::Satellites,
+}
+
+impl GpsSource {
+    pub fn new(context: &Context) -> anyhow::Result<Self> {
+        let (device, baud) = context.config.sensors.gps.port()?;
+        let mut source = Self {
+            clock: context.clock.clone(),
+            device,
+            baud,
+            sensor: Pending::new("gps"),
+            satellites: gps::nmea::Satellites::default(),
+        };
+
+        if context.config.sensors.gps.required {
+            source.sensor.require(|| gps::GPS::new(&source.device, source.baud))?;
+        }
+
+        Ok(source)
+    }
+}
+
+impl Source for GpsSource {
+    fn poll(&mut self, data: &mut Data) -> bool {
+        let Some(gps) = self.sensor.poll(|| gps::GPS::new(&self.device, self.baud)) else {
+            return false;
+        };
+
+        // Erreur de lecture: le port est fermé et réouvert, le périphérique peut avoir changé
+        let messages = match gps.read() {
+            Ok(Some(messages)) => messages,
+            Ok(None) => return false,
+            Err(e) => {
+                self.sensor.lost(&e);
+                return false;
+            }
+        };
+
+        for message in messages.iter() {
+            gps::nmea::apply(&mut data.gps, message);
+            self.satellites.apply(message);
+        }
+        if let Some(satellites) = self.satellites.sample(self.clock.stamp()) {
+            data.satellites = satellites;
+        }
+
+        true
+    }
+
+    fn status(&self) -> &SensorStatus {
+        self.sensor.status()
+    }
+
+    fn hardware(&self) -> bool {
+        true
+    }
+}
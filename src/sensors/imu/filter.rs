@@ -0,0 +1,62 @@
+use std::time::Duration;
+
+use nalgebra::Vector3;
+
+/// Filtre complémentaire: angles d'Euler (tangage, roulis, lacet en degrés) depuis le gyroscope,
+/// corrigés par l'accéléromètre. L'accéléromètre pèse davantage à basse vitesse.
+pub struct Complementary {
+    angles: Vector3<f32>,
+}
+
+impl Default for Complementary {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Complementary {
+    pub fn new() -> Self {
+        Self {
+            angles: Vector3::new(0.0, 0.0, 0.0),
+        }
+    }
+
+    /// Nouvelle mesure: accélération (g), vitesse angulaire (°/s), temps écoulé depuis la mesure
+    /// précédente et vitesse du véhicule (km/h). Retourne les angles filtrés.
+    pub fn update(
+        &mut self,
+        acceleration: Vector3<f32>,
+        gyroscope: Vector3<f32>,
+        elapsed: Duration,
+        speed: f64,
+    ) -> Vector3<f32> {
+        let elapsed = elapsed.as_secs_f32();
+
+        // Calcul d'angle en degrée via l'accéléromètre (2D)
+        let accel_pitch = acceleration.y.atan2(acceleration.z).to_degrees();
+        let accel_roll = acceleration.x.atan2(acceleration.z).to_degrees();
+
+        // Calcul d'angle en degrée via le gyroscope (3D)
+        let gyroscope_pitch = self.angles.x + gyroscope.x * elapsed;
+        let gyroscope_roll = self.angles.y - gyroscope.y * elapsed;
+        let gyroscope_yaw = self.angles.z + gyroscope.z * elapsed;
+
+        // Calcul dynamique du filtre via la vitesse
+        let mut gyro_mult: f32 = 1.0;
+        if speed <= 10.0 {
+            gyro_mult = 0.80 + 0.18 * (1.0 - (speed as f32 / 10.0));
+        }
+        let accel_mult = 1.0 - gyro_mult;
+
+        self.angles.x = gyro_mult * gyroscope_pitch + accel_mult * accel_pitch;
+        self.angles.y = gyro_mult * gyroscope_roll + accel_mult * accel_roll;
+        // Très imprécis, utiliser le magnétomètre
+        self.angles.z = gyroscope_yaw;
+
+        self.angles
+    }
+
+    pub fn angles(&self) -> Vector3<f32> {
+        self.angles
+    }
+}
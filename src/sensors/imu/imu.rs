@@ -10,30 +10,35 @@ use std::thread::sleep;
 use std::time::Instant;
 use nalgebra::Vector3;
 use crate::sensors::imu::registry;
+use crate::sensors::imu::filter::Complementary;
+use crate::sensors::imu::settings::Settings;
 
-pub(crate) struct IMU {
+pub struct IMU {
     gyro_cal: Vector3<f32>,
     accel_cal: Vector3<f32>,
-    gyro_scale: f32,
-    accel_scale: f32,
-    angles: Vector3<f32>,
+    settings: Settings,
+    filter: Complementary,
     temp: f32,
+    /// Dernière accélération (g) et vitesse angulaire (°/s) transmises au filtre
+    accel: Vector3<f32>,
+    gyro: Vector3<f32>,
     speed: f64,
     last_measurment: Option<Instant>,
 }
 
 impl IMU {
     /// Constructeur
-    pub(crate) fn new(i2c: &mut I2c) -> anyhow::Result<Self> {
+    pub fn new(i2c: &mut I2c, settings: Settings) -> anyhow::Result<Self> {
 
         // Créer l'objet et commence l'initialisation
         let mut imu = Self {
             gyro_cal: Vector3::new(0.0, 0.0, 0.0),
             accel_cal: Vector3::new(0.0, 0.0, 0.0),
-            gyro_scale: 131.0,
-            accel_scale: 16384.0,
-            angles: Vector3::new(0.0, 0.0, 0.0),
+            settings,
+            filter: Complementary::new(),
             temp: 0.0,
+            accel: Vector3::zeros(),
+            gyro: Vector3::zeros(),
             speed: 0.0,
             last_measurment: Option::None,
         };
@@ -42,6 +47,7 @@ impl IMU {
         imu.set_slave(i2c)?;
         imu.reset(i2c)?;
         imu.init_module(i2c)?;
+        imu.check_settings(i2c)?;
         imu.calibration_imu(i2c)?;
 
         // Vérification
@@ -50,7 +56,7 @@ impl IMU {
         Ok(imu)
     }
 
-    pub(crate) fn set_speed(&mut self, speed: f64) {
+    pub fn set_speed(&mut self, speed: f64) {
         self.speed = speed;
     }
 
@@ -68,16 +74,29 @@ impl IMU {
         let who = self.whoami(i2c)?;
         let i2cbypass = self.get_i2c_bypass_enable(i2c)?;
 
-        println!("[IMU] Who i am: {}", who);
-        println!("[IMU] Temp. Enable: {}", temp_enable);
-        println!("[IMU] I2C Bypass Enable: {}", i2cbypass);
-        println!("[IMU] Sleep: {}", sleep);
-        println!("[IMU] Clock source: {:#04x}", clock);
-        println!("[IMU] Gyro scale range: {:#04x}", gyro_scale_range);
-        println!("[IMU] Accel scale range: {:#04x}", accel_scale_range);
+        tracing::info!(target: "imu", "Who i am: {}", who);
+        tracing::info!(target: "imu", "Temp. Enable: {}", temp_enable);
+        tracing::info!(target: "imu", "I2C Bypass Enable: {}", i2cbypass);
+        tracing::info!(target: "imu", "Sleep: {}", sleep);
+        tracing::info!(target: "imu", "Clock source: {:#04x}", clock);
+        tracing::info!(target: "imu", "Gyro scale range: {:#04x}", gyro_scale_range);
+        tracing::info!(target: "imu", "Accel scale range: {:#04x}", accel_scale_range);
+        tracing::info!(target: "imu", "DLPF: {:#04x}", self.get_dlpf_mode(i2c)?);
+        tracing::info!(target: "imu", "Sample rate divider: {}", self.get_rate(i2c)?);
         Ok(())
     }
 
+    /// Vérifie l'identité du capteur (registre WHO_AM_I), avant toute initialisation
+    pub fn identify(i2c: &mut I2c) -> anyhow::Result<String> {
+        i2c.set_slave_address(registry::IMU_ADDR)?;
+        let who = i2c.lecture_word(registry::MPU6050_RA_WHO_AM_I)?;
+        if who != registry::MPU6050_WHO_AM_I_VALUE {
+            return Err(anyhow::anyhow!("WHO_AM_I inattendu: {:#04x}", who));
+        }
+
+        Ok(format!("WHO_AM_I: {:#04x}", who))
+    }
+
     /// Qui suis-je ?
     fn whoami(&self, i2c: &mut I2c) -> anyhow::Result<u8>  {
         i2c.lecture_word(registry::MPU6050_RA_WHO_AM_I)
@@ -85,13 +104,15 @@ impl IMU {
 
     /// Initialise rapidement le module avec des valeurs pré-défini
     fn init_module(&mut self, i2c: &mut I2c) -> anyhow::Result<()>  {
-        println!("[IMU] Initialisation ...");
+        tracing::info!(target: "imu", "Initialisation ...");
         self.set_clock_source(i2c, registry::MPU6050_CLOCK_PLL_XGYRO)?;
         self.set_i2c_bypass_enable(i2c, true)?;
         self.set_temp_sensor_enable(i2c, true)?;
         self.set_sleep_mode(i2c, false)?;
-        self.set_fullscale_accel_range(i2c, registry::MPU6050_ACCEL_FS_2)?;
-        self.set_fullscale_gyro_range(i2c, registry::MPU6050_GYRO_FS_250)?;
+        self.set_fullscale_accel_range(i2c, self.settings.accel_sel)?;
+        self.set_fullscale_gyro_range(i2c, self.settings.gyro_sel)?;
+        self.set_dlpf_mode(i2c, self.settings.dlpf_cfg)?;
+        self.set_rate(i2c, self.settings.divider)?;
         Ok(())
     }
 
@@ -151,14 +172,6 @@ impl IMU {
     
     /// Défini le scale du gyroscope
     fn set_fullscale_gyro_range(&mut self, i2c: &mut I2c, range: u8) -> anyhow::Result<()>  {
-        match range {
-            registry::MPU6050_GYRO_FS_250  => self.gyro_scale=131.0,
-            registry::MPU6050_GYRO_FS_500  => self.gyro_scale=65.5,
-            registry::MPU6050_GYRO_FS_1000 => self.gyro_scale=32.8,
-            registry::MPU6050_GYRO_FS_2000 => self.gyro_scale=16.4,
-            _ => println!("Gyro range invalide."),
-        }
-        
         i2c.ecriture_bits8(registry::MPU6050_RA_GYRO_CONFIG, registry::MPU6050_GCONFIG_FS_SEL_BIT, registry::MPU6050_GCONFIG_FS_SEL_LENGTH, range)
     }
 
@@ -169,24 +182,51 @@ impl IMU {
     
     /// Défini le scale de l'accélérométre
     fn set_fullscale_accel_range(&mut self, i2c: &mut I2c, range: u8) -> anyhow::Result<()>  {
-        match range {
-            registry::MPU6050_ACCEL_FS_2 => self.accel_scale=16384.0,
-            registry::MPU6050_ACCEL_FS_4 => self.accel_scale=8192.0,
-            registry::MPU6050_ACCEL_FS_8 => self.accel_scale=4096.0,
-            registry::MPU6050_ACCEL_FS_16=> self.accel_scale=2048.0,
-            _ => println!("Accel range invalide."),
-        }
-
         i2c.ecriture_bits8(registry::MPU6050_RA_ACCEL_CONFIG, registry::MPU6050_ACONFIG_AFS_SEL_BIT, registry::MPU6050_ACONFIG_AFS_SEL_LENGTH, range)
     }
 
+    /// Récupére le filtre passe-bas numérique
+    fn get_dlpf_mode(&self, i2c: &mut I2c) -> anyhow::Result<u8>  {
+        i2c.lecture_bits8(registry::MPU6050_RA_CONFIG, registry::MPU6050_CFG_DLPF_CFG_BIT, registry::MPU6050_CFG_DLPF_CFG_LENGTH)
+    }
+
+    /// Défini le filtre passe-bas numérique
+    fn set_dlpf_mode(&self, i2c: &mut I2c, mode: u8) -> anyhow::Result<()>  {
+        i2c.ecriture_bits8(registry::MPU6050_RA_CONFIG, registry::MPU6050_CFG_DLPF_CFG_BIT, registry::MPU6050_CFG_DLPF_CFG_LENGTH, mode)
+    }
+
+    /// Récupére le diviseur de la fréquence d'échantillonnage
+    fn get_rate(&self, i2c: &mut I2c) -> anyhow::Result<u8>  {
+        i2c.lecture_word(registry::MPU6050_RA_SMPLRT_DIV)
+    }
+
+    /// Défini le diviseur de la fréquence d'échantillonnage
+    fn set_rate(&self, i2c: &mut I2c, divider: u8) -> anyhow::Result<()>  {
+        i2c.ecriture_word(registry::MPU6050_RA_SMPLRT_DIV, divider)
+    }
+
+    /// Vérifie que les plages, le filtre et la fréquence relus correspondent aux réglages
+    fn check_settings(&self, i2c: &mut I2c) -> anyhow::Result<()>  {
+        let read = (
+            self.get_fullscale_accel_range(i2c)?,
+            self.get_fullscale_gyro_range(i2c)?,
+            self.get_dlpf_mode(i2c)?,
+            self.get_rate(i2c)?,
+        );
+        let expected = (self.settings.accel_sel, self.settings.gyro_sel, self.settings.dlpf_cfg, self.settings.divider);
+        if read != expected {
+            return Err(anyhow::anyhow!("Réglages relus {:?}, attendus {:?}", read, expected));
+        }
+        Ok(())
+    }
+
     ///////////////////////////////////
     /// GESTION DES MESURES
     ///////////////////////////////////
 
     /// Calibration de l'IMU
     fn calibration_imu(&mut self, i2c: &mut I2c) -> anyhow::Result<()>  {
-        println!("[IMU] Calibration ...");
+        tracing::info!(target: "imu", "Calibration ...");
 
         // Récupére ~500 mesures et fait une moyenne
         let mut offset_gyro = Vector3::new(0.0 as f32, 0.0 as f32, 0.0 as f32);
@@ -205,8 +245,8 @@ impl IMU {
         self.gyro_cal = offset_gyro / 500.0;
         self.accel_cal = offset_accel / 500.0;
 
-        println!("Calibration GYRO: (X: {} Y: {} Z: {})", self.gyro_cal.x, self.gyro_cal.y, self.gyro_cal.z);
-        println!("Calibration ACCEL: (X: {} Y: {} Z: {})", self.accel_cal.x, self.accel_cal.y, self.accel_cal.z);
+        tracing::info!(target: "imu", "Calibration GYRO: (X: {} Y: {} Z: {})", self.gyro_cal.x, self.gyro_cal.y, self.gyro_cal.z);
+        tracing::info!(target: "imu", "Calibration ACCEL: (X: {} Y: {} Z: {})", self.accel_cal.x, self.accel_cal.y, self.accel_cal.z);
         Ok(())
     }
 
@@ -282,27 +322,37 @@ impl IMU {
     /// Récupére l'accélération dans un vecteur
     fn get_accel(&self, i2c: &mut I2c) -> anyhow::Result<Vector3<f32>>  {
         let mut accel_measurement = self.get_accel_raw(i2c)?;
-        Ok(accel_measurement / self.accel_scale)
+        Ok(accel_measurement.map(|raw| self.settings.accel_g(raw)))
     }
 
     /// Récupére la vitesse angulaire dans un vecteur
     fn get_gyro(&self, i2c: &mut I2c) -> anyhow::Result<Vector3<f32>>  {
         let mut gyro_measurement = self.get_gyro_raw(i2c)? - self.gyro_cal;
-        Ok(gyro_measurement / self.gyro_scale)
+        Ok(gyro_measurement.map(|raw| self.settings.gyro_dps(raw)))
     }
 
     /// Récupére un angle d'euler à partir d'un filtre complémentaire, du gyroscope et de l'accélération
-    pub(crate) fn get_angles(&self) -> Vector3<f32> {
-        self.angles * -1.0 // -1.0 car j'ai monté le capteur à l'envers :)
+    pub fn get_angles(&self) -> Vector3<f32> {
+        self.filter.angles() * -1.0 // -1.0 car j'ai monté le capteur à l'envers :)
     }
 
     /// Récupére la température enregistrer depuis la dernière update
-    pub(crate) fn get_temp(&self) -> f32 {
+    pub fn get_temp(&self) -> f32 {
         self.temp
     }
 
+    /// Récupére l'accélération (g) enregistrer depuis la dernière update
+    pub fn get_last_accel(&self) -> Vector3<f32> {
+        self.accel
+    }
+
+    /// Récupére la vitesse angulaire (°/s) enregistrer depuis la dernière update
+    pub fn get_last_gyro(&self) -> Vector3<f32> {
+        self.gyro
+    }
+
     /// Lis et mets à jour les valeurs de l'IMU
-    pub(crate) fn update(&mut self, i2c: &mut I2c) -> anyhow::Result<()>  {
+    pub fn update(&mut self, i2c: &mut I2c) -> anyhow::Result<()>  {
         self.set_slave(i2c)?;
 
         let acceleration = self.get_accel(i2c)?;
@@ -322,27 +372,10 @@ impl IMU {
         // Remet le compteur à 0
         self.last_measurment = Some(Instant::now());
 
-        // Calcul d'angle en degrée via l'accéléromètre (2D)
-        let accel_pitch  = (acceleration.y.atan2(acceleration.z)).to_degrees(); // Pitch (SUR)
-        let accel_roll = (acceleration.x.atan2(acceleration.z)).to_degrees(); // Roll (SUR)
-
-        // Calcul d'angle en degrée via le gyroscope (3D)
-        let gyroscope_pitch = self.angles.x + (gyroscope.x * elapsed_time.as_secs_f32()); // Pitch (SUR)
-        let gyroscope_roll = self.angles.y - (gyroscope.y * elapsed_time.as_secs_f32()); // Roll (SUR)
-        let gyroscope_yaw = self.angles.z + (gyroscope.z * elapsed_time.as_secs_f32());
-
-        // Calcul dynamique du filtre via la vitesse
-        let mut gyro_mult: f32 = 1.0;
-        if (self.speed <= 10.0) {
-            gyro_mult = 0.80 + (0.18 * ( 1.0 - (self.speed as f32 / 10.0)));
-        }
-
-        let accel_mult = 1.0 - gyro_mult;
-
-        // Filte complémentaire
-        self.angles.x = (gyro_mult * gyroscope_pitch) + (accel_mult * accel_pitch); // Pitch
-        self.angles.y = (gyro_mult * gyroscope_roll) + (accel_mult * accel_roll); // Roll
-        self.angles.z = gyroscope_yaw; // Très imprécis, utiliser le magnétomètre
+        // Filtre complémentaire
+        self.filter.update(acceleration, gyroscope, elapsed_time, self.speed);
+        self.accel = acceleration;
+        self.gyro = gyroscope;
 
         Ok(())
     }
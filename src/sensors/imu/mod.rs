@@ -1,4 +1,6 @@
+pub mod filter;
 #[cfg(feature = "real-sensors")]
 mod registry;
 #[cfg(feature = "real-sensors")]
-pub mod imu;
\ No newline at end of file
+pub mod imu;
+pub mod settings;
\ No newline at end of file
@@ -333,6 +333,8 @@ pub const MPU6050_BANKSEL_MEM_SEL_BIT: u8 = 4;
 pub const MPU6050_BANKSEL_MEM_SEL_LENGTH: u8 = 5;
 pub const MPU6050_WHO_AM_I_BIT: u8 =  6;
 pub const MPU6050_WHO_AM_I_LENGTH: u8 = 6;
+/// Contenu du registre WHO_AM_I (indépendant de la broche AD0)
+pub const MPU6050_WHO_AM_I_VALUE: u8 = 0x68;
 pub const MPU6050_DMP_MEMORY_BANKS: u8 =  8;
 pub const MPU6050_DMP_MEMORY_BANK_SIZE: u16 =  256;
 pub const MPU6050_DMP_MEMORY_CHUNK_SIZE: u16 =  16;
\ No newline at end of file
@@ -0,0 +1,100 @@
+use anyhow::anyhow;
+
+// Voir documentation : https://invensense.tdk.com/wp-content/uploads/2015/02/MPU-6000-Register-Map1.pdf
+
+/// Fréquence maximale de l'accéléromètre (Hz)
+const ACCEL_MAX_RATE_HZ: u32 = 1000;
+
+/// Réglages du MPU6050 (valeurs des registres) et sensibilités correspondantes
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Settings {
+    /// AFS_SEL (ACCEL_CONFIG)
+    pub accel_sel: u8,
+    /// FS_SEL (GYRO_CONFIG)
+    pub gyro_sel: u8,
+    /// DLPF_CFG (CONFIG)
+    pub dlpf_cfg: u8,
+    /// SMPLRT_DIV: fréquence = sortie du gyroscope / (1 + diviseur)
+    pub divider: u8,
+    /// Sensibilité de l'accéléromètre (LSB/g)
+    pub accel_lsb: f32,
+    /// Sensibilité du gyroscope (LSB/°/s)
+    pub gyro_lsb: f32,
+}
+
+impl Settings {
+    /// Réglages pour la fréquence (Hz), les plages (±g, ±°/s) et la bande passante du filtre (Hz)
+    /// demandées. Une combinaison impossible pour le capteur est rejetée.
+    pub fn new(rate_hz: u32, accel_range_g: u8, gyro_range_dps: u16, dlpf_hz: u16) -> anyhow::Result<Self> {
+        let (accel_sel, accel_lsb) = match accel_range_g {
+            2 => (0x00, 16384.0),
+            4 => (0x01, 8192.0),
+            8 => (0x02, 4096.0),
+            16 => (0x03, 2048.0),
+            range => return Err(anyhow!("imu: accel_range_g {} invalide (2, 4, 8 ou 16)", range)),
+        };
+        let (gyro_sel, gyro_lsb) = match gyro_range_dps {
+            250 => (0x00, 131.0),
+            500 => (0x01, 65.5),
+            1000 => (0x02, 32.8),
+            2000 => (0x03, 16.4),
+            range => return Err(anyhow!("imu: gyro_range_dps {} invalide (250, 500, 1000 ou 2000)", range)),
+        };
+
+        // Sortie du gyroscope à 8 kHz sans filtre, 1 kHz avec
+        let (dlpf_cfg, output_hz) = match dlpf_hz {
+            256 => (0x00, 8000),
+            188 => (0x01, 1000),
+            98 => (0x02, 1000),
+            42 => (0x03, 1000),
+            20 => (0x04, 1000),
+            10 => (0x05, 1000),
+            5 => (0x06, 1000),
+            bandwidth => {
+                return Err(anyhow!(
+                    "imu: dlpf_hz {} invalide (256, 188, 98, 42, 20, 10 ou 5)",
+                    bandwidth
+                ))
+            }
+        };
+
+        if rate_hz == 0 || rate_hz > ACCEL_MAX_RATE_HZ {
+            return Err(anyhow!("imu: rate_hz {} hors de 1 à {} Hz", rate_hz, ACCEL_MAX_RATE_HZ));
+        }
+        if output_hz % rate_hz != 0 || output_hz / rate_hz > 256 {
+            return Err(anyhow!(
+                "imu: rate_hz {} impossible avec dlpf_hz {} (diviseur entier de {} Hz, au plus 256)",
+                rate_hz,
+                dlpf_hz,
+                output_hz
+            ));
+        }
+        // Repliement du spectre: le filtre doit couper sous la moitié de la fréquence des mesures
+        if 2 * dlpf_hz as u32 > rate_hz {
+            return Err(anyhow!(
+                "imu: dlpf_hz {} supérieur à la moitié de rate_hz {}",
+                dlpf_hz,
+                rate_hz
+            ));
+        }
+
+        Ok(Self {
+            accel_sel,
+            gyro_sel,
+            dlpf_cfg,
+            divider: (output_hz / rate_hz - 1) as u8,
+            accel_lsb,
+            gyro_lsb,
+        })
+    }
+
+    /// Accélération (g) d'une mesure brute
+    pub fn accel_g(&self, raw: f32) -> f32 {
+        raw / self.accel_lsb
+    }
+
+    /// Vitesse angulaire (°/s) d'une mesure brute
+    pub fn gyro_dps(&self, raw: f32) -> f32 {
+        raw / self.gyro_lsb
+    }
+}
@@ -0,0 +1,118 @@
+use anyhow::anyhow;
+use nalgebra::{Matrix3, SMatrix, SVector, SymmetricEigen, Vector3};
+use serde::Deserialize;
+
+use crate::config::MagCalibration;
+
+/// Commande de calibration écrite dans la base (control:mag_calibration)
+#[derive(Clone, Copy, Debug, PartialEq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Command {
+    /// Début de la collecte des mesures: faire tourner le véhicule dans toutes les directions
+    Start,
+    /// Fin de la collecte, ajustement et enregistrement de la calibration
+    Stop,
+    /// Abandon de la collecte, la calibration actuelle est conservée
+    Cancel,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+pub struct Request {
+    pub command: Command,
+}
+
+/// Résultat de l'ajustement
+#[derive(Clone, Copy, Debug)]
+pub struct Fit {
+    pub calibration: MagCalibration,
+    /// Norme du champ corrigé (unités brutes)
+    pub radius: f32,
+    /// Ecart quadratique moyen de la norme du champ corrigé, relatif au rayon
+    pub residual: f32,
+}
+
+/// Ajuste un ellipsoïde aux mesures brutes (moindres carrés): son centre donne le décalage
+/// (hard iron), sa forme la matrice qui le ramène à une sphère de même volume (soft iron)
+pub fn fit(samples: &[(i16, i16, i16)]) -> anyhow::Result<Fit> {
+    if samples.len() < 20 {
+        return Err(anyhow!("{} mesure(s), au moins 20 nécessaires", samples.len()));
+    }
+
+    // Mesures centrées et réduites: conditionnement du système
+    let points: Vec<Vector3<f64>> = samples
+        .iter()
+        .map(|(x, y, z)| Vector3::new(*x as f64, *y as f64, *z as f64))
+        .collect();
+    let mean = points.iter().sum::<Vector3<f64>>() / points.len() as f64;
+    let scale = points.iter().map(|point| (point - mean).norm()).fold(0.0, f64::max);
+    if scale == 0.0 {
+        return Err(anyhow!("Mesures identiques, capteur immobile"));
+    }
+
+    // x² + y² + z² + 2xy + 2xz + 2yz + 2x + 2y + 2z = 1
+    let mut normal = SMatrix::<f64, 9, 9>::zeros();
+    let mut rhs = SVector::<f64, 9>::zeros();
+    for point in &points {
+        let p = (point - mean) / scale;
+        let row = SVector::<f64, 9>::from_column_slice(&[
+            p.x * p.x,
+            p.y * p.y,
+            p.z * p.z,
+            2.0 * p.x * p.y,
+            2.0 * p.x * p.z,
+            2.0 * p.y * p.z,
+            2.0 * p.x,
+            2.0 * p.y,
+            2.0 * p.z,
+        ]);
+        normal += row * row.transpose();
+        rhs += row;
+    }
+    let u = normal
+        .lu()
+        .solve(&rhs)
+        .ok_or_else(|| anyhow!("Mesures insuffisantes, tourner le véhicule dans toutes les directions"))?;
+
+    let shape = Matrix3::new(u[0], u[3], u[4], u[3], u[1], u[5], u[4], u[5], u[2]);
+    let linear = Vector3::new(u[6], u[7], u[8]);
+    let center = -shape
+        .try_inverse()
+        .ok_or_else(|| anyhow!("Ellipsoïde dégénéré, tourner le véhicule dans toutes les directions"))?
+        * linear;
+
+    // (p - centre)ᵀ A (p - centre) = 1, dans les unités brutes
+    let k = 1.0 + center.dot(&(shape * center));
+    let shape = shape / (k * scale * scale);
+    let eigen = SymmetricEigen::new(shape);
+    if eigen.eigenvalues.iter().any(|value| *value <= 0.0 || !value.is_finite()) {
+        return Err(anyhow!("Mesures insuffisantes, tourner le véhicule dans toutes les directions"));
+    }
+
+    // Racine de A (vers la sphère unité), mise à l'échelle du rayon moyen de l'ellipsoïde
+    let radius = eigen.eigenvalues.iter().product::<f64>().powf(-1.0 / 6.0);
+    let root = eigen.eigenvectors
+        * Matrix3::from_diagonal(&eigen.eigenvalues.map(f64::sqrt))
+        * eigen.eigenvectors.transpose()
+        * radius;
+    let hard_iron = mean + center * scale;
+
+    let calibration = MagCalibration {
+        hard_iron: [hard_iron.x as f32, hard_iron.y as f32, hard_iron.z as f32],
+        soft_iron: [0, 1, 2].map(|row| [0, 1, 2].map(|column| root[(row, column)] as f32)),
+    };
+
+    let squares: f64 = samples
+        .iter()
+        .map(|raw| {
+            let (x, y, z) = calibration.apply(*raw);
+            let norm = ((x * x + y * y + z * z) as f64).sqrt();
+            (norm / radius - 1.0).powi(2)
+        })
+        .sum();
+
+    Ok(Fit {
+        calibration,
+        radius: radius as f32,
+        residual: (squares / samples.len() as f64).sqrt() as f32,
+    })
+}
@@ -0,0 +1,234 @@
+use std::path::Path;
+
+use anyhow::anyhow;
+use chrono::{DateTime, Datelike, Timelike, Utc};
+
+use crate::sensors::reader::GpsData;
+
+// Voir documentation : https://www.ncei.noaa.gov/products/world-magnetic-model (rapport technique)
+
+/// Demi grand axe et aplatissement de l'ellipsoïde WGS84 (km)
+const WGS84_A: f64 = 6378.137;
+const WGS84_F: f64 = 1.0 / 298.257223563;
+/// Rayon de référence du modèle (km)
+const REFERENCE_RADIUS: f64 = 6371.2;
+/// Déplacement (°) au-delà duquel la déclinaison est recalculée
+const RECOMPUTE_DEG: f64 = 0.05;
+
+/// Coefficients de Gauss du World Magnetic Model (fichier WMM.COF)
+#[derive(Clone, Debug)]
+pub struct Model {
+    /// Année de référence des coefficients
+    pub epoch: f64,
+    /// Degré maximal
+    degree: usize,
+    /// g, h et leurs variations annuelles, indexés par [n][m]
+    g: Vec<Vec<f64>>,
+    h: Vec<Vec<f64>>,
+    g_dot: Vec<Vec<f64>>,
+    h_dot: Vec<Vec<f64>>,
+}
+
+impl Model {
+    pub fn load(path: &Path) -> anyhow::Result<Self> {
+        let content = std::fs::read_to_string(path).map_err(|e| anyhow!("{}: {}", path.display(), e))?;
+        Self::parse(&content).map_err(|e| anyhow!("{}: {}", path.display(), e))
+    }
+
+    /// En-tête (année de référence, nom, date), puis une ligne par coefficient:
+    /// n m g h g_dot h_dot. Le fichier se termine par une ligne de 9.
+    pub fn parse(content: &str) -> anyhow::Result<Self> {
+        let mut lines = content.lines().filter(|line| !line.trim().is_empty());
+        let epoch = lines
+            .next()
+            .and_then(|header| header.split_whitespace().next())
+            .and_then(|epoch| epoch.parse::<f64>().ok())
+            .ok_or_else(|| anyhow!("En-tête invalide"))?;
+
+        let mut coefficients = Vec::new();
+        for line in lines {
+            if line.trim_start().starts_with("9999") {
+                break;
+            }
+            let fields: Vec<&str> = line.split_whitespace().collect();
+            let [n, m, g, h, g_dot, h_dot] = fields[..] else {
+                return Err(anyhow!("Ligne invalide: {}", line));
+            };
+            let invalid = |_| anyhow!("Ligne invalide: {}", line);
+            let (n, m) = (n.parse::<usize>().map_err(invalid)?, m.parse::<usize>().map_err(invalid)?);
+            if n == 0 || m > n {
+                return Err(anyhow!("Ligne invalide: {}", line));
+            }
+            let values = [g, h, g_dot, h_dot].map(|value| value.parse::<f64>());
+            let [Ok(g), Ok(h), Ok(g_dot), Ok(h_dot)] = values else {
+                return Err(anyhow!("Ligne invalide: {}", line));
+            };
+            coefficients.push((n, m, [g, h, g_dot, h_dot]));
+        }
+
+        let degree = coefficients.iter().map(|(n, _, _)| *n).max().ok_or_else(|| anyhow!("Aucun coefficient"))?;
+        let empty = vec![vec![0.0; degree + 1]; degree + 1];
+        let mut model = Self {
+            epoch,
+            degree,
+            g: empty.clone(),
+            h: empty.clone(),
+            g_dot: empty.clone(),
+            h_dot: empty,
+        };
+        for (n, m, [g, h, g_dot, h_dot]) in coefficients {
+            model.g[n][m] = g;
+            model.h[n][m] = h;
+            model.g_dot[n][m] = g_dot;
+            model.h_dot[n][m] = h_dot;
+        }
+        Ok(model)
+    }
+
+    /// Déclinaison (°, positive vers l'est) à une position géodésique (°, km) et une date
+    /// (année décimale)
+    pub fn declination(&self, latitude: f64, longitude: f64, altitude_km: f64, year: f64) -> f64 {
+        let (north, east) = self.field(latitude, longitude, altitude_km, year);
+        east.atan2(north).to_degrees()
+    }
+
+    /// Composantes nord et est du champ (nT), dans le repère géodésique
+    fn field(&self, latitude: f64, longitude: f64, altitude_km: f64, year: f64) -> (f64, f64) {
+        let dt = year - self.epoch;
+
+        // Coordonnées géodésiques vers sphériques géocentriques
+        let (sin_lat, cos_lat) = latitude.to_radians().sin_cos();
+        let e2 = WGS84_F * (2.0 - WGS84_F);
+        let rc = WGS84_A / (1.0 - e2 * sin_lat * sin_lat).sqrt();
+        let p = (rc + altitude_km) * cos_lat;
+        let z = (rc * (1.0 - e2) + altitude_km) * sin_lat;
+        let r = (p * p + z * z).sqrt();
+        let geocentric = (z / r).asin();
+
+        // Colatitude géocentrique; aux pôles, la déclinaison n'est pas définie
+        let theta = std::f64::consts::FRAC_PI_2 - geocentric;
+        let (sin_theta, cos_theta) = theta.sin_cos();
+        let sin_theta = sin_theta.max(1e-9);
+        let (p, dp) = legendre(self.degree, sin_theta, cos_theta);
+
+        let lambda = longitude.to_radians();
+        let (mut x, mut y, mut zr) = (0.0, 0.0, 0.0);
+        for n in 1..=self.degree {
+            let ratio = (REFERENCE_RADIUS / r).powi(n as i32 + 2);
+            for m in 0..=n {
+                let g = self.g[n][m] + dt * self.g_dot[n][m];
+                let h = self.h[n][m] + dt * self.h_dot[n][m];
+                let (sin_m, cos_m) = (m as f64 * lambda).sin_cos();
+
+                x += ratio * (g * cos_m + h * sin_m) * dp[n][m];
+                y += ratio * m as f64 * (g * sin_m - h * cos_m) * p[n][m] / sin_theta;
+                zr -= ratio * (n + 1) as f64 * (g * cos_m + h * sin_m) * p[n][m];
+            }
+        }
+
+        // Rotation du repère géocentrique vers le repère géodésique
+        let (sin_psi, cos_psi) = (geocentric - latitude.to_radians()).sin_cos();
+        (x * cos_psi - zr * sin_psi, y)
+    }
+}
+
+/// Fonctions de Legendre associées (semi-normalisées de Schmidt) et leurs dérivées par rapport
+/// à la colatitude, indexées par [n][m]
+fn legendre(degree: usize, sin_theta: f64, cos_theta: f64) -> (Vec<Vec<f64>>, Vec<Vec<f64>>) {
+    let mut p = vec![vec![0.0; degree + 1]; degree + 1];
+    let mut dp = vec![vec![0.0; degree + 1]; degree + 1];
+    p[0][0] = 1.0;
+
+    // Normalisation de Gauss
+    for n in 1..=degree {
+        for m in 0..=n {
+            if m == n {
+                p[n][m] = sin_theta * p[n - 1][m - 1];
+                dp[n][m] = sin_theta * dp[n - 1][m - 1] + cos_theta * p[n - 1][m - 1];
+            } else {
+                let (previous, previous_dp) = if n >= 2 && m <= n - 2 { (p[n - 2][m], dp[n - 2][m]) } else { (0.0, 0.0) };
+                let k = ((n - 1) * (n - 1)) as f64 - (m * m) as f64;
+                let k = if n >= 2 { k / ((2 * n - 1) * (2 * n - 3)) as f64 } else { 0.0 };
+                p[n][m] = cos_theta * p[n - 1][m] - k * previous;
+                dp[n][m] = cos_theta * dp[n - 1][m] - sin_theta * p[n - 1][m] - k * previous_dp;
+            }
+        }
+    }
+
+    // Passage à la normalisation de Schmidt
+    let mut schmidt = 1.0;
+    for n in 1..=degree {
+        schmidt *= (2 * n - 1) as f64 / n as f64;
+        let mut factor = schmidt;
+        for m in 0..=n {
+            if m > 0 {
+                let delta = if m == 1 { 2.0 } else { 1.0 };
+                factor *= ((n - m + 1) as f64 * delta / (n + m) as f64).sqrt();
+            }
+            p[n][m] *= factor;
+            dp[n][m] *= factor;
+        }
+    }
+    (p, dp)
+}
+
+/// Année décimale d'une date
+pub fn decimal_year(time: DateTime<Utc>) -> f64 {
+    let days = if chrono::NaiveDate::from_ymd_opt(time.year(), 2, 29).is_some() { 366.0 } else { 365.0 };
+    time.year() as f64 + (time.ordinal0() as f64 + time.num_seconds_from_midnight() as f64 / 86400.0) / days
+}
+
+/// Cap vrai (°) d'un cap magnétique
+pub fn true_heading(heading: f32, declination: f32) -> f32 {
+    (heading + declination).rem_euclid(360.0)
+}
+
+/// Déclinaison appliquée au cap magnétique: calculée par le modèle à la dernière position GPS,
+/// sinon la valeur fixe de la configuration
+pub struct Declination {
+    fixed: f32,
+    model: Option<Model>,
+    /// Dernier calcul: latitude, longitude et déclinaison
+    computed: Option<(f64, f64, f32)>,
+}
+
+impl Declination {
+    pub fn new(fixed: f32, model: Option<Model>) -> Self {
+        Self {
+            fixed,
+            model,
+            computed: None,
+        }
+    }
+
+    /// Déclinaison (°) pour la dernière position connue (latitude, longitude), recalculée après
+    /// un déplacement. Sans modèle ni position: valeur fixe.
+    pub fn update(&mut self, position: Option<(f64, f64)>, time: DateTime<Utc>) -> f32 {
+        let Some(model) = self.model.as_ref() else {
+            return self.fixed;
+        };
+
+        match (position, self.computed) {
+            (Some((latitude, longitude)), Some((last_latitude, last_longitude, _)))
+                if (latitude - last_latitude).abs() > RECOMPUTE_DEG || (longitude - last_longitude).abs() > RECOMPUTE_DEG =>
+            {
+                let declination = model.declination(latitude, longitude, 0.0, decimal_year(time)) as f32;
+                self.computed = Some((latitude, longitude, declination));
+            }
+            (Some((latitude, longitude)), None) => {
+                let declination = model.declination(latitude, longitude, 0.0, decimal_year(time)) as f32;
+                tracing::info!(target: "mag", "Déclinaison à {:.3}, {:.3}: {:.2}°", latitude, longitude, declination);
+                self.computed = Some((latitude, longitude, declination));
+            }
+            _ => {}
+        }
+
+        self.computed.map_or(self.fixed, |(_, _, declination)| declination)
+    }
+
+    /// Déclinaison à la dernière position du GPS, à sa date (sinon la date du système)
+    pub fn at_gps(&mut self, gps: &GpsData) -> f32 {
+        let position = gps.fix.then_some((gps.latitude, gps.longitude));
+        self.update(position, gps.time.unwrap_or_else(Utc::now))
+    }
+}
@@ -13,33 +13,14 @@ use std::time::Duration;
 use std::time::Instant;
 use std::{error::Error, f32::consts::PI};
 
-pub (crate) struct HMC8553L {
-    mag_decl: f32,
-    hard_cal: Vector3<f32>,
-    soft_cal: Matrix3<f32>,
-}
+pub (crate) struct HMC8553L {}
 
 impl HMC8553L {
     /// Constructeur
     pub (crate) fn new(i2c: &mut I2c) -> anyhow::Result<Self> {
         // Créer l'objet et commence l'initialisation
-        // NOTE : Pour obtenir les données de calibration, utiliser la partie "RAW" sur l'UI puis
-        // le script : https://github.com/nliaudat/magnetometer_calibration/
-        let mut mag = Self {
-            mag_decl: 2.44,
-            hard_cal: Vector3::new(569.68423502, 246.04798002, -166.97661026),
-            soft_cal: Matrix3::new(
-                1.08480289,
-                -0.04408938,
-                0.06070396,
-                -0.04408938,
-                1.03604676,
-                0.09354455,
-                0.06070396,
-                0.09354455,
-                0.99634431,
-            ),
-        };
+        // NOTE : La correction "Hard Iron" & "Soft Iron" est appliquée par la source (sensors.mag.calibration)
+        let mut mag = Self {};
 
         // Prépare le module à être utilisé
         mag.set_slave(i2c)?;
@@ -48,6 +29,22 @@ impl HMC8553L {
         Ok(mag)
     }
 
+    /// Vérifie l'identité du capteur (registres d'identification), avant toute initialisation
+    pub (crate) fn identify(i2c: &mut I2c) -> anyhow::Result<String> {
+        i2c.set_slave_address(registry::HMC8553L_MAG_ADDR)?;
+        let id = [
+            i2c.lecture_word(registry::HMC8553L_ID_A)?,
+            i2c.lecture_word(registry::HMC8553L_ID_B)?,
+            i2c.lecture_word(registry::HMC8553L_ID_C)?,
+        ];
+
+        if id != registry::HMC8553L_ID {
+            return Err(anyhow!("Identifiant inattendu: {:02x?}", id));
+        }
+
+        Ok(format!("ID: {}", String::from_utf8_lossy(&id)))
+    }
+
     fn set_slave(&self, i2c: &mut I2c) -> anyhow::Result<()> {
         i2c.set_slave_address(registry::HMC8553L_MAG_ADDR);
         Ok(())
@@ -55,19 +52,19 @@ impl HMC8553L {
 
     /// Initialise rapidement le module avec des valeurs pré-défini
     fn init_module(&mut self, i2c: &mut I2c) -> anyhow::Result<()> {
-        println!("[HMC8554L] Initialisation (CONF A) ...");
+        tracing::info!(target: "mag", "Initialisation (CONF A) ...");
 
         // Configuration par défaut pour le HMC8553L
         i2c.ecriture_word(registry::HMC8553L_CONF_A, 0x10)?;
 
-        println!("[HMC8554L] Initialisation (CONF B) ...");
+        tracing::info!(target: "mag", "Initialisation (CONF B) ...");
         i2c.ecriture_word(registry::HMC8553L_CONF_B, 0x20)?;
 
         // Activation de la mesure continue
-        println!("[HMC8554L] Initialisation (MODE) ...");
+        tracing::info!(target: "mag", "Initialisation (MODE) ...");
         i2c.ecriture_word(registry::HMC8553L_MODE, 0x00)?;
 
-        println!("[HMC8554L] Fin d'initialisation.");
+        tracing::info!(target: "mag", "Fin d'initialisation.");
 
         Ok(())
     }
@@ -106,30 +103,9 @@ impl HMC8553L {
         Ok(Vector3::new(raw_x, raw_y, raw_z))
     }
 
-    /// Récupére le heading
-    pub (crate) fn get_heading(&self, i2c: &mut I2c) -> anyhow::Result<f32> {
-        // Défini mon capteur sur le bus I2C
-        self.set_slave(i2c)?;
-
-        // Récupération des données brut
-        let raw_x = self.get_mag_x_raw(i2c)? as f32;
-        let raw_y = self.get_mag_y_raw(i2c)? as f32;
-        let raw_z = self.get_mag_z_raw(i2c)? as f32;
-
-        // Correction "Hard Iron" & "Soft Iron"
-        let hard_mag = Matrix1x3::new(
-            raw_x - self.hard_cal.x,
-            raw_y - self.hard_cal.y,
-            raw_z - self.hard_cal.z,
-        );
-        let corrected_mag = hard_mag * self.soft_cal;
-
-        // Calcul du heading, prend en compte la déclinaison magnétique
-        let mut heading = (-((corrected_mag.x.atan2(corrected_mag.y) * (180.0 / PI)) + self.mag_decl)) + 180.0;
-        if (heading < 0.0) {
-            heading = heading + 360.0;
-        }
-
-        Ok(heading)
+    /// Calcul du heading magnétique à partir du champ corrigé
+    pub (crate) fn heading(corrected: (f32, f32, f32)) -> f32 {
+        let heading = -(corrected.0.atan2(corrected.1) * (180.0 / PI)) + 180.0;
+        heading.rem_euclid(360.0)
     }
 }
@@ -1,5 +1,8 @@
+pub mod calibration;
+pub mod declination;
+
 #[cfg(feature = "real-sensors")]
 mod registry;
 
 #[cfg(feature = "real-sensors")]
-pub(crate) mod hmc8553l;
\ No newline at end of file
+pub mod hmc8553l;
\ No newline at end of file
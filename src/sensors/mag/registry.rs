@@ -38,4 +38,7 @@ pub const HMC8553L_Z_H: u8 = 0x05;
 pub const HMC8553L_Z_L: u8 = 0x06;
 pub const HMC8553L_Y_H: u8 = 0x07;
 pub const HMC8553L_Y_L: u8 = 0x08;
-
+pub const HMC8553L_ID_A: u8 = 0x0A;
+pub const HMC8553L_ID_B: u8 = 0x0B;
+pub const HMC8553L_ID_C: u8 = 0x0C;
+pub const HMC8553L_ID: [u8; 3] = [b'H', b'4', b'3'];
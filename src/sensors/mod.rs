@@ -1,5 +1,20 @@
 pub mod gps;
 pub mod imu;
 pub mod analog;
+pub mod can;
 pub mod mag;
-pub mod reader;
\ No newline at end of file
+pub mod power;
+pub mod baro;
+pub mod encoder;
+pub mod range;
+pub mod reader;
+pub mod replay;
+pub mod retry;
+pub mod sim;
+pub mod source;
+pub mod supervisor;
+pub mod track;
+pub mod watchdog;
+
+#[cfg(feature = "real-sensors")]
+mod hardware;
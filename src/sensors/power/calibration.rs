@@ -0,0 +1,58 @@
+/// Valeur du registre de calibration: 0.04096 / (LSB du courant x shunt)
+const CALIBRATION_SCALE: f64 = 0.04096;
+
+/// Pleines échelles de la tension du shunt selon le gain (PG = /1, /2, /4, /8)
+const SHUNT_RANGES_V: [f64; 4] = [0.04, 0.08, 0.16, 0.32];
+
+/// Calibration du INA219 pour un shunt et un courant maximal: registre de calibration, gain et
+/// résolution des registres de courant et de puissance
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Calibration {
+    /// Valeur du registre de calibration
+    pub register: u16,
+    /// Gain du shunt (champ PG du registre de configuration)
+    pub gain: u16,
+    /// Courant d'un pas du registre de courant (A)
+    pub current_lsb: f64,
+    /// Puissance d'un pas du registre de puissance (W)
+    pub power_lsb: f64,
+}
+
+impl Calibration {
+    pub fn new(shunt_ohm: f64, max_current_a: f64) -> anyhow::Result<Self> {
+        // Plus petite plage couvrant la chute de tension au courant maximal (0.1 x 3.2 = 0.32)
+        let shunt_v = shunt_ohm * max_current_a;
+        let Some(gain) = SHUNT_RANGES_V.iter().position(|range| shunt_v <= range + 1e-9) else {
+            return Err(anyhow::anyhow!("Tension du shunt {:.3} V hors plage", shunt_v));
+        };
+
+        // Registre sur 15 bits (bit 0 non utilisé), LSB recalculé depuis la valeur retenue
+        let register = (CALIBRATION_SCALE / (max_current_a / 32768.0 * shunt_ohm)).min(65534.0) as u16 & 0xFFFE;
+        if register == 0 {
+            return Err(anyhow::anyhow!("Calibration impossible: shunt {} ohm", shunt_ohm));
+        }
+        let current_lsb = CALIBRATION_SCALE / (register as f64 * shunt_ohm);
+
+        Ok(Self {
+            register,
+            gain: gain as u16,
+            current_lsb,
+            power_lsb: 20.0 * current_lsb,
+        })
+    }
+
+    /// Courant (A) du registre de courant (complément à deux)
+    pub fn current(&self, raw: u16) -> f32 {
+        (raw as i16 as f64 * self.current_lsb) as f32
+    }
+
+    /// Puissance (W) du registre de puissance
+    pub fn power(&self, raw: u16) -> f32 {
+        (raw as f64 * self.power_lsb) as f32
+    }
+}
+
+/// Tension du bus (V) du registre de tension: bits 15 à 3, 4 mV par pas
+pub fn bus_voltage(raw: u16) -> f32 {
+    (raw >> 3) as f32 * 0.004
+}
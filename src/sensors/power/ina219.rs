@@ -0,0 +1,79 @@
+use rppal::i2c::I2c;
+use std::thread::sleep;
+use std::time::Duration;
+
+use crate::i2c::I2CBit;
+use crate::sensors::power::calibration::{self, Calibration};
+use crate::sensors::power::registry;
+
+// Voir documentation : https://www.ti.com/lit/ds/symlink/ina219.pdf
+
+/// Mesure du moniteur de puissance
+#[derive(Clone, Copy, Debug)]
+pub struct Measure {
+    /// Tension du bus (V)
+    pub voltage: f32,
+    /// Courant dans le shunt (A)
+    pub current: f32,
+    /// Puissance (W)
+    pub power: f32,
+}
+
+pub struct Ina219 {
+    address: u16,
+    calibration: Calibration,
+}
+
+impl Ina219 {
+    /// Constructeur
+    pub fn new(i2c: &mut I2c, address: u16, shunt_ohm: f64, max_current_a: f64) -> anyhow::Result<Self> {
+        let power = Ina219 {
+            address,
+            calibration: Calibration::new(shunt_ohm, max_current_a)?,
+        };
+
+        power.set_slave(i2c)?;
+        power.init(i2c)?;
+
+        Ok(power)
+    }
+
+    fn set_slave(&self, i2c: &mut I2c) -> anyhow::Result<()> {
+        i2c.set_slave_address(self.address)?;
+        Ok(())
+    }
+
+    // Réinitialise le module puis configure la plage, le gain et la calibration
+    fn init(&self, i2c: &mut I2c) -> anyhow::Result<()> {
+        tracing::info!(target: "power", "Initialisation ...");
+        i2c.ecriture_dword(registry::INA219_CONFIG, registry::INA219_CONFIG_RESET)?;
+        sleep(Duration::from_millis(1));
+
+        let config = registry::INA219_CONFIG_BRNG_32V
+            | self.calibration.gain << registry::INA219_CONFIG_PG_BIT
+            | registry::INA219_CONFIG_BADC_12BIT
+            | registry::INA219_CONFIG_SADC_12BIT
+            | registry::INA219_CONFIG_MODE_SHUNT_BUS_CONTINUOUS;
+        i2c.ecriture_dword(registry::INA219_CONFIG, config)?;
+        i2c.ecriture_dword(registry::INA219_CALIBRATION, self.calibration.register)
+    }
+
+    /// Récupère la tension, le courant et la puissance
+    pub fn get_measure(&self, i2c: &mut I2c) -> anyhow::Result<Measure> {
+        self.set_slave(i2c)?;
+
+        // Calibration perdue si le capteur a redémarré (chute de tension): réécrite à chaque lecture
+        i2c.ecriture_dword(registry::INA219_CALIBRATION, self.calibration.register)?;
+
+        let bus = i2c.lecture_dword(registry::INA219_BUS_VOLTAGE)?;
+        if bus & (1 << registry::INA219_BUS_VOLTAGE_OVF_BIT) != 0 {
+            return Err(anyhow::anyhow!("Dépassement de la plage du courant ou de la puissance"));
+        }
+
+        Ok(Measure {
+            voltage: calibration::bus_voltage(bus),
+            current: self.calibration.current(i2c.lecture_dword(registry::INA219_CURRENT)?),
+            power: self.calibration.power(i2c.lecture_dword(registry::INA219_POWER)?),
+        })
+    }
+}
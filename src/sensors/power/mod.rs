@@ -0,0 +1,6 @@
+#[cfg(feature = "real-sensors")]
+mod registry;
+
+pub mod calibration;
+#[cfg(feature = "real-sensors")]
+pub mod ina219;
@@ -0,0 +1,23 @@
+#![allow(unused)]
+
+pub const INA219_CONFIG: u8 = 0x0;
+pub const INA219_SHUNT_VOLTAGE: u8 = 0x1;
+pub const INA219_BUS_VOLTAGE: u8 = 0x2;
+pub const INA219_POWER: u8 = 0x3;
+pub const INA219_CURRENT: u8 = 0x4;
+pub const INA219_CALIBRATION: u8 = 0x5;
+
+pub const INA219_CONFIG_RESET: u16 = 0x8000;
+
+pub const INA219_CONFIG_BRNG_16V: u16 = 0x0000;
+pub const INA219_CONFIG_BRNG_32V: u16 = 0x2000;
+
+pub const INA219_CONFIG_PG_BIT: u8 = 11;
+
+pub const INA219_CONFIG_BADC_12BIT: u16 = 0b0011 << 7;
+pub const INA219_CONFIG_SADC_12BIT: u16 = 0b0011 << 3;
+
+pub const INA219_CONFIG_MODE_SHUNT_BUS_CONTINUOUS: u16 = 0b111;
+
+pub const INA219_BUS_VOLTAGE_OVF_BIT: u8 = 0;
+pub const INA219_BUS_VOLTAGE_CNVR_BIT: u8 = 1;
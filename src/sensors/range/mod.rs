@@ -0,0 +1,5 @@
+#[cfg(feature = "real-sensors")]
+mod registry;
+
+#[cfg(feature = "real-sensors")]
+pub mod vl53l1x;
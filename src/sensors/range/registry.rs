@@ -0,0 +1,45 @@
+#![allow(unused)]
+
+// Registres sur 16 bits (adresse de poids fort en premier)
+
+pub const VL53L1X_SOFT_RESET: u16 = 0x0000;
+pub const VL53L1X_VHV_CONFIG_TIMEOUT_MACROP_LOOP_BOUND: u16 = 0x0008;
+pub const VL53L1X_VHV_CONFIG_INIT: u16 = 0x000B;
+/// Début de la configuration par défaut (0x2D à 0x87)
+pub const VL53L1X_DEFAULT_CONFIG: u16 = 0x002D;
+pub const VL53L1X_GPIO_HV_MUX_CTRL: u16 = 0x0030;
+pub const VL53L1X_GPIO_TIO_HV_STATUS: u16 = 0x0031;
+pub const VL53L1X_SYSTEM_INTERRUPT_CLEAR: u16 = 0x0086;
+pub const VL53L1X_SYSTEM_MODE_START: u16 = 0x0087;
+pub const VL53L1X_RESULT_RANGE_STATUS: u16 = 0x0089;
+/// Distance corrigée (mm, 2 octets)
+pub const VL53L1X_RESULT_RANGE_MM: u16 = 0x0096;
+pub const VL53L1X_FIRMWARE_SYSTEM_STATUS: u16 = 0x00E5;
+/// Identifiant du modèle (2 octets)
+pub const VL53L1X_MODEL_ID: u16 = 0x010F;
+
+pub const VL53L1X_MODEL_ID_VAL: u16 = 0xEACC;
+
+/// Mesure continue
+pub const VL53L1X_MODE_START_VAL: u8 = 0x40;
+pub const VL53L1X_MODE_STOP_VAL: u8 = 0x00;
+pub const VL53L1X_INTERRUPT_CLEAR_VAL: u8 = 0x01;
+/// Polarité de l'interruption (GPIO_HV_MUX_CTRL): bit 4 à 0, active à l'état haut
+pub const VL53L1X_INTERRUPT_POLARITY_BIT: u8 = 4;
+
+/// Codes de RESULT_RANGE_STATUS (5 bits de poids faible)
+pub const VL53L1X_STATUS_SIGNAL_FAIL: u8 = 4;
+pub const VL53L1X_STATUS_OUT_OF_BOUNDS: u8 = 5;
+pub const VL53L1X_STATUS_WRAP_AROUND: u8 = 7;
+pub const VL53L1X_STATUS_VALID: u8 = 9;
+
+/// Configuration par défaut du pilote ST (ULD), registres 0x2D à 0x87: interruption par
+/// scrutation de GPIO_TIO_HV_STATUS, mesure arrêtée
+pub const VL53L1X_DEFAULT_CONFIG_VAL: [u8; 91] = [
+    0x00, 0x00, 0x00, 0x01, 0x02, 0x00, 0x02, 0x08, 0x00, 0x08, 0x10, 0x01, 0x01, 0x00, 0x00, 0x00,
+    0x00, 0xFF, 0x00, 0x0F, 0x00, 0x00, 0x00, 0x00, 0x00, 0x20, 0x0B, 0x00, 0x00, 0x02, 0x0A, 0x21,
+    0x00, 0x00, 0x05, 0x00, 0x00, 0x00, 0x00, 0xC8, 0x00, 0x00, 0x38, 0xFF, 0x01, 0x00, 0x08, 0x00,
+    0x00, 0x01, 0xCC, 0x0F, 0x01, 0xF1, 0x0D, 0x01, 0x68, 0x00, 0x80, 0x08, 0xB8, 0x00, 0x00, 0x00,
+    0x00, 0x0F, 0x89, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x01, 0x0F, 0x0D, 0x0E, 0x0E, 0x00,
+    0x00, 0x02, 0xC7, 0xFF, 0x9B, 0x00, 0x00, 0x00, 0x01, 0x00, 0x00,
+];
@@ -0,0 +1,126 @@
+use rppal::i2c::I2c;
+use std::thread::sleep;
+use std::time::{Duration, Instant};
+
+use anyhow::anyhow;
+
+use crate::sensors::range::registry;
+use crate::sensors::reader::RangeStatus;
+
+// Voir documentation : https://www.st.com/resource/en/datasheet/vl53l1x.pdf
+// Séquence d'initialisation du pilote ST (UM2510, "ultra lite driver")
+
+/// Attente maximale du démarrage du capteur et de la première mesure
+const BOOT_TIMEOUT: Duration = Duration::from_millis(500);
+
+/// Mesure de distance
+pub struct Measure {
+    pub status: RangeStatus,
+    /// Distance (mm), mesure valide uniquement
+    pub distance_mm: Option<u16>,
+}
+
+pub struct Vl53l1x {
+    address: u16,
+    /// Niveau de GPIO_TIO_HV_STATUS signalant une nouvelle mesure
+    ready_level: u8,
+}
+
+impl Vl53l1x {
+    /// Constructeur, configure le capteur et démarre la mesure continue
+    pub fn new(i2c: &mut I2c, address: u16) -> anyhow::Result<Self> {
+        i2c.set_slave_address(address)?;
+        let id = read_u16(i2c, registry::VL53L1X_MODEL_ID)?;
+        if id != registry::VL53L1X_MODEL_ID_VAL {
+            return Err(anyhow!("Identifiant inattendu: {:#06x}", id));
+        }
+
+        tracing::info!(target: "range", "Initialisation ...");
+        let start = Instant::now();
+        while read_u8(i2c, registry::VL53L1X_FIRMWARE_SYSTEM_STATUS)? & 0x01 == 0 {
+            if start.elapsed() > BOOT_TIMEOUT {
+                return Err(anyhow!("Démarrage du capteur non terminé"));
+            }
+            sleep(Duration::from_millis(2));
+        }
+
+        let mut config = vec![0u8; 2 + registry::VL53L1X_DEFAULT_CONFIG_VAL.len()];
+        config[..2].copy_from_slice(&registry::VL53L1X_DEFAULT_CONFIG.to_be_bytes());
+        config[2..].copy_from_slice(&registry::VL53L1X_DEFAULT_CONFIG_VAL);
+        i2c.write(&config)?;
+
+        let mux = read_u8(i2c, registry::VL53L1X_GPIO_HV_MUX_CTRL)?;
+        let sensor = Vl53l1x {
+            address,
+            ready_level: !(mux >> registry::VL53L1X_INTERRUPT_POLARITY_BIT) & 0x01,
+        };
+
+        // Première mesure jetée, puis calibration de la température (VHV) au démarrage uniquement
+        write_u8(i2c, registry::VL53L1X_SYSTEM_MODE_START, registry::VL53L1X_MODE_START_VAL)?;
+        let start = Instant::now();
+        while !sensor.ready(i2c)? {
+            if start.elapsed() > BOOT_TIMEOUT {
+                return Err(anyhow!("Aucune mesure après l'initialisation"));
+            }
+            sleep(Duration::from_millis(2));
+        }
+        write_u8(i2c, registry::VL53L1X_SYSTEM_INTERRUPT_CLEAR, registry::VL53L1X_INTERRUPT_CLEAR_VAL)?;
+        write_u8(i2c, registry::VL53L1X_SYSTEM_MODE_START, registry::VL53L1X_MODE_STOP_VAL)?;
+        write_u8(i2c, registry::VL53L1X_VHV_CONFIG_TIMEOUT_MACROP_LOOP_BOUND, 0x09)?;
+        write_u8(i2c, registry::VL53L1X_VHV_CONFIG_INIT, 0x00)?;
+
+        write_u8(i2c, registry::VL53L1X_SYSTEM_MODE_START, registry::VL53L1X_MODE_START_VAL)?;
+        Ok(sensor)
+    }
+
+    /// Nouvelle mesure disponible
+    fn ready(&self, i2c: &mut I2c) -> anyhow::Result<bool> {
+        Ok(read_u8(i2c, registry::VL53L1X_GPIO_TIO_HV_STATUS)? & 0x01 == self.ready_level)
+    }
+
+    /// Récupère la dernière mesure, None: aucune nouvelle mesure
+    pub fn get_measure(&self, i2c: &mut I2c) -> anyhow::Result<Option<Measure>> {
+        i2c.set_slave_address(self.address)?;
+        if !self.ready(i2c)? {
+            return Ok(None);
+        }
+
+        let status = status(read_u8(i2c, registry::VL53L1X_RESULT_RANGE_STATUS)?);
+        let distance = read_u16(i2c, registry::VL53L1X_RESULT_RANGE_MM)?;
+        write_u8(i2c, registry::VL53L1X_SYSTEM_INTERRUPT_CLEAR, registry::VL53L1X_INTERRUPT_CLEAR_VAL)?;
+
+        Ok(Some(Measure {
+            status,
+            distance_mm: (status == RangeStatus::Valid).then_some(distance),
+        }))
+    }
+}
+
+/// Résultat d'une mesure selon RESULT_RANGE_STATUS
+fn status(raw: u8) -> RangeStatus {
+    match raw & 0x1F {
+        registry::VL53L1X_STATUS_VALID => RangeStatus::Valid,
+        registry::VL53L1X_STATUS_SIGNAL_FAIL | registry::VL53L1X_STATUS_OUT_OF_BOUNDS | registry::VL53L1X_STATUS_WRAP_AROUND => {
+            RangeStatus::OutOfRange
+        }
+        _ => RangeStatus::Invalid,
+    }
+}
+
+fn write_u8(i2c: &mut I2c, register: u16, value: u8) -> anyhow::Result<()> {
+    let [high, low] = register.to_be_bytes();
+    i2c.write(&[high, low, value])?;
+    Ok(())
+}
+
+fn read_u8(i2c: &mut I2c, register: u16) -> anyhow::Result<u8> {
+    let mut value = [0u8; 1];
+    i2c.write_read(&register.to_be_bytes(), &mut value)?;
+    Ok(value[0])
+}
+
+fn read_u16(i2c: &mut I2c, register: u16) -> anyhow::Result<u16> {
+    let mut value = [0u8; 2];
+    i2c.write_read(&register.to_be_bytes(), &mut value)?;
+    Ok(u16::from_be_bytes(value))
+}
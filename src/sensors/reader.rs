@@ -1,233 +1,670 @@
+use chrono::{DateTime, Utc};
 use futures::Stream;
-use nmea_parser::gnss::GgaQualityIndicator;
-use nmea_parser::ParsedMessage;
-use rppal::i2c::I2c;
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 use std::pin::Pin;
-use std::sync::{Arc, Mutex};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{mpsc, Arc, Mutex};
 use std::task::Poll;
 use std::thread;
+use std::time::{Duration, Instant};
+use tokio::sync::watch;
 use tokio_util::sync::CancellationToken;
 
-use crate::sensors::{analog, gps, imu, mag};
-
-#[derive(Clone, Copy, Serialize, Deserialize)]
-pub(crate) struct MagData {
+use crate::clock::{Clock, Stamp};
+use crate::config::{Config, MagCalibration, SensorMode};
+use crate::selftest::SelfTest;
+use crate::sensors::replay::{self, ReplayOptions};
+pub use crate::sensors::sim::MAX_CELLS;
+use crate::sensors::sim::SharedSimulation;
+use crate::sensors::source::{self, Kind};
+use crate::sensors::supervisor::Supervised;
+use crate::timing::{LoopReport, LoopTimer};
+
+/// Intervalle de publication des durées du thread de lecture
+const TIMING_PUBLISH: Duration = Duration::from_secs(1);
+
+/// Intervalle minimal entre deux échantillons de satellites
+pub const SATELLITES_INTERVAL: Duration = Duration::from_secs(1);
+
+#[derive(Clone, Copy, Default, Serialize, Deserialize)]
+pub struct MagData {
+    pub stamp: Stamp,
     pub raw: (i16, i16, i16),
+    /// Cap magnétique (°)
     pub heading: f32,
+    /// Champ corrigé par la calibration (sensors.mag.calibration ou calibration:mag), d'où est
+    /// calculé le cap
+    #[serde(default)]
+    pub calibrated: (f32, f32, f32),
+    /// Cap vrai (°): cap magnétique corrigé de la déclinaison
+    #[serde(default)]
+    pub true_heading: f32,
+    /// Déclinaison appliquée (°, positive vers l'est)
+    #[serde(default)]
+    pub declination: f32,
 }
 
-#[derive(Clone, Copy, Serialize, Deserialize)]
-pub(crate) struct ImuData {
+#[derive(Clone, Copy, Default, Serialize, Deserialize)]
+pub struct ImuData {
+    pub stamp: Stamp,
+    /// Tangage, roulis, lacet (degrés)
     pub angles: (f32, f32, f32),
     pub temp: f32,
+    /// Accélération X, Y, Z (g), avant la fusion
+    #[serde(default)]
+    pub accel: (f32, f32, f32),
+    /// Vitesse angulaire X, Y, Z (°/s), biais du démarrage retiré, avant la fusion
+    #[serde(default)]
+    pub gyro: (f32, f32, f32),
 }
 
-#[derive(Clone, Copy, Serialize, Deserialize)]
-pub(crate) struct AnalogData {
+#[derive(Clone, Copy, Default, Serialize, Deserialize)]
+pub struct AnalogData {
+    pub stamp: Stamp,
     pub battery: f32,
+    /// Tension de chaque élément (V), de l'élément le plus bas, None: non mesuré
+    #[serde(default, skip_serializing_if = "no_cells")]
+    pub cells: [Option<f32>; MAX_CELLS],
+    /// Courant de la batterie (A), None: non mesuré
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub current: Option<f32>,
+}
+
+/// Moniteur de puissance (INA219) de l'alimentation de l'ESC
+#[derive(Clone, Copy, Default, Serialize, Deserialize)]
+pub struct PowerData {
+    pub stamp: Stamp,
+    /// Tension du bus (V)
+    pub voltage: f32,
+    /// Courant (A)
+    pub current: f32,
+    /// Puissance (W)
+    pub power: f32,
+}
+
+/// Baromètre (BMP280, BME280)
+#[derive(Clone, Copy, Default, Serialize, Deserialize)]
+pub struct BaroData {
+    pub stamp: Stamp,
+    /// Pression (Pa)
+    pub pressure: f32,
+    /// Température ambiante (°C)
+    pub temperature: f32,
+    /// Humidité relative (%), None: BMP280
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub humidity: Option<f32>,
+    /// Altitude relative au démarrage (m)
+    pub altitude: f32,
 }
 
-#[derive(Clone, Copy, Serialize, Deserialize)]
-pub(crate) struct GpsData {
+/// Encodeur de roue (capteur à effet Hall)
+#[derive(Clone, Copy, Default, Serialize, Deserialize)]
+pub struct EncoderData {
+    pub stamp: Stamp,
+    /// Vitesse des roues (km/h)
+    pub speed_kmh: f64,
+    /// Distance parcourue depuis le démarrage (m)
+    pub distance_m: f64,
+    /// Impulsions comptées depuis le démarrage
+    pub pulses: u64,
+}
+
+/// Résultat d'une mesure de distance
+#[derive(Clone, Copy, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RangeStatus {
+    /// Distance mesurée
+    Valid,
+    /// Aucune cible à portée (signal trop faible, hors de la plage de mesure)
+    OutOfRange,
+    /// Mesure rejetée par le capteur (bruit, cible trop proche, erreur interne)
+    Invalid,
+    /// Aucune mesure du capteur pendant le délai (range.timeout_ms)
+    #[default]
+    Timeout,
+}
+
+impl RangeStatus {
+    pub fn name(&self) -> &'static str {
+        match self {
+            RangeStatus::Valid => "valid",
+            RangeStatus::OutOfRange => "out_of_range",
+            RangeStatus::Invalid => "invalid",
+            RangeStatus::Timeout => "timeout",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        [RangeStatus::Valid, RangeStatus::OutOfRange, RangeStatus::Invalid, RangeStatus::Timeout]
+            .into_iter()
+            .find(|status| status.name() == name)
+    }
+}
+
+/// Capteur de distance vers l'avant (VL53L1X)
+#[derive(Clone, Copy, Default, Serialize, Deserialize)]
+pub struct RangeData {
+    pub stamp: Stamp,
+    /// Distance de l'obstacle (mm), None sauf pour une mesure valide: 0 est une vraie distance
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub distance_mm: Option<u16>,
+    pub status: RangeStatus,
+}
+
+fn no_cells(cells: &[Option<f32>; MAX_CELLS]) -> bool {
+    cells.iter().all(Option::is_none)
+}
+
+#[derive(Clone, Copy, Default, Serialize, Deserialize)]
+pub struct GpsData {
+    pub stamp: Stamp,
     pub speed_kmh: f64,
     pub latitude: f64,
     pub longitude: f64,
     pub satellites: u8,
     pub fix: bool,
     pub heading: f64,
+    /// Date et heure UTC du récepteur (RMC), None: aucune trame RMC reçue
+    #[serde(default)]
+    pub time: Option<DateTime<Utc>>,
+    /// Dernière trame RMC active (A), faux: aucune position (V) ou aucune trame RMC
+    #[serde(default)]
+    pub valid: bool,
 }
 
-#[derive(Clone)]
-pub(crate) struct Data {
+/// Satellites et précision du GPS (GSA, GSV), un échantillon par seconde au plus
+#[derive(Clone, Copy, Default, Serialize, Deserialize)]
+pub struct SatellitesData {
+    pub stamp: Stamp,
+    /// Dilution de la précision: position, horizontale, verticale
+    pub pdop: f64,
+    pub hdop: f64,
+    pub vdop: f64,
+    /// Satellites utilisés pour la position
+    pub used: u8,
+    /// Satellites visibles, cycle GSV complet de chaque constellation
+    pub in_view: u8,
+    /// Rapport signal/bruit moyen des satellites visibles reçus (dB-Hz), 0: aucun
+    pub snr_mean: f64,
+}
+
+/// Etat d'initialisation d'un capteur
+#[derive(Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct SensorStatus {
+    pub available: bool,
+    pub error: Option<String>,
+    pub attempts: u32,
+    /// Redémarrages par le superviseur (panique, capteur bloqué)
+    #[serde(default)]
+    pub restarts: u32,
+}
+
+#[derive(Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct SensorsStatus {
+    pub imu: SensorStatus,
+    pub mag: SensorStatus,
+    pub analog: SensorStatus,
+    #[serde(default)]
+    pub power: SensorStatus,
+    #[serde(default)]
+    pub baro: SensorStatus,
+    #[serde(default)]
+    pub encoder: SensorStatus,
+    #[serde(default)]
+    pub range: SensorStatus,
+    pub gps: SensorStatus,
+    /// Bus I2C des capteurs réels par numéro de bus, vide: aucun capteur I2C réel
+    #[serde(default)]
+    pub i2c: BTreeMap<u8, SensorStatus>,
+}
+
+#[derive(Clone, Copy, Default, Serialize, Deserialize)]
+pub struct Data {
     pub mag: MagData,
     pub imu: ImuData,
     pub analog: AnalogData,
+    #[serde(default)]
+    pub power: PowerData,
+    #[serde(default)]
+    pub baro: BaroData,
+    #[serde(default)]
+    pub encoder: EncoderData,
+    #[serde(default)]
+    pub range: RangeData,
     pub gps: GpsData,
+    #[serde(default)]
+    pub satellites: SatellitesData,
 }
 
-pub(crate) struct Reader {
-    data: Arc<Mutex<Data>>,
-    token: CancellationToken,
+impl MagData {
+    /// Vérifie la plausibilité d'un échantillon
+    pub fn plausible(&self) -> anyhow::Result<String> {
+        if self.raw == (0, 0, 0) {
+            return Err(anyhow::anyhow!("Aucun champ mesuré"));
+        }
+
+        // -4096: dépassement de la plage de mesure
+        if self.raw.0 == -4096 || self.raw.1 == -4096 || self.raw.2 == -4096 {
+            return Err(anyhow::anyhow!("Saturation: {:?}", self.raw));
+        }
+
+        Ok(format!("Cap: {:.1}°", self.heading))
+    }
 }
 
-impl Reader {
+impl ImuData {
+    /// Vérifie la plausibilité d'un échantillon
+    pub fn plausible(&self) -> anyhow::Result<String> {
+        if !(-40.0..=85.0).contains(&self.temp) {
+            return Err(anyhow::anyhow!("Température hors plage: {:.1}°C", self.temp));
+        }
+
+        let (x, y, z) = self.angles;
+        if !x.is_finite() || !y.is_finite() || !z.is_finite() {
+            return Err(anyhow::anyhow!("Angles invalides: {:?}", self.angles));
+        }
+
+        Ok(format!("Température: {:.1}°C", self.temp))
+    }
+}
+
+impl AnalogData {
+    /// Tensions des éléments par différence entre les tensions cumulées de prises successives,
+    /// jusqu'à la première prise non mesurée
+    pub fn cells_from_taps(taps: [Option<f32>; MAX_CELLS]) -> [Option<f32>; MAX_CELLS] {
+        let mut cells = [None; MAX_CELLS];
+        let mut below = 0.0;
+        for (cell, tap) in cells.iter_mut().zip(taps.into_iter().map_while(|tap| tap)) {
+            *cell = Some(tap - below);
+            below = tap;
+        }
+        cells
+    }
+
+    /// Eléments mesurés
+    pub fn measured_cells(&self) -> Vec<f32> {
+        self.cells.iter().flatten().copied().collect()
+    }
+
+    /// Vérifie la plausibilité d'un échantillon
+    pub fn plausible(&self) -> anyhow::Result<String> {
+        if !(1.0..=30.0).contains(&self.battery) {
+            return Err(anyhow::anyhow!("Tension batterie hors plage: {:.2}V", self.battery));
+        }
+
+        Ok(format!("Batterie: {:.2}V", self.battery))
+    }
+}
+
+impl PowerData {
+    /// Vérifie la plausibilité d'un échantillon
+    pub fn plausible(&self) -> anyhow::Result<String> {
+        if !(0.0..=32.0).contains(&self.voltage) {
+            return Err(anyhow::anyhow!("Tension hors plage: {:.2}V", self.voltage));
+        }
+
+        Ok(format!("{:.2}V, {:.2}A", self.voltage, self.current))
+    }
+}
+
+impl BaroData {
+    /// Vérifie la plausibilité d'un échantillon
+    pub fn plausible(&self) -> anyhow::Result<String> {
+        if !(30_000.0..=110_000.0).contains(&self.pressure) {
+            return Err(anyhow::anyhow!("Pression hors plage: {:.0}Pa", self.pressure));
+        }
+
+        if !(-40.0..=85.0).contains(&self.temperature) {
+            return Err(anyhow::anyhow!("Température hors plage: {:.1}°C", self.temperature));
+        }
+
+        Ok(format!("{:.0}Pa, {:.1}°C", self.pressure, self.temperature))
+    }
+}
+
+impl EncoderData {
+    /// Vérifie la plausibilité d'un échantillon
+    pub fn plausible(&self) -> anyhow::Result<String> {
+        if !(0.0..=200.0).contains(&self.speed_kmh) {
+            return Err(anyhow::anyhow!("Vitesse hors plage: {:.1}km/h", self.speed_kmh));
+        }
+
+        Ok(format!("{:.1}km/h, {:.1}m", self.speed_kmh, self.distance_m))
+    }
+}
+
+impl RangeData {
+    /// Vérifie la plausibilité d'un échantillon
+    pub fn plausible(&self) -> anyhow::Result<String> {
+        match (self.status, self.distance_mm) {
+            (RangeStatus::Valid, Some(distance)) => Ok(format!("{}mm", distance)),
+            (RangeStatus::Valid, None) => Err(anyhow::anyhow!("Mesure valide sans distance")),
+            (RangeStatus::Timeout, _) => Err(anyhow::anyhow!("Aucune mesure du capteur")),
+            (status, _) => Ok(format!("Aucune distance ({})", status.name())),
+        }
+    }
+}
+
+impl GpsData {
+    /// Vérifie la plausibilité d'un échantillon
+    pub fn plausible(&self) -> anyhow::Result<String> {
+        if self.latitude.abs() > 90.0 || self.longitude.abs() > 180.0 {
+            return Err(anyhow::anyhow!("Position invalide: {}, {}", self.latitude, self.longitude));
+        }
+
+        Ok(format!("Trames NMEA reçues, {} satellites", self.satellites))
+    }
+}
+
+impl Data {
+    /// Vérifie la plausibilité de l'échantillon d'un capteur
+    pub fn plausible(&self, kind: Kind) -> anyhow::Result<String> {
+        match kind {
+            Kind::Imu => self.imu.plausible(),
+            Kind::Mag => self.mag.plausible(),
+            Kind::Analog => self.analog.plausible(),
+            Kind::Power => self.power.plausible(),
+            Kind::Baro => self.baro.plausible(),
+            Kind::Encoder => self.encoder.plausible(),
+            Kind::Range => self.range.plausible(),
+            Kind::Gps => self.gps.plausible(),
+        }
+    }
+
+    /// Horodate l'échantillon d'un capteur
+    pub fn set_stamp(&mut self, kind: Kind, stamp: Stamp) {
+        match kind {
+            Kind::Imu => self.imu.stamp = stamp,
+            Kind::Mag => self.mag.stamp = stamp,
+            Kind::Analog => self.analog.stamp = stamp,
+            Kind::Power => self.power.stamp = stamp,
+            Kind::Baro => self.baro.stamp = stamp,
+            Kind::Encoder => self.encoder.stamp = stamp,
+            Kind::Range => self.range.stamp = stamp,
+            Kind::Gps => self.gps.stamp = stamp,
+        }
+    }
+
+    /// Recalcule l'horodatage de chaque capteur
+    pub fn restamp(&mut self, f: impl Fn(Stamp) -> Stamp) {
+        self.imu.stamp = f(self.imu.stamp);
+        self.mag.stamp = f(self.mag.stamp);
+        self.analog.stamp = f(self.analog.stamp);
+        self.power.stamp = f(self.power.stamp);
+        self.baro.stamp = f(self.baro.stamp);
+        self.encoder.stamp = f(self.encoder.stamp);
+        self.range.stamp = f(self.range.stamp);
+        self.gps.stamp = f(self.gps.stamp);
+    }
+}
+
+/// Lecture des capteurs: chaque itération interroge toutes les sources une fois.
+/// Appelé à la fréquence des capteurs, sans allocation en fonctionnement normal.
+pub struct Poller {
+    sources: Vec<Supervised>,
+    /// Ressources des sources, pour les recréer
+    context: source::Context,
+    clock: Clock,
+    selftest: SelfTest,
+    pub data: Data,
+    pub status: SensorsStatus,
+    /// Bus I2C des capteurs réels, pour l'état publié
     #[cfg(feature = "real-sensors")]
-    pub(crate) fn new(token: CancellationToken) -> anyhow::Result<Self> {
-        // Initalisation des données
-        let current_data = Data {
-            mag: MagData {
-                raw: (0, 0, 0),
-                heading: 0.0,
-            },
-
-            imu: ImuData {
-                angles: (0.0, 0.0, 0.0),
-                temp: 0.0,
-            },
-
-            analog: AnalogData {
-                battery: 0.0
-            },
-
-            gps: GpsData {
-                speed_kmh: 0.0,
-                latitude: 0.0,
-                longitude: 0.0,
-                satellites: 0,
-                fix: false,
-                heading: 0.0,
+    buses: Option<Arc<Mutex<crate::i2c::I2cBuses>>>,
+}
+
+impl Poller {
+    /// Crée les sources, réelles ou simulées selon la configuration.
+    /// L'ordre compte: l'IMU utilise la vitesse GPS de l'itération précédente.
+    pub fn new(
+        config: &Config,
+        clock: &Clock,
+        simulation: &SharedSimulation,
+        selftest: &SelfTest,
+        mag_calibration: &watch::Receiver<MagCalibration>,
+    ) -> anyhow::Result<Self> {
+        let context = source::Context::new(config, clock, simulation, selftest, mag_calibration)?;
+        let mut sources = Vec::new();
+        for kind in [Kind::Mag, Kind::Imu, Kind::Analog, Kind::Power, Kind::Baro, Kind::Encoder, Kind::Range, Kind::Gps] {
+            let source = source::build(kind, &context)?;
+            sources.push(Supervised::new(kind, source, &config.sensors.supervisor, clock.now()));
+        }
+
+        Ok(Self {
+            sources,
+            #[cfg(feature = "real-sensors")]
+            buses: context.real_i2c.then(|| context.buses.clone()),
+            context,
+            clock: clock.clone(),
+            selftest: selftest.clone(),
+            data: Data::default(),
+            status: SensorsStatus::default(),
+        })
+    }
+
+    /// Au moins une source matérielle (lecture bloquante)
+    pub fn hardware(&self) -> bool {
+        self.sources.iter().any(|source| source.hardware())
+    }
+
+    /// Interroge chaque source une fois, une source arrêtée (panique, blocage) est recréée
+    pub fn poll(&mut self) {
+        let now = self.clock.now();
+        for supervised in self.sources.iter_mut() {
+            let kind = supervised.kind;
+            let context = &self.context;
+            if supervised.poll(&mut self.data, now, |kind| source::build(kind, context)) {
+                self.data.set_stamp(kind, self.clock.stamp());
+
+                let sample = kind.sample_check();
+                if !self.selftest.contains(sample) {
+                    self.selftest.record(sample, self.data.plausible(kind));
+                }
             }
-        };
 
+            let status = supervised.status();
+            self.selftest.record_init(kind.init_check(), status);
+            let current = match kind {
+                Kind::Imu => &mut self.status.imu,
+                Kind::Mag => &mut self.status.mag,
+                Kind::Analog => &mut self.status.analog,
+                Kind::Power => &mut self.status.power,
+                Kind::Baro => &mut self.status.baro,
+                Kind::Encoder => &mut self.status.encoder,
+                Kind::Range => &mut self.status.range,
+                Kind::Gps => &mut self.status.gps,
+            };
+            // Redémarrages comptés par le superviseur: une source recréée repart de zéro
+            if current.available != status.available || current.error != status.error || current.attempts != status.attempts {
+                current.clone_from(status);
+            }
+            current.restarts = supervised.restarts();
+        }
+
+        #[cfg(feature = "real-sensors")]
+        if let Some(buses) = self.buses.as_ref() {
+            let buses = buses.lock().unwrap();
+            if self.status.i2c != *buses.status() {
+                self.status.i2c.clone_from(buses.status());
+            }
+        }
+    }
+}
+
+pub struct Reader {
+    data: Arc<Mutex<Data>>,
+    status: Arc<Mutex<SensorsStatus>>,
+    finished: Arc<AtomicBool>,
+    clock: Clock,
+    timing: Arc<Mutex<LoopReport>>,
+    warnings: Option<mpsc::Receiver<String>>,
+    token: CancellationToken,
+}
+
+impl Reader {
+    pub fn new(
+        token: CancellationToken,
+        mut updates: watch::Receiver<Config>,
+        clock: &Clock,
+        simulation: &SharedSimulation,
+        selftest: &SelfTest,
+        mag_calibration: &watch::Receiver<MagCalibration>,
+    ) -> anyhow::Result<Self> {
         // Gestion des données
-        let data: Arc<Mutex<Data>> = Arc::new(Mutex::new(current_data.clone()));
+        let data: Arc<Mutex<Data>> = Arc::new(Mutex::new(Data::default()));
         let data_thread = data.clone();
+        let status: Arc<Mutex<SensorsStatus>> = Arc::new(Mutex::new(SensorsStatus::default()));
+        let status_thread = status.clone();
         let thread_token = token.clone();
-        let reader = Reader { data, token };
+        let timing: Arc<Mutex<LoopReport>> = Arc::new(Mutex::new(LoopReport::default()));
+        let timing_thread = timing.clone();
+        let (warnings, warnings_receiver) = mpsc::channel();
+        let reader = Reader {
+            data,
+            status,
+            finished: Arc::new(AtomicBool::new(false)),
+            clock: clock.clone(),
+            timing,
+            warnings: Some(warnings_receiver),
+            token,
+        };
 
-        // I2C
-        let mut i2c_bus = I2c::new().expect("[I2C] Erreur de bus");
+        let config = updates.borrow_and_update().clone();
+
+        // Capteurs, réels ou simulés selon la configuration.
+        let mut poller = Poller::new(&config, clock, simulation, selftest, mag_calibration)?;
+
+        // Sans capteur matériel, le thread suit le pas de la simulation
+        let hardware = poller.hardware();
+        let step = simulation.lock().unwrap().step_duration();
+
+        // Intervalle attendu: pas de la simulation, fréquence de l'IMU réelle, ou intervalle
+        // configuré avec un autre matériel
+        let target = move |config: &Config| match (hardware, config.sensors.imu.mode) {
+            (false, _) => step,
+            (true, SensorMode::Real) => config.sensors.imu.period(),
+            (true, _) => Duration::from_millis(config.timing.reader_interval_ms),
+        };
+        let mut interval = target(&config);
+        let mut timer = LoopTimer::new("capteurs", interval, &config.timing);
 
-        println!("[CAPTEURS] Démarrage du thread ...\n");
+        tracing::info!(target: "sensors", "Démarrage du thread ...");
         thread::spawn(move || {
-            let mut current_data = current_data;
+            let _span = tracing::info_span!("task", name = "sensors").entered();
+            let mut last_publish = Instant::now();
 
-            let mag = mag::hmc8553l::HMC8553L::new(&mut i2c_bus).expect("[MAG] Capteur non disponible.");
-            let mut imu = imu::imu::IMU::new(&mut i2c_bus).expect("[IMU] Capteur non disponible.");
-            let mut analog = analog::analog::Analog::new(&mut  i2c_bus).expect("[ANALOG] Capteur indisponible.");
-            let mut gps = gps::GPS::new().expect("[GPS] Capteur indisponible.");
-            
             while !thread_token.is_cancelled() {
-                // Capteur: Magnétique
-                let heading = mag.get_heading(&mut i2c_bus);
-                let raw = mag.get_mag_axes_raw(&mut i2c_bus);
-
-                if heading.is_ok() || raw.is_ok() {
-                    let heading: f32 = heading.unwrap();
-                    current_data.mag = MagData {
-                        heading,
-                        raw: raw.map(|x| (x.x, x.y, x.z)).unwrap(),
-                    };
-                } else {
-                    println!("[MAG] Erreur lors de la récupération des données.");
+                // Rechargement de la configuration
+                if updates.has_changed().unwrap_or(false) {
+                    let config = updates.borrow_and_update();
+                    interval = target(&config);
+                    timer.configure(interval, &config.timing);
                 }
 
-                // Capteur: IMU
-                imu.set_speed(current_data.gps.speed_kmh);
-                if let Err(e) = imu.update(&mut i2c_bus) {
-                    println!("[IMU] Erreur de calcul: {}\n", e);
-                } else {
-                    let angles = imu.get_angles();
-                    let temp: f32 = imu.get_temp();
+                let start = timer.start();
+                poller.poll();
+                timer.read_done(start.elapsed());
 
-                    current_data.imu = ImuData {
-                        angles: (angles.x, angles.y, angles.z),
-                        temp,
+                // L'état n'est copié que s'il a changé
+                let submit = Instant::now();
+                {
+                    let mut status = status_thread.lock().unwrap();
+                    if *status != poller.status {
+                        status.clone_from(&poller.status);
                     }
                 }
+                *data_thread.lock().unwrap() = poller.data;
+                timer.submit_done(submit.elapsed());
 
-                // Capteur: Analog
-                let battery = analog.get_battery(&mut i2c_bus);
-                if let Err(e)  = battery {
-                    println!("[ANALOG] Erreur: {}\n", e);
-                } else {
-                    current_data.analog.battery = battery.unwrap();
+                if let Some(warning) = timer.check() {
+                    let _ = warnings.send(warning);
+                }
+                if last_publish.elapsed() >= TIMING_PUBLISH {
+                    last_publish = Instant::now();
+                    *timing_thread.lock().unwrap() = timer.report();
                 }
 
-                // Capteur: GPS
-                let messages = gps.read();
-                if let Err(e) = messages {
-                    println!("[GPS] Erreur: {}\n", e);
+                if hardware {
+                    // Cadence des mesures: attente du reste de l'intervalle
+                    thread::sleep(interval.saturating_sub(start.elapsed()));
                 } else {
-                    if let Some(messages) = messages.unwrap() {
-                        for message in messages {
-                            match message {
-                                ParsedMessage::Gga(gga) => {
-                                    // println!("Source:    {}",     gga.source);
-                                    // println!("Latitude:  {:.3}°", gga.latitude.unwrap_or(0.0));
-                                    // println!("Longitude: {:.3}°", gga.longitude.unwrap_or(0.0));
-                                    // println!("Satelites: {}", gga.satellite_count.unwrap_or(0));
-                                    // println!("Fix?: {}",  gga.quality == GgaQualityIndicator::GpsFix);
-                                    current_data.gps.latitude = gga.latitude.unwrap_or(0.0);
-                                    current_data.gps.longitude = gga.longitude.unwrap_or(0.0);
-                                    current_data.gps.satellites = gga.satellite_count.unwrap_or(0);
-                                    current_data.gps.fix = gga.quality == GgaQualityIndicator::GpsFix;
-
-                                }
-                                ParsedMessage::Vtg(vtg) => {
-                                    current_data.gps.speed_kmh = vtg.sog_kph.unwrap_or(0.0);
-                                    current_data.gps.heading = vtg.cog_true.unwrap_or(0.0);
-                                }
-                                _ => {
-                                    // println!("Trame NMEA Inconnue.");
-                                }
-                            }
-                        }
-                    }
+                    thread::sleep(step);
                 }
-
-                *data_thread.lock().unwrap() = current_data.clone();
             }
 
-
-            println!("[CAPTEURS] Fin du thread.\n");
+            tracing::info!(target: "sensors", "Fin du thread.");
         });
 
         Ok(reader)
     }
+}
 
-    #[cfg(feature = "fake-sensors")]
-    pub(crate) fn new(token: CancellationToken) -> anyhow::Result<Self> {
-        // Initalisation des données
-        let current_data = Data {
-            mag: MagData {
-                raw: (0, 0, 0),
-                heading: 0.0,
-            },
-
-            imu: ImuData {
-                angles: (0.0, 0.0, 0.0),
-                temp: 0.0,
-            },
-
-            analog: AnalogData {
-                battery: 0.0
-            },
-
-            gps: GpsData {
-                speed_kmh: 0.0,
-                latitude: 0.0,
-                longitude: 0.0,
-                satellites: 0,
-                fix: false,
-                heading: 0.0,
-            }
+impl Reader {
+    /// Rejoue un enregistrement à la place des capteurs
+    pub fn replay(token: CancellationToken, options: ReplayOptions, selftest: &SelfTest) -> anyhow::Result<Self> {
+        let data = Arc::new(Mutex::new(Data::default()));
+        let finished = Arc::new(AtomicBool::new(false));
+        let available = SensorStatus {
+            available: true,
+            error: None,
+            attempts: 1,
+            restarts: 0,
         };
+        let status = Arc::new(Mutex::new(SensorsStatus {
+            imu: available.clone(),
+            mag: available.clone(),
+            analog: available.clone(),
+            power: available.clone(),
+            baro: available.clone(),
+            encoder: available.clone(),
+            range: available.clone(),
+            gps: available,
+            i2c: BTreeMap::new(),
+        }));
+
+        for name in ["i2c.bus", "i2c.scan", "imu.whoami", "mag.whoami", "imu.init", "mag.init", "analog.init", "power.init", "baro.init", "encoder.init", "range.init", "gps.init"] {
+            selftest.skip(name, "Rejeu d'un enregistrement");
+        }
+        for name in ["imu.sample", "mag.sample", "analog.sample", "power.sample", "baro.sample", "encoder.sample", "range.sample", "gps.sample"] {
+            selftest.skip(name, "Rejeu d'un enregistrement");
+        }
 
-        // Gestion des données
-        let data: Arc<Mutex<Data>> = Arc::new(Mutex::new(current_data.clone()));
-        let data_thread = data.clone();
-        let thread_token = token.clone();
-        let reader = Reader { data, token };
-
-        println!("[CAPTEURS] Démarrage du thread [FAKE] ...\n");
-        thread::spawn(move || {
-            let mut rng = rand::thread_rng();
-            let mut current_data = current_data;
+        let clock = replay::spawn(options, data.clone(), finished.clone(), token.clone())?;
+
+        Ok(Reader {
+            data,
+            status,
+            finished,
+            clock,
+            timing: Arc::new(Mutex::new(LoopReport::default())),
+            warnings: None,
+            token,
+        })
+    }
 
-            while !thread_token.is_cancelled() {
-                current_data.mag.heading = rng.gen();
-                current_data.analog.battery = rng.gen();
-                current_data.gps.speed_kmh = rng.gen();
+    /// Horloge des échantillons (virtuelle lors d'un rejeu)
+    pub fn clock(&self) -> &Clock {
+        &self.clock
+    }
 
-                *data_thread.lock().unwrap() = current_data.clone();
-            }
+    /// Etat d'initialisation des capteurs, uniquement s'il diffère du dernier état connu
+    pub fn status_changed(&self, last: Option<&SensorsStatus>) -> Option<SensorsStatus> {
+        let status = self.status.lock().unwrap();
+        (last != Some(&*status)).then(|| status.clone())
+    }
 
-            println!("[CAPTEURS] Fin du thread [FAKE].\n");
-        });
+    /// Durées du thread de lecture (vide lors d'un rejeu)
+    pub fn timing(&self) -> LoopReport {
+        *self.timing.lock().unwrap()
+    }
 
-        Ok(reader)
+    /// Avertissements de gigue du thread de lecture, depuis le dernier appel
+    pub fn warnings(&self) -> Vec<String> {
+        match &self.warnings {
+            Some(receiver) => receiver.try_iter().collect(),
+            None => Vec::new(),
+        }
     }
 }
 
@@ -238,11 +675,11 @@ impl Stream for Reader {
         self: Pin<&mut Self>,
         _cx: &mut std::task::Context<'_>,
     ) -> Poll<Option<Self::Item>> {
-        if self.token.is_cancelled() {
+        if self.token.is_cancelled() || self.finished.load(Ordering::Relaxed) {
             return Poll::Ready(None);
         }
 
-        let data = self.data.lock().unwrap().clone();
+        let data = *self.data.lock().unwrap();
         Poll::Ready(Some(Ok(data)))
     }
 }
@@ -0,0 +1,348 @@
+use std::fs::File;
+use std::io::{BufRead, BufReader, BufWriter, Lines, Read, Write};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use chrono::{DateTime, Utc};
+
+use prost::Message;
+use serde::{Deserialize, Serialize};
+use tokio_util::sync::CancellationToken;
+
+use crate::clock::{Clock, Stamp};
+use crate::config::Encoding;
+use crate::proto;
+use crate::sensors::reader::Data;
+
+/// Nombre d'échantillons écrits entre deux vidages du tampon
+const FLUSH_EVERY: u32 = 30;
+
+/// En-tête d'un enregistrement binaire, suivi de la version du format
+const CAPTURE_MAGIC: &[u8; 4] = b"RCPB";
+const CAPTURE_VERSION: u8 = 1;
+
+/// Taille maximale d'un échantillon binaire, au-delà le fichier est considéré corrompu
+const MAX_SAMPLE: usize = 64 * 1024;
+
+/// Echantillon enregistré, horodaté par l'horloge commune
+#[derive(Serialize, Deserialize)]
+struct Sample {
+    stamp: Stamp,
+    data: Data,
+}
+
+/// Enregistre les données des capteurs dans un fichier par exécution
+/// (JSON lines, ou protobuf: en-tête puis échantillons précédés de leur taille)
+pub struct Recorder {
+    file: BufWriter<File>,
+    clock: Clock,
+    encoding: Encoding,
+    pending: u32,
+}
+
+impl Recorder {
+    /// Créer un nouveau fichier d'enregistrement dans le dossier donné
+    pub fn create(directory: &Path, clock: &Clock, encoding: Encoding) -> anyhow::Result<Self> {
+        std::fs::create_dir_all(directory)?;
+
+        let now = SystemTime::now().duration_since(UNIX_EPOCH)?.as_secs();
+        let extension = match encoding {
+            Encoding::Json => "jsonl",
+            Encoding::Protobuf => "pb",
+        };
+        let path = directory.join(format!("run-{}.{}", now, extension));
+        let mut file = BufWriter::new(File::create(&path)?);
+
+        if encoding == Encoding::Protobuf {
+            file.write_all(CAPTURE_MAGIC)?;
+            file.write_all(&[CAPTURE_VERSION])?;
+        }
+
+        tracing::info!(target: "record", "Enregistrement dans {}", path.display());
+
+        Ok(Self {
+            file,
+            clock: clock.clone(),
+            encoding,
+            pending: 0,
+        })
+    }
+
+    /// Ajoute un échantillon à l'enregistrement
+    pub fn write(&mut self, data: &Data) -> anyhow::Result<()> {
+        let sample = Sample {
+            stamp: self.clock.stamp(),
+            data: *data,
+        };
+
+        match self.encoding {
+            Encoding::Json => {
+                serde_json::to_writer(&mut self.file, &sample)?;
+                self.file.write_all(b"\n")?;
+            }
+            Encoding::Protobuf => {
+                let sample = proto::Sample {
+                    stamp: Some(sample.stamp.into()),
+                    data: Some(sample.data.into()),
+                };
+                self.file.write_all(&sample.encode_length_delimited_to_vec())?;
+            }
+        }
+
+        self.pending += 1;
+        if self.pending >= FLUSH_EVERY {
+            self.file.flush()?;
+            self.pending = 0;
+        }
+
+        Ok(())
+    }
+}
+
+/// Lecture des échantillons d'un enregistrement, format détecté depuis l'en-tête.
+/// Un échantillon invalide (ex: dernière ligne tronquée) est retourné en erreur sans arrêter la
+/// lecture d'un fichier JSON; en binaire, la lecture s'arrête après un échantillon tronqué.
+enum Samples<R> {
+    Json(Lines<R>),
+    Protobuf { file: R, done: bool },
+}
+
+impl Samples<BufReader<File>> {
+    fn open(path: &Path) -> anyhow::Result<Self> {
+        Samples::new(BufReader::new(File::open(path)?)).map_err(|e| anyhow::anyhow!("{}: {}", path.display(), e))
+    }
+}
+
+impl<R: BufRead> Samples<R> {
+    fn new(mut file: R) -> anyhow::Result<Self> {
+        if !file.fill_buf()?.starts_with(CAPTURE_MAGIC) {
+            return Ok(Samples::Json(file.lines()));
+        }
+
+        let mut header = [0u8; 5];
+        file.read_exact(&mut header)?;
+        if header[4] != CAPTURE_VERSION {
+            return Err(anyhow::anyhow!("version {} du format binaire non supportée", header[4]));
+        }
+
+        Ok(Samples::Protobuf { file, done: false })
+    }
+}
+
+/// Lit un échantillon binaire, None en fin de fichier
+fn read_sample(file: &mut impl Read) -> anyhow::Result<Option<Sample>> {
+    // Taille (varint)
+    let mut length = 0usize;
+    let mut shift = 0;
+    loop {
+        let mut byte = [0u8; 1];
+        if file.read(&mut byte)? == 0 {
+            if shift == 0 {
+                return Ok(None);
+            }
+            return Err(anyhow::anyhow!("Taille d'échantillon tronquée"));
+        }
+
+        length |= ((byte[0] & 0x7f) as usize) << shift;
+        if byte[0] & 0x80 == 0 {
+            break;
+        }
+        shift += 7;
+        if shift > 28 {
+            return Err(anyhow::anyhow!("Taille d'échantillon invalide"));
+        }
+    }
+
+    if length > MAX_SAMPLE {
+        return Err(anyhow::anyhow!("Echantillon de {} octets, fichier corrompu", length));
+    }
+
+    let mut buffer = vec![0u8; length];
+    file.read_exact(&mut buffer)
+        .map_err(|_| anyhow::anyhow!("Echantillon tronqué ({} octets attendus)", length))?;
+    let sample = proto::Sample::decode(buffer.as_slice())?;
+
+    Ok(Some(Sample {
+        stamp: sample.stamp.map(Into::into).unwrap_or_default(),
+        data: sample.data.map(Into::into).unwrap_or_default(),
+    }))
+}
+
+impl<R: BufRead> Iterator for Samples<R> {
+    type Item = anyhow::Result<Sample>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        match self {
+            Samples::Json(lines) => {
+                let line = match lines.next()? {
+                    Ok(line) => line,
+                    Err(e) => return Some(Err(e.into())),
+                };
+                Some(serde_json::from_str(&line).map_err(Into::into))
+            }
+            Samples::Protobuf { file, done } => {
+                if *done {
+                    return None;
+                }
+                match read_sample(file) {
+                    Ok(sample) => sample.map(Ok),
+                    Err(e) => {
+                        *done = true;
+                        Some(Err(e))
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Convertit un enregistrement (binaire ou JSON) en JSON lines, relisible par --replay.
+/// Les échantillons invalides sont ignorés. Retourne le nombre d'échantillons écrits.
+pub fn decode(input: &Path, output: &mut impl Write) -> anyhow::Result<u64> {
+    decode_from(BufReader::new(File::open(input)?), output).map_err(|e| anyhow::anyhow!("{}: {}", input.display(), e))
+}
+
+/// Comme `decode`, depuis un contenu déjà ouvert (ex: octets en mémoire)
+pub fn decode_from(input: impl BufRead, output: &mut impl Write) -> anyhow::Result<u64> {
+    let mut written = 0;
+    for (n, sample) in Samples::new(input)?.enumerate() {
+        let sample = match sample {
+            Ok(sample) => sample,
+            Err(e) => {
+                tracing::warn!(target: "decode", "Echantillon {} ignoré: {}", n + 1, e);
+                continue;
+            }
+        };
+
+        serde_json::to_writer(&mut *output, &sample)?;
+        output.write_all(b"\n")?;
+        written += 1;
+    }
+
+    output.flush()?;
+    Ok(written)
+}
+
+/// Options du rejeu
+#[derive(Clone)]
+pub struct ReplayOptions {
+    pub path: PathBuf,
+    /// Facteur d'accélération (2.0 => deux fois plus rapide)
+    pub speed: f64,
+    /// Recommence au début du fichier une fois la fin atteinte
+    pub looping: bool,
+    /// Horloge qui cadence le rejeu (réelle, virtuelle dans les tests)
+    pub pace: Clock,
+}
+
+/// Rejoue un enregistrement dans un thread, en respectant l'écart de temps entre les échantillons.
+/// `finished` passe à vrai une fois la fin du fichier atteinte (hors mode boucle).
+/// Retourne l'horloge virtuelle du rejeu, ancrée sur l'heure de l'enregistrement.
+pub fn spawn(
+    options: ReplayOptions,
+    data: Arc<Mutex<Data>>,
+    finished: Arc<AtomicBool>,
+    token: CancellationToken,
+) -> anyhow::Result<Clock> {
+    // Vérifie que le fichier est lisible avant de démarrer, et récupère l'ancrage de l'horloge
+    let clock = Clock::virtual_at(anchor(&options.path)?);
+    let thread_clock = clock.clone();
+
+    tracing::info!(target: "replay", "Rejeu de {} (x{}) ...", options.path.display(), options.speed);
+    thread::spawn(move || {
+        // Décalage ajouté à chaque boucle, pour que le temps du rejeu ne recule jamais
+        let mut offset = Duration::ZERO;
+
+        while !token.is_cancelled() {
+            let last = match play(&options, &data, &thread_clock, offset, &token) {
+                Ok(last) => last,
+                Err(e) => {
+                    tracing::error!(target: "replay", "Erreur: {}", e);
+                    break;
+                }
+            };
+
+            let Some(last) = last else {
+                break;
+            };
+
+            if !options.looping {
+                break;
+            }
+
+            offset = last;
+            tracing::info!(target: "replay", "Fin du fichier, reprise au début.");
+        }
+
+        tracing::info!(target: "replay", "Fin du rejeu.");
+        finished.store(true, Ordering::Relaxed);
+    });
+
+    Ok(clock)
+}
+
+/// Ancrage UTC de l'horloge de l'exécution enregistrée, depuis le premier échantillon valide
+fn anchor(path: &Path) -> anyhow::Result<DateTime<Utc>> {
+    if let Some(sample) = Samples::open(path)?.flatten().next() {
+        return Ok(sample.stamp.utc - chrono::Duration::microseconds(sample.stamp.mono_us as i64));
+    }
+
+    Err(anyhow::anyhow!("Aucun échantillon dans {}", path.display()))
+}
+
+/// Joue le fichier une fois, avec l'horloge virtuelle décalée de `offset`.
+/// Retourne le temps du dernier échantillon rejoué (None si aucun).
+fn play(
+    options: &ReplayOptions,
+    data: &Mutex<Data>,
+    clock: &Clock,
+    offset: Duration,
+    token: &CancellationToken,
+) -> anyhow::Result<Option<Duration>> {
+    let samples = Samples::open(&options.path)?;
+    let start = options.pace.now();
+    let mut first: Option<u64> = None;
+    let mut last = None;
+
+    for (n, sample) in samples.enumerate() {
+        if token.is_cancelled() {
+            break;
+        }
+
+        // Un échantillon invalide (ex: dernière ligne tronquée) est ignoré
+        let sample = match sample {
+            Ok(sample) => sample,
+            Err(e) => {
+                tracing::warn!(target: "replay", "Echantillon {} ignoré: {}", n + 1, e);
+                continue;
+            }
+        };
+
+        // Planification absolue depuis le début: pas de dérive cumulée
+        let origin = *first.get_or_insert(sample.stamp.mono_us);
+        let delay = sample.stamp.mono_us.saturating_sub(origin) as f64 / options.speed;
+        let due = start + Duration::from_micros(delay as u64);
+        while !token.is_cancelled() {
+            let now = options.pace.now();
+            if due <= now {
+                break;
+            }
+            options.pace.sleep_blocking((due - now).min(Duration::from_millis(100)));
+        }
+
+        // Horodatages d'origine, décalés lors des boucles
+        let now = offset + Duration::from_micros(sample.stamp.mono_us);
+        clock.set(now);
+
+        let mut replayed = sample.data;
+        replayed.restamp(|stamp| clock.stamp_at(offset + Duration::from_micros(stamp.mono_us)));
+
+        *data.lock().unwrap() = replayed;
+        last = Some(now);
+    }
+
+    Ok(last)
+}
@@ -0,0 +1,99 @@
+use std::time::{Duration, Instant};
+
+use crate::sensors::reader::SensorStatus;
+
+/// Délai initial avant une nouvelle tentative d'initialisation
+const RETRY_MIN: Duration = Duration::from_secs(1);
+
+/// Délai maximum entre deux tentatives d'initialisation
+const RETRY_MAX: Duration = Duration::from_secs(60);
+
+/// Capteur dont l'initialisation est réessayée (délai croissant) tant qu'elle échoue
+pub struct Pending<T> {
+    name: &'static str,
+    sensor: Option<T>,
+    delay: Duration,
+    next_try: Instant,
+    status: SensorStatus,
+}
+
+impl<T> Pending<T> {
+    /// Constructeur
+    pub fn new(name: &'static str) -> Self {
+        Self {
+            name,
+            sensor: None,
+            delay: RETRY_MIN,
+            next_try: Instant::now(),
+            status: SensorStatus::default(),
+        }
+    }
+
+    /// Récupére le capteur, tente de l'initialiser si besoin (et si le délai est écoulé)
+    pub fn poll(&mut self, init: impl FnOnce() -> anyhow::Result<T>) -> Option<&mut T> {
+        if self.sensor.is_none() && Instant::now() >= self.next_try {
+            self.status.attempts += 1;
+
+            match init() {
+                Ok(sensor) => {
+                    tracing::info!(target: "sensors", sensor = self.name, "Capteur disponible.");
+                    self.sensor = Some(sensor);
+                    self.status.available = true;
+                    self.status.error = None;
+                }
+                Err(e) => {
+                    tracing::warn!(
+                        target: "sensors",
+                        sensor = self.name,
+                        "Initialisation impossible ({}), nouvel essai dans {}s.",
+                        e,
+                        self.delay.as_secs()
+                    );
+                    self.status.error = Some(e.to_string());
+                    self.next_try = Instant::now() + self.delay;
+                    self.delay = (self.delay * 2).min(RETRY_MAX);
+                }
+            }
+        }
+
+        self.sensor.as_mut()
+    }
+
+    /// Capteur perdu (erreur de lecture, périphérique débranché): fermé, puis réouvert par `poll`
+    /// avec le délai initial
+    pub fn lost(&mut self, error: &anyhow::Error) {
+        if self.sensor.take().is_none() {
+            return;
+        }
+
+        tracing::warn!(
+            target: "sensors",
+            sensor = self.name,
+            "Capteur perdu ({}), en attente du périphérique, nouvel essai dans {}s.",
+            error,
+            RETRY_MIN.as_secs()
+        );
+        self.status.available = false;
+        self.status.error = Some(error.to_string());
+        self.next_try = Instant::now() + RETRY_MIN;
+        self.delay = (RETRY_MIN * 2).min(RETRY_MAX);
+    }
+
+    /// Initialise un capteur requis, une erreur est retournée s'il n'est pas disponible
+    pub fn require(&mut self, init: impl FnOnce() -> anyhow::Result<T>) -> anyhow::Result<()> {
+        if self.poll(init).is_none() {
+            return Err(anyhow::anyhow!(
+                "Capteur requis {} indisponible: {}",
+                self.name,
+                self.status.error.clone().unwrap_or_default()
+            ));
+        }
+
+        Ok(())
+    }
+
+    /// Etat d'initialisation du capteur
+    pub fn status(&self) -> &SensorStatus {
+        &self.status
+    }
+}
@@ -0,0 +1,575 @@
+use std::collections::hash_map::RandomState;
+use std::hash::BuildHasher;
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, SystemTime};
+
+use serde::{Deserialize, Serialize};
+
+use crate::sensors::track;
+
+/// Rayon moyen de la terre (m)
+const EARTH_RADIUS: f64 = 6_371_000.0;
+/// Distance à partir de laquelle un point de passage est considéré atteint (m)
+const WAYPOINT_RADIUS: f64 = 5.0;
+/// Vitesse de rotation maximale du véhicule (°/s)
+const MAX_YAW_RATE: f64 = 90.0;
+/// Accélération maximale (km/h par seconde)
+const MAX_ACCEL: f64 = 15.0;
+/// Intensité du champ magnétique simulé (LSB du magnétomètre)
+const MAG_FIELD: f64 = 450.0;
+/// Gravité (m/s²)
+const GRAVITY: f64 = 9.81;
+/// Eléments de la batterie mesurés au plus (entrées de l'ADC), simulés ou réels
+pub const MAX_CELLS: usize = 4;
+
+/// Scénario de conduite simulé, partagé par tous les capteurs simulés
+#[derive(Clone, Deserialize, Serialize)]
+#[serde(default)]
+pub struct Scenario {
+    /// Graine du générateur, deux exécutions avec la même graine sont identiques. Sinon variable
+    /// d'environnement RC_SIM_SEED, sinon tirée au hasard au démarrage.
+    pub seed: Option<u64>,
+    /// Pas de temps de la simulation (ms)
+    pub step_ms: u64,
+    /// Accélération du temps (1.0: temps réel), uniquement avec des capteurs simulés
+    pub time_scale: f64,
+    /// Points de passage [latitude, longitude], parcourus en boucle
+    pub route: Vec<[f64; 2]>,
+    /// Vitesse (km/h) pour chaque segment du parcours, la dernière valeur est réutilisée
+    pub speeds: Vec<f64>,
+    /// Parcours GPX ou CSV remplaçant route et speeds, suivi exactement (position interpolée
+    /// entre les points, cap du segment). Sans fichier lisible, route est utilisé.
+    pub track: Option<PathBuf>,
+    /// Vitesse (km/h) des segments du fichier sans vitesse connue
+    pub track_speed_kmh: f64,
+    /// Vitesse correspondant à une commande de 1.0 en conduite manuelle (km/h)
+    pub max_speed_kmh: f64,
+    pub battery: BatteryScenario,
+    pub signal: SignalScenario,
+}
+
+#[derive(Clone, Deserialize, Serialize)]
+#[serde(default)]
+pub struct BatteryScenario {
+    /// Tension batterie chargée (V)
+    pub full: f64,
+    /// Tension batterie vide (V)
+    pub empty: f64,
+    /// Autonomie à vitesse maximale (minutes)
+    pub autonomy_min: f64,
+    /// Eléments en série, mesurés aux prises d'équilibrage simulées (0: aucun, au plus 4)
+    pub cells: u32,
+    /// Capacité (mAh), le courant simulé la décharge en autonomy_min à vitesse maximale
+    pub capacity_mah: f64,
+}
+
+#[derive(Clone, Deserialize, Serialize)]
+#[serde(default)]
+pub struct SignalScenario {
+    /// Qualité du signal hors des zones (%)
+    pub base: f64,
+    pub zones: Vec<SignalZone>,
+}
+
+/// Zone où la qualité du signal diffère (tunnel, antenne, ...)
+#[derive(Clone, Deserialize, Serialize)]
+pub struct SignalZone {
+    pub latitude: f64,
+    pub longitude: f64,
+    /// Rayon de la zone (m), la qualité varie progressivement jusqu'au bord
+    pub radius_m: f64,
+    /// Qualité au centre de la zone (%)
+    pub quality: f64,
+}
+
+/// Commande de conduite manuelle (identique à l'enregistrement control:realtime)
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct ManualControl {
+    pub steer: f64,
+    pub speed: f64,
+}
+
+/// Mesures simulées, bruitées, telles que les capteurs les verraient
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Readings {
+    pub latitude: f64,
+    pub longitude: f64,
+    pub speed_kmh: f64,
+    pub gps_heading: f64,
+    pub satellites: u8,
+    pub fix: bool,
+    pub angles: (f32, f32, f32),
+    pub temp: f32,
+    /// Accélération X (avant), Y (gauche), Z (haut) en g, pesanteur comprise
+    pub accel: (f32, f32, f32),
+    /// Vitesse angulaire X, Y, Z (°/s)
+    pub gyro: (f32, f32, f32),
+    pub mag_raw: (i16, i16, i16),
+    pub mag_heading: f32,
+    pub battery: f32,
+    /// Tensions cumulées aux prises d'équilibrage, de l'élément le plus bas au pack complet
+    pub taps: [Option<f32>; MAX_CELLS],
+    /// Courant de la batterie (A)
+    pub current: f32,
+    /// Pression atmosphérique (Pa), légère dérive autour de la pression standard
+    pub pressure: f64,
+    /// Température ambiante du baromètre (°C)
+    pub ambient: f32,
+    /// Humidité relative (%)
+    pub humidity: f32,
+    /// Distance parcourue par les roues depuis le départ (m), marche arrière comprise
+    pub odometer_m: f64,
+    /// Distance de l'obstacle devant la voiture (mm)
+    pub range_mm: u16,
+    pub signal: u32,
+}
+
+impl Scenario {
+    /// Scénario avec le parcours du fichier `track` chargé (inchangé sans fichier)
+    pub fn with_track(&self) -> anyhow::Result<Scenario> {
+        let mut scenario = self.clone();
+        let Some(path) = self.track.as_ref() else {
+            return Ok(scenario);
+        };
+
+        let points = track::load(path)?;
+        scenario.route = points.iter().map(|point| [point.latitude, point.longitude]).collect();
+        // Arrêt sur un segment (heures de passage identiques): la voiture ne repartirait plus
+        scenario.speeds = points
+            .iter()
+            .map(|point| point.speed_kmh.filter(|speed| *speed > 0.0).unwrap_or(self.track_speed_kmh))
+            .collect();
+        Ok(scenario)
+    }
+
+    /// Scénario avec une graine: celle de la configuration, sinon RC_SIM_SEED, sinon tirée au hasard
+    pub fn with_seed(&self) -> anyhow::Result<Scenario> {
+        let seed = match (self.seed, std::env::var("RC_SIM_SEED")) {
+            (Some(seed), _) => seed,
+            (None, Ok(seed)) => seed
+                .parse()
+                .map_err(|e| anyhow::anyhow!("sim: RC_SIM_SEED {} invalide: {}", seed, e))?,
+            (None, Err(_)) => random_seed(),
+        };
+        Ok(Scenario {
+            seed: Some(seed),
+            ..self.clone()
+        })
+    }
+}
+
+/// Graine tirée au hasard (clés aléatoires du système et heure courante)
+pub fn random_seed() -> u64 {
+    RandomState::new().hash_one(SystemTime::now())
+}
+
+/// Générateur pseudo-aléatoire (SplitMix64), stable entre les versions du programme
+struct Rng(u64);
+
+impl Rng {
+    fn next_u64(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+
+    /// Valeur uniforme dans [0, 1)
+    fn uniform(&mut self) -> f64 {
+        (self.next_u64() >> 11) as f64 / (1u64 << 53) as f64
+    }
+
+    /// Bruit centré, approximativement gaussien, d'écart-type `sigma`
+    fn noise(&mut self, sigma: f64) -> f64 {
+        let sum: f64 = (0..4).map(|_| self.uniform()).sum();
+        (sum - 2.0) * sigma * 3f64.sqrt()
+    }
+}
+
+/// Simulation partagée entre les capteurs simulés, le modem et le contrôle
+pub type SharedSimulation = Arc<Mutex<Simulation>>;
+
+/// Simulation d'un véhicule unique parcourant le scénario
+pub struct Simulation {
+    scenario: Scenario,
+    rng: Rng,
+    control: Option<ManualControl>,
+    elapsed: Duration,
+    waypoint: usize,
+    /// Sur le parcours chargé (track), distance parcourue depuis le début du segment (m)
+    along: Option<f64>,
+    latitude: f64,
+    longitude: f64,
+    heading: f64,
+    speed_kmh: f64,
+    yaw_rate: f64,
+    accel: f64,
+    consumed: f64,
+    odometer: f64,
+    readings: Readings,
+}
+
+impl Simulation {
+    /// Simulation partagée
+    pub fn shared(scenario: &Scenario) -> SharedSimulation {
+        Arc::new(Mutex::new(Self::new(scenario)))
+    }
+
+    /// Constructeur
+    pub fn new(scenario: &Scenario) -> Self {
+        let mut scenario = scenario.clone();
+        if scenario.route.is_empty() {
+            scenario.route = Scenario::default().route;
+        }
+
+        let [latitude, longitude] = scenario.route[0];
+        let mut simulation = Self {
+            rng: Rng(scenario.seed.unwrap_or_else(random_seed)),
+            control: None,
+            elapsed: Duration::ZERO,
+            waypoint: 1 % scenario.route.len(),
+            along: scenario.track.is_some().then_some(0.0),
+            latitude,
+            longitude,
+            heading: 0.0,
+            speed_kmh: 0.0,
+            yaw_rate: 0.0,
+            accel: 0.0,
+            consumed: 0.0,
+            odometer: 0.0,
+            readings: Readings::default(),
+            scenario,
+        };
+
+        // Départ orienté vers le premier point de passage
+        let [lat, lon] = simulation.scenario.route[simulation.waypoint];
+        simulation.heading = simulation.bearing_to(lat, lon);
+        simulation.measure();
+        simulation
+    }
+
+    /// Pas de temps configuré
+    pub fn step_duration(&self) -> Duration {
+        Duration::from_millis(self.scenario.step_ms.max(1))
+    }
+
+    /// Avance pas par pas jusqu'au temps écoulé de l'horloge commune
+    pub fn sync(&mut self, target: Duration) {
+        while self.elapsed + self.step_duration() <= target {
+            self.step();
+        }
+    }
+
+    /// Conduite manuelle (Some) ou suivi du parcours (None)
+    pub fn set_control(&mut self, control: Option<ManualControl>) {
+        // Hors du parcours chargé, il est rejoint au prochain point de passage
+        if control.is_some() {
+            self.along = None;
+        }
+        self.control = control;
+    }
+
+    /// Perte des commandes: arrêt du véhicule, direction à `steer`
+    pub fn failsafe(&mut self, steer: f64) {
+        if let Some(control) = self.control.as_mut() {
+            control.speed = 0.0;
+            control.steer = steer;
+        }
+    }
+
+    /// Dernières mesures simulées
+    pub fn readings(&self) -> &Readings {
+        &self.readings
+    }
+
+    /// Avance la simulation d'un pas de temps
+    pub fn step(&mut self) {
+        let dt = self.step_duration().as_secs_f64();
+
+        match (self.control, self.along) {
+            (None, Some(along)) => self.follow_track(along, dt),
+            _ => self.drive(dt),
+        }
+
+        // Décharge de la batterie, proportionnelle à la vitesse
+        let load = 0.05 + 0.95 * (self.speed_kmh.abs() / self.scenario.max_speed_kmh).min(1.0);
+        self.consumed = (self.consumed + load * dt / (self.scenario.battery.autonomy_min * 60.0)).min(1.0);
+
+        self.elapsed += self.step_duration();
+        self.measure();
+    }
+
+    /// Conduite manuelle, ou vers le prochain point de passage
+    fn drive(&mut self, dt: f64) {
+        // Consigne: commande manuelle ou parcours
+        let (target_speed, target_yaw) = match self.control {
+            Some(control) => {
+                let steer = control.steer.clamp(-1.0, 1.0);
+                let speed = control.speed.clamp(-1.0, 1.0);
+                let grip = (self.speed_kmh.abs() / 10.0).min(1.0);
+                (speed * self.scenario.max_speed_kmh, steer * MAX_YAW_RATE * grip)
+            }
+            None => {
+                let [lat, lon] = self.scenario.route[self.waypoint];
+                if self.distance_to(lat, lon) < WAYPOINT_RADIUS {
+                    self.waypoint = (self.waypoint + 1) % self.scenario.route.len();
+                    // Parcours chargé rejoint: suivi exact depuis ce point
+                    if self.scenario.track.is_some() {
+                        self.along = Some(0.0);
+                    }
+                }
+
+                let [lat, lon] = self.scenario.route[self.waypoint];
+                let error = wrap_180(self.bearing_to(lat, lon) - self.heading);
+                (self.segment_speed(), (error * 2.0).clamp(-MAX_YAW_RATE, MAX_YAW_RATE))
+            }
+        };
+
+        // Dynamique du véhicule
+        self.accelerate(target_speed, dt);
+        self.yaw_rate = target_yaw;
+        self.heading = (self.heading + self.yaw_rate * dt).rem_euclid(360.0);
+
+        let distance = self.speed_kmh / 3.6 * dt;
+        self.odometer += distance.abs();
+        let heading = self.heading.to_radians();
+        self.latitude += (distance * heading.cos() / EARTH_RADIUS).to_degrees();
+        self.longitude +=
+            (distance * heading.sin() / (EARTH_RADIUS * self.latitude.to_radians().cos())).to_degrees();
+    }
+
+    /// Avance sur le parcours chargé: position interpolée sur le segment, cap du segment
+    fn follow_track(&mut self, along: f64, dt: f64) {
+        self.accelerate(self.segment_speed(), dt);
+
+        let distance = self.speed_kmh / 3.6 * dt;
+        self.odometer += distance.abs();
+        let mut along = along + distance;
+
+        // Segments terminés, points confondus compris (au plus un tour par pas)
+        let count = self.scenario.route.len();
+        let (mut from, mut to) = self.segment();
+        for _ in 0..count {
+            let length = track::distance(from, to);
+            if along < length {
+                break;
+            }
+            along -= length;
+            self.waypoint = (self.waypoint + 1) % count;
+            (from, to) = self.segment();
+        }
+        self.along = Some(along);
+
+        let length = track::distance(from, to);
+        let ratio = if length > 0.0 { (along / length).min(1.0) } else { 1.0 };
+        self.latitude = from[0] + (to[0] - from[0]) * ratio;
+        self.longitude = from[1] + (to[1] - from[1]) * ratio;
+
+        if length > 0.0 {
+            let (north, east) = track::offset(from, to);
+            let heading = east.atan2(north).to_degrees().rem_euclid(360.0);
+            self.yaw_rate = (wrap_180(heading - self.heading) / dt).clamp(-MAX_YAW_RATE, MAX_YAW_RATE);
+            self.heading = heading;
+        } else {
+            self.yaw_rate = 0.0;
+        }
+    }
+
+    /// Vitesse et accélération vers la consigne, accélération limitée
+    fn accelerate(&mut self, target_speed: f64, dt: f64) {
+        let previous = self.speed_kmh;
+        let max_delta = MAX_ACCEL * dt;
+        self.speed_kmh += (target_speed - self.speed_kmh).clamp(-max_delta, max_delta);
+        self.accel = (self.speed_kmh - previous) / 3.6 / dt;
+    }
+
+    /// Segment en cours: point précédent et point de passage visé
+    fn segment(&self) -> ([f64; 2], [f64; 2]) {
+        let count = self.scenario.route.len();
+        let from = self.scenario.route[(self.waypoint + count - 1) % count];
+        (from, self.scenario.route[self.waypoint])
+    }
+
+    /// Vitesse du segment en cours (km/h)
+    fn segment_speed(&self) -> f64 {
+        let count = self.scenario.route.len();
+        let segment = (self.waypoint + count - 1) % count;
+        self.scenario
+            .speeds
+            .get(segment)
+            .or(self.scenario.speeds.last())
+            .copied()
+            .unwrap_or(0.0)
+    }
+
+    /// Calcul des mesures bruitées à partir de l'état du véhicule
+    fn measure(&mut self) {
+        let battery = &self.scenario.battery;
+        let throttle = (self.speed_kmh.abs() / self.scenario.max_speed_kmh).min(1.0);
+        let voltage = battery.full - (battery.full - battery.empty) * self.consumed - 0.2 * throttle;
+
+        // Angles: roulis dû à l'accélération latérale, tangage à l'accélération longitudinale
+        let lateral = self.speed_kmh / 3.6 * self.yaw_rate.to_radians();
+        let roll = (lateral / GRAVITY).atan().to_degrees();
+        let pitch = -(self.accel / GRAVITY).atan().to_degrees();
+
+        // Champ magnétique cohérent avec le calcul de cap du HMC8553L
+        let mag_heading = (self.heading + self.rng.noise(1.0)).rem_euclid(360.0);
+        let h = mag_heading.to_radians();
+
+        let signal = self.signal_quality() + self.rng.noise(2.0);
+        let satellites = (9.0 + self.rng.noise(1.0)).round().clamp(4.0, 14.0) as u8;
+        let gps_noise = 1.5 / EARTH_RADIUS;
+
+        self.readings = Readings {
+            latitude: self.latitude + self.rng.noise(gps_noise).to_degrees(),
+            longitude: self.longitude + self.rng.noise(gps_noise).to_degrees(),
+            speed_kmh: (self.speed_kmh.abs() + self.rng.noise(0.2)).max(0.0),
+            gps_heading: (self.heading + self.rng.noise(2.0)).rem_euclid(360.0),
+            satellites,
+            fix: true,
+            // Même ordre que l'IMU réelle: tangage, roulis, lacet
+            angles: (
+                (pitch + self.rng.noise(0.3)) as f32,
+                (roll + self.rng.noise(0.3)) as f32,
+                self.heading as f32,
+            ),
+            temp: (25.0 + 10.0 * (1.0 - (-self.elapsed.as_secs_f64() / 600.0).exp()) + self.rng.noise(0.1)) as f32,
+            // Pesanteur sur Z, accélérations longitudinale et latérale, bruit du capteur
+            accel: (
+                (self.accel / GRAVITY + self.rng.noise(0.02)) as f32,
+                (lateral / GRAVITY + self.rng.noise(0.02)) as f32,
+                (1.0 + self.rng.noise(0.02)) as f32,
+            ),
+            gyro: (
+                self.rng.noise(0.3) as f32,
+                self.rng.noise(0.3) as f32,
+                (self.yaw_rate + self.rng.noise(0.3)) as f32,
+            ),
+            mag_raw: (
+                (MAG_FIELD * h.sin()) as i16,
+                (-MAG_FIELD * h.cos()) as i16,
+                (-MAG_FIELD * 0.6 + self.rng.noise(3.0)) as i16,
+            ),
+            mag_heading: mag_heading as f32,
+            battery: (voltage + self.rng.noise(0.01)) as f32,
+            signal: signal.round().clamp(0.0, 100.0) as u32,
+            taps: [None; MAX_CELLS],
+            current: 0.0,
+            pressure: 0.0,
+            ambient: 0.0,
+            humidity: 0.0,
+            odometer_m: self.odometer,
+            range_mm: 0,
+        };
+
+        // Eléments déséquilibrés au fil de la décharge, la somme reste la tension du pack
+        let count = battery.cells as f64;
+        let mut tap = 0.0;
+        for (index, measured) in self.readings.taps.iter_mut().take(battery.cells as usize).enumerate() {
+            tap += voltage / count + (index as f64 - (count - 1.0) / 2.0) * 0.05 * self.consumed;
+            *measured = Some((tap + self.rng.noise(0.005)) as f32);
+        }
+
+        // Courant cohérent avec la décharge: capacité complète en autonomy_min à pleine charge
+        let full_load_a = battery.capacity_mah / 1000.0 / (battery.autonomy_min / 60.0);
+        let current = (0.05 + 0.95 * throttle) * full_load_a + self.rng.noise(0.05);
+        self.readings.current = current.max(0.0) as f32;
+
+        // Pression: dérive lente (±40 Pa, environ ±3 m sur 15 min) et bruit du capteur
+        let drift = 40.0 * (std::f64::consts::TAU * self.elapsed.as_secs_f64() / 900.0).sin();
+        self.readings.pressure = 101_325.0 + drift + self.rng.noise(2.0);
+        self.readings.ambient = (21.0 + self.rng.noise(0.05)) as f32;
+        self.readings.humidity = (45.0 + self.rng.noise(0.5)) as f32;
+
+        // Obstacle qui s'approche et s'éloigne (1,5 à 3,5 m, période de 2 min)
+        let range = 2500.0 + 1000.0 * (std::f64::consts::TAU * self.elapsed.as_secs_f64() / 120.0).sin();
+        self.readings.range_mm = (range + self.rng.noise(10.0)).round().clamp(0.0, u16::MAX as f64) as u16;
+    }
+
+    /// Qualité du signal à la position actuelle (sans bruit)
+    fn signal_quality(&self) -> f64 {
+        let mut quality = self.scenario.signal.base;
+        for zone in self.scenario.signal.zones.iter() {
+            let distance = self.distance_to(zone.latitude, zone.longitude);
+            if distance < zone.radius_m {
+                let weight = 1.0 - distance / zone.radius_m;
+                quality += (zone.quality - quality) * weight;
+            }
+        }
+
+        quality
+    }
+
+    /// Distance jusqu'à un point (m, approximation locale)
+    fn distance_to(&self, latitude: f64, longitude: f64) -> f64 {
+        let (north, east) = self.offset_to(latitude, longitude);
+        north.hypot(east)
+    }
+
+    /// Cap vers un point (°)
+    fn bearing_to(&self, latitude: f64, longitude: f64) -> f64 {
+        let (north, east) = self.offset_to(latitude, longitude);
+        east.atan2(north).to_degrees().rem_euclid(360.0)
+    }
+
+    fn offset_to(&self, latitude: f64, longitude: f64) -> (f64, f64) {
+        track::offset([self.latitude, self.longitude], [latitude, longitude])
+    }
+}
+
+/// Ramène un angle dans [-180, 180[
+fn wrap_180(angle: f64) -> f64 {
+    (angle + 180.0).rem_euclid(360.0) - 180.0
+}
+
+impl Default for Scenario {
+    fn default() -> Self {
+        // Boucle d'environ 150m x 100m
+        Self {
+            seed: None,
+            step_ms: 50,
+            time_scale: 1.0,
+            route: vec![
+                [46.5200, 6.6300],
+                [46.5200, 6.6320],
+                [46.5209, 6.6320],
+                [46.5209, 6.6300],
+            ],
+            speeds: vec![25.0, 15.0, 25.0, 15.0],
+            track: None,
+            track_speed_kmh: 20.0,
+            max_speed_kmh: 40.0,
+            battery: BatteryScenario::default(),
+            signal: SignalScenario::default(),
+        }
+    }
+}
+
+impl Default for BatteryScenario {
+    fn default() -> Self {
+        Self {
+            full: 12.6,
+            empty: 9.9,
+            autonomy_min: 20.0,
+            cells: 3,
+            capacity_mah: 5000.0,
+        }
+    }
+}
+
+impl Default for SignalScenario {
+    fn default() -> Self {
+        Self {
+            base: 70.0,
+            zones: vec![SignalZone {
+                latitude: 46.5209,
+                longitude: 6.6320,
+                radius_m: 40.0,
+                quality: 15.0,
+            }],
+        }
+    }
+}
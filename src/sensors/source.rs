@@ -0,0 +1,425 @@
+use tokio::sync::watch;
+
+use crate::clock::Clock;
+use crate::config::{Config, MagCalibration, SensorMode};
+use crate::selftest::SelfTest;
+use crate::sensors::reader::{
+    AnalogData, BaroData, Data, EncoderData, GpsData, ImuData, MagData, PowerData, RangeData, RangeStatus, SatellitesData, SensorStatus, SATELLITES_INTERVAL,
+};
+use crate::sensors::baro::altitude::Zero;
+use crate::sensors::encoder::odometry::Odometry;
+use crate::sensors::mag::declination::{self, Declination, Model};
+use crate::sensors::sim::SharedSimulation;
+
+/// Source des données d'un capteur, réelle ou simulée
+pub trait Source: Send {
+    /// Met à jour les données avec un nouvel échantillon, retourne vrai si un échantillon a été lu
+    fn poll(&mut self, data: &mut Data) -> bool;
+
+    /// Etat d'initialisation du capteur
+    fn status(&self) -> &SensorStatus;
+
+    /// Source matérielle: la lecture est bloquante, le thread n'a pas besoin d'attendre
+    fn hardware(&self) -> bool {
+        false
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Kind {
+    Imu,
+    Mag,
+    Analog,
+    Power,
+    Baro,
+    Encoder,
+    Range,
+    Gps,
+}
+
+impl Kind {
+    pub fn name(self) -> &'static str {
+        match self {
+            Kind::Imu => "imu",
+            Kind::Mag => "mag",
+            Kind::Analog => "analog",
+            Kind::Power => "power",
+            Kind::Baro => "baro",
+            Kind::Encoder => "encoder",
+            Kind::Range => "range",
+            Kind::Gps => "gps",
+        }
+    }
+
+    /// Nom de la vérification du premier échantillon (sans allocation, appelé à chaque lecture)
+    pub fn sample_check(self) -> &'static str {
+        match self {
+            Kind::Imu => "imu.sample",
+            Kind::Mag => "mag.sample",
+            Kind::Analog => "analog.sample",
+            Kind::Power => "power.sample",
+            Kind::Baro => "baro.sample",
+            Kind::Encoder => "encoder.sample",
+            Kind::Range => "range.sample",
+            Kind::Gps => "gps.sample",
+        }
+    }
+
+    /// Nom de la vérification d'initialisation
+    pub fn init_check(self) -> &'static str {
+        match self {
+            Kind::Imu => "imu.init",
+            Kind::Mag => "mag.init",
+            Kind::Analog => "analog.init",
+            Kind::Power => "power.init",
+            Kind::Baro => "baro.init",
+            Kind::Encoder => "encoder.init",
+            Kind::Range => "range.init",
+            Kind::Gps => "gps.init",
+        }
+    }
+
+    /// Capteur dont l'identité est vérifiée au démarrage (registre WHO_AM_I)
+    fn identified(self) -> bool {
+        matches!(self, Kind::Imu | Kind::Mag)
+    }
+
+    /// Mode configuré pour ce capteur
+    pub fn mode(self, config: &Config) -> SensorMode {
+        match self {
+            Kind::Imu => config.sensors.imu.mode,
+            Kind::Mag => config.sensors.mag.mode,
+            Kind::Analog => config.sensors.analog.mode,
+            Kind::Power => config.sensors.power.mode,
+            Kind::Baro => config.sensors.baro.mode,
+            Kind::Encoder => config.sensors.encoder.mode,
+            Kind::Range => config.sensors.range.mode,
+            Kind::Gps => config.sensors.gps.mode,
+        }
+    }
+}
+
+/// Ressources partagées par les sources lors de leur création
+pub struct Context {
+    /// Au moins un capteur I2C réel
+    #[cfg(feature = "real-sensors")]
+    pub real_i2c: bool,
+    pub config: Config,
+    pub clock: Clock,
+    pub simulation: SharedSimulation,
+    pub selftest: SelfTest,
+    /// Calibration du magnétomètre, remplacée à la fin d'une calibration
+    pub mag_calibration: watch::Receiver<MagCalibration>,
+    /// Modèle du champ magnétique terrestre (sensors.mag.wmm_file), None: déclinaison fixe
+    pub wmm: Option<Model>,
+    #[cfg(feature = "real-sensors")]
+    pub buses: std::sync::Arc<std::sync::Mutex<crate::i2c::I2cBuses>>,
+}
+
+impl Context {
+    /// Constructeur, ouverture et inventaire des bus I2C si au moins un capteur I2C réel est
+    /// utilisé. Un bus indisponible n'arrête le programme que si `i2c.required` est activé.
+    pub fn new(
+        config: &Config,
+        clock: &Clock,
+        simulation: &SharedSimulation,
+        selftest: &SelfTest,
+        mag_calibration: &watch::Receiver<MagCalibration>,
+    ) -> anyhow::Result<Self> {
+        let real_i2c = [Kind::Imu, Kind::Mag, Kind::Analog, Kind::Power, Kind::Baro, Kind::Range]
+            .iter()
+            .any(|kind| kind.mode(config) == SensorMode::Real);
+
+        #[cfg(feature = "real-sensors")]
+        let buses = {
+            let mut buses = crate::i2c::init_i2c(&config.i2c);
+            if real_i2c {
+                let sensors = [
+                    (config.sensors.imu.mode, &config.sensors.imu.i2c),
+                    (config.sensors.mag.mode, &config.sensors.mag.i2c),
+                    (config.sensors.analog.mode, &config.sensors.analog.i2c),
+                    (config.sensors.power.mode, &config.sensors.power.i2c),
+                    (config.sensors.baro.mode, &config.sensors.baro.i2c),
+                    (config.sensors.range.mode, &config.sensors.range.i2c),
+                ];
+                let devices: Vec<_> = sensors
+                    .iter()
+                    .filter(|(mode, _)| *mode == SensorMode::Real)
+                    .map(|(_, device)| *device)
+                    .collect();
+
+                // Mode dégradé: capteurs I2C indisponibles, bus réessayé par chaque capteur
+                let opened = buses.open_all(&devices);
+                if let Err(e) = opened.as_ref() {
+                    if config.i2c.required {
+                        return Err(anyhow::anyhow!("[I2C] Bus requis indisponible: {}", e));
+                    }
+                    tracing::warn!(target: "i2c", "{}, capteurs I2C indisponibles, nouvel essai périodique.", e);
+                }
+
+                let available = opened.is_ok();
+                selftest.record("i2c.bus", opened);
+                if available {
+                    selftest.record("i2c.scan", buses.inventory());
+                } else {
+                    selftest.skip("i2c.scan", "Bus I2C indisponible");
+                }
+            }
+            std::sync::Arc::new(std::sync::Mutex::new(buses))
+        };
+
+        if !real_i2c {
+            for check in ["i2c.bus", "i2c.scan"] {
+                selftest.skip(check, "Aucun capteur I2C réel");
+            }
+        }
+
+        let wmm = match config.sensors.mag.wmm_file.as_ref() {
+            Some(path) if config.sensors.mag.mode != SensorMode::Disabled => {
+                let model = Model::load(path)?;
+                tracing::info!(target: "mag", "World Magnetic Model {} chargé.", model.epoch);
+                Some(model)
+            }
+            _ => None,
+        };
+
+        Ok(Self {
+            #[cfg(feature = "real-sensors")]
+            real_i2c,
+            config: config.clone(),
+            clock: clock.clone(),
+            simulation: simulation.clone(),
+            selftest: selftest.clone(),
+            mag_calibration: mag_calibration.clone(),
+            wmm,
+            #[cfg(feature = "real-sensors")]
+            buses,
+        })
+    }
+}
+
+/// Création de la source d'un capteur selon son mode
+pub fn build(kind: Kind, context: &Context) -> anyhow::Result<Box<dyn Source>> {
+    let name = kind.name();
+
+    match kind.mode(&context.config) {
+        SensorMode::Real => real(kind, context),
+        SensorMode::Fake => {
+            tracing::info!(target: "sensors", sensor = name, "Capteur simulé.");
+            if kind.identified() {
+                context.selftest.skip(&format!("{}.whoami", name), "Capteur simulé");
+            }
+            context.selftest.record(kind.init_check(), Ok("Capteur simulé".to_string()));
+
+            Ok(Box::new(Simulated {
+                kind,
+                clock: context.clock.clone(),
+                simulation: context.simulation.clone(),
+                mag_calibration: context.mag_calibration.clone(),
+                declination: Declination::new(context.config.sensors.mag.declination_deg, context.wmm.clone()),
+                zero: Zero::new(context.config.sensors.baro.zero_samples),
+                odometry: Odometry::new(
+                    context.config.sensors.encoder.meters_per_pulse(),
+                    context.config.sensors.encoder.interval_ms * 1000,
+                ),
+                meters_per_pulse: context.config.sensors.encoder.meters_per_pulse(),
+                status: SensorStatus {
+                    available: true,
+                    error: None,
+                    attempts: 1,
+                    restarts: 0,
+                },
+            }))
+        }
+        SensorMode::Disabled => {
+            tracing::info!(target: "sensors", sensor = name, "Capteur désactivé.");
+            for check in ["init", "sample"] {
+                context.selftest.skip(&format!("{}.{}", name, check), "Capteur désactivé");
+            }
+            if kind.identified() {
+                context.selftest.skip(&format!("{}.whoami", name), "Capteur désactivé");
+            }
+
+            Ok(Box::new(Disabled {
+                status: SensorStatus {
+                    available: false,
+                    error: Some("Capteur désactivé".to_string()),
+                    attempts: 0,
+                    restarts: 0,
+                },
+            }))
+        }
+    }
+}
+
+#[cfg(feature = "real-sensors")]
+fn real(kind: Kind, context: &Context) -> anyhow::Result<Box<dyn Source>> {
+    use crate::sensors::hardware;
+
+    Ok(match kind {
+        Kind::Imu => Box::new(hardware::ImuSource::new(context)?),
+        Kind::Mag => Box::new(hardware::MagSource::new(context)?),
+        Kind::Analog => Box::new(hardware::AnalogSource::new(context)?),
+        Kind::Power => Box::new(hardware::PowerSource::new(context)?),
+        Kind::Baro => Box::new(hardware::BaroSource::new(context)?),
+        Kind::Encoder => Box::new(hardware::EncoderSource::new(context)?),
+        Kind::Range => Box::new(hardware::RangeSource::new(context)?),
+        Kind::Gps => Box::new(hardware::GpsSource::new(context)?),
+    })
+}
+
+#[cfg(not(feature = "real-sensors"))]
+fn real(kind: Kind, _context: &Context) -> anyhow::Result<Box<dyn Source>> {
+    Err(anyhow::anyhow!(
+        "{}: capteur réel indisponible, programme compilé sans la feature real-sensors",
+        kind.name()
+    ))
+}
+
+/// Capteur simulé, dérivé du véhicule simulé partagé
+struct Simulated {
+    kind: Kind,
+    clock: Clock,
+    simulation: SharedSimulation,
+    mag_calibration: watch::Receiver<MagCalibration>,
+    /// Déclinaison appliquée au cap (magnétomètre)
+    declination: Declination,
+    /// Mise à zéro de l'altitude (baromètre)
+    zero: Zero,
+    /// Vitesse et distance de l'encodeur de roue
+    odometry: Odometry,
+    meters_per_pulse: f64,
+    status: SensorStatus,
+}
+
+impl Source for Simulated {
+    fn poll(&mut self, data: &mut Data) -> bool {
+        let readings = {
+            let mut simulation = self.simulation.lock().unwrap();
+            simulation.sync(self.clock.elapsed());
+            simulation.readings().clone()
+        };
+
+        match self.kind {
+            Kind::Imu => {
+                data.imu = ImuData {
+                    angles: readings.angles,
+                    temp: readings.temp,
+                    accel: readings.accel,
+                    gyro: readings.gyro,
+                    ..data.imu
+                }
+            }
+            Kind::Mag => {
+                let declination = self.declination.at_gps(&data.gps);
+                data.mag = MagData {
+                    raw: readings.mag_raw,
+                    heading: readings.mag_heading,
+                    calibrated: self.mag_calibration.borrow().apply(readings.mag_raw),
+                    true_heading: declination::true_heading(readings.mag_heading, declination),
+                    declination,
+                    ..data.mag
+                }
+            }
+            Kind::Analog => {
+                data.analog.battery = readings.battery;
+                data.analog.cells = AnalogData::cells_from_taps(readings.taps);
+                data.analog.current = Some(readings.current);
+            }
+            Kind::Power => {
+                // Moniteur sur l'alimentation de l'ESC: tension et courant du pack
+                data.power = PowerData {
+                    voltage: readings.battery,
+                    current: readings.current,
+                    power: readings.battery * readings.current,
+                    ..data.power
+                }
+            }
+            Kind::Baro => {
+                data.baro = BaroData {
+                    pressure: readings.pressure as f32,
+                    temperature: readings.ambient,
+                    humidity: Some(readings.humidity),
+                    altitude: self.zero.altitude(readings.pressure),
+                    ..data.baro
+                }
+            }
+            Kind::Encoder => {
+                // Impulsions entières sur la distance parcourue par les roues simulées
+                let count = (readings.odometer_m / self.meters_per_pulse) as u64;
+                let Some(measure) = self.odometry.update(self.clock.stamp().mono_us, count) else {
+                    return false;
+                };
+                data.encoder = EncoderData {
+                    speed_kmh: measure.speed_kmh,
+                    distance_m: measure.distance_m,
+                    pulses: measure.pulses,
+                    ..data.encoder
+                }
+            }
+            Kind::Range => {
+                // Cible toujours à portée du capteur simulé
+                data.range = RangeData {
+                    distance_mm: Some(readings.range_mm),
+                    status: RangeStatus::Valid,
+                    ..data.range
+                }
+            }
+            Kind::Gps => {
+                let stamp = self.clock.stamp();
+                data.gps = GpsData {
+                    speed_kmh: readings.speed_kmh,
+                    latitude: readings.latitude,
+                    longitude: readings.longitude,
+                    satellites: readings.satellites,
+                    fix: readings.fix,
+                    heading: readings.gps_heading,
+                    // Trame RMC simulée: heure du récepteur, active avec une position
+                    time: Some(stamp.utc),
+                    valid: readings.fix,
+                    ..data.gps
+                };
+
+                // Satellites simulés, précision dégradée avec peu de satellites
+                let elapsed = stamp.mono_us.saturating_sub(data.satellites.stamp.mono_us);
+                if elapsed >= SATELLITES_INTERVAL.as_micros() as u64 {
+                    let hdop = if readings.fix {
+                        6.0 / readings.satellites.max(1) as f64
+                    } else {
+                        99.99
+                    };
+                    data.satellites = SatellitesData {
+                        stamp,
+                        pdop: hdop * 1.5,
+                        hdop,
+                        vdop: hdop * 1.2,
+                        used: readings.satellites,
+                        in_view: readings.satellites.saturating_add(4),
+                        snr_mean: 38.0,
+                    };
+                }
+            }
+        }
+
+        true
+    }
+
+    fn status(&self) -> &SensorStatus {
+        &self.status
+    }
+}
+
+/// Capteur désactivé, ne produit aucune donnée
+struct Disabled {
+    status: SensorStatus,
+}
+
+impl Source for Disabled {
+    fn poll(&mut self, _data: &mut Data) -> bool {
+        false
+    }
+
+    fn status(&self) -> &SensorStatus {
+        &self.status
+    }
+}
@@ -0,0 +1,161 @@
+use std::any::Any;
+use std::panic::{catch_unwind, AssertUnwindSafe};
+use std::time::{Duration, Instant};
+
+use crate::config::SupervisorConfig;
+use crate::sensors::reader::{Data, SensorStatus};
+use crate::sensors::source::{Kind, Source};
+
+/// Attente maximale entre deux redémarrages
+const BACKOFF_MAX: Duration = Duration::from_secs(60);
+
+/// Source d'un capteur supervisée: recréée après une panique de sa lecture, ou lorsqu'elle se
+/// dit disponible sans plus produire d'échantillon, au plus `max_restarts` fois
+pub struct Supervised {
+    pub kind: Kind,
+    source: Option<Box<dyn Source>>,
+    max_restarts: u32,
+    backoff: Duration,
+    /// None: blocage non détecté
+    stuck: Option<Duration>,
+    restarts: u32,
+    /// Prochaine recréation de la source
+    retry_at: Instant,
+    /// Dernier échantillon de la source en cours, None: aucun
+    last_sample: Option<Instant>,
+    /// Etat publié sans source
+    down: SensorStatus,
+}
+
+impl Supervised {
+    pub fn new(kind: Kind, source: Box<dyn Source>, config: &SupervisorConfig, now: Instant) -> Self {
+        Self {
+            kind,
+            source: Some(source),
+            max_restarts: config.max_restarts,
+            backoff: Duration::from_millis(config.backoff_ms),
+            stuck: (config.stuck_s > 0).then(|| Duration::from_secs(config.stuck_s)),
+            restarts: 0,
+            retry_at: now,
+            last_sample: None,
+            down: SensorStatus::default(),
+        }
+    }
+
+    /// Interroge la source, recréée par `build` après l'attente si elle a été arrêtée. Vrai si
+    /// un échantillon a été produit.
+    pub fn poll(
+        &mut self,
+        data: &mut Data,
+        now: Instant,
+        build: impl FnOnce(Kind) -> anyhow::Result<Box<dyn Source>>,
+    ) -> bool {
+        if self.source.is_none() {
+            if self.restarts > self.max_restarts || now < self.retry_at {
+                return false;
+            }
+            match catch_unwind(AssertUnwindSafe(|| build(self.kind))) {
+                Ok(Ok(source)) => {
+                    tracing::info!(
+                        target: "sensors",
+                        sensor = self.kind.name(),
+                        "Capteur recréé ({} redémarrage(s))",
+                        self.restarts
+                    );
+                    self.source = Some(source);
+                    self.last_sample = None;
+                }
+                Ok(Err(e)) => {
+                    self.stop(now, format!("recréation impossible: {}", e));
+                    return false;
+                }
+                Err(payload) => {
+                    self.stop(now, format!("panique à la recréation: {}", message(payload.as_ref())));
+                    return false;
+                }
+            }
+        }
+
+        let Some(source) = self.source.as_mut() else {
+            return false;
+        };
+        match catch_unwind(AssertUnwindSafe(|| source.poll(data))) {
+            Ok(true) => {
+                self.last_sample = Some(now);
+                true
+            }
+            Ok(false) => {
+                // Un capteur perdu (indisponible) est déjà réessayé par sa source
+                if let (Some(stuck), Some(last)) = (self.stuck, self.last_sample) {
+                    if source.status().available && now.saturating_duration_since(last) >= stuck {
+                        self.stop(now, format!("aucun échantillon depuis {} s", stuck.as_secs()));
+                    }
+                }
+                false
+            }
+            Err(payload) => {
+                self.stop(now, format!("panique: {}", message(payload.as_ref())));
+                false
+            }
+        }
+    }
+
+    /// Arrête la source, recréée après une attente doublée à chaque redémarrage
+    fn stop(&mut self, now: Instant, reason: String) {
+        self.source = None;
+        self.restarts += 1;
+        let delay = self.backoff.saturating_mul(1 << (self.restarts - 1).min(16)).min(BACKOFF_MAX);
+        self.retry_at = now + delay;
+
+        if self.restarts > self.max_restarts {
+            tracing::error!(
+                target: "sensors",
+                sensor = self.kind.name(),
+                "Capteur arrêté ({}), abandonné après {} redémarrage(s)",
+                reason,
+                self.max_restarts
+            );
+        } else {
+            tracing::warn!(
+                target: "sensors",
+                sensor = self.kind.name(),
+                "Capteur arrêté ({}), redémarrage {}/{} dans {:.1} s",
+                reason,
+                self.restarts,
+                self.max_restarts,
+                delay.as_secs_f64()
+            );
+        }
+
+        self.down = SensorStatus {
+            available: false,
+            error: Some(reason),
+            attempts: 0,
+            restarts: self.restarts,
+        };
+    }
+
+    /// Etat de la source, ou de l'arrêt en attendant sa recréation
+    pub fn status(&self) -> &SensorStatus {
+        self.source.as_ref().map_or(&self.down, |source| source.status())
+    }
+
+    /// Redémarrages depuis le démarrage
+    pub fn restarts(&self) -> u32 {
+        self.restarts
+    }
+
+    /// Source matérielle (lecture bloquante)
+    pub fn hardware(&self) -> bool {
+        self.source.as_ref().is_some_and(|source| source.hardware())
+    }
+}
+
+/// Message d'une panique
+fn message(payload: &(dyn Any + Send)) -> String {
+    payload
+        .downcast_ref::<&str>()
+        .map(|message| message.to_string())
+        .or_else(|| payload.downcast_ref::<String>().cloned())
+        .unwrap_or_else(|| "(message non textuel)".to_string())
+}
@@ -0,0 +1,158 @@
+use std::path::Path;
+
+use chrono::{DateTime, Utc};
+
+/// Rayon moyen de la terre (m)
+const EARTH_RADIUS: f64 = 6_371_000.0;
+
+/// Point d'un parcours chargé depuis un fichier
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Point {
+    pub latitude: f64,
+    pub longitude: f64,
+    /// Vitesse (km/h) sur le segment partant de ce point, si connue
+    pub speed_kmh: Option<f64>,
+}
+
+/// Lecture d'un parcours: GPX (trkpt ou rtept, vitesse déduite des balises time) ou CSV
+/// (latitude,longitude[,vitesse km/h] par ligne, en-tête et lignes # ignorés)
+pub fn load(path: &Path) -> anyhow::Result<Vec<Point>> {
+    let content = std::fs::read_to_string(path)?;
+    parse(&content).map_err(|e| anyhow::anyhow!("{}: {}", path.display(), e))
+}
+
+/// Comme `load`, format détecté depuis le contenu
+pub fn parse(content: &str) -> anyhow::Result<Vec<Point>> {
+    let points = if content.trim_start().starts_with('<') {
+        parse_gpx(content)?
+    } else {
+        parse_csv(content)?
+    };
+
+    if points.len() < 2 {
+        return Err(anyhow::anyhow!("{} point(s), au moins 2 attendus", points.len()));
+    }
+
+    Ok(points)
+}
+
+fn parse_csv(content: &str) -> anyhow::Result<Vec<Point>> {
+    let mut points = Vec::new();
+    let mut header = false;
+
+    for (n, line) in content.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+
+        let fields: Vec<&str> = line.split([',', ';']).map(str::trim).collect();
+        let values: Result<Vec<f64>, _> = fields.iter().map(|field| field.parse::<f64>()).collect();
+        let values = match values {
+            Ok(values) if (2..=3).contains(&values.len()) => values,
+            // En-tête (latitude,longitude,...)
+            Err(_) if points.is_empty() && !header => {
+                header = true;
+                continue;
+            }
+            _ => return Err(anyhow::anyhow!("ligne {}: \"{}\" invalide", n + 1, line)),
+        };
+
+        points.push(point(values[0], values[1], values.get(2).copied())?);
+    }
+
+    Ok(points)
+}
+
+fn parse_gpx(content: &str) -> anyhow::Result<Vec<Point>> {
+    let mut points: Vec<Point> = Vec::new();
+    let mut previous_time: Option<DateTime<Utc>> = None;
+    let mut rest = content;
+
+    while let Some(start) = next_point(rest) {
+        rest = &rest[start..];
+        let tag_end = rest.find('>').ok_or_else(|| anyhow::anyhow!("balise de point non terminée"))?;
+        let tag = &rest[..tag_end];
+
+        // Contenu du point jusqu'à sa fermeture (absent pour <trkpt ... />)
+        let body = if tag.ends_with('/') {
+            ""
+        } else {
+            let close = rest[tag_end..].find("pt>").map_or(rest.len(), |close| tag_end + close);
+            &rest[tag_end..close]
+        };
+
+        let latitude = attribute(tag, "lat")?;
+        let longitude = attribute(tag, "lon")?;
+        let time = element(body, "time").and_then(|time| DateTime::parse_from_rfc3339(time).ok());
+        let time = time.map(|time| time.with_timezone(&Utc));
+
+        // Vitesse du segment précédent, depuis les heures de passage
+        if let (Some(last), Some(from), Some(to)) = (points.last_mut(), previous_time, time) {
+            let seconds = (to - from).num_milliseconds() as f64 / 1000.0;
+            if seconds > 0.0 {
+                let distance = distance([last.latitude, last.longitude], [latitude, longitude]);
+                last.speed_kmh = Some(distance / seconds * 3.6);
+            }
+        }
+
+        points.push(point(latitude, longitude, None)?);
+        previous_time = time;
+        rest = &rest[tag_end..];
+    }
+
+    Ok(points)
+}
+
+/// Position de la prochaine balise de point de trace ou de route
+fn next_point(content: &str) -> Option<usize> {
+    ["<trkpt", "<rtept"].iter().filter_map(|tag| content.find(tag)).min()
+}
+
+/// Valeur numérique d'un attribut (lat="46.52")
+fn attribute(tag: &str, name: &str) -> anyhow::Result<f64> {
+    let value = tag
+        .split_whitespace()
+        .find_map(|token| token.strip_prefix(name)?.strip_prefix('='))
+        .ok_or_else(|| anyhow::anyhow!("attribut {} absent: {}", name, tag))?;
+    let value = value.trim_end_matches('/').trim_matches(['"', '\'']);
+
+    value
+        .parse()
+        .map_err(|_| anyhow::anyhow!("attribut {} invalide: {}", name, value))
+}
+
+/// Texte d'un élément (<time>...</time>)
+fn element<'a>(body: &'a str, name: &str) -> Option<&'a str> {
+    let start = body.find(&format!("<{}>", name))? + name.len() + 2;
+    let end = body[start..].find("</")?;
+    Some(body[start..start + end].trim())
+}
+
+fn point(latitude: f64, longitude: f64, speed_kmh: Option<f64>) -> anyhow::Result<Point> {
+    if !(-90.0..=90.0).contains(&latitude) || !(-180.0..=180.0).contains(&longitude) {
+        return Err(anyhow::anyhow!("position invalide: {}, {}", latitude, longitude));
+    }
+    if let Some(speed) = speed_kmh.filter(|speed| !(speed.is_finite() && *speed >= 0.0)) {
+        return Err(anyhow::anyhow!("vitesse invalide: {}", speed));
+    }
+
+    Ok(Point {
+        latitude,
+        longitude,
+        speed_kmh,
+    })
+}
+
+/// Déplacement (nord, est) entre deux points (m, approximation locale)
+pub fn offset(from: [f64; 2], to: [f64; 2]) -> (f64, f64) {
+    let north = (to[0] - from[0]).to_radians() * EARTH_RADIUS;
+    let east = (to[1] - from[1]).to_radians() * EARTH_RADIUS * from[0].to_radians().cos();
+    (north, east)
+}
+
+/// Distance entre deux points (m, approximation locale)
+pub fn distance(from: [f64; 2], to: [f64; 2]) -> f64 {
+    let (north, east) = offset(from, to);
+    north.hypot(east)
+}
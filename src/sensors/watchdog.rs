@@ -0,0 +1,327 @@
+use serde::Serialize;
+use std::time::{Duration, Instant};
+
+use crate::clock::Stamp;
+use crate::config::{Config, Mitigation, SensorMode, Severity, WatchdogConfig};
+use crate::sensors::reader::{Data, SensorStatus, SensorsStatus};
+use crate::sensors::source::Kind;
+
+/// Etat de santé global des capteurs
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Health {
+    #[default]
+    Ok,
+    /// Au moins un capteur périmé
+    Degraded,
+    /// Au moins un capteur critique périmé
+    Critical,
+}
+
+/// Etat d'un composant (capteur ou liaison de contrôle)
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Status {
+    Ok,
+    /// Aucune lecture ou commande depuis le seuil du composant
+    Stale,
+    /// Capteur indisponible (initialisation ou lecture en échec)
+    Error,
+    Disabled,
+}
+
+/// Santé d'un composant, écrite dans health:<name>
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct Component {
+    pub name: &'static str,
+    pub status: Status,
+    /// Durée depuis la dernière lecture ou commande (ms), None: jamais
+    pub silent_ms: Option<u64>,
+    /// Dernière erreur du capteur
+    pub error: Option<String>,
+    /// Redémarrages du capteur par le superviseur
+    pub restarts: u32,
+}
+
+/// Santé de la liaison de contrôle selon l'âge de la dernière commande (None: aucune)
+pub fn control(last_command: Option<Duration>, stale: Duration) -> Component {
+    Component {
+        name: "control",
+        status: match last_command {
+            Some(age) if age < stale => Status::Ok,
+            _ => Status::Stale,
+        },
+        silent_ms: last_command.map(|age| age.as_millis() as u64),
+        error: None,
+        restarts: 0,
+    }
+}
+
+/// Santé de la boucle de contrôle selon l'âge de la dernière sortie appliquée (None: aucune).
+/// Une boucle terminée (actionneurs indisponibles) est désactivée.
+pub fn control_loop(last_applied: Option<Duration>, closed: bool, stale: Duration) -> Component {
+    Component {
+        name: "control_loop",
+        status: match last_applied {
+            _ if closed => Status::Disabled,
+            Some(age) if age < stale => Status::Ok,
+            _ => Status::Stale,
+        },
+        silent_ms: last_applied.map(|age| age.as_millis() as u64),
+        error: None,
+        restarts: 0,
+    }
+}
+
+/// Dernière santé des composants publiée par le watchdog, partagée avec le watchdog systemd
+#[derive(Clone, Debug)]
+#[cfg_attr(not(feature = "systemd"), allow(dead_code))]
+pub struct Snapshot {
+    /// Instant de la mesure (temps réel)
+    pub at: Instant,
+    pub components: Vec<Component>,
+}
+
+impl Snapshot {
+    /// Composant en défaut (périmé ou en erreur), hors liaison de contrôle: une commande absente
+    /// n'est pas un défaut du véhicule
+    pub fn degraded(&self) -> bool {
+        self.components.iter().any(|component| {
+            component.name != "control" && matches!(component.status, Status::Stale | Status::Error)
+        })
+    }
+
+    /// Raison d'un blocage des tâches principales, None: progression normale. Bloquées si la
+    /// santé n'a plus été publiée depuis `max_age` ou si la boucle de contrôle est périmée.
+    #[cfg_attr(not(feature = "systemd"), allow(dead_code))]
+    pub fn blocked(&self, now: Instant, max_age: Duration) -> Option<String> {
+        let age = now.saturating_duration_since(self.at);
+        if age >= max_age {
+            return Some(format!("santé non publiée depuis {:.1} s", age.as_secs_f64()));
+        }
+        self.components
+            .iter()
+            .find(|component| component.name == "control_loop" && component.status == Status::Stale)
+            .map(|component| match component.silent_ms {
+                Some(silent) => format!("boucle de contrôle sans sortie depuis {} ms", silent),
+                None => "boucle de contrôle non démarrée".to_string(),
+            })
+    }
+}
+
+/// Capteur devenu périmé ou de retour
+#[derive(Debug, PartialEq)]
+pub struct Transition {
+    pub kind: Kind,
+    pub stale: bool,
+    pub mitigation: Mitigation,
+    /// Durée sans échantillon
+    pub silent: Duration,
+}
+
+impl Transition {
+    pub fn event(&self) -> &'static str {
+        if self.stale {
+            "sensor_stale"
+        } else {
+            "sensor_recovered"
+        }
+    }
+
+    pub fn severity(&self) -> Severity {
+        match (self.stale, self.mitigation) {
+            (false, _) => Severity::Info,
+            (true, Mitigation::None) => Severity::Warning,
+            (true, _) => Severity::Critical,
+        }
+    }
+
+    pub fn message(&self) -> String {
+        let name = self.kind.name();
+        if !self.stale {
+            return format!("Capteur {} rétabli après {:.1} s", name, self.silent.as_secs_f64());
+        }
+        let action = match self.mitigation {
+            Mitigation::None => "",
+            Mitigation::Limit => ": vitesse limitée",
+            Mitigation::Failsafe => ": moteur coupé, véhicule désarmé",
+        };
+        format!(
+            "Capteur {} périmé, aucun échantillon depuis {:.1} s{}",
+            name,
+            self.silent.as_secs_f64(),
+            action
+        )
+    }
+}
+
+/// Suivi d'un capteur
+struct Watched {
+    kind: Kind,
+    timeout: Duration,
+    mitigation: Mitigation,
+    last_stamp: Option<Stamp>,
+    last_change: Instant,
+    stale: bool,
+}
+
+/// Fraîcheur des capteurs: un capteur est surveillé à partir de son premier échantillon, un
+/// capteur désactivé ou jamais initialisé n'est donc jamais périmé.
+pub struct Watchdog {
+    sensors: Vec<Watched>,
+    limit: f64,
+}
+
+impl Watchdog {
+    pub fn new(config: &WatchdogConfig, now: Instant) -> Self {
+        let sensors = [
+            (Kind::Imu, &config.imu),
+            (Kind::Mag, &config.mag),
+            (Kind::Analog, &config.analog),
+            (Kind::Power, &config.power),
+            (Kind::Baro, &config.baro),
+            (Kind::Encoder, &config.encoder),
+            (Kind::Range, &config.range),
+            (Kind::Gps, &config.gps),
+        ]
+        .into_iter()
+        .map(|(kind, sensor)| Watched {
+            kind,
+            timeout: Duration::from_millis(sensor.interval_ms).mul_f64(config.tolerance),
+            mitigation: sensor.mitigation,
+            last_stamp: None,
+            last_change: now,
+            stale: false,
+        })
+        .collect();
+
+        Self {
+            sensors,
+            limit: config.limit_max_speed,
+        }
+    }
+
+    /// Dernières valeurs des capteurs, retourne les capteurs périmés ou rétablis
+    pub fn update(&mut self, data: &Data, now: Instant) -> Vec<Transition> {
+        let mut transitions = Vec::new();
+
+        for sensor in self.sensors.iter_mut() {
+            let stamp = match sensor.kind {
+                Kind::Imu => data.imu.stamp,
+                Kind::Mag => data.mag.stamp,
+                Kind::Analog => data.analog.stamp,
+                Kind::Power => data.power.stamp,
+                Kind::Baro => data.baro.stamp,
+                Kind::Encoder => data.encoder.stamp,
+                Kind::Range => data.range.stamp,
+                Kind::Gps => data.gps.stamp,
+            };
+            if stamp.mono_us == 0 {
+                continue;
+            }
+
+            let silent = now.duration_since(sensor.last_change);
+            if sensor.last_stamp != Some(stamp) {
+                // Premier échantillon: pas de retour à signaler
+                let first = sensor.last_stamp.is_none();
+                sensor.last_stamp = Some(stamp);
+                sensor.last_change = now;
+                if sensor.stale && !first {
+                    sensor.stale = false;
+                    transitions.push(Transition {
+                        kind: sensor.kind,
+                        stale: false,
+                        mitigation: sensor.mitigation,
+                        silent,
+                    });
+                }
+                continue;
+            }
+
+            if !sensor.stale && silent >= sensor.timeout {
+                sensor.stale = true;
+                transitions.push(Transition {
+                    kind: sensor.kind,
+                    stale: true,
+                    mitigation: sensor.mitigation,
+                    silent,
+                });
+            }
+        }
+
+        transitions
+    }
+
+    /// Santé de chaque capteur: désactivé, en erreur (indisponible), périmé (y compris sans
+    /// aucun échantillon depuis le démarrage) ou ok
+    pub fn components(&self, config: &Config, status: &SensorsStatus, now: Instant) -> Vec<Component> {
+        self.sensors
+            .iter()
+            .map(|sensor| {
+                let sensor_status = match sensor.kind {
+                    Kind::Imu => &status.imu,
+                    Kind::Mag => &status.mag,
+                    Kind::Analog => &status.analog,
+                    Kind::Power => &status.power,
+                    Kind::Baro => &status.baro,
+                    Kind::Encoder => &status.encoder,
+                    Kind::Range => &status.range,
+                    Kind::Gps => &status.gps,
+                };
+                let silent = now.duration_since(sensor.last_change);
+                let status = match sensor_status {
+                    _ if sensor.kind.mode(config) == SensorMode::Disabled => Status::Disabled,
+                    SensorStatus {
+                        available: false,
+                        error: Some(_),
+                        ..
+                    } => Status::Error,
+                    _ if sensor.stale || silent >= sensor.timeout => Status::Stale,
+                    _ => Status::Ok,
+                };
+
+                Component {
+                    name: sensor.kind.name(),
+                    status,
+                    silent_ms: sensor.last_stamp.map(|_| silent.as_millis() as u64),
+                    error: sensor_status.error.clone(),
+                    restarts: sensor_status.restarts,
+                }
+            })
+            .collect()
+    }
+
+    /// Capteurs périmés
+    pub fn stale(&self) -> Vec<&'static str> {
+        self.sensors
+            .iter()
+            .filter(|sensor| sensor.stale)
+            .map(|sensor| sensor.kind.name())
+            .collect()
+    }
+
+    pub fn health(&self) -> Health {
+        self.sensors
+            .iter()
+            .filter(|sensor| sensor.stale)
+            .map(|sensor| match sensor.mitigation {
+                Mitigation::None => Health::Degraded,
+                _ => Health::Critical,
+            })
+            .max()
+            .unwrap_or(Health::Ok)
+    }
+
+    /// Vitesse maximale imposée par les capteurs critiques périmés, None: aucune
+    pub fn max_speed(&self) -> Option<f64> {
+        self.sensors
+            .iter()
+            .filter(|sensor| sensor.stale)
+            .filter_map(|sensor| match sensor.mitigation {
+                Mitigation::None => None,
+                Mitigation::Limit => Some(self.limit),
+                Mitigation::Failsafe => Some(0.0),
+            })
+            .reduce(f64::min)
+    }
+}
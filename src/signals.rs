@@ -0,0 +1,39 @@
+#[cfg(unix)]
+use tokio::signal::unix::{signal, Signal, SignalKind};
+
+/// Signaux d'arrêt: SIGINT et SIGTERM (`systemctl stop`) sous Unix, Ctrl-C ailleurs.
+/// A installer dès le démarrage: un signal reçu pendant l'initialisation est conservé.
+pub struct Signals {
+    #[cfg(unix)]
+    interrupt: Signal,
+    #[cfg(unix)]
+    terminate: Signal,
+}
+
+impl Signals {
+    pub fn install() -> anyhow::Result<Self> {
+        #[cfg(unix)]
+        return Ok(Self {
+            interrupt: signal(SignalKind::interrupt())?,
+            terminate: signal(SignalKind::terminate())?,
+        });
+
+        #[cfg(not(unix))]
+        Ok(Self {})
+    }
+
+    /// Attend le prochain signal d'arrêt, renvoie son nom
+    pub async fn recv(&mut self) -> &'static str {
+        #[cfg(unix)]
+        return tokio::select! {
+            _ = self.interrupt.recv() => "SIGINT",
+            _ = self.terminate.recv() => "SIGTERM",
+        };
+
+        #[cfg(not(unix))]
+        {
+            let _ = tokio::signal::ctrl_c().await;
+            "Ctrl-C"
+        }
+    }
+}
@@ -0,0 +1,333 @@
+use std::collections::VecDeque;
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::path::{Path, PathBuf};
+
+use prost::Message;
+use serde::Serialize;
+
+use crate::config::SpoolConfig;
+use crate::proto;
+use crate::record::Record;
+
+/// En-tête d'un segment, suivi de la version du format
+const SPOOL_MAGIC: &[u8; 4] = b"RCSP";
+const SPOOL_VERSION: u8 = 1;
+
+/// Nombre de segments pour la taille maximale: un segment est la plus petite partie supprimée
+const SEGMENTS: u64 = 8;
+
+/// Echantillons au plus par segment (relu entièrement en mémoire)
+const SEGMENT_MAX_RECORDS: u64 = 10_000;
+
+/// Occupation du tampon
+#[derive(Clone, Copy, Debug, Default, PartialEq, Serialize)]
+pub struct SpoolStats {
+    /// Echantillons en attente
+    pub records: u64,
+    /// Taille des segments sur le disque
+    pub bytes: u64,
+    /// Echantillons supprimés, tampon plein (les plus anciens d'abord)
+    pub evicted: u64,
+    /// Echantillons relus
+    pub replayed: u64,
+    /// Echecs d'écriture dans le tampon
+    pub errors: u64,
+}
+
+/// Fichier segment-<n>.spool
+struct Segment {
+    number: u64,
+    records: u64,
+    bytes: u64,
+}
+
+/// Tampon local des échantillons non écrits en base, relus dans l'ordre d'arrivée.
+/// Segments: en-tête puis échantillons précédés de leur taille (protobuf, même schéma que les
+/// enregistrements). Un segment est supprimé une fois entièrement relu: après un arrêt, le segment
+/// en cours de relecture est relu depuis le début.
+pub struct Spool {
+    directory: PathBuf,
+    max_records: u64,
+    max_bytes: u64,
+    /// Taille d'un segment avant d'en commencer un nouveau
+    segment_records: u64,
+    segment_bytes: u64,
+    segments: VecDeque<Segment>,
+    /// Dernier segment, ouvert en écriture
+    file: Option<BufWriter<File>>,
+    /// Echantillons du premier segment pas encore relus, None: segment pas encore lu
+    pending: Option<VecDeque<Record>>,
+    /// Ecriture en échec (journal uniquement pour le premier échec d'une série)
+    failing: bool,
+    stats: SpoolStats,
+}
+
+impl Spool {
+    /// Ouvre le tampon, reprend les segments d'une exécution précédente
+    pub fn open(config: &SpoolConfig) -> anyhow::Result<Self> {
+        std::fs::create_dir_all(&config.directory)?;
+
+        let max_bytes = config.max_mb * 1024 * 1024;
+        let mut spool = Self {
+            directory: config.directory.clone(),
+            max_records: config.max_records,
+            max_bytes,
+            segment_records: (config.max_records / SEGMENTS).clamp(1, SEGMENT_MAX_RECORDS),
+            segment_bytes: (max_bytes / SEGMENTS).max(1),
+            segments: VecDeque::new(),
+            file: None,
+            pending: None,
+            failing: false,
+            stats: SpoolStats::default(),
+        };
+
+        let mut numbers = Vec::new();
+        for entry in std::fs::read_dir(&config.directory)? {
+            let name = entry?.file_name().to_string_lossy().to_string();
+            if let Some(number) = name
+                .strip_prefix("segment-")
+                .and_then(|name| name.strip_suffix(".spool"))
+                .and_then(|number| number.parse::<u64>().ok())
+            {
+                numbers.push(number);
+            }
+        }
+        numbers.sort_unstable();
+
+        for number in numbers {
+            let path = spool.path(number);
+            match read(&path) {
+                Ok(records) => {
+                    let bytes = std::fs::metadata(&path)?.len();
+                    spool.stats.records += records.len() as u64;
+                    spool.stats.bytes += bytes;
+                    spool.segments.push_back(Segment {
+                        number,
+                        records: records.len() as u64,
+                        bytes,
+                    });
+                }
+                Err(e) => {
+                    tracing::warn!(target: "spool", "Segment {} ignoré: {}", path.display(), e);
+                    let _ = std::fs::remove_file(&path);
+                }
+            }
+        }
+
+        Ok(spool)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.stats.records == 0
+    }
+
+    pub fn stats(&self) -> SpoolStats {
+        self.stats
+    }
+
+    /// Ajoute un échantillon à la fin du tampon, supprime les plus anciens au-delà des limites
+    pub fn push(&mut self, record: &Record) {
+        let result = self.append(record);
+        self.result(result);
+
+        while self.stats.records > self.max_records || self.stats.bytes > self.max_bytes {
+            if !self.evict() {
+                break;
+            }
+        }
+    }
+
+    /// Compte un échec d'écriture, journal uniquement pour le premier échec d'une série
+    fn result(&mut self, result: anyhow::Result<()>) {
+        match result {
+            Ok(_) => self.failing = false,
+            Err(e) => {
+                self.stats.errors += 1;
+                if !self.failing {
+                    tracing::error!(
+                        target: "spool",
+                        "Impossible d'écrire dans {}: {}",
+                        self.directory.display(),
+                        e
+                    );
+                }
+                self.failing = true;
+            }
+        }
+    }
+
+    fn append(&mut self, record: &Record) -> anyhow::Result<()> {
+        let full = self.segments.back().is_none_or(|last| {
+            last.records >= self.segment_records || last.bytes >= self.segment_bytes
+        });
+        if self.file.is_none() || full {
+            self.start()?;
+        }
+
+        let buffer = proto::Record::from(*record).encode_length_delimited_to_vec();
+        self.file.as_mut().unwrap().write_all(&buffer)?;
+
+        let last = self.segments.back_mut().unwrap();
+        last.records += 1;
+        last.bytes += buffer.len() as u64;
+        self.stats.records += 1;
+        self.stats.bytes += buffer.len() as u64;
+        Ok(())
+    }
+
+    /// Commence un nouveau segment
+    fn start(&mut self) -> anyhow::Result<()> {
+        self.seal()?;
+
+        let number = self.segments.back().map_or(0, |last| last.number + 1);
+        let mut file = BufWriter::new(File::create(self.path(number))?);
+        file.write_all(SPOOL_MAGIC)?;
+        file.write_all(&[SPOOL_VERSION])?;
+
+        self.segments.push_back(Segment {
+            number,
+            records: 0,
+            bytes: (SPOOL_MAGIC.len() + 1) as u64,
+        });
+        self.stats.bytes += (SPOOL_MAGIC.len() + 1) as u64;
+        self.file = Some(file);
+        Ok(())
+    }
+
+    /// Ferme le segment en cours d'écriture
+    fn seal(&mut self) -> anyhow::Result<()> {
+        if let Some(mut file) = self.file.take() {
+            file.flush()?;
+        }
+        Ok(())
+    }
+
+    /// Ecrit les échantillons en attente dans le segment
+    pub fn flush(&mut self) {
+        if let Some(file) = self.file.as_mut() {
+            let result = file.flush().map_err(Into::into);
+            self.result(result);
+        }
+    }
+
+    /// Plus ancien échantillon, None: tampon vide
+    pub fn front(&mut self) -> anyhow::Result<Option<Record>> {
+        loop {
+            if let Some(record) = self.pending.as_ref().and_then(|pending| pending.front()) {
+                return Ok(Some(*record));
+            }
+
+            // Premier segment entièrement relu
+            if self.pending.is_some() {
+                self.remove_front();
+            }
+
+            if self.segments.is_empty() {
+                return Ok(None);
+            }
+
+            // Le segment en cours d'écriture est fermé avant d'être relu
+            if self.segments.len() == 1 {
+                self.seal()?;
+            }
+
+            let number = self.segments[0].number;
+            let records = match read(&self.path(number)) {
+                Ok(records) => records,
+                Err(e) => {
+                    tracing::error!(target: "spool", "Segment {} illisible: {}", number, e);
+                    Vec::new()
+                }
+            };
+
+            // Echantillons illisibles (fin tronquée): retirés du compte
+            let front = &mut self.segments[0];
+            self.stats.records -= front
+                .records
+                .saturating_sub(records.len() as u64)
+                .min(self.stats.records);
+            front.records = records.len() as u64;
+            self.pending = Some(records.into());
+        }
+    }
+
+    /// Retire le plus ancien échantillon, après son écriture en base
+    pub fn pop(&mut self) {
+        if let Some(pending) = self.pending.as_mut() {
+            if pending.pop_front().is_some() {
+                self.stats.records -= 1;
+                self.stats.replayed += 1;
+            }
+        }
+    }
+
+    /// Supprime le plus ancien segment, faux: rien à supprimer
+    fn evict(&mut self) -> bool {
+        // Le segment en cours d'écriture est conservé s'il est seul
+        if self.segments.len() < 2 {
+            return false;
+        }
+
+        let remaining = match self.pending.as_ref() {
+            Some(pending) => pending.len() as u64,
+            None => self.segments[0].records,
+        };
+        self.stats.evicted += remaining;
+        self.stats.records -= remaining.min(self.stats.records);
+        self.remove_front();
+        true
+    }
+
+    fn remove_front(&mut self) {
+        self.pending = None;
+        if let Some(segment) = self.segments.pop_front() {
+            self.stats.bytes -= segment.bytes.min(self.stats.bytes);
+            if let Err(e) = std::fs::remove_file(self.path(segment.number)) {
+                tracing::error!(
+                    target: "spool",
+                    "Impossible de supprimer le segment {}: {}",
+                    segment.number, e
+                );
+            }
+        }
+    }
+
+    fn path(&self, number: u64) -> PathBuf {
+        self.directory.join(format!("segment-{:010}.spool", number))
+    }
+}
+
+/// Echantillons d'un segment. Lecture arrêtée au premier échantillon tronqué ou invalide
+/// (arrêt pendant une écriture).
+fn read(path: &Path) -> anyhow::Result<Vec<Record>> {
+    let content = std::fs::read(path)?;
+    let Some(mut buffer) = content.strip_prefix(SPOOL_MAGIC.as_slice()) else {
+        return Err(anyhow::anyhow!("en-tête invalide"));
+    };
+
+    match buffer.first() {
+        Some(&SPOOL_VERSION) => buffer = &buffer[1..],
+        Some(version) => {
+            return Err(anyhow::anyhow!(
+                "version {} du format non supportée",
+                version
+            ))
+        }
+        None => return Err(anyhow::anyhow!("en-tête tronqué")),
+    }
+
+    let mut records = Vec::new();
+    while !buffer.is_empty() {
+        let Ok(record) = proto::Record::decode_length_delimited(&mut buffer) else {
+            tracing::error!(target: "spool", "{}: fin du segment tronquée", path.display());
+            break;
+        };
+        match Record::try_from(record) {
+            Ok(record) => records.push(record),
+            Err(e) => tracing::warn!(target: "spool", "{}: échantillon ignoré ({})", path.display(), e),
+        }
+    }
+
+    Ok(records)
+}
@@ -0,0 +1,9 @@
+pub mod protocol;
+pub mod responder;
+
+// Port série du Raspberry Pi (rppal)
+#[cfg(feature = "real-sensors")]
+mod serial;
+
+#[cfg(feature = "real-sensors")]
+pub use serial::run;
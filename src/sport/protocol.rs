@@ -0,0 +1,139 @@
+/// Début d'une interrogation du récepteur, suivi de l'identifiant physique du capteur
+pub const START: u8 = 0x7E;
+
+/// Octet d'échappement: 0x7E et 0x7D sont transmis en 0x7D, octet ^ 0x20
+const STUFF: u8 = 0x7D;
+
+/// Type de trame: données d'un capteur
+const DATA_FRAME: u8 = 0x10;
+
+/// Nombre d'identifiants de capteurs sur le bus
+pub const SENSORS: u8 = 28;
+
+/// Identifiants des valeurs (data ID)
+const CURRENT: u16 = 0x0200;
+const VFAS: u16 = 0x0210;
+const GPS_LONG_LATI: u16 = 0x0800;
+const GPS_SPEED: u16 = 0x0830;
+const GPS_COURS: u16 = 0x0840;
+
+/// Identifiant physique (octet transmis après 0x7E) d'un capteur 0-27: 5 bits d'identifiant
+/// et 3 bits de parité
+pub fn physical_id(id: u8) -> u8 {
+    let bit = |n: u8| (id >> n) & 1;
+    let parity5 = bit(0) ^ bit(1) ^ bit(2);
+    let parity6 = bit(2) ^ bit(3) ^ bit(4);
+    let parity7 = bit(0) ^ bit(2) ^ bit(4);
+    (id & 0x1F) | (parity5 << 5) | (parity6 << 6) | (parity7 << 7)
+}
+
+/// Capteur interrogé, None si l'octet n'est pas un identifiant physique valide
+pub fn sensor_id(physical: u8) -> Option<u8> {
+    let id = physical & 0x1F;
+    (id < SENSORS && physical_id(id) == physical).then_some(id)
+}
+
+/// Somme de contrôle d'une trame (sans l'octet de début ni l'échappement)
+pub fn crc(bytes: &[u8]) -> u8 {
+    let mut crc: u16 = 0;
+    for byte in bytes {
+        crc += *byte as u16;
+        crc += crc >> 8;
+        crc &= 0xFF;
+    }
+    0xFF - crc as u8
+}
+
+/// Trame de données prête à être transmise: type, identifiant, valeur et somme de contrôle,
+/// avec échappement
+pub fn frame(data_id: u16, value: u32) -> Vec<u8> {
+    let mut raw = vec![DATA_FRAME];
+    raw.extend_from_slice(&data_id.to_le_bytes());
+    raw.extend_from_slice(&value.to_le_bytes());
+    raw.push(crc(&raw));
+
+    let mut frame = Vec::with_capacity(raw.len() + 2);
+    for byte in raw {
+        if byte == START || byte == STUFF {
+            frame.push(STUFF);
+            frame.push(byte ^ 0x20);
+        } else {
+            frame.push(byte);
+        }
+    }
+    frame
+}
+
+/// Valeur transmise au récepteur
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Value {
+    /// Tension de la batterie (V)
+    Vfas(f32),
+    /// Courant (A)
+    Current(f64),
+    /// Degrés, négatif au sud
+    Latitude(f64),
+    /// Degrés, négatif à l'ouest
+    Longitude(f64),
+    /// Vitesse GPS (km/h)
+    Speed(f64),
+    /// Cap (degrés)
+    Heading(f64),
+}
+
+impl Value {
+    /// Identifiant et valeur encodée selon les unités S.Port
+    pub fn encode(&self) -> (u16, u32) {
+        match *self {
+            Value::Vfas(volts) => (VFAS, (volts.max(0.0) * 100.0).round() as u32),
+            Value::Current(amps) => (CURRENT, (amps.max(0.0) * 10.0).round() as u32),
+            Value::Latitude(degrees) => (GPS_LONG_LATI, coordinate(degrees, false)),
+            Value::Longitude(degrees) => (GPS_LONG_LATI, coordinate(degrees, true)),
+            // Noeuds / 1000
+            Value::Speed(kmh) => (GPS_SPEED, (kmh.max(0.0) / 1.852 * 1000.0).round() as u32),
+            Value::Heading(degrees) => (GPS_COURS, (degrees.rem_euclid(360.0) * 100.0).round() as u32),
+        }
+    }
+
+    pub fn frame(&self) -> Vec<u8> {
+        let (data_id, value) = self.encode();
+        frame(data_id, value)
+    }
+}
+
+/// Coordonnée GPS: minutes / 10000 sur 30 bits, bit 30 pour le sud ou l'ouest, bit 31 pour
+/// la longitude
+fn coordinate(degrees: f64, longitude: bool) -> u32 {
+    let mut value = ((degrees.abs() * 600_000.0).round() as u32) & 0x3FFF_FFFF;
+    if degrees < 0.0 {
+        value |= 0x4000_0000;
+    }
+    if longitude {
+        value |= 0x8000_0000;
+    }
+    value
+}
+
+/// Détection des interrogations dans les octets reçus (y compris l'écho de nos propres
+/// trames sur le fil unique: elles ne contiennent jamais 0x7E)
+#[derive(Default)]
+pub struct Polls {
+    start: bool,
+}
+
+impl Polls {
+    /// Capteur interrogé par cet octet, le cas échéant
+    pub fn push(&mut self, byte: u8) -> Option<u8> {
+        if byte == START {
+            self.start = true;
+            return None;
+        }
+
+        let start = std::mem::take(&mut self.start);
+        if start {
+            sensor_id(byte)
+        } else {
+            None
+        }
+    }
+}
@@ -0,0 +1,38 @@
+use std::collections::BTreeMap;
+
+use crate::config::{SportSensor, SportValue};
+
+use super::protocol::Value;
+
+/// Réponses des capteurs simulés: chaque interrogation d'un capteur transmet la valeur
+/// suivante de sa liste, les valeurs inconnues (ex: pas de fix GPS) sont passées
+pub struct Responder {
+    sensors: BTreeMap<u8, (Vec<SportValue>, usize)>,
+}
+
+impl Responder {
+    pub fn new(sensors: &[SportSensor]) -> Self {
+        Self {
+            sensors: sensors
+                .iter()
+                .map(|sensor| (sensor.id, (sensor.values.clone(), 0)))
+                .collect(),
+        }
+    }
+
+    /// Valeur à transmettre pour une interrogation du capteur `id`, None si ce capteur n'est pas
+    /// simulé ou n'a aucune valeur connue (pas de réponse)
+    pub fn next(&mut self, id: u8, lookup: impl Fn(SportValue) -> Option<Value>) -> Option<Value> {
+        let (values, position) = self.sensors.get_mut(&id)?;
+
+        for _ in 0..values.len() {
+            let kind = values[*position];
+            *position = (*position + 1) % values.len();
+            if let Some(value) = lookup(kind) {
+                return Some(value);
+            }
+        }
+
+        None
+    }
+}
@@ -0,0 +1,87 @@
+use std::collections::BTreeMap;
+use std::time::Duration;
+
+use rppal::uart::{Parity, Uart};
+use tokio_util::sync::CancellationToken;
+
+use crate::clock::Stamp;
+use crate::config::{SportConfig, SportValue};
+use crate::sensors::can::CanData;
+use crate::writer::{Latest, Writer};
+
+use super::protocol::{Polls, Value};
+use super::responder::Responder;
+
+/// Valeur d'un capteur tirée des derniers échantillons, None si elle est inconnue
+fn value(kind: SportValue, latest: &Latest, can: impl FnOnce() -> BTreeMap<String, CanData>, signal: &str) -> Option<Value> {
+    let data = &latest.data;
+    let gps = data.gps.fix.then_some(&data.gps);
+
+    match kind {
+        SportValue::Vfas => (data.analog.stamp != Stamp::default()).then_some(Value::Vfas(data.analog.battery)),
+        SportValue::Current => {
+            let (message, signal) = signal.split_once('.')?;
+            can().get(message)?.signals.get(signal).map(|amps| Value::Current(*amps))
+        }
+        SportValue::Latitude => gps.map(|gps| Value::Latitude(gps.latitude)),
+        SportValue::Longitude => gps.map(|gps| Value::Longitude(gps.longitude)),
+        SportValue::Speed => gps.map(|gps| Value::Speed(gps.speed_kmh)),
+        SportValue::Heading => match gps {
+            Some(gps) => Some(Value::Heading(gps.heading)),
+            None => (data.mag.stamp != Stamp::default()).then_some(Value::Heading(data.mag.true_heading as f64)),
+        },
+    }
+}
+
+/// Télémétrie S.Port vers l'émetteur, à partir des dernières valeurs de l'écrivain.
+/// Lecture et écriture bloquantes sur le port série, dans un thread dédié.
+pub async fn run(config: SportConfig, writer: Writer, token: CancellationToken) {
+    if !config.external_inverter {
+        tracing::info!(
+            target: "sport",
+            "Pas d'inverseur externe: {} doit inverser lui-même le signal.",
+            config.device.display()
+        );
+    }
+
+    let result = tokio::task::spawn_blocking(move || serve(&config, &writer, &token)).await;
+    match result {
+        Ok(Ok(())) => tracing::info!(target: "sport", "Arrêt."),
+        Ok(Err(e)) => tracing::error!(target: "sport", "Arrêt de la télémétrie: {}", e),
+        Err(e) => tracing::error!(target: "sport", "Arrêt de la télémétrie: {}", e),
+    }
+}
+
+fn serve(config: &SportConfig, writer: &Writer, token: &CancellationToken) -> anyhow::Result<()> {
+    let mut uart = Uart::with_path(&config.device, 57_600, Parity::None, 8, 1)?;
+    // Au moins un octet, au plus 100 ms d'attente: pas de boucle active sans récepteur
+    uart.set_read_mode(1, Duration::from_millis(100))?;
+    uart.set_write_mode(true)?;
+
+    tracing::info!(target: "sport", "Télémétrie sur {}", config.device.display());
+
+    let mut polls = Polls::default();
+    let mut responder = Responder::new(&config.sensors);
+    let mut buffer = [0u8; 64];
+
+    while !token.is_cancelled() {
+        let count = uart.read(&mut buffer)?;
+
+        // Seule une interrogation en fin de lecture attend encore sa réponse
+        let mut polled = None;
+        for byte in &buffer[..count] {
+            polled = polls.push(*byte);
+        }
+        let Some(id) = polled else {
+            continue;
+        };
+
+        let latest = writer.latest();
+        let reply = responder.next(id, |kind| value(kind, &latest, || writer.latest_can(), &config.current_signal));
+        if let Some(reply) = reply {
+            uart.write(&reply.frame())?;
+        }
+    }
+
+    Ok(())
+}
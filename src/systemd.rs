@@ -0,0 +1,109 @@
+use std::ffi::OsStr;
+use std::os::linux::net::SocketAddrExt;
+use std::os::unix::ffi::OsStrExt;
+use std::os::unix::net::{SocketAddr, UnixDatagram};
+use std::time::Duration;
+
+use anyhow::bail;
+use tokio_util::sync::CancellationToken;
+
+use crate::logs::Repeated;
+
+/// Notifications au gestionnaire de service systemd (sd_notify), pour un service `Type=notify`
+/// avec `WatchdogSec=`. Sans NOTIFY_SOCKET (hors systemd), aucune notification.
+pub struct Notifier {
+    socket: UnixDatagram,
+    address: SocketAddr,
+}
+
+impl Notifier {
+    /// Socket de NOTIFY_SOCKET, None: variable absente
+    pub fn from_env() -> anyhow::Result<Option<Self>> {
+        match std::env::var_os("NOTIFY_SOCKET") {
+            Some(path) => Self::new(&path).map(Some),
+            None => Ok(None),
+        }
+    }
+
+    /// `path`: chemin du socket, ou nom abstrait préfixé par '@'
+    pub fn new(path: &OsStr) -> anyhow::Result<Self> {
+        let bytes = path.as_bytes();
+        let address = match bytes.strip_prefix(b"@") {
+            Some(name) => SocketAddr::from_abstract_name(name)?,
+            None if bytes.starts_with(b"/") => SocketAddr::from_pathname(path)?,
+            None => bail!("NOTIFY_SOCKET invalide: {}", path.to_string_lossy()),
+        };
+        Ok(Self {
+            socket: UnixDatagram::unbound()?,
+            address,
+        })
+    }
+
+    /// Envoie un état (ex: "READY=1")
+    pub fn notify(&self, state: &str) -> anyhow::Result<()> {
+        self.socket.send_to_addr(state.as_bytes(), &self.address)?;
+        Ok(())
+    }
+}
+
+/// Intervalle des pings du watchdog: moitié de `usec` (WATCHDOG_USEC). None: watchdog
+/// désactivé, ou destiné à un autre processus (`pid`: WATCHDOG_PID).
+pub fn watchdog_interval(usec: Option<&str>, pid: Option<&str>, own: u32) -> Option<Duration> {
+    if pid.is_some_and(|pid| pid.parse() != Ok(own)) {
+        return None;
+    }
+    let usec: u64 = usec?.parse().ok().filter(|usec| *usec > 0)?;
+    Some(Duration::from_micros(usec / 2))
+}
+
+/// Intervalle des pings du watchdog selon l'environnement du service
+pub fn watchdog_interval_from_env() -> Option<Duration> {
+    let usec = std::env::var("WATCHDOG_USEC").ok();
+    let pid = std::env::var("WATCHDOG_PID").ok();
+    watchdog_interval(usec.as_deref(), pid.as_deref(), std::process::id())
+}
+
+/// Pings du watchdog systemd à chaque intervalle, suspendus tant que `blocked` signale un
+/// blocage: systemd redémarre alors le service après WatchdogSec.
+pub async fn watchdog(
+    notifier: std::sync::Arc<Notifier>,
+    interval: Duration,
+    mut blocked: impl FnMut() -> Option<String>,
+    token: CancellationToken,
+) {
+    let mut tick = tokio::time::interval(interval);
+    tick.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+    let mut blocks = Repeated::default();
+    let mut errors = Repeated::default();
+
+    loop {
+        tokio::select! {
+            _ = token.cancelled() => break,
+            _ = tick.tick() => {}
+        }
+
+        if let Some(reason) = blocked() {
+            if blocks.first() {
+                tracing::error!(target: "systemd", "Tâches bloquées ({}), pings du watchdog suspendus", reason);
+            }
+            continue;
+        }
+        let count = blocks.clear();
+        if count > 0 {
+            tracing::info!(target: "systemd", "Tâches débloquées, pings du watchdog repris ({} suspendu(s))", count);
+        }
+
+        match notifier.notify("WATCHDOG=1") {
+            Ok(()) => {
+                errors.clear();
+            }
+            Err(e) => {
+                if errors.first() {
+                    tracing::error!(target: "systemd", "Ping du watchdog impossible: {}", e);
+                } else {
+                    tracing::debug!(target: "systemd", "Ping du watchdog impossible: {}", e);
+                }
+            }
+        }
+    }
+}
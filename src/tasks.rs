@@ -0,0 +1,70 @@
+use std::collections::HashMap;
+use std::future::Future;
+use std::time::Duration;
+
+use tokio::task::{Id, JoinSet};
+use tracing::Instrument;
+
+/// Tâches de l'exécution, attendues lors de l'arrêt
+#[derive(Default)]
+pub struct Tasks {
+    set: JoinSet<()>,
+    names: HashMap<Id, &'static str>,
+}
+
+impl Tasks {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Lance une tâche, son résultat est ignoré. Les lignes du journal de la tâche portent son nom.
+    pub fn spawn<F>(&mut self, name: &'static str, task: F)
+    where
+        F: Future + Send + 'static,
+    {
+        let span = tracing::info_span!("task", name);
+        let handle = self.set.spawn(
+            async move {
+                task.await;
+            }
+            .instrument(span),
+        );
+        self.names.insert(handle.id(), name);
+    }
+
+    /// Attend la fin des tâches (déjà annulées) pendant au plus `grace`, puis interrompt les
+    /// tâches restantes. Renvoie les noms des tâches interrompues.
+    pub async fn shutdown(mut self, grace: Duration) -> Vec<&'static str> {
+        let names = &mut self.names;
+        let set = &mut self.set;
+        let joined = tokio::time::timeout(grace, async {
+            while let Some(result) = set.join_next_with_id().await {
+                let (id, result) = match result {
+                    Ok((id, _)) => (id, Ok(())),
+                    Err(e) => (e.id(), Err(e)),
+                };
+                let name = names.remove(&id).unwrap_or("?");
+                match result {
+                    Ok(_) => tracing::info!(target: "main", "Tâche {} arrêtée", name),
+                    Err(e) => tracing::error!(target: "main", "Tâche {} arrêtée en erreur: {}", name, e),
+                }
+            }
+        })
+        .await;
+
+        if joined.is_ok() {
+            return Vec::new();
+        }
+
+        let mut remaining: Vec<&'static str> = self.names.values().copied().collect();
+        remaining.sort_unstable();
+        tracing::error!(
+            target: "main",
+            "Tâche(s) non terminée(s) après {:.1} s, interrompue(s): {}",
+            grace.as_secs_f64(),
+            remaining.join(", ")
+        );
+        self.set.shutdown().await;
+        remaining
+    }
+}
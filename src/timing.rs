@@ -0,0 +1,185 @@
+use std::collections::VecDeque;
+use std::time::{Duration, Instant};
+
+use serde::{Deserialize, Serialize};
+
+use crate::config::TimingConfig;
+
+/// Percentiles d'une fenêtre de mesures (µs)
+#[derive(Clone, Copy, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct Percentiles {
+    pub p50_us: u64,
+    pub p95_us: u64,
+    pub p99_us: u64,
+    pub max_us: u64,
+}
+
+/// Fenêtre glissante des dernières mesures
+struct Window {
+    samples: VecDeque<u64>,
+    size: usize,
+}
+
+impl Window {
+    fn new(size: usize) -> Self {
+        let size = size.max(1);
+        Self {
+            samples: VecDeque::with_capacity(size),
+            size,
+        }
+    }
+
+    fn resize(&mut self, size: usize) {
+        self.size = size.max(1);
+        while self.samples.len() > self.size {
+            self.samples.pop_front();
+        }
+    }
+
+    fn push(&mut self, duration: Duration) {
+        if self.samples.len() >= self.size {
+            self.samples.pop_front();
+        }
+        self.samples.push_back(duration.as_micros() as u64);
+    }
+
+    fn percentiles(&self) -> Percentiles {
+        if self.samples.is_empty() {
+            return Percentiles::default();
+        }
+
+        let mut sorted: Vec<u64> = self.samples.iter().copied().collect();
+        sorted.sort_unstable();
+        let at = |p: usize| sorted[(sorted.len() - 1) * p / 100];
+
+        Percentiles {
+            p50_us: at(50),
+            p95_us: at(95),
+            p99_us: at(99),
+            max_us: *sorted.last().unwrap(),
+        }
+    }
+}
+
+/// Résumé des durées d'une boucle: intervalle entre itérations, lecture et envoi
+#[derive(Clone, Copy, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct LoopReport {
+    pub interval: Percentiles,
+    pub read: Percentiles,
+    pub submit: Percentiles,
+}
+
+/// Durées des boucles du programme, publiées périodiquement
+#[derive(Clone, Copy, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct TimingReport {
+    /// Thread de lecture des capteurs
+    pub reader: LoopReport,
+    /// Tâche d'envoi des données vers l'écrivain
+    pub pipeline: LoopReport,
+}
+
+/// Mesure des durées d'une boucle de capteurs.
+/// Une itération se découpe en deux phases: lecture (capteurs) puis envoi (données vers l'écrivain).
+pub struct LoopTimer {
+    name: &'static str,
+    target: Duration,
+    jitter: Duration,
+    sustain: Duration,
+    interval: Window,
+    read: Window,
+    submit: Window,
+    last_start: Option<Instant>,
+    over_since: Option<Instant>,
+    warned: bool,
+}
+
+impl LoopTimer {
+    /// Constructeur, `target` est l'intervalle attendu entre deux itérations
+    pub fn new(name: &'static str, target: Duration, config: &TimingConfig) -> Self {
+        Self {
+            name,
+            target,
+            jitter: Duration::from_millis(config.jitter_ms),
+            sustain: Duration::from_secs(config.sustain_s),
+            interval: Window::new(config.window),
+            read: Window::new(config.window),
+            submit: Window::new(config.window),
+            last_start: None,
+            over_since: None,
+            warned: false,
+        }
+    }
+
+    /// Applique une nouvelle configuration (rechargement), les mesures sont conservées
+    pub fn configure(&mut self, target: Duration, config: &TimingConfig) {
+        self.target = target;
+        self.jitter = Duration::from_millis(config.jitter_ms);
+        self.sustain = Duration::from_secs(config.sustain_s);
+        self.interval.resize(config.window);
+        self.read.resize(config.window);
+        self.submit.resize(config.window);
+    }
+
+    /// Début d'une itération, retourne l'instant de début
+    pub fn start(&mut self) -> Instant {
+        let now = Instant::now();
+        if let Some(last) = self.last_start.replace(now) {
+            self.interval.push(now - last);
+        }
+        now
+    }
+
+    /// Fin de la phase de lecture
+    pub fn read_done(&mut self, duration: Duration) {
+        self.read.push(duration);
+    }
+
+    /// Fin de la phase d'envoi
+    pub fn submit_done(&mut self, duration: Duration) {
+        self.submit.push(duration);
+    }
+
+    /// Vérifie le dernier intervalle. Retourne un avertissement (une seule fois) si l'intervalle
+    /// dépasse la cible de plus du seuil de gigue pendant toute la durée configurée.
+    pub fn check(&mut self) -> Option<String> {
+        let last = *self.interval.samples.back()?;
+        if Duration::from_micros(last) <= self.target + self.jitter {
+            self.over_since = None;
+            self.warned = false;
+            return None;
+        }
+
+        let since = *self.over_since.get_or_insert_with(Instant::now);
+        if self.warned || since.elapsed() < self.sustain {
+            return None;
+        }
+        self.warned = true;
+
+        // Phase responsable: celle dont le p95 est le plus élevé
+        let report = self.report();
+        let phase = if report.read.p95_us >= report.submit.p95_us {
+            "lecture des capteurs"
+        } else {
+            "envoi des données"
+        };
+
+        Some(format!(
+            "{}: intervalle de {}ms (p95) au lieu de {}ms depuis {}s, phase la plus lente: {} (lecture p95 {}ms, envoi p95 {}ms)",
+            self.name,
+            report.interval.p95_us / 1000,
+            self.target.as_millis(),
+            since.elapsed().as_secs(),
+            phase,
+            report.read.p95_us / 1000,
+            report.submit.p95_us / 1000
+        ))
+    }
+
+    pub fn report(&self) -> LoopReport {
+        LoopReport {
+            interval: self.interval.percentiles(),
+            read: self.read.percentiles(),
+            submit: self.submit.percentiles(),
+        }
+    }
+}
@@ -0,0 +1,110 @@
+use crate::record::ModemData;
+use crate::sensors::reader::Data;
+
+/// Début de chaque trame
+const MAGIC: [u8; 2] = *b"RC";
+
+/// Version du format, incrémentée à chaque modification de la disposition
+pub const VERSION: u8 = 1;
+
+/// Trame binaire compacte des dernières valeurs, de taille fixe, entiers et flottants en
+/// petit-boutiste:
+///
+/// | octets | champ       | type | unité                              |
+/// |--------|-------------|------|------------------------------------|
+/// | 0-1    | "RC"        | u8×2 |                                    |
+/// | 2      | version     | u8   | 1                                  |
+/// | 3      | drapeaux    | u8   | bit 0: fix GPS                     |
+/// | 4-11   | horodatage  | i64  | µs depuis l'époque Unix (UTC)      |
+/// | 12-15  | seq         | u32  | +1 à chaque trame (pertes)         |
+/// | 16-23  | latitude    | f64  | °                                  |
+/// | 24-31  | longitude   | f64  | °                                  |
+/// | 32-35  | vitesse     | f32  | km/h (GPS)                         |
+/// | 36-39  | cap         | f32  | ° vrai (magnétomètre)              |
+/// | 40-43  | roulis      | f32  | °                                  |
+/// | 44-47  | tangage     | f32  | °                                  |
+/// | 48-51  | batterie    | f32  | V                                  |
+/// | 52     | signal      | u8   | qualité du modem, %                |
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Frame {
+    pub timestamp_us: i64,
+    pub seq: u32,
+    pub fix: bool,
+    pub latitude: f64,
+    pub longitude: f64,
+    pub speed_kmh: f32,
+    pub heading: f32,
+    pub roll: f32,
+    pub pitch: f32,
+    pub battery: f32,
+    pub signal: u8,
+}
+
+impl Frame {
+    /// Taille d'une trame (octets), bien en dessous d'un MTU
+    pub const SIZE: usize = 53;
+
+    pub fn new(data: &Data, modem: &ModemData, timestamp_us: i64, seq: u32) -> Self {
+        let (pitch, roll, _) = data.imu.angles;
+        Self {
+            timestamp_us,
+            seq,
+            fix: data.gps.fix,
+            latitude: data.gps.latitude,
+            longitude: data.gps.longitude,
+            speed_kmh: data.gps.speed_kmh as f32,
+            heading: data.mag.true_heading,
+            roll,
+            pitch,
+            battery: data.analog.battery,
+            signal: modem.status.quality.min(100) as u8,
+        }
+    }
+
+    pub fn encode(&self) -> [u8; Self::SIZE] {
+        let mut bytes = [0; Self::SIZE];
+        bytes[0..2].copy_from_slice(&MAGIC);
+        bytes[2] = VERSION;
+        bytes[3] = self.fix as u8;
+        bytes[4..12].copy_from_slice(&self.timestamp_us.to_le_bytes());
+        bytes[12..16].copy_from_slice(&self.seq.to_le_bytes());
+        bytes[16..24].copy_from_slice(&self.latitude.to_le_bytes());
+        bytes[24..32].copy_from_slice(&self.longitude.to_le_bytes());
+        let floats = [(32, self.speed_kmh), (36, self.heading), (40, self.roll), (44, self.pitch), (48, self.battery)];
+        for (offset, value) in floats {
+            bytes[offset..offset + 4].copy_from_slice(&value.to_le_bytes());
+        }
+        bytes[52] = self.signal;
+        bytes
+    }
+
+    /// Lit une trame reçue, refusée si sa taille, son en-tête ou sa version ne correspondent pas.
+    /// Référence pour les récepteurs, le véhicule ne fait qu'envoyer.
+    #[allow(dead_code)]
+    pub fn decode(bytes: &[u8]) -> anyhow::Result<Self> {
+        let bytes: &[u8; Self::SIZE] = bytes
+            .try_into()
+            .map_err(|_| anyhow::anyhow!("taille {} au lieu de {} octets", bytes.len(), Self::SIZE))?;
+        if bytes[0..2] != MAGIC {
+            return Err(anyhow::anyhow!("en-tête invalide"));
+        }
+        if bytes[2] != VERSION {
+            return Err(anyhow::anyhow!("version {} non supportée", bytes[2]));
+        }
+
+        let f32_at = |offset: usize| f32::from_le_bytes(bytes[offset..offset + 4].try_into().unwrap());
+        Ok(Self {
+            fix: bytes[3] & 1 != 0,
+            timestamp_us: i64::from_le_bytes(bytes[4..12].try_into().unwrap()),
+            seq: u32::from_le_bytes(bytes[12..16].try_into().unwrap()),
+            latitude: f64::from_le_bytes(bytes[16..24].try_into().unwrap()),
+            longitude: f64::from_le_bytes(bytes[24..32].try_into().unwrap()),
+            speed_kmh: f32_at(32),
+            heading: f32_at(36),
+            roll: f32_at(40),
+            pitch: f32_at(44),
+            battery: f32_at(48),
+            signal: bytes[52],
+        })
+    }
+}
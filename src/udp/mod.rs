@@ -0,0 +1,252 @@
+use std::collections::BTreeMap;
+use std::net::{IpAddr, SocketAddr};
+use std::time::{Duration, Instant};
+
+use prost::Message;
+use serde::Serialize;
+use tokio::net::UdpSocket;
+use tokio::sync::broadcast::error::RecvError;
+use tokio::time::MissedTickBehavior;
+use tokio_util::sync::CancellationToken;
+
+use crate::clock::{Clock, Stamp};
+use crate::config::{Encoding, UdpConfig, UdpFrameConfig};
+use crate::proto;
+use crate::writer::{Record, Writer};
+
+pub mod frame;
+
+use frame::Frame;
+
+/// Intervalle du résumé des envois en erreur
+const ERROR_SUMMARY: Duration = Duration::from_secs(30);
+
+/// Datagramme: un échantillon, numéroté pour détecter les pertes
+#[derive(Serialize)]
+struct Datagram<'a> {
+    /// Numéro de séquence, commun à tous les types
+    seq: u64,
+    #[serde(rename = "type")]
+    kind: &'static str,
+    stamp: Stamp,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    data: Option<&'a Record>,
+    /// Contenu retiré, le datagramme dépassait la taille maximale
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    truncated: bool,
+}
+
+/// Limite la fréquence d'envoi de chaque type d'échantillon
+struct RateLimiter {
+    intervals: BTreeMap<&'static str, Duration>,
+    last: BTreeMap<&'static str, Instant>,
+}
+
+impl RateLimiter {
+    fn new(config: &UdpConfig) -> Self {
+        let intervals = Record::KINDS
+            .iter()
+            .filter_map(|kind| {
+                let rate = config.rates.get(*kind).copied().unwrap_or(0.0);
+                (rate > 0.0).then(|| (*kind, Duration::from_secs_f64(1.0 / rate)))
+            })
+            .collect();
+
+        Self {
+            intervals,
+            last: BTreeMap::new(),
+        }
+    }
+
+    /// Vrai si l'échantillon peut être envoyé maintenant
+    fn allow(&mut self, kind: &'static str, now: Instant) -> bool {
+        let Some(interval) = self.intervals.get(kind) else {
+            return true;
+        };
+
+        match self.last.get(kind) {
+            Some(last) if now.duration_since(*last) < *interval => false,
+            _ => {
+                self.last.insert(kind, now);
+                true
+            }
+        }
+    }
+}
+
+/// Encode un échantillon, sans son contenu s'il dépasse `mtu` octets
+fn encode(seq: u64, record: &Record, mtu: usize, encoding: Encoding) -> anyhow::Result<(Vec<u8>, bool)> {
+    match encoding {
+        Encoding::Json => encode_json(seq, record, mtu),
+        Encoding::Protobuf => Ok(encode_protobuf(seq, record, mtu)),
+    }
+}
+
+fn encode_json(seq: u64, record: &Record, mtu: usize) -> anyhow::Result<(Vec<u8>, bool)> {
+    let mut datagram = Datagram {
+        seq,
+        kind: record.kind(),
+        stamp: record.stamp(),
+        data: Some(record),
+        truncated: false,
+    };
+
+    let bytes = serde_json::to_vec(&datagram)?;
+    if bytes.len() <= mtu {
+        return Ok((bytes, false));
+    }
+
+    datagram.data = None;
+    datagram.truncated = true;
+    Ok((serde_json::to_vec(&datagram)?, true))
+}
+
+fn encode_protobuf(seq: u64, record: &Record, mtu: usize) -> (Vec<u8>, bool) {
+    let mut datagram = proto::Datagram {
+        seq,
+        r#type: record.kind().to_string(),
+        stamp: Some(record.stamp().into()),
+        record: Some((*record).into()),
+        truncated: false,
+    };
+
+    let bytes = datagram.encode_to_vec();
+    if bytes.len() <= mtu {
+        return (bytes, false);
+    }
+
+    datagram.record = None;
+    datagram.truncated = true;
+    (datagram.encode_to_vec(), true)
+}
+
+/// Envoi UDP des échantillons diffusés par l'écrivain. Les envois ne sont jamais attendus:
+/// un datagramme qui ne peut pas partir immédiatement est perdu.
+pub async fn run(config: UdpConfig, writer: Writer, token: CancellationToken) {
+    if let Err(e) = send_loop(&config, &writer, &token).await {
+        tracing::error!(target: "udp", "Arrêt de l'envoi: {}", e);
+    }
+}
+
+/// Socket d'envoi vers une adresse unicast, de diffusion ou multicast
+async fn socket(target: SocketAddr, multicast_ttl: u32) -> anyhow::Result<UdpSocket> {
+    let bind: SocketAddr = match target.ip() {
+        IpAddr::V4(_) => "0.0.0.0:0".parse()?,
+        IpAddr::V6(_) => "[::]:0".parse()?,
+    };
+
+    let socket = UdpSocket::bind(bind).await?;
+    match target.ip() {
+        IpAddr::V4(ip) if ip.is_multicast() => socket.set_multicast_ttl_v4(multicast_ttl)?,
+        IpAddr::V4(_) => socket.set_broadcast(true)?,
+        IpAddr::V6(_) => {}
+    }
+    Ok(socket)
+}
+
+async fn send_loop(config: &UdpConfig, writer: &Writer, token: &CancellationToken) -> anyhow::Result<()> {
+    let target: SocketAddr = config.target.parse()?;
+    let socket = socket(target, config.multicast_ttl).await?;
+
+    for kind in config.records.iter() {
+        if !Record::KINDS.contains(&kind.as_str()) {
+            tracing::warn!(target: "udp", "Type d'échantillon inconnu ignoré: {}", kind);
+        }
+    }
+
+    tracing::info!(target: "udp", "Envoi vers {} ({})", target, config.records.join(", "));
+
+    let mut records = writer.subscribe();
+    let mut limiter = RateLimiter::new(config);
+    let mut seq = 0u64;
+    let mut errors = 0u64;
+    let mut last_summary = Instant::now();
+
+    loop {
+        let record = tokio::select! {
+            _ = token.cancelled() => return Ok(()),
+            record = records.recv() => match record {
+                Ok(record) => record,
+                Err(RecvError::Lagged(_)) => continue,
+                Err(RecvError::Closed) => return Ok(()),
+            },
+        };
+
+        let kind = record.kind();
+        if !config.records.iter().any(|k| k == kind) || !limiter.allow(kind, Instant::now()) {
+            continue;
+        }
+
+        let Ok((bytes, truncated)) = encode(seq, &record, config.mtu, config.encoding) else {
+            continue;
+        };
+        if truncated {
+            tracing::error!(target: "udp", "Echantillon {} trop grand ({} octets max), contenu retiré.", kind, config.mtu);
+        }
+        seq += 1;
+
+        // Non bloquant: en cas d'erreur ou de file pleine, le datagramme est perdu
+        if let Err(e) = socket.try_send_to(&bytes, target) {
+            if errors == 0 {
+                tracing::error!(target: "udp", "Erreur d'envoi vers {}: {}", target, e);
+            }
+            errors += 1;
+        }
+
+        if errors > 0 && last_summary.elapsed() >= ERROR_SUMMARY {
+            tracing::error!(target: "udp", "{} datagramme(s) perdu(s) depuis {} s.", errors, ERROR_SUMMARY.as_secs());
+            errors = 0;
+            last_summary = Instant::now();
+        }
+    }
+}
+
+/// Diffusion des trames compactes à fréquence fixe, depuis les dernières valeurs de l'écrivain.
+/// Les envois ne sont jamais attendus: une trame qui ne peut pas partir immédiatement est perdue.
+pub async fn frames(config: UdpFrameConfig, writer: Writer, clock: Clock, token: CancellationToken) {
+    if let Err(e) = frame_loop(&config, &writer, &clock, &token).await {
+        tracing::error!(target: "udp", "Arrêt des trames: {}", e);
+    }
+}
+
+async fn frame_loop(
+    config: &UdpFrameConfig,
+    writer: &Writer,
+    clock: &Clock,
+    token: &CancellationToken,
+) -> anyhow::Result<()> {
+    let target: SocketAddr = config.target.parse()?;
+    let socket = socket(target, config.multicast_ttl).await?;
+
+    tracing::info!(target: "udp", "Trames compactes vers {} ({} Hz)", target, config.rate_hz);
+
+    let mut tick = tokio::time::interval(Duration::from_secs_f64(1.0 / config.rate_hz));
+    tick.set_missed_tick_behavior(MissedTickBehavior::Skip);
+    let mut seq = 0u32;
+    let mut errors = 0u64;
+    let mut last_summary = Instant::now();
+
+    loop {
+        tokio::select! {
+            _ = token.cancelled() => return Ok(()),
+            _ = tick.tick() => {}
+        }
+
+        let latest = writer.latest();
+        let frame = Frame::new(&latest.data, &latest.modem, clock.stamp().utc.timestamp_micros(), seq);
+        seq = seq.wrapping_add(1);
+
+        if let Err(e) = socket.try_send_to(&frame.encode(), target) {
+            if errors == 0 {
+                tracing::error!(target: "udp", "Erreur d'envoi vers {}: {}", target, e);
+            }
+            errors += 1;
+        }
+
+        if errors > 0 && last_summary.elapsed() >= ERROR_SUMMARY {
+            tracing::error!(target: "udp", "{} trame(s) perdue(s) depuis {} s.", errors, ERROR_SUMMARY.as_secs());
+            errors = 0;
+            last_summary = Instant::now();
+        }
+    }
+}
@@ -0,0 +1,725 @@
+use std::collections::BTreeMap;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use serde::Serialize;
+use tokio::sync::{broadcast, mpsc, watch, Notify};
+use tokio::task::JoinHandle;
+use tokio::time::sleep;
+use tokio_util::sync::CancellationToken;
+
+use crate::actuators::arbiter::SpeedLimit;
+use crate::actuators::auto_disarm::Countdown;
+use crate::breaker::{Breaker, BreakerState, BreakerStats, Transition};
+use crate::capacity::CapacityState;
+use crate::channel::{ChannelStats, Coalesce, DropOldest, Lossless};
+use crate::clock::{Clock, Stamp};
+use crate::config::{Config, Severity, SpoolConfig, WriterConfig};
+use crate::database::{Connection, Database, TelemetryStore};
+use crate::fusion::Attitude;
+use crate::heading_check::HeadingError;
+use crate::logs::{LogUsage, Repeated};
+use crate::metadata::Metadata;
+use crate::run::RunState;
+use crate::selftest::Report;
+use crate::sensors::can::{CanData, CanStats};
+use crate::sensors::mag::calibration::Fit;
+use crate::sensors::reader::{
+    AnalogData, BaroData, Data, EncoderData, GpsData, ImuData, MagData, PowerData, RangeData, SatellitesData, SensorsStatus,
+};
+use crate::sensors::watchdog::{Component, Health};
+use crate::spool::{Spool, SpoolStats};
+use crate::timing::TimingReport;
+
+pub use crate::record::{ModemData, Record};
+
+/// Délai entre deux tentatives d'écriture d'un évènement
+const EVENT_RETRY: Duration = Duration::from_secs(1);
+
+/// Evènements diffusés aux alertes en attente de lecture
+const NOTICES_QUEUE: usize = 64;
+
+/// Echantillons relus du tampon local à chaque passage, entre les évènements
+const REPLAY_BATCH: usize = 200;
+
+/// Evènements: jamais perdus, écrits dans l'ordre d'arrivée
+pub enum Event {
+    Status(SensorsStatus, Stamp),
+    /// Etat de santé des capteurs et capteurs périmés
+    Health(Health, Vec<&'static str>, Stamp),
+    /// Santé de chaque capteur et de la liaison de contrôle
+    Components(Vec<Component>, Stamp),
+    SelfTest(Report),
+    Timing(TimingReport, Stamp),
+    Warning(String, Stamp),
+    /// Changement de configuration (rechargement)
+    Config(String, Stamp),
+    /// Etat de l'exécution et version du programme, au démarrage
+    Run(RunState, Metadata, Stamp),
+    /// L'exécution précédente (identifiant) ne s'est pas arrêtée proprement
+    UncleanShutdown(String, Stamp),
+    /// Bus CAN: ouverture impossible, bus-off, redémarrage
+    Can(String, Stamp),
+    CanStats(CanStats, Stamp),
+    /// Coupure du moteur (voiture retournée ou choc) activée ou levée: type d'évènement
+    Rollover(&'static str, bool, String, Stamp),
+    /// Limite de vitesse la plus basse et sa cause, None: aucune
+    Limit(Option<SpeedLimit>, Stamp),
+    /// Durées restantes avant le désarmement automatique
+    Countdown(Countdown, Stamp),
+    /// Vitesse commandée et vitesse appliquée au moteur (rampe)
+    Speed(f64, f64, Stamp),
+    /// Capacité consommée (mAh) et restante (%) de la batterie
+    Capacity(f64, f64, Stamp),
+    /// Attitude estimée par la fusion de l'IMU et du magnétomètre
+    Attitude(Attitude, Stamp),
+    /// Ecart entre la route GPS et le cap du magnétomètre
+    HeadingError(HeadingError, Stamp),
+    /// Calibration du magnétomètre suspecte (écart persistant avec la route GPS) ou rétablie
+    MagSuspect(bool, Severity, String, Stamp),
+    /// Calibration du magnétomètre ajustée et nombre de mesures utilisées
+    MagCalibration(Fit, usize, Stamp),
+    /// Evènement de surveillance ou de sécurité (base et alertes): type, gravité et détails
+    Alert(&'static str, Severity, String, Stamp),
+    /// Occupation du dossier des journaux
+    Logs(LogUsage, Stamp),
+}
+
+impl Event {
+    /// Type, gravité et détails des évènements transmis aux alertes
+    fn notice(&self) -> Option<Notice> {
+        let (kind, severity, details, stamp) = match self {
+            Event::Warning(message, stamp) => ("warning", Severity::Warning, message.clone(), *stamp),
+            Event::Config(message, stamp) => ("config", Severity::Info, message.clone(), *stamp),
+            Event::UncleanShutdown(previous, stamp) => (
+                "unclean_shutdown",
+                Severity::Warning,
+                format!("Exécution précédente: {}", previous),
+                *stamp,
+            ),
+            Event::Can(message, stamp) => ("can", Severity::Warning, message.clone(), *stamp),
+            Event::Rollover(kind, active, message, stamp) => {
+                (*kind, rollover_severity(*active), message.clone(), *stamp)
+            }
+            Event::MagSuspect(suspect, severity, message, stamp) => {
+                (mag_suspect_kind(*suspect), *severity, message.clone(), *stamp)
+            }
+            Event::Alert(kind, severity, message, stamp) => (*kind, *severity, message.clone(), *stamp),
+            _ => return None,
+        };
+        Some(Notice {
+            kind,
+            severity,
+            details,
+            stamp,
+        })
+    }
+}
+
+/// Gravité de l'évènement de coupure du moteur
+fn rollover_severity(active: bool) -> Severity {
+    if active {
+        Severity::Critical
+    } else {
+        Severity::Info
+    }
+}
+
+/// Type de l'évènement de calibration du magnétomètre suspecte
+fn mag_suspect_kind(suspect: bool) -> &'static str {
+    if suspect {
+        "mag_suspect"
+    } else {
+        "mag_suspect_cleared"
+    }
+}
+
+/// Evènement diffusé aux alertes
+#[derive(Clone, Debug)]
+pub struct Notice {
+    pub kind: &'static str,
+    pub severity: Severity,
+    pub details: String,
+    pub stamp: Stamp,
+}
+
+/// Dernières valeurs reçues par l'écrivain, lues par les sorties locales (sans passer par la base)
+#[derive(Clone, Copy, Default, Serialize)]
+pub struct Latest {
+    #[serde(flatten)]
+    pub data: Data,
+    pub modem: ModemData,
+    /// Capacité de la batterie, None: pas encore publiée
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub capacity: Option<CapacityState>,
+}
+
+/// Etat des files d'écriture
+#[derive(Clone, Copy, Default, PartialEq, Serialize)]
+pub struct WriterStats {
+    pub imu: ChannelStats,
+    pub mag: ChannelStats,
+    pub analog: ChannelStats,
+    pub power: ChannelStats,
+    pub baro: ChannelStats,
+    pub encoder: ChannelStats,
+    pub range: ChannelStats,
+    pub gps: ChannelStats,
+    pub modem: ChannelStats,
+    pub satellites: ChannelStats,
+    pub can: ChannelStats,
+    pub events: ChannelStats,
+    /// Coupe-circuit des écritures d'échantillons
+    pub breaker: BreakerStats,
+    /// Connexion à la base et reconnexions
+    pub connection: Connection,
+    /// Tampon local des échantillons, None: désactivé
+    pub spool: Option<SpoolStats>,
+}
+
+/// Accès aux files de l'écrivain unique de la base de donnée.
+/// IMU, magnétomètre, analogique, puissance, baromètre, encodeur, distance et GPS: le plus ancien est perdu si la file est pleine (file GPS
+/// dimensionnée pour garder chaque position). Modem et satellites: seule la dernière valeur est
+/// conservée.
+/// Evènements: jamais perdus.
+#[derive(Clone)]
+pub struct Writer {
+    imu: DropOldest<ImuData>,
+    mag: DropOldest<MagData>,
+    analog: DropOldest<AnalogData>,
+    power: DropOldest<PowerData>,
+    baro: DropOldest<BaroData>,
+    encoder: DropOldest<EncoderData>,
+    range: DropOldest<RangeData>,
+    /// Positions GPS: jamais perdues, la boucle des capteurs attend qu'une place se libère
+    gps: Lossless<GpsData>,
+    gps_receiver: Arc<Mutex<mpsc::Receiver<GpsData>>>,
+    /// Signal des nouveaux échantillons (files sans perte)
+    notify: Arc<Notify>,
+    modem: Coalesce<ModemData>,
+    satellites: Coalesce<SatellitesData>,
+    can: DropOldest<CanData>,
+    events: Lossless<Event>,
+    latest: Arc<Mutex<Latest>>,
+    /// Dernier message décodé de chaque type sur le bus CAN
+    latest_can: Arc<Mutex<BTreeMap<String, CanData>>>,
+    /// Diffusion des échantillons, un client trop lent perd les plus anciens
+    records: broadcast::Sender<Record>,
+    /// Diffusion des évènements destinés aux alertes
+    notices: broadcast::Sender<Notice>,
+    /// Dernier état des capteurs transmis
+    status: Arc<Mutex<SensorsStatus>>,
+    /// Dernier état de santé transmis et capteurs périmés
+    health: Arc<Mutex<(Health, Vec<&'static str>)>>,
+    /// Dernier état du coupe-circuit des écritures
+    breaker: Arc<Mutex<BreakerStats>>,
+    /// Etat de la connexion à la base
+    connection: watch::Receiver<Connection>,
+    /// Dernier état du tampon local
+    spool: Arc<Mutex<Option<SpoolStats>>>,
+}
+
+impl Writer {
+    /// Files vides, sans tâche d'écriture: file des évènements et signal des nouveaux échantillons
+    /// à transmettre à la tâche qui les vide (`drain`)
+    pub fn new(
+        queues: &WriterConfig,
+        connection: watch::Receiver<Connection>,
+    ) -> (Writer, mpsc::Receiver<Event>, Arc<Notify>) {
+        let notify = Arc::new(Notify::new());
+        let (events, receiver) = Lossless::new(queues.events_queue);
+        let (gps, gps_receiver) = Lossless::new(queues.gps_queue);
+        let writer = Writer {
+            imu: DropOldest::new(queues.imu_queue, notify.clone()),
+            mag: DropOldest::new(queues.mag_queue, notify.clone()),
+            analog: DropOldest::new(queues.analog_queue, notify.clone()),
+            power: DropOldest::new(queues.power_queue, notify.clone()),
+            baro: DropOldest::new(queues.baro_queue, notify.clone()),
+            encoder: DropOldest::new(queues.encoder_queue, notify.clone()),
+            range: DropOldest::new(queues.range_queue, notify.clone()),
+            gps,
+            gps_receiver: Arc::new(Mutex::new(gps_receiver)),
+            notify: notify.clone(),
+            modem: Coalesce::new(notify.clone()),
+            satellites: Coalesce::new(notify.clone()),
+            can: DropOldest::new(queues.can_queue, notify.clone()),
+            events,
+            latest: Arc::new(Mutex::new(Latest::default())),
+            latest_can: Arc::new(Mutex::new(BTreeMap::new())),
+            records: broadcast::channel(queues.records_queue.max(1)).0,
+            notices: broadcast::channel(NOTICES_QUEUE).0,
+            status: Arc::new(Mutex::new(SensorsStatus::default())),
+            health: Arc::new(Mutex::new((Health::Ok, Vec::new()))),
+            breaker: Arc::new(Mutex::new(BreakerStats::default())),
+            connection,
+            spool: Arc::new(Mutex::new(None)),
+        };
+
+        (writer, receiver, notify)
+    }
+
+    pub fn imu(&self, data: ImuData) {
+        self.latest.lock().unwrap().data.imu = data;
+        let _ = self.records.send(Record::Imu(data));
+        self.imu.push(data);
+    }
+
+    pub fn mag(&self, data: MagData) {
+        self.latest.lock().unwrap().data.mag = data;
+        let _ = self.records.send(Record::Mag(data));
+        self.mag.push(data);
+    }
+
+    pub fn analog(&self, data: AnalogData) {
+        self.latest.lock().unwrap().data.analog = data;
+        let _ = self.records.send(Record::Analog(data));
+        self.analog.push(data);
+    }
+
+    pub fn power(&self, data: PowerData) {
+        self.latest.lock().unwrap().data.power = data;
+        let _ = self.records.send(Record::Power(data));
+        self.power.push(data);
+    }
+
+    pub fn baro(&self, data: BaroData) {
+        self.latest.lock().unwrap().data.baro = data;
+        let _ = self.records.send(Record::Baro(data));
+        self.baro.push(data);
+    }
+
+    pub fn encoder(&self, data: EncoderData) {
+        self.latest.lock().unwrap().data.encoder = data;
+        let _ = self.records.send(Record::Encoder(data));
+        self.encoder.push(data);
+    }
+
+    pub fn range(&self, data: RangeData) {
+        self.latest.lock().unwrap().data.range = data;
+        let _ = self.records.send(Record::Range(data));
+        self.range.push(data);
+    }
+
+    /// Ajoute une position, attend si la file est pleine
+    pub async fn gps(&self, data: GpsData) {
+        self.latest.lock().unwrap().data.gps = data;
+        let _ = self.records.send(Record::Gps(data));
+        if self.gps.send(data).await.is_ok() {
+            self.notify.notify_one();
+        }
+    }
+
+    /// Récupère la position la plus ancienne
+    fn pop_gps(&self) -> Option<GpsData> {
+        self.gps_receiver.lock().unwrap().try_recv().ok()
+    }
+
+    pub fn modem(&self, data: ModemData) {
+        self.latest.lock().unwrap().modem = data;
+        let _ = self.records.send(Record::Modem(data));
+        self.modem.push(data);
+    }
+
+    pub fn satellites(&self, data: SatellitesData) {
+        self.latest.lock().unwrap().data.satellites = data;
+        let _ = self.records.send(Record::Satellites(data));
+        self.satellites.push(data);
+    }
+
+    pub fn can(&self, data: CanData) {
+        self.latest_can.lock().unwrap().insert(data.message.clone(), data.clone());
+        self.can.push(data);
+    }
+
+    /// Dernières valeurs de chaque capteur
+    pub fn latest(&self) -> Latest {
+        *self.latest.lock().unwrap()
+    }
+
+    /// Dernier message de chaque type reçu sur le bus CAN
+    pub fn latest_can(&self) -> BTreeMap<String, CanData> {
+        self.latest_can.lock().unwrap().clone()
+    }
+
+    /// Abonnement aux échantillons reçus à partir de maintenant
+    pub fn subscribe(&self) -> broadcast::Receiver<Record> {
+        self.records.subscribe()
+    }
+
+    /// Abonnement aux évènements destinés aux alertes
+    pub fn notices(&self) -> broadcast::Receiver<Notice> {
+        self.notices.subscribe()
+    }
+
+    /// Dernier état des capteurs
+    pub fn status(&self) -> SensorsStatus {
+        self.status.lock().unwrap().clone()
+    }
+
+    /// Dernier état de santé des capteurs et capteurs périmés
+    pub fn health(&self) -> (Health, Vec<&'static str>) {
+        self.health.lock().unwrap().clone()
+    }
+
+    /// Ajoute un évènement, attend si la file est pleine
+    pub async fn event(&self, event: Event) -> anyhow::Result<()> {
+        match &event {
+            Event::Status(status, _) => *self.status.lock().unwrap() = status.clone(),
+            Event::Health(health, stale, _) => *self.health.lock().unwrap() = (*health, stale.clone()),
+            Event::Capacity(consumed_mah, remaining_pct, _) => {
+                self.latest.lock().unwrap().capacity = Some(CapacityState {
+                    consumed_mah: *consumed_mah,
+                    remaining_pct: *remaining_pct,
+                })
+            }
+            _ => {}
+        }
+        if let Some(notice) = event.notice() {
+            let _ = self.notices.send(notice);
+        }
+        self.events.send(event).await
+    }
+
+    pub fn stats(&self) -> WriterStats {
+        WriterStats {
+            imu: self.imu.stats(),
+            mag: self.mag.stats(),
+            analog: self.analog.stats(),
+            power: self.power.stats(),
+            baro: self.baro.stats(),
+            encoder: self.encoder.stats(),
+            range: self.range.stats(),
+            gps: self.gps.stats(),
+            modem: self.modem.stats(),
+            satellites: self.satellites.stats(),
+            can: self.can.stats(),
+            events: self.events.stats(),
+            breaker: *self.breaker.lock().unwrap(),
+            connection: *self.connection.borrow(),
+            spool: *self.spool.lock().unwrap(),
+        }
+    }
+}
+
+/// Démarre l'écrivain, seule tâche à écrire les données dans la base.
+/// La taille des files est fixée au démarrage, l'intervalle des statistiques suit la configuration.
+/// La tâche se termine après l'écriture des files restantes, une fois le jeton annulé.
+pub fn spawn(
+    db: Arc<Database>,
+    config: watch::Receiver<Config>,
+    clock: &Clock,
+    token: CancellationToken,
+) -> (Writer, JoinHandle<()>) {
+    let (writer, receiver, notify) = Writer::new(&config.borrow().writer, db.subscribe());
+    let handle = tokio::spawn(run(db, writer.clone(), receiver, notify, config, clock.clone(), token));
+
+    (writer, handle)
+}
+
+async fn run(
+    db: Arc<Database>,
+    writer: Writer,
+    mut receiver: mpsc::Receiver<Event>,
+    notify: Arc<Notify>,
+    config: watch::Receiver<Config>,
+    clock: Clock,
+    token: CancellationToken,
+) {
+    tracing::info!(target: "writer", "Démarrage ...");
+    let mut last_stats = Instant::now();
+    let mut last_dropped = 0;
+
+    // Latence de la base mesurée en temps réel, même en simulation accélérée
+    let mut breaker = Breaker::new(&config.borrow().writer.breaker, &Clock::start());
+    let mut spool = open_spool(&config.borrow().writer.spool);
+    let mut repeated = Repeated::default();
+
+    loop {
+        let interval = Duration::from_secs(config.borrow().writer.stats_interval_s.max(1));
+
+        tokio::select! {
+            _ = token.cancelled() => break,
+            event = receiver.recv() => match event {
+                Some(event) => write_event(&db, event, &mut repeated, &token).await,
+                None => break,
+            },
+            _ = notify.notified() => {}
+            _ = sleep(interval) => {}
+        }
+
+        // Relecture du tampon local à poursuivre sans attendre
+        if drain(db.as_ref(), &writer, &mut breaker, &mut spool).await {
+            notify.notify_one();
+        }
+        for transition in breaker.transitions() {
+            report(&writer, &clock, transition);
+        }
+        *writer.breaker.lock().unwrap() = breaker.stats();
+        *writer.spool.lock().unwrap() = spool.as_ref().map(Spool::stats);
+
+        // Etat des files, publié périodiquement
+        if last_stats.elapsed() >= interval {
+            last_stats = Instant::now();
+
+            let stats = writer.stats();
+            let dropped = stats.imu.dropped
+                + stats.mag.dropped
+                + stats.analog.dropped
+                + stats.power.dropped
+                + stats.baro.dropped
+                + stats.encoder.dropped
+                + stats.range.dropped;
+            if dropped != last_dropped {
+                tracing::info!(
+                    target: "writer",
+                    "{} échantillon(s) perdu(s) (IMU: {}, MAG: {}, ANALOG: {}, POWER: {}, BARO: {}, ENCODER: {}, RANGE: {})",
+                    dropped - last_dropped,
+                    stats.imu.dropped,
+                    stats.mag.dropped,
+                    stats.analog.dropped,
+                    stats.power.dropped,
+                    stats.baro.dropped,
+                    stats.encoder.dropped,
+                    stats.range.dropped
+                );
+                last_dropped = dropped;
+            }
+
+            let _ = db.send_writer_status(stats).await;
+
+            if let Some(summary) = db.dry_run_summary() {
+                tracing::info!(target: "writer", "{}", summary);
+            }
+        }
+    }
+
+    // Vidage des files à l'arrêt
+    while let Ok(event) = receiver.try_recv() {
+        write_event(&db, event, &mut repeated, &token).await;
+    }
+    drain(db.as_ref(), &writer, &mut breaker, &mut spool).await;
+
+    tracing::info!(target: "writer", "Arrêt.");
+}
+
+/// Tampon local des échantillons, None: désactivé ou indisponible
+fn open_spool(config: &SpoolConfig) -> Option<Spool> {
+    if !config.enabled {
+        return None;
+    }
+
+    match Spool::open(config) {
+        Ok(spool) => {
+            if !spool.is_empty() {
+                tracing::info!(target: "writer", "{} échantillon(s) en attente dans le tampon local", spool.stats().records);
+            }
+            Some(spool)
+        }
+        Err(e) => {
+            tracing::error!(target: "writer", "Tampon local {} indisponible: {}", config.directory.display(), e);
+            None
+        }
+    }
+}
+
+/// Ecrit les échantillons en attente. Ecriture en échec ou coupe-circuit ouvert: ajoutés au tampon
+/// local s'il est activé, sinon retirés des files (déjà transmis aux sorties locales).
+/// Retourne vrai s'il reste des échantillons à relire dans le tampon.
+pub async fn drain<S: TelemetryStore>(db: &S, writer: &Writer, breaker: &mut Breaker, spool: &mut Option<Spool>) -> bool {
+    // Tampon relu avant les nouveaux échantillons, pour conserver l'ordre
+    let replaying = match spool.as_mut() {
+        Some(spool) => replay(db, breaker, spool).await,
+        None => false,
+    };
+
+    while let Some(data) = writer.pop_gps() {
+        store(db, breaker, spool, Record::Gps(data)).await;
+    }
+
+    if let Some(data) = writer.modem.take() {
+        store(db, breaker, spool, Record::Modem(data)).await;
+    }
+
+    if let Some(data) = writer.satellites.take() {
+        store(db, breaker, spool, Record::Satellites(data)).await;
+    }
+
+    while let Some(data) = writer.imu.pop() {
+        store(db, breaker, spool, Record::Imu(data)).await;
+    }
+
+    while let Some(data) = writer.mag.pop() {
+        store(db, breaker, spool, Record::Mag(data)).await;
+    }
+
+    while let Some(data) = writer.analog.pop() {
+        store(db, breaker, spool, Record::Analog(data)).await;
+    }
+
+    while let Some(data) = writer.power.pop() {
+        store(db, breaker, spool, Record::Power(data)).await;
+    }
+
+    while let Some(data) = writer.baro.pop() {
+        store(db, breaker, spool, Record::Baro(data)).await;
+    }
+
+    while let Some(data) = writer.encoder.pop() {
+        store(db, breaker, spool, Record::Encoder(data)).await;
+    }
+
+    while let Some(data) = writer.range.pop() {
+        store(db, breaker, spool, Record::Range(data)).await;
+    }
+
+    // Dernier état de chaque message CAN: pas de relecture
+    while let Some(data) = writer.can.pop() {
+        let _ = breaker.call(db.send_can(data)).await;
+    }
+
+    if let Some(spool) = spool.as_mut() {
+        spool.flush();
+    }
+
+    replaying
+}
+
+/// Ecrit un échantillon, ou l'ajoute au tampon local: relecture en cours (ordre conservé), base
+/// déconnectée, écriture en échec ou détournée par le coupe-circuit
+async fn store<S: TelemetryStore>(db: &S, breaker: &mut Breaker, spool: &mut Option<Spool>, record: Record) {
+    match spool {
+        Some(spool) if !spool.is_empty() || !db.connection().connected => spool.push(&record),
+        Some(spool) => {
+            if !matches!(breaker.call(db.send_record(record)).await, Some(Ok(_))) {
+                spool.push(&record);
+            }
+        }
+        None => {
+            let _ = breaker.call(db.send_record(record)).await;
+        }
+    }
+}
+
+/// Relit le tampon local dans l'ordre, par lots pour ne pas retarder les évènements.
+/// Retourne vrai si des échantillons ont été relus et qu'il en reste.
+async fn replay<S: TelemetryStore>(db: &S, breaker: &mut Breaker, spool: &mut Spool) -> bool {
+    if spool.is_empty() || !db.connection().connected {
+        return false;
+    }
+
+    let mut replayed = 0;
+    while replayed < REPLAY_BATCH {
+        let record = match spool.front() {
+            Ok(Some(record)) => record,
+            Ok(None) => break,
+            Err(e) => {
+                tracing::error!(target: "writer", "Relecture du tampon local impossible: {}", e);
+                break;
+            }
+        };
+
+        match breaker.call(db.send_record(record)).await {
+            Some(Ok(_)) => {
+                spool.pop();
+                replayed += 1;
+            }
+            _ => break,
+        }
+    }
+
+    if replayed > 0 && spool.is_empty() {
+        tracing::info!(target: "writer", "Tampon local relu ({} échantillon(s) depuis le démarrage)", spool.stats().replayed);
+    }
+
+    replayed > 0 && !spool.is_empty()
+}
+
+/// Journal et évènement d'un changement d'état du coupe-circuit
+fn report(writer: &Writer, clock: &Clock, transition: Transition) {
+    let (kind, severity, message) = match transition.state {
+        BreakerState::Open => ("db_breaker_open", Severity::Warning, "ouvert, échantillons non écrits en base"),
+        BreakerState::HalfOpen => ("db_breaker_half_open", Severity::Info, "semi-ouvert"),
+        BreakerState::Closed => ("db_breaker_closed", Severity::Info, "refermé"),
+    };
+    let message = format!("Coupe-circuit de la base {} ({})", message, transition.reason);
+    tracing::info!(target: "writer", "{}", message);
+
+    // Hors de la tâche de l'écrivain, qui vide la file des évènements
+    let writer = writer.clone();
+    let event = Event::Alert(kind, severity, message, clock.stamp());
+    tokio::spawn(async move {
+        let _ = writer.event(event).await;
+    });
+}
+
+/// Ecrit un évènement, réessaye jusqu'à réussite (ou arrêt du programme). Une série d'erreurs
+/// n'est journalisée qu'une fois, les suivantes en debug.
+async fn write_event(db: &Database, event: Event, repeated: &mut Repeated, token: &CancellationToken) {
+    loop {
+        let result = match &event {
+            Event::Status(status, stamp) => db.send_status(status.clone(), *stamp).await,
+            Event::Health(health, stale, stamp) => db.send_health(*health, stale, *stamp).await,
+            Event::Components(components, stamp) => db.send_components(components.clone(), *stamp).await,
+            Event::SelfTest(report) => db.send_selftest(report.clone()).await,
+            Event::Timing(report, stamp) => db.send_timing(*report, *stamp).await,
+            Event::Warning(message, stamp) => db.send_event("warning", message, *stamp).await,
+            Event::Config(message, stamp) => db.send_event("config", message, *stamp).await,
+            Event::Run(state, metadata, stamp) => db.send_run(state.clone(), metadata.clone(), *stamp).await,
+            Event::UncleanShutdown(previous, stamp) => {
+                let message = format!("Exécution précédente: {}", previous);
+                db.send_event("unclean_shutdown", &message, *stamp).await
+            }
+            Event::Can(message, stamp) => db.send_event("can", message, *stamp).await,
+            Event::CanStats(stats, stamp) => db.send_can_status(stats.clone(), *stamp).await,
+            Event::Rollover(kind, active, message, stamp) => match db.send_rollover_status(*active, *stamp).await {
+                Ok(_) => db.send_event(kind, message, *stamp).await,
+                Err(e) => Err(e),
+            },
+            Event::Limit(limit, stamp) => db.send_limit_status(limit.clone(), *stamp).await,
+            Event::Countdown(countdown, stamp) => db.send_countdown_status(*countdown, *stamp).await,
+            Event::Speed(command, output, stamp) => db.send_speed_status(*command, *output, *stamp).await,
+            Event::Capacity(consumed, remaining, stamp) => {
+                db.send_capacity_status(*consumed, *remaining, *stamp).await
+            }
+            Event::Attitude(attitude, stamp) => db.send_attitude(*attitude, *stamp).await,
+            Event::HeadingError(error, stamp) => db.send_heading_error(*error, *stamp).await,
+            Event::MagSuspect(suspect, _, message, stamp) => match db.send_mag_suspect_status(*suspect, *stamp).await {
+                Ok(_) => db.send_event(mag_suspect_kind(*suspect), message, *stamp).await,
+                Err(e) => Err(e),
+            },
+            Event::MagCalibration(fit, samples, stamp) => db.send_mag_calibration(*fit, *samples, *stamp).await,
+            Event::Alert(kind, _, message, stamp) => db.send_event(kind, message, *stamp).await,
+            Event::Logs(usage, stamp) => db.send_logs_status(*usage, *stamp).await,
+        };
+
+        match result {
+            Ok(_) => {
+                let failures = repeated.clear();
+                if failures > 1 {
+                    tracing::info!(target: "writer", "Ecriture des évènements rétablie après {} échec(s)", failures);
+                }
+                return;
+            }
+            Err(e) => {
+                if repeated.first() {
+                    tracing::error!(target: "writer", "Erreur lors de l'écriture d'un évènement: {}", e);
+                } else {
+                    tracing::debug!(target: "writer", "Erreur lors de l'écriture d'un évènement: {}", e);
+                }
+                if token.is_cancelled() {
+                    return;
+                }
+
+                // Connexion perdue: attend la reconnexion plutôt que de réessayer en boucle
+                if !db.connection().connected {
+                    tracing::info!(target: "writer", "Base déconnectée, évènements en attente de la reconnexion ...");
+                    tokio::select! {
+                        _ = token.cancelled() => return,
+                        _ = db.connected() => {}
+                    }
+                }
+                sleep(EVENT_RETRY).await;
+            }
+        }
+    }
+}
@@ -0,0 +1,109 @@
+use std::collections::BTreeMap;
+
+use zenoh::bytes::Encoding;
+use zenoh::pubsub::Publisher;
+use zenoh::qos::CongestionControl;
+use zenoh::Session;
+
+use crate::config::{ZenohConfig, ZenohEncoding, ZenohMode};
+use crate::record::Record;
+
+use super::cdr;
+
+/// Clé de publication d'un type d'échantillon
+pub fn key(config: &ZenohConfig, vehicle: &str, kind: &str) -> String {
+    config.key.replace("{vehicle}", vehicle).replace("{type}", kind)
+}
+
+/// Configuration de la session Zenoh
+fn session_config(config: &ZenohConfig) -> anyhow::Result<zenoh::Config> {
+    let mode = match config.mode {
+        ZenohMode::Peer => "peer",
+        ZenohMode::Client => "client",
+    };
+
+    let mut session = zenoh::Config::default();
+    let mut set = |key: &str, value: serde_json::Value| {
+        session
+            .insert_json5(key, &value.to_string())
+            .map_err(|e| anyhow::anyhow!("{}: {}", key, e))
+    };
+    set("mode", mode.into())?;
+    set("connect/endpoints", config.connect.clone().into())?;
+    if !config.listen.is_empty() {
+        set("listen/endpoints", config.listen.clone().into())?;
+    }
+    set("scouting/multicast/enabled", config.multicast.into())?;
+
+    Ok(session)
+}
+
+/// Session Zenoh et un éditeur par type d'échantillon publié
+pub struct Bridge {
+    session: Session,
+    publishers: BTreeMap<&'static str, Publisher<'static>>,
+    encoding: ZenohEncoding,
+}
+
+impl Bridge {
+    pub async fn open(config: &ZenohConfig, vehicle: &str) -> anyhow::Result<Self> {
+        let session = zenoh::open(session_config(config)?)
+            .await
+            .map_err(|e| anyhow::anyhow!(e))?;
+
+        let encoding = match config.encoding {
+            ZenohEncoding::Json => Encoding::APPLICATION_JSON,
+            ZenohEncoding::Cdr => Encoding::APPLICATION_CDR,
+        };
+
+        let mut publishers = BTreeMap::new();
+        for kind in Record::KINDS {
+            if !config.records.iter().any(|k| k == kind) {
+                continue;
+            }
+
+            // Réseau lent: les messages sont perdus, la publication n'attend jamais
+            let publisher = session
+                .declare_publisher(key(config, vehicle, kind))
+                .congestion_control(CongestionControl::Drop)
+                .encoding(encoding.clone())
+                .await
+                .map_err(|e| anyhow::anyhow!(e))?;
+            publishers.insert(kind, publisher);
+        }
+
+        Ok(Self {
+            session,
+            publishers,
+            encoding: config.encoding,
+        })
+    }
+
+    /// Publie un échantillon, ignoré si son type n'est pas publié
+    pub async fn publish(&self, record: &Record) -> anyhow::Result<()> {
+        let Some(publisher) = self.publishers.get(record.kind()) else {
+            return Ok(());
+        };
+
+        let payload = match self.encoding {
+            ZenohEncoding::Json => serde_json::to_vec(record)?,
+            ZenohEncoding::Cdr => cdr::encode(record),
+        };
+        publisher.put(payload).await.map_err(|e| anyhow::anyhow!(e))
+    }
+
+    /// Clés publiées
+    pub fn keys(&self) -> Vec<String> {
+        self.publishers
+            .values()
+            .map(|publisher| publisher.key_expr().to_string())
+            .collect()
+    }
+
+    pub async fn close(self) {
+        for (_, publisher) in self.publishers {
+            let _ = publisher.undeclare().await;
+        }
+        let _ = self.session.close().await;
+    }
+}
@@ -0,0 +1,148 @@
+use crate::clock::Stamp;
+use crate::record::Record;
+use crate::sensors::reader::RangeStatus;
+
+/// En-tête d'encapsulation CDR little-endian (ROS 2)
+const CDR_LE: [u8; 4] = [0x00, 0x01, 0x00, 0x00];
+
+/// Ecriture CDR: chaque valeur est alignée sur sa taille, à partir de la fin de l'en-tête
+struct Writer {
+    buffer: Vec<u8>,
+}
+
+impl Writer {
+    fn new() -> Self {
+        Self {
+            buffer: CDR_LE.to_vec(),
+        }
+    }
+
+    fn align(&mut self, size: usize) {
+        while !(self.buffer.len() - CDR_LE.len()).is_multiple_of(size) {
+            self.buffer.push(0);
+        }
+    }
+
+    fn bytes<const N: usize>(&mut self, bytes: [u8; N]) {
+        self.align(N);
+        self.buffer.extend_from_slice(&bytes);
+    }
+
+    fn u8(&mut self, value: u8) {
+        self.bytes(value.to_le_bytes());
+    }
+
+    fn bool(&mut self, value: bool) {
+        self.u8(value as u8);
+    }
+
+    fn i16(&mut self, value: i16) {
+        self.bytes(value.to_le_bytes());
+    }
+
+    fn u16(&mut self, value: u16) {
+        self.bytes(value.to_le_bytes());
+    }
+
+    fn u32(&mut self, value: u32) {
+        self.bytes(value.to_le_bytes());
+    }
+
+    fn i32(&mut self, value: i32) {
+        self.bytes(value.to_le_bytes());
+    }
+
+    fn u64(&mut self, value: u64) {
+        self.bytes(value.to_le_bytes());
+    }
+
+    fn f32(&mut self, value: f32) {
+        self.bytes(value.to_le_bytes());
+    }
+
+    fn f64(&mut self, value: f64) {
+        self.bytes(value.to_le_bytes());
+    }
+
+    /// builtin_interfaces/Time (heure UTC) suivi de l'horloge monotone
+    fn stamp(&mut self, stamp: &Stamp) {
+        self.i32(stamp.utc.timestamp() as i32);
+        self.u32(stamp.utc.timestamp_subsec_nanos());
+        self.u64(stamp.mono_us);
+    }
+}
+
+/// Message ROS 2 d'un échantillon, encodé en CDR (voir ros/msg)
+pub fn encode(record: &Record) -> Vec<u8> {
+    let mut writer = Writer::new();
+    writer.stamp(&record.stamp());
+
+    match record {
+        Record::Imu(data) => {
+            writer.f32(data.angles.0);
+            writer.f32(data.angles.1);
+            writer.f32(data.angles.2);
+            writer.f32(data.temp);
+            for value in [data.accel.0, data.accel.1, data.accel.2, data.gyro.0, data.gyro.1, data.gyro.2] {
+                writer.f32(value);
+            }
+        }
+        Record::Mag(data) => {
+            writer.i16(data.raw.0);
+            writer.i16(data.raw.1);
+            writer.i16(data.raw.2);
+            writer.f32(data.heading);
+            writer.f32(data.calibrated.0);
+            writer.f32(data.calibrated.1);
+            writer.f32(data.calibrated.2);
+            writer.f32(data.true_heading);
+            writer.f32(data.declination);
+        }
+        Record::Analog(data) => writer.f32(data.battery),
+        Record::Power(data) => {
+            writer.f32(data.voltage);
+            writer.f32(data.current);
+            writer.f32(data.power);
+        }
+        Record::Baro(data) => {
+            writer.f32(data.pressure);
+            writer.f32(data.temperature);
+            writer.f32(data.humidity.unwrap_or(f32::NAN));
+            writer.f32(data.altitude);
+        }
+        Record::Encoder(data) => {
+            writer.f64(data.speed_kmh);
+            writer.f64(data.distance_m);
+            writer.u64(data.pulses);
+        }
+        Record::Range(data) => {
+            // Distance 0 sans mesure valide: l'état fait foi
+            writer.u16(data.distance_mm.unwrap_or(0));
+            writer.u8(match data.status {
+                RangeStatus::Valid => 0,
+                RangeStatus::OutOfRange => 1,
+                RangeStatus::Invalid => 2,
+                RangeStatus::Timeout => 3,
+            });
+        }
+        Record::Gps(data) => {
+            writer.f64(data.speed_kmh);
+            writer.f64(data.latitude);
+            writer.f64(data.longitude);
+            writer.u8(data.satellites);
+            writer.bool(data.fix);
+            writer.f64(data.heading);
+        }
+        Record::Modem(data) => writer.u32(data.status.quality),
+        Record::Satellites(data) => {
+            writer.f64(data.pdop);
+            writer.f64(data.hdop);
+            writer.f64(data.vdop);
+            writer.u8(data.used);
+            writer.u8(data.in_view);
+            writer.f64(data.snr_mean);
+        }
+    }
+
+    writer.buffer
+}
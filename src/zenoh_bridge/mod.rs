@@ -0,0 +1,70 @@
+use std::time::{Duration, Instant};
+
+use tokio::sync::broadcast::error::RecvError;
+use tokio_util::sync::CancellationToken;
+
+use crate::config::ZenohConfig;
+use crate::writer::{Record, Writer};
+
+pub mod bridge;
+pub mod cdr;
+
+use bridge::Bridge;
+
+/// Intervalle du résumé des publications en erreur
+const ERROR_SUMMARY: Duration = Duration::from_secs(30);
+
+/// Publication Zenoh des échantillons diffusés par l'écrivain (interopérabilité ROS 2).
+/// Les messages qui ne peuvent pas partir sont perdus, la télémétrie n'est jamais bloquée.
+pub async fn run(config: ZenohConfig, vehicle: String, writer: Writer, token: CancellationToken) {
+    for kind in config.records.iter() {
+        if !Record::KINDS.contains(&kind.as_str()) {
+            tracing::warn!(target: "zenoh", "Type d'échantillon inconnu ignoré: {}", kind);
+        }
+    }
+
+    let bridge = match Bridge::open(&config, &vehicle).await {
+        Ok(bridge) => bridge,
+        Err(e) => {
+            tracing::error!(target: "zenoh", "Impossible d'ouvrir la session: {}", e);
+            return;
+        }
+    };
+    tracing::info!(target: "zenoh", "Publication sur {}", bridge.keys().join(", "));
+
+    let mut records = writer.subscribe();
+    let mut errors = 0u64;
+    let mut last_summary = Instant::now();
+
+    loop {
+        let record = tokio::select! {
+            _ = token.cancelled() => break,
+            record = records.recv() => match record {
+                Ok(record) => record,
+                Err(RecvError::Lagged(_)) => continue,
+                Err(RecvError::Closed) => break,
+            },
+        };
+
+        if let Err(e) = bridge.publish(&record).await {
+            if errors == 0 {
+                tracing::error!(target: "zenoh", "Erreur de publication: {}", e);
+            }
+            errors += 1;
+        }
+
+        if errors > 0 && last_summary.elapsed() >= ERROR_SUMMARY {
+            tracing::error!(
+                target: "zenoh",
+                "{} message(s) perdu(s) depuis {} s.",
+                errors,
+                ERROR_SUMMARY.as_secs()
+            );
+            errors = 0;
+            last_summary = Instant::now();
+        }
+    }
+
+    bridge.close().await;
+    tracing::info!(target: "zenoh", "Arrêt.");
+}
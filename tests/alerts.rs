@@ -0,0 +1,249 @@
+// Alertes: contenu envoyé aux webhooks, filtre, surveillance et nouvelles tentatives
+#![cfg(not(feature = "real-sensors"))]
+
+mod common;
+
+use voiturerc::{alerts, clock, config, record, sensors};
+
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use alerts::filter::{Decision, Filter, TEST};
+use alerts::monitor::Monitor;
+use alerts::webhook::{self, AlertMetrics, Outcome, Payload, Queue};
+use axum::extract::State;
+use axum::http::StatusCode;
+use axum::routing::post;
+use axum::{Json, Router};
+use clock::Stamp;
+use config::{AlertsConfig, Severity};
+use record::Record;
+use sensors::reader::{AnalogData, ImuData};
+use serde_json::Value;
+use tokio::sync::mpsc;
+use tokio_util::sync::CancellationToken;
+
+fn stamp() -> Stamp {
+    common::stamp_at(42)
+}
+
+fn analog(battery: f32) -> Record {
+    Record::Analog(AnalogData {
+        stamp: stamp(),
+        battery,
+        ..AnalogData::default()
+    })
+}
+
+fn payload(event: &str) -> Arc<Payload> {
+    Arc::new(Payload::new("voiturerc", "run-1", event, Severity::Critical, "détails", stamp()))
+}
+
+#[test]
+fn payload_fields() {
+    let payload = Payload::new(
+        "voiturerc",
+        "run-1717243242",
+        "battery_critical",
+        Severity::Critical,
+        "Batterie critique: 6.31 V (seuil 6.40 V)",
+        stamp(),
+    );
+    let json = serde_json::to_value(&payload).unwrap();
+
+    assert_eq!(json["vehicle"], "voiturerc");
+    assert_eq!(json["run"], "run-1717243242");
+    assert_eq!(json["event"], "battery_critical");
+    assert_eq!(json["severity"], "critical");
+    assert_eq!(json["details"], "Batterie critique: 6.31 V (seuil 6.40 V)");
+    assert_eq!(json["timestamp"], "2024-06-01T12:00:42.000Z");
+    assert_eq!(
+        json["text"],
+        "[voiturerc] Critique (battery_critical): Batterie critique: 6.31 V (seuil 6.40 V)"
+    );
+    assert_eq!(json["content"], json["text"]);
+}
+
+#[test]
+fn filter_types_severity_and_interval() {
+    let config = AlertsConfig {
+        events: vec!["estop".to_string(), "battery_critical".to_string(), "config".to_string()],
+        min_severity: Severity::Warning,
+        min_interval_s: 60,
+        ..AlertsConfig::default()
+    };
+    let mut filter = Filter::new(&config);
+    let start = Instant::now();
+
+    assert_eq!(filter.check("estop", Severity::Critical, start), Decision::Send);
+    // Type absent de la liste, gravité insuffisante
+    assert_eq!(filter.check("can", Severity::Warning, start), Decision::Ignored);
+    assert_eq!(filter.check("config", Severity::Info, start), Decision::Ignored);
+
+    // Intervalle minimal par type
+    let later = start + Duration::from_secs(30);
+    assert_eq!(filter.check("estop", Severity::Critical, later), Decision::Suppressed);
+    assert_eq!(filter.check("battery_critical", Severity::Critical, later), Decision::Send);
+    let after = start + Duration::from_secs(60);
+    assert_eq!(filter.check("estop", Severity::Critical, after), Decision::Send);
+
+    // L'alerte de test passe toujours
+    assert_eq!(filter.check(TEST, Severity::Info, after), Decision::Send);
+    assert_eq!(filter.check(TEST, Severity::Info, after), Decision::Send);
+}
+
+#[test]
+fn monitor_estop_battery_and_silence() {
+    let config = AlertsConfig {
+        battery_critical_v: 6.4,
+        silence_s: 10,
+        ..AlertsConfig::default()
+    };
+    let start = Instant::now();
+    let mut monitor = Monitor::new(&config, start);
+
+    // Arrêt d'urgence au désarmement uniquement
+    assert_eq!(monitor.armed(false).unwrap().kind, "estop");
+    assert!(monitor.armed(false).is_none());
+    assert!(monitor.armed(true).is_none());
+    assert_eq!(monitor.armed(false).unwrap().severity, Severity::Critical);
+
+    // Batterie: une alerte par passage sous le seuil, réarmée après une remontée suffisante
+    let alerts = monitor.record(&analog(6.3), start);
+    assert_eq!(alerts.len(), 1);
+    assert_eq!(alerts[0].kind, "battery_critical");
+    assert_eq!(alerts[0].details, "Batterie critique: 6.30 V (seuil 6.40 V)");
+    assert!(monitor.record(&analog(6.2), start).is_empty());
+    assert!(monitor.record(&analog(6.5), start).is_empty());
+    assert!(monitor.record(&analog(6.3), start).is_empty());
+    assert!(monitor.record(&analog(6.7), start).is_empty());
+    assert_eq!(monitor.record(&analog(6.3), start).len(), 1);
+
+    // Perte de la télémétrie, puis retour
+    assert!(monitor.tick(start + Duration::from_secs(9)).is_none());
+    let silent = monitor.tick(start + Duration::from_secs(10)).unwrap();
+    assert_eq!(silent.kind, "telemetry_silent");
+    assert_eq!(silent.details, "Aucun échantillon depuis 10 s");
+    assert!(monitor.tick(start + Duration::from_secs(20)).is_none());
+
+    let imu = Record::Imu(ImuData::default());
+    let restored = monitor.record(&imu, start + Duration::from_secs(25));
+    assert_eq!(restored.len(), 1);
+    assert_eq!(restored[0].kind, "telemetry_restored");
+    assert_eq!(restored[0].severity, Severity::Info);
+    assert!(monitor.record(&imu, start + Duration::from_secs(26)).is_empty());
+}
+
+#[test]
+fn queue_retries_with_backoff_then_gives_up() {
+    let start = tokio::time::Instant::now();
+    let mut queue = Queue::new(3, start);
+    queue.push(payload("estop"));
+    queue.push(payload("battery_critical"));
+
+    assert_eq!(queue.result(false, start), Outcome::Retry(Duration::from_secs(1)));
+    assert_eq!(queue.next().unwrap().1, start + Duration::from_secs(1));
+    assert_eq!(queue.result(false, start), Outcome::Retry(Duration::from_secs(2)));
+    assert_eq!(queue.result(false, start), Outcome::Failed);
+
+    // L'alerte suivante attend le délai suivant, puis le délai repart du minimum
+    let (next, at) = queue.next().unwrap();
+    assert_eq!(next.event, "battery_critical");
+    assert_eq!(at, start + Duration::from_secs(4));
+    assert_eq!(queue.result(true, start), Outcome::Sent);
+    assert!(queue.is_empty());
+    queue.push(payload("estop"));
+    assert_eq!(queue.result(false, start), Outcome::Retry(Duration::from_secs(1)));
+
+    // File pleine: la plus ancienne est perdue
+    let mut queue = Queue::new(3, start);
+    for _ in 0..webhook::QUEUE_LEN {
+        assert!(!queue.push(payload("estop")));
+    }
+    assert!(queue.push(payload("battery_critical")));
+    assert_eq!(queue.len(), webhook::QUEUE_LEN);
+}
+
+/// Webhook local: refuse la première requête, transmet les suivantes
+async fn hook(
+    State((requests, received)): State<(Arc<AtomicU64>, mpsc::UnboundedSender<Value>)>,
+    Json(body): Json<Value>,
+) -> StatusCode {
+    if requests.fetch_add(1, Ordering::Relaxed) == 0 {
+        return StatusCode::INTERNAL_SERVER_ERROR;
+    }
+    let _ = received.send(body);
+    StatusCode::NO_CONTENT
+}
+
+#[tokio::test]
+async fn delivery_retries_until_accepted() {
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let address = listener.local_addr().unwrap();
+    let (received, mut bodies) = mpsc::unbounded_channel();
+    let requests = Arc::new(AtomicU64::new(0));
+    let app = Router::new()
+        .route("/hook", post(hook))
+        .with_state((requests.clone(), received));
+    tokio::spawn(async move { axum::serve(listener, app).await });
+
+    let token = CancellationToken::new();
+    let metrics = Arc::new(AlertMetrics::default());
+    let (sender, receiver) = mpsc::channel(webhook::QUEUE_LEN);
+    let delivery = tokio::spawn(webhook::deliver(
+        format!("http://{}/hook", address),
+        reqwest::Client::new(),
+        3,
+        receiver,
+        metrics.clone(),
+        token.clone(),
+    ));
+
+    sender.send(payload("estop")).await.unwrap();
+    let body = tokio::time::timeout(Duration::from_secs(10), bodies.recv())
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(body["event"], "estop");
+    assert_eq!(body["vehicle"], "voiturerc");
+
+    token.cancel();
+    delivery.await.unwrap();
+    assert_eq!(requests.load(Ordering::Relaxed), 2);
+    assert_eq!(metrics.sent.load(Ordering::Relaxed), 1);
+    assert_eq!(metrics.retries.load(Ordering::Relaxed), 1);
+    assert_eq!(metrics.failed.load(Ordering::Relaxed), 0);
+}
+
+#[tokio::test]
+async fn unreachable_webhook_counts_failure() {
+    // Port fermé: connexion refusée immédiatement
+    let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+    let address = listener.local_addr().unwrap();
+    drop(listener);
+
+    let token = CancellationToken::new();
+    let metrics = Arc::new(AlertMetrics::default());
+    let (sender, receiver) = mpsc::channel(webhook::QUEUE_LEN);
+    let delivery = tokio::spawn(webhook::deliver(
+        format!("http://{}/hook", address),
+        reqwest::Client::new(),
+        1,
+        receiver,
+        metrics.clone(),
+        token.clone(),
+    ));
+
+    sender.send(payload("estop")).await.unwrap();
+    let deadline = Instant::now() + Duration::from_secs(10);
+    while metrics.failed.load(Ordering::Relaxed) == 0 && Instant::now() < deadline {
+        tokio::time::sleep(Duration::from_millis(20)).await;
+    }
+
+    token.cancel();
+    delivery.await.unwrap();
+    assert_eq!(metrics.failed.load(Ordering::Relaxed), 1);
+    assert_eq!(metrics.sent.load(Ordering::Relaxed), 0);
+    assert_eq!(metrics.retries.load(Ordering::Relaxed), 0);
+}
@@ -0,0 +1,123 @@
+// Allocations par échantillon dans la lecture des capteurs (capteurs simulés), mesurées
+// avec un allocateur qui compte. A lancer avec:
+//   cargo test --no-default-features --features fake-sensors,fake-actuators
+#![cfg(not(feature = "real-sensors"))]
+
+use voiturerc::{channel, clock, config, selftest, sensors};
+
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::cell::Cell;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+use channel::DropOldest;
+use chrono::Utc;
+use config::{Config, MagCalibration, SensorMode};
+use selftest::SelfTest;
+use sensors::reader::Poller;
+use sensors::sim::Simulation;
+use tokio::sync::{watch, Notify};
+
+/// Allocateur du système, compte les allocations du thread en cours de mesure
+struct Counting;
+
+static ALLOCATIONS: AtomicUsize = AtomicUsize::new(0);
+
+thread_local! {
+    static MEASURING: Cell<bool> = const { Cell::new(false) };
+}
+
+unsafe impl GlobalAlloc for Counting {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        if MEASURING.with(Cell::get) {
+            ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+        }
+        System.alloc(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout)
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        if MEASURING.with(Cell::get) {
+            ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+        }
+        System.realloc(ptr, layout, new_size)
+    }
+}
+
+#[global_allocator]
+static ALLOCATOR: Counting = Counting;
+
+/// Compte les allocations faites par `f` sur le thread courant
+fn count(f: impl FnOnce()) -> usize {
+    ALLOCATIONS.store(0, Ordering::Relaxed);
+    MEASURING.with(|m| m.set(true));
+    f();
+    MEASURING.with(|m| m.set(false));
+    ALLOCATIONS.load(Ordering::Relaxed)
+}
+
+/// Itérations mesurées
+const ITERATIONS: usize = 1000;
+
+/// Avant optimisation: 8 allocations par itération (noms des vérifications et état des capteurs
+/// désactivés recréés à chaque lecture), aucune depuis. Objectif: au moins deux fois moins.
+const BUDGET_PER_SAMPLE: f64 = 4.0;
+
+fn allocations_per_sample(config: &Config) -> f64 {
+    let clock = clock::Clock::virtual_at(Utc::now());
+    let simulation = Simulation::shared(&config.simulation);
+    let step = simulation.lock().unwrap().step_duration();
+    let selftest = SelfTest::new();
+    let (_calibration, mag_calibration) = watch::channel(MagCalibration::default());
+    let mut poller = Poller::new(config, &clock, &simulation, &selftest, &mag_calibration).unwrap();
+
+    let queue = DropOldest::new(config.writer.imu_queue, Arc::new(Notify::new()));
+    let mut elapsed = Duration::ZERO;
+    let mut iterate = |poller: &mut Poller| {
+        elapsed += step;
+        clock.set(elapsed);
+
+        let last = poller.data.imu.stamp;
+        poller.poll();
+        if poller.data.imu.stamp != last {
+            queue.push(poller.data.imu);
+        }
+        while queue.pop().is_some() {}
+    };
+
+    // Premier échantillon: vérifications du test de démarrage enregistrées une seule fois
+    for _ in 0..10 {
+        iterate(&mut poller);
+    }
+
+    let allocations = count(|| {
+        for _ in 0..ITERATIONS {
+            iterate(&mut poller);
+        }
+    });
+
+    allocations as f64 / ITERATIONS as f64
+}
+
+#[test]
+fn simulated_sensors_allocations() {
+    let per_sample = allocations_per_sample(&Config::default());
+
+    assert!(per_sample <= BUDGET_PER_SAMPLE, "{} allocation(s) par échantillon", per_sample);
+}
+
+#[test]
+fn disabled_sensors_do_not_allocate() {
+    let mut config = Config::default();
+    config.sensors.mag.mode = SensorMode::Disabled;
+    config.sensors.analog.mode = SensorMode::Disabled;
+    config.sensors.gps.mode = SensorMode::Disabled;
+
+    let per_sample = allocations_per_sample(&config);
+
+    assert!(per_sample <= BUDGET_PER_SAMPLE, "{} allocation(s) par échantillon", per_sample);
+}
@@ -0,0 +1,76 @@
+// Désarmement automatique: durée maximale armé, inactivité et durées restantes
+#![cfg(not(feature = "real-sensors"))]
+
+use voiturerc::{actuators, config};
+
+use std::time::{Duration, Instant};
+
+use actuators::auto_disarm::{AutoDisarm, Countdown, Expiry};
+use actuators::Control;
+use config::AutoDisarmConfig;
+
+fn at(start: Instant, s: u64) -> Instant {
+    start + Duration::from_secs(s)
+}
+
+fn config(max_armed_min: u64, idle_min: u64) -> AutoDisarmConfig {
+    AutoDisarmConfig {
+        max_armed_min,
+        idle_min,
+        ..AutoDisarmConfig::default()
+    }
+}
+
+#[test]
+fn max_armed_time_resets_on_arm() {
+    let start = Instant::now();
+    let mut timers = AutoDisarm::new(&config(10, 0), true, start);
+
+    assert_eq!(timers.update(at(start, 599)), None);
+    assert_eq!(
+        timers.countdown(at(start, 540)),
+        Countdown {
+            armed: Some(Duration::from_secs(60)),
+            idle: None,
+        }
+    );
+    assert_eq!(timers.update(at(start, 600)), Some(Expiry::Armed));
+    assert_eq!(timers.update(at(start, 1200)), None);
+
+    // Désarmé: pas de durée restante, un nouvel armement repart de zéro
+    timers.armed(false, at(start, 601));
+    assert_eq!(timers.countdown(at(start, 700)).armed, None);
+    timers.armed(true, at(start, 1000));
+    assert_eq!(timers.update(at(start, 1599)), None);
+    assert_eq!(timers.update(at(start, 1600)), Some(Expiry::Armed));
+}
+
+#[test]
+fn idle_resets_on_input_above_threshold() {
+    let start = Instant::now();
+    let mut timers = AutoDisarm::new(&config(0, 5), true, start);
+
+    // Commandes sous le seuil: toujours inactif
+    timers.input(&Control { steer: 0.02, speed: -0.04 }, at(start, 200));
+    assert_eq!(timers.countdown(at(start, 200)).idle, Some(Duration::from_secs(100)));
+
+    timers.input(&Control { steer: 0.0, speed: 0.3 }, at(start, 250));
+    assert_eq!(timers.update(at(start, 549)), None);
+    assert_eq!(timers.update(at(start, 550)), Some(Expiry::Idle));
+    assert_eq!(timers.countdown(at(start, 560)).idle, Some(Duration::ZERO));
+
+    // Une seule expiration jusqu'à la prochaine activité
+    assert_eq!(timers.update(at(start, 2000)), None);
+    timers.armed(true, at(start, 2000));
+    assert_eq!(timers.update(at(start, 2300)), Some(Expiry::Idle));
+}
+
+#[test]
+fn disabled_timers_never_expire() {
+    let start = Instant::now();
+    let mut timers = AutoDisarm::new(&config(0, 0), true, start);
+
+    assert_eq!(timers.update(at(start, 100_000)), None);
+    assert_eq!(timers.countdown(at(start, 100_000)), Countdown::default());
+    assert_eq!(Expiry::Idle.kind(), "idle_timeout");
+}
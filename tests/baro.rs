@@ -0,0 +1,88 @@
+// Baromètre BMP280/BME280: compensation Bosch et mise à zéro de l'altitude
+
+use voiturerc::{sensors::baro::altitude, sensors::baro::compensation};
+
+use altitude::Zero;
+use compensation::{raw_sample, Calibration};
+
+/// Coefficients de l'exemple de la fiche technique du BMP280 (registres 0x88 à 0x9F)
+fn datasheet_registers() -> [u8; 24] {
+    let words: [i32; 12] = [27504, 26435, -1000, 36477, -10685, 3024, 2855, 140, -7, 15500, -14600, 6000];
+    let mut registers = [0u8; 24];
+    for (index, word) in words.iter().enumerate() {
+        registers[2 * index..2 * index + 2].copy_from_slice(&(*word as u16).to_le_bytes());
+    }
+    registers
+}
+
+#[test]
+fn parses_calibration_registers() {
+    let calibration = Calibration::from_registers(&datasheet_registers(), None);
+    assert_eq!((calibration.t1, calibration.t2, calibration.t3), (27504, 26435, -1000));
+    assert_eq!((calibration.p1, calibration.p6, calibration.p9), (36477, -7, 6000));
+    assert!(calibration.humidity.is_none());
+
+    // H4 et H5 sur 12 bits, partageant l'octet 0xE5
+    let humidity = [0x6A, 0x01, 0x00, 0x13, 0x25, 0x03, 0x1E];
+    let calibration = Calibration::from_registers(&datasheet_registers(), Some((75, &humidity)));
+    let humidity = calibration.humidity.unwrap();
+    assert_eq!((humidity.h1, humidity.h2, humidity.h3), (75, 362, 0));
+    assert_eq!((humidity.h4, humidity.h5, humidity.h6), (309, 50, 30));
+}
+
+#[test]
+fn datasheet_example_compensation() {
+    let calibration = Calibration::from_registers(&datasheet_registers(), None);
+    let measure = calibration.compensate(415148, 519888, Some(30000));
+
+    assert!((measure.temperature - 25.08).abs() < 0.01, "{}", measure.temperature);
+    assert!((measure.pressure - 100653.27).abs() < 0.1, "{}", measure.pressure);
+    // BMP280: pas d'humidité même avec une valeur brute
+    assert!(measure.humidity.is_none());
+}
+
+#[test]
+fn humidity_compensation() {
+    let humidity = [0x6A, 0x01, 0x00, 0x13, 0x25, 0x03, 0x1E];
+    let calibration = Calibration::from_registers(&datasheet_registers(), Some((75, &humidity)));
+
+    let measure = calibration.compensate(415148, 519888, Some(30000));
+    let humidity = measure.humidity.unwrap();
+    assert!((humidity - 56.42).abs() < 0.01, "{}", humidity);
+
+    // Bornée à 100 %
+    let measure = calibration.compensate(415148, 519888, Some(65535));
+    assert_eq!(measure.humidity, Some(100.0));
+}
+
+#[test]
+fn raw_sample_layout() {
+    // Pression et température sur 20 bits (xlsb: 4 bits de poids fort), humidité sur 16 bits
+    let (adc_p, adc_t, adc_h) = raw_sample(&[0x65, 0x5A, 0xC0, 0x7E, 0xED, 0x00, 0x75, 0x30]);
+    assert_eq!(adc_p, 415148);
+    assert_eq!(adc_t, 519888);
+    assert_eq!(adc_h, 30000);
+}
+
+#[test]
+fn altitude_zeroed_at_startup() {
+    let mut zero = Zero::new(3);
+
+    // Altitude nulle pendant la mise à zéro, référence: moyenne des échantillons
+    for pressure in [101_320.0, 101_325.0, 101_330.0] {
+        assert_eq!(zero.altitude(pressure), 0.0);
+    }
+    assert!(zero.altitude(101_325.0).abs() < 1e-3);
+
+    // Environ 8.4 m par hPa près du niveau de la mer, pression en baisse quand on monte
+    let altitude = zero.altitude(101_225.0);
+    assert!((altitude - 8.3).abs() < 0.2, "{}", altitude);
+    assert!(zero.altitude(101_425.0) < 0.0);
+}
+
+#[test]
+fn zero_samples_at_least_one() {
+    let mut zero = Zero::new(0);
+    assert_eq!(zero.altitude(100_000.0), 0.0);
+    assert!(zero.altitude(100_000.0).abs() < 1e-3);
+}
@@ -0,0 +1,176 @@
+// Journal blackbox: champs selon les capteurs, relecture exacte des trames écrites
+#![cfg(not(feature = "real-sensors"))]
+
+use voiturerc::{actuators, blackbox, config, sensors};
+
+use actuators::arbiter::ControlState;
+use actuators::Control;
+use blackbox::decode::Log;
+use blackbox::encode::{Encoder, Field, LOG_END};
+use blackbox::fields::{fields, Source};
+use config::{SensorMode, SensorsConfig};
+use sensors::reader::Data;
+
+fn field(name: &str, signed: bool) -> Field {
+    Field {
+        name: name.to_string(),
+        signed,
+    }
+}
+
+fn encode(fields: &[Field], i_interval: u32, frames: &[(u32, Vec<i32>)], finish: bool) -> Vec<u8> {
+    let mut encoder = Encoder::new(Vec::new(), fields, i_interval, &[("Run", "run-1".to_string())]).unwrap();
+    for (time, values) in frames {
+        encoder.write(*time, values).unwrap();
+    }
+    let mut bytes = encoder.finish().unwrap();
+    if !finish {
+        // Arrêt brutal: pas d'événement de fin
+        bytes.truncate(bytes.len() - 2 - LOG_END.len());
+    }
+    bytes
+}
+
+/// Trames variées: grands écarts, valeurs négatives, temps proche du débordement
+fn frames(count: usize) -> Vec<(u32, Vec<i32>)> {
+    (0..count)
+        .map(|n| {
+            let n = n as i32;
+            let time = (u32::MAX - 50_000).wrapping_add(n as u32 * 20_000 + (n as u32 % 3) * 7);
+            let values = vec![
+                (n * 37) % 1800 - 900,
+                if n % 10 == 0 { i32::MIN } else { -n * 1000 },
+                1500 + (n % 7) * 80,
+                if n % 13 == 0 { i32::MAX } else { n },
+            ];
+            (time, values)
+        })
+        .collect()
+}
+
+#[test]
+fn round_trip() {
+    let fields = [
+        field("attitude[0]", true),
+        field("rcCommand[0]", true),
+        field("motor[0]", false),
+        field("vbatLatest", false),
+    ];
+    let frames = frames(100);
+    let bytes = encode(&fields, 8, &frames, true);
+
+    let log = Log::parse(&bytes).unwrap();
+    assert!(log.complete);
+    assert_eq!(log.headers["I interval"], "8");
+    assert_eq!(log.headers["Run"], "run-1");
+    assert_eq!(
+        log.names,
+        ["loopIteration", "time", "attitude[0]", "rcCommand[0]", "motor[0]", "vbatLatest"]
+    );
+    assert_eq!(log.frames.len(), frames.len());
+
+    for (n, ((time, values), decoded)) in frames.iter().zip(&log.frames).enumerate() {
+        let mut expected = vec![n as i64, *time as i64];
+        expected.extend(values.iter().zip(&fields).map(|(value, field)| {
+            if field.signed {
+                *value as i64
+            } else {
+                *value as u32 as i64
+            }
+        }));
+        assert_eq!(*decoded, expected, "trame {}", n);
+    }
+}
+
+#[test]
+fn constant_values_give_small_p_frames() {
+    // Valeurs constantes, temps régulier: une trame P tient en un octet par champ
+    let fields = [field("motor[0]", false), field("vbatLatest", false)];
+    let frames: Vec<_> = (0..33).map(|n| (n * 20_000, vec![1500, 1180])).collect();
+    let header = encode(&fields, 32, &[], true).len();
+    let bytes = encode(&fields, 32, &frames, true);
+
+    // Trames I: type, loopIteration, time (1 puis 3 octets), 2 champs de 2 octets.
+    // Trames P: type, écart au temps prédit, 2 écarts nuls. La première trame P après une
+    // trame I prédit le temps de la trame I: écart de 3 octets.
+    assert_eq!(bytes.len() - header, 7 + 9 + 31 * 4 + 2);
+    assert_eq!(Log::parse(&bytes).unwrap().frames.len(), 33);
+}
+
+#[test]
+fn truncated_log() {
+    let fields = [field("heading", false)];
+    let frames: Vec<_> = (0..10).map(|n| (n * 1000, vec![n as i32 * 10])).collect();
+    let log = Log::parse(&encode(&fields, 4, &frames, false)).unwrap();
+    assert!(!log.complete);
+    assert_eq!(log.frames.len(), 10);
+    assert_eq!(log.frames[9], [9, 9000, 90]);
+}
+
+#[test]
+fn fields_follow_enabled_sensors() {
+    let names = |sensors: &SensorsConfig, current: bool| -> Vec<String> {
+        fields(sensors, current).into_iter().map(|(field, _)| field.name).collect()
+    };
+
+    let mut sensors = SensorsConfig::default();
+    sensors.gps.mode = SensorMode::Fake;
+    assert_eq!(
+        names(&sensors, true),
+        [
+            "attitude[0]",
+            "attitude[1]",
+            "attitude[2]",
+            "rcCommand[0]",
+            "rcCommand[3]",
+            "servo[0]",
+            "motor[0]",
+            "vbatLatest",
+            "amperageLatest",
+            "heading"
+        ]
+    );
+
+    sensors.imu.mode = SensorMode::Disabled;
+    sensors.analog.mode = SensorMode::Disabled;
+    sensors.mag.mode = SensorMode::Disabled;
+    sensors.gps.mode = SensorMode::Disabled;
+    assert_eq!(
+        names(&sensors, false),
+        ["rcCommand[0]", "rcCommand[3]", "servo[0]", "motor[0]"]
+    );
+}
+
+#[test]
+fn field_values() {
+    let mut data = Data::default();
+    data.imu.angles = (-5.25, 12.5, 270.0);
+    data.analog.battery = 7.42;
+    data.mag.heading = 121.0;
+    data.mag.true_heading = 123.4;
+    let control = ControlState {
+        input: Control { steer: -0.5, speed: 0.4 },
+        output: Control { steer: -0.5, speed: 0.0 },
+        ..ControlState::default()
+    };
+
+    let value = |source: Source| source.value(&data, &control, Some(12.34));
+    assert_eq!(value(Source::Attitude(0)), 125);
+    assert_eq!(value(Source::Attitude(1)), -53);
+    assert_eq!(value(Source::Attitude(2)), 2700);
+    assert_eq!(value(Source::SteerInput), -250);
+    assert_eq!(value(Source::SpeedInput), 1700);
+    assert_eq!(value(Source::Steering), 1250);
+    assert_eq!(value(Source::Motor), 1500);
+    assert_eq!(value(Source::Battery), 742);
+    assert_eq!(value(Source::Current), 1234);
+    // Magnétomètre sans échantillon: cap nul
+    assert_eq!(value(Source::Heading), 0);
+
+    // Cap vrai, corrigé de la déclinaison
+    data.mag.stamp.mono_us = 1;
+    assert_eq!(Source::Heading.value(&data, &control, None), 1234);
+    data.gps.fix = true;
+    data.gps.heading = -10.0;
+    assert_eq!(Source::Heading.value(&data, &control, None), 3500);
+}
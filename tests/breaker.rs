@@ -0,0 +1,184 @@
+// Coupe-circuit des écritures dans la base: ouverture, écritures de test et fermeture, sur une
+// base simulée dont la latence de chaque écriture est scriptée (horloge virtuelle)
+#![cfg(not(feature = "real-sensors"))]
+
+use voiturerc::{breaker, clock, config};
+
+use std::collections::VecDeque;
+use std::time::Duration;
+
+use chrono::Utc;
+
+use breaker::{Breaker, BreakerState, BreakerStats};
+use clock::Clock;
+use config::BreakerConfig;
+
+const FAST: Duration = Duration::from_millis(10);
+const SLOW: Duration = Duration::from_millis(300);
+const PROBE_INTERVAL: Duration = Duration::from_millis(1000);
+
+/// Avance l'horloge virtuelle
+fn advance(clock: &Clock, duration: Duration) {
+    clock.set(clock.elapsed() + duration);
+}
+
+fn config() -> BreakerConfig {
+    BreakerConfig {
+        enabled: true,
+        window: 4,
+        min_writes: 3,
+        max_latency_ms: 100,
+        max_error_rate: 0.5,
+        probe_interval_ms: PROBE_INTERVAL.as_millis() as u64,
+        close_after: 2,
+    }
+}
+
+/// Base simulée: latence et réussite de chaque écriture, dans l'ordre
+struct Sink {
+    clock: Clock,
+    script: VecDeque<(Duration, bool)>,
+    written: usize,
+}
+
+impl Sink {
+    fn new(clock: &Clock) -> Self {
+        Self {
+            clock: clock.clone(),
+            script: VecDeque::new(),
+            written: 0,
+        }
+    }
+
+    fn script(&mut self, count: usize, latency: Duration, ok: bool) {
+        self.script.extend(std::iter::repeat_n((latency, ok), count));
+    }
+
+    async fn write(&mut self) -> anyhow::Result<()> {
+        let (latency, ok) = self.script.pop_front().expect("écriture non scriptée");
+        advance(&self.clock, latency);
+        self.written += 1;
+        if ok {
+            Ok(())
+        } else {
+            Err(anyhow::anyhow!("écriture refusée"))
+        }
+    }
+}
+
+/// Effectue `count` écritures, nombre d'écritures détournées
+async fn write(breaker: &mut Breaker, sink: &mut Sink, count: usize) -> usize {
+    let mut diverted = 0;
+    for _ in 0..count {
+        if breaker.call(sink.write()).await.is_none() {
+            diverted += 1;
+        }
+    }
+    diverted
+}
+
+fn states(breaker: &mut Breaker) -> Vec<BreakerState> {
+    breaker.transitions().into_iter().map(|transition| transition.state).collect()
+}
+
+#[tokio::test]
+async fn opens_on_latency_probes_and_closes() {
+    let clock = Clock::virtual_at(Utc::now());
+    let mut breaker = Breaker::new(&config(), &clock);
+    let mut sink = Sink::new(&clock);
+
+    // Base rapide: fermé
+    sink.script(3, FAST, true);
+    assert_eq!(write(&mut breaker, &mut sink, 3).await, 0);
+    assert_eq!(breaker.stats().state, BreakerState::Closed);
+    assert!(states(&mut breaker).is_empty());
+
+    // Base lente: ouvert lorsque la latence moyenne de la fenêtre dépasse le seuil
+    sink.script(2, SLOW, true);
+    assert_eq!(write(&mut breaker, &mut sink, 2).await, 0);
+    let transitions = breaker.transitions();
+    assert_eq!(transitions.len(), 1);
+    assert_eq!(transitions[0].state, BreakerState::Open);
+    assert!(transitions[0].reason.contains("latence"), "{}", transitions[0].reason);
+
+    // Ouvert: écritures détournées sans attendre la base
+    let before = clock.elapsed();
+    assert_eq!(write(&mut breaker, &mut sink, 5).await, 5);
+    assert_eq!((sink.written, clock.elapsed()), (5, before));
+
+    // Ecriture de test encore lente: de nouveau ouvert, jusqu'au test suivant
+    advance(&clock, PROBE_INTERVAL);
+    sink.script(1, SLOW, true);
+    assert_eq!(write(&mut breaker, &mut sink, 1).await, 0);
+    assert_eq!(states(&mut breaker), [BreakerState::HalfOpen, BreakerState::Open]);
+    advance(&clock, PROBE_INTERVAL / 2);
+    assert_eq!(write(&mut breaker, &mut sink, 1).await, 1);
+
+    // Base rétablie: semi-ouvert, puis refermé après deux tests réussis
+    advance(&clock, PROBE_INTERVAL / 2);
+    sink.script(1, FAST, true);
+    assert_eq!(write(&mut breaker, &mut sink, 1).await, 0);
+    assert_eq!(breaker.stats().state, BreakerState::HalfOpen);
+    sink.script(1, FAST, true);
+    assert_eq!(write(&mut breaker, &mut sink, 1).await, 0);
+    assert_eq!(states(&mut breaker), [BreakerState::HalfOpen, BreakerState::Closed]);
+
+    // Fermé: fenêtre vidée, une écriture lente isolée ne rouvre pas
+    sink.script(3, FAST, true);
+    sink.script(1, SLOW, true);
+    assert_eq!(write(&mut breaker, &mut sink, 4).await, 0);
+    assert_eq!(breaker.stats().state, BreakerState::Closed);
+    assert!(sink.script.is_empty());
+
+    assert_eq!(
+        breaker.stats(),
+        BreakerStats {
+            state: BreakerState::Closed,
+            opened: 1,
+            diverted: 6,
+            probes: 3,
+        }
+    );
+}
+
+#[tokio::test]
+async fn opens_on_error_rate() {
+    let clock = Clock::virtual_at(Utc::now());
+    let mut breaker = Breaker::new(&config(), &clock);
+    let mut sink = Sink::new(&clock);
+
+    // Moitié des écritures en erreur: seuil atteint, pas dépassé
+    sink.script(2, FAST, true);
+    sink.script(2, FAST, false);
+    assert_eq!(write(&mut breaker, &mut sink, 4).await, 0);
+    assert_eq!(breaker.stats().state, BreakerState::Closed);
+
+    sink.script(1, FAST, false);
+    assert_eq!(write(&mut breaker, &mut sink, 1).await, 0);
+    let transitions = breaker.transitions();
+    assert_eq!(transitions.len(), 1);
+    assert!(transitions[0].reason.contains("erreur"), "{}", transitions[0].reason);
+
+    // Ecriture de test en erreur: de nouveau ouvert
+    advance(&clock, PROBE_INTERVAL);
+    sink.script(1, FAST, false);
+    assert_eq!(write(&mut breaker, &mut sink, 2).await, 1);
+    assert_eq!(states(&mut breaker), [BreakerState::HalfOpen, BreakerState::Open]);
+    assert_eq!(breaker.stats().opened, 1);
+}
+
+#[tokio::test]
+async fn disabled_never_diverts() {
+    let clock = Clock::virtual_at(Utc::now());
+    let config = BreakerConfig {
+        enabled: false,
+        ..config()
+    };
+    let mut breaker = Breaker::new(&config, &clock);
+    let mut sink = Sink::new(&clock);
+
+    sink.script(10, SLOW, false);
+    assert_eq!(write(&mut breaker, &mut sink, 10).await, 0);
+    assert_eq!(breaker.stats(), BreakerStats::default());
+    assert!(breaker.transitions().is_empty());
+}
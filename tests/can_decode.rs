@@ -0,0 +1,96 @@
+use voiturerc::sensors::can::decode;
+
+use decode::{CanMessage, CanSignal, Decoder};
+
+fn signal(name: &str, start_bit: u16, length: u8) -> CanSignal {
+    CanSignal {
+        name: name.to_string(),
+        start_bit,
+        length,
+        ..CanSignal::default()
+    }
+}
+
+#[test]
+fn little_endian_unsigned() {
+    // rpm = 0x1234 sur les deux premiers octets (Intel)
+    let frame = [0x34, 0x12, 0, 0, 0, 0, 0, 0];
+
+    assert_eq!(signal("rpm", 0, 16).raw(&frame), Some(0x1234));
+    assert_eq!(signal("low", 0, 8).raw(&frame), Some(0x34));
+    assert_eq!(signal("nibble", 4, 8).raw(&frame), Some(0x23));
+}
+
+#[test]
+fn little_endian_signed_scaled() {
+    // courant = -25.0 A: brut -250 (0xFF06) avec un facteur 0.1
+    let frame = [0, 0, 0, 0x06, 0xFF, 0, 0, 0];
+    let current = CanSignal {
+        scale: 0.1,
+        signed: true,
+        ..signal("current", 24, 16)
+    };
+
+    assert_eq!(current.raw(&frame), Some(-250));
+    assert!((current.decode(&frame).unwrap() + 25.0).abs() < 1e-9);
+}
+
+#[test]
+fn offset() {
+    // température = 65 - 40 = 25 °C
+    let frame = [0, 0, 65, 0, 0, 0, 0, 0];
+    let temperature = CanSignal {
+        offset: -40.0,
+        ..signal("temperature", 16, 8)
+    };
+
+    assert_eq!(temperature.decode(&frame), Some(25.0));
+}
+
+#[test]
+fn big_endian() {
+    // Motorola: départ sur le bit 7 de l'octet 0 (poids fort), 16 bits = 0x1234
+    let frame = [0x12, 0x34, 0, 0, 0, 0, 0, 0];
+    let voltage = CanSignal {
+        big_endian: true,
+        ..signal("voltage", 7, 16)
+    };
+
+    assert_eq!(voltage.raw(&frame), Some(0x1234));
+
+    // 12 bits à partir du bit 3 de l'octet 0: 0x2 (4 bits) puis 0x34
+    let partial = CanSignal {
+        big_endian: true,
+        ..signal("partial", 3, 12)
+    };
+
+    assert_eq!(partial.raw(&frame), Some(0x234));
+}
+
+#[test]
+fn short_frame() {
+    // Signal au-delà de la longueur de la trame reçue
+    let frame = [0x01, 0x02];
+
+    assert_eq!(signal("far", 16, 8).raw(&frame), None);
+    assert!(signal("far", 60, 8).validate().is_err());
+    assert!(signal("ok", 56, 8).validate().is_ok());
+}
+
+#[test]
+fn decoder() {
+    let messages = vec![CanMessage {
+        id: 0x101,
+        name: "esc".to_string(),
+        signals: vec![signal("rpm", 0, 16), signal("far", 56, 8)],
+    }];
+    let decoder = Decoder::new(&messages);
+
+    // Trame de 2 octets: seul rpm est présent
+    let (name, signals) = decoder.decode(0x101, &[0xE8, 0x03]).unwrap();
+    assert_eq!(name, "esc");
+    assert_eq!(signals.get("rpm"), Some(&1000.0));
+    assert!(!signals.contains_key("far"));
+
+    assert!(decoder.decode(0x102, &[0; 8]).is_none());
+}
@@ -0,0 +1,69 @@
+// Capacité consommée de la batterie: intégration du courant, mesures perdues et remise à zéro
+
+use voiturerc::capacity;
+
+use capacity::Capacity;
+
+/// Echantillons de courant constant toutes les 100 ms pendant `seconds`, à partir de `start_us`
+fn run(capacity: &mut Capacity, start_us: u64, seconds: u64, current: f32) -> u64 {
+    let mut mono_us = start_us;
+    for _ in 0..seconds * 10 {
+        mono_us += 100_000;
+        capacity.update(mono_us, Some(current));
+    }
+    mono_us
+}
+
+#[test]
+fn integrates_current() {
+    let mut capacity = Capacity::new(5000.0);
+    capacity.update(0, Some(36.0));
+    run(&mut capacity, 0, 100, 36.0);
+
+    // 36 A pendant 100 s: 1000 mAh
+    assert!((capacity.consumed_mah() - 1000.0).abs() < 1e-6, "{}", capacity.consumed_mah());
+    assert!((capacity.remaining_pct() - 80.0).abs() < 1e-6);
+}
+
+#[test]
+fn holds_last_current_once() {
+    let mut capacity = Capacity::new(5000.0);
+    capacity.update(0, Some(36.0));
+    capacity.update(100_000, Some(36.0));
+    let consumed = capacity.consumed_mah();
+
+    // Première mesure perdue: courant précédent
+    capacity.update(200_000, None);
+    assert!((capacity.consumed_mah() - 2.0 * consumed).abs() < 1e-9);
+
+    // Mesures perdues suivantes: plus d'intégration, ni jusqu'à la mesure suivante
+    capacity.update(300_000, None);
+    capacity.update(400_000, Some(36.0));
+    assert!((capacity.consumed_mah() - 2.0 * consumed).abs() < 1e-9);
+    capacity.update(500_000, Some(36.0));
+    assert!((capacity.consumed_mah() - 3.0 * consumed).abs() < 1e-9);
+}
+
+#[test]
+fn skips_long_gaps() {
+    let mut capacity = Capacity::new(5000.0);
+    capacity.update(0, Some(36.0));
+    capacity.update(capacity::MAX_GAP_US + 1, Some(36.0));
+    assert_eq!(capacity.consumed_mah(), 0.0);
+}
+
+#[test]
+fn reset_and_clamped_remaining() {
+    let mut capacity = Capacity::new(1000.0);
+    capacity.update(0, Some(36.0));
+    let end = run(&mut capacity, 0, 200, 36.0);
+    assert!(capacity.consumed_mah() > 1000.0);
+    assert_eq!(capacity.remaining_pct(), 0.0);
+
+    // Nouvelle batterie, déjà entamée: l'intégration reprend à la mesure suivante
+    capacity.reset(250.0);
+    assert_eq!(capacity.consumed_mah(), 250.0);
+    assert_eq!(capacity.remaining_pct(), 75.0);
+    capacity.update(end + 100_000, Some(36.0));
+    assert_eq!(capacity.consumed_mah(), 250.0);
+}
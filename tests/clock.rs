@@ -0,0 +1,115 @@
+// Horloge partagée: temps réel, temps virtuel et horloge accélérée de la simulation
+
+use voiturerc::{clock, sensors::sim};
+
+use std::sync::{Arc, Barrier};
+use std::thread;
+use std::time::Duration;
+
+use chrono::{TimeZone, Utc};
+use clock::Clock;
+use futures::FutureExt;
+use sim::{Scenario, Simulation};
+
+/// Ecart maximum toléré entre deux capteurs lus "en même temps"
+const MAX_DELTA_US: u64 = 20_000;
+
+/// Avance l'horloge virtuelle
+fn advance(clock: &Clock, duration: Duration) {
+    clock.set(clock.elapsed() + duration);
+}
+
+#[test]
+fn simultaneous_stamps_are_close() {
+    let clock = Clock::start();
+    let barrier = Arc::new(Barrier::new(2));
+
+    // Deux capteurs, chacun dans son thread, horodatent au même moment
+    let sensors: Vec<_> = (0..2)
+        .map(|_| {
+            let clock = clock.clone();
+            let barrier = barrier.clone();
+            thread::spawn(move || {
+                barrier.wait();
+                clock.stamp()
+            })
+        })
+        .collect();
+
+    let stamps: Vec<_> = sensors.into_iter().map(|s| s.join().unwrap()).collect();
+    let delta = stamps[0].mono_us.abs_diff(stamps[1].mono_us);
+    assert!(delta < MAX_DELTA_US, "écart de {}µs", delta);
+
+    // L'heure UTC est dérivée du temps monotone: même écart
+    let utc_delta = (stamps[0].utc - stamps[1].utc).num_microseconds().unwrap();
+    assert_eq!(utc_delta.unsigned_abs(), delta);
+}
+
+#[test]
+fn virtual_clock_is_controlled() {
+    let anchor = Utc.with_ymd_and_hms(2024, 6, 1, 12, 0, 0).unwrap();
+    let clock = Clock::virtual_at(anchor);
+
+    clock.set(Duration::from_millis(50));
+    let imu = clock.stamp();
+    let gps = clock.stamp();
+    assert_eq!(imu, gps);
+    assert_eq!(imu.mono_us, 50_000);
+    assert_eq!(imu.utc, anchor + chrono::Duration::milliseconds(50));
+
+    // Le temps virtuel ne recule jamais
+    clock.set(Duration::from_millis(10));
+    assert_eq!(clock.stamp().mono_us, 50_000);
+}
+
+#[test]
+fn simulation_follows_virtual_clock() {
+    let scenario = Scenario {
+        seed: Some(1),
+        ..Scenario::default()
+    };
+    let clock = Clock::virtual_at(Utc::now());
+
+    let mut synced = Simulation::new(&scenario);
+    clock.set(Duration::from_secs(1));
+    synced.sync(clock.elapsed());
+
+    // Une seconde au pas par défaut (50 ms)
+    let mut stepped = Simulation::new(&scenario);
+    for _ in 0..20 {
+        stepped.step();
+    }
+
+    assert_eq!(synced.readings(), stepped.readings());
+}
+
+#[tokio::test]
+async fn virtual_sleep_follows_advance() {
+    let clock = Clock::virtual_at(Utc::now());
+    let start = clock.now();
+
+    let mut sleep = Box::pin(clock.sleep(Duration::from_millis(500)));
+    assert!(sleep.as_mut().now_or_never().is_none());
+    advance(&clock, Duration::from_millis(499));
+    assert!(sleep.as_mut().now_or_never().is_none());
+    advance(&clock, Duration::from_millis(1));
+    assert!(sleep.as_mut().now_or_never().is_some());
+
+    assert_eq!(clock.since(start), Duration::from_millis(500));
+    assert_eq!(clock.now() - start, Duration::from_millis(500));
+}
+
+#[tokio::test]
+async fn virtual_timeout() {
+    let clock = Clock::virtual_at(Utc::now());
+
+    // Résultat disponible avant le délai
+    assert_eq!(clock.timeout(Duration::from_secs(1), async { 42 }).now_or_never(), Some(Some(42)));
+
+    // Délai expiré uniquement lorsque le temps virtuel avance
+    let (_sender, receiver) = tokio::sync::oneshot::channel::<()>();
+    let mut timeout = Box::pin(clock.timeout(Duration::from_secs(1), receiver));
+    assert!(timeout.as_mut().now_or_never().is_none());
+    clock.set(Duration::from_secs(1));
+    assert!(matches!(timeout.as_mut().now_or_never(), Some(None)));
+}
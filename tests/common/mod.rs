@@ -0,0 +1,49 @@
+// Données de test partagées: horodatages et échantillons types des capteurs
+#![allow(dead_code)]
+
+use voiturerc::{clock, sensors};
+
+use chrono::{TimeZone, Utc};
+use clock::Stamp;
+use sensors::reader::{GpsData, ImuData};
+
+/// Horodatage des échantillons: 1,5 s après le démarrage, le 1er juin 2024 à midi
+pub fn stamp() -> Stamp {
+    Stamp {
+        mono_us: 1_500_000,
+        utc: Utc.with_ymd_and_hms(2024, 6, 1, 12, 0, 0).unwrap(),
+    }
+}
+
+/// Horodatage `second` secondes après le démarrage, à midi et `second` secondes
+pub fn stamp_at(second: u32) -> Stamp {
+    Stamp {
+        mono_us: second as u64 * 1_000_000,
+        utc: Utc.with_ymd_and_hms(2024, 6, 1, 12, 0, second).unwrap(),
+    }
+}
+
+/// Position avec fix, sans heure GPS
+pub fn gps() -> GpsData {
+    GpsData {
+        stamp: stamp(),
+        speed_kmh: 25.5,
+        latitude: 46.52,
+        longitude: 6.632,
+        satellites: 9,
+        fix: true,
+        heading: 90.0,
+        time: None,
+        valid: false,
+    }
+}
+
+pub fn imu() -> ImuData {
+    ImuData {
+        stamp: stamp(),
+        angles: (1.5, -2.25, 180.0),
+        temp: 31.0,
+        accel: (0.25, -0.125, 1.0),
+        gyro: (0.5, -1.25, 12.0),
+    }
+}
@@ -0,0 +1,322 @@
+// Chargement du fichier de configuration: fichier absent, invalide, section [database]
+#![cfg(not(feature = "real-sensors"))]
+
+use voiturerc::{config, sensors};
+
+use std::path::PathBuf;
+use std::time::Duration;
+
+use config::Config;
+
+/// Fichier temporaire propre au test
+fn file(name: &str, content: Option<&str>) -> PathBuf {
+    let path = std::env::temp_dir().join(format!("rc-telemetrie-config-{}-{}.toml", name, std::process::id()));
+    let _ = std::fs::remove_file(&path);
+    if let Some(content) = content {
+        std::fs::write(&path, content).unwrap();
+    }
+    path
+}
+
+#[test]
+fn missing_file_uses_defaults() {
+    let config = Config::load(&file("missing", None)).unwrap();
+    assert_eq!(config.database.namespace, "voiturerc");
+    assert_eq!(config.database.database, "voiturerc");
+    config.validate().unwrap();
+}
+
+#[test]
+fn malformed_file_names_the_file() {
+    let path = file("malformed", Some("[database\nurl = 1\n"));
+    let error = Config::load(&path).err().expect("fichier invalide accepté").to_string();
+    assert!(error.contains(&path.display().to_string()), "{}", error);
+    std::fs::remove_file(&path).unwrap();
+}
+
+#[test]
+fn database_section() {
+    let path = file(
+        "database",
+        Some("[database]\nurl = \"wss://db.local:8000\"\nnamespace = \"rc\"\npassword = \"secret\"\n"),
+    );
+    let config = Config::load(&path).unwrap();
+    std::fs::remove_file(&path).unwrap();
+
+    assert_eq!(config.database.url, "wss://db.local:8000");
+    assert_eq!(config.database.namespace, "rc");
+    assert_eq!(config.database.database, "voiturerc");
+    assert_eq!(config.database.password, "secret");
+    config.validate().unwrap();
+
+    // Le mot de passe n'apparaît jamais dans la configuration sérialisée
+    assert!(!toml::to_string(&config).unwrap().contains("secret"));
+
+    let mut invalid = config.clone();
+    invalid.database.url = "db.local:8000".to_string();
+    assert!(invalid.validate().is_err());
+    invalid.database.url = "ws://db.local:8000".to_string();
+    invalid.database.namespace.clear();
+    assert!(invalid.validate().is_err());
+}
+
+#[test]
+fn gps_port_section() {
+    let path = file("gps", Some("[sensors.gps]\nmode = \"fake\"\ndevice = \"/dev/ttyACM0\"\nbaud = 9600\n"));
+    let config = Config::load(&path).unwrap();
+    std::fs::remove_file(&path).unwrap();
+
+    let (device, baud) = config.sensors.gps.port().unwrap();
+    assert_eq!(device, PathBuf::from("/dev/ttyACM0"));
+    assert_eq!(baud, 9600);
+
+    let mut invalid = config.sensors.gps.clone();
+    invalid.baud = Some(0);
+    assert!(invalid.port().is_err());
+}
+
+#[test]
+fn control_section() {
+    let path = file("control", Some("[control]\ndead_timeout_ms = 200\nfailsafe_steer = -0.1\n"));
+    let config = Config::load(&path).unwrap();
+    std::fs::remove_file(&path).unwrap();
+
+    assert_eq!(config.control.dead_timeout(), std::time::Duration::from_millis(200));
+    assert_eq!(config.control.failsafe_steer, -0.1);
+    config.validate().unwrap();
+
+    let mut invalid = config.clone();
+    invalid.control.dead_timeout_ms = 0;
+    assert!(invalid.validate().is_err());
+    let mut invalid = config.clone();
+    invalid.control.failsafe_steer = 1.5;
+    assert!(invalid.validate().is_err());
+
+    // Commandes par MQTT: broker requis
+    let mut mqtt = config.clone();
+    mqtt.control.source = config::ControlSource::Mqtt;
+    assert!(mqtt.validate().is_err());
+    mqtt.mqtt.enabled = true;
+    mqtt.validate().unwrap();
+}
+
+#[test]
+fn analog_channels_section() {
+    let content = "[sensors.analog]\nmode = \"fake\"\n\n\
+        [[sensors.analog.channels]]\ninput = 0\nratio = 1.5\n\n\
+        [[sensors.analog.channels]]\ninput = 1\nratio = 3.0\n\n\
+        [[sensors.analog.channels]]\ninput = 3\nratio = 4.5\nenabled = false\n";
+    let path = file("analog", Some(content));
+    let config = Config::load(&path).unwrap();
+    std::fs::remove_file(&path).unwrap();
+
+    let channels = &config.sensors.analog.channels;
+    assert_eq!(channels.len(), 3);
+    assert_eq!((channels[1].input, channels[1].ratio, channels[1].enabled), (1, 3.0, true));
+    assert!(!channels[2].enabled);
+    config.validate().unwrap();
+
+    let mut invalid = config.clone();
+    invalid.sensors.analog.channels[2].input = 0;
+    assert!(invalid.validate().is_err());
+    let mut invalid = config.clone();
+    invalid.sensors.analog.channels[0].ratio = 0.0;
+    assert!(invalid.validate().is_err());
+}
+
+#[test]
+fn current_channel_and_capacity() {
+    let content = "[sensors.analog]\nmode = \"fake\"\n\n\
+        [[sensors.analog.channels]]\ninput = 0\nratio = 1.5\n\n\
+        [sensors.analog.current]\ninput = 3\nzero_v = 0.5\nvolts_per_a = 0.04\n\n\
+        [capacity]\nenabled = true\ncapacity_mah = 2200.0\n";
+    let path = file("current", Some(content));
+    let config = Config::load(&path).unwrap();
+    std::fs::remove_file(&path).unwrap();
+
+    let current = config.sensors.analog.current.as_ref().unwrap();
+    assert_eq!((current.input, current.zero_v, current.volts_per_a), (3, 0.5, 0.04));
+    assert_eq!(config.capacity.capacity_mah, 2200.0);
+    config.validate().unwrap();
+
+    // Entrée déjà utilisée par une prise d'équilibrage
+    let mut invalid = config.clone();
+    invalid.sensors.analog.current.as_mut().unwrap().input = 0;
+    assert!(invalid.validate().is_err());
+    let mut invalid = config.clone();
+    invalid.sensors.analog.current.as_mut().unwrap().volts_per_a = 0.0;
+    assert!(invalid.validate().is_err());
+    let mut invalid = config.clone();
+    invalid.capacity.capacity_mah = 0.0;
+    assert!(invalid.validate().is_err());
+}
+
+#[test]
+fn power_monitor_section() {
+    let content = "[sensors.power]\nmode = \"fake\"\naddress = 0x44\nshunt_ohm = 0.01\nmax_current_a = 20.0\n";
+    let path = file("power", Some(content));
+    let config = Config::load(&path).unwrap();
+    std::fs::remove_file(&path).unwrap();
+
+    let power = &config.sensors.power;
+    assert_eq!((power.address, power.shunt_ohm, power.max_current_a), (0x44, 0.01, 20.0));
+    config.validate().unwrap();
+
+    // Désactivé par défaut
+    assert!(Config::default().sensors.power.mode == config::SensorMode::Disabled);
+
+    let mut invalid = config.clone();
+    invalid.sensors.power.address = 0x50;
+    assert!(invalid.validate().is_err());
+    // 40 A dans 0.01 ohm: 0.4 V, au-delà de la plage du shunt
+    let mut invalid = config.clone();
+    invalid.sensors.power.max_current_a = 40.0;
+    assert!(invalid.validate().is_err());
+}
+
+#[test]
+fn imu_rate_and_ranges_section() {
+    let content = "[sensors.imu]\nmode = \"fake\"\nrate_hz = 100\naccel_range_g = 8\ngyro_range_dps = 1000\ndlpf_hz = 42\n";
+    let path = file("imu", Some(content));
+    let config = Config::load(&path).unwrap();
+    std::fs::remove_file(&path).unwrap();
+
+    let imu = &config.sensors.imu;
+    assert_eq!((imu.rate_hz, imu.accel_range_g, imu.gyro_range_dps, imu.dlpf_hz), (100, 8, 1000, 42));
+    assert_eq!(imu.period(), Duration::from_millis(10));
+
+    // Par défaut: 20 Hz, soit l'intervalle historique de 50 ms
+    assert_eq!(Config::default().sensors.imu.period(), Duration::from_millis(50));
+}
+
+#[test]
+fn magnetometer_calibration_section() {
+    let content = "[sensors.mag]\ndeclination_deg = -1.5\ncalibration_min_samples = 100\n\n[sensors.mag.calibration]\nhard_iron = [10.0, -20.0, 5.0]\nsoft_iron = [[2.0, 0.0, 0.0], [0.0, 1.0, 0.0], [0.0, 0.0, 0.5]]\n";
+    let path = file("mag", Some(content));
+    let config = Config::load(&path).unwrap();
+    std::fs::remove_file(&path).unwrap();
+    config.validate().unwrap();
+
+    let mag = &config.sensors.mag;
+    assert_eq!((mag.declination_deg, mag.calibration_min_samples, mag.calibration_timeout_s), (-1.5, 100, 120));
+    assert_eq!(mag.calibration.apply((20, 0, 9)), (20.0, 20.0, 2.0));
+
+    // Sans calibration: mesures inchangées
+    assert_eq!(Config::default().sensors.mag.calibration.apply((-120, 45, 300)), (-120.0, 45.0, 300.0));
+
+    let mut invalid = config.clone();
+    invalid.sensors.mag.calibration_min_samples = 5;
+    assert!(invalid.validate().is_err());
+}
+
+#[test]
+fn barometer_section() {
+    let content = "[sensors.baro]\nmode = \"fake\"\naddress = 0x77\nzero_samples = 20\n";
+    let path = file("baro", Some(content));
+    let config = Config::load(&path).unwrap();
+    std::fs::remove_file(&path).unwrap();
+
+    let baro = &config.sensors.baro;
+    assert_eq!((baro.address, baro.zero_samples), (0x77, 20));
+    config.validate().unwrap();
+
+    // Désactivé par défaut
+    assert!(Config::default().sensors.baro.mode == config::SensorMode::Disabled);
+
+    let mut invalid = config.clone();
+    invalid.sensors.baro.address = 0x40;
+    assert!(invalid.validate().is_err());
+    let mut invalid = config.clone();
+    invalid.sensors.baro.zero_samples = 0;
+    assert!(invalid.validate().is_err());
+}
+
+#[test]
+fn wheel_encoder_section() {
+    let content = "[sensors.encoder]\nmode = \"fake\"\npin = 22\npulses_per_rev = 4\nwheel_circumference_m = 0.2\n";
+    let path = file("encoder", Some(content));
+    let config = Config::load(&path).unwrap();
+    std::fs::remove_file(&path).unwrap();
+
+    let encoder = &config.sensors.encoder;
+    assert_eq!((encoder.pin, encoder.pulses_per_rev, encoder.interval_ms), (22, 4, 100));
+    assert!((encoder.meters_per_pulse() - 0.05).abs() < 1e-12);
+    config.validate().unwrap();
+
+    // Désactivé par défaut
+    assert!(Config::default().sensors.encoder.mode == config::SensorMode::Disabled);
+
+    let mut invalid = config.clone();
+    invalid.sensors.encoder.pin = 28;
+    assert!(invalid.validate().is_err());
+    let mut invalid = config.clone();
+    invalid.sensors.encoder.pulses_per_rev = 0;
+    assert!(invalid.validate().is_err());
+    let mut invalid = config.clone();
+    invalid.sensors.encoder.wheel_circumference_m = 0.0;
+    assert!(invalid.validate().is_err());
+}
+
+#[test]
+fn range_section_and_obstacle_rule() {
+    let content = "[sensors.range]\nmode = \"fake\"\naddress = 0x30\n\n[obstacle]\nenabled = true\nstop_mm = 500\n";
+    let path = file("range", Some(content));
+    let config = Config::load(&path).unwrap();
+    std::fs::remove_file(&path).unwrap();
+
+    assert_eq!((config.sensors.range.address, config.sensors.range.timeout_ms), (0x30, 500));
+    assert_eq!((config.obstacle.stop_mm, config.obstacle.clear_mm), (500, 400));
+    // Distance de reprise sous la distance d'arrêt
+    assert!(config.validate().is_err());
+
+    let mut valid = config.clone();
+    valid.obstacle.clear_mm = 600;
+    valid.validate().unwrap();
+
+    // Règle et capteur désactivés par défaut
+    let defaults = Config::default();
+    assert!(!defaults.obstacle.enabled);
+    assert!(defaults.sensors.range.mode == config::SensorMode::Disabled);
+
+    // Règle activée sans capteur de distance
+    let mut invalid = valid.clone();
+    invalid.sensors.range.mode = config::SensorMode::Disabled;
+    assert!(invalid.validate().is_err());
+    let mut invalid = valid.clone();
+    invalid.sensors.range.timeout_ms = 0;
+    assert!(invalid.validate().is_err());
+}
+
+#[test]
+fn record_replay_section() {
+    let content = "[record]\nreplay_speed = 4.0\nreplay_loop = true\n";
+    let path = file("record", Some(content));
+    let config = Config::load(&path).unwrap();
+    assert_eq!(config.record.replay_speed, 4.0);
+    assert!(config.record.replay_loop);
+    config.validate().unwrap();
+
+    // Rejeu à vitesse réelle, arrêt à la fin du fichier par défaut
+    let defaults = Config::default();
+    assert_eq!(defaults.record.replay_speed, 1.0);
+    assert!(!defaults.record.replay_loop);
+
+    let mut invalid = config.clone();
+    invalid.record.replay_speed = 0.0;
+    assert!(invalid.validate().is_err());
+}
+
+#[test]
+fn cells_from_balance_taps() {
+    use sensors::reader::AnalogData;
+
+    let cells = AnalogData::cells_from_taps([Some(4.1), Some(8.15), Some(12.2), None]);
+    let expected = [4.1, 4.05, 4.05];
+    for (cell, expected) in cells.iter().zip(expected) {
+        assert!((cell.unwrap() - expected).abs() < 1e-4, "{:?}", cells);
+    }
+    assert_eq!(cells[3], None);
+
+    // Aucune prise mesurée
+    assert_eq!(AnalogData::cells_from_taps([None; 4]), [None; 4]);
+}
@@ -0,0 +1,296 @@
+// Commandes de la base (control:realtime): actions du live prises en compte et arrêt d'urgence,
+// appliquées par les actionneurs simulés. A lancer avec:
+//   cargo test --no-default-features --features fake-sensors,fake-actuators --test control_stream
+#![cfg(not(feature = "real-sensors"))]
+
+use voiturerc::{actuators, clock, config, control, selftest, sensors};
+
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use chrono::Utc;
+use surrealdb::Action;
+use tokio::sync::watch;
+use tokio::time::{sleep, timeout};
+use tokio_util::sync::CancellationToken;
+
+use actuators::arbiter::{Arbiter, Commands, ControlState};
+use actuators::ramp;
+use actuators::{Control, ControlRecord};
+use clock::Clock;
+use config::{Config, LinkLossPolicy};
+use selftest::SelfTest;
+use sensors::sim::Simulation;
+
+const DEAD_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Boucle de contrôle simulée, comme avec la feature fake-actuators
+fn fake_control(config: Config) -> (Commands, watch::Receiver<ControlState>, CancellationToken) {
+    fake_control_with(&Clock::start(), config)
+}
+
+/// Boucle de contrôle simulée cadencée par `clock`
+fn fake_control_with(
+    clock: &Clock,
+    config: Config,
+) -> (Commands, watch::Receiver<ControlState>, CancellationToken) {
+    let (commands, arbiter) = Arbiter::new(clock, DEAD_TIMEOUT, LinkLossPolicy::Stop);
+    let state = commands.state();
+    let token = CancellationToken::new();
+    tokio::spawn(control::fake(
+        arbiter,
+        config.control.clone(),
+        Simulation::shared(&config.simulation),
+        SelfTest::new(),
+        token.clone(),
+    ));
+    (commands, state, token)
+}
+
+/// Configuration sans rampe: sortie égale à la commande
+fn unramped() -> Config {
+    let mut config = Config::default();
+    config.control.accel_per_s = 0.0;
+    config.control.decel_per_s = 0.0;
+    config
+}
+
+fn record(json: &str) -> ControlRecord {
+    serde_json::from_str(json).unwrap()
+}
+
+/// Sortie des actionneurs une fois la commande traitée par la boucle de contrôle
+async fn output(state: &watch::Receiver<ControlState>) -> (f64, f64) {
+    sleep(Duration::from_millis(50)).await;
+    let output = state.borrow().output;
+    (output.steer, output.speed)
+}
+
+/// Vitesse appliquée à l'instant donné par la boucle de contrôle (arrondie au millième)
+async fn speed_at(state: &watch::Receiver<ControlState>, at: Instant) -> f64 {
+    let mut state = state.clone();
+    let applied = timeout(Duration::from_secs(2), state.wait_for(|state| state.applied == Some(at)))
+        .await
+        .unwrap_or_else(|_| panic!("aucune sortie appliquée à {:?}", at))
+        .unwrap();
+    (applied.output.speed * 1000.0).round() / 1000.0
+}
+
+#[test]
+fn record_shape() {
+    let plain = record(r#"{"steer": 0.2, "speed": -0.5}"#);
+    assert_eq!((plain.steer, plain.speed, plain.estop), (0.2, -0.5, None));
+
+    let estop = record(r#"{"steer": 0.0, "speed": 0.0, "estop": true, "id": "control:realtime"}"#);
+    assert_eq!(estop.estop, Some(true));
+}
+
+#[tokio::test]
+async fn create_and_update_are_applied() {
+    let (commands, state, token) = fake_control(unramped());
+
+    // Premier enregistrement écrit par l'application: création
+    control::apply(&commands, Action::Create, &record(r#"{"steer": 0.1, "speed": 0.5}"#)).unwrap();
+    assert_eq!(output(&state).await, (0.1, 0.5));
+
+    control::apply(&commands, Action::Update, &record(r#"{"steer": -0.3, "speed": 0.2}"#)).unwrap();
+    assert_eq!(output(&state).await, (-0.3, 0.2));
+
+    // Suppression: dernière commande conservée jusqu'à l'homme mort
+    control::apply(&commands, Action::Delete, &record(r#"{"steer": 0.0, "speed": 1.0}"#)).unwrap();
+    assert_eq!(output(&state).await, (-0.3, 0.2));
+
+    token.cancel();
+}
+
+#[tokio::test]
+async fn estop_holds_motor_until_cleared() {
+    let (commands, state, token) = fake_control(unramped());
+
+    control::apply(&commands, Action::Update, &record(r#"{"steer": 0.0, "speed": 0.6}"#)).unwrap();
+    assert_eq!(output(&state).await, (0.0, 0.6));
+
+    // Arrêt d'urgence: moteur coupé, même avec une vitesse dans l'enregistrement
+    let stop = record(r#"{"steer": 0.0, "speed": 0.6, "estop": true}"#);
+    control::apply(&commands, Action::Update, &stop).unwrap();
+    assert_eq!(output(&state).await, (0.0, 0.0));
+    assert_eq!(state.borrow().limit.as_ref().map(|limit| limit.cause.as_str()), Some("estop"));
+
+    // Commandes suivantes sans le champ: la vitesse reste coupée, la direction est commandée
+    control::apply(&commands, Action::Update, &record(r#"{"steer": 0.4, "speed": 1.0}"#)).unwrap();
+    assert_eq!(output(&state).await, (0.4, 0.0));
+
+    let clear = record(r#"{"steer": 0.4, "speed": 0.3, "estop": false}"#);
+    control::apply(&commands, Action::Update, &clear).unwrap();
+    assert_eq!(output(&state).await, (0.4, 0.3));
+    assert!(state.borrow().limit.is_none());
+
+    token.cancel();
+}
+
+#[tokio::test]
+async fn speed_ramps_but_estop_cuts_instantly() {
+    let mut config = Config::default();
+    config.control.accel_per_s = 2.0;
+    config.control.decel_per_s = 4.0;
+    let (commands, state, token) = fake_control(config);
+
+    // Pleine vitesse atteinte en 0,5 s
+    control::apply(&commands, Action::Update, &record(r#"{"steer": 0.0, "speed": 1.0}"#)).unwrap();
+    let (_, speed) = output(&state).await;
+    assert!(speed > 0.0 && speed < 0.5, "{}", speed);
+    sleep(Duration::from_millis(600)).await;
+    assert_eq!(state.borrow().output.speed, 1.0);
+    assert_eq!(state.borrow().input.speed, 1.0);
+
+    // Arrêt d'urgence: sans rampe
+    control::apply(&commands, Action::Update, &record(r#"{"steer": 0.0, "speed": 1.0, "estop": true}"#)).unwrap();
+    assert_eq!(output(&state).await, (0.0, 0.0));
+
+    token.cancel();
+}
+
+#[tokio::test]
+async fn ramp_follows_virtual_clock() {
+    let mut config = Config::default();
+    config.control.accel_per_s = 2.0;
+    config.control.decel_per_s = 4.0;
+    let clock = Clock::virtual_at(Utc::now());
+    let (commands, state, token) = fake_control_with(&clock, config);
+
+    control::apply(&commands, Action::Update, &record(r#"{"steer": 0.0, "speed": 1.0}"#)).unwrap();
+    assert_eq!(speed_at(&state, clock.now()).await, 0.0);
+
+    // Une mise à jour par pas de rampe: +0,04 (2/s) jusqu'à la pleine vitesse, en 0,5 s
+    let mut profile = Vec::new();
+    for _ in 0..25 {
+        clock.set(clock.elapsed() + ramp::TICK);
+        profile.push(speed_at(&state, clock.now()).await);
+    }
+    let expected: Vec<f64> = (1..=25).map(|n| (40.0 * n as f64).round() / 1000.0).collect();
+    assert_eq!(profile, expected);
+
+    // Consigne atteinte: plus de mise à jour, la commande suivante arrive entre deux pas
+    clock.set(clock.elapsed() + ramp::TICK / 2);
+
+    // Décélération: -0,08 (4/s) par pas jusqu'au neutre
+    control::apply(&commands, Action::Update, &record(r#"{"steer": 0.0, "speed": 0.0}"#)).unwrap();
+    assert_eq!(speed_at(&state, clock.now()).await, 1.0);
+    let mut profile = Vec::new();
+    for _ in 0..13 {
+        clock.set(clock.elapsed() + ramp::TICK);
+        profile.push(speed_at(&state, clock.now()).await);
+    }
+    assert_eq!(
+        profile,
+        vec![0.92, 0.84, 0.76, 0.68, 0.6, 0.52, 0.44, 0.36, 0.28, 0.2, 0.12, 0.04, 0.0]
+    );
+
+    // Au neutre, le temps qui passe ne change plus la sortie
+    let settled = clock.now();
+    clock.set(clock.elapsed() + ramp::TICK * 5);
+    sleep(Duration::from_millis(50)).await;
+    assert_eq!(state.borrow().applied, Some(settled));
+    assert_eq!(state.borrow().output.speed, 0.0);
+
+    token.cancel();
+}
+
+#[tokio::test]
+async fn lower_limit_caps_ramp_target() {
+    let mut config = Config::default();
+    config.control.accel_per_s = 2.0;
+    config.control.decel_per_s = 4.0;
+    let clock = Clock::virtual_at(Utc::now());
+    let (commands, state, token) = fake_control_with(&clock, config);
+
+    control::apply(&commands, Action::Update, &record(r#"{"steer": 0.0, "speed": 1.0}"#)).unwrap();
+    assert_eq!(speed_at(&state, clock.now()).await, 0.0);
+    for _ in 0..3 {
+        clock.set(clock.elapsed() + ramp::TICK);
+        speed_at(&state, clock.now()).await;
+    }
+
+    // Limite plus basse que la commande mais au-dessus de la sortie (0,12): la rampe
+    // s'arrête à la limite au lieu de rejoindre la commande
+    clock.set(clock.elapsed() + ramp::TICK / 2);
+    commands.limit("test", Some(0.3));
+    assert_eq!(speed_at(&state, clock.now()).await, 0.12);
+    let mut profile = Vec::new();
+    for _ in 0..4 {
+        clock.set(clock.elapsed() + ramp::TICK);
+        profile.push(speed_at(&state, clock.now()).await);
+    }
+    assert_eq!(profile, vec![0.18, 0.22, 0.26, 0.3]);
+
+    // Limite atteinte: plus de mise à jour
+    clock.set(clock.elapsed() + ramp::TICK * 5);
+    sleep(Duration::from_millis(50)).await;
+    assert_eq!(state.borrow().output.speed, 0.3);
+    assert_eq!(state.borrow().input.speed, 1.0);
+
+    token.cancel();
+}
+
+/// Actionneurs enregistrant la vitesse appliquée. Comme l'ESC au passage en marche arrière, deux
+/// mises à jour périodiques sont demandées après une commande de marche arrière.
+#[derive(Default)]
+struct Recorder {
+    speeds: Arc<Mutex<Vec<f64>>>,
+    pending: usize,
+}
+
+impl control::Actuators for Recorder {
+    fn steer(&mut self, _control: &Control) -> anyhow::Result<()> {
+        Ok(())
+    }
+
+    fn drive(&mut self, output: Control, _now: Instant) -> anyhow::Result<()> {
+        let mut speeds = self.speeds.lock().unwrap();
+        self.pending = match speeds.last() {
+            Some(last) if *last < 0.0 => self.pending.saturating_sub(1),
+            _ if output.speed < 0.0 => 2,
+            _ => 0,
+        };
+        speeds.push(output.speed);
+        Ok(())
+    }
+
+    fn failsafe(&mut self, _failsafe: Control, _now: Instant) {}
+
+    fn pending(&self) -> bool {
+        self.pending > 0
+    }
+
+    fn stop(&mut self) {}
+}
+
+#[tokio::test]
+async fn pending_actuators_updated_every_tick() {
+    let clock = Clock::virtual_at(Utc::now());
+    let (commands, arbiter) = Arbiter::new(&clock, DEAD_TIMEOUT, LinkLossPolicy::Stop);
+    let state = commands.state();
+    let token = CancellationToken::new();
+    let mut actuators = Recorder::default();
+    let speeds = actuators.speeds.clone();
+    tokio::spawn({
+        let config = unramped().control;
+        let token = token.clone();
+        async move { control::run(arbiter, &config, &mut actuators, token).await }
+    });
+
+    // Consigne atteinte sans rampe, sorties répétées tant que les actionneurs le demandent: au
+    // pas en attente (même instant que la commande) puis au pas suivant
+    control::apply(&commands, Action::Update, &record(r#"{"steer": 0.0, "speed": -0.5}"#)).unwrap();
+    assert_eq!(speed_at(&state, clock.now()).await, -0.5);
+    clock.set(clock.elapsed() + ramp::TICK);
+    assert_eq!(speed_at(&state, clock.now()).await, -0.5);
+
+    let settled = clock.now();
+    clock.set(clock.elapsed() + ramp::TICK * 5);
+    sleep(Duration::from_millis(50)).await;
+    assert_eq!(state.borrow().applied, Some(settled));
+    assert_eq!(*speeds.lock().unwrap(), vec![-0.5, -0.5, -0.5]);
+
+    token.cancel();
+}
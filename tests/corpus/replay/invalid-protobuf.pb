@@ -0,0 +1 @@
+RCPB
\ No newline at end of file
@@ -0,0 +1,2 @@
+RCPB2
+
\ No newline at end of file
@@ -0,0 +1,80 @@
+// En-têtes et lignes CSV des enregistrements GPS et IMU
+#![cfg(not(feature = "real-sensors"))]
+
+mod common;
+
+use voiturerc::csv::table;
+
+use std::fs;
+use std::path::PathBuf;
+
+use common::{gps, imu};
+use table::Table;
+
+/// Dossier temporaire propre au test
+fn directory(name: &str) -> PathBuf {
+    let directory = std::env::temp_dir().join(format!("rc-telemetrie-csv-{}-{}", name, std::process::id()));
+    let _ = fs::remove_dir_all(&directory);
+    fs::create_dir_all(&directory).unwrap();
+    directory
+}
+
+#[test]
+fn gps_header_and_row() {
+    let directory = directory("gps");
+    let mut table = Table::new(&directory, "gps", 1024 * 1024);
+    table.write(&gps()).unwrap();
+    table.flush().unwrap();
+
+    assert_eq!(
+        fs::read_to_string(directory.join("gps.csv")).unwrap(),
+        "stamp.mono_us,stamp.utc,speed_kmh,latitude,longitude,satellites,fix,heading,time,valid\n\
+         1500000,2024-06-01T12:00:00Z,25.5,46.52,6.632,9,true,90.0,,false\n"
+    );
+}
+
+#[test]
+fn imu_header_and_row() {
+    let directory = directory("imu");
+    let mut table = Table::new(&directory, "imu", 1024 * 1024);
+    table.write(&imu()).unwrap();
+    table.flush().unwrap();
+
+    assert_eq!(
+        fs::read_to_string(directory.join("imu.csv")).unwrap(),
+        "stamp.mono_us,stamp.utc,angles.0,angles.1,angles.2,temp,accel.0,accel.1,accel.2,gyro.0,gyro.1,gyro.2\n\
+         1500000,2024-06-01T12:00:00Z,1.5,-2.25,180.0,31.0,0.25,-0.125,1.0,0.5,-1.25,12.0\n"
+    );
+}
+
+#[test]
+fn escaping() {
+    assert_eq!(table::escape("simple"), "simple");
+    assert_eq!(table::escape("a,b"), "\"a,b\"");
+    assert_eq!(table::escape("dit \"bonjour\""), "\"dit \"\"bonjour\"\"\"");
+    assert_eq!(table::escape("deux\nlignes"), "\"deux\nlignes\"");
+}
+
+#[test]
+fn rotation_by_size() {
+    let directory = directory("rotation");
+    let mut table = Table::new(&directory, "imu", 200);
+    for _ in 0..10 {
+        table.write(&imu()).unwrap();
+    }
+    table.flush().unwrap();
+
+    let produced = table.produced();
+    assert!(produced.len() > 1);
+    assert_eq!(produced[0].file, "imu.csv");
+    assert_eq!(produced[1].file, "imu.1.csv");
+    assert_eq!(produced.iter().map(|file| file.rows).sum::<u64>(), 10);
+
+    // Chaque fichier commence par l'en-tête et reste sous la taille maximale
+    for file in produced {
+        let content = fs::read_to_string(directory.join(&file.file)).unwrap();
+        assert!(content.starts_with("stamp.mono_us,"));
+        assert!(content.len() <= 200);
+        assert_eq!(content.len() as u64, file.bytes);
+    }
+}
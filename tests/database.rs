@@ -0,0 +1,302 @@
+// Ecritures de Database relues dans une base SurrealDB embarquée (mem://), sans service externe:
+// champs de chaque enregistrement, historique par exécution et flux live des commandes.
+#![cfg(not(feature = "real-sensors"))]
+
+use voiturerc::{clock, config, database, record, sensors};
+
+use std::collections::BTreeMap;
+use std::time::Duration;
+
+use chrono::{TimeZone, Utc};
+use futures::StreamExt;
+use serde::Deserialize;
+use surrealdb::engine::any::Any;
+use surrealdb::Surreal;
+use tokio::time::timeout;
+
+use clock::Stamp;
+use config::Config;
+use database::Database;
+use record::{AccessTechnology, ModemStatus, Operator, Registration};
+use sensors::reader::{AnalogData, GpsData, ImuData, MagData, SensorStatus, SensorsStatus};
+
+/// Base embarquée, partagée par l'application et le test
+async fn open() -> (Database, Surreal<Any>) {
+    let config = Config::default();
+    let client = surrealdb::engine::any::connect("mem://").await.unwrap();
+    let db = Database::open(client.clone(), &config.database, false).await.unwrap();
+    client.use_ns(&config.database.namespace).use_db(&config.database.database).await.unwrap();
+    (db, client)
+}
+
+/// Résultat d'une requête, None: enregistrement absent
+async fn select<T: for<'de> Deserialize<'de>>(client: &Surreal<Any>, query: &str) -> Option<T> {
+    let mut result = client.query(query).await.unwrap();
+    result.take::<Option<T>>(0).unwrap()
+}
+
+fn stamp(mono_us: u64) -> Stamp {
+    Stamp {
+        mono_us,
+        utc: Utc.with_ymd_and_hms(2024, 6, 1, 12, 0, 0).unwrap(),
+    }
+}
+
+#[derive(Deserialize)]
+struct Levels {
+    battery: f32,
+    cells: Option<Vec<f32>>,
+    current: Option<f32>,
+    stamp: Stamp,
+}
+
+#[tokio::test]
+async fn analog_fields() {
+    let (db, client) = open().await;
+
+    db.send_analog(AnalogData {
+        stamp: stamp(1_000),
+        battery: 11.1,
+        cells: [Some(3.7), Some(7.4), None, None],
+        current: Some(12.5),
+    })
+    .await
+    .unwrap();
+    let levels: Levels = select(&client, "SELECT battery, cells, current, stamp FROM ONLY levels:realtime;")
+        .await
+        .unwrap();
+    assert_eq!(levels.battery, 11.1);
+    assert_eq!(levels.cells, Some(vec![3.7, 7.4]));
+    assert_eq!(levels.current, Some(12.5));
+    assert_eq!(levels.stamp, stamp(1_000));
+
+    // Sans prises d'équilibrage ni courant: champs absents
+    db.send_analog(AnalogData {
+        stamp: stamp(2_000),
+        battery: 11.0,
+        ..AnalogData::default()
+    })
+    .await
+    .unwrap();
+    let levels: Levels = select(&client, "SELECT battery, cells, current, stamp FROM ONLY levels:realtime;")
+        .await
+        .unwrap();
+    assert_eq!(levels.battery, 11.0);
+    assert_eq!(levels.cells, None);
+    assert_eq!(levels.current, None);
+}
+
+#[derive(Deserialize)]
+struct Nav {
+    latitude: f64,
+    longitude: f64,
+    satellite_count: u8,
+    fix: bool,
+    speed: f64,
+    gps_heading: f64,
+    gps_stamp: Stamp,
+    angles: (f32, f32, f32),
+    accel: (f32, f32, f32),
+    imu_stamp: Stamp,
+    mag_raw: (i16, i16, i16),
+    mag_true_heading: f32,
+    mag_stamp: Stamp,
+}
+
+#[tokio::test]
+async fn gps_imu_and_mag_share_nav() {
+    let (db, client) = open().await;
+
+    db.send_gps(GpsData {
+        stamp: stamp(1_000),
+        speed_kmh: 36.0,
+        latitude: 46.5196543,
+        longitude: 6.6322731,
+        satellites: 9,
+        fix: true,
+        heading: 90.0,
+        ..GpsData::default()
+    })
+    .await
+    .unwrap();
+    db.send_imu(ImuData {
+        stamp: stamp(2_000),
+        angles: (-1.5, 2.5, 180.0),
+        temp: 31.0,
+        accel: (0.1, 0.0, 1.0),
+        gyro: (0.0, 0.0, 12.0),
+    })
+    .await
+    .unwrap();
+    db.send_mag(MagData {
+        stamp: stamp(3_000),
+        raw: (120, -340, -270),
+        heading: 88.0,
+        true_heading: 90.5,
+        declination: 2.5,
+        ..MagData::default()
+    })
+    .await
+    .unwrap();
+
+    // Les trois écritures mettent à jour le même enregistrement sans écraser les autres champs
+    let nav: Nav = select(&client, "SELECT * FROM ONLY nav:realtime;").await.unwrap();
+    assert_eq!((nav.latitude, nav.longitude), (46.5196543, 6.6322731));
+    assert_eq!((nav.satellite_count, nav.fix), (9, true));
+    assert_eq!((nav.speed, nav.gps_heading), (36.0, 90.0));
+    assert_eq!(nav.gps_stamp, stamp(1_000));
+    assert_eq!(nav.angles, (-1.5, 2.5, 180.0));
+    assert_eq!(nav.accel, (0.1, 0.0, 1.0));
+    assert_eq!(nav.imu_stamp, stamp(2_000));
+    assert_eq!(nav.mag_raw, (120, -340, -270));
+    assert_eq!(nav.mag_true_heading, 90.5);
+    assert_eq!(nav.mag_stamp, stamp(3_000));
+}
+
+#[tokio::test]
+async fn modem_status() {
+    let (db, client) = open().await;
+
+    let status = ModemStatus {
+        quality: 64,
+        technology: Some(AccessTechnology::Lte),
+        registration: Some(Registration::Home),
+        operator: Operator::new("Swisscom"),
+        rsrp: Some(-101.0),
+        ..ModemStatus::default()
+    };
+    db.send_modem(status, stamp(1_000)).await.unwrap();
+
+    let quality: Option<u32> = select(&client, "SELECT VALUE quality FROM ONLY modem:realtime;").await;
+    assert_eq!(quality, Some(64));
+    for (field, expected) in [("technology", "lte"), ("registration", "home"), ("operator", "Swisscom")] {
+        let query = format!("SELECT VALUE {} FROM ONLY modem:realtime;", field);
+        let value: Option<String> = select(&client, &query).await;
+        assert_eq!(value.as_deref(), Some(expected));
+    }
+    let rsrp: Option<f64> = select(&client, "SELECT VALUE rsrp FROM ONLY modem:realtime;").await;
+    assert_eq!(rsrp, Some(-101.0));
+    // Mesure non fournie par le modem: aucune valeur plutôt que 0
+    let sinr: Option<f64> = select(&client, "SELECT VALUE sinr FROM ONLY modem:realtime;").await;
+    assert_eq!(sinr, None);
+}
+
+#[tokio::test]
+async fn sensors_status_per_i2c_bus() {
+    let (db, client) = open().await;
+
+    let mut status = SensorsStatus::default();
+    status.i2c.insert(
+        1,
+        SensorStatus {
+            available: true,
+            attempts: 1,
+            ..SensorStatus::default()
+        },
+    );
+    status.i2c.insert(
+        3,
+        SensorStatus {
+            error: Some("No such file or directory".into()),
+            attempts: 2,
+            ..SensorStatus::default()
+        },
+    );
+    db.send_status(status, stamp(1_000)).await.unwrap();
+
+    let buses: BTreeMap<String, SensorStatus> =
+        select(&client, "SELECT VALUE i2c FROM ONLY status:sensors;").await.unwrap();
+    assert_eq!(buses.keys().collect::<Vec<_>>(), ["1", "3"]);
+    assert!(buses["1"].available);
+    assert_eq!(buses["1"].error, None);
+    assert!(!buses["3"].available);
+    assert_eq!(buses["3"].attempts, 2);
+}
+
+#[tokio::test]
+async fn live_control_yields_updates() {
+    let (db, client) = open().await;
+    let mut stream = db.live_control().await.unwrap();
+
+    client
+        .query("UPDATE control:realtime SET steer = -0.25, speed = 0.5;")
+        .await
+        .unwrap()
+        .check()
+        .unwrap();
+
+    let notification = timeout(Duration::from_secs(5), stream.next())
+        .await
+        .expect("aucune notification")
+        .expect("flux terminé")
+        .unwrap();
+    let record = notification.data;
+    assert_eq!((record.steer, record.speed), (-0.25, 0.5));
+    assert_eq!(record.estop, None);
+}
+
+#[tokio::test]
+async fn run_id_on_samples_and_events() {
+    let (db, client) = open().await;
+    let db = db.with_run("run-1717243200");
+
+    db.send_imu(ImuData {
+        stamp: stamp(1_000),
+        ..ImuData::default()
+    })
+    .await
+    .unwrap();
+    db.send_event("warning", "test", stamp(2_000)).await.unwrap();
+
+    let run: Option<String> = select(&client, "SELECT VALUE run FROM ONLY nav:realtime;").await;
+    assert_eq!(run.as_deref(), Some("run-1717243200"));
+    let runs: Vec<String> = client
+        .query("SELECT VALUE run FROM event;")
+        .await
+        .unwrap()
+        .take(0)
+        .unwrap();
+    assert_eq!(runs, vec!["run-1717243200".to_string()]);
+}
+
+/// Echantillons IMU d'une exécution dans l'historique: temps monotone et température
+async fn imu_history(client: &Surreal<Any>, run: &str) -> Vec<(u64, f64)> {
+    let mut result = client
+        .query("SELECT stamp.mono_us AS mono_us, temp FROM imu_history WHERE run = $run ORDER BY mono_us;")
+        .bind(("run", run.to_string()))
+        .await
+        .unwrap();
+    let samples: Vec<serde_json::Value> = result.take(0).unwrap();
+    samples
+        .iter()
+        .map(|sample| (sample["mono_us"].as_u64().unwrap(), sample["temp"].as_f64().unwrap()))
+        .collect()
+}
+
+#[tokio::test]
+async fn history_grouped_by_run() {
+    let (db, client) = open().await;
+    let config = Config::default();
+    let first = db.with_run("run-1");
+    let second = Database::open(client.clone(), &config.database, false).await.unwrap().with_run("run-2");
+
+    // Temps monotone repris de zéro à chaque exécution: aucun échantillon remplacé
+    for mono_us in [1_000, 2_000, 3_000] {
+        let data = ImuData {
+            stamp: stamp(mono_us),
+            ..ImuData::default()
+        };
+        first.send_imu(data).await.unwrap();
+    }
+    for mono_us in [1_000, 2_000] {
+        let data = ImuData {
+            stamp: stamp(mono_us),
+            temp: 30.0,
+            ..ImuData::default()
+        };
+        second.send_imu(data).await.unwrap();
+    }
+
+    assert_eq!(imu_history(&client, "run-1").await, vec![(1_000, 0.0), (2_000, 0.0), (3_000, 0.0)]);
+    assert_eq!(imu_history(&client, "run-2").await, vec![(1_000, 30.0), (2_000, 30.0)]);
+}
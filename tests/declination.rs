@@ -0,0 +1,131 @@
+// Déclinaison magnétique: World Magnetic Model sur des coefficients synthétiques, valeur fixe sans
+// modèle ni position
+#![cfg(not(feature = "real-sensors"))]
+
+use voiturerc::sensors;
+
+use chrono::{TimeZone, Utc};
+
+use sensors::mag::declination::{self, Declination, Model};
+
+/// Rayon de référence du modèle et rayon équatorial WGS84 (km)
+const REFERENCE_RADIUS: f64 = 6371.2;
+const EQUATOR_RADIUS: f64 = 6378.137;
+
+/// Fichier de coefficients au format WMM.COF
+fn cof(epoch: f64, lines: &[&str]) -> String {
+    let mut content = format!("    {:.1}            TEST     01/01/2025\n", epoch);
+    for line in lines {
+        content.push_str(line);
+        content.push('\n');
+    }
+    content.push_str(&"9".repeat(48));
+    content.push('\n');
+    content
+}
+
+fn model(lines: &[&str]) -> Model {
+    Model::parse(&cof(2025.0, lines)).unwrap()
+}
+
+#[test]
+fn axial_dipole_points_to_true_north() {
+    let model = model(&["  1  0  -30000.0       0.0        0.0        0.0"]);
+
+    for (latitude, longitude) in [(0.0, 0.0), (46.5, 6.6), (-33.9, 151.2), (70.0, -120.0)] {
+        let declination = model.declination(latitude, longitude, 0.0, 2025.0);
+        assert!(declination.abs() < 1e-9, "{} à {}, {}", declination, latitude, longitude);
+    }
+}
+
+#[test]
+fn tilted_dipole_at_equator() {
+    let model = model(&[
+        "  1  0  -30000.0       0.0        0.0        0.0",
+        "  1  1   -2000.0    5000.0        0.0        0.0",
+    ]);
+
+    // A l'équateur: nord = -g10, est = g11 sin(λ) - h11 cos(λ)
+    let expected = |longitude: f64| {
+        let lambda = f64::to_radians(longitude);
+        (-2000.0 * lambda.sin() - 5000.0 * lambda.cos()).atan2(30000.0).to_degrees()
+    };
+    for longitude in [0.0, 90.0, -45.0, 180.0] {
+        let declination = model.declination(0.0, longitude, 0.0, 2025.0);
+        assert!((declination - expected(longitude)).abs() < 1e-6, "{} à {}", declination, longitude);
+    }
+    assert!((model.declination(0.0, 0.0, 0.0, 2025.0) + 9.4623).abs() < 1e-3);
+}
+
+#[test]
+fn schmidt_normalization_of_sectoral_terms() {
+    let model = model(&[
+        "  1  0  -30000.0       0.0        0.0        0.0",
+        "  2  2       0.0   -1000.0        0.0        0.0",
+    ]);
+
+    // P22 = √3/2 sin²θ, terme en (a/r)⁴ au lieu de (a/r)³ pour le dipôle
+    let east = 2.0 * 1000.0 * 3f64.sqrt() / 2.0 * REFERENCE_RADIUS / EQUATOR_RADIUS;
+    let expected = east.atan2(30000.0).to_degrees();
+    let declination = model.declination(0.0, 0.0, 0.0, 2025.0);
+    assert!((declination - expected).abs() < 1e-6, "{} attendu {}", declination, expected);
+}
+
+#[test]
+fn secular_variation_from_epoch() {
+    // h11 de 5000 nT à l'époque, 5500 nT cinq ans plus tard
+    let model = model(&[
+        "  1  0  -30000.0       0.0        0.0        0.0",
+        "  1  1       0.0    5000.0        0.0      100.0",
+    ]);
+
+    let at = |year: f64| model.declination(0.0, 0.0, 0.0, year);
+    assert!((at(2025.0) - (-5000f64).atan2(30000.0).to_degrees()).abs() < 1e-6);
+    assert!((at(2030.0) - (-5500f64).atan2(30000.0).to_degrees()).abs() < 1e-6);
+}
+
+#[test]
+fn rejects_invalid_coefficient_files() {
+    assert!(Model::parse("").is_err());
+    assert!(Model::parse(&cof(2025.0, &[])).is_err());
+    assert!(Model::parse(&cof(2025.0, &["  1  0  -30000.0  0.0"])).is_err());
+    assert!(Model::parse(&cof(2025.0, &["  1  2  -30000.0  0.0  0.0  0.0"])).is_err());
+    assert!(Model::parse(&cof(2025.0, &["  1  0  abc  0.0  0.0  0.0"])).is_err());
+
+    let model = Model::parse(&cof(2020.0, &["  1  0  -30000.0       0.0        0.0        0.0"])).unwrap();
+    assert_eq!(model.epoch, 2020.0);
+}
+
+#[test]
+fn fixed_declination_without_model_or_fix() {
+    let time = Utc.with_ymd_and_hms(2026, 7, 1, 0, 0, 0).unwrap();
+
+    let mut fixed = Declination::new(2.5, None);
+    assert_eq!(fixed.update(Some((46.5, 6.6)), time), 2.5);
+
+    let tilted = model(&[
+        "  1  0  -30000.0       0.0        0.0        0.0",
+        "  1  1       0.0    5000.0        0.0        0.0",
+    ]);
+    let mut computed = Declination::new(2.5, Some(tilted.clone()));
+    assert_eq!(computed.update(None, time), 2.5);
+
+    // Position connue: modèle, puis dernière valeur conservée sans position
+    let at_origin = computed.update(Some((0.0, 0.0)), time);
+    assert!((at_origin as f64 - tilted.declination(0.0, 0.0, 0.0, 2026.5)).abs() < 1e-4);
+    assert_eq!(computed.update(None, time), at_origin);
+
+    // Petit déplacement: pas de nouveau calcul; au-delà, nouvelle valeur
+    assert_eq!(computed.update(Some((0.01, 0.01)), time), at_origin);
+    let moved = computed.update(Some((0.0, 90.0)), time);
+    assert!((moved as f64 - tilted.declination(0.0, 90.0, 0.0, 2026.5)).abs() < 1e-4);
+}
+
+#[test]
+fn true_heading_and_decimal_year() {
+    assert_eq!(declination::true_heading(359.0, 2.5), 1.5);
+    assert_eq!(declination::true_heading(1.0, -2.5), 358.5);
+
+    let year = declination::decimal_year(Utc.with_ymd_and_hms(2024, 7, 2, 0, 0, 0).unwrap());
+    assert!((year - (2024.0 + 183.0 / 366.0)).abs() < 1e-9);
+}
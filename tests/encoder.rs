@@ -0,0 +1,50 @@
+// Encodeur de roue: vitesse et distance à partir du compte d'impulsions
+
+use voiturerc::sensors::encoder::odometry;
+
+use odometry::Odometry;
+
+/// Une impulsion par tour d'une roue de 0.33 m, calcul toutes les 100 ms
+fn odometry() -> Odometry {
+    Odometry::new(0.33, 100_000)
+}
+
+#[test]
+fn first_count_is_the_reference() {
+    let mut odometry = odometry();
+
+    // Impulsions comptées avant le démarrage ignorées
+    let measure = odometry.update(1_000_000, 42).unwrap();
+    assert_eq!((measure.speed_kmh, measure.distance_m, measure.pulses), (0.0, 0.0, 0));
+}
+
+#[test]
+fn speed_and_distance_per_interval() {
+    let mut odometry = odometry();
+    odometry.update(0, 0).unwrap();
+
+    // Intervalle pas encore écoulé: pas de mesure
+    assert!(odometry.update(50_000, 2).is_none());
+
+    // 3 impulsions en 100 ms: 0.99 m, 9.9 m/s
+    let measure = odometry.update(100_000, 3).unwrap();
+    assert!((measure.speed_kmh - 35.64).abs() < 1e-9, "{}", measure.speed_kmh);
+    assert!((measure.distance_m - 0.99).abs() < 1e-9);
+    assert_eq!(measure.pulses, 3);
+
+    // Vitesse sur la durée réelle de l'intervalle, distance cumulée
+    let measure = odometry.update(300_000, 5).unwrap();
+    assert!((measure.speed_kmh - 0.66 / 0.2 * 3.6).abs() < 1e-9, "{}", measure.speed_kmh);
+    assert!((measure.distance_m - 1.65).abs() < 1e-9);
+}
+
+#[test]
+fn stopped_wheel() {
+    let mut odometry = odometry();
+    odometry.update(0, 10).unwrap();
+    odometry.update(100_000, 20).unwrap();
+
+    let measure = odometry.update(200_000, 20).unwrap();
+    assert_eq!(measure.speed_kmh, 0.0);
+    assert!((measure.distance_m - 3.3).abs() < 1e-9);
+}
@@ -0,0 +1,293 @@
+// Encodage binaire (protobuf) des enregistrements: aller-retour et compatibilité entre une
+// ancienne et une nouvelle version du schéma (voir proto/records.proto)
+#![cfg(not(feature = "real-sensors"))]
+
+use voiturerc::{clock, config, proto, record, sensors};
+
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use chrono::{TimeZone, Utc};
+use clock::{Clock, Stamp};
+use config::Encoding;
+use prost::Message;
+use record::{ModemData, ModemStatus, Record};
+use sensors::reader::{
+    AnalogData, BaroData, Data, EncoderData, GpsData, ImuData, MagData, PowerData, RangeData, RangeStatus, SatellitesData,
+};
+use sensors::replay::{self, Recorder};
+
+/// Ancienne version simulée du schéma: GPS sans satellites, fix ni cap, seul type d'échantillon
+mod v1 {
+    #[derive(Clone, Copy, PartialEq, prost::Message)]
+    pub struct Stamp {
+        #[prost(uint64, tag = "1")]
+        pub mono_us: u64,
+        #[prost(int64, tag = "2")]
+        pub utc_us: i64,
+    }
+
+    #[derive(Clone, Copy, PartialEq, prost::Message)]
+    pub struct Gps {
+        #[prost(message, optional, tag = "1")]
+        pub stamp: Option<Stamp>,
+        #[prost(double, tag = "2")]
+        pub speed_kmh: f64,
+        #[prost(double, tag = "3")]
+        pub latitude: f64,
+        #[prost(double, tag = "4")]
+        pub longitude: f64,
+    }
+
+    #[derive(Clone, Copy, PartialEq, prost::Message)]
+    pub struct Record {
+        #[prost(oneof = "record::Record", tags = "4")]
+        pub record: Option<record::Record>,
+    }
+
+    pub mod record {
+        #[derive(Clone, Copy, PartialEq, prost::Oneof)]
+        pub enum Record {
+            #[prost(message, tag = "4")]
+            Gps(super::Gps),
+        }
+    }
+
+    #[derive(Clone, Copy, PartialEq, prost::Message)]
+    pub struct Data {
+        #[prost(message, optional, tag = "4")]
+        pub gps: Option<Gps>,
+    }
+
+    #[derive(Clone, Copy, PartialEq, prost::Message)]
+    pub struct Sample {
+        #[prost(message, optional, tag = "1")]
+        pub stamp: Option<Stamp>,
+        #[prost(message, optional, tag = "2")]
+        pub data: Option<Data>,
+    }
+}
+
+const UTC_US: i64 = 1_717_243_200_000_000;
+
+fn stamp(mono_us: u64) -> Stamp {
+    Stamp {
+        mono_us,
+        utc: Utc.with_ymd_and_hms(2024, 6, 1, 12, 0, 0).unwrap() + chrono::Duration::microseconds(mono_us as i64),
+    }
+}
+
+fn data(mono_us: u64) -> Data {
+    Data {
+        imu: ImuData {
+            stamp: stamp(mono_us),
+            angles: (1.5, -2.25, 180.0),
+            temp: 31.0,
+            accel: (0.05, -0.1, 1.0),
+            gyro: (0.5, -1.25, 12.0),
+        },
+        mag: MagData {
+            stamp: stamp(mono_us),
+            raw: (-120, 45, 300),
+            heading: 92.5,
+            calibrated: (-120.0, 45.0, 300.0),
+            true_heading: 94.5,
+            declination: 2.0,
+        },
+        analog: AnalogData {
+            stamp: stamp(mono_us),
+            battery: 7.4,
+            ..AnalogData::default()
+        },
+        power: PowerData {
+            stamp: stamp(mono_us),
+            voltage: 7.3,
+            current: 4.5,
+            power: 32.85,
+        },
+        baro: BaroData {
+            stamp: stamp(mono_us),
+            pressure: 101_325.0,
+            temperature: 21.5,
+            humidity: Some(45.0),
+            altitude: 1.2,
+        },
+        encoder: EncoderData {
+            stamp: stamp(mono_us),
+            speed_kmh: 24.8,
+            distance_m: 1520.5,
+            pulses: 4608,
+        },
+        range: RangeData {
+            stamp: stamp(mono_us),
+            distance_mm: Some(1250),
+            status: RangeStatus::Valid,
+        },
+        gps: GpsData {
+            stamp: stamp(mono_us),
+            speed_kmh: 25.5,
+            latitude: 46.52,
+            longitude: 6.632,
+            satellites: 9,
+            fix: true,
+            heading: 90.0,
+            time: None,
+            valid: false,
+        },
+        satellites: SatellitesData {
+            stamp: stamp(mono_us),
+            pdop: 1.8,
+            hdop: 0.9,
+            vdop: 1.5,
+            used: 9,
+            in_view: 14,
+            snr_mean: 38.5,
+        },
+    }
+}
+
+/// Dossier temporaire propre au test
+fn directory(name: &str) -> PathBuf {
+    let directory = std::env::temp_dir().join(format!("rc-telemetrie-encoding-{}-{}", name, std::process::id()));
+    let _ = fs::remove_dir_all(&directory);
+    fs::create_dir_all(&directory).unwrap();
+    directory
+}
+
+/// Enregistre `count` échantillons, un toutes les 100 ms, retourne le fichier produit
+fn record(directory: &Path, encoding: Encoding, count: u64) -> PathBuf {
+    let clock = Clock::virtual_at(Utc.with_ymd_and_hms(2024, 6, 1, 12, 0, 0).unwrap());
+    {
+        let mut recorder = Recorder::create(directory, &clock, encoding).unwrap();
+        for n in 0..count {
+            clock.set(Duration::from_millis(100 * n));
+            recorder.write(&data(100_000 * n)).unwrap();
+        }
+    }
+
+    fs::read_dir(directory).unwrap().next().unwrap().unwrap().path()
+}
+
+fn decode(path: &Path) -> (u64, String) {
+    let mut output = Vec::new();
+    let written = replay::decode(path, &mut output).unwrap();
+    (written, String::from_utf8(output).unwrap())
+}
+
+#[test]
+fn capture_decodes_to_same_json() {
+    let json = record(&directory("json"), Encoding::Json, 5);
+    let binary = record(&directory("binary"), Encoding::Protobuf, 5);
+
+    let (written, decoded) = decode(&binary);
+
+    assert_eq!(binary.extension().unwrap(), "pb");
+    assert_eq!(written, 5);
+    assert_eq!(decoded, fs::read_to_string(&json).unwrap());
+    assert!(fs::metadata(&binary).unwrap().len() < fs::metadata(&json).unwrap().len() / 2);
+}
+
+#[test]
+fn truncated_capture_keeps_complete_samples() {
+    let binary = record(&directory("truncated"), Encoding::Protobuf, 3);
+    let content = fs::read(&binary).unwrap();
+    fs::write(&binary, &content[..content.len() - 3]).unwrap();
+
+    let (written, decoded) = decode(&binary);
+
+    assert_eq!(written, 2);
+    assert_eq!(decoded.lines().count(), 2);
+}
+
+#[test]
+fn old_reader_skips_new_fields() {
+    let gps: proto::Gps = data(0).gps.into();
+
+    let old = v1::Gps::decode(gps.encode_to_vec().as_slice()).unwrap();
+
+    assert_eq!(old.stamp.unwrap().utc_us, UTC_US);
+    assert_eq!(old.speed_kmh, 25.5);
+    assert_eq!(old.latitude, 46.52);
+    assert_eq!(old.longitude, 6.632);
+}
+
+#[test]
+fn old_reader_skips_new_record_kinds() {
+    let modem: proto::Record = Record::Modem(ModemData {
+        status: ModemStatus { quality: 80, ..ModemStatus::default() },
+        stamp: stamp(0),
+    })
+    .into();
+    let gps: proto::Record = Record::Gps(data(0).gps).into();
+
+    let modem = v1::Record::decode(modem.encode_to_vec().as_slice()).unwrap();
+    let gps = v1::Record::decode(gps.encode_to_vec().as_slice()).unwrap();
+
+    assert_eq!(modem.record, None);
+    assert!(matches!(gps.record, Some(v1::record::Record::Gps(gps)) if gps.latitude == 46.52));
+}
+
+#[test]
+fn new_reader_defaults_missing_fields() {
+    let old = v1::Gps {
+        stamp: Some(v1::Stamp {
+            mono_us: 1_500_000,
+            utc_us: UTC_US,
+        }),
+        speed_kmh: 12.0,
+        latitude: 46.52,
+        longitude: 6.632,
+    };
+
+    let gps: GpsData = proto::Gps::decode(old.encode_to_vec().as_slice()).unwrap().into();
+
+    assert_eq!(gps.stamp.mono_us, 1_500_000);
+    assert_eq!(gps.stamp.utc, Utc.with_ymd_and_hms(2024, 6, 1, 12, 0, 0).unwrap());
+    assert_eq!(gps.speed_kmh, 12.0);
+    assert_eq!(gps.latitude, 46.52);
+    assert_eq!(gps.satellites, 0);
+    assert!(!gps.fix);
+    assert_eq!(gps.heading, 0.0);
+}
+
+#[test]
+fn old_capture_decodes_with_new_reader() {
+    let path = directory("old").join("run-1.pb");
+    let mut content = b"RCPB\x01".to_vec();
+    for n in 0..2u64 {
+        let sample = v1::Sample {
+            stamp: Some(v1::Stamp {
+                mono_us: 100_000 * n,
+                utc_us: UTC_US + 100_000 * n as i64,
+            }),
+            data: Some(v1::Data {
+                gps: Some(v1::Gps {
+                    stamp: None,
+                    speed_kmh: 10.0 * n as f64,
+                    latitude: 46.52,
+                    longitude: 6.632,
+                }),
+            }),
+        };
+        content.extend(sample.encode_length_delimited_to_vec());
+    }
+    fs::write(&path, content).unwrap();
+
+    let (written, decoded) = decode(&path);
+    let last: serde_json::Value = serde_json::from_str(decoded.lines().last().unwrap()).unwrap();
+
+    assert_eq!(written, 2);
+    assert_eq!(last["stamp"]["mono_us"], 100_000);
+    assert_eq!(last["data"]["gps"]["speed_kmh"], 10.0);
+    assert_eq!(last["data"]["gps"]["satellites"], 0);
+    assert_eq!(last["data"]["imu"]["temp"], 0.0);
+}
+
+#[test]
+fn unsupported_capture_version() {
+    let path = directory("version").join("run-1.pb");
+    fs::write(&path, b"RCPB\x02").unwrap();
+
+    assert!(replay::decode(&path, &mut Vec::new()).is_err());
+}
@@ -0,0 +1,168 @@
+// Bout en bout en mode simulé: base SurrealDB embarquée (mem://), capteurs et actionneurs simulés,
+// écrivain, boucle des capteurs et contrôle par la base, assemblés comme dans la tâche principale.
+// Une minute simulée en quelques secondes (temps accéléré), à lancer avec:
+//   cargo test --no-default-features --features fake-sensors,fake-actuators --test end_to_end
+#![cfg(not(feature = "real-sensors"))]
+
+use voiturerc::{actuators, clock, config, control, database, pipeline, selftest, sensors, writer};
+
+use std::collections::BTreeMap;
+use std::sync::Arc;
+use std::time::Duration;
+
+use surrealdb::engine::any::Any;
+use surrealdb::Surreal;
+use tokio::sync::watch;
+use tokio::time::{sleep, Instant};
+use tokio_util::sync::CancellationToken;
+
+use actuators::arbiter::Arbiter;
+use clock::Clock;
+use config::{Config, LinkLossPolicy, MagCalibration};
+use database::Database;
+use selftest::SelfTest;
+use sensors::reader::Reader;
+use sensors::sim::Simulation;
+
+/// Accélération du temps: une minute simulée en 3 s
+const TIME_SCALE: f64 = 20.0;
+/// Durée simulée
+const SIMULATED: Duration = Duration::from_secs(60);
+/// Temps simulé pendant lequel le test écrit des commandes
+const CONTROLLED: Duration = Duration::from_secs(30);
+/// Délai sans commande avant le failsafe (temps réel, comme control.dead_timeout_ms)
+const DEAD_TIMEOUT: Duration = Duration::from_millis(500);
+/// Période des écritures de commandes et des relevés de la base (temps réel)
+const PERIOD: Duration = Duration::from_millis(100);
+
+/// Horodatages relevés: enregistrement, requête de l'horodatage monotone
+const RECORDS: [(&str, &str); 5] = [
+    ("imu", "SELECT VALUE imu_stamp.mono_us FROM nav:realtime;"),
+    ("mag", "SELECT VALUE mag_stamp.mono_us FROM nav:realtime;"),
+    ("gps", "SELECT VALUE gps_stamp.mono_us FROM nav:realtime;"),
+    ("analog", "SELECT VALUE stamp.mono_us FROM levels:realtime;"),
+    ("modem", "SELECT VALUE stamp.mono_us FROM modem:realtime;"),
+];
+
+/// Horodatage monotone d'un enregistrement, None: absent
+async fn stamp(client: &Surreal<Any>, query: &str) -> Option<u64> {
+    let mut result = client.query(query).await.unwrap();
+    result.take::<Option<u64>>(0).unwrap()
+}
+
+/// Commande écrite comme par l'application de pilotage
+async fn send_control(client: &Surreal<Any>, steer: f64, speed: f64) {
+    client
+        .query("UPDATE control:realtime SET steer = $steer, speed = $speed;")
+        .bind(("steer", steer))
+        .bind(("speed", speed))
+        .await
+        .unwrap()
+        .check()
+        .unwrap();
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn simulated_minute_end_to_end() {
+    let mut config = Config::default();
+    config.simulation.seed = Some(42);
+    config.simulation.time_scale = TIME_SCALE;
+    config.validate().expect("configuration simulée valide");
+
+    // Base embarquée, partagée par l'application et le test
+    let client = surrealdb::engine::any::connect("mem://").await.unwrap();
+    let db = Arc::new(Database::open(client.clone(), &config.database, false).await.unwrap());
+    client.use_ns("voiturerc").use_db("voiturerc").await.unwrap();
+
+    let token = CancellationToken::new();
+    let clock = Clock::accelerated(TIME_SCALE);
+    let (_sender, updates) = watch::channel(config.clone());
+    let selftest = SelfTest::new();
+    let simulation = Simulation::shared(&config.simulation);
+    let (_calibration, mag_calibration) = watch::channel(MagCalibration::default());
+
+    let (writer, _) = writer::spawn(db.clone(), updates.clone(), &clock, token.child_token());
+    let reader = Reader::new(token.child_token(), updates.clone(), &clock, &simulation, &selftest, &mag_calibration).unwrap();
+    tokio::spawn(pipeline::run(reader, None, writer.clone(), updates.clone(), token.child_token()));
+    tokio::spawn(pipeline::fake_modem(
+        simulation.clone(),
+        writer.clone(),
+        clock.clone(),
+        token.child_token(),
+    ));
+
+    let (commands, arbiter) = Arbiter::new(&Clock::start(), DEAD_TIMEOUT, LinkLossPolicy::Stop);
+    let state = commands.state();
+    tokio::spawn(control::db(db.clone(), commands.clone(), token.child_token()));
+    tokio::spawn(control::fake(
+        arbiter,
+        config.control.clone(),
+        simulation.clone(),
+        selftest.clone(),
+        token.child_token(),
+    ));
+
+    let start = Instant::now();
+    let mut stamps: BTreeMap<&str, Vec<u64>> = BTreeMap::new();
+    let mut moved = false;
+    let mut stopped_at = None;
+    let mut failsafe = None;
+    let mut steer = 0.2;
+
+    while clock.elapsed() < SIMULATED {
+        if clock.elapsed() < CONTROLLED {
+            // Direction alternée: un enregistrement inchangé ne produit aucune notification, la
+            // première écriture peut précéder la création du live
+            steer = -steer;
+            send_control(&client, steer, 0.5).await;
+            moved |= state.borrow().output.speed == 0.5;
+        } else if stopped_at.is_none() {
+            stopped_at = Some(Instant::now());
+        }
+
+        // Arrêt des commandes: les actionneurs simulés reviennent au neutre
+        if let (Some(stopped), None) = (stopped_at, failsafe) {
+            if state.borrow().output.speed == 0.0 {
+                failsafe = Some(stopped.elapsed());
+            }
+        }
+
+        for (record, query) in RECORDS {
+            if let Some(stamp) = stamp(&client, query).await {
+                stamps.entry(record).or_default().push(stamp);
+            }
+        }
+
+        sleep(PERIOD).await;
+    }
+    let elapsed = start.elapsed();
+    token.cancel();
+
+    assert!(elapsed < Duration::from_secs(10), "minute simulée en {:?}", elapsed);
+
+    // Chaque enregistrement existe et avance avec le temps simulé
+    for (record, _) in RECORDS {
+        let stamps = stamps.get(record).unwrap_or_else(|| panic!("{}: aucun enregistrement", record));
+        assert!(stamps.windows(2).all(|w| w[0] <= w[1]), "{}: horodatages non monotones", record);
+        let (first, last) = (stamps[0], stamps[stamps.len() - 1]);
+        assert!(first < last, "{}: horodatage figé", record);
+        assert!(last <= clock.elapsed().as_micros() as u64, "{}: horodatage dans le futur", record);
+    }
+
+    // Etat des capteurs, écrit uniquement lors d'un changement
+    assert!(stamp(&client, "SELECT VALUE stamp.mono_us FROM status:sensors;").await.is_some());
+    let imu = &stamps["imu"];
+    assert!(
+        imu[imu.len() - 1] >= Duration::from_secs(50).as_micros() as u64,
+        "IMU: {} µs simulées",
+        imu[imu.len() - 1]
+    );
+
+    // La commande de la base a atteint les actionneurs simulés
+    assert!(moved, "commande jamais appliquée");
+
+    // Failsafe après le délai sans commande
+    let failsafe = failsafe.expect("failsafe non déclenché");
+    assert!(failsafe >= DEAD_TIMEOUT - PERIOD, "failsafe après {:?}", failsafe);
+    assert!(failsafe < DEAD_TIMEOUT * 4, "failsafe après {:?}", failsafe);
+}
@@ -0,0 +1,294 @@
+// Export d'une exécution du journal JSON lines ou de la base (SurrealDB embarquée) en GPX, CSV et KML
+#![cfg(not(feature = "real-sensors"))]
+
+mod common;
+
+use voiturerc::{clock, config, database, export, jsonl, record, sensors};
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use clock::Clock;
+use common::stamp_at;
+use config::Config;
+use database::Database;
+use export::{export, export_db, Format, Options};
+use jsonl::file::{Limits, Sink};
+use record::Record;
+use sensors::reader::{AnalogData, GpsData, ImuData};
+
+const RUN: &str = "run-1717243200";
+
+fn gps(second: u32, speed_kmh: f64, fix: bool) -> Record {
+    Record::Gps(GpsData {
+        stamp: stamp_at(second),
+        speed_kmh,
+        latitude: 46.52 + second as f64 * 0.0001,
+        longitude: 6.632,
+        satellites: if fix { 9 } else { 0 },
+        fix,
+        heading: 0.0,
+        time: None,
+        valid: false,
+    })
+}
+
+fn analog(second: u32, battery: f32) -> Record {
+    Record::Analog(AnalogData {
+        stamp: stamp_at(second),
+        battery,
+        ..AnalogData::default()
+    })
+}
+
+/// Dossier temporaire propre au test
+fn directory(name: &str) -> PathBuf {
+    let directory = std::env::temp_dir().join(format!("rc-telemetrie-export-{}-{}", name, std::process::id()));
+    let _ = fs::remove_dir_all(&directory);
+    fs::create_dir_all(&directory).unwrap();
+    directory
+}
+
+/// Journal d'une exécution, réparti sur plusieurs fichiers dont certains compressés, avec une
+/// ligne illisible
+fn journal(directory: &Path, records: &[Record]) {
+    let limits = Limits {
+        max_bytes: 600,
+        max_age: None,
+        gzip: true,
+        budget: None,
+    };
+    let mut sink = Sink::new(directory, RUN, limits, &Clock::start()).unwrap();
+    for record in records {
+        let mut line = serde_json::json!({
+            "type": record.kind(),
+            "ts": record.stamp().utc,
+            "run_id": RUN,
+        });
+        line.as_object_mut()
+            .unwrap()
+            .extend(serde_json::to_value(record).unwrap().as_object().unwrap().clone());
+        sink.write(&serde_json::to_vec(&line).unwrap()).unwrap();
+    }
+    sink.write(br#"{"type":"gps","ts""#).unwrap();
+    sink.close().unwrap();
+}
+
+fn records() -> Vec<Record> {
+    vec![
+        gps(0, 2.0, true),
+        analog(0, 7.4),
+        gps(1, 3.0, true),
+        Record::Imu(ImuData {
+            stamp: stamp_at(1),
+            angles: (1.0, 2.0, 3.0),
+            temp: 30.0,
+            ..ImuData::default()
+        }),
+        gps(2, 20.0, true),
+        analog(2, 6.4),
+        gps(3, 40.0, true),
+        gps(4, 0.0, false),
+        analog(4, 6.3),
+        gps(5, 4.0, true),
+        analog(5, 7.0),
+        analog(6, 6.2),
+    ]
+}
+
+fn options(journal: &Path, out: &Path, format: Format) -> Options {
+    Options {
+        run: RUN.to_string(),
+        format,
+        out: out.to_path_buf(),
+        journal: journal.to_path_buf(),
+        battery_min: 6.6,
+    }
+}
+
+#[test]
+fn gpx_segments() {
+    let directory = directory("gpx");
+    journal(&directory, &records());
+    assert!(fs::read_dir(&directory)
+        .unwrap()
+        .any(|entry| entry.unwrap().file_name().to_string_lossy().ends_with(".jsonl.gz")));
+
+    let out = directory.join("out");
+    let summary = export(&options(&directory, &out, Format::Gpx)).unwrap();
+    assert_eq!(summary.files, [out.join(format!("{}.gpx", RUN))]);
+    assert_eq!(summary.records, 12);
+    assert_eq!(summary.skipped, 1);
+
+    let gpx = fs::read_to_string(&summary.files[0]).unwrap();
+    assert_eq!(gpx.matches("<trkseg>").count(), 2);
+    assert_eq!(gpx.matches("</trkseg>").count(), 2);
+    assert_eq!(gpx.matches("<trkpt ").count(), 5);
+    assert!(gpx.contains(r#"<trkpt lat="46.5202000" lon="6.6320000"><time>2024-06-01T12:00:02.000Z</time></trkpt>"#));
+    assert!(gpx.trim_end().ends_with("</gpx>"));
+}
+
+#[test]
+fn csv_per_table() {
+    let directory = directory("csv");
+    journal(&directory, &records());
+
+    let out = directory.join("out");
+    let summary = export(&options(&directory, &out, Format::Csv)).unwrap();
+    let names: Vec<String> = summary
+        .files
+        .iter()
+        .map(|file| file.file_name().unwrap().to_string_lossy().into_owned())
+        .collect();
+    assert_eq!(
+        names,
+        [
+            format!("{}.analog.csv", RUN),
+            format!("{}.gps.csv", RUN),
+            format!("{}.imu.csv", RUN)
+        ]
+    );
+
+    let gps = fs::read_to_string(out.join(format!("{}.gps.csv", RUN))).unwrap();
+    let mut lines = gps.lines();
+    assert_eq!(
+        lines.next().unwrap(),
+        "ts,stamp.mono_us,stamp.utc,speed_kmh,latitude,longitude,satellites,fix,heading,time,valid"
+    );
+    assert_eq!(lines.count(), 6);
+
+    let analog = fs::read_to_string(out.join(format!("{}.analog.csv", RUN))).unwrap();
+    assert_eq!(analog.lines().count(), 1 + 5);
+}
+
+#[test]
+fn kml_speed_colors_and_events() {
+    let directory = directory("kml");
+    journal(&directory, &records());
+
+    let out = directory.join("out");
+    let summary = export(&options(&directory, &out, Format::Kml)).unwrap();
+    let kml = fs::read_to_string(&summary.files[0]).unwrap();
+
+    // Une ligne par tranche de vitesse: 2-3 km/h, 20 km/h, 40 km/h, puis 4 km/h après le fix perdu
+    let styles: Vec<&str> = kml
+        .match_indices("<styleUrl>#")
+        .map(|(position, _)| &kml[position + 11..position + 17])
+        .collect();
+    assert_eq!(styles, ["speed0", "speed2", "speed3", "speed0"]);
+    // La ligne à 20 km/h part du dernier point à 3 km/h
+    assert!(kml.contains("#speed2</styleUrl><LineString><tessellate>1</tessellate><coordinates>6.6320000,46.5201000,0 6.6320000,46.5202000,0 </coordinates>"));
+
+    // Passage sous le seuil, perte du fix, nouveau passage sous le seuil
+    let events: Vec<&str> = kml
+        .match_indices("<Placemark><name>")
+        .map(|(position, _)| {
+            let name = &kml[position + 17..];
+            &name[..name.find('<').unwrap()]
+        })
+        .collect();
+    assert_eq!(events, ["Batterie faible (6.40 V)", "Perte du fix GPS", "Batterie faible (6.20 V)"]);
+    assert!(kml.trim_end().ends_with("</kml>"));
+}
+
+#[test]
+fn unknown_run() {
+    let directory = directory("unknown");
+    journal(&directory, &records());
+
+    let mut options = options(&directory, &directory.join("out"), Format::Csv);
+    options.run = "run-1".to_string();
+    let error = export(&options).err().unwrap().to_string();
+    assert!(error.contains("run-1 introuvable"), "{}", error);
+    assert!(!directory.join("out").exists());
+
+    // Dossier du journal absent
+    options.journal = directory.join("absent");
+    assert!(export(&options).is_err());
+}
+
+/// Base embarquée contenant l'exécution RUN: historique des échantillons et évènements
+async fn database() -> Database {
+    let config = Config::default();
+    let client = surrealdb::engine::any::connect("mem://").await.unwrap();
+    let db = Database::open(client.clone(), &config.database, false).await.unwrap().with_run(RUN);
+    client
+        .query("CREATE type::thing('run', $id) SET pid = 1;")
+        .bind(("id", RUN))
+        .await
+        .unwrap();
+
+    for record in records() {
+        db.send_record(record).await.unwrap();
+    }
+    db.send_event("failsafe", "Failsafe: aucune commande", stamp_at(3)).await.unwrap();
+    db.send_event("crash", "Choc détecté", stamp_at(1)).await.unwrap();
+
+    // Evènement d'une autre exécution
+    let other = Database::open(client, &config.database, false).await.unwrap().with_run("run-1");
+    other.send_event("failsafe", "Autre exécution", stamp_at(2)).await.unwrap();
+
+    db
+}
+
+#[tokio::test]
+async fn db_events_and_history() {
+    let db = database().await;
+    let directory = directory("db");
+
+    let out = directory.join("csv");
+    let summary = export_db(&options(&directory, &out, Format::Csv), &db).await.unwrap();
+    let names: Vec<String> = summary
+        .files
+        .iter()
+        .map(|file| file.file_name().unwrap().to_string_lossy().into_owned())
+        .collect();
+    assert_eq!(
+        names,
+        [
+            format!("{}.analog.csv", RUN),
+            format!("{}.event.csv", RUN),
+            format!("{}.gps.csv", RUN),
+            format!("{}.imu.csv", RUN)
+        ]
+    );
+    // Tous les échantillons de l'exécution, évènements de l'exécution dans l'ordre
+    assert_eq!(summary.records, records().len() as u64 + 2);
+    assert_eq!(summary.skipped, 0);
+    let gps = fs::read_to_string(out.join(format!("{}.gps.csv", RUN))).unwrap();
+    assert_eq!(gps.lines().count(), 1 + 6);
+    let analog = fs::read_to_string(out.join(format!("{}.analog.csv", RUN))).unwrap();
+    assert_eq!(analog.lines().count(), 1 + 5);
+    let events = fs::read_to_string(out.join(format!("{}.event.csv", RUN))).unwrap();
+    let mut lines = events.lines();
+    let column = lines.next().unwrap().split(',').position(|name| name == "message").unwrap();
+    let messages: Vec<&str> = lines.map(|line| line.split(',').nth(column).unwrap()).collect();
+    assert_eq!(messages, ["Choc détecté", "Failsafe: aucune commande"]);
+
+    // Trace complète, dans l'ordre des échantillons
+    let out = directory.join("gpx");
+    let summary = export_db(&options(&directory, &out, Format::Gpx), &db).await.unwrap();
+    let gpx = fs::read_to_string(&summary.files[0]).unwrap();
+    assert_eq!(gpx.matches("</trkseg>").count(), 2);
+    assert_eq!(gpx.matches("<trkpt ").count(), 5);
+
+    let out = directory.join("kml");
+    let summary = export_db(&options(&directory, &out, Format::Kml), &db).await.unwrap();
+    let kml = fs::read_to_string(&summary.files[0]).unwrap();
+    assert!(kml.contains("<Placemark><name>Choc détecté</name>"));
+    assert!(kml.contains("<Placemark><name>Failsafe: aucune commande</name>"));
+    assert!(kml.contains("<Placemark><name>Batterie faible (6.20 V)</name>"));
+    assert!(!kml.contains("Autre exécution"));
+}
+
+#[tokio::test]
+async fn db_unknown_run() {
+    let db = database().await;
+    let directory = directory("db-unknown");
+
+    let mut options = options(&directory, &directory.join("out"), Format::Csv);
+    options.run = "run-1".to_string();
+    let error = export_db(&options, &db).await.err().unwrap().to_string();
+    assert!(error.contains("run-1 introuvable"), "{}", error);
+    assert!(!directory.join("out").exists());
+}
@@ -0,0 +1,118 @@
+// Chaîne complète en mode simulé: simulation, capteurs simulés, thread de lecture et files de l'écrivain.
+// Sans matériel ni base de donnée, à lancer avec:
+//   cargo test --no-default-features --features fake-sensors,fake-actuators
+#![cfg(not(feature = "real-sensors"))]
+
+use voiturerc::{channel, clock, config, selftest, sensors};
+
+use std::sync::Arc;
+use std::time::Duration;
+
+use channel::DropOldest;
+use futures::StreamExt;
+use tokio::sync::{watch, Notify};
+use tokio::time::{sleep, Instant};
+use tokio_util::sync::CancellationToken;
+
+use config::{Config, MagCalibration, SensorMode};
+use selftest::{Outcome, SelfTest};
+use sensors::reader::{Data, Reader};
+use sensors::sim::Simulation;
+
+/// Durée de fonctionnement de la chaîne
+const RUN: Duration = Duration::from_secs(2);
+
+#[tokio::test]
+async fn fake_pipeline_end_to_end() {
+    let mut config = Config::default();
+    for mode in [
+        config.sensors.imu.mode,
+        config.sensors.mag.mode,
+        config.sensors.analog.mode,
+        config.sensors.gps.mode,
+    ] {
+        assert!(mode == SensorMode::Fake, "capteurs simulés par défaut sans real-sensors");
+    }
+    config.validate().expect("configuration par défaut valide");
+    // Moniteur de puissance, baromètre, encodeur et distance désactivés par défaut (capteurs optionnels)
+    for mode in [
+        config.sensors.power.mode,
+        config.sensors.baro.mode,
+        config.sensors.encoder.mode,
+        config.sensors.range.mode,
+    ] {
+        assert!(mode == SensorMode::Disabled);
+    }
+    config.sensors.power.mode = SensorMode::Fake;
+    config.sensors.baro.mode = SensorMode::Fake;
+    config.sensors.encoder.mode = SensorMode::Fake;
+    config.sensors.range.mode = SensorMode::Fake;
+
+    let token = CancellationToken::new();
+    let clock = clock::Clock::start();
+    let simulation = Simulation::shared(&config.simulation);
+    let selftest = SelfTest::new();
+    let (_sender, updates) = watch::channel(config.clone());
+    let (_calibration, mag_calibration) = watch::channel(MagCalibration::default());
+
+    let mut reader = Reader::new(token.clone(), updates, &clock, &simulation, &selftest, &mag_calibration).unwrap();
+
+    // Files de l'écrivain, comme dans la tâche principale
+    let notify = Arc::new(Notify::new());
+    let imu = DropOldest::new(config.writer.imu_queue, notify.clone());
+    let gps = DropOldest::new(config.writer.gps_queue, notify.clone());
+
+    let mut last = Data::default();
+    let mut imu_samples = Vec::new();
+    let mut gps_samples = Vec::new();
+    let start = Instant::now();
+    while start.elapsed() < RUN {
+        let data = reader.next().await.unwrap().unwrap();
+        if data.imu.stamp != last.imu.stamp {
+            imu.push(data.imu);
+        }
+        if data.gps.stamp != last.gps.stamp {
+            gps.push(data.gps);
+        }
+        last = data;
+
+        // Ecrivain: vide les files
+        while let Some(sample) = imu.pop() {
+            imu_samples.push(sample);
+        }
+        while let Some(sample) = gps.pop() {
+            gps_samples.push(sample);
+        }
+
+        sleep(Duration::from_millis(1000 / 30)).await;
+    }
+    token.cancel();
+
+    assert!(imu_samples.len() > 10, "{} échantillons IMU", imu_samples.len());
+    assert!(!gps_samples.is_empty());
+    assert!(imu_samples.windows(2).all(|w| w[0].stamp.mono_us < w[1].stamp.mono_us));
+    assert!(gps_samples.iter().all(|s| s.plausible().is_ok()));
+    assert_eq!(imu.stats().dropped, 0);
+    assert_eq!(gps.stats().dropped, 0);
+
+    // Le véhicule simulé se déplace
+    let first = gps_samples.first().unwrap();
+    let last = gps_samples.last().unwrap();
+    assert!((first.latitude, first.longitude) != (last.latitude, last.longitude));
+
+    // Tous les capteurs simulés ont produit un échantillon plausible
+    let report = selftest.report(&[
+        "imu.sample",
+        "mag.sample",
+        "analog.sample",
+        "power.sample",
+        "baro.sample",
+        "encoder.sample",
+        "range.sample",
+        "gps.sample",
+    ]);
+    for check in report.checks.iter().filter(|c| c.name.ends_with(".sample")) {
+        assert!(check.outcome == Outcome::Pass, "{}: {}", check.name, check.details);
+    }
+    assert!(report.passed);
+}
@@ -0,0 +1,196 @@
+// Formats des sorties locales: mêmes champs et mêmes types numériques en JSON et en MessagePack
+#![cfg(not(feature = "real-sensors"))]
+
+mod common;
+
+use voiturerc::{config, record, sensors};
+
+use common::{gps, stamp};
+use config::Format;
+use record::{AccessTechnology, ModemData, ModemStatus, Operator, Record, Registration};
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use serde_json::Value;
+use sensors::reader::{
+    AnalogData, BaroData, EncoderData, ImuData, MagData, PowerData, RangeData, RangeStatus, SatellitesData,
+    SensorStatus,
+};
+
+fn records() -> Vec<Record> {
+    vec![
+        Record::Imu(ImuData {
+            stamp: stamp(),
+            angles: (1.5, -2.25, 180.0),
+            temp: 31.0,
+            accel: (0.05, -0.1, 1.0),
+            gyro: (0.5, -1.25, 12.0),
+        }),
+        Record::Mag(MagData {
+            stamp: stamp(),
+            raw: (-120, 45, 300),
+            heading: 92.5,
+            calibrated: (-120.0, 45.0, 300.0),
+            true_heading: 94.5,
+            declination: 2.0,
+        }),
+        Record::Analog(AnalogData {
+            stamp: stamp(),
+            battery: 7.4,
+            ..AnalogData::default()
+        }),
+        Record::Gps(gps()),
+        Record::Modem(ModemData {
+            status: ModemStatus {
+                quality: 80,
+                technology: Some(AccessTechnology::Lte),
+                registration: Some(Registration::Home),
+                operator: Operator::new("Swisscom"),
+                rsrp: Some(-95.0),
+                rsrq: Some(-11.5),
+                sinr: Some(12.0),
+            },
+            stamp: stamp(),
+        }),
+        Record::Satellites(SatellitesData {
+            stamp: stamp(),
+            pdop: 1.8,
+            hdop: 0.9,
+            vdop: 1.5,
+            used: 9,
+            in_view: 14,
+            snr_mean: 38.5,
+        }),
+        Record::Power(PowerData {
+            stamp: stamp(),
+            voltage: 7.3,
+            current: 4.5,
+            power: 32.85,
+        }),
+        Record::Baro(BaroData {
+            stamp: stamp(),
+            pressure: 101_325.0,
+            temperature: 21.5,
+            humidity: Some(45.0),
+            altitude: 1.2,
+        }),
+        Record::Encoder(EncoderData {
+            stamp: stamp(),
+            speed_kmh: 24.8,
+            distance_m: 1520.5,
+            pulses: 4608,
+        }),
+        Record::Range(RangeData {
+            stamp: stamp(),
+            distance_mm: None,
+            status: RangeStatus::OutOfRange,
+        }),
+    ]
+}
+
+/// Message décodé, quel que soit le format
+fn decode(format: Format, bytes: &[u8]) -> Value {
+    match format {
+        Format::Json => serde_json::from_slice(bytes).unwrap(),
+        Format::Msgpack => rmp_serde::from_slice(bytes).unwrap(),
+    }
+}
+
+/// Mêmes champs (noms et ordre) et mêmes types de valeurs
+fn assert_same_shape(path: &str, json: &Value, msgpack: &Value) {
+    match (json, msgpack) {
+        (Value::Object(json), Value::Object(msgpack)) => {
+            let keys = |map: &serde_json::Map<String, Value>| map.keys().cloned().collect::<Vec<_>>();
+            assert_eq!(keys(json), keys(msgpack), "{}", path);
+            for (key, value) in json {
+                assert_same_shape(&format!("{}.{}", path, key), value, &msgpack[key]);
+            }
+        }
+        (Value::Array(json), Value::Array(msgpack)) => {
+            assert_eq!(json.len(), msgpack.len(), "{}", path);
+            for (n, (json, msgpack)) in json.iter().zip(msgpack).enumerate() {
+                assert_same_shape(&format!("{}.{}", path, n), json, msgpack);
+            }
+        }
+        (Value::Number(json), Value::Number(msgpack)) => {
+            assert_eq!(json.is_u64(), msgpack.is_u64(), "{}", path);
+            assert_eq!(json.is_i64(), msgpack.is_i64(), "{}", path);
+            assert_eq!(json.is_f64(), msgpack.is_f64(), "{}", path);
+            // Les f32 sont écrits au plus court en JSON, exacts en MessagePack
+            let (json, msgpack) = (json.as_f64().unwrap(), msgpack.as_f64().unwrap());
+            assert!((json - msgpack).abs() <= json.abs() * 1e-6, "{}: {} != {}", path, json, msgpack);
+        }
+        _ => assert_eq!(json, msgpack, "{}", path),
+    }
+}
+
+/// Aller-retour par le format: la structure relue est identique à l'originale
+fn assert_round_trip<T: Serialize + DeserializeOwned>(value: &T) {
+    let expected = serde_json::to_string(value).unwrap();
+    for format in [Format::Json, Format::Msgpack] {
+        let bytes = format.encode(value).unwrap();
+        let decoded: T = match format {
+            Format::Json => serde_json::from_slice(&bytes).unwrap(),
+            Format::Msgpack => rmp_serde::from_slice(&bytes).unwrap(),
+        };
+        assert_eq!(serde_json::to_string(&decoded).unwrap(), expected, "{:?}", format);
+    }
+}
+
+#[test]
+fn records_have_same_fields_and_types() {
+    for record in records() {
+        let json = decode(Format::Json, &Format::Json.encode(&record).unwrap());
+        let msgpack = decode(Format::Msgpack, &Format::Msgpack.encode(&record).unwrap());
+
+        assert!(json.is_object());
+        assert_same_shape(record.kind(), &json, &msgpack);
+    }
+}
+
+#[test]
+fn gps_fields() {
+    let msgpack = decode(Format::Msgpack, &Format::Msgpack.encode(&gps()).unwrap());
+
+    assert_eq!(msgpack["stamp"]["utc"], "2024-06-01T12:00:00Z");
+    assert_eq!(msgpack["stamp"]["mono_us"].as_u64(), Some(1_500_000));
+    assert_eq!(msgpack["satellites"].as_u64(), Some(9));
+    assert_eq!(msgpack["fix"], true);
+    assert_eq!(msgpack["latitude"].as_f64(), Some(46.52));
+}
+
+#[test]
+fn records_round_trip() {
+    for record in records() {
+        match record {
+            Record::Imu(data) => assert_round_trip(&data),
+            Record::Mag(data) => assert_round_trip(&data),
+            Record::Analog(data) => assert_round_trip(&data),
+            Record::Gps(data) => assert_round_trip(&data),
+            Record::Modem(_) => {}
+            Record::Satellites(data) => assert_round_trip(&data),
+            Record::Power(data) => assert_round_trip(&data),
+            Record::Baro(data) => assert_round_trip(&data),
+            Record::Encoder(data) => assert_round_trip(&data),
+            Record::Range(data) => assert_round_trip(&data),
+        }
+    }
+}
+
+#[test]
+fn optional_fields() {
+    let missing = SensorStatus::default();
+    let present = SensorStatus {
+        available: false,
+        error: Some("Pas de réponse".to_string()),
+        attempts: 3,
+        restarts: 0,
+    };
+
+    for status in [missing, present] {
+        let json = decode(Format::Json, &Format::Json.encode(&status).unwrap());
+        let msgpack = decode(Format::Msgpack, &Format::Msgpack.encode(&status).unwrap());
+
+        assert_same_shape("status", &json, &msgpack);
+        assert_round_trip(&status);
+    }
+}
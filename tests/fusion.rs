@@ -0,0 +1,95 @@
+// Fusion de l'IMU et du magnétomètre: convergence du filtre de Madgwick sur des mesures
+// synthétiques d'un capteur en rotation
+
+use voiturerc::fusion;
+
+use fusion::Madgwick;
+
+/// Fréquence des mesures (Hz)
+const RATE: f32 = 100.0;
+/// Champ magnétique terrestre (x vers le nord, z vers le haut), inclinaison de 60°
+const FIELD: (f32, f32, f32) = (0.5, 0.0, -0.866);
+
+/// Mesures d'un capteur à plat, tourné de `yaw` degrés autour de la verticale et incliné de
+/// `roll` degrés: accélération (g) et champ magnétique dans le repère du capteur
+fn measure(roll: f32, yaw: f32) -> ((f32, f32, f32), (f32, f32, f32)) {
+    let (sr, cr) = roll.to_radians().sin_cos();
+    let (sy, cy) = yaw.to_radians().sin_cos();
+
+    // Repère terrestre (z vers le haut) vers le capteur: lacet puis roulis
+    let rotate = |(x, y, z): (f32, f32, f32)| {
+        let (x, y) = (cy * x + sy * y, -sy * x + cy * y);
+        (x, cr * y + sr * z, -sr * y + cr * z)
+    };
+    (rotate((0.0, 0.0, 1.0)), rotate(FIELD))
+}
+
+/// Ecart entre deux angles (°), entre 0 et 180
+fn angle_error(a: f32, b: f32) -> f32 {
+    let error = (a - b).rem_euclid(360.0);
+    error.min(360.0 - error)
+}
+
+#[test]
+fn converges_to_static_attitude() {
+    let mut filter = Madgwick::new(0.5);
+    let (accel, mag) = measure(20.0, 0.0);
+
+    for _ in 0..(20.0 * RATE) as usize {
+        filter.update((0.0, 0.0, 0.0), accel, Some(mag), 1.0 / RATE);
+    }
+
+    let attitude = filter.attitude();
+    assert!(angle_error(attitude.roll, 20.0) < 1.0, "roulis {}", attitude.roll);
+    assert!(attitude.pitch.abs() < 1.0, "tangage {}", attitude.pitch);
+    assert!(angle_error(attitude.yaw, 0.0) < 1.0, "lacet {}", attitude.yaw);
+
+    let norm: f32 = attitude.quaternion.iter().map(|v| v * v).sum();
+    assert!((norm - 1.0).abs() < 1e-4);
+}
+
+#[test]
+fn tracks_rotation_around_vertical() {
+    // Rotation à 30 °/s, capteur à plat
+    let rate = 30.0;
+    let mut filter = Madgwick::new(0.1);
+    let mut yaw = 0.0;
+
+    for _ in 0..(12.0 * RATE) as usize {
+        yaw += rate / RATE;
+        let (accel, mag) = measure(0.0, yaw);
+        filter.update((0.0, 0.0, rate), accel, Some(mag), 1.0 / RATE);
+    }
+
+    // Lacet mesuré dans le sens trigonométrique autour de la verticale
+    let attitude = filter.attitude();
+    assert!(angle_error(attitude.yaw, yaw) < 2.0, "lacet {} attendu {}", attitude.yaw, yaw);
+    assert!(attitude.roll.abs() < 1.0 && attitude.pitch.abs() < 1.0);
+}
+
+#[test]
+fn magnetometer_corrects_gyro_drift() {
+    // Biais du gyroscope de 2 °/s sur la verticale, capteur immobile
+    let (accel, mag) = measure(0.0, 90.0);
+
+    let mut gyro_only = Madgwick::new(0.1);
+    let mut fused = Madgwick::new(0.1);
+    for _ in 0..(60.0 * RATE) as usize {
+        gyro_only.update((0.0, 0.0, 2.0), accel, None, 1.0 / RATE);
+        fused.update((0.0, 0.0, 2.0), accel, Some(mag), 1.0 / RATE);
+    }
+
+    // Sans magnétomètre, le lacet dérive de 2 °/s; avec, il reste sur le nord magnétique
+    assert!(angle_error(gyro_only.attitude().yaw, 120.0) < 1.0, "lacet {}", gyro_only.attitude().yaw);
+    assert!(angle_error(fused.attitude().yaw, 90.0) < 5.0, "lacet {}", fused.attitude().yaw);
+}
+
+#[test]
+fn ignores_missing_acceleration() {
+    let mut filter = Madgwick::new(0.1);
+    filter.update((0.0, 0.0, 0.0), (0.0, 0.0, 0.0), None, 1.0 / RATE);
+    let attitude = filter.attitude();
+
+    assert_eq!(attitude.quaternion, [1.0, 0.0, 0.0, 0.0]);
+    assert_eq!((attitude.roll, attitude.pitch, attitude.yaw), (0.0, 0.0, 0.0));
+}
@@ -0,0 +1,140 @@
+// Zones GPS: distance aux limites, hystérésis, zones lentes et qualité de la position
+#![cfg(not(feature = "real-sensors"))]
+
+use voiturerc::{actuators, config, sensors};
+
+use actuators::geofence::{Fence, Geofence, Zone};
+use config::{GeofenceConfig, Severity, SlowZone};
+use sensors::reader::GpsData;
+
+const LATITUDE: f64 = 45.0;
+const LONGITUDE: f64 = 5.0;
+
+/// Position à x m à l'est et y m au nord de l'origine
+fn position(x: f64, y: f64) -> [f64; 2] {
+    let latitude = LATITUDE + (y / 6_371_000.0).to_degrees();
+    let longitude = LONGITUDE + (x / (6_371_000.0 * LATITUDE.to_radians().cos())).to_degrees();
+    [latitude, longitude]
+}
+
+/// Carré de `size` m dont le coin sud-ouest est en (x, y)
+fn square(x: f64, y: f64, size: f64) -> Vec<[f64; 2]> {
+    vec![
+        position(x, y),
+        position(x + size, y),
+        position(x + size, y + size),
+        position(x, y + size),
+    ]
+}
+
+fn gps(x: f64, y: f64) -> GpsData {
+    let [latitude, longitude] = position(x, y);
+    GpsData {
+        latitude,
+        longitude,
+        satellites: 10,
+        fix: true,
+        ..GpsData::default()
+    }
+}
+
+fn config() -> GeofenceConfig {
+    GeofenceConfig {
+        enabled: true,
+        containment: square(0.0, 0.0, 100.0),
+        slow_zones: vec![
+            SlowZone {
+                name: "paddock".to_string(),
+                max_speed: 0.5,
+                polygon: square(10.0, 10.0, 40.0),
+            },
+            SlowZone {
+                name: "stand".to_string(),
+                max_speed: 0.2,
+                polygon: square(20.0, 20.0, 10.0),
+            },
+        ],
+        ..GeofenceConfig::default()
+    }
+}
+
+#[test]
+fn signed_distance_to_boundary() {
+    let fence = Fence::new("terrain", &square(0.0, 0.0, 100.0));
+    let depth = |x, y| {
+        let [latitude, longitude] = position(x, y);
+        fence.depth(latitude, longitude)
+    };
+
+    assert!((depth(50.0, 50.0) - 50.0).abs() < 0.1);
+    assert!((depth(90.0, 30.0) - 10.0).abs() < 0.1);
+    assert!((depth(105.0, 50.0) + 5.0).abs() < 0.1);
+    // Hors d'un coin: distance au sommet
+    assert!((depth(-3.0, -4.0) + 5.0).abs() < 0.1);
+}
+
+#[test]
+fn containment_with_hysteresis() {
+    let mut geofence = Geofence::new(&config());
+
+    assert_eq!(geofence.update(Some(&gps(80.0, 50.0))), None);
+
+    let outside = geofence.update(Some(&gps(100.5, 50.0))).unwrap();
+    assert_eq!(outside.zone, Zone::Outside { name: "terrain".to_string() });
+    assert_eq!(outside.max, Some(0.0));
+    assert_eq!(outside.severity(), Severity::Critical);
+    assert!(outside.message().contains("terrain"));
+    assert!(outside.message().contains(&format!("{:.6}", outside.longitude)));
+
+    // Juste revenu à l'intérieur: toujours dans la marge
+    assert_eq!(geofence.update(Some(&gps(99.0, 50.0))), None);
+    assert_eq!(geofence.update(Some(&gps(100.2, 50.0))), None);
+
+    let back = geofence.update(Some(&gps(96.0, 50.0))).unwrap();
+    assert_eq!(back.zone, Zone::Free);
+    assert_eq!(back.max, None);
+    assert_eq!(back.severity(), Severity::Info);
+}
+
+#[test]
+fn slowest_zone_applies() {
+    let mut geofence = Geofence::new(&config());
+
+    let paddock = geofence.update(Some(&gps(15.0, 15.0))).unwrap();
+    assert_eq!(paddock.max, Some(0.5));
+    assert!(paddock.message().contains("paddock"));
+
+    let stand = geofence.update(Some(&gps(25.0, 25.0))).unwrap();
+    assert_eq!(stand.zone, Zone::Slow { name: "stand".to_string(), max: 0.2 });
+
+    // Sortie du stand dans la marge: limite inchangée
+    assert_eq!(geofence.update(Some(&gps(31.0, 25.0))), None);
+    assert_eq!(geofence.update(Some(&gps(34.0, 25.0))).unwrap().max, Some(0.5));
+
+    // Sortie du paddock au-delà de la marge
+    assert_eq!(geofence.update(Some(&gps(52.0, 25.0))), None);
+    assert_eq!(geofence.update(Some(&gps(54.0, 25.0))).unwrap().zone, Zone::Free);
+}
+
+#[test]
+fn bad_fix_caps_speed_without_containment() {
+    let mut geofence = Geofence::new(&config());
+
+    // Hors de la zone, mais position non fiable: limite prudente, pas d'arrêt
+    let weak = GpsData {
+        satellites: 4,
+        ..gps(120.0, 50.0)
+    };
+    let degraded = geofence.update(Some(&weak)).unwrap();
+    assert_eq!(degraded.zone, Zone::Degraded);
+    assert_eq!(degraded.max, Some(0.3));
+    assert_eq!(degraded.severity(), Severity::Warning);
+
+    let lost = GpsData { fix: false, ..gps(50.0, 50.0) };
+    assert_eq!(geofence.update(Some(&lost)), None);
+    assert_eq!(geofence.update(None), None);
+
+    // Position fiable retrouvée hors de la zone: arrêt
+    let outside = geofence.update(Some(&gps(120.0, 50.0))).unwrap();
+    assert_eq!(outside.max, Some(0.0));
+}
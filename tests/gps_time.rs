@@ -0,0 +1,60 @@
+// Réglage de l'horloge système sur l'heure GPS: heure utilisable, seuil et capacité CAP_SYS_TIME
+#![cfg(not(feature = "real-sensors"))]
+
+use voiturerc::{gps_time, sensors};
+
+use std::time::Duration;
+
+use chrono::{DateTime, TimeZone, Utc};
+
+use gps_time::{cap_sys_time, gps_time, offset};
+use sensors::reader::GpsData;
+
+const THRESHOLD: Duration = Duration::from_secs(2);
+
+fn gps_at(time: DateTime<Utc>) -> GpsData {
+    GpsData {
+        fix: true,
+        valid: true,
+        time: Some(time),
+        ..GpsData::default()
+    }
+}
+
+#[test]
+fn gps_time_needs_fix_and_active_rmc() {
+    let time = Utc.with_ymd_and_hms(2024, 6, 1, 12, 0, 0).unwrap();
+
+    assert_eq!(gps_time(&gps_at(time)), Some(time));
+    assert_eq!(gps_time(&GpsData { fix: false, ..gps_at(time) }), None);
+    assert_eq!(gps_time(&GpsData { valid: false, ..gps_at(time) }), None);
+    assert_eq!(gps_time(&GpsData { time: None, ..gps_at(time) }), None);
+}
+
+#[test]
+fn offset_beyond_threshold_only() {
+    let gps = Utc.with_ymd_and_hms(2024, 6, 1, 12, 0, 0).unwrap();
+
+    // Pi démarré sans RTC ni réseau: horloge en 1970
+    let boot = Utc.timestamp_opt(42, 0).unwrap();
+    assert_eq!(offset(gps, boot, THRESHOLD), Some(gps - boot));
+
+    // Horloge en avance: écart négatif
+    let ahead = gps + chrono::Duration::seconds(30);
+    assert_eq!(offset(gps, ahead, THRESHOLD), Some(chrono::Duration::seconds(-30)));
+
+    // Sous le seuil, dans les deux sens
+    assert_eq!(offset(gps, gps + chrono::Duration::milliseconds(1500), THRESHOLD), None);
+    assert_eq!(offset(gps, gps - chrono::Duration::milliseconds(1500), THRESHOLD), None);
+}
+
+#[test]
+fn cap_sys_time_from_status() {
+    let status = |mask: &str| format!("Name:\tvoiturerc\nCapPrm:\t0000000000000000\nCapEff:\t{}\n", mask);
+
+    assert!(!cap_sys_time(&status("0000000000000000")));
+    assert!(cap_sys_time(&status("0000000002000000")));
+    assert!(cap_sys_time(&status("000001ffffffffff")));
+    assert!(!cap_sys_time(&status("0000000001000000")));
+    assert!(!cap_sys_time("Name:\tvoiturerc\n"));
+}
@@ -0,0 +1,89 @@
+// Lignes envoyées à Grafana Live: champs numériques aplatis et protocole Influx
+#![cfg(not(feature = "real-sensors"))]
+
+mod common;
+
+use voiturerc::{grafana, record, sensors};
+
+use common::stamp;
+use record::{ModemData, ModemStatus, Record};
+use sensors::reader::{GpsData, ImuData, MagData};
+
+fn names(fields: &[(String, f64)]) -> Vec<&str> {
+    fields.iter().map(|(name, _)| name.as_str()).collect()
+}
+
+#[test]
+fn flattens_numeric_fields() {
+    let imu = Record::Imu(ImuData {
+        stamp: stamp(),
+        angles: (1.5, -2.25, 180.0),
+        temp: 31.0,
+        accel: (0.05, -0.1, 1.0),
+        gyro: (0.5, -1.25, 12.0),
+    });
+    let mag = Record::Mag(MagData {
+        stamp: stamp(),
+        raw: (-120, 45, 300),
+        heading: 92.5,
+        calibrated: (-120.0, 45.0, 300.0),
+        true_heading: 94.5,
+        declination: 2.0,
+    });
+
+    let imu = grafana::line::fields(&imu).unwrap();
+    let mag = grafana::line::fields(&mag).unwrap();
+
+    assert_eq!(
+        names(&imu),
+        ["angles_0", "angles_1", "angles_2", "temp", "accel_0", "accel_1", "accel_2", "gyro_0", "gyro_1", "gyro_2"]
+    );
+    assert_eq!(imu[1].1, -2.25);
+    assert_eq!(
+        names(&mag),
+        [
+            "raw_0",
+            "raw_1",
+            "raw_2",
+            "heading",
+            "calibrated_0",
+            "calibrated_1",
+            "calibrated_2",
+            "true_heading",
+            "declination"
+        ]
+    );
+    assert_eq!(mag[0].1, -120.0);
+}
+
+#[test]
+fn booleans_are_numbers() {
+    let gps = Record::Gps(GpsData {
+        stamp: stamp(),
+        speed_kmh: 25.5,
+        latitude: 46.52,
+        longitude: 6.632,
+        satellites: 9,
+        fix: true,
+        heading: 90.0,
+        time: None,
+        valid: false,
+    });
+
+    let fields = grafana::line::fields(&gps).unwrap();
+
+    assert!(fields.contains(&("fix".to_string(), 1.0)));
+    assert!(fields.contains(&("satellites".to_string(), 9.0)));
+}
+
+#[test]
+fn influx_line() {
+    let modem = Record::Modem(ModemData {
+        status: ModemStatus { quality: 80, ..ModemStatus::default() },
+        stamp: stamp(),
+    });
+
+    let line = grafana::line::line("voiturerc", &modem).unwrap().unwrap();
+
+    assert_eq!(line, "voiturerc/modem quality=80 1717243200000000000");
+}
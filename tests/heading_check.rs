@@ -0,0 +1,114 @@
+// Cohérence de la route GPS et du cap du magnétomètre: passage par 0/360°, arrêt, écart persistant
+#![cfg(not(feature = "real-sensors"))]
+
+use voiturerc::{config, heading_check};
+
+use config::HeadingCheckConfig;
+use heading_check::{wrap_180, HeadingCheck, Transition};
+
+/// Intervalle entre deux échantillons GPS (µs)
+const STEP_US: u64 = 200_000;
+
+fn check() -> HeadingCheck {
+    HeadingCheck::new(&HeadingCheckConfig {
+        enabled: true,
+        ..HeadingCheckConfig::default()
+    })
+}
+
+#[test]
+fn wraps_angles_around_north() {
+    assert_eq!(wrap_180(0.0), 0.0);
+    assert_eq!(wrap_180(359.0 - 1.0), -2.0);
+    assert_eq!(wrap_180(1.0 - 359.0), 2.0);
+    assert_eq!(wrap_180(180.0), 180.0);
+    assert_eq!(wrap_180(-180.0), 180.0);
+    assert_eq!(wrap_180(540.0), 180.0);
+    assert_eq!(wrap_180(-190.0), 170.0);
+
+    // Route juste à l'ouest du nord, cap juste à l'est: écart faible, pas 358°
+    let mut check = check();
+    let (error, _) = check.update(359.0, 5.0, 1.0, 0).unwrap();
+    assert_eq!(error.error, -2.0);
+    let (error, _) = check.update(1.0, 5.0, 359.0, STEP_US).unwrap();
+    assert_eq!(error.error, 2.0);
+    assert!(error.smoothed.abs() < 2.0, "lissé {}", error.smoothed);
+}
+
+#[test]
+fn smoothing_across_half_turn() {
+    // Ecart oscillant autour de ±180°: le lissage reste au voisinage de 180°, pas de 0°
+    let mut check = check();
+    let mut smoothed = 0.0;
+    for n in 0..50u64 {
+        let heading = if n % 2 == 0 { 1.0 } else { 359.0 };
+        let (error, _) = check.update(180.0, 5.0, heading, n * STEP_US).unwrap();
+        smoothed = error.smoothed;
+    }
+    assert!(smoothed.abs() > 178.0, "lissé {}", smoothed);
+}
+
+#[test]
+fn standstill_has_no_course() {
+    let mut check = check();
+    assert!(check.update(90.0, 0.0, 0.0, 0).is_none());
+    assert!(check.update(90.0, 1.9, 0.0, STEP_US).is_none());
+    assert!(check.update(f32::NAN, 5.0, 0.0, 2 * STEP_US).is_none());
+    assert!(check.update(90.0, 2.0, 0.0, 3 * STEP_US).is_some());
+}
+
+#[test]
+fn persistent_error_marks_calibration_suspect() {
+    let mut check = check();
+    let mut transitions = Vec::new();
+
+    // 30° d'écart en mouvement: signalé après persist_s (10 s)
+    let mut now = 0;
+    for _ in 0..=60 {
+        if let Some((_, Some(transition))) = check.update(120.0, 5.0, 90.0, now) {
+            transitions.push((now, transition));
+        }
+        now += STEP_US;
+    }
+    assert_eq!(transitions.len(), 1);
+    let (at, transition) = &transitions[0];
+    assert!(transition.suspect());
+    assert_eq!(*at, 10_000_000);
+
+    // Retour sous la moitié du seuil: calibration rétablie, une seule fois
+    transitions.clear();
+    for _ in 0..200 {
+        if let Some((_, Some(transition))) = check.update(90.0, 5.0, 90.0, now) {
+            transitions.push((now, transition));
+        }
+        now += STEP_US;
+    }
+    assert_eq!(transitions.len(), 1);
+    assert_eq!(transitions[0].1, Transition::Cleared);
+}
+
+#[test]
+fn standstill_does_not_count_towards_persistence() {
+    let mut check = check();
+    let mut now = 0;
+    let drive = |check: &mut HeadingCheck, now: &mut u64, steps: usize| {
+        let mut suspect = false;
+        for _ in 0..steps {
+            if let Some((_, Some(transition))) = check.update(120.0, 5.0, 90.0, *now) {
+                suspect |= transition.suspect();
+            }
+            *now += STEP_US;
+        }
+        suspect
+    };
+
+    // 6 s en mouvement, arrêt prolongé, puis 3 s: moins de 10 s au total
+    assert!(!drive(&mut check, &mut now, 31));
+    for _ in 0..100 {
+        assert!(check.update(120.0, 0.0, 90.0, now).is_none());
+        now += STEP_US;
+    }
+    // Après l'arrêt, le lissage repart de la nouvelle mesure (intervalle trop long)
+    assert!(!drive(&mut check, &mut now, 15));
+    assert!(drive(&mut check, &mut now, 25));
+}
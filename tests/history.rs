@@ -0,0 +1,47 @@
+use voiturerc::http::history;
+
+use history::Ring;
+
+#[test]
+fn ring_is_bounded() {
+    let mut ring = Ring::new(100);
+    for i in 0..10_000u64 {
+        ring.push(i, i);
+        assert!(ring.len() <= ring.capacity());
+    }
+
+    assert_eq!(ring.len(), 100);
+    assert_eq!(ring.capacity(), 100);
+}
+
+#[test]
+fn ring_evicts_oldest() {
+    let mut ring = Ring::new(3);
+    for i in 1..=5u64 {
+        ring.push(i * 1000, i);
+    }
+
+    assert_eq!(ring.since(0), vec![3, 4, 5]);
+}
+
+#[test]
+fn ring_since() {
+    let mut ring = Ring::new(10);
+    for i in 1..=5u64 {
+        ring.push(i * 1000, i);
+    }
+
+    assert_eq!(ring.since(3000), vec![3, 4, 5]);
+    assert_eq!(ring.since(3001), vec![4, 5]);
+    assert!(ring.since(6000).is_empty());
+}
+
+#[test]
+fn ring_minimum_capacity() {
+    let mut ring = Ring::new(0);
+    ring.push(1, "a");
+    ring.push(2, "b");
+
+    assert_eq!(ring.capacity(), 1);
+    assert_eq!(ring.since(0), vec!["b"]);
+}
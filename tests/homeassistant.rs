@@ -0,0 +1,98 @@
+// Découverte Home Assistant: sujets et contenus des entités, états et commandes d'armement
+#![cfg(not(feature = "real-sensors"))]
+
+mod common;
+
+use voiturerc::{config, mqtt, record, sensors};
+
+use common::stamp;
+use config::HomeAssistantConfig;
+use mqtt::homeassistant::{self, Topics};
+use record::{ModemData, ModemStatus, Record};
+use sensors::reader::{AnalogData, GpsData};
+use serde_json::Value;
+
+fn gps(fix: bool) -> Record {
+    Record::Gps(GpsData { fix, ..common::gps() })
+}
+
+#[test]
+fn discovery_topics() {
+    let topics = Topics::new("rc", "voiturerc");
+    let messages = homeassistant::discovery(&HomeAssistantConfig::default(), &topics, "voiturerc", "0.1.0");
+
+    let names: Vec<&str> = messages.iter().map(|message| message.topic.as_str()).collect();
+    assert_eq!(
+        names,
+        [
+            "homeassistant/sensor/voiturerc/battery/config",
+            "homeassistant/device_tracker/voiturerc/position/config",
+            "homeassistant/sensor/voiturerc/signal/config",
+            "homeassistant/switch/voiturerc/armed/config",
+        ]
+    );
+    assert!(messages.iter().all(|message| message.retain));
+
+    let switch: Value = serde_json::from_str(&messages[3].payload).unwrap();
+    assert_eq!(switch["unique_id"], "voiturerc_armed");
+    assert_eq!(switch["command_topic"], "rc/voiturerc/armed/set");
+    assert_eq!(switch["state_topic"], "rc/voiturerc/armed");
+    assert_eq!(switch["availability_topic"], "rc/voiturerc/availability");
+    assert_eq!(switch["device"]["identifiers"][0], "voiturerc");
+}
+
+#[test]
+fn configured_entities_only() {
+    let config = HomeAssistantConfig {
+        entities: vec!["signal".to_string()],
+        ..HomeAssistantConfig::default()
+    };
+    let topics = Topics::new("rc", "ma voiture");
+
+    let messages = homeassistant::discovery(&config, &topics, "ma voiture", "0.1.0");
+
+    assert_eq!(messages.len(), 1);
+    assert_eq!(messages[0].topic, "homeassistant/sensor/ma_voiture/signal/config");
+}
+
+#[test]
+fn states() {
+    let topics = Topics::new("rc", "voiturerc");
+    let battery = Record::Analog(AnalogData {
+        stamp: stamp(),
+        battery: 7.4,
+        ..AnalogData::default()
+    });
+    let modem = Record::Modem(ModemData {
+        status: ModemStatus { quality: 80, ..ModemStatus::default() },
+        stamp: stamp(),
+    });
+
+    let (object, battery) = homeassistant::state(&topics, &battery).unwrap();
+    assert_eq!(object, "battery");
+    assert_eq!(battery.topic, "rc/voiturerc/battery");
+    assert_eq!(battery.payload, "7.40");
+
+    let (_, signal) = homeassistant::state(&topics, &modem).unwrap();
+    assert_eq!(signal.payload, "80");
+
+    let (_, position) = homeassistant::state(&topics, &gps(true)).unwrap();
+    let position: Value = serde_json::from_str(&position.payload).unwrap();
+    assert_eq!(position["latitude"], 46.52);
+    assert_eq!(position["longitude"], 6.632);
+
+    // Pas de position sans fix
+    assert!(homeassistant::state(&topics, &gps(false)).is_none());
+}
+
+#[test]
+fn arm_commands() {
+    assert!(homeassistant::parse_arm(b"ON").unwrap());
+    assert!(!homeassistant::parse_arm(b"OFF").unwrap());
+    assert!(homeassistant::parse_arm(b"on").is_err());
+    assert!(homeassistant::parse_arm(&[0xff]).is_err());
+
+    let topics = Topics::new("rc", "voiturerc");
+    let state = homeassistant::armed(&topics, false);
+    assert_eq!((state.topic.as_str(), state.payload.as_str(), state.retain), ("rc/voiturerc/armed", "OFF", true));
+}
@@ -0,0 +1,51 @@
+// Résumé /status du serveur HTTP local: dernières valeurs et état du contrôle
+#![cfg(not(feature = "real-sensors"))]
+
+use voiturerc::{actuators, http, record, sensors};
+
+use actuators::arbiter::{ControlState, SpeedLimit};
+use actuators::Control;
+use http::status::Status;
+use record::{ModemData, ModemStatus};
+use sensors::reader::Data;
+
+#[test]
+fn status_summarizes_latest_values_and_control() {
+    let mut data = Data::default();
+    data.gps.fix = true;
+    data.gps.latitude = 45.5;
+    data.gps.longitude = 4.25;
+    data.gps.satellites = 9;
+    data.imu.angles = (1.5, -2.0, 90.0);
+    data.analog.battery = 7.4;
+    data.mag.heading = 120.0;
+    data.mag.true_heading = 122.5;
+    let modem = ModemData {
+        status: ModemStatus { quality: 72, ..ModemStatus::default() },
+        ..ModemData::default()
+    };
+    let control = ControlState {
+        input: Control { steer: 0.25, speed: 0.8 },
+        output: Control { steer: 0.25, speed: 0.5 },
+        limit: Some(SpeedLimit {
+            max: 0.5,
+            cause: "thermal.esc".to_string(),
+        }),
+        ..ControlState::default()
+    };
+
+    let status = Status::new(&data, &modem, &control, true);
+    assert!(status.gps.fix);
+    assert_eq!((status.gps.latitude, status.gps.longitude, status.gps.satellites), (45.5, 4.25, 9));
+    assert_eq!(status.angles, (1.5, -2.0, 90.0));
+    assert_eq!((status.battery_v, status.heading, status.true_heading), (7.4, 120.0, 122.5));
+    assert_eq!(status.modem_quality, 72);
+    assert!(status.control.armed);
+    assert_eq!((status.control.speed, status.control.output_speed), (0.8, 0.5));
+    assert_eq!(status.control.limit, Some(0.5));
+    assert_eq!(status.control.limit_cause.as_deref(), Some("thermal.esc"));
+
+    let json = serde_json::to_value(&status).unwrap();
+    assert_eq!(json["control"]["limit_cause"], "thermal.esc");
+    assert_eq!(json["gps"]["satellites"], 9);
+}
@@ -0,0 +1,64 @@
+// Réglages du MPU6050: registres, combinaisons refusées et conversion des mesures brutes
+
+use voiturerc::sensors::imu::settings;
+
+use settings::Settings;
+
+#[test]
+fn accel_scale_follows_range() {
+    for (range, sel, lsb) in [(2, 0x00, 16384.0), (4, 0x01, 8192.0), (8, 0x02, 4096.0), (16, 0x03, 2048.0)] {
+        let settings = Settings::new(20, range, 250, 10).unwrap();
+        assert_eq!((settings.accel_sel, settings.accel_lsb), (sel, lsb), "±{} g", range);
+        // Pleine échelle
+        assert!((settings.accel_g(32767.0) - range as f32).abs() < 0.01, "±{} g", range);
+    }
+
+    let settings = Settings::new(20, 4, 250, 10).unwrap();
+    assert_eq!(settings.accel_g(8192.0), 1.0);
+    assert_eq!(settings.accel_g(-4096.0), -0.5);
+}
+
+#[test]
+fn gyro_scale_follows_range() {
+    for (range, sel, lsb) in [(250, 0x00, 131.0), (500, 0x01, 65.5), (1000, 0x02, 32.8), (2000, 0x03, 16.4)] {
+        let settings = Settings::new(20, 2, range, 10).unwrap();
+        assert_eq!((settings.gyro_sel, settings.gyro_lsb), (sel, lsb), "±{} °/s", range);
+        assert!((settings.gyro_dps(32767.0) - range as f32).abs() / (range as f32) < 0.01, "±{} °/s", range);
+    }
+
+    let settings = Settings::new(20, 2, 500, 10).unwrap();
+    assert_eq!(settings.gyro_dps(655.0), 10.0);
+    assert_eq!(settings.gyro_dps(-131.0), -2.0);
+}
+
+#[test]
+fn rate_divider_and_filter() {
+    let settings = Settings::new(100, 2, 250, 42).unwrap();
+    assert_eq!((settings.dlpf_cfg, settings.divider), (0x03, 9));
+
+    // Défaut: 20 Hz, filtre à 10 Hz
+    let settings = Settings::new(20, 2, 250, 10).unwrap();
+    assert_eq!((settings.dlpf_cfg, settings.divider), (0x05, 49));
+
+    // Sans filtre, le gyroscope sort à 8 kHz
+    let settings = Settings::new(1000, 2, 250, 256).unwrap();
+    assert_eq!((settings.dlpf_cfg, settings.divider), (0x00, 7));
+}
+
+#[test]
+fn rejects_invalid_combinations() {
+    // Plages et filtre hors des valeurs du capteur
+    assert!(Settings::new(20, 3, 250, 10).is_err());
+    assert!(Settings::new(20, 2, 300, 10).is_err());
+    assert!(Settings::new(20, 2, 250, 12).is_err());
+
+    // Fréquence nulle, au-delà de l'accéléromètre, ou pas un diviseur de la sortie du gyroscope
+    assert!(Settings::new(0, 2, 250, 5).is_err());
+    assert!(Settings::new(2000, 2, 250, 256).is_err());
+    assert!(Settings::new(30, 2, 250, 10).is_err());
+
+    // Filtre au-delà de la moitié de la fréquence des mesures (repliement)
+    assert!(Settings::new(20, 2, 250, 20).is_err());
+    assert!(Settings::new(100, 2, 250, 98).is_err());
+    assert!(Settings::new(500, 2, 250, 256).is_err());
+}
@@ -0,0 +1,249 @@
+// Fichiers du journal JSON lines: rotation, compression, budget disque et arrêt brutal
+use voiturerc::{clock, jsonl::file};
+
+use std::fs;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::thread::sleep;
+use std::time::{Duration, Instant};
+
+use chrono::Utc;
+use clock::Clock;
+use file::{Limits, Sink};
+use flate2::read::GzDecoder;
+
+/// Dossier du test lancé dans un processus enfant, tué pendant l'écriture
+const CHILD_DIRECTORY: &str = "RC_TELEMETRIE_JSONL_CHILD";
+
+/// Avance l'horloge virtuelle
+fn advance(clock: &Clock, duration: Duration) {
+    clock.set(clock.elapsed() + duration);
+}
+
+fn limits() -> Limits {
+    Limits {
+        max_bytes: 1024 * 1024,
+        max_age: None,
+        gzip: false,
+        budget: None,
+    }
+}
+
+/// Dossier temporaire propre au test
+fn directory(name: &str) -> PathBuf {
+    let directory = std::env::temp_dir().join(format!("rc-telemetrie-jsonl-{}-{}", name, std::process::id()));
+    let _ = fs::remove_dir_all(&directory);
+    fs::create_dir_all(&directory).unwrap();
+    directory
+}
+
+/// Fichiers du dossier, triés par nom
+fn files(directory: &Path) -> Vec<String> {
+    let mut files: Vec<String> = fs::read_dir(directory)
+        .unwrap()
+        .map(|entry| entry.unwrap().file_name().to_string_lossy().into_owned())
+        .collect();
+    files.sort();
+    files
+}
+
+/// Ligne de taille fixe
+fn line(n: u64) -> Vec<u8> {
+    format!(r#"{{"type":"imu","ts":"2024-06-01T12:00:00Z","run_id":"run-1","n":{:8}}}"#, n).into_bytes()
+}
+
+#[test]
+fn rotates_by_size() {
+    let directory = directory("size");
+    let length = line(0).len() as u64 + 1;
+    let mut sink = Sink::new(
+        &directory,
+        "run-1",
+        Limits {
+            max_bytes: length * 4,
+            ..limits()
+        },
+        &Clock::start(),
+    )
+    .unwrap();
+
+    for n in 0..10 {
+        sink.write(&line(n)).unwrap();
+    }
+    sink.close().unwrap();
+
+    assert_eq!(files(&directory), ["run-1.0000.jsonl", "run-1.0001.jsonl", "run-1.0002.jsonl"]);
+    let first = fs::read_to_string(directory.join("run-1.0000.jsonl")).unwrap();
+    assert_eq!(first.lines().count(), 4);
+    assert_eq!(first.len() as u64, length * 4);
+}
+
+#[test]
+fn rotates_by_age() {
+    let directory = directory("age");
+    let clock = Clock::virtual_at(Utc::now());
+    let mut sink = Sink::new(
+        &directory,
+        "run-1",
+        Limits {
+            max_age: Some(Duration::from_secs(60)),
+            ..limits()
+        },
+        &clock,
+    )
+    .unwrap();
+
+    sink.write(&line(0)).unwrap();
+    advance(&clock, Duration::from_secs(59));
+    sink.flush().unwrap();
+    sink.write(&line(1)).unwrap();
+
+    advance(&clock, Duration::from_secs(1));
+    // Fichier trop ancien terminé même sans nouvelle ligne
+    sink.flush().unwrap();
+    assert_eq!(files(&directory), ["run-1.0000.jsonl"]);
+
+    sink.write(&line(2)).unwrap();
+    sink.close().unwrap();
+
+    assert_eq!(files(&directory), ["run-1.0000.jsonl", "run-1.0001.jsonl"]);
+    let first = fs::read_to_string(directory.join("run-1.0000.jsonl")).unwrap();
+    assert_eq!(first.lines().count(), 2);
+}
+
+#[test]
+fn compresses_finished_files() {
+    let directory = directory("gzip");
+    let mut sink = Sink::new(
+        &directory,
+        "run-1",
+        Limits {
+            gzip: true,
+            ..limits()
+        },
+        &Clock::start(),
+    )
+    .unwrap();
+
+    for n in 0..100 {
+        sink.write(&line(n)).unwrap();
+    }
+    sink.close().unwrap();
+
+    assert_eq!(files(&directory), ["run-1.0000.jsonl.gz"]);
+    let mut content = String::new();
+    GzDecoder::new(fs::File::open(directory.join("run-1.0000.jsonl.gz")).unwrap())
+        .read_to_string(&mut content)
+        .unwrap();
+    assert_eq!(content.lines().count(), 100);
+    assert_eq!(content.lines().last().unwrap().as_bytes(), line(99));
+}
+
+#[test]
+fn prunes_oldest_files_to_budget() {
+    let directory = directory("budget");
+    let length = line(0).len() as u64 + 1;
+    let mut sink = Sink::new(
+        &directory,
+        "run-1",
+        Limits {
+            max_bytes: length * 2,
+            budget: Some(length * 5),
+            ..limits()
+        },
+        &Clock::start(),
+    )
+    .unwrap();
+
+    for n in 0..20 {
+        sink.write(&line(n)).unwrap();
+    }
+    sink.close().unwrap();
+
+    let total: u64 = files(&directory)
+        .iter()
+        .map(|file| fs::metadata(directory.join(file)).unwrap().len())
+        .sum();
+    assert!(total <= length * 5, "{} octets", total);
+    // Les plus récents sont conservés
+    assert_eq!(files(&directory), ["run-1.0008.jsonl", "run-1.0009.jsonl"]);
+}
+
+#[test]
+fn repairs_truncated_last_line() {
+    let directory = directory("repair");
+    let path = directory.join("run-1.0000.jsonl");
+    let mut content = Vec::new();
+    for n in 0..3 {
+        content.extend(line(n));
+        content.push(b'\n');
+    }
+    let complete = content.len() as u64;
+    content.extend(&line(3)[..20]);
+    fs::write(&path, &content).unwrap();
+
+    assert_eq!(file::repair(&path).unwrap(), 20);
+    assert_eq!(fs::metadata(&path).unwrap().len(), complete);
+    // Fichier complet: rien à retirer
+    assert_eq!(file::repair(&path).unwrap(), 0);
+}
+
+/// Ecrit sans fin dans le dossier donné par l'environnement, jusqu'à être tué
+#[test]
+#[ignore]
+fn child_writer() {
+    let Ok(directory) = std::env::var(CHILD_DIRECTORY) else {
+        return;
+    };
+
+    let limits = Limits {
+        max_bytes: u64::MAX,
+        ..limits()
+    };
+    let mut sink = Sink::new(Path::new(&directory), "run-1", limits, &Clock::start()).unwrap();
+    for n in 0.. {
+        sink.write(&line(n)).unwrap();
+    }
+}
+
+#[test]
+fn killed_during_write() {
+    let directory = directory("killed");
+    let path = directory.join("run-1.0000.jsonl");
+
+    let mut child = Command::new(std::env::current_exe().unwrap())
+        .args(["child_writer", "--exact", "--ignored", "--quiet"])
+        .stdout(std::process::Stdio::null())
+        .env(CHILD_DIRECTORY, &directory)
+        .spawn()
+        .unwrap();
+
+    let start = Instant::now();
+    while fs::metadata(&path).map(|m| m.len()).unwrap_or(0) < 256 * 1024 {
+        assert!(start.elapsed() < Duration::from_secs(30), "le processus enfant n'écrit pas");
+        sleep(Duration::from_millis(5));
+    }
+    child.kill().unwrap();
+    child.wait().unwrap();
+
+    // Toutes les lignes complètes sont lisibles, seule la dernière peut être tronquée
+    let content = fs::read(&path).unwrap();
+    let lines: Vec<&[u8]> = content.split(|&byte| byte == b'\n').collect();
+    let (last, complete) = lines.split_last().unwrap();
+    for (n, line) in complete.iter().enumerate() {
+        let value: serde_json::Value = serde_json::from_slice(line).unwrap();
+        assert_eq!(value["n"], n as u64);
+    }
+
+    // Reprise au démarrage suivant: ligne incomplète retirée, fichier compressé
+    file::recover(&directory, true).unwrap();
+
+    assert_eq!(files(&directory), ["run-1.0000.jsonl.gz"]);
+    let mut recovered = Vec::new();
+    GzDecoder::new(fs::File::open(directory.join("run-1.0000.jsonl.gz")).unwrap())
+        .read_to_end(&mut recovered)
+        .unwrap();
+    assert_eq!(recovered.len(), content.len() - last.len());
+    assert!(recovered.is_empty() || recovered.ends_with(b"\n"));
+}
@@ -0,0 +1,172 @@
+// Perte de la liaison de contrôle: politique appliquée, désarmement et retour des commandes
+#![cfg(not(feature = "real-sensors"))]
+
+use voiturerc::{actuators, clock, config};
+
+use std::time::Duration;
+
+use chrono::Utc;
+use futures::FutureExt;
+
+use actuators::arbiter::{Arbiter, LinkEvent, Next};
+use actuators::Control;
+use clock::Clock;
+use config::LinkLossPolicy;
+
+const DEAD_TIMEOUT: Duration = Duration::from_millis(50);
+
+/// Avance l'horloge virtuelle
+fn advance(clock: &Clock, duration: Duration) {
+    clock.set(clock.elapsed() + duration);
+}
+
+#[tokio::test]
+async fn no_link_loss_before_first_command() {
+    let (commands, mut arbiter) = Arbiter::new(&Clock::start(), DEAD_TIMEOUT, LinkLossPolicy::StopAndDisarm);
+    let mut link = commands.link();
+
+    assert!(matches!(arbiter.next().await, Next::Timeout));
+    assert!(link.try_recv().is_err());
+    assert!(*commands.armed().borrow());
+}
+
+#[tokio::test]
+async fn stop_keeps_vehicle_armed() {
+    let (commands, mut arbiter) = Arbiter::new(&Clock::start(), DEAD_TIMEOUT, LinkLossPolicy::Stop);
+    let mut link = commands.link();
+    let control = Control { steer: 0.0, speed: 0.5 };
+
+    commands.submit("db", control).unwrap();
+    assert!(matches!(arbiter.next().await, Next::Command(_)));
+    assert!(matches!(arbiter.next().await, Next::Timeout));
+    assert!(matches!(arbiter.next().await, Next::Timeout));
+
+    // Une seule perte par coupure
+    assert_eq!(link.try_recv().unwrap(), LinkEvent::Lost(LinkLossPolicy::Stop));
+    assert!(link.try_recv().is_err());
+    assert!(*commands.armed().borrow());
+
+    commands.submit("db", control).unwrap();
+    let LinkEvent::Restored { outage, policy } = link.try_recv().unwrap() else {
+        panic!("retour attendu");
+    };
+    assert!(outage >= DEAD_TIMEOUT * 2);
+    assert_eq!(policy, LinkLossPolicy::Stop);
+}
+
+#[tokio::test]
+async fn stop_and_disarm_until_rearmed() {
+    let (commands, mut arbiter) = Arbiter::new(&Clock::start(), DEAD_TIMEOUT, LinkLossPolicy::StopAndDisarm);
+    let mut link = commands.link();
+    let control = Control { steer: 0.0, speed: 0.5 };
+
+    commands.submit("db", control).unwrap();
+    assert!(matches!(arbiter.next().await, Next::Command(_)));
+    assert!(matches!(arbiter.next().await, Next::Timeout));
+    assert_eq!(link.try_recv().unwrap(), LinkEvent::Lost(LinkLossPolicy::StopAndDisarm));
+    assert!(!*commands.armed().borrow());
+
+    // Commande refusée, mais la liaison est rétablie
+    assert!(commands.submit("db", control).is_err());
+    assert!(matches!(link.try_recv().unwrap(), LinkEvent::Restored { .. }));
+
+    commands.arm("test", true);
+    commands.submit("db", control).unwrap();
+    assert!(matches!(arbiter.next().await, Next::Command(_)));
+}
+
+#[tokio::test]
+async fn other_source_suppresses_link_loss() {
+    let (commands, mut arbiter) = Arbiter::new(&Clock::start(), DEAD_TIMEOUT, LinkLossPolicy::StopAndDisarm);
+    let mut link = commands.link();
+    let control = Control { steer: 0.0, speed: 0.5 };
+
+    // Base de donnée muette, gRPC actif
+    commands.submit("db", control).unwrap();
+    for _ in 0..5 {
+        tokio::time::sleep(DEAD_TIMEOUT / 2).await;
+        commands.submit("grpc", control).unwrap();
+        assert!(matches!(arbiter.next().await, Next::Command(_)));
+    }
+    assert!(link.try_recv().is_err());
+    assert!(*commands.armed().borrow());
+}
+
+/// Homme mort sur une horloge virtuelle: séquence exacte, sans attente réelle
+#[tokio::test]
+async fn dead_timeout_failsafe_sequence() {
+    let clock = Clock::virtual_at(Utc::now());
+    let dead_timeout = Duration::from_millis(500);
+    let (commands, mut arbiter) = Arbiter::new(&clock, dead_timeout, LinkLossPolicy::Stop);
+    let mut link = commands.link();
+    let control = Control { steer: 0.0, speed: 0.5 };
+
+    commands.submit("db", control).unwrap();
+    assert!(matches!(arbiter.next().now_or_never(), Some(Next::Command(_))));
+
+    // Aucune commande: neutre exactement au délai de l'homme mort
+    let mut next = Box::pin(arbiter.next());
+    assert!(next.as_mut().now_or_never().is_none());
+    advance(&clock, dead_timeout - Duration::from_millis(1));
+    assert!(next.as_mut().now_or_never().is_none());
+    assert!(link.try_recv().is_err());
+    advance(&clock, Duration::from_millis(1));
+    assert!(matches!(next.as_mut().now_or_never(), Some(Next::Timeout)));
+    drop(next);
+    assert_eq!(link.try_recv().unwrap(), LinkEvent::Lost(LinkLossPolicy::Stop));
+
+    // Le neutre est répété à chaque délai, une seule perte signalée
+    let mut next = Box::pin(arbiter.next());
+    assert!(next.as_mut().now_or_never().is_none());
+    advance(&clock, dead_timeout);
+    assert!(matches!(next.as_mut().now_or_never(), Some(Next::Timeout)));
+    drop(next);
+    assert!(link.try_recv().is_err());
+
+    // Retour de la liaison: durée de la coupure exacte
+    advance(&clock, Duration::from_millis(250));
+    commands.submit("db", control).unwrap();
+    assert_eq!(
+        link.try_recv().unwrap(),
+        LinkEvent::Restored {
+            outage: Duration::from_millis(1250),
+            policy: LinkLossPolicy::Stop,
+        }
+    );
+    assert!(matches!(arbiter.next().now_or_never(), Some(Next::Command(_))));
+    assert_eq!(commands.last_command(), Some(Duration::ZERO));
+}
+
+#[tokio::test]
+async fn queued_command_older_than_dead_timeout_is_dropped() {
+    let clock = Clock::virtual_at(Utc::now());
+    let dead_timeout = Duration::from_millis(500);
+    let (commands, mut arbiter) = Arbiter::new(&clock, dead_timeout, LinkLossPolicy::Stop);
+
+    commands.submit("db", Control { steer: 0.0, speed: 0.5 }).unwrap();
+    advance(&clock, dead_timeout + Duration::from_millis(1));
+
+    // Commande ignorée, l'attente reprend
+    let mut next = Box::pin(arbiter.next());
+    assert!(next.as_mut().now_or_never().is_none());
+    advance(&clock, dead_timeout);
+    assert!(matches!(next.as_mut().now_or_never(), Some(Next::Timeout)));
+}
+
+#[tokio::test]
+async fn interrupted_wait_keeps_dead_timeout() {
+    let (commands, mut arbiter) = Arbiter::new(&Clock::start(), DEAD_TIMEOUT, LinkLossPolicy::Stop);
+    commands.submit("db", Control { steer: 0.0, speed: 0.5 }).unwrap();
+    assert!(matches!(arbiter.next().await, Next::Command(_)));
+
+    // Attente interrompue régulièrement (mise à jour de la rampe): le délai n'est pas relancé
+    let start = tokio::time::Instant::now();
+    let next = loop {
+        tokio::select! {
+            next = arbiter.next() => break next,
+            _ = tokio::time::sleep(DEAD_TIMEOUT / 5) => {}
+        }
+    };
+    assert!(matches!(next, Next::Timeout));
+    assert!(start.elapsed() < DEAD_TIMEOUT * 2, "{:?}", start.elapsed());
+}
@@ -0,0 +1,79 @@
+// Nouvelles tentatives d'un live en échec: attente croissante, remise à zéro à la première
+// notification reçue.
+
+use voiturerc::live;
+
+use std::time::Duration;
+
+use tokio::time::Instant;
+use tokio_util::sync::CancellationToken;
+
+use live::{Live, RETRY_MIN};
+
+/// Live simulé: la création échoue `failures` fois avant de réussir
+struct MockDatabase {
+    failures: u32,
+    attempts: Vec<Instant>,
+}
+
+impl MockDatabase {
+    async fn live_control(&mut self) -> anyhow::Result<()> {
+        self.attempts.push(Instant::now());
+        if self.attempts.len() as u32 <= self.failures {
+            return Err(anyhow::anyhow!("table control absente"));
+        }
+        Ok(())
+    }
+}
+
+/// Crée le live comme la tâche de contrôle, retourne les attentes entre les tentatives
+async fn open(live: &mut Live, db: &mut MockDatabase, token: &CancellationToken) -> Vec<Duration> {
+    db.attempts.clear();
+    loop {
+        match db.live_control().await {
+            Ok(()) => break,
+            Err(e) => assert!(live.failed(token, e).await),
+        }
+    }
+    db.attempts.windows(2).map(|w| w[1] - w[0]).collect()
+}
+
+#[tokio::test]
+async fn backs_off_until_live_is_created() {
+    let token = CancellationToken::new();
+    let mut live = Live::new("control");
+    let mut db = MockDatabase {
+        failures: 2,
+        attempts: Vec::new(),
+    };
+
+    let waits = open(&mut live, &mut db, &token).await;
+    assert_eq!(db.attempts.len(), 3);
+    for (wait, expected) in waits.iter().zip([RETRY_MIN, RETRY_MIN * 2]) {
+        assert!(*wait >= expected, "{:?} < {:?}", wait, expected);
+        assert!(*wait < expected + RETRY_MIN, "{:?} >= {:?}", wait, expected + RETRY_MIN);
+    }
+
+    // Live créé mais terminé sans notification: toujours en échec, attente encore doublée
+    let start = Instant::now();
+    assert!(live.failed(&token, "flux terminé").await);
+    assert!(start.elapsed() >= RETRY_MIN * 4);
+
+    // Notification reçue: attente au minimum pour l'échec suivant
+    live.received();
+    db.failures = 1;
+    let waits = open(&mut live, &mut db, &token).await;
+    assert_eq!(waits.len(), 1);
+    assert!(waits[0] >= RETRY_MIN && waits[0] < RETRY_MIN * 2, "{:?}", waits[0]);
+}
+
+#[tokio::test]
+async fn cancelled_while_waiting() {
+    let token = CancellationToken::new();
+    let mut live = Live::new("control");
+
+    token.cancel();
+    let start = Instant::now();
+    assert!(!live.failed(&token, "création impossible").await);
+    assert!(start.elapsed() < RETRY_MIN);
+}
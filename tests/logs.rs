@@ -0,0 +1,106 @@
+// Fichiers journaux: rotation, reprise du fichier précédent, écriture du journal du programme
+// (panique, arrêt) et messages répétés
+use voiturerc::{config, logs, logs::repeat, logs::rolling};
+
+use std::path::{Path, PathBuf};
+
+use config::LogsConfig;
+use logs::subscriber::{self, LogFormat};
+use repeat::Repeated;
+use rolling::{LogUsage, Rolling, LOG_FILE};
+
+/// Dossier temporaire propre au test
+fn directory(name: &str) -> PathBuf {
+    let directory = std::env::temp_dir().join(format!("rc-telemetrie-logs-{}-{}", name, std::process::id()));
+    let _ = std::fs::remove_dir_all(&directory);
+    directory
+}
+
+fn read(directory: &Path, suffix: &str) -> String {
+    std::fs::read_to_string(directory.join(format!("{}{}", LOG_FILE, suffix))).unwrap()
+}
+
+#[test]
+fn rotates_and_keeps_max_files() {
+    let directory = directory("rotate");
+    let mut rolling = Rolling::open(&directory, 16, 3).unwrap();
+
+    // Lignes de 8 octets: deux par fichier
+    for n in 0..7 {
+        rolling.write(format!("ligne {}\n", n).as_bytes()).unwrap();
+    }
+    rolling.sync().unwrap();
+
+    assert_eq!(read(&directory, ""), "ligne 6\n");
+    assert_eq!(read(&directory, ".1"), "ligne 4\nligne 5\n");
+    assert_eq!(read(&directory, ".2"), "ligne 2\nligne 3\n");
+    assert!(!directory.join(format!("{}.3", LOG_FILE)).exists());
+
+    assert_eq!(rolling::usage(&directory).unwrap(), LogUsage { bytes: 40, files: 3 });
+    std::fs::remove_dir_all(&directory).unwrap();
+}
+
+#[test]
+fn appends_to_previous_run() {
+    let directory = directory("append");
+    {
+        let mut rolling = Rolling::open(&directory, 1024, 2).unwrap();
+        rolling.write(b"avant\n").unwrap();
+    }
+
+    let mut rolling = Rolling::open(&directory, 1024, 2).unwrap();
+    rolling.write(b"apres\n").unwrap();
+    assert_eq!(read(&directory, ""), "avant\napres\n");
+    std::fs::remove_dir_all(&directory).unwrap();
+}
+
+#[test]
+fn oversized_write_starts_a_new_file() {
+    let directory = directory("oversized");
+    let mut rolling = Rolling::open(&directory, 4, 1).unwrap();
+
+    // Un seul fichier conservé: le précédent est supprimé
+    rolling.write(b"abc").unwrap();
+    rolling.write(b"0123456789").unwrap();
+    assert_eq!(read(&directory, ""), "0123456789");
+    assert_eq!(rolling::usage(&directory).unwrap().files, 1);
+    std::fs::remove_dir_all(&directory).unwrap();
+}
+
+#[test]
+fn repeated_error_is_reported_once_until_cleared() {
+    let mut repeated = Repeated::default();
+    assert!(repeated.first());
+    assert!(!repeated.first());
+    assert!(!repeated.first());
+    assert_eq!(repeated.clear(), 3);
+    assert_eq!(repeated.clear(), 0);
+    assert!(repeated.first());
+}
+
+#[test]
+fn journal_written_on_panic_and_close() {
+    subscriber::init(LogFormat::Text);
+    let directory = directory("journal");
+    let config = LogsConfig {
+        enabled: true,
+        directory: directory.clone(),
+        ..LogsConfig::default()
+    };
+    let logs = logs::start(&config).unwrap();
+
+    // Lignes en attente écrites par le crochet de panique, sans attendre l'arrêt
+    tracing::info!(target: "test", "avant la panique");
+    let _ = std::thread::spawn(|| panic!("panique du test")).join();
+    assert!(read(&directory, "").contains("INFO test: avant la panique"));
+    let panic = std::fs::read_to_string(directory.join("panic.log")).unwrap();
+    assert!(panic.contains("Message: panique du test"), "{}", panic);
+
+    // L'écriture reprend après la panique, jusqu'à la fermeture
+    tracing::info!(target: "test", "après la panique");
+    logs.close();
+    assert!(read(&directory, "").contains("INFO test: après la panique"));
+
+    tracing::info!(target: "test", "après la fermeture");
+    assert!(!read(&directory, "").contains("après la fermeture"));
+}
@@ -0,0 +1,206 @@
+// Protection de la batterie: paliers, chute de tension sous charge et coupure définitive
+#![cfg(not(feature = "real-sensors"))]
+
+use voiturerc::{actuators, clock, config};
+
+use std::time::{Duration, Instant};
+
+use actuators::arbiter::{Arbiter, Next};
+use actuators::low_voltage::{LowVoltage, Sample, Step};
+use actuators::Control;
+use clock::Clock;
+use config::{LinkLossPolicy, LowVoltageConfig};
+
+fn idle(voltage: f32) -> Sample {
+    Sample {
+        voltage,
+        current: None,
+        throttle: 0.0,
+    }
+}
+
+fn at(start: Instant, s: u64) -> Instant {
+    start + Duration::from_secs(s)
+}
+
+#[test]
+fn escalates_then_latches() {
+    // 2 éléments, 3.3 V minimum, 10 s et 3 mesures par palier
+    let mut guard = LowVoltage::new(&LowVoltageConfig::default());
+    let start = Instant::now();
+
+    assert_eq!(guard.update(&idle(7.0), at(start, 0)), None);
+    assert_eq!(guard.update(&idle(6.5), at(start, 1)), None);
+    // Remontée au-dessus du seuil: la période repart
+    assert_eq!(guard.update(&idle(6.7), at(start, 5)), None);
+    assert_eq!(guard.update(&idle(6.5), at(start, 6)), None);
+    assert_eq!(guard.update(&idle(6.5), at(start, 15)), None);
+
+    let step = guard.update(&idle(6.5), at(start, 16)).unwrap();
+    assert_eq!(
+        step,
+        Step::Limit {
+            stage: 1,
+            max: 0.6,
+            cell_v: 3.25
+        }
+    );
+    assert_eq!(step.kind(), "low_voltage");
+    assert_eq!(step.message(), "Tension basse (3.25 V/élément): vitesse limitée à 60 % (palier 1)");
+
+    // Chaque palier demande une nouvelle période complète
+    assert_eq!(guard.update(&idle(6.5), at(start, 25)), None);
+    assert_eq!(guard.update(&idle(6.5), at(start, 26)).unwrap().max(), 0.3);
+
+    assert_eq!(guard.update(&idle(6.4), at(start, 30)), None);
+    let step = guard.update(&idle(6.4), at(start, 36)).unwrap();
+    assert_eq!(step, Step::Cutoff { cell_v: 3.2 });
+    assert_eq!(step.max(), 0.0);
+    assert_eq!(step.kind(), "low_voltage_cutoff");
+    assert!(guard.latched());
+
+    // Jamais levée, même batterie remplacée
+    assert_eq!(guard.update(&idle(8.4), at(start, 100)), None);
+    assert_eq!(guard.update(&idle(6.0), at(start, 200)), None);
+    assert!(guard.latched());
+}
+
+#[test]
+fn hysteresis_keeps_period_running() {
+    let config = LowVoltageConfig {
+        sustain_s: 3.0,
+        throttle_limits: vec![0.3],
+        ..LowVoltageConfig::default()
+    };
+    let mut guard = LowVoltage::new(&config);
+    let start = Instant::now();
+
+    // Au seuil sans période en cours: rien
+    assert_eq!(guard.update(&idle(6.62), at(start, 0)), None);
+    // Oscillation autour du seuil (3.25 - 3.31 V/élément): la période continue
+    assert_eq!(guard.update(&idle(6.5), at(start, 1)), None);
+    assert_eq!(guard.update(&idle(6.62), at(start, 2)), None);
+    assert_eq!(guard.update(&idle(6.5), at(start, 3)), None);
+    assert_eq!(guard.update(&idle(6.62), at(start, 4)).unwrap().max(), 0.3);
+
+    // Remontée au-delà de l'hystérésis (3.35 V/élément): la période repart
+    assert_eq!(guard.update(&idle(6.5), at(start, 5)), None);
+    assert_eq!(guard.update(&idle(6.7), at(start, 6)), None);
+    assert_eq!(guard.update(&idle(6.5), at(start, 7)), None);
+    assert_eq!(guard.update(&idle(6.5), at(start, 9)), None);
+    assert!(guard.update(&idle(6.5), at(start, 10)).is_some());
+    assert!(guard.latched());
+}
+
+#[test]
+fn ignores_sag_under_load() {
+    let config = LowVoltageConfig {
+        sustain_s: 2.0,
+        throttle_limits: Vec::new(),
+        ..LowVoltageConfig::default()
+    };
+    let start = Instant::now();
+
+    // Sans courant mesuré: la vitesse appliquée indique la charge
+    let mut guard = LowVoltage::new(&config);
+    let loaded = Sample {
+        voltage: 6.0,
+        current: None,
+        throttle: 0.8,
+    };
+    for s in 0..10 {
+        assert_eq!(guard.update(&loaded, at(start, s)), None);
+    }
+
+    // Courant mesuré: forte charge ignorée, faible charge compensée par la résistance interne
+    let config = LowVoltageConfig {
+        resistance_mohm: 50.0,
+        ..config
+    };
+    let mut guard = LowVoltage::new(&config);
+    let sample = |voltage, current| Sample {
+        voltage,
+        current: Some(current),
+        throttle: 0.0,
+    };
+    assert_eq!(guard.update(&sample(5.8, 20.0), at(start, 0)), None);
+    assert_eq!(guard.update(&sample(5.8, 20.0), at(start, 5)), None);
+    // 6.5 V + 2 A x 0.05 Ω = 6.6 V: au seuil
+    assert_eq!(guard.update(&sample(6.5, 2.0), at(start, 6)), None);
+    assert_eq!(guard.update(&sample(6.5, 2.0), at(start, 20)), None);
+    assert_eq!(guard.update(&sample(6.4, 2.0), at(start, 21)), None);
+    assert_eq!(guard.update(&sample(6.4, 2.0), at(start, 22)), None);
+    assert_eq!(
+        guard.update(&sample(6.4, 2.0), at(start, 23)),
+        Some(Step::Cutoff { cell_v: 3.25 })
+    );
+}
+
+#[test]
+fn needs_consecutive_samples() {
+    let config = LowVoltageConfig {
+        sustain_s: 1.0,
+        sustain_samples: 3,
+        throttle_limits: vec![0.5],
+        ..LowVoltageConfig::default()
+    };
+    let mut guard = LowVoltage::new(&config);
+    let start = Instant::now();
+
+    // Mesures isolées, même espacées de plus de sustain_s: aucun palier
+    assert_eq!(guard.update(&idle(6.5), at(start, 0)), None);
+    assert_eq!(guard.update(&idle(6.5), at(start, 5)), None);
+    // Une remontée au-delà de l'hystérésis remet le compte à zéro
+    assert_eq!(guard.update(&idle(6.8), at(start, 6)), None);
+    assert_eq!(guard.update(&idle(6.5), at(start, 7)), None);
+    assert_eq!(guard.update(&idle(6.5), at(start, 8)), None);
+    assert_eq!(guard.update(&idle(6.5), at(start, 9)).unwrap().max(), 0.5);
+
+    // Mesures sous forte charge ignorées: ni comptées, ni interruption de la période
+    let loaded = Sample {
+        voltage: 6.5,
+        current: None,
+        throttle: 0.8,
+    };
+    assert_eq!(guard.update(&idle(6.5), at(start, 10)), None);
+    assert_eq!(guard.update(&loaded, at(start, 11)), None);
+    assert_eq!(guard.update(&loaded, at(start, 12)), None);
+    assert_eq!(guard.update(&idle(6.5), at(start, 13)), Some(Step::Cutoff { cell_v: 3.25 }));
+}
+
+#[tokio::test]
+async fn lowest_limit_applies() {
+    let (commands, mut arbiter) = Arbiter::new(&Clock::start(), Duration::from_millis(500), LinkLossPolicy::Stop);
+    let control = Control { steer: -0.2, speed: -0.9 };
+
+    commands.submit("test", control).unwrap();
+    let Next::Command(command) = arbiter.next().await else {
+        panic!("commande attendue");
+    };
+    arbiter.applied(command.control);
+
+    // Limite plus basse que la sortie: appliquée immédiatement, marche arrière comprise
+    commands.limit("low_voltage", Some(0.6));
+    let Next::Command(command) = arbiter.next().await else {
+        panic!("limite attendue");
+    };
+    assert_eq!(command.source, "limit");
+    assert_eq!(command.control.speed, -0.6);
+    assert_eq!(command.control.steer, -0.2);
+    arbiter.applied(command.control);
+
+    commands.cutoff("rollover", true);
+    let Next::Command(command) = arbiter.next().await else {
+        panic!("coupure attendue");
+    };
+    assert_eq!(command.control.speed, 0.0);
+    arbiter.applied(command.control);
+
+    // La levée d'une règle laisse les autres limites en place
+    commands.cutoff("rollover", false);
+    commands.submit("test", control).unwrap();
+    let Next::Command(command) = arbiter.next().await else {
+        panic!("commande attendue");
+    };
+    assert_eq!(command.control.speed, -0.6);
+}
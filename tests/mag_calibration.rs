@@ -0,0 +1,107 @@
+// Calibration du magnétomètre: ajustement d'un ellipsoïde aux mesures brutes, correction "hard iron"
+// et "soft iron"
+#![cfg(not(feature = "real-sensors"))]
+
+use voiturerc::{config, sensors};
+
+use config::MagCalibration;
+use sensors::mag::calibration;
+
+/// Champ terrestre mesuré par un capteur sans perturbation (unités brutes)
+const FIELD: f32 = 400.0;
+/// Décalage dû aux pièces aimantées du châssis
+const HARD_IRON: [f32; 3] = [569.0, 246.0, -167.0];
+/// Déformation due aux pièces ferromagnétiques (symétrique)
+const SOFT_IRON: [[f32; 3]; 3] = [[1.2, 0.05, -0.1], [0.05, 0.9, 0.08], [-0.1, 0.08, 1.0]];
+
+/// Directions réparties sur la sphère (spirale de Fibonacci)
+fn directions(count: usize) -> Vec<[f32; 3]> {
+    let golden = std::f32::consts::PI * (3.0 - 5f32.sqrt());
+    (0..count)
+        .map(|n| {
+            let z = 1.0 - 2.0 * (n as f32 + 0.5) / count as f32;
+            let r = (1.0 - z * z).sqrt();
+            let (sin, cos) = (golden * n as f32).sin_cos();
+            [r * cos, r * sin, z]
+        })
+        .collect()
+}
+
+/// Mesure brute du champ dans une direction, perturbée par le châssis
+fn measure(direction: [f32; 3]) -> (i16, i16, i16) {
+    let [x, y, z] = [0, 1, 2].map(|row| {
+        let distorted: f32 = SOFT_IRON[row].iter().zip(direction).map(|(a, b)| a * b * FIELD).sum();
+        (distorted + HARD_IRON[row]).round() as i16
+    });
+    (x, y, z)
+}
+
+fn norm((x, y, z): (f32, f32, f32)) -> f32 {
+    (x * x + y * y + z * z).sqrt()
+}
+
+#[test]
+fn recovers_hard_and_soft_iron() {
+    let samples: Vec<_> = directions(300).into_iter().map(measure).collect();
+    let fit = calibration::fit(&samples).unwrap();
+
+    for (found, expected) in fit.calibration.hard_iron.iter().zip(HARD_IRON) {
+        assert!((found - expected).abs() < 2.0, "hard iron {:?}", fit.calibration.hard_iron);
+    }
+
+    // Champ corrigé de norme constante, quelle que soit la direction
+    assert!(fit.residual < 0.01, "écart {}", fit.residual);
+    for sample in &samples {
+        let corrected = norm(fit.calibration.apply(*sample));
+        assert!((corrected / fit.radius - 1.0).abs() < 0.02, "norme {} rayon {}", corrected, fit.radius);
+    }
+
+    // La correction annule la déformation, à un facteur d'échelle près
+    let soft = fit.calibration.soft_iron;
+    let product = [0, 1, 2].map(|row| [0, 1, 2].map(|column| (0..3).map(|k| soft[row][k] * SOFT_IRON[k][column]).sum::<f32>()));
+    for row in 0..3 {
+        for column in 0..3 {
+            let expected = if row == column { product[0][0] } else { 0.0 };
+            assert!((product[row][column] - expected).abs() < 0.01, "{:?}", product);
+        }
+    }
+}
+
+#[test]
+fn clean_sensor_needs_no_correction() {
+    let samples: Vec<_> = directions(200)
+        .into_iter()
+        .map(|[x, y, z]| ((x * FIELD).round() as i16, (y * FIELD).round() as i16, (z * FIELD).round() as i16))
+        .collect();
+    let fit = calibration::fit(&samples).unwrap();
+
+    assert!((fit.radius - FIELD).abs() < 1.0, "rayon {}", fit.radius);
+    let identity = MagCalibration::default();
+    for (found, expected) in fit.calibration.soft_iron.iter().flatten().zip(identity.soft_iron.iter().flatten()) {
+        assert!((found - expected).abs() < 0.01, "soft iron {:?}", fit.calibration.soft_iron);
+    }
+    for found in fit.calibration.hard_iron {
+        assert!(found.abs() < 1.0, "hard iron {:?}", fit.calibration.hard_iron);
+    }
+}
+
+#[test]
+fn rejects_rotation_in_a_single_plane() {
+    // Véhicule tourné à plat uniquement: l'axe vertical n'est pas observé
+    let samples: Vec<_> = (0..360)
+        .map(|degrees| {
+            let (sin, cos) = (degrees as f32).to_radians().sin_cos();
+            measure([cos * 0.5, sin * 0.5, -0.866])
+        })
+        .collect();
+
+    assert!(calibration::fit(&samples).is_err());
+}
+
+#[test]
+fn rejects_too_few_or_identical_samples() {
+    let samples: Vec<_> = directions(10).into_iter().map(measure).collect();
+    assert!(calibration::fit(&samples).is_err());
+
+    assert!(calibration::fit(&[(120, -45, 300); 50]).is_err());
+}
@@ -0,0 +1,183 @@
+// Sortie MAVLink: trames MAVLink 2 (en-tête, CRC) et conversions des champs (degE7, cm/s, mV),
+// vérifiées en décodant les trames avec le dialecte common du crate mavlink
+#![cfg(not(feature = "real-sensors"))]
+
+mod common;
+
+use voiturerc::mavlink::{self as output, Encoder};
+use voiturerc::{capacity, writer};
+
+use std::time::Duration;
+
+use ::mavlink::dialects::common::{GpsFixType, MavMessage, MavSysStatusSensor, MavType};
+use ::mavlink::MavHeader;
+
+use capacity::CapacityState;
+use common::stamp;
+use writer::Latest;
+
+/// Décode une trame seule (CRC vérifié par le décodeur)
+fn parse(frame: &[u8]) -> (MavHeader, MavMessage) {
+    assert_eq!(frame[0], 0xFD, "MAVLink 2");
+    let mut messages = output::decode(frame);
+    assert_eq!(messages.len(), 1, "trame invalide");
+    messages.remove(0)
+}
+
+fn latest() -> Latest {
+    let mut latest = Latest::default();
+    let gps = &mut latest.data.gps;
+    gps.stamp = stamp();
+    gps.fix = true;
+    gps.latitude = 46.519_654_3;
+    gps.longitude = -6.632_273_1;
+    gps.speed_kmh = 36.0;
+    gps.heading = 90.0;
+    gps.satellites = 9;
+    latest.data.imu.stamp = stamp();
+    latest.data.imu.angles = (-10.0, 30.0, 180.0);
+    let analog = &mut latest.data.analog;
+    analog.stamp = stamp();
+    analog.battery = 7.42;
+    analog.current = Some(12.5);
+    latest
+}
+
+#[test]
+fn header_ids_and_sequence() {
+    let mut encoder = Encoder::new(42, 191);
+    let (first, message) = parse(&encoder.frame(&output::heartbeat()));
+    let (second, _) = parse(&encoder.frame(&output::heartbeat()));
+
+    assert_eq!((first.sequence, first.system_id, first.component_id), (0, 42, 191));
+    assert_eq!(second.sequence, 1);
+
+    let MavMessage::HEARTBEAT(heartbeat) = message else {
+        panic!("HEARTBEAT attendu: {:?}", message);
+    };
+    assert_eq!(heartbeat.mavtype, MavType::MAV_TYPE_GROUND_ROVER);
+}
+
+#[test]
+fn gps_raw_int_in_deg_e7_and_cm_per_s() {
+    let (_, message) = parse(&Encoder::new(1, 1).frame(&output::gps_raw_int(&latest())));
+    let MavMessage::GPS_RAW_INT(gps) = message else {
+        panic!("GPS_RAW_INT attendu: {:?}", message);
+    };
+
+    assert_eq!(gps.lat, 465_196_543);
+    assert_eq!(gps.lon, -66_322_731);
+    assert_eq!(gps.vel, 1000, "36 km/h");
+    assert_eq!(gps.cog, 9000, "cap en centi-degrés");
+    assert_eq!(gps.fix_type, GpsFixType::GPS_FIX_TYPE_3D_FIX);
+    assert_eq!(gps.satellites_visible, 9);
+}
+
+#[test]
+fn attitude_and_vfr_hud() {
+    let latest = latest();
+
+    let (_, message) = parse(&Encoder::new(1, 1).frame(&output::attitude(&latest, Duration::from_millis(1500))));
+    let MavMessage::ATTITUDE(attitude) = message else {
+        panic!("ATTITUDE attendu: {:?}", message);
+    };
+    assert_eq!(attitude.time_boot_ms, 1500);
+    assert!((attitude.roll - 30f32.to_radians()).abs() < 1e-6, "roulis");
+    assert!((attitude.pitch + 10f32.to_radians()).abs() < 1e-6, "tangage");
+    assert!(attitude.yaw.abs() - std::f32::consts::PI < 1e-6, "lacet dans [-pi, pi]");
+
+    let (_, message) = parse(&Encoder::new(1, 1).frame(&output::vfr_hud(&latest)));
+    let MavMessage::VFR_HUD(hud) = message else {
+        panic!("VFR_HUD attendu: {:?}", message);
+    };
+    assert_eq!(hud.groundspeed, 10.0, "m/s");
+    assert_eq!(hud.heading, 90);
+}
+
+#[test]
+fn sys_status_voltage_current_and_remaining() {
+    let sys_status = |latest: &Latest| {
+        let (_, message) = parse(&Encoder::new(1, 1).frame(&output::sys_status(latest, Duration::from_millis(1500))));
+        match message {
+            MavMessage::SYS_STATUS(status) => status,
+            message => panic!("SYS_STATUS attendu: {:?}", message),
+        }
+    };
+
+    let status = sys_status(&latest());
+    assert_eq!(status.voltage_battery, 7420);
+    assert_eq!(status.current_battery, 1250, "cA");
+    assert_eq!(status.battery_remaining, -1, "capacité pas encore publiée");
+    assert!(status
+        .onboard_control_sensors_health
+        .contains(MavSysStatusSensor::MAV_SYS_STATUS_SENSOR_GPS | MavSysStatusSensor::MAV_SYS_STATUS_SENSOR_BATTERY));
+    assert!(!status.onboard_control_sensors_present.contains(MavSysStatusSensor::MAV_SYS_STATUS_SENSOR_3D_MAG));
+
+    let mut latest = latest();
+    latest.data.analog.current = None;
+    latest.capacity = Some(CapacityState {
+        consumed_mah: 1234.4,
+        remaining_pct: 62.6,
+    });
+    let status = sys_status(&latest);
+    assert_eq!(status.current_battery, -1, "courant inconnu");
+    assert_eq!(status.battery_remaining, 63);
+}
+
+#[test]
+fn battery_status_cells_and_current() {
+    let battery = |latest: &Latest| {
+        let (_, message) = parse(&Encoder::new(1, 1).frame(&output::battery_status(latest)));
+        match message {
+            MavMessage::BATTERY_STATUS(battery) => battery,
+            message => panic!("BATTERY_STATUS attendu: {:?}", message),
+        }
+    };
+
+    // Sans tension par élément: batterie entière dans le premier
+    let status = battery(&latest());
+    assert_eq!(status.current_consumed, -1, "consommation inconnue");
+    assert_eq!(status.voltages[0], 7420);
+    assert!(status.voltages[1..].iter().all(|voltage| *voltage == u16::MAX));
+    assert_eq!(status.current_battery, 1250, "cA");
+    assert_eq!(status.battery_remaining, -1, "restant inconnu");
+
+    let mut latest = latest();
+    latest.data.analog.cells = [Some(3.71), Some(3.705), None, None];
+    latest.data.analog.current = None;
+    let status = battery(&latest);
+    assert_eq!(&status.voltages[..3], &[3710, 3705, u16::MAX]);
+    assert_eq!(status.current_battery, -1, "courant inconnu");
+
+    // Capacité publiée par le suivi de la batterie
+    latest.capacity = Some(CapacityState {
+        consumed_mah: 1234.4,
+        remaining_pct: 62.6,
+    });
+    let status = battery(&latest);
+    assert_eq!(status.current_consumed, 1234, "mAh");
+    assert_eq!(status.battery_remaining, 63);
+
+    // Batterie jamais mesurée
+    assert_eq!(battery(&Latest::default()).voltages[0], u16::MAX);
+}
+
+#[test]
+fn decode_skips_invalid_frames() {
+    let mut encoder = Encoder::new(255, 190);
+    let heartbeat = encoder.frame(&output::heartbeat());
+    let mut corrupted = encoder.frame(&output::vfr_hud(&latest()));
+    let last = corrupted.len() - 1;
+    corrupted[last] ^= 0xFF;
+    let gps = encoder.frame(&output::gps_raw_int(&latest()));
+
+    // Octets parasites, trame au CRC invalide: seules les trames valides sont rendues
+    let datagram = [&[0x00, 0x42][..], &heartbeat, &corrupted, &gps].concat();
+    let messages = output::decode(&datagram);
+    assert_eq!(messages.len(), 2);
+    assert!(matches!(messages[0].1, MavMessage::HEARTBEAT(_)));
+    assert!(matches!(messages[1].1, MavMessage::GPS_RAW_INT(_)));
+    assert_eq!(messages[1].0.sequence, 2);
+
+    assert!(output::decode(&[]).is_empty());
+}
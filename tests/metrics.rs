@@ -0,0 +1,43 @@
+// Compteurs Prometheus des écritures en base: réussites, échecs et histogramme de latence
+#![cfg(not(feature = "real-sensors"))]
+
+use voiturerc::metrics;
+
+use std::time::Duration;
+
+use metrics::DbMetrics;
+
+#[test]
+fn counts_inserts_per_table_with_latency() {
+    let db = DbMetrics::default();
+    db.record("imu", true, Duration::from_millis(2));
+    db.record("imu", true, Duration::from_millis(30));
+    db.record("imu", false, Duration::from_secs(2));
+    db.record("gps", true, Duration::from_micros(500));
+    // Table inconnue: ignorée
+    db.record("inconnue", true, Duration::from_millis(1));
+
+    let mut text = String::new();
+    db.write(&mut text);
+    let lines: Vec<&str> = text.lines().collect();
+
+    for expected in [
+        "# TYPE rc_telemetrie_db_inserts_total counter",
+        "rc_telemetrie_db_inserts_total{table=\"imu\"} 2",
+        "rc_telemetrie_db_insert_failures_total{table=\"imu\"} 1",
+        "rc_telemetrie_db_inserts_total{table=\"gps\"} 1",
+        "rc_telemetrie_db_insert_failures_total{table=\"mag\"} 0",
+        "# TYPE rc_telemetrie_db_insert_seconds histogram",
+        "rc_telemetrie_db_insert_seconds_bucket{table=\"imu\",le=\"0.001\"} 0",
+        "rc_telemetrie_db_insert_seconds_bucket{table=\"imu\",le=\"0.0025\"} 1",
+        "rc_telemetrie_db_insert_seconds_bucket{table=\"imu\",le=\"0.05\"} 2",
+        "rc_telemetrie_db_insert_seconds_bucket{table=\"imu\",le=\"1\"} 2",
+        "rc_telemetrie_db_insert_seconds_bucket{table=\"imu\",le=\"+Inf\"} 3",
+        "rc_telemetrie_db_insert_seconds_sum{table=\"imu\"} 2.032",
+        "rc_telemetrie_db_insert_seconds_count{table=\"imu\"} 3",
+        "rc_telemetrie_db_insert_seconds_bucket{table=\"gps\",le=\"0.001\"} 1",
+    ] {
+        assert!(lines.contains(&expected), "{} absent de:\n{}", expected, text);
+    }
+    assert!(!text.contains("inconnue"));
+}
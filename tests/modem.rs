@@ -0,0 +1,119 @@
+// Modem: choix parmi les modems de ModemManager, propriétés (technologie, enregistrement, mesures étendues)
+#![cfg(not(feature = "real-sensors"))]
+
+use voiturerc::{config, modem, record};
+
+use std::collections::HashMap;
+
+use zbus::fdo::ManagedObjects;
+use zbus::zvariant::{OwnedObjectPath, OwnedValue, Value};
+
+use config::ModemConfig;
+use record::{AccessTechnology, ModemStatus, Operator, Registration};
+
+#[test]
+fn most_recent_technology() {
+    // GSM | EDGE | UMTS | HSPA+ | LTE
+    assert_eq!(modem::technology(1 << 1 | 1 << 4 | 1 << 5 | 1 << 9 | 1 << 14), Some(AccessTechnology::Lte));
+    // 5G NSA: LTE et NR
+    assert_eq!(modem::technology(1 << 14 | 1 << 15), Some(AccessTechnology::Nr));
+    assert_eq!(modem::technology(1 << 4), Some(AccessTechnology::Edge));
+    assert_eq!(modem::technology(1 << 7), Some(AccessTechnology::Hspa));
+    // Inconnue ou POTS seul
+    assert_eq!(modem::technology(0), None);
+    assert_eq!(modem::technology(1), None);
+}
+
+#[test]
+fn registration_states() {
+    assert_eq!(modem::registration(1), Some(Registration::Home));
+    assert_eq!(modem::registration(5), Some(Registration::Roaming));
+    assert_eq!(modem::registration(7), Some(Registration::Roaming));
+    assert_eq!(modem::registration(2), Some(Registration::Searching));
+    assert_eq!(modem::registration(3), Some(Registration::Denied));
+    assert_eq!(modem::registration(4), None);
+    assert_eq!(modem::registration(42), None);
+}
+
+#[test]
+fn missing_signal_metrics_are_absent() {
+    let values = HashMap::from([
+        ("rsrp".to_string(), OwnedValue::from(-98.5)),
+        ("rsrq".to_string(), OwnedValue::from(f64::NEG_INFINITY)),
+        ("rssi".to_string(), OwnedValue::from(-70.0)),
+    ]);
+    let mut status = ModemStatus { quality: 55, ..ModemStatus::default() };
+
+    modem::apply_signal(&mut status, &values);
+
+    assert_eq!((status.rsrp, status.rsrq, status.sinr), (Some(-98.5), None, None));
+    assert_eq!(status.quality, 55);
+}
+
+#[test]
+fn operator_name_is_truncated() {
+    assert_eq!(Operator::new("  Swisscom ").unwrap().as_str(), "Swisscom");
+    assert!(Operator::new("").is_none());
+
+    // 32 octets au plus, sans couper un caractère
+    let long = "é".repeat(20);
+    assert_eq!(Operator::new(&long).unwrap().as_str(), "é".repeat(16));
+}
+
+/// Objets de ModemManager: (numéro du modem, IMEI, port principal)
+fn objects(modems: &[(u32, &str, &str)]) -> ManagedObjects {
+    let text = |text: &str| OwnedValue::try_from(Value::from(text)).unwrap();
+
+    modems
+        .iter()
+        .map(|(n, imei, port)| {
+            let path = OwnedObjectPath::try_from(format!("/org/freedesktop/ModemManager1/Modem/{}", n)).unwrap();
+            let properties = HashMap::from([
+                ("EquipmentIdentifier".to_string(), text(imei)),
+                ("PrimaryPort".to_string(), text(port)),
+            ]);
+            let interfaces = HashMap::from([
+                (modem::MODEM.try_into().unwrap(), properties),
+                ("org.freedesktop.ModemManager1.Modem.Simple".try_into().unwrap(), HashMap::new()),
+            ]);
+            (path, interfaces)
+        })
+        .collect()
+}
+
+fn selected(objects: &ManagedObjects, config: &ModemConfig) -> Option<String> {
+    modem::select(objects, config).map(|path| path.as_str().to_string())
+}
+
+#[test]
+fn first_modem_by_number() {
+    let objects = objects(&[(10, "867698041234567", "cdc-wdm1"), (2, "867698047654321", "cdc-wdm0")]);
+
+    assert_eq!(selected(&objects, &ModemConfig::default()).as_deref(), Some("/org/freedesktop/ModemManager1/Modem/2"));
+    assert_eq!(selected(&ManagedObjects::new(), &ModemConfig::default()), None);
+}
+
+#[test]
+fn modem_matching_imei_or_port() {
+    let objects = objects(&[(1, "867698041234567", "cdc-wdm0"), (3, "867698047654321", "cdc-wdm1")]);
+
+    let imei = ModemConfig {
+        imei: Some("867698047654321".to_string()),
+        ..ModemConfig::default()
+    };
+    assert_eq!(selected(&objects, &imei).as_deref(), Some("/org/freedesktop/ModemManager1/Modem/3"));
+
+    let port = ModemConfig {
+        port: Some("cdc-wdm0".to_string()),
+        ..ModemConfig::default()
+    };
+    assert_eq!(selected(&objects, &port).as_deref(), Some("/org/freedesktop/ModemManager1/Modem/1"));
+
+    // Les deux critères doivent correspondre
+    let both = ModemConfig {
+        imei: Some("867698047654321".to_string()),
+        port: Some("cdc-wdm0".to_string()),
+        ..ModemConfig::default()
+    };
+    assert_eq!(selected(&objects, &both), None);
+}
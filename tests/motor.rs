@@ -0,0 +1,160 @@
+// ESC bidirectionnel: calibration (neutre, butées, zone morte), passage en marche arrière par
+// freinage puis neutre, retour au neutre. Impulsions relevées sur une sortie PWM factice.
+#![cfg(not(feature = "real-sensors"))]
+
+use voiturerc::{actuators, config};
+
+use std::cell::RefCell;
+use std::time::{Duration, Instant};
+
+use actuators::motor::{Motor, PwmOutput};
+use config::{Config, MotorConfig};
+
+/// Sortie PWM factice: impulsions appliquées (µs)
+#[derive(Default)]
+struct Recorder {
+    pulses: RefCell<Vec<f64>>,
+}
+
+impl PwmOutput for &Recorder {
+    fn set_duty_cycle(&self, cycle: f64) -> anyhow::Result<()> {
+        self.pulses.borrow_mut().push((cycle * 20_000.0 * 1000.0).round() / 1000.0);
+        Ok(())
+    }
+
+    fn duty_cycle(&self) -> anyhow::Result<f64> {
+        Ok(self.pulses.borrow().last().map_or(0.0, |pulse| pulse / 20_000.0))
+    }
+}
+
+impl Recorder {
+    fn last(&self) -> f64 {
+        *self.pulses.borrow().last().unwrap()
+    }
+}
+
+/// ESC standard: 1000-2000 µs, neutre à 1500 µs
+fn esc(deadband_us: f64, brake_ms: u64) -> MotorConfig {
+    MotorConfig {
+        neutral_us: 1500.0,
+        forward_us: 2000.0,
+        reverse_us: 1000.0,
+        deadband_us,
+        brake_ms,
+        neutral_ms: 100,
+    }
+}
+
+#[test]
+fn bidirectional_mapping_with_deadband() {
+    let pwm = Recorder::default();
+    let mut motor = Motor::with_pwm(&pwm, esc(20.0, 0));
+    let now = Instant::now();
+
+    for (speed, pulse) in [(0.0, 1500.0), (1.0, 2000.0), (0.5, 1760.0), (-1.0, 1000.0), (-0.5, 1240.0)] {
+        motor.set_speed(speed, now).unwrap();
+        assert_eq!(pwm.last(), pulse, "{}", speed);
+    }
+
+    // Hors limites ou NaN: neutre
+    for speed in [1.5, -2.0, f64::NAN] {
+        motor.set_speed(speed, now).unwrap();
+        assert_eq!(pwm.last(), 1500.0, "{}", speed);
+    }
+}
+
+#[test]
+fn default_calibration_unchanged() {
+    let pwm = Recorder::default();
+    let mut motor = Motor::with_pwm(&pwm, MotorConfig::default());
+    let now = Instant::now();
+
+    for (speed, pulse) in [(0.0, 1400.0), (1.0, 2000.0), (-1.0, 800.0), (-0.5, 1100.0)] {
+        motor.set_speed(speed, now).unwrap();
+        assert_eq!(pwm.last(), pulse, "{}", speed);
+    }
+}
+
+#[test]
+fn reverse_after_forward_brakes_then_neutral() {
+    let pwm = Recorder::default();
+    let mut motor = Motor::with_pwm(&pwm, esc(0.0, 100));
+    let start = Instant::now();
+    let at = |ms| start + Duration::from_millis(ms);
+
+    motor.set_speed(0.5, at(0)).unwrap();
+    assert_eq!(pwm.last(), 1750.0);
+
+    // Freinage pendant 100 ms, neutre pendant 100 ms, puis marche arrière
+    let sequence = [(0, 1250.0), (50, 1250.0), (100, 1500.0), (150, 1500.0), (200, 1250.0), (250, 1250.0)];
+    for (ms, pulse) in sequence {
+        motor.set_speed(-0.5, at(100 + ms)).unwrap();
+        assert_eq!(pwm.last(), pulse, "{} ms", ms);
+        assert_eq!(motor.pending(), ms < 200, "{} ms", ms);
+    }
+
+    // Arrêt puis marche arrière: déjà engagée
+    motor.set_speed(0.0, at(400)).unwrap();
+    motor.set_speed(-1.0, at(420)).unwrap();
+    assert_eq!(pwm.last(), 1000.0);
+    assert!(!motor.pending());
+
+    // Retour en marche avant: nouvelle séquence au passage suivant
+    motor.set_speed(0.2, at(500)).unwrap();
+    motor.set_speed(-1.0, at(520)).unwrap();
+    assert!(motor.pending());
+}
+
+#[test]
+fn interrupted_sequence_restarts() {
+    let pwm = Recorder::default();
+    let mut motor = Motor::with_pwm(&pwm, esc(0.0, 100));
+    let start = Instant::now();
+    let at = |ms| start + Duration::from_millis(ms);
+
+    motor.set_speed(1.0, at(0)).unwrap();
+    motor.set_speed(-1.0, at(10)).unwrap();
+    motor.set_speed(0.0, at(50)).unwrap();
+    assert_eq!(pwm.last(), 1500.0);
+    assert!(!motor.pending());
+
+    // Le freinage recommence
+    motor.set_speed(-1.0, at(150)).unwrap();
+    assert_eq!(pwm.last(), 1000.0);
+    motor.set_speed(-1.0, at(250)).unwrap();
+    assert_eq!(pwm.last(), 1500.0);
+    motor.set_speed(-1.0, at(350)).unwrap();
+    assert_eq!(pwm.last(), 1000.0);
+    assert!(!motor.pending());
+}
+
+#[test]
+fn safe_stop_goes_to_neutral_and_holds() {
+    let pwm = Recorder::default();
+    let mut motor = Motor::with_pwm(&pwm, esc(0.0, 0));
+    let now = Instant::now();
+
+    motor.set_speed(-1.0, now).unwrap();
+    motor.safe_stop();
+    assert_eq!(pwm.last(), 1500.0);
+    assert!(motor.neutral_check().is_ok());
+
+    // Commandes ignorées après l'arrêt
+    motor.set_speed(1.0, now).unwrap();
+    assert_eq!(pwm.last(), 1500.0);
+}
+
+#[test]
+fn invalid_calibration_rejected() {
+    let mut config = Config::default();
+    config.control.motor = esc(30.0, 100);
+    config.validate().unwrap();
+
+    // Neutre en dehors des butées
+    config.control.motor = MotorConfig { neutral_us: 900.0, ..esc(0.0, 0) };
+    assert!(config.validate().is_err());
+
+    // Zone morte plus large que la course
+    config.control.motor = esc(500.0, 0);
+    assert!(config.validate().is_err());
+}
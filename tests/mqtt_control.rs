@@ -0,0 +1,67 @@
+// Commandes du pilote par MQTT: lecture, ordre des numéros et homme mort de l'arbitrage
+#![cfg(not(feature = "real-sensors"))]
+
+use voiturerc::{actuators, clock, config, mqtt};
+
+use std::time::{Duration, Instant};
+
+use actuators::arbiter::{Arbiter, Next};
+use clock::Clock;
+use config::LinkLossPolicy;
+use mqtt::control::{ControlCommand, Sequence};
+
+const DEAD_TIMEOUT: Duration = Duration::from_millis(50);
+
+#[test]
+fn parses_commands() {
+    let command = ControlCommand::parse(br#"{"steer": -0.25, "speed": 0.5, "seq": 42}"#).unwrap();
+    assert_eq!(command.seq, 42);
+    let control = command.control();
+    assert_eq!((control.steer, control.speed), (-0.25, 0.5));
+
+    assert!(ControlCommand::parse(br#"{"steer": 0.0, "speed": 0.5}"#).is_err(), "seq manquant");
+    assert!(ControlCommand::parse(b"stop").is_err());
+}
+
+#[test]
+fn drops_out_of_order_commands() {
+    let mut sequence = Sequence::new(DEAD_TIMEOUT);
+    let now = Instant::now();
+
+    assert!(sequence.accept(10, now));
+    assert!(!sequence.accept(9, now), "en retard");
+    assert!(!sequence.accept(10, now), "en double");
+    assert!(sequence.accept(12, now));
+    assert!(!sequence.accept(11, now + Duration::from_millis(10)));
+}
+
+#[test]
+fn restarted_sender_accepted_after_dead_timeout() {
+    let mut sequence = Sequence::new(DEAD_TIMEOUT);
+    let now = Instant::now();
+
+    assert!(sequence.accept(500, now));
+    assert!(!sequence.accept(0, now + DEAD_TIMEOUT / 2));
+    assert!(sequence.accept(0, now + DEAD_TIMEOUT));
+    assert!(sequence.accept(1, now + DEAD_TIMEOUT));
+}
+
+#[tokio::test]
+async fn mqtt_commands_use_the_same_dead_man() {
+    let (commands, mut arbiter) = Arbiter::new(&Clock::start(), DEAD_TIMEOUT, LinkLossPolicy::Stop);
+    let mut sequence = Sequence::new(DEAD_TIMEOUT);
+
+    let command = ControlCommand::parse(br#"{"steer": 0.0, "speed": 0.5, "seq": 1}"#).unwrap();
+    assert!(sequence.accept(command.seq, Instant::now()));
+    commands.submit("mqtt", command.control()).unwrap();
+
+    let Next::Command(received) = arbiter.next().await else {
+        panic!("commande attendue");
+    };
+    assert_eq!(received.source, "mqtt");
+    assert_eq!(received.control.speed, 0.5);
+
+    // Plus de commande: failsafe après le délai de l'homme mort
+    assert!(matches!(arbiter.next().await, Next::Timeout));
+    assert!(commands.link_lost());
+}
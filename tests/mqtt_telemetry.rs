@@ -0,0 +1,111 @@
+// Télémétrie MQTT: sujets et contenus des échantillons, limite de fréquence et file pendant une coupure
+#![cfg(not(feature = "real-sensors"))]
+
+mod common;
+
+use voiturerc::{config, mqtt, record, sensors};
+
+use std::time::{Duration, Instant};
+
+use common::stamp;
+use config::MqttTelemetryConfig;
+use mqtt::homeassistant::{Publish, Topics};
+use mqtt::telemetry::Telemetry;
+use record::{ModemData, ModemStatus, Record};
+use sensors::reader::AnalogData;
+use serde_json::Value;
+
+fn modem(quality: u32) -> Record {
+    Record::Modem(ModemData {
+        status: ModemStatus { quality, ..ModemStatus::default() },
+        stamp: stamp(),
+    })
+}
+
+/// Messages envoyés par `flush`, tous acceptés
+fn sent(telemetry: &mut Telemetry) -> Vec<Publish> {
+    let mut messages = Vec::new();
+    telemetry.flush(|message| {
+        messages.push(Publish {
+            topic: message.topic.clone(),
+            payload: message.payload.clone(),
+            retain: message.retain,
+        });
+        true
+    });
+    messages
+}
+
+#[test]
+fn publishes_selected_records_as_json() {
+    let topics = Topics::new("rc", "voiturerc");
+    let mut telemetry = Telemetry::new(&MqttTelemetryConfig {
+        records: vec!["analog".to_string()],
+        max_rate: 0.0,
+        ..MqttTelemetryConfig::default()
+    });
+    let now = Instant::now();
+
+    let battery = Record::Analog(AnalogData {
+        stamp: stamp(),
+        battery: 7.4,
+        ..AnalogData::default()
+    });
+    assert!(telemetry.push(&topics, &battery, now));
+    assert!(!telemetry.push(&topics, &modem(80), now), "type non sélectionné");
+
+    let messages = sent(&mut telemetry);
+    assert_eq!(messages.len(), 1);
+    assert_eq!(messages[0].topic, "rc/voiturerc/analog");
+    assert!(!messages[0].retain);
+    let payload: Value = serde_json::from_str(&messages[0].payload).unwrap();
+    assert!((payload["battery"].as_f64().unwrap() - 7.4).abs() < 1e-6);
+    assert_eq!(telemetry.pending(), 0);
+}
+
+#[test]
+fn limits_rate_per_record_type() {
+    let topics = Topics::new("rc", "voiturerc");
+    let mut telemetry = Telemetry::new(&MqttTelemetryConfig {
+        max_rate: 10.0,
+        ..MqttTelemetryConfig::default()
+    });
+    let now = Instant::now();
+
+    assert!(telemetry.push(&topics, &modem(80), now));
+    assert!(!telemetry.push(&topics, &modem(81), now + Duration::from_millis(50)));
+    assert!(telemetry.push(&topics, &modem(82), now + Duration::from_millis(100)));
+    assert_eq!(telemetry.pending(), 2);
+}
+
+#[test]
+fn bounded_queue_drops_oldest_during_outage() {
+    let topics = Topics::new("rc", "voiturerc");
+    let mut telemetry = Telemetry::new(&MqttTelemetryConfig {
+        max_rate: 0.0,
+        queue: 3,
+        ..MqttTelemetryConfig::default()
+    });
+    let now = Instant::now();
+
+    for quality in 0..5 {
+        telemetry.push(&topics, &modem(quality), now);
+    }
+    assert_eq!(telemetry.pending(), 3);
+    assert_eq!(telemetry.take_dropped(), 2);
+    assert_eq!(telemetry.take_dropped(), 0);
+
+    // File du client pleine après un message: les suivants restent en attente, dans l'ordre
+    let mut accepted = 0;
+    telemetry.flush(|_| {
+        accepted += 1;
+        accepted == 1
+    });
+    assert_eq!(telemetry.pending(), 2);
+
+    let qualities: Vec<u64> = sent(&mut telemetry)
+        .iter()
+        .map(|message| serde_json::from_str::<Value>(&message.payload).unwrap()["quality"].as_u64().unwrap())
+        .collect();
+    assert_eq!(qualities, [3, 4]);
+}
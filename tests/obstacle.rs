@@ -0,0 +1,119 @@
+// Arrêt devant un obstacle: blocage de la marche avant, hystérésis, mesures sans distance
+#![cfg(not(feature = "real-sensors"))]
+
+use voiturerc::{actuators, clock, config, sensors};
+
+use std::time::Duration;
+
+use actuators::arbiter::{Arbiter, Next};
+use actuators::obstacle::{Obstacle, Transition};
+use actuators::Control;
+use clock::Clock;
+use config::{LinkLossPolicy, ObstacleConfig};
+use sensors::reader::{RangeData, RangeStatus};
+
+fn valid(distance_mm: u16) -> RangeData {
+    RangeData {
+        distance_mm: Some(distance_mm),
+        status: RangeStatus::Valid,
+        ..RangeData::default()
+    }
+}
+
+fn without_distance(status: RangeStatus) -> RangeData {
+    RangeData {
+        distance_mm: None,
+        status,
+        ..RangeData::default()
+    }
+}
+
+#[test]
+fn stop_then_clear_with_hysteresis() {
+    let mut obstacle = Obstacle::new(&ObstacleConfig::default());
+
+    assert_eq!(obstacle.update(Some(&valid(1200))), None);
+    assert_eq!(obstacle.update(Some(&valid(300))), None);
+
+    // Sous la distance d'arrêt, 0 mm compris
+    let triggered = obstacle.update(Some(&valid(0))).unwrap();
+    assert_eq!(triggered, Transition::Triggered { distance_mm: 0 });
+    assert!(triggered.active());
+    assert_eq!(triggered.event(), "obstacle");
+    assert_eq!(triggered.message(), "Obstacle à 0 mm: marche avant bloquée");
+    assert_eq!(obstacle.update(Some(&valid(100))), None);
+
+    // Entre les deux seuils: toujours bloqué
+    assert_eq!(obstacle.update(Some(&valid(350))), None);
+    let cleared = obstacle.update(Some(&valid(400))).unwrap();
+    assert_eq!(cleared, Transition::Cleared);
+    assert!(!cleared.active());
+    assert_eq!(cleared.event(), "obstacle_cleared");
+    assert_eq!(obstacle.update(Some(&valid(350))), None);
+}
+
+#[test]
+fn readings_without_distance_are_not_zero() {
+    let mut obstacle = Obstacle::new(&ObstacleConfig::default());
+
+    // Aucune mesure exploitable: jamais de blocage
+    for status in [RangeStatus::OutOfRange, RangeStatus::Invalid, RangeStatus::Timeout] {
+        assert_eq!(obstacle.update(Some(&without_distance(status))), None);
+    }
+    assert_eq!(obstacle.update(None), None);
+
+    // Mesure rejetée: blocage maintenu, aucune cible à portée: voie libre
+    assert!(obstacle.update(Some(&valid(120))).unwrap().active());
+    assert_eq!(obstacle.update(Some(&without_distance(RangeStatus::Invalid))), None);
+    assert_eq!(
+        obstacle.update(Some(&without_distance(RangeStatus::OutOfRange))),
+        Some(Transition::Cleared)
+    );
+
+    // Capteur muet ou mesures périmées: blocage levé
+    assert!(obstacle.update(Some(&valid(120))).unwrap().active());
+    assert_eq!(
+        obstacle.update(Some(&without_distance(RangeStatus::Timeout))),
+        Some(Transition::Stale)
+    );
+    assert!(obstacle.update(Some(&valid(120))).unwrap().active());
+    assert_eq!(obstacle.update(None), Some(Transition::Stale));
+    assert_eq!(obstacle.update(None), None);
+}
+
+#[tokio::test]
+async fn forward_limit_keeps_reverse() {
+    let (commands, mut arbiter) = Arbiter::new(&Clock::start(), Duration::from_millis(500), LinkLossPolicy::Stop);
+    let forward = Control { steer: 0.2, speed: 0.6 };
+
+    commands.submit("test", forward).unwrap();
+    let Next::Command(command) = arbiter.next().await else {
+        panic!("commande attendue");
+    };
+    arbiter.applied(command.control);
+
+    // Obstacle: marche avant ramenée au neutre sans attendre la commande suivante
+    commands.limit_forward("obstacle", Some(0.0));
+    let Next::Command(command) = arbiter.next().await else {
+        panic!("limite attendue");
+    };
+    assert_eq!(command.source, "limit");
+    assert_eq!(command.control.speed, 0.0);
+    assert_eq!(command.control.steer, 0.2);
+    let limit = commands.state().borrow().limit.clone().unwrap();
+    assert_eq!((limit.max, limit.cause.as_str()), (0.0, "obstacle"));
+
+    // La marche arrière reste disponible pour s'éloigner
+    commands.submit("test", Control { steer: 0.0, speed: -0.5 }).unwrap();
+    let Next::Command(command) = arbiter.next().await else {
+        panic!("commande attendue");
+    };
+    assert_eq!(command.control.speed, -0.5);
+
+    commands.limit_forward("obstacle", None);
+    commands.submit("test", forward).unwrap();
+    let Next::Command(command) = arbiter.next().await else {
+        panic!("commande attendue");
+    };
+    assert_eq!(command.control.speed, 0.6);
+}
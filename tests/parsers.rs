@@ -0,0 +1,314 @@
+// Entrées externes non fiables (trames NMEA, trames CAN, commandes de la base, enregistrements
+// rejoués, commandes MQTT): aucune panique, mémoire bornée, rejet des trames invalides.
+// Les entrées ayant provoqué une panique ou un blocage sont conservées dans tests/corpus.
+#![cfg(not(feature = "real-sensors"))]
+
+use voiturerc::{actuators, clock, config, mqtt, sensors};
+
+use std::path::Path;
+
+use chrono::{TimeZone, Utc};
+use nmea_parser::gnss::{GsaData, GsvData, NavigationSystem, RmcData};
+use nmea_parser::ParsedMessage;
+use proptest::prelude::*;
+use serde::Deserialize;
+
+use actuators::{duty, Control};
+use config::{MotorConfig, SteeringConfig};
+use mqtt::homeassistant;
+use sensors::can::decode::CanSignal;
+use clock::Stamp;
+use sensors::gps::nmea::{self, Nmea, Satellites, MAX_LINE};
+use sensors::reader::GpsData;
+use sensors::replay;
+
+/// Trame GGA valide (somme de contrôle correcte)
+const GGA: &[u8] = b"$GNGGA,123005.00,4631.20000,N,00637.80389,E,1,09,1.03,412.1,M,48.1,M,,*4D\r\n";
+
+/// Contenu des fichiers d'un dossier du corpus de régression
+fn corpus(name: &str) -> Vec<(String, Vec<u8>)> {
+    let directory = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/corpus").join(name);
+    let mut entries: Vec<_> = std::fs::read_dir(&directory)
+        .unwrap()
+        .map(|entry| {
+            let path = entry.unwrap().path();
+            let name = path.file_name().unwrap().to_string_lossy().to_string();
+            (name, std::fs::read(&path).unwrap())
+        })
+        .collect();
+    entries.sort();
+    assert!(!entries.is_empty(), "{}: corpus vide", directory.display());
+    entries
+}
+
+/// Trame NMEA avec sa somme de contrôle
+fn sentence(data: &[u8]) -> Vec<u8> {
+    let sum = data.iter().fold(0, |sum, x| sum ^ x);
+    [b"$", data, format!("*{:02X}\r\n", sum).as_bytes()].concat()
+}
+
+/// Trame RMC reçue le 01.06.2024 à 12:30:05 UTC
+fn rmc(active: bool, position: Option<(f64, f64)>) -> ParsedMessage {
+    ParsedMessage::Rmc(RmcData {
+        source: NavigationSystem::Combination,
+        timestamp: Some(Utc.with_ymd_and_hms(2024, 6, 1, 12, 30, 5).unwrap()),
+        status_active: Some(active),
+        latitude: position.map(|p| p.0),
+        longitude: position.map(|p| p.1),
+        sog_knots: position.map(|_| 10.0),
+        bearing: position.map(|_| 88.95),
+        variation: None,
+    })
+}
+
+/// Trame GSA d'une constellation
+fn gsa(source: NavigationSystem, used: u8) -> ParsedMessage {
+    ParsedMessage::Gsa(GsaData {
+        source,
+        mode1_automatic: Some(true),
+        mode2_3d: None,
+        prn_numbers: (1..=used).collect(),
+        pdop: Some(1.8),
+        hdop: Some(0.9),
+        vdop: Some(1.5),
+    })
+}
+
+/// Groupe GSV complet d'une constellation
+fn gsv(source: NavigationSystem, snr: &[Option<f32>]) -> ParsedMessage {
+    ParsedMessage::Gsv(
+        snr.iter()
+            .enumerate()
+            .map(|(n, &snr)| GsvData {
+                source,
+                prn_number: n as u8 + 1,
+                elevation: Some(45.0),
+                azimuth: Some(180.0),
+                snr,
+            })
+            .collect(),
+    )
+}
+
+fn at(seconds: f64) -> Stamp {
+    Stamp {
+        mono_us: (seconds * 1e6) as u64,
+        utc: Utc.with_ymd_and_hms(2024, 6, 1, 12, 30, 5).unwrap(),
+    }
+}
+
+/// Entrée du corpus CAN: définition du signal (non validée) et contenu de la trame
+#[derive(Deserialize)]
+struct CanCase {
+    signal: CanSignal,
+    data: Vec<u8>,
+}
+
+fn can_signal() -> impl Strategy<Value = CanSignal> {
+    (0u16..80, any::<u8>(), any::<bool>(), any::<bool>()).prop_map(|(start_bit, length, signed, big_endian)| {
+        CanSignal {
+            name: "signal".to_string(),
+            start_bit,
+            length,
+            signed,
+            big_endian,
+            ..CanSignal::default()
+        }
+    })
+}
+
+#[test]
+fn nmea_checksum() {
+    assert!(nmea::checksum(GGA));
+    assert!(nmea::checksum(&GGA[..GGA.len() - 2]));
+    for line in include_str!("../benches/fixtures/nmea.txt").lines() {
+        assert!(nmea::checksum(line.as_bytes()), "{}", line);
+    }
+
+    assert!(!nmea::checksum(b""));
+    assert!(!nmea::checksum(b"$\r\n"));
+    assert!(!nmea::checksum(b"$GNGGA,1*"));
+    assert!(!nmea::checksum(b"$GNGGA,1*zz\r\n"));
+}
+
+#[test]
+fn nmea_resynchronises_after_noise() {
+    let mut nmea = Nmea::default();
+    assert!(nmea.push(&vec![b'A'; MAX_LINE * 4]).is_empty());
+    assert_eq!(nmea.push(GGA).len(), 1);
+}
+
+#[test]
+fn rmc_sets_time_and_validity() {
+    let mut gps = GpsData::default();
+    nmea::apply(&mut gps, &rmc(true, Some((46.52, 6.63))));
+
+    assert_eq!(gps.time, Some(Utc.with_ymd_and_hms(2024, 6, 1, 12, 30, 5).unwrap()));
+    assert!(gps.valid);
+    assert_eq!((gps.latitude, gps.longitude), (46.52, 6.63));
+    assert!((gps.speed_kmh - 18.52).abs() < 1e-9);
+    assert_eq!(gps.heading, 88.95);
+}
+
+#[test]
+fn void_rmc_is_kept_with_flag() {
+    let mut gps = GpsData::default();
+    nmea::apply(&mut gps, &rmc(true, Some((46.52, 6.63))));
+    nmea::apply(&mut gps, &rmc(false, None));
+
+    // Heure toujours reçue, dernière position conservée
+    assert!(gps.time.is_some());
+    assert!(!gps.valid);
+    assert_eq!((gps.latitude, gps.longitude), (46.52, 6.63));
+}
+
+#[test]
+fn satellites_aggregate_one_cycle_per_constellation() {
+    let mut satellites = Satellites::default();
+    for message in [
+        gsa(NavigationSystem::Gps, 6),
+        gsa(NavigationSystem::Glonass, 3),
+        ParsedMessage::Incomplete,
+        gsv(NavigationSystem::Gps, &[Some(40.0), Some(30.0), None, Some(35.0)]),
+        ParsedMessage::Incomplete,
+        gsv(NavigationSystem::Glonass, &[Some(39.0), None]),
+    ] {
+        satellites.apply(&message);
+    }
+
+    // Cycle GSV terminé à la trame suivante uniquement
+    let partial = satellites.sample(at(1.0)).unwrap();
+    assert_eq!((partial.used, partial.in_view), (9, 0));
+
+    satellites.apply(&rmc(true, None));
+    let sample = satellites.sample(at(2.0)).unwrap();
+    assert_eq!(sample.stamp.mono_us, 2_000_000);
+    assert_eq!((sample.pdop, sample.hdop, sample.vdop), (1.8, 0.9, 1.5));
+    assert_eq!((sample.used, sample.in_view), (9, 6));
+    assert_eq!(sample.snr_mean, 36.0);
+}
+
+#[test]
+fn satellites_at_most_one_sample_per_second() {
+    let mut satellites = Satellites::default();
+    assert!(satellites.sample(at(5.0)).is_none());
+
+    for (seconds, expected) in [(5.0, true), (5.5, false), (5.9, false), (6.0, true)] {
+        satellites.apply(&gsa(NavigationSystem::Gps, 7));
+        satellites.apply(&rmc(true, None));
+        assert_eq!(satellites.sample(at(seconds)).is_some(), expected, "{} s", seconds);
+    }
+}
+
+#[test]
+fn nmea_corpus() {
+    for (name, bytes) in corpus("nmea") {
+        let mut nmea = Nmea::default();
+        assert!(nmea.push(&bytes).is_empty(), "{}", name);
+        assert!(nmea.push(b"\n").is_empty(), "{}", name);
+    }
+}
+
+#[test]
+fn can_corpus() {
+    for (name, bytes) in corpus("can") {
+        let case: CanCase = serde_json::from_slice(&bytes).unwrap();
+        assert!(case.signal.validate().is_err() || case.data.len() < 8, "{}", name);
+        assert_eq!(case.signal.raw(&case.data), None, "{}", name);
+    }
+}
+
+#[test]
+fn control_corpus() {
+    for (name, bytes) in corpus("control") {
+        let control = serde_json::from_slice::<Control>(&bytes);
+        assert!(control.is_err() || control.unwrap().validate().is_err(), "{}", name);
+    }
+}
+
+#[test]
+fn replay_corpus() {
+    for (name, bytes) in corpus("replay") {
+        let written = replay::decode_from(bytes.as_slice(), &mut Vec::new());
+        assert!(written.is_err() || written.unwrap() == 0, "{}", name);
+    }
+}
+
+proptest! {
+    #[test]
+    fn nmea_never_panics(bytes in prop::collection::vec(any::<u8>(), 0..2048), chunk in 1usize..300) {
+        let mut nmea = Nmea::default();
+        for part in bytes.chunks(chunk) {
+            nmea.push(part);
+        }
+        // Toujours resynchronisé sur la trame suivante, après une fin de ligne
+        prop_assert_eq!(nmea.push(&[b"\n", GGA].concat()).len(), 1);
+    }
+
+    #[test]
+    fn nmea_rejects_corrupted_sentences(data in "[A-Z0-9.,]{1,70}", index in any::<prop::sample::Index>(), flip in 1u8..=255) {
+        let mut line = sentence(data.as_bytes());
+        prop_assert!(nmea::checksum(&line));
+
+        // Un octet modifié entre $ et * change la somme de contrôle
+        let index = 1 + index.index(data.len());
+        line[index] ^= flip;
+        prop_assert!(!nmea::checksum(&line));
+        prop_assert!(Nmea::default().push(&line).is_empty());
+    }
+
+    #[test]
+    fn can_never_panics(signal in can_signal(), data in prop::collection::vec(any::<u8>(), 0..16)) {
+        let raw = signal.raw(&data);
+
+        // Signal valide et trame complète: valeur toujours présente et dans la plage du signal
+        if signal.validate().is_ok() && data.len() >= 8 {
+            let raw = raw.unwrap();
+            let length = signal.length as u32;
+            if signal.signed {
+                prop_assert!(raw >= -(1i128 << (length - 1)) && raw < 1i128 << (length - 1));
+            } else {
+                prop_assert!(raw >= 0 && raw < 1i128 << length);
+            }
+        }
+    }
+
+    #[test]
+    fn control_never_panics(bytes in prop::collection::vec(any::<u8>(), 0..128)) {
+        if let Ok(control) = serde_json::from_slice::<Control>(&bytes) {
+            let _ = control.validate();
+        }
+    }
+
+    #[test]
+    fn control_duty_cycles_bounded(steer in any::<f64>(), speed in any::<f64>()) {
+        let control = Control { steer, speed };
+        let (steer_valid, speed_valid) = ((-1.0..=1.0).contains(&steer), (-1.0..=1.0).contains(&speed));
+        prop_assert_eq!(control.validate().is_ok(), steer_valid && speed_valid);
+
+        let motor = duty::calibrated_motor(speed, &MotorConfig::default());
+        let steering = duty::calibrated_steering(steer, &SteeringConfig::default());
+        prop_assert!((0.04 - 1e-9..=0.10 + 1e-9).contains(&motor), "{}", motor);
+        prop_assert!((0.064 - 1e-9..=0.088 + 1e-9).contains(&steering), "{}", steering);
+
+        // Valeurs hors limites ou NaN: neutre pour le moteur, butée ou centre pour la direction
+        prop_assert!(speed_valid || (motor - 0.07).abs() < 1e-9);
+        prop_assert!(!steer.is_nan() || (steering - 0.076).abs() < 1e-9);
+    }
+
+    #[test]
+    fn replay_never_panics(header in any::<bool>(), bytes in prop::collection::vec(any::<u8>(), 0..1024)) {
+        let input = if header { [b"RCPB\x01".as_slice(), &bytes].concat() } else { bytes };
+        let _ = replay::decode_from(input.as_slice(), &mut Vec::new());
+    }
+
+    #[test]
+    fn arm_command_never_panics(bytes in prop::collection::vec(any::<u8>(), 0..32)) {
+        let expected = match std::str::from_utf8(&bytes).map(str::trim) {
+            Ok("ON") => Some(true),
+            Ok("OFF") => Some(false),
+            _ => None,
+        };
+        prop_assert_eq!(homeassistant::parse_arm(&bytes).ok(), expected);
+    }
+}
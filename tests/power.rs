@@ -0,0 +1,44 @@
+// Moniteur de puissance INA219: calibration et conversion des registres
+
+use voiturerc::sensors::power::calibration;
+
+use calibration::{bus_voltage, Calibration};
+
+#[test]
+fn calibration_for_breakout_shunt() {
+    // Carte courante: shunt de 0.1 ohm, 3.2 A au maximum
+    let calibration = Calibration::new(0.1, 3.2).unwrap();
+
+    assert_eq!(calibration.register, 4194);
+    assert_eq!(calibration.gain, 3);
+    assert!((calibration.current_lsb - 97.66e-6).abs() < 0.01e-6, "{}", calibration.current_lsb);
+    assert!((calibration.power_lsb - 20.0 * calibration.current_lsb).abs() < 1e-12);
+}
+
+#[test]
+fn smallest_shunt_range() {
+    // 10 A dans 0.01 ohm: 0.1 V, plage de 0.16 V
+    let calibration = Calibration::new(0.01, 10.0).unwrap();
+    assert_eq!(calibration.gain, 2);
+    // Bit 0 du registre non utilisé
+    assert_eq!(calibration.register, 13420);
+
+    assert_eq!(Calibration::new(0.001, 5.0).unwrap().gain, 0);
+    assert!(Calibration::new(0.1, 4.0).is_err());
+}
+
+#[test]
+fn register_conversions() {
+    let calibration = Calibration::new(0.1, 3.2).unwrap();
+
+    // Tension du bus: bits 15 à 3, CNVR et OVF ignorés
+    assert!((bus_voltage(3000 << 3 | 0b10) - 12.0).abs() < 1e-4);
+
+    let amps = calibration.current(10240);
+    assert!((amps - 1.0).abs() < 1e-3, "{}", amps);
+    // Courant négatif en complément à deux
+    assert!(calibration.current(0xFFFF) < 0.0);
+
+    let watts = calibration.power(512);
+    assert!((watts - 1.0).abs() < 1e-3, "{}", watts);
+}
@@ -0,0 +1,179 @@
+// Vérifications avant armement: états de santé synthétiques, échecs listés et désactivation
+#![cfg(not(feature = "real-sensors"))]
+
+mod common;
+
+use voiturerc::{actuators, config, selftest, sensors};
+
+use std::collections::BTreeMap;
+use std::time::Duration;
+
+use actuators::prearm::{evaluate, Failure, Snapshot, ACTUATOR_CHECKS};
+use common::stamp;
+use config::PreArmConfig;
+use selftest::Outcome;
+use sensors::reader::{Data, SensorStatus, SensorsStatus};
+use sensors::watchdog::Health;
+
+fn available() -> SensorStatus {
+    SensorStatus {
+        available: true,
+        error: None,
+        attempts: 1,
+        restarts: 0,
+    }
+}
+
+/// Véhicule prêt: batterie chargée, à plat, commandé, actionneurs testés
+fn ready() -> Snapshot {
+    let mut data = Data::default();
+    data.analog.stamp = stamp();
+    data.analog.battery = 8.1;
+    data.imu.stamp = stamp();
+    data.imu.angles = (2.0, -3.0, 120.0);
+
+    Snapshot {
+        data,
+        status: SensorsStatus {
+            imu: available(),
+            mag: available(),
+            analog: available(),
+            power: SensorStatus {
+                available: false,
+                error: Some("Capteur désactivé".to_string()),
+                attempts: 0,
+                restarts: 0,
+            },
+            baro: SensorStatus {
+                available: false,
+                error: Some("Capteur désactivé".to_string()),
+                attempts: 0,
+                restarts: 0,
+            },
+            encoder: SensorStatus {
+                available: false,
+                error: Some("Capteur désactivé".to_string()),
+                attempts: 0,
+                restarts: 0,
+            },
+            range: SensorStatus {
+                available: false,
+                error: Some("Capteur désactivé".to_string()),
+                attempts: 0,
+                restarts: 0,
+            },
+            gps: SensorStatus {
+                available: false,
+                error: Some("Capteur désactivé".to_string()),
+                attempts: 0,
+                restarts: 0,
+            },
+            i2c: BTreeMap::new(),
+        },
+        health: Health::Ok,
+        stale: Vec::new(),
+        cutoff: None,
+        control_age: Some(Duration::from_millis(200)),
+        actuators: ACTUATOR_CHECKS.iter().map(|check| (*check, Some(Outcome::Pass))).collect(),
+    }
+}
+
+#[test]
+fn ready_vehicle_arms() {
+    assert_eq!(evaluate(&PreArmConfig::default(), &ready()), Vec::new());
+}
+
+#[test]
+fn every_failure_is_listed() {
+    let config = PreArmConfig::default();
+    let mut snapshot = ready();
+    snapshot.data.analog.battery = 6.2;
+    snapshot.data.imu.angles = (5.0, 170.0, 0.0);
+    snapshot.cutoff = Some("rollover".to_string());
+    snapshot.control_age = None;
+    snapshot.actuators[0].1 = Some(Outcome::Fail);
+    snapshot.actuators[1].1 = None;
+    snapshot.status.mag = SensorStatus {
+        available: false,
+        error: Some("Aucune réponse".to_string()),
+        attempts: 3,
+        restarts: 0,
+    };
+    snapshot.health = Health::Critical;
+    snapshot.stale = vec!["analog"];
+
+    let failures = evaluate(&config, &snapshot);
+    assert_eq!(
+        failures,
+        vec![
+            Failure::Battery(Some(6.2)),
+            Failure::Cutoff("rollover".to_string()),
+            Failure::ImuTilted { pitch: 5.0, roll: 170.0 },
+            Failure::Control(None),
+            Failure::Actuator {
+                check: "motor.neutral",
+                outcome: Some(Outcome::Fail),
+            },
+            Failure::Actuator {
+                check: "steering.neutral",
+                outcome: None,
+            },
+            Failure::SensorFault {
+                sensor: "mag",
+                error: "Aucune réponse".to_string(),
+            },
+            Failure::SensorsStale(vec!["analog"]),
+        ]
+    );
+    assert_eq!(failures[0].message(&config), "batterie 6.20 V sous 6.80 V");
+    assert!(failures[5].message(&config).contains("en cours"));
+}
+
+#[test]
+fn missing_samples_fail() {
+    let config = PreArmConfig {
+        gps: true,
+        ..PreArmConfig::default()
+    };
+    let mut snapshot = ready();
+    snapshot.data = Data::default();
+    snapshot.control_age = Some(Duration::from_secs(5));
+
+    let failures = evaluate(&config, &snapshot);
+    assert_eq!(
+        failures,
+        vec![
+            Failure::Battery(None),
+            Failure::ImuUnavailable,
+            Failure::Control(Some(Duration::from_secs(5))),
+            Failure::NoGpsFix,
+        ]
+    );
+}
+
+#[test]
+fn disabled_checks_are_skipped() {
+    let config = PreArmConfig {
+        battery: false,
+        cutoff: false,
+        imu: false,
+        control: false,
+        actuators: false,
+        sensors: false,
+        gps: false,
+        ..PreArmConfig::default()
+    };
+    let mut snapshot = ready();
+    snapshot.data = Data::default();
+    snapshot.cutoff = Some("low_voltage".to_string());
+    snapshot.control_age = None;
+    snapshot.actuators[0].1 = Some(Outcome::Fail);
+    snapshot.health = Health::Critical;
+
+    assert_eq!(evaluate(&config, &snapshot), Vec::new());
+
+    // Actionneurs simulés: vérification ignorée, pas en échec
+    let mut snapshot = ready();
+    snapshot.actuators[0].1 = Some(Outcome::Skip);
+    assert_eq!(evaluate(&PreArmConfig::default(), &snapshot), Vec::new());
+}
@@ -0,0 +1,195 @@
+// Conversion des échantillons internes vers les messages protobuf
+#![cfg(not(feature = "real-sensors"))]
+
+mod common;
+
+use voiturerc::{proto, record, sensors};
+
+use chrono::{TimeZone, Utc};
+use common::stamp;
+use prost::Message;
+use record::{AccessTechnology, ModemData, ModemStatus, Operator, Record, Registration};
+use sensors::reader::{AnalogData, GpsData, ImuData, MagData, SensorStatus};
+
+fn expected_stamp() -> Option<proto::Stamp> {
+    Some(proto::Stamp {
+        mono_us: 1_500_000,
+        utc_us: 1_717_243_200_000_000,
+    })
+}
+
+#[test]
+fn imu_angles_in_order() {
+    let imu = ImuData {
+        stamp: stamp(),
+        angles: (1.5, -2.0, 90.0),
+        temp: 31.25,
+        accel: (0.05, -0.1, 1.0),
+        gyro: (0.5, -1.25, 12.0),
+    };
+
+    let record: proto::Record = Record::Imu(imu).into();
+
+    assert_eq!(
+        record.record,
+        Some(proto::record::Record::Imu(proto::Imu {
+            stamp: expected_stamp(),
+            pitch: 1.5,
+            roll: -2.0,
+            yaw: 90.0,
+            temp: 31.25,
+            accel_x: 0.05,
+            accel_y: -0.1,
+            accel_z: 1.0,
+            gyro_x: 0.5,
+            gyro_y: -1.25,
+            gyro_z: 12.0,
+        }))
+    );
+}
+
+#[test]
+fn mag_keeps_negative_raw_values() {
+    let mag = MagData {
+        stamp: stamp(),
+        raw: (-32768, 0, 32767),
+        heading: 270.0,
+        calibrated: (-0.5, 0.25, 1.5),
+        true_heading: 272.0,
+        declination: 2.0,
+    };
+
+    let record: proto::Record = Record::Mag(mag).into();
+
+    assert_eq!(
+        record.record,
+        Some(proto::record::Record::Mag(proto::Mag {
+            stamp: expected_stamp(),
+            raw_x: -32768,
+            raw_y: 0,
+            raw_z: 32767,
+            heading: 270.0,
+            calibrated_x: -0.5,
+            calibrated_y: 0.25,
+            calibrated_z: 1.5,
+            true_heading: 272.0,
+            declination: 2.0,
+        }))
+    );
+}
+
+#[test]
+fn gps_analog_and_modem() {
+    let gps = GpsData {
+        stamp: stamp(),
+        speed_kmh: 25.5,
+        latitude: 46.52,
+        longitude: 6.632,
+        satellites: 9,
+        fix: true,
+        heading: 182.5,
+        time: Some(Utc.with_ymd_and_hms(2024, 6, 1, 11, 59, 59).unwrap()),
+        valid: true,
+    };
+    let analog = AnalogData {
+        stamp: stamp(),
+        battery: 11.4,
+        cells: [Some(3.8), Some(3.75), Some(3.85), None],
+        current: Some(12.5),
+    };
+    let modem = ModemData {
+        status: ModemStatus {
+            quality: 80,
+            technology: Some(AccessTechnology::Lte),
+            registration: Some(Registration::Roaming),
+            operator: Operator::new("Swisscom"),
+            rsrp: Some(-95.0),
+            rsrq: Some(-11.5),
+            sinr: None,
+        },
+        stamp: stamp(),
+    };
+
+    let gps: proto::Record = Record::Gps(gps).into();
+    let analog: proto::Record = Record::Analog(analog).into();
+    let modem: proto::Record = Record::Modem(modem).into();
+
+    assert_eq!(
+        gps.record,
+        Some(proto::record::Record::Gps(proto::Gps {
+            stamp: expected_stamp(),
+            speed_kmh: 25.5,
+            latitude: 46.52,
+            longitude: 6.632,
+            satellites: 9,
+            fix: true,
+            heading: 182.5,
+            time_us: 1_717_243_199_000_000,
+            valid: true,
+        }))
+    );
+    assert_eq!(
+        analog.record,
+        Some(proto::record::Record::Analog(proto::Analog {
+            stamp: expected_stamp(),
+            battery: 11.4,
+            cells: vec![3.8, 3.75, 3.85],
+            current: Some(12.5),
+        }))
+    );
+    let Some(proto::record::Record::Analog(decoded)) = analog.record else { unreachable!() };
+    let decoded = AnalogData::from(decoded);
+    assert_eq!(decoded.cells, [Some(3.8), Some(3.75), Some(3.85), None]);
+    assert_eq!(decoded.current, Some(12.5));
+    assert_eq!(
+        modem.record,
+        Some(proto::record::Record::Modem(proto::Modem {
+            stamp: expected_stamp(),
+            quality: 80,
+            technology: "lte".to_string(),
+            registration: "roaming".to_string(),
+            operator: "Swisscom".to_string(),
+            rsrp: Some(-95.0),
+            rsrq: Some(-11.5),
+            sinr: None,
+        }))
+    );
+    let Some(proto::record::Record::Modem(decoded)) = modem.record else { unreachable!() };
+    let decoded = ModemData::from(decoded);
+    assert_eq!((decoded.status.technology, decoded.status.registration), (Some(AccessTechnology::Lte), Some(Registration::Roaming)));
+    assert_eq!(decoded.status.operator.map(|operator| operator.as_str().to_string()), Some("Swisscom".to_string()));
+    assert_eq!((decoded.status.rsrp, decoded.status.rsrq, decoded.status.sinr), (Some(-95.0), Some(-11.5), None));
+}
+
+#[test]
+fn sensor_status_error() {
+    let status = SensorStatus {
+        available: false,
+        error: Some("Pas de réponse".to_string()),
+        attempts: 3,
+        restarts: 0,
+    };
+
+    let ok: proto::SensorStatus = (&SensorStatus::default()).into();
+    let failed: proto::SensorStatus = (&status).into();
+
+    assert_eq!(ok.error, "");
+    assert!(!failed.available);
+    assert_eq!(failed.error, "Pas de réponse");
+    assert_eq!(failed.attempts, 3);
+}
+
+#[test]
+fn record_round_trip() {
+    let imu = ImuData {
+        stamp: stamp(),
+        angles: (1.0, 2.0, 3.0),
+        temp: 20.0,
+        ..ImuData::default()
+    };
+    let record: proto::Record = Record::Imu(imu).into();
+
+    let decoded = proto::Record::decode(record.encode_to_vec().as_slice()).unwrap();
+
+    assert_eq!(decoded, record);
+}
@@ -0,0 +1,90 @@
+// Rampe de la vitesse moteur: accélération et décélération limitées, passage par le neutre
+
+use voiturerc::actuators::ramp;
+
+use std::time::{Duration, Instant};
+
+use ramp::Ramp;
+
+const STEP: Duration = Duration::from_millis(100);
+
+/// Sorties successives de la rampe, une mise à jour par pas
+fn run(ramp: &mut Ramp, start: Instant, steps: u32) -> Vec<f64> {
+    (1..=steps)
+        .map(|n| (ramp.update(start + STEP * n) * 1000.0).round() / 1000.0)
+        .collect()
+}
+
+#[test]
+fn accelerates_at_limited_rate() {
+    let start = Instant::now();
+    let mut ramp = Ramp::new(2.0, 4.0);
+
+    assert_eq!(ramp.set(1.0, start), 0.0);
+    assert!(!ramp.settled());
+    assert_eq!(run(&mut ramp, start, 6), vec![0.2, 0.4, 0.6, 0.8, 1.0, 1.0]);
+    assert!(ramp.settled());
+}
+
+#[test]
+fn decelerates_through_neutral_on_reversal() {
+    let start = Instant::now();
+    let mut ramp = Ramp::new(2.0, 4.0);
+    ramp.cut(0.8);
+
+    // Décélération jusqu'au neutre, puis accélération en marche arrière
+    ramp.set(-0.4, start);
+    assert_eq!(run(&mut ramp, start, 5), vec![0.4, 0.0, -0.2, -0.4, -0.4]);
+}
+
+#[test]
+fn slows_down_in_same_direction() {
+    let start = Instant::now();
+    let mut ramp = Ramp::new(2.0, 4.0);
+    ramp.cut(1.0);
+
+    ramp.set(0.5, start);
+    assert_eq!(run(&mut ramp, start, 2), vec![0.6, 0.5]);
+}
+
+#[test]
+fn zero_rate_is_unlimited() {
+    let start = Instant::now();
+    let mut ramp = Ramp::new(0.0, 0.0);
+
+    assert_eq!(ramp.set(0.7, start), 0.7);
+    assert!(ramp.settled());
+    assert_eq!(ramp.set(0.2, start), 0.2);
+}
+
+#[test]
+fn cut_bypasses_ramp() {
+    let start = Instant::now();
+    let mut ramp = Ramp::new(2.0, 4.0);
+    ramp.set(1.0, start);
+    run(&mut ramp, start, 5);
+
+    assert_eq!(ramp.cut(0.0), 0.0);
+    assert!(ramp.settled());
+
+    // Pas de saut après une longue pause sans mise à jour
+    let later = start + Duration::from_secs(10);
+    assert_eq!(ramp.set(1.0, later), 0.0);
+    assert_eq!((ramp.update(later + STEP) * 1000.0).round() / 1000.0, 0.2);
+}
+
+#[test]
+fn clamp_caps_target_and_output() {
+    let start = Instant::now();
+    let mut ramp = Ramp::new(2.0, 4.0);
+    ramp.set(1.0, start);
+    run(&mut ramp, start, 1);
+
+    // Sortie sous la limite: la rampe continue jusqu'à la limite, pas jusqu'à la consigne
+    assert_eq!(ramp.clamp(-0.5, 0.5), 0.2);
+    assert_eq!(run(&mut ramp, start + STEP, 3), vec![0.4, 0.5, 0.5]);
+
+    // Sortie au-delà: ramenée sans rampe
+    assert_eq!(ramp.clamp(-0.3, 0.3), 0.3);
+    assert!(ramp.settled());
+}
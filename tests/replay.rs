@@ -0,0 +1,188 @@
+// Rejeu d'un enregistrement cadencé par une horloge virtuelle: écart entre les échantillons selon
+// la vitesse, arrêt en fin de fichier et reprise en boucle
+#![cfg(not(feature = "real-sensors"))]
+
+use voiturerc::{clock, config, sensors};
+
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use chrono::{TimeZone, Utc};
+use clock::Clock;
+use config::Encoding;
+use sensors::reader::Data;
+use sensors::replay::{self, Recorder, ReplayOptions};
+use tokio_util::sync::CancellationToken;
+
+/// Délai réel laissé au thread du rejeu pour réagir
+const SETTLE: Duration = Duration::from_millis(50);
+
+/// Dossier temporaire propre au test
+fn directory(name: &str) -> PathBuf {
+    let directory = std::env::temp_dir().join(format!("rc-telemetrie-replay-{}-{}", name, std::process::id()));
+    let _ = fs::remove_dir_all(&directory);
+    fs::create_dir_all(&directory).unwrap();
+    directory
+}
+
+/// Enregistre `count` échantillons numérotés à partir de 1, un toutes les 100 ms
+fn record(directory: &Path, count: u64) -> PathBuf {
+    let clock = Clock::virtual_at(Utc.with_ymd_and_hms(2024, 6, 1, 12, 0, 0).unwrap());
+    {
+        let mut recorder = Recorder::create(directory, &clock, Encoding::Json).unwrap();
+        for n in 0..count {
+            clock.set(Duration::from_millis(100 * n));
+            let mut data = Data::default();
+            data.imu.stamp = clock.stamp();
+            data.imu.temp = (n + 1) as f32;
+            recorder.write(&data).unwrap();
+        }
+    }
+
+    fs::read_dir(directory).unwrap().next().unwrap().unwrap().path()
+}
+
+/// Rejeu en cours, cadencé par `pace`
+struct Replay {
+    pace: Clock,
+    clock: Clock,
+    data: Arc<Mutex<Data>>,
+    finished: Arc<AtomicBool>,
+    token: CancellationToken,
+}
+
+impl Replay {
+    fn start(path: PathBuf, speed: f64, looping: bool) -> Self {
+        let pace = Clock::virtual_at(Utc::now());
+        let data = Arc::new(Mutex::new(Data::default()));
+        let finished = Arc::new(AtomicBool::new(false));
+        let token = CancellationToken::new();
+        let options = ReplayOptions {
+            path,
+            speed,
+            looping,
+            pace: pace.clone(),
+        };
+        let clock = replay::spawn(options, data.clone(), finished.clone(), token.clone()).unwrap();
+
+        Self {
+            pace,
+            clock,
+            data,
+            finished,
+            token,
+        }
+    }
+
+    /// Avance le temps qui cadence le rejeu
+    fn advance(&self, duration: Duration) {
+        self.pace.set(self.pace.elapsed() + duration);
+    }
+
+    /// Numéro (0: aucun) et horodatage (µs) du dernier échantillon rejoué
+    fn last(&self) -> (u64, u64) {
+        let data = self.data.lock().unwrap();
+        (data.imu.temp as u64, data.imu.stamp.mono_us)
+    }
+
+    /// Attend (en temps réel) que le rejeu atteigne l'échantillon donné, à l'horodatage donné
+    fn wait(&self, sample: u64, stamp_us: u64) {
+        let deadline = Instant::now() + Duration::from_secs(2);
+        while self.last() != (sample, stamp_us) {
+            assert!(
+                Instant::now() < deadline,
+                "échantillon {} à {} µs attendu, {:?} rejoué",
+                sample,
+                stamp_us,
+                self.last()
+            );
+            thread::sleep(Duration::from_millis(1));
+        }
+    }
+}
+
+impl Drop for Replay {
+    fn drop(&mut self) {
+        self.token.cancel();
+        // Réveille le thread s'il attend le temps virtuel
+        self.advance(Duration::from_secs(1));
+    }
+}
+
+/// Les échantillons (100 ms d'écart) sont rejoués tous les `period` de temps cadencé
+fn check_spacing(name: &str, speed: f64, period: Duration) {
+    let replay = Replay::start(record(&directory(name), 3), speed, false);
+
+    // Le premier échantillon est rejoué immédiatement
+    replay.wait(1, 0);
+    assert_eq!(replay.clock.elapsed(), Duration::ZERO);
+
+    for sample in 2..=3 {
+        // Pas avant l'échéance
+        replay.advance(period - Duration::from_millis(1));
+        thread::sleep(SETTLE);
+        assert_eq!(replay.last().0, sample - 1);
+
+        // Dès l'échéance, avec les horodatages d'origine
+        replay.advance(Duration::from_millis(1));
+        replay.wait(sample, 100_000 * (sample - 1));
+        assert_eq!(replay.clock.elapsed(), Duration::from_millis(100 * (sample - 1)));
+    }
+}
+
+#[test]
+fn speed_one_keeps_recorded_spacing() {
+    check_spacing("speed-1", 1.0, Duration::from_millis(100));
+}
+
+#[test]
+fn speed_two_halves_spacing() {
+    check_spacing("speed-2", 2.0, Duration::from_millis(50));
+}
+
+#[test]
+fn stops_at_end_of_file() {
+    let replay = Replay::start(record(&directory("eof"), 3), 1.0, false);
+
+    replay.wait(1, 0);
+    assert!(!replay.finished.load(Ordering::Relaxed));
+
+    replay.advance(Duration::from_millis(200));
+    replay.wait(3, 200_000);
+
+    let deadline = Instant::now() + Duration::from_secs(2);
+    while !replay.finished.load(Ordering::Relaxed) {
+        assert!(Instant::now() < deadline, "rejeu non terminé en fin de fichier");
+        thread::sleep(Duration::from_millis(1));
+    }
+
+    // Plus rien n'est rejoué ensuite
+    replay.advance(Duration::from_secs(1));
+    thread::sleep(SETTLE);
+    assert_eq!(replay.last(), (3, 200_000));
+    assert_eq!(replay.clock.elapsed(), Duration::from_millis(200));
+}
+
+#[test]
+fn loop_restarts_with_increasing_stamps() {
+    let replay = Replay::start(record(&directory("loop"), 3), 1.0, true);
+
+    // Chaque passe reprend au début, horodatée à partir de la fin de la précédente
+    for pass in 0..3 {
+        let start = 200_000 * pass;
+
+        replay.wait(1, start);
+        assert_eq!(replay.clock.elapsed(), Duration::from_micros(start));
+        assert!(!replay.finished.load(Ordering::Relaxed));
+
+        replay.advance(Duration::from_millis(100));
+        replay.wait(2, start + 100_000);
+
+        // Le dernier échantillon est aussitôt suivi de la passe suivante
+        replay.advance(Duration::from_millis(100));
+    }
+}
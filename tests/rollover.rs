@@ -0,0 +1,188 @@
+// Coupure du moteur en cas de retournement: anti-rebond, reprise, maintien et données périmées
+#![cfg(not(feature = "real-sensors"))]
+
+use voiturerc::{actuators, clock, config, sensors};
+
+use std::time::{Duration, Instant};
+
+use actuators::arbiter::{Arbiter, Next};
+use actuators::rollover::{Rollover, Transition};
+use actuators::Control;
+use clock::Clock;
+use config::{LinkLossPolicy, RolloverConfig};
+use sensors::reader::ImuData;
+
+/// IMU: tangage, roulis (degrés)
+fn imu(pitch: f32, roll: f32) -> ImuData {
+    ImuData {
+        angles: (pitch, roll, 90.0),
+        ..ImuData::default()
+    }
+}
+
+fn at(start: Instant, ms: u64) -> Instant {
+    start + Duration::from_millis(ms)
+}
+
+#[test]
+fn debounce_then_recovery() {
+    let mut rollover = Rollover::new(&RolloverConfig::default());
+    let start = Instant::now();
+
+    // Sous l'angle maximal (70°) ou inclinaison brève (moins de 200 ms): pas de coupure
+    assert_eq!(rollover.update(Some(&imu(0.0, 69.0)), at(start, 0)), None);
+    assert_eq!(rollover.update(Some(&imu(0.0, 69.0)), at(start, 500)), None);
+    assert_eq!(rollover.update(Some(&imu(0.0, 80.0)), at(start, 600)), None);
+    assert_eq!(rollover.update(Some(&imu(0.0, 80.0)), at(start, 750)), None);
+    assert_eq!(rollover.update(Some(&imu(0.0, 10.0)), at(start, 780)), None);
+    assert_eq!(rollover.update(Some(&imu(0.0, 80.0)), at(start, 800)), None);
+    assert_eq!(rollover.update(Some(&imu(0.0, 80.0)), at(start, 950)), None);
+
+    // Sur le toit au-delà de l'anti-rebond, tangage ou roulis
+    let triggered = rollover.update(Some(&imu(-5.0, 178.0)), at(start, 1000)).unwrap();
+    assert_eq!(triggered, Transition::Triggered { roll: 178.0, pitch: -5.0, g: 0.0 });
+    assert!(triggered.active());
+    assert_eq!(triggered.event(), "rollover");
+    assert_eq!(
+        triggered.message(),
+        "Retournement (roulis 178.0°, tangage -5.0°, 0.00 g): moteur coupé"
+    );
+
+    // Reprise après le délai d'attitude normale, remis à zéro par une nouvelle inclinaison
+    assert_eq!(rollover.update(Some(&imu(0.0, 5.0)), at(start, 1200)), None);
+    assert_eq!(rollover.update(Some(&imu(-90.0, 5.0)), at(start, 2200)), None);
+    assert_eq!(rollover.update(Some(&imu(0.0, 5.0)), at(start, 2300)), None);
+    assert_eq!(rollover.update(Some(&imu(0.0, 5.0)), at(start, 4200)), None);
+    assert_eq!(rollover.update(Some(&imu(0.0, 5.0)), at(start, 4300)), Some(Transition::Recovered));
+    assert_eq!(rollover.rearm(), None);
+
+    let mut pitched = Rollover::new(&RolloverConfig::default());
+    assert_eq!(pitched.update(Some(&imu(71.0, 0.0)), at(start, 0)), None);
+    assert!(pitched.update(Some(&imu(71.0, 0.0)), at(start, 200)).unwrap().active());
+}
+
+#[test]
+fn stale_imu_never_triggers() {
+    let mut rollover = Rollover::new(&RolloverConfig::default());
+    let start = Instant::now();
+
+    assert_eq!(rollover.update(Some(&imu(0.0, 120.0)), at(start, 0)), None);
+    // Données périmées: l'anti-rebond repart de zéro
+    assert_eq!(rollover.update(None, at(start, 200)), None);
+    assert_eq!(rollover.update(Some(&imu(0.0, 120.0)), at(start, 400)), None);
+    assert_eq!(rollover.update(None, at(start, 2000)), None);
+
+    // Coupure active: levée si l'IMU ne répond plus
+    assert_eq!(rollover.update(Some(&imu(0.0, 120.0)), at(start, 3000)), None);
+    assert!(rollover.update(Some(&imu(0.0, 120.0)), at(start, 3200)).is_some());
+    assert_eq!(rollover.update(None, at(start, 3300)), Some(Transition::Stale));
+    assert_eq!(rollover.update(None, at(start, 3400)), None);
+}
+
+#[test]
+fn latched_until_rearm() {
+    let config = RolloverConfig {
+        latch: true,
+        ..RolloverConfig::default()
+    };
+    let mut rollover = Rollover::new(&config);
+    let start = Instant::now();
+
+    assert_eq!(rollover.update(Some(&imu(0.0, -150.0)), at(start, 0)), None);
+    assert!(rollover.update(Some(&imu(0.0, -150.0)), at(start, 200)).unwrap().active());
+
+    // Ni l'attitude rétablie ni des données périmées ne lèvent la coupure
+    assert_eq!(rollover.update(Some(&imu(0.0, 0.0)), at(start, 1000)), None);
+    assert_eq!(rollover.update(Some(&imu(0.0, 0.0)), at(start, 10_000)), None);
+    assert_eq!(rollover.update(None, at(start, 11_000)), None);
+
+    assert_eq!(rollover.rearm(), Some(Transition::Rearmed));
+    assert_eq!(rollover.rearm(), None);
+    assert_eq!(rollover.update(Some(&imu(0.0, 0.0)), at(start, 12_000)), None);
+}
+
+#[test]
+fn rearm_lifts_cutoff_while_tilted() {
+    let mut rollover = Rollover::new(&RolloverConfig::default());
+    let start = Instant::now();
+
+    assert_eq!(rollover.update(Some(&imu(0.0, 160.0)), at(start, 0)), None);
+    assert!(rollover.update(Some(&imu(0.0, 160.0)), at(start, 200)).unwrap().active());
+
+    // Commande explicite: coupure levée sans attendre, reprise si la voiture reste retournée
+    assert_eq!(rollover.rearm(), Some(Transition::Rearmed));
+    assert_eq!(rollover.update(Some(&imu(0.0, 160.0)), at(start, 300)), None);
+    assert_eq!(rollover.update(Some(&imu(0.0, 160.0)), at(start, 450)), None);
+    assert!(rollover.update(Some(&imu(0.0, 160.0)), at(start, 500)).unwrap().active());
+}
+
+#[test]
+fn crash_cuts_without_debounce() {
+    let config = RolloverConfig {
+        crash_g: 1.5,
+        ..RolloverConfig::default()
+    };
+    let mut rollover = Rollover::new(&config);
+    let start = Instant::now();
+    let sample = |accel: (f32, f32, f32)| ImuData {
+        angles: (3.0, -4.0, 90.0),
+        accel,
+        ..ImuData::default()
+    };
+
+    // Pesanteur et accélération modérée: pas de choc
+    assert_eq!(rollover.impact(&sample((0.0, 0.0, 1.0))), None);
+    assert_eq!(rollover.impact(&sample((0.8, 0.6, 1.0))), None);
+
+    let crash = rollover.impact(&sample((1.2, 0.0, 1.6))).unwrap();
+    assert_eq!(crash, Transition::Crash { roll: -4.0, pitch: 3.0, g: 2.0 });
+    assert!(crash.active());
+    assert_eq!(crash.event(), "crash");
+    assert_eq!(crash.message(), "Choc de 2.00 g (roulis -4.0°, tangage 3.0°): moteur coupé");
+    assert_eq!(rollover.impact(&sample((1.2, 0.0, 1.6))), None);
+
+    // Voiture sur ses roues: reprise après le délai d'attitude normale
+    assert_eq!(rollover.update(Some(&sample((0.0, 0.0, 1.0))), at(start, 0)), None);
+    assert_eq!(rollover.update(Some(&sample((0.0, 0.0, 1.0))), at(start, 2000)), Some(Transition::Recovered));
+
+    // Désactivé par défaut
+    let mut disabled = Rollover::new(&RolloverConfig::default());
+    assert_eq!(disabled.impact(&sample((4.0, 0.0, 1.0))), None);
+}
+
+#[tokio::test]
+async fn cutoff_forces_neutral_throttle() {
+    let (commands, mut arbiter) = Arbiter::new(&Clock::start(), Duration::from_millis(500), LinkLossPolicy::Stop);
+    let control = Control { steer: 0.4, speed: 0.8 };
+
+    commands.submit("test", control).unwrap();
+    let Next::Command(command) = arbiter.next().await else {
+        panic!("commande attendue");
+    };
+    assert_eq!(command.control.speed, 0.8);
+    arbiter.applied(command.control);
+
+    // Coupure: neutre appliqué sans attendre la commande suivante, direction conservée
+    commands.cutoff("rollover", true);
+    let Next::Command(command) = arbiter.next().await else {
+        panic!("coupure attendue");
+    };
+    assert_eq!(command.source, "limit");
+    assert_eq!(command.control.speed, 0.0);
+    assert_eq!(command.control.steer, 0.4);
+
+    commands.submit("test", control).unwrap();
+    let Next::Command(command) = arbiter.next().await else {
+        panic!("commande attendue");
+    };
+    assert_eq!(command.control.speed, 0.0);
+    assert_eq!(command.control.steer, 0.4);
+
+    // Reprise: les commandes suivantes passent telles quelles
+    commands.cutoff("rollover", false);
+    commands.submit("test", control).unwrap();
+    let Next::Command(command) = arbiter.next().await else {
+        panic!("commande attendue");
+    };
+    assert_eq!(command.control.speed, 0.8);
+}
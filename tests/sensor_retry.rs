@@ -0,0 +1,110 @@
+// Capteur perdu en cours de lecture (GPS USB débranché ou renuméroté): le port est fermé, rien
+// n'est lu pendant l'absence, puis il est rouvert après le délai et la lecture reprend.
+// A lancer avec:
+//   cargo test --no-default-features --features fake-sensors,fake-actuators --test sensor_retry
+#![cfg(not(feature = "real-sensors"))]
+
+use voiturerc::sensors;
+
+use std::collections::VecDeque;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use sensors::gps::nmea::{self, Nmea};
+use sensors::reader::GpsData;
+use sensors::retry::Pending;
+
+const GGA: &[u8] = b"$GPGGA,123519,4807.038,N,01131.000,E,1,08,0.9,545.4,M,46.9,M,,*47\r\n";
+
+/// Lectures d'un port série simulé: données, ou erreur (périphérique débranché)
+type Reads = Arc<Mutex<VecDeque<Result<Vec<u8>, &'static str>>>>;
+
+/// Port série simulé, comme sensors::gps::GPS
+struct Port {
+    reads: Reads,
+    nmea: Nmea,
+}
+
+impl Port {
+    fn read(&mut self) -> anyhow::Result<Vec<nmea_parser::ParsedMessage>> {
+        match self.reads.lock().unwrap().pop_front() {
+            Some(Ok(bytes)) => Ok(self.nmea.push(&bytes)),
+            Some(Err(e)) => Err(anyhow::anyhow!(e)),
+            None => Ok(Vec::new()),
+        }
+    }
+}
+
+/// Source GPS simulée, même gestion des erreurs que GpsSource
+struct Source {
+    reads: Reads,
+    /// Périphérique présent, sinon l'ouverture échoue
+    present: Arc<Mutex<bool>>,
+    sensor: Pending<Port>,
+    data: GpsData,
+}
+
+impl Source {
+    fn poll(&mut self) -> bool {
+        let (reads, present) = (self.reads.clone(), self.present.clone());
+        let Some(port) = self.sensor.poll(|| match *present.lock().unwrap() {
+            true => Ok(Port { reads, nmea: Nmea::default() }),
+            false => Err(anyhow::anyhow!("/dev/ttyACM0 absent")),
+        }) else {
+            return false;
+        };
+
+        let messages = match port.read() {
+            Ok(messages) => messages,
+            Err(e) => {
+                self.sensor.lost(&e);
+                return false;
+            }
+        };
+
+        for message in messages.iter() {
+            nmea::apply(&mut self.data, message);
+        }
+        !messages.is_empty()
+    }
+}
+
+#[test]
+fn reopens_port_after_read_error() {
+    let reads: Reads = Default::default();
+    let present = Arc::new(Mutex::new(true));
+    let mut source = Source {
+        reads: reads.clone(),
+        present: present.clone(),
+        sensor: Pending::new("GPS"),
+        data: GpsData::default(),
+    };
+
+    // Débranché au milieu d'une trame: la fin de trame lue ensuite n'est pas mélangée
+    reads.lock().unwrap().extend([Ok(GGA.to_vec()), Ok(GGA[..20].to_vec()), Err("Input/output error")]);
+    assert!(source.poll());
+    assert!(source.data.fix);
+    assert!(!source.poll());
+    assert!(!source.poll());
+    assert!(!source.sensor.status().available);
+    assert!(source.sensor.status().error.as_deref().unwrap().contains("Input/output error"));
+
+    // Port fermé: rien n'est lu, même si des données arrivent
+    *present.lock().unwrap() = false;
+    reads.lock().unwrap().extend([Ok(GGA[20..].to_vec()), Ok(GGA.to_vec())]);
+    assert!(!source.poll());
+    assert_eq!(reads.lock().unwrap().len(), 2);
+    assert_eq!(source.sensor.status().attempts, 1);
+
+    // Périphérique revenu: réouvert après le délai, la trame incomplète est ignorée
+    *present.lock().unwrap() = true;
+    std::thread::sleep(Duration::from_millis(1100));
+    source.data = GpsData::default();
+    assert!(!source.poll());
+    assert!(!source.data.fix);
+    assert!(source.poll());
+    assert!(source.data.fix);
+    assert!(source.sensor.status().available);
+    assert_eq!(source.sensor.status().error, None);
+    assert_eq!(source.sensor.status().attempts, 2);
+}
@@ -0,0 +1,21 @@
+// Signaux d'arrêt: SIGTERM (systemctl stop) et SIGINT mènent au même arrêt propre
+#![cfg(unix)]
+
+use voiturerc::signals;
+
+use std::time::Duration;
+
+use tokio::time::timeout;
+
+use signals::Signals;
+
+#[tokio::test]
+async fn terminate_and_interrupt_stop_the_program() {
+    let mut signals = Signals::install().unwrap();
+
+    for (signal, name) in [(libc::SIGTERM, "SIGTERM"), (libc::SIGINT, "SIGINT")] {
+        assert_eq!(unsafe { libc::kill(libc::getpid(), signal) }, 0);
+        let received = timeout(Duration::from_secs(5), signals.recv()).await;
+        assert_eq!(received, Ok(name));
+    }
+}
@@ -0,0 +1,249 @@
+// Simulateur: reproductibilité, parcours, batterie, commande manuelle et odométrie
+use voiturerc::sensors::sim;
+
+use sim::{ManualControl, Readings, Scenario, Simulation};
+
+/// Une minute de simulation au pas par défaut
+const STEPS: usize = 1200;
+
+/// Scénario par défaut, graine fixée
+fn seeded() -> Scenario {
+    Scenario {
+        seed: Some(1),
+        ..Scenario::default()
+    }
+}
+
+fn run(scenario: &Scenario, steps: usize) -> Vec<Readings> {
+    let mut simulation = Simulation::new(scenario);
+    (0..steps)
+        .map(|_| {
+            simulation.step();
+            simulation.readings().clone()
+        })
+        .collect()
+}
+
+#[test]
+fn same_seed_same_run() {
+    let scenario = seeded();
+
+    assert_eq!(run(&scenario, STEPS), run(&scenario, STEPS));
+}
+
+#[test]
+fn different_seed_different_run() {
+    let first = seeded();
+    let second = Scenario {
+        seed: Some(2),
+        ..seeded()
+    };
+
+    assert_ne!(run(&first, STEPS), run(&second, STEPS));
+}
+
+#[test]
+fn seed_from_config_env_or_random() {
+    // Seul test de ce fichier à lire RC_SIM_SEED
+    std::env::remove_var("RC_SIM_SEED");
+
+    // Sans graine: tirée au hasard, différente à chaque démarrage
+    let first = Scenario::default().with_seed().unwrap().seed.unwrap();
+    let second = Scenario::default().with_seed().unwrap().seed.unwrap();
+    assert_ne!(first, second);
+
+    // Variable d'environnement, sauf graine de la configuration
+    std::env::set_var("RC_SIM_SEED", "42");
+    assert_eq!(Scenario::default().with_seed().unwrap().seed, Some(42));
+    assert_eq!(seeded().with_seed().unwrap().seed, Some(1));
+
+    std::env::set_var("RC_SIM_SEED", "abc");
+    assert!(Scenario::default().with_seed().is_err());
+    std::env::remove_var("RC_SIM_SEED");
+}
+
+#[test]
+fn vehicle_follows_route() {
+    let scenario = seeded();
+    let readings = run(&scenario, STEPS);
+    let last = readings.last().unwrap();
+
+    // Le véhicule roule et reste à proximité du parcours
+    assert!(readings.iter().any(|r| r.speed_kmh > 10.0));
+    assert!((last.latitude - 46.52045).abs() < 0.002);
+    assert!((last.longitude - 6.6310).abs() < 0.003);
+
+    // La batterie se décharge
+    assert!(last.battery < readings[0].battery);
+}
+
+#[test]
+fn three_cell_pack_discharges() {
+    let scenario = seeded();
+    let readings = run(&scenario, STEPS);
+    let (first, last) = (&readings[0], readings.last().unwrap());
+
+    // Prises d'équilibrage cumulées, la dernière au niveau du pack
+    let taps: Vec<f32> = last.taps.iter().flatten().copied().collect();
+    assert_eq!(taps.len(), 3);
+    assert!(taps.windows(2).all(|w| w[1] - w[0] > 3.0 && w[1] - w[0] < 4.3));
+    assert!((taps[2] - last.battery).abs() < 0.1, "{:?} / {}", taps, last.battery);
+    assert!(last.taps[2].unwrap() < first.taps[2].unwrap());
+
+    let scenario = Scenario {
+        battery: sim::BatteryScenario {
+            cells: 0,
+            ..Default::default()
+        },
+        ..seeded()
+    };
+    assert!(run(&scenario, 10).iter().all(|r| r.taps == [None; 4]));
+}
+
+#[test]
+fn manual_control_and_failsafe() {
+    let scenario = seeded();
+    let mut simulation = Simulation::new(&scenario);
+
+    simulation.set_control(Some(ManualControl {
+        steer: 0.0,
+        speed: 1.0,
+    }));
+    for _ in 0..100 {
+        simulation.step();
+    }
+    assert!(simulation.readings().speed_kmh > scenario.max_speed_kmh * 0.9);
+
+    simulation.failsafe(0.0);
+    for _ in 0..100 {
+        simulation.step();
+    }
+    assert!(simulation.readings().speed_kmh < 1.0);
+}
+
+#[test]
+fn wheel_odometer_follows_motor_command() {
+    let scenario = seeded();
+    let mut simulation = Simulation::new(&scenario);
+
+    // A l'arrêt: aucune distance
+    simulation.set_control(Some(ManualControl {
+        steer: 0.0,
+        speed: 0.0,
+    }));
+    for _ in 0..20 {
+        simulation.step();
+    }
+    assert_eq!(simulation.readings().odometer_m, 0.0);
+
+    // Distance cohérente avec la vitesse du véhicule, marche arrière comprise
+    simulation.set_control(Some(ManualControl {
+        steer: 0.0,
+        speed: -0.5,
+    }));
+    let mut expected = 0.0;
+    for _ in 0..100 {
+        simulation.step();
+        expected += simulation.readings().speed_kmh / 3.6 * simulation.step_duration().as_secs_f64();
+    }
+    let odometer = simulation.readings().odometer_m;
+    assert!(odometer > 0.0);
+    assert!((odometer - expected).abs() < expected * 0.05, "{} / {}", odometer, expected);
+}
+
+/// Ecrit un parcours dans un fichier temporaire
+fn track_file(name: &str, content: &str) -> std::path::PathBuf {
+    let path = std::env::temp_dir().join(format!("rc-telemetrie-track-{}-{}", name, std::process::id()));
+    std::fs::write(&path, content).unwrap();
+    path
+}
+
+#[test]
+fn track_from_csv_and_gpx() {
+    let csv = track_file(
+        "csv",
+        "latitude,longitude,speed\n# départ\n46.5200,6.6300,30\n46.5200;6.6320\n46.5209,6.6320,10\n",
+    );
+    let scenario = Scenario {
+        track: Some(csv.clone()),
+        track_speed_kmh: 12.0,
+        ..seeded()
+    }
+    .with_track()
+    .unwrap();
+    std::fs::remove_file(csv).unwrap();
+    assert_eq!(scenario.route, vec![[46.5200, 6.6300], [46.5200, 6.6320], [46.5209, 6.6320]]);
+    assert_eq!(scenario.speeds, vec![30.0, 12.0, 10.0]);
+
+    // Vitesse déduite des heures de passage: 100 m vers le nord en 18 s, soit 20 km/h
+    let gpx = track_file(
+        "gpx",
+        r#"<?xml version="1.0"?>
+<gpx version="1.1"><trk><trkseg>
+  <trkpt lat="46.5200" lon="6.6300"><time>2024-06-01T12:00:00Z</time></trkpt>
+  <trkpt lat="46.520899322" lon="6.6300"><time>2024-06-01T12:00:18Z</time></trkpt>
+  <trkpt lat='46.5209' lon='6.6320'/>
+</trkseg></trk></gpx>"#,
+    );
+    let scenario = Scenario {
+        track: Some(gpx.clone()),
+        ..seeded()
+    }
+    .with_track()
+    .unwrap();
+    std::fs::remove_file(gpx).unwrap();
+    assert_eq!(scenario.route.len(), 3);
+    assert!((scenario.speeds[0] - 20.0).abs() < 0.01, "{:?}", scenario.speeds);
+    assert_eq!(scenario.speeds[1..], [20.0, 20.0]);
+
+    let single = track_file("single", "46.52,6.63\n");
+    let result = Scenario {
+        track: Some(single.clone()),
+        ..seeded()
+    }
+    .with_track();
+    std::fs::remove_file(single).unwrap();
+    assert!(result.is_err());
+}
+
+#[test]
+fn vehicle_follows_track_exactly() {
+    // Carré d'environ 100 m de côté, parcouru dans le sens horaire
+    let scenario = Scenario {
+        route: vec![[46.5200, 6.6300], [46.5209, 6.6300], [46.5209, 6.6313], [46.5200, 6.6313]],
+        speeds: vec![36.0],
+        track: Some("parcours.gpx".into()),
+        ..seeded()
+    };
+    let mut simulation = Simulation::new(&scenario);
+
+    // Premier segment vers le nord: sur la ligne, cap GPS et magnétomètre cohérents
+    for _ in 0..100 {
+        simulation.step();
+        let readings = simulation.readings();
+        assert!((readings.longitude - 6.6300).abs() < 0.0001, "{}", readings.longitude);
+        assert!(wrap(readings.gps_heading) < 10.0, "{}", readings.gps_heading);
+        assert!(wrap(readings.mag_heading as f64) < 5.0, "{}", readings.mag_heading);
+    }
+
+    // Deuxième segment vers l'est
+    for _ in 0..200 {
+        simulation.step();
+    }
+    let readings = simulation.readings();
+    assert!((readings.latitude - 46.5209).abs() < 0.0001, "{}", readings.latitude);
+    assert!((readings.gps_heading - 90.0).abs() < 10.0, "{}", readings.gps_heading);
+
+    // Environ 400 m à 10 m/s: retour au début du parcours après un tour
+    for _ in 0..600 {
+        simulation.step();
+    }
+    let readings = simulation.readings();
+    assert!(readings.longitude < 6.6302 && readings.latitude > 46.5200, "{:?}", readings);
+    assert!(wrap(readings.gps_heading) < 10.0, "{}", readings.gps_heading);
+}
+
+/// Ecart angulaire au nord (°)
+fn wrap(heading: f64) -> f64 {
+    heading.min(360.0 - heading)
+}
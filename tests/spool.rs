@@ -0,0 +1,188 @@
+// Tampon local des échantillons: coupure de la base, redémarrage, relecture dans l'ordre,
+// suppression des plus anciens et segment tronqué
+#![cfg(not(feature = "real-sensors"))]
+
+use voiturerc::{clock, config, record, sensors, spool};
+
+use std::path::PathBuf;
+
+use clock::Stamp;
+use config::SpoolConfig;
+use record::Record;
+use sensors::reader::ImuData;
+use spool::Spool;
+
+/// Tampon dans un dossier temporaire propre au test
+fn config(name: &str, max_records: u64) -> SpoolConfig {
+    let directory = std::env::temp_dir().join(format!("rc-telemetrie-spool-{}-{}", name, std::process::id()));
+    let _ = std::fs::remove_dir_all(&directory);
+    SpoolConfig {
+        enabled: true,
+        directory,
+        max_records,
+        max_mb: 64,
+    }
+}
+
+fn record(n: u64) -> Record {
+    Record::Imu(ImuData {
+        stamp: Stamp {
+            mono_us: n,
+            ..Stamp::default()
+        },
+        angles: (n as f32, 0.0, 0.0),
+        temp: 20.0,
+        ..ImuData::default()
+    })
+}
+
+/// Base simulée: écriture refusée tant qu'elle est coupée
+struct Sink {
+    up: bool,
+    written: Vec<u64>,
+}
+
+impl Sink {
+    fn write(&mut self, record: &Record) -> bool {
+        if self.up {
+            self.written.push(record.stamp().mono_us);
+        }
+        self.up
+    }
+}
+
+/// Relit le tampon comme l'écrivain, retourne le nombre d'échantillons relus
+fn replay(spool: &mut Spool, sink: &mut Sink) -> usize {
+    let mut replayed = 0;
+    while let Some(record) = spool.front().unwrap() {
+        if !sink.write(&record) {
+            break;
+        }
+        spool.pop();
+        replayed += 1;
+    }
+    replayed
+}
+
+fn segments(directory: &PathBuf) -> usize {
+    std::fs::read_dir(directory).unwrap().count()
+}
+
+#[test]
+fn replays_all_records_after_outage_and_restart() {
+    let config = config("outage", 1000);
+    let mut sink = Sink {
+        up: true,
+        written: Vec::new(),
+    };
+
+    // Connexion coupée: les échantillons sont gardés dans le tampon
+    let mut spool = Spool::open(&config).unwrap();
+    sink.up = false;
+    for n in 0..300 {
+        let record = record(n);
+        if !spool.is_empty() || !sink.write(&record) {
+            spool.push(&record);
+        }
+    }
+    spool.flush();
+    assert_eq!(replay(&mut spool, &mut sink), 0);
+    assert_eq!(spool.stats().records, 300);
+
+    // Redémarrage pendant la coupure
+    drop(spool);
+    let mut spool = Spool::open(&config).unwrap();
+    assert_eq!(spool.stats().records, 300);
+
+    // Connexion rétablie: tout arrive, dans l'ordre, avec l'horodatage d'origine
+    sink.up = true;
+    assert_eq!(replay(&mut spool, &mut sink), 300);
+    assert_eq!(sink.written, (0..300).collect::<Vec<u64>>());
+    assert!(spool.is_empty());
+    assert_eq!(spool.stats().replayed, 300);
+    assert_eq!(segments(&config.directory), 0);
+
+    std::fs::remove_dir_all(&config.directory).unwrap();
+}
+
+#[test]
+fn restart_during_replay_resends_current_segment_only() {
+    let config = config("partial", 1000);
+    let mut sink = Sink {
+        up: true,
+        written: Vec::new(),
+    };
+
+    let mut spool = Spool::open(&config).unwrap();
+    for n in 0..400 {
+        spool.push(&record(n));
+    }
+    spool.flush();
+
+    // Arrêt au milieu du premier segment (125 échantillons par segment)
+    for _ in 0..200 {
+        let record = spool.front().unwrap().unwrap();
+        sink.write(&record);
+        spool.pop();
+    }
+    drop(spool);
+
+    let mut spool = Spool::open(&config).unwrap();
+    assert_eq!(spool.stats().records, 275);
+    replay(&mut spool, &mut sink);
+
+    // Premier segment supprimé une fois relu, le second est relu depuis son début
+    let expected: Vec<u64> = (0..200).chain(125..400).collect();
+    assert_eq!(sink.written, expected);
+
+    std::fs::remove_dir_all(&config.directory).unwrap();
+}
+
+#[test]
+fn evicts_oldest_when_full() {
+    let config = config("evict", 100);
+    let mut spool = Spool::open(&config).unwrap();
+    for n in 0..250 {
+        spool.push(&record(n));
+    }
+
+    let stats = spool.stats();
+    assert!(stats.records <= 100, "{} échantillons", stats.records);
+    assert_eq!(stats.records + stats.evicted, 250);
+
+    // Les plus récents sont conservés
+    let mut sink = Sink {
+        up: true,
+        written: Vec::new(),
+    };
+    replay(&mut spool, &mut sink);
+    assert_eq!(sink.written, (stats.evicted..250).collect::<Vec<u64>>());
+
+    std::fs::remove_dir_all(&config.directory).unwrap();
+}
+
+#[test]
+fn truncated_segment_keeps_complete_records() {
+    let config = config("truncated", 1000);
+    let mut spool = Spool::open(&config).unwrap();
+    for n in 0..10 {
+        spool.push(&record(n));
+    }
+    spool.flush();
+    drop(spool);
+
+    // Arrêt pendant l'écriture du dernier échantillon
+    let path = std::fs::read_dir(&config.directory).unwrap().next().unwrap().unwrap().path();
+    let content = std::fs::read(&path).unwrap();
+    std::fs::write(&path, &content[..content.len() - 3]).unwrap();
+
+    let mut spool = Spool::open(&config).unwrap();
+    assert_eq!(spool.stats().records, 9);
+    let mut sink = Sink {
+        up: true,
+        written: Vec::new(),
+    };
+    assert_eq!(replay(&mut spool, &mut sink), 9);
+
+    std::fs::remove_dir_all(&config.directory).unwrap();
+}
@@ -0,0 +1,86 @@
+// Télémétrie FrSky S.Port: identifiants, trames de référence et réponses aux interrogations
+#![cfg(not(feature = "real-sensors"))]
+
+use voiturerc::{config, sport};
+
+use config::{SportSensor, SportValue};
+use sport::protocol::{crc, frame, physical_id, sensor_id, Polls, Value};
+use sport::responder::Responder;
+
+#[test]
+fn physical_ids() {
+    let expected = [
+        0x00, 0xA1, 0x22, 0x83, 0xE4, 0x45, 0xC6, 0x67, 0x48, 0xE9, 0x6A, 0xCB, 0xAC, 0x0D, 0x8E, 0x2F, 0xD0, 0x71,
+        0xF2, 0x53, 0x34, 0x95, 0x16, 0xB7, 0x98, 0x39, 0xBA, 0x1B,
+    ];
+    for (id, physical) in expected.iter().enumerate() {
+        assert_eq!(physical_id(id as u8), *physical);
+        assert_eq!(sensor_id(*physical), Some(id as u8));
+    }
+
+    // Parité fausse, identifiant hors du bus
+    assert_eq!(sensor_id(0x01), None);
+    assert_eq!(sensor_id(0x1C), None);
+}
+
+#[test]
+fn reference_frames() {
+    // 12,34 V
+    assert_eq!(frame(0x0210, 1234), [0x10, 0x10, 0x02, 0xD2, 0x04, 0x00, 0x00, 0x07]);
+    assert_eq!(Value::Vfas(12.34).frame(), frame(0x0210, 1234));
+
+    // Octets 0x7E et 0x7D échappés dans la valeur
+    assert_eq!(frame(0x0200, 0x7E), [0x10, 0x00, 0x02, 0x7D, 0x5E, 0x00, 0x00, 0x00, 0x6F]);
+    assert_eq!(frame(0x0840, 0x7D), [0x10, 0x40, 0x08, 0x7D, 0x5D, 0x00, 0x00, 0x00, 0x2A]);
+
+    // Somme de contrôle échappée
+    assert_eq!(crc(&[0x10, 0x10, 0x02, 0x5F, 0x00, 0x00, 0x00]), 0x7E);
+    assert_eq!(frame(0x0210, 0x5F), [0x10, 0x10, 0x02, 0x5F, 0x00, 0x00, 0x00, 0x7D, 0x5E]);
+}
+
+#[test]
+fn encoded_values() {
+    assert_eq!(Value::Current(12.5).encode(), (0x0200, 125));
+    assert_eq!(Value::Latitude(48.8566).encode(), (0x0800, 0x01BF_4BA8));
+    assert_eq!(Value::Longitude(2.3522).encode(), (0x0800, 0x8015_88F8));
+    assert_eq!(Value::Latitude(-33.8688).encode(), (0x0800, 0x4136_1400));
+    assert_eq!(Value::Longitude(-151.2093).encode(), (0x0800, 0xC568_5CCC));
+    // 1,852 km/h = 1 noeud
+    assert_eq!(Value::Speed(1.852).encode(), (0x0830, 1000));
+    assert_eq!(Value::Heading(-90.0).encode(), (0x0840, 27000));
+}
+
+#[test]
+fn polls() {
+    let mut polls = Polls::default();
+    let received = [0x10, 0x7E, 0xA1, 0x7E, 0x7E, 0x22, 0xA1, 0x7E, 0x01, 0x7E];
+    let polled: Vec<_> = received.iter().filter_map(|byte| polls.push(*byte)).collect();
+    assert_eq!(polled, [1, 2]);
+}
+
+#[test]
+fn responder_cycles_known_values() {
+    let mut responder = Responder::new(&[SportSensor {
+        id: 3,
+        values: vec![SportValue::Latitude, SportValue::Speed, SportValue::Heading],
+    }]);
+    let fix = |kind| match kind {
+        SportValue::Latitude => Some(Value::Latitude(48.0)),
+        SportValue::Speed => Some(Value::Speed(10.0)),
+        SportValue::Heading => Some(Value::Heading(90.0)),
+        _ => None,
+    };
+    let no_fix = |kind| match kind {
+        SportValue::Heading => Some(Value::Heading(90.0)),
+        _ => None,
+    };
+
+    assert_eq!(responder.next(3, fix), Some(Value::Latitude(48.0)));
+    assert_eq!(responder.next(3, fix), Some(Value::Speed(10.0)));
+    // Sans fix, seul le cap est transmis
+    assert_eq!(responder.next(3, no_fix), Some(Value::Heading(90.0)));
+    assert_eq!(responder.next(3, no_fix), Some(Value::Heading(90.0)));
+    assert_eq!(responder.next(3, |_| None), None);
+    // Capteur non simulé: pas de réponse
+    assert_eq!(responder.next(4, fix), None);
+}
@@ -0,0 +1,78 @@
+// LED d'état: état signalé selon la santé, clignotements et extinction à l'arrêt
+#![cfg(not(feature = "real-sensors"))]
+
+use voiturerc::{actuators, config};
+
+use std::cell::RefCell;
+use std::rc::Rc;
+use std::time::{Duration, Instant};
+
+use actuators::status_led::{pattern, LedOutput, Pattern, StatusLed};
+use config::StatusLedConfig;
+
+/// Sortie enregistrant chaque changement de niveau et d'état
+#[derive(Clone, Default)]
+struct Recorded {
+    levels: Rc<RefCell<Vec<bool>>>,
+    patterns: Rc<RefCell<Vec<Pattern>>>,
+}
+
+impl LedOutput for Recorded {
+    fn set(&mut self, on: bool) {
+        self.levels.borrow_mut().push(on);
+    }
+
+    fn pattern(&mut self, pattern: Pattern) {
+        self.patterns.borrow_mut().push(pattern);
+    }
+}
+
+fn at(start: Instant, ms: u64) -> Instant {
+    start + Duration::from_millis(ms)
+}
+
+#[test]
+fn failsafe_takes_precedence_over_degraded() {
+    assert_eq!(pattern(true, false, false, false), Pattern::Solid);
+    assert_eq!(pattern(false, false, false, false), Pattern::SlowBlink);
+    assert_eq!(pattern(true, true, false, false), Pattern::SlowBlink);
+    assert_eq!(pattern(true, false, true, false), Pattern::SlowBlink);
+    assert_eq!(pattern(false, true, true, true), Pattern::FastBlink);
+}
+
+#[test]
+fn blinks_at_configured_periods_and_turns_off() {
+    let output = Recorded::default();
+    let config = StatusLedConfig {
+        slow_period_ms: 1000,
+        fast_period_ms: 200,
+        ..StatusLedConfig::default()
+    };
+    let start = Instant::now();
+    let mut led = StatusLed::new(output.clone(), &config, start);
+
+    assert!(led.update(Pattern::Solid, start));
+    assert!(!led.update(Pattern::Solid, at(start, 700)));
+    assert_eq!(*output.levels.borrow(), vec![true]);
+
+    // Lent: allumée 500 ms puis éteinte 500 ms, depuis le changement d'état
+    assert!(led.update(Pattern::SlowBlink, at(start, 1000)));
+    for ms in [1200, 1500, 1999, 2000] {
+        led.update(Pattern::SlowBlink, at(start, ms));
+    }
+    assert_eq!(*output.levels.borrow(), vec![true, false, true]);
+
+    // Rapide: 100 ms par état
+    led.update(Pattern::FastBlink, at(start, 2050));
+    for ms in [2150, 2250, 2350] {
+        led.update(Pattern::FastBlink, at(start, ms));
+    }
+    assert_eq!(*output.levels.borrow(), vec![true, false, true, false, true, false]);
+
+    led.update(Pattern::Off, at(start, 2400));
+    assert_eq!(output.levels.borrow().last(), Some(&false));
+    assert_eq!(
+        *output.patterns.borrow(),
+        vec![Pattern::Solid, Pattern::SlowBlink, Pattern::FastBlink, Pattern::Off]
+    );
+}
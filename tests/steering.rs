@@ -0,0 +1,76 @@
+// Calibration de la direction: trim, butées asymétriques, inversion et limitation des commandes
+#![cfg(not(feature = "real-sensors"))]
+
+use voiturerc::{actuators, config};
+
+use actuators::duty::calibrated_steering;
+use config::{Config, SteeringConfig};
+
+/// Impulsion (µs) à 50 Hz
+fn pulse(cycle: f64) -> f64 {
+    (cycle * 20_000.0 * 1000.0).round() / 1000.0
+}
+
+fn calibration(center_us: f64, left_us: f64, right_us: f64, reverse: bool) -> SteeringConfig {
+    SteeringConfig {
+        center_us,
+        left_us,
+        right_us,
+        reverse,
+    }
+}
+
+#[test]
+fn default_calibration() {
+    let default = SteeringConfig::default();
+    for (steer, cycle) in [(-1.0, 0.088), (-0.5, 0.082), (0.0, 0.076), (0.5, 0.070), (1.0, 0.064)] {
+        assert!((calibrated_steering(steer, &default) - cycle).abs() < 1e-9, "{}", steer);
+    }
+}
+
+#[test]
+fn trim_and_asymmetric_endpoints() {
+    let servo = calibration(1540.0, 1740.0, 1340.0, false);
+
+    assert_eq!(pulse(calibrated_steering(0.0, &servo)), 1540.0);
+    assert_eq!(pulse(calibrated_steering(-1.0, &servo)), 1740.0);
+    assert_eq!(pulse(calibrated_steering(-0.5, &servo)), 1640.0);
+    assert_eq!(pulse(calibrated_steering(1.0, &servo)), 1340.0);
+    assert_eq!(pulse(calibrated_steering(0.25, &servo)), 1490.0);
+}
+
+#[test]
+fn commands_clamped_to_endpoints() {
+    let servo = calibration(1540.0, 1740.0, 1340.0, false);
+
+    assert_eq!(pulse(calibrated_steering(-3.0, &servo)), 1740.0);
+    assert_eq!(pulse(calibrated_steering(1.5, &servo)), 1340.0);
+    assert_eq!(pulse(calibrated_steering(f64::INFINITY, &servo)), 1340.0);
+    assert_eq!(pulse(calibrated_steering(f64::NAN, &servo)), 1540.0);
+}
+
+#[test]
+fn reversal_swaps_sides_around_trimmed_center() {
+    let servo = calibration(1540.0, 1740.0, 1340.0, true);
+
+    assert_eq!(pulse(calibrated_steering(0.0, &servo)), 1540.0);
+    assert_eq!(pulse(calibrated_steering(-1.0, &servo)), 1340.0);
+    assert_eq!(pulse(calibrated_steering(0.5, &servo)), 1640.0);
+    assert_eq!(pulse(calibrated_steering(2.0, &servo)), 1740.0);
+}
+
+#[test]
+fn invalid_calibration_rejected() {
+    let mut config = Config::default();
+    config.validate().unwrap();
+
+    config.control.steering = calibration(1520.0, 1760.0, 1280.0, true);
+    config.validate().unwrap();
+
+    // Centre en dehors des butées
+    config.control.steering = calibration(1800.0, 1760.0, 1280.0, false);
+    assert!(config.validate().is_err());
+
+    config.control.steering = calibration(1520.0, 3000.0, 1280.0, false);
+    assert!(config.validate().is_err());
+}
@@ -0,0 +1,252 @@
+// Ecritures de l'écrivain vers une base simulée (TelemetryStore): appels reçus, échecs à la
+// demande et tampon local, puis relecture du tampon vers une base embarquée (mem://)
+#![cfg(not(feature = "real-sensors"))]
+
+use voiturerc::{breaker, clock, config, database, record, sensors, spool, writer};
+
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use serde::Deserialize;
+use tokio::sync::watch;
+use tokio::time::{sleep, timeout};
+
+use breaker::Breaker;
+use clock::{Clock, Stamp};
+use config::{BreakerConfig, Config, SpoolConfig, WriterConfig};
+use database::{Connection, Database, TelemetryStore};
+use record::Record;
+use sensors::can::CanData;
+use sensors::reader::{GpsData, ImuData};
+use spool::Spool;
+use writer::{drain, Writer};
+
+/// Base simulée: écritures reçues dans l'ordre, refusées, lentes ou déconnectée sur demande
+#[derive(Default)]
+struct MockStore {
+    calls: Mutex<Vec<(&'static str, u64)>>,
+    fail: AtomicBool,
+    disconnected: AtomicBool,
+    slow: AtomicBool,
+}
+
+impl MockStore {
+    fn calls(&self) -> Vec<(&'static str, u64)> {
+        self.calls.lock().unwrap().clone()
+    }
+}
+
+impl TelemetryStore for MockStore {
+    fn connection(&self) -> Connection {
+        Connection {
+            connected: !self.disconnected.load(Ordering::Relaxed),
+            ..Connection::default()
+        }
+    }
+
+    async fn send_record(&self, record: Record) -> anyhow::Result<()> {
+        if self.slow.load(Ordering::Relaxed) {
+            sleep(Duration::from_millis(2)).await;
+        }
+        self.calls.lock().unwrap().push((record.kind(), record.stamp().mono_us));
+        if self.fail.load(Ordering::Relaxed) {
+            anyhow::bail!("écriture refusée");
+        }
+        Ok(())
+    }
+
+    async fn send_can(&self, data: CanData) -> anyhow::Result<()> {
+        self.calls.lock().unwrap().push(("can", data.stamp.mono_us));
+        Ok(())
+    }
+}
+
+/// Files de l'écrivain, sans tâche d'écriture
+fn writer() -> Writer {
+    let (_, connection) = watch::channel(Connection::default());
+    Writer::new(&WriterConfig::default(), connection).0
+}
+
+/// Coupe-circuit désactivé: chaque écriture atteint la base simulée
+fn breaker() -> Breaker {
+    let config = BreakerConfig {
+        enabled: false,
+        ..BreakerConfig::default()
+    };
+    Breaker::new(&config, &Clock::start())
+}
+
+/// Tampon dans un dossier temporaire propre au test
+fn spool(name: &str) -> Option<Spool> {
+    let directory = std::env::temp_dir().join(format!("rc-telemetrie-store-{}-{}", name, std::process::id()));
+    let _ = std::fs::remove_dir_all(&directory);
+    let config = SpoolConfig {
+        enabled: true,
+        directory,
+        ..SpoolConfig::default()
+    };
+    Some(Spool::open(&config).unwrap())
+}
+
+fn stamp(mono_us: u64) -> Stamp {
+    Stamp {
+        mono_us,
+        ..Stamp::default()
+    }
+}
+
+fn imu(mono_us: u64) -> ImuData {
+    ImuData {
+        stamp: stamp(mono_us),
+        ..ImuData::default()
+    }
+}
+
+#[tokio::test]
+async fn each_sample_written_once() {
+    let store = MockStore::default();
+    let writer = writer();
+    let mut breaker = breaker();
+    let mut spool = None;
+
+    writer.imu(imu(1));
+    writer
+        .gps(GpsData {
+            stamp: stamp(2),
+            ..GpsData::default()
+        })
+        .await;
+    writer.imu(imu(3));
+
+    drain(&store, &writer, &mut breaker, &mut spool).await;
+    drain(&store, &writer, &mut breaker, &mut spool).await;
+
+    // GPS écrit en premier, puis chaque file dans l'ordre, une seule fois
+    assert_eq!(store.calls(), vec![("gps", 2), ("imu", 1), ("imu", 3)]);
+}
+
+#[tokio::test]
+async fn failed_writes_replayed_from_spool() {
+    let store = MockStore::default();
+    let writer = writer();
+    let mut breaker = breaker();
+    let mut spool = spool("failed");
+
+    store.fail.store(true, Ordering::Relaxed);
+    for n in 1..=3 {
+        writer.imu(imu(n));
+    }
+    drain(&store, &writer, &mut breaker, &mut spool).await;
+
+    // Premier échec: les suivants vont directement au tampon, derrière lui
+    assert_eq!(store.calls(), vec![("imu", 1)]);
+    assert_eq!(spool.as_ref().unwrap().stats().records, 3);
+
+    // Base rétablie: le tampon est relu avant le nouvel échantillon
+    store.fail.store(false, Ordering::Relaxed);
+    store.calls.lock().unwrap().clear();
+    writer.imu(imu(4));
+    while drain(&store, &writer, &mut breaker, &mut spool).await {}
+    assert_eq!(store.calls(), vec![("imu", 1), ("imu", 2), ("imu", 3), ("imu", 4)]);
+    assert!(spool.as_ref().unwrap().is_empty());
+}
+
+#[tokio::test]
+async fn disconnected_store_not_called() {
+    let store = MockStore::default();
+    let writer = writer();
+    let mut breaker = breaker();
+    let mut spool = spool("disconnected");
+
+    store.disconnected.store(true, Ordering::Relaxed);
+    writer.imu(imu(1));
+    drain(&store, &writer, &mut breaker, &mut spool).await;
+
+    assert!(store.calls().is_empty());
+    assert_eq!(spool.as_ref().unwrap().stats().records, 1);
+}
+
+#[tokio::test]
+async fn gps_fixes_never_dropped_by_slow_store() {
+    let store = Arc::new(MockStore::default());
+    store.slow.store(true, Ordering::Relaxed);
+    let config = WriterConfig {
+        gps_queue: 4,
+        ..WriterConfig::default()
+    };
+    let (_, connection) = watch::channel(Connection::default());
+    let (writer, _events, notify) = Writer::new(&config, connection);
+
+    // Tâche d'écriture, plus lente que les positions reçues
+    let task = tokio::spawn({
+        let writer = writer.clone();
+        let store = store.clone();
+        async move {
+            let mut breaker = breaker();
+            let mut spool = None;
+            loop {
+                notify.notified().await;
+                drain(store.as_ref(), &writer, &mut breaker, &mut spool).await;
+            }
+        }
+    });
+
+    // File pleine: l'émetteur attend au lieu de perdre les positions les plus anciennes
+    for n in 1..=100 {
+        let data = GpsData {
+            stamp: stamp(n),
+            ..GpsData::default()
+        };
+        writer.gps(data).await;
+    }
+    timeout(Duration::from_secs(5), async {
+        while store.calls().len() < 100 {
+            sleep(Duration::from_millis(10)).await;
+        }
+    })
+    .await
+    .unwrap();
+    task.abort();
+
+    let expected: Vec<(&'static str, u64)> = (1..=100).map(|n| ("gps", n)).collect();
+    assert_eq!(store.calls(), expected);
+    assert_eq!(writer.stats().gps.dropped, 0);
+}
+
+#[derive(Deserialize)]
+struct Sample {
+    stamp: Stamp,
+}
+
+#[tokio::test]
+async fn replayed_samples_kept_in_history() {
+    let store = MockStore::default();
+    let writer = writer();
+    let mut breaker = breaker();
+    let mut spool = spool("history");
+
+    store.disconnected.store(true, Ordering::Relaxed);
+    for n in 1..=5 {
+        writer.imu(imu(n));
+    }
+    drain(&store, &writer, &mut breaker, &mut spool).await;
+    assert_eq!(spool.as_ref().unwrap().stats().records, 5);
+
+    // Relecture vers la base: un enregistrement par échantillon, la dernière valeur à part
+    let config = Config::default();
+    let client = surrealdb::engine::any::connect("mem://").await.unwrap();
+    let db = Database::open(client.clone(), &config.database, false).await.unwrap();
+    writer.imu(imu(6));
+    while drain(&db, &writer, &mut breaker, &mut spool).await {}
+    assert!(spool.as_ref().unwrap().is_empty());
+
+    let mut result = client.query("SELECT stamp FROM imu_history;").await.unwrap();
+    let samples: Vec<Sample> = result.take(0).unwrap();
+    let mut stamps: Vec<u64> = samples.iter().map(|sample| sample.stamp.mono_us).collect();
+    stamps.sort();
+    assert_eq!(stamps, vec![1, 2, 3, 4, 5, 6]);
+
+    let mut result = client.query("SELECT VALUE imu_stamp.mono_us FROM ONLY nav:realtime;").await.unwrap();
+    assert_eq!(result.take::<Option<u64>>(0).unwrap(), Some(6));
+}
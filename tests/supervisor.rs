@@ -0,0 +1,160 @@
+// Supervision des sources des capteurs: redémarrage après une panique ou un blocage
+#![cfg(not(feature = "real-sensors"))]
+
+use voiturerc::{config, sensors};
+
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use config::SupervisorConfig;
+use sensors::reader::{Data, SensorStatus};
+use sensors::source::{Kind, Source};
+use sensors::supervisor::Supervised;
+
+#[derive(Clone, Copy)]
+enum Behaviour {
+    Samples,
+    Panics,
+    Silent,
+}
+
+/// Source de test: produit des échantillons, panique ou reste muette selon le comportement
+/// choisi par le test
+struct Scripted {
+    behaviour: Arc<Mutex<Behaviour>>,
+    status: SensorStatus,
+}
+
+impl Source for Scripted {
+    fn poll(&mut self, _data: &mut Data) -> bool {
+        let behaviour = *self.behaviour.lock().unwrap();
+        match behaviour {
+            Behaviour::Samples => true,
+            Behaviour::Panics => panic!("lecture I2C impossible"),
+            Behaviour::Silent => false,
+        }
+    }
+
+    fn status(&self) -> &SensorStatus {
+        &self.status
+    }
+}
+
+fn scripted(behaviour: &Arc<Mutex<Behaviour>>, available: bool) -> Box<dyn Source> {
+    Box::new(Scripted {
+        behaviour: behaviour.clone(),
+        status: SensorStatus {
+            available,
+            error: None,
+            attempts: 1,
+            restarts: 0,
+        },
+    })
+}
+
+fn behaviour(behaviour: Behaviour) -> Arc<Mutex<Behaviour>> {
+    Arc::new(Mutex::new(behaviour))
+}
+
+fn config() -> SupervisorConfig {
+    SupervisorConfig {
+        max_restarts: 2,
+        backoff_ms: 100,
+        stuck_s: 1,
+    }
+}
+
+fn at(start: Instant, ms: u64) -> Instant {
+    start + Duration::from_millis(ms)
+}
+
+#[test]
+fn panicking_source_is_rebuilt_after_backoff() {
+    let start = Instant::now();
+    let mut data = Data::default();
+    let script = behaviour(Behaviour::Panics);
+    let mut supervised = Supervised::new(Kind::Imu, scripted(&script, true), &config(), start);
+
+    assert!(!supervised.poll(&mut data, at(start, 0), |_| unreachable!()));
+    assert_eq!(supervised.restarts(), 1);
+    assert!(!supervised.status().available);
+    assert_eq!(supervised.status().error.as_deref(), Some("panique: lecture I2C impossible"));
+
+    // Attente de 100 ms avant la recréation
+    *script.lock().unwrap() = Behaviour::Samples;
+    assert!(!supervised.poll(&mut data, at(start, 50), |_| unreachable!()));
+    assert!(supervised.poll(&mut data, at(start, 100), |kind| {
+        assert_eq!(kind, Kind::Imu);
+        Ok(scripted(&script, true))
+    }));
+    assert!(supervised.status().available);
+    assert_eq!(supervised.restarts(), 1);
+}
+
+#[test]
+fn gives_up_after_max_restarts() {
+    let start = Instant::now();
+    let mut data = Data::default();
+    let script = behaviour(Behaviour::Panics);
+    let mut supervised = Supervised::new(Kind::Mag, scripted(&script, true), &config(), start);
+
+    supervised.poll(&mut data, at(start, 0), |_| unreachable!());
+    // Attente doublée à chaque redémarrage: 100 ms, puis 200 ms
+    supervised.poll(&mut data, at(start, 100), |_| Ok(scripted(&script, true)));
+    assert_eq!(supervised.restarts(), 2);
+    assert!(!supervised.poll(&mut data, at(start, 250), |_| unreachable!()));
+    supervised.poll(&mut data, at(start, 300), |_| Ok(scripted(&script, true)));
+    assert_eq!(supervised.restarts(), 3);
+
+    assert!(!supervised.poll(&mut data, at(start, 60_000), |_| unreachable!()));
+    assert_eq!(supervised.restarts(), 3);
+}
+
+#[test]
+fn failed_rebuild_counts_as_restart() {
+    let start = Instant::now();
+    let mut data = Data::default();
+    let script = behaviour(Behaviour::Panics);
+    let mut supervised = Supervised::new(Kind::Baro, scripted(&script, true), &config(), start);
+
+    supervised.poll(&mut data, at(start, 0), |_| unreachable!());
+    supervised.poll(&mut data, at(start, 100), |_| Err(anyhow::anyhow!("bus absent")));
+    assert_eq!(supervised.restarts(), 2);
+    assert_eq!(supervised.status().error.as_deref(), Some("recréation impossible: bus absent"));
+    assert_eq!(supervised.status().restarts, 2);
+}
+
+#[test]
+fn stuck_source_is_restarted_once_it_produced_samples() {
+    let start = Instant::now();
+    let mut data = Data::default();
+
+    // Jamais d'échantillon: pas de blocage détecté
+    let silent = behaviour(Behaviour::Silent);
+    let mut supervised = Supervised::new(Kind::Gps, scripted(&silent, true), &config(), start);
+    assert!(!supervised.poll(&mut data, at(start, 5_000), |_| unreachable!()));
+    assert_eq!(supervised.restarts(), 0);
+
+    let script = behaviour(Behaviour::Samples);
+    let mut supervised = Supervised::new(Kind::Gps, scripted(&script, true), &config(), start);
+    assert!(supervised.poll(&mut data, at(start, 0), |_| unreachable!()));
+    *script.lock().unwrap() = Behaviour::Silent;
+    assert!(!supervised.poll(&mut data, at(start, 500), |_| unreachable!()));
+    assert_eq!(supervised.restarts(), 0);
+    assert!(!supervised.poll(&mut data, at(start, 1_000), |_| unreachable!()));
+    assert_eq!(supervised.restarts(), 1);
+    assert_eq!(supervised.status().error.as_deref(), Some("aucun échantillon depuis 1 s"));
+}
+
+#[test]
+fn unavailable_source_is_left_to_its_own_retry() {
+    let start = Instant::now();
+    let mut data = Data::default();
+    let script = behaviour(Behaviour::Samples);
+    let mut supervised = Supervised::new(Kind::Range, scripted(&script, false), &config(), start);
+
+    assert!(supervised.poll(&mut data, at(start, 0), |_| unreachable!()));
+    *script.lock().unwrap() = Behaviour::Silent;
+    assert!(!supervised.poll(&mut data, at(start, 5_000), |_| unreachable!()));
+    assert_eq!(supervised.restarts(), 0);
+}
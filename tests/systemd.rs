@@ -0,0 +1,92 @@
+// Notifications systemd: socket de NOTIFY_SOCKET, intervalle et suspension des pings du watchdog
+#![cfg(target_os = "linux")]
+
+use voiturerc::systemd;
+
+use std::ffi::OsStr;
+use std::os::linux::net::SocketAddrExt;
+use std::os::unix::net::{SocketAddr, UnixDatagram};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+use tokio_util::sync::CancellationToken;
+
+use systemd::{watchdog_interval, Notifier};
+
+/// Socket de réception à la place de systemd
+fn listener(name: &str) -> (UnixDatagram, std::path::PathBuf) {
+    let path = std::env::temp_dir().join(format!("rc-telemetrie-notify-{}-{}", name, std::process::id()));
+    let _ = std::fs::remove_file(&path);
+    let socket = UnixDatagram::bind(&path).unwrap();
+    socket.set_read_timeout(Some(Duration::from_secs(5))).unwrap();
+    (socket, path)
+}
+
+fn recv(socket: &UnixDatagram) -> String {
+    let mut buffer = [0; 64];
+    let size = socket.recv(&mut buffer).unwrap();
+    String::from_utf8_lossy(&buffer[..size]).into_owned()
+}
+
+#[test]
+fn notifies_path_and_abstract_sockets() {
+    let (socket, path) = listener("path");
+    Notifier::new(path.as_os_str()).unwrap().notify("READY=1").unwrap();
+    assert_eq!(recv(&socket), "READY=1");
+    let _ = std::fs::remove_file(&path);
+
+    // Nom abstrait (préfixe '@'), sans fichier
+    let name = format!("rc-telemetrie-notify-{}", std::process::id());
+    let address = SocketAddr::from_abstract_name(name.as_bytes()).unwrap();
+    let socket = UnixDatagram::bind_addr(&address).unwrap();
+    socket.set_read_timeout(Some(Duration::from_secs(5))).unwrap();
+    Notifier::new(OsStr::new(&format!("@{}", name))).unwrap().notify("STOPPING=1").unwrap();
+    assert_eq!(recv(&socket), "STOPPING=1");
+
+    assert!(Notifier::new(OsStr::new("relative/socket")).is_err());
+}
+
+#[test]
+fn pings_at_half_the_watchdog_timeout() {
+    let own = std::process::id();
+    assert_eq!(watchdog_interval(Some("10000000"), None, own), Some(Duration::from_secs(5)));
+    assert_eq!(watchdog_interval(Some("10000000"), Some(&own.to_string()), own), Some(Duration::from_secs(5)));
+    // Watchdog d'un autre processus, désactivé ou invalide
+    assert_eq!(watchdog_interval(Some("10000000"), Some("1"), own), None);
+    assert_eq!(watchdog_interval(None, None, own), None);
+    assert_eq!(watchdog_interval(Some("0"), None, own), None);
+    assert_eq!(watchdog_interval(Some("dix"), None, own), None);
+}
+
+#[tokio::test]
+async fn blocked_tasks_suspend_pings() {
+    let (socket, path) = listener("watchdog");
+    socket.set_nonblocking(true).unwrap();
+    let notifier = Arc::new(Notifier::new(path.as_os_str()).unwrap());
+    let blocked = Arc::new(AtomicBool::new(true));
+    let token = CancellationToken::new();
+
+    let task = tokio::spawn(systemd::watchdog(
+        notifier,
+        Duration::from_millis(20),
+        {
+            let blocked = blocked.clone();
+            move || blocked.load(Ordering::SeqCst).then(|| "boucle de contrôle bloquée".to_string())
+        },
+        token.clone(),
+    ));
+
+    tokio::time::sleep(Duration::from_millis(100)).await;
+    let mut buffer = [0; 64];
+    assert!(socket.recv(&mut buffer).is_err(), "aucun ping pendant le blocage");
+
+    blocked.store(false, Ordering::SeqCst);
+    tokio::time::sleep(Duration::from_millis(100)).await;
+    let size = socket.recv(&mut buffer).unwrap();
+    assert_eq!(&buffer[..size], b"WATCHDOG=1");
+
+    token.cancel();
+    task.await.unwrap();
+    let _ = std::fs::remove_file(&path);
+}
@@ -0,0 +1,65 @@
+// Arrêt des tâches: attente des tâches annulées, interruption des tâches bloquées après le délai.
+
+use voiturerc::tasks;
+
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+use tokio::time::{sleep, Instant};
+use tokio_util::sync::CancellationToken;
+
+use tasks::Tasks;
+
+const GRACE: Duration = Duration::from_millis(300);
+
+#[tokio::test]
+async fn waits_for_cancelled_tasks() {
+    let token = CancellationToken::new();
+    let stopped = Arc::new(AtomicBool::new(false));
+    let mut tasks = Tasks::new();
+
+    {
+        let token = token.child_token();
+        let stopped = stopped.clone();
+        tasks.spawn("cleanup", async move {
+            token.cancelled().await;
+            // Nettoyage après l'annulation, comme le passage au neutre des actionneurs
+            sleep(Duration::from_millis(50)).await;
+            stopped.store(true, Ordering::SeqCst);
+        });
+    }
+    tasks.spawn("done", async {});
+
+    token.cancel();
+    let start = Instant::now();
+    assert!(tasks.shutdown(GRACE).await.is_empty());
+    assert!(stopped.load(Ordering::SeqCst));
+    assert!(start.elapsed() < GRACE);
+}
+
+#[tokio::test]
+async fn aborts_stuck_tasks_after_grace() {
+    let token = CancellationToken::new();
+    let mut tasks = Tasks::new();
+
+    {
+        let token = token.child_token();
+        tasks.spawn("cancelled", async move { token.cancelled().await });
+    }
+    tasks.spawn("stuck", sleep(Duration::from_secs(3600)));
+
+    token.cancel();
+    let start = Instant::now();
+    assert_eq!(tasks.shutdown(GRACE).await, vec!["stuck"]);
+    assert!(start.elapsed() >= GRACE);
+    assert!(start.elapsed() < GRACE * 4);
+}
+
+#[tokio::test]
+async fn reports_panicked_tasks_as_stopped() {
+    let mut tasks = Tasks::new();
+    tasks.spawn("panic", async { panic!("tâche en erreur") });
+
+    assert!(tasks.shutdown(GRACE).await.is_empty());
+}
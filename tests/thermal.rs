@@ -0,0 +1,110 @@
+// Limitation thermique: vitesse maximale selon la température, hystérésis et cause de la limite
+#![cfg(not(feature = "real-sensors"))]
+
+use voiturerc::{actuators, clock, config};
+
+use std::time::Duration;
+
+use actuators::arbiter::{Arbiter, SpeedLimit};
+use actuators::thermal::{scale, Change, Thermal};
+use actuators::Control;
+use clock::Clock;
+use config::{LinkLossPolicy, ThermalSensor};
+
+/// 80 °C: alerte, 100 °C: critique (30 %), reprise sous 85 °C
+fn esc() -> ThermalSensor {
+    ThermalSensor {
+        name: "esc".to_string(),
+        signal: "esc.temperature".to_string(),
+        ..ThermalSensor::default()
+    }
+}
+
+#[test]
+fn scale_breakpoints() {
+    let sensor = esc();
+    let cases = [
+        (-10.0, 1.0),
+        (79.9, 1.0),
+        (80.0, 1.0),
+        // 1 - 0.7 x 0.1 = 0.93: arrondi au pas inférieur
+        (82.0, 0.9),
+        (85.0, 0.80),
+        (90.0, 0.65),
+        (95.0, 0.45),
+        (99.0, 0.30),
+        (99.99, 0.30),
+        (100.0, 0.0),
+        (150.0, 0.0),
+    ];
+    for (temp, expected) in cases {
+        let max = scale(&sensor, temp);
+        assert!((max - expected).abs() < 1e-9, "{} °C: {} au lieu de {}", temp, max, expected);
+    }
+
+    // Décroissance monotone entre l'alerte et la critique
+    let mut previous = 1.0;
+    for tenth in 800..1000 {
+        let max = scale(&sensor, tenth as f32 / 10.0);
+        assert!(max <= previous && max >= sensor.floor);
+        previous = max;
+    }
+
+    // Plancher nul: coupure progressive jusqu'à la température critique
+    let sensor = ThermalSensor { floor: 0.0, ..esc() };
+    assert_eq!(scale(&sensor, 90.0), 0.5);
+    assert_eq!(scale(&sensor, 99.9), 0.0);
+}
+
+#[test]
+fn critical_until_recovery() {
+    let mut thermal = Thermal::new(esc());
+
+    assert_eq!(thermal.update(70.0), (1.0, None));
+    assert_eq!(thermal.update(90.0).0, 0.65);
+    assert_eq!(thermal.update(100.0), (0.0, Some(Change::Tripped)));
+    // Hystérésis: coupure maintenue entre la reprise et la critique
+    assert_eq!(thermal.update(99.0), (0.0, None));
+    assert_eq!(thermal.update(85.0), (0.0, None));
+    // Sous la reprise: retour à la limite linéaire
+    let (max, change) = thermal.update(84.9);
+    assert_eq!(change, Some(Change::Recovered));
+    assert!((max - 0.80).abs() < 1e-9);
+    assert_eq!(thermal.update(60.0), (1.0, None));
+}
+
+#[tokio::test]
+async fn limit_cause_is_visible() {
+    let (commands, _arbiter) = Arbiter::new(&Clock::start(), Duration::from_millis(500), LinkLossPolicy::Stop);
+    let state = commands.state();
+    assert_eq!(state.borrow().limit, None);
+
+    commands.limit("thermal.esc", Some(0.65));
+    commands.limit("thermal.motor", Some(0.8));
+    assert_eq!(
+        state.borrow().limit,
+        Some(SpeedLimit {
+            max: 0.65,
+            cause: "thermal.esc".to_string()
+        })
+    );
+
+    commands.cutoff("rollover", true);
+    assert_eq!(state.borrow().limit.as_ref().unwrap().cause, "rollover");
+
+    commands.cutoff("rollover", false);
+    commands.limit("thermal.esc", None);
+    assert_eq!(
+        state.borrow().limit,
+        Some(SpeedLimit {
+            max: 0.8,
+            cause: "thermal.motor".to_string()
+        })
+    );
+
+    commands.limit("thermal.motor", None);
+    assert_eq!(state.borrow().limit, None);
+    // La commande reste inchangée
+    commands.submit("test", Control { steer: 0.0, speed: 1.0 }).unwrap();
+    assert_eq!(state.borrow().input.speed, 1.0);
+}
@@ -0,0 +1,71 @@
+// Trame UDP compacte: disposition, aller-retour et refus des trames invalides
+#![cfg(not(feature = "real-sensors"))]
+
+use voiturerc::{record, sensors, udp};
+
+use record::{ModemData, ModemStatus};
+use sensors::reader::Data;
+use udp::frame::{Frame, VERSION};
+
+fn data() -> Data {
+    let mut data = Data::default();
+    data.gps.fix = true;
+    data.gps.latitude = 46.519_654;
+    data.gps.longitude = 6.632_273;
+    data.gps.speed_kmh = 32.5;
+    data.mag.true_heading = 271.5;
+    data.imu.angles = (-3.5, 12.25, 90.0);
+    data.analog.battery = 7.42;
+    data
+}
+
+fn modem(quality: u32) -> ModemData {
+    ModemData {
+        status: ModemStatus { quality, ..ModemStatus::default() },
+        ..ModemData::default()
+    }
+}
+
+#[test]
+fn frame_from_latest_values() {
+    let frame = Frame::new(&data(), &modem(87), 1_717_243_200_000_000, 7);
+
+    assert!(frame.fix);
+    assert_eq!((frame.latitude, frame.longitude), (46.519_654, 6.632_273));
+    assert_eq!(frame.speed_kmh, 32.5);
+    assert_eq!(frame.heading, 271.5);
+    assert_eq!((frame.roll, frame.pitch), (12.25, -3.5));
+    assert_eq!(frame.battery, 7.42);
+    assert_eq!(frame.signal, 87);
+
+    // Qualité hors plage bornée à 100 %
+    let frame = Frame::new(&data(), &modem(300), 0, 0);
+    assert_eq!(frame.signal, 100);
+}
+
+#[test]
+fn round_trip_under_one_mtu() {
+    let frame = Frame::new(&data(), &modem(87), 1_717_243_200_123_456, u32::MAX);
+    let bytes = frame.encode();
+
+    // Plus petite charge UDP garantie sans fragmentation (IPv4)
+    assert!(bytes.len() <= 508);
+    assert_eq!(&bytes[0..3], &[b'R', b'C', VERSION]);
+    assert_eq!(Frame::decode(&bytes).unwrap(), frame);
+}
+
+#[test]
+fn rejects_invalid_frames() {
+    let bytes = Frame::new(&data(), &ModemData::default(), 0, 0).encode();
+
+    assert!(Frame::decode(&bytes[..Frame::SIZE - 1]).is_err());
+    assert!(Frame::decode(&[&bytes[..], &[0]].concat()).is_err());
+
+    let mut header = bytes;
+    header[0] = b'X';
+    assert!(Frame::decode(&header).is_err());
+
+    let mut version = bytes;
+    version[2] = VERSION + 1;
+    assert!(Frame::decode(&version).is_err());
+}
@@ -0,0 +1,259 @@
+// Fraîcheur des capteurs: capteurs périmés, retour, état de santé et mesures de sécurité
+#![cfg(not(feature = "real-sensors"))]
+
+use voiturerc::{clock, config, sensors};
+
+use std::time::{Duration, Instant};
+
+use chrono::Utc;
+
+use clock::{Clock, Stamp};
+use config::{Config, Mitigation, SensorMode, Severity, WatchdogConfig};
+use sensors::reader::{Data, SensorStatus, SensorsStatus};
+use sensors::source::Kind;
+use sensors::watchdog::{Health, Snapshot, Status, Watchdog};
+
+/// Avance l'horloge virtuelle
+fn advance(clock: &Clock, duration: Duration) {
+    clock.set(clock.elapsed() + duration);
+}
+
+fn at(start: Instant, ms: u64) -> Instant {
+    start + Duration::from_millis(ms)
+}
+
+/// Echantillons de l'IMU et de la batterie horodatés
+fn data(imu: u64, analog: u64) -> Data {
+    let mut data = Data::default();
+    data.imu.stamp = Stamp {
+        mono_us: imu,
+        ..Stamp::default()
+    };
+    data.analog.stamp = Stamp {
+        mono_us: analog,
+        ..Stamp::default()
+    };
+    data
+}
+
+fn config() -> WatchdogConfig {
+    let mut config = WatchdogConfig::default();
+    config.imu.mitigation = Mitigation::Failsafe;
+    config.analog.interval_ms = 100;
+    config
+}
+
+#[test]
+fn stale_then_recovered() {
+    let start = Instant::now();
+    let mut watchdog = Watchdog::new(&config(), start);
+
+    assert!(watchdog.update(&data(1, 1), at(start, 0)).is_empty());
+    assert!(watchdog.update(&data(2, 2), at(start, 100)).is_empty());
+    assert!(watchdog.update(&data(3, 2), at(start, 200)).is_empty());
+
+    // IMU: 3 × 50 ms, batterie: 3 × 100 ms
+    let transitions = watchdog.update(&data(3, 2), at(start, 350));
+    assert_eq!(transitions.len(), 1);
+    assert_eq!(transitions[0].kind, Kind::Imu);
+    assert!(transitions[0].stale);
+    assert_eq!(transitions[0].event(), "sensor_stale");
+    assert_eq!(transitions[0].severity(), Severity::Critical);
+    assert!(transitions[0].message().contains("imu"));
+    assert_eq!(watchdog.health(), Health::Critical);
+    assert_eq!(watchdog.max_speed(), Some(0.0));
+
+    let transitions = watchdog.update(&data(3, 2), at(start, 400));
+    assert_eq!(transitions.len(), 1);
+    assert_eq!(transitions[0].kind, Kind::Analog);
+    assert_eq!(transitions[0].severity(), Severity::Warning);
+    assert_eq!(watchdog.stale(), vec!["imu", "analog"]);
+
+    // Une seule transition par capteur
+    assert!(watchdog.update(&data(3, 2), at(start, 1000)).is_empty());
+
+    let transitions = watchdog.update(&data(4, 2), at(start, 1100));
+    assert_eq!(transitions.len(), 1);
+    assert!(!transitions[0].stale);
+    assert_eq!(transitions[0].event(), "sensor_recovered");
+    assert_eq!(transitions[0].silent, Duration::from_millis(900));
+    assert_eq!(watchdog.health(), Health::Degraded);
+    assert_eq!(watchdog.max_speed(), None);
+}
+
+#[test]
+fn silent_sensor_never_stale() {
+    let start = Instant::now();
+    let mut watchdog = Watchdog::new(&config(), start);
+
+    // Aucun échantillon: capteur désactivé ou jamais initialisé
+    assert!(watchdog.update(&Data::default(), at(start, 5000)).is_empty());
+    assert_eq!(watchdog.health(), Health::Ok);
+
+    // Premier échantillon tardif: pas de retour signalé
+    assert!(watchdog.update(&data(1, 0), at(start, 6000)).is_empty());
+}
+
+#[test]
+fn lowest_mitigation_applies() {
+    let mut config = config();
+    config.imu.mitigation = Mitigation::Limit;
+    config.analog.mitigation = Mitigation::Limit;
+    config.limit_max_speed = 0.4;
+    let start = Instant::now();
+    let mut watchdog = Watchdog::new(&config, start);
+
+    watchdog.update(&data(1, 1), at(start, 0));
+    assert_eq!(watchdog.update(&data(1, 2), at(start, 200)).len(), 1);
+    assert_eq!(watchdog.max_speed(), Some(0.4));
+    assert_eq!(watchdog.health(), Health::Critical);
+
+    config.gps.mitigation = Mitigation::Failsafe;
+    let mut watchdog = Watchdog::new(&config, start);
+    let mut gps = data(1, 1);
+    gps.gps.stamp.mono_us = 1;
+    watchdog.update(&gps, at(start, 0));
+    assert_eq!(watchdog.update(&gps, at(start, 3000)).len(), 3);
+    assert_eq!(watchdog.max_speed(), Some(0.0));
+}
+
+/// Surveillance périodique sur une horloge virtuelle, comme la tâche de surveillance: la
+/// péremption est détectée au premier contrôle après le délai, sans attente réelle
+#[tokio::test]
+async fn periodic_check_on_virtual_clock() {
+    const CHECK: Duration = Duration::from_millis(50);
+
+    let clock = Clock::virtual_at(Utc::now());
+    let (sender, mut checks) = tokio::sync::mpsc::unbounded_channel();
+    let task = {
+        let clock = clock.clone();
+        tokio::spawn(async move {
+            let mut watchdog = Watchdog::new(&config(), clock.now());
+            let mut stamp = 0;
+            loop {
+                // IMU muette après 100 ms, batterie toujours reçue
+                if clock.elapsed() <= Duration::from_millis(100) {
+                    stamp += 1;
+                }
+                let analog = clock.elapsed().as_micros() as u64;
+                let transitions = watchdog.update(&data(stamp, analog), clock.now());
+                if sender.send((clock.elapsed(), transitions)).is_err() {
+                    break;
+                }
+                clock.sleep(CHECK).await;
+            }
+        })
+    };
+
+    // Premier contrôle au démarrage, puis un contrôle à chaque avance de l'horloge
+    assert!(checks.recv().await.unwrap().1.is_empty());
+    let mut stale = None;
+    for _ in 0..10 {
+        advance(&clock, CHECK);
+        let (elapsed, transitions) = checks.recv().await.unwrap();
+        if let Some(transition) = transitions.first() {
+            assert_eq!(transition.kind, Kind::Imu);
+            assert!(transition.stale);
+            stale.get_or_insert(elapsed);
+        }
+    }
+
+    // Dernière mesure à 100 ms, délai de 3 × 50 ms
+    assert_eq!(stale, Some(Duration::from_millis(250)));
+    drop(checks);
+    advance(&clock, CHECK);
+    task.await.unwrap();
+}
+
+#[test]
+fn component_health_from_samples_errors_and_modes() {
+    let start = Instant::now();
+    let mut watchdog = Watchdog::new(&config(), start);
+    let mut full = Config::default();
+    full.sensors.imu.mode = SensorMode::Fake;
+    full.sensors.analog.mode = SensorMode::Fake;
+    full.sensors.mag.mode = SensorMode::Fake;
+    full.sensors.baro.mode = SensorMode::Fake;
+    full.sensors.gps.mode = SensorMode::Disabled;
+    let status = SensorsStatus {
+        mag: SensorStatus {
+            available: false,
+            error: Some("I2C: pas de réponse".to_string()),
+            attempts: 3,
+            restarts: 0,
+        },
+        ..SensorsStatus::default()
+    };
+
+    watchdog.update(&data(1, 1), at(start, 0));
+    watchdog.update(&data(2, 1), at(start, 100));
+    let components = watchdog.components(&full, &status, at(start, 120));
+    let find = |name: &str| components.iter().find(|component| component.name == name).unwrap().clone();
+
+    assert_eq!(find("imu").status, Status::Ok);
+    assert_eq!(find("imu").silent_ms, Some(20));
+    assert_eq!(find("analog").status, Status::Ok);
+    assert_eq!(find("mag").status, Status::Error);
+    assert_eq!(find("mag").error.as_deref(), Some("I2C: pas de réponse"));
+    assert_eq!(find("gps").status, Status::Disabled);
+
+    // Sans aucun échantillon depuis le démarrage: périmé après le seuil
+    let components = watchdog.components(&full, &status, at(start, 400));
+    let find = |name: &str| components.iter().find(|component| component.name == name).unwrap().clone();
+    assert_eq!(find("imu").status, Status::Stale);
+    assert_eq!(find("baro").status, Status::Stale);
+    assert_eq!(find("baro").silent_ms, None);
+}
+
+#[test]
+fn control_health_from_last_command() {
+    let stale = Duration::from_secs(1);
+    let control = sensors::watchdog::control(Some(Duration::from_millis(200)), stale);
+    assert_eq!(control.name, "control");
+    assert_eq!(control.status, Status::Ok);
+    assert_eq!(control.silent_ms, Some(200));
+    assert_eq!(sensors::watchdog::control(Some(Duration::from_secs(2)), stale).status, Status::Stale);
+    assert_eq!(sensors::watchdog::control(None, stale).status, Status::Stale);
+}
+
+#[test]
+fn stuck_control_loop_blocks_progress() {
+    let stale = Duration::from_secs(1);
+    let running = sensors::watchdog::control_loop(Some(Duration::from_millis(100)), false, stale);
+    assert_eq!(running.status, Status::Ok);
+    // Boucle terminée (actionneurs indisponibles): pas un blocage
+    assert_eq!(sensors::watchdog::control_loop(None, true, stale).status, Status::Disabled);
+
+    let now = Instant::now();
+    let max_age = Duration::from_secs(6);
+    let snapshot = Snapshot { at: now, components: vec![running] };
+    assert_eq!(snapshot.blocked(now, max_age), None);
+
+    // Santé plus publiée: tâche du watchdog ou runtime bloqué
+    let late = snapshot.blocked(now + max_age, max_age).unwrap();
+    assert!(late.contains("santé non publiée"), "{}", late);
+
+    let stuck = Snapshot {
+        at: now,
+        components: vec![sensors::watchdog::control_loop(Some(Duration::from_secs(3)), false, stale)],
+    };
+    let reason = stuck.blocked(now, max_age).unwrap();
+    assert!(reason.contains("3000 ms"), "{}", reason);
+}
+
+#[test]
+fn degraded_ignores_missing_commands() {
+    let stale = Duration::from_secs(1);
+    let mut snapshot = Snapshot {
+        at: Instant::now(),
+        components: vec![
+            sensors::watchdog::control(None, stale),
+            sensors::watchdog::control_loop(Some(Duration::from_millis(100)), false, stale),
+        ],
+    };
+    // Aucune commande reçue: véhicule en ordre
+    assert!(!snapshot.degraded());
+
+    snapshot.components.push(sensors::watchdog::control_loop(None, false, stale));
+    assert!(snapshot.degraded());
+}
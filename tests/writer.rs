@@ -0,0 +1,132 @@
+// Files de l'écrivain: perte des plus anciens, regroupement du GPS, événements jamais perdus
+use voiturerc::channel;
+
+use std::sync::Arc;
+use std::time::Duration;
+
+use channel::{Coalesce, DropOldest, Lossless};
+use tokio::sync::Notify;
+use tokio::time::sleep;
+
+/// Temps d'écriture d'un élément par la base simulée (lente)
+const SINK_DELAY: Duration = Duration::from_millis(2);
+
+#[tokio::test]
+async fn imu_drops_oldest() {
+    let notify = Arc::new(Notify::new());
+    let queue = DropOldest::new(8, notify.clone());
+
+    // Base lente: un élément toutes les 2 ms
+    let sink = {
+        let queue = queue.clone();
+        tokio::spawn(async move {
+            let mut written = Vec::new();
+            loop {
+                match queue.pop() {
+                    Some(n) => {
+                        written.push(n);
+                        sleep(SINK_DELAY).await;
+                    }
+                    None if written.last() == Some(&499) => break,
+                    None => notify.notified().await,
+                }
+            }
+            written
+        })
+    };
+
+    for n in 0..500u32 {
+        queue.push(n);
+        if n % 10 == 0 {
+            tokio::task::yield_now().await;
+        }
+    }
+
+    let written = sink.await.unwrap();
+    let stats = queue.stats();
+
+    // Rien n'est perdu sans être compté, l'ordre est conservé, le dernier échantillon est écrit
+    assert!(stats.dropped > 0);
+    assert_eq!(written.len() as u64 + stats.dropped, 500);
+    assert!(written.windows(2).all(|w| w[0] < w[1]));
+    assert_eq!(stats.depth, 0);
+    assert_eq!(stats.capacity, 8);
+}
+
+#[tokio::test]
+async fn drop_oldest_keeps_newest() {
+    let queue = DropOldest::new(4, Arc::new(Notify::new()));
+    for n in 0..10u32 {
+        queue.push(n);
+    }
+
+    assert_eq!(queue.stats().dropped, 6);
+    assert_eq!(queue.stats().depth, 4);
+    let remaining: Vec<_> = std::iter::from_fn(|| queue.pop()).collect();
+    assert_eq!(remaining, vec![6, 7, 8, 9]);
+}
+
+#[tokio::test]
+async fn gps_coalesces_to_latest() {
+    let notify = Arc::new(Notify::new());
+    let slot = Coalesce::new(notify.clone());
+
+    let sink = {
+        let slot = slot.clone();
+        tokio::spawn(async move {
+            let mut written = Vec::new();
+            loop {
+                match slot.take() {
+                    Some(n) => {
+                        written.push(n);
+                        sleep(SINK_DELAY).await;
+                    }
+                    None if written.last() == Some(&99) => break,
+                    None => notify.notified().await,
+                }
+            }
+            written
+        })
+    };
+
+    for n in 0..100u32 {
+        slot.push(n);
+        sleep(Duration::from_micros(200)).await;
+    }
+
+    let written = sink.await.unwrap();
+    let stats = slot.stats();
+
+    // Seule la dernière position compte: elle est toujours écrite
+    assert!(stats.dropped > 0);
+    assert_eq!(written.len() as u64 + stats.dropped, 100);
+    assert!(written.windows(2).all(|w| w[0] < w[1]));
+    assert_eq!(*written.last().unwrap(), 99);
+}
+
+#[tokio::test]
+async fn events_are_never_dropped() {
+    let (events, mut receiver) = Lossless::new(4);
+
+    let sink = tokio::spawn(async move {
+        let mut written = Vec::new();
+        while let Some(n) = receiver.recv().await {
+            written.push(n);
+            sleep(SINK_DELAY).await;
+        }
+        written
+    });
+
+    for n in 0..100u32 {
+        events.send(n).await.unwrap();
+
+        // L'émetteur attend: la file ne dépasse jamais sa capacité
+        let stats = events.stats();
+        assert!(stats.depth <= stats.capacity);
+        assert_eq!(stats.dropped, 0);
+    }
+    drop(events);
+
+    let written = sink.await.unwrap();
+    assert_eq!(written, (0..100).collect::<Vec<_>>());
+}
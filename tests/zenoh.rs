@@ -0,0 +1,113 @@
+// Publication Zenoh: messages CDR et livraison entre deux sessions locales
+#![cfg(not(feature = "real-sensors"))]
+
+mod common;
+
+use voiturerc::{config, record, sensors, zenoh_bridge};
+
+use std::net::TcpListener;
+use std::time::Duration;
+
+use config::{ZenohConfig, ZenohEncoding};
+use record::Record;
+use sensors::reader::ImuData;
+use zenoh::bytes::Encoding;
+use zenoh_bridge::bridge::Bridge;
+
+fn gps() -> Record {
+    Record::Gps(common::gps())
+}
+
+fn imu() -> Record {
+    Record::Imu(ImuData {
+        accel: (0.05, -0.1, 1.0),
+        ..common::imu()
+    })
+}
+
+fn f64_at(payload: &[u8], offset: usize) -> f64 {
+    f64::from_le_bytes(payload[4 + offset..4 + offset + 8].try_into().unwrap())
+}
+
+fn f32_at(payload: &[u8], offset: usize) -> f32 {
+    f32::from_le_bytes(payload[4 + offset..4 + offset + 4].try_into().unwrap())
+}
+
+#[test]
+fn cdr_layout() {
+    let gps = zenoh_bridge::cdr::encode(&gps());
+
+    // En-tête, builtin_interfaces/Time puis mono_us
+    assert_eq!(gps[..4], [0x00, 0x01, 0x00, 0x00]);
+    assert_eq!(i32::from_le_bytes(gps[4..8].try_into().unwrap()), 1_717_243_200);
+    assert_eq!(u32::from_le_bytes(gps[8..12].try_into().unwrap()), 0);
+    assert_eq!(u64::from_le_bytes(gps[12..20].try_into().unwrap()), 1_500_000);
+
+    assert_eq!(f64_at(&gps, 16), 25.5);
+    assert_eq!(f64_at(&gps, 24), 46.52);
+    assert_eq!(f64_at(&gps, 32), 6.632);
+    assert_eq!(gps[4 + 40], 9);
+    assert_eq!(gps[4 + 41], 1);
+    // Cap aligné sur 8 octets
+    assert_eq!(f64_at(&gps, 48), 90.0);
+    assert_eq!(gps.len(), 4 + 56);
+
+    let imu = zenoh_bridge::cdr::encode(&imu());
+    assert_eq!(f32_at(&imu, 20), -2.25);
+    assert_eq!(f32_at(&imu, 52), 12.0);
+    assert_eq!(imu.len(), 4 + 56);
+}
+
+/// Deux sessions en mode pair, sans routeur ni découverte multicast: la seconde se connecte
+/// au point d'accès de la première
+#[tokio::test(flavor = "multi_thread")]
+async fn delivers_gps_and_imu() {
+    let port = TcpListener::bind("127.0.0.1:0").unwrap().local_addr().unwrap().port();
+    let endpoint = format!("tcp/127.0.0.1:{}", port);
+
+    let config = ZenohConfig {
+        enabled: true,
+        listen: vec![endpoint.clone()],
+        multicast: false,
+        records: vec!["imu".to_string(), "gps".to_string()],
+        encoding: ZenohEncoding::Cdr,
+        ..ZenohConfig::default()
+    };
+    let bridge = Bridge::open(&config, "test").await.unwrap();
+    assert_eq!(bridge.keys(), ["rc/test/gps", "rc/test/imu"]);
+
+    let mut subscriber_config = zenoh::Config::default();
+    subscriber_config.insert_json5("mode", r#""peer""#).unwrap();
+    subscriber_config
+        .insert_json5("connect/endpoints", &format!(r#"["{}"]"#, endpoint))
+        .unwrap();
+    subscriber_config
+        .insert_json5("scouting/multicast/enabled", "false")
+        .unwrap();
+    let session = zenoh::open(subscriber_config).await.unwrap();
+    let subscriber = session.declare_subscriber("rc/test/**").await.unwrap();
+
+    // Publication répétée jusqu'à l'établissement de la route
+    let mut received = std::collections::BTreeMap::new();
+    let deadline = tokio::time::Instant::now() + Duration::from_secs(10);
+    while received.len() < 2 {
+        assert!(
+            tokio::time::Instant::now() < deadline,
+            "rien reçu: {:?}",
+            received.keys()
+        );
+        bridge.publish(&gps()).await.unwrap();
+        bridge.publish(&imu()).await.unwrap();
+
+        while let Ok(Ok(sample)) = tokio::time::timeout(Duration::from_millis(100), subscriber.recv_async()).await {
+            assert_eq!(*sample.encoding(), Encoding::APPLICATION_CDR);
+            received.insert(sample.key_expr().to_string(), sample.payload().to_bytes().into_owned());
+        }
+    }
+
+    assert_eq!(f64_at(&received["rc/test/gps"], 24), 46.52);
+    assert_eq!(f32_at(&received["rc/test/imu"], 28), 31.0);
+
+    session.close().await.unwrap();
+    bridge.close().await;
+}